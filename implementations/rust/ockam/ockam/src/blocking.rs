@@ -0,0 +1,226 @@
+//! Blocking API, for the applications which don't use `async` code.
+//!
+//! A [`BlockingNode`] starts an Ockam node, with its own runtime running on a background thread,
+//! and exposes the most common operations as blocking calls:
+//!
+//! ```rust,no_run
+//! use ockam::blocking::BlockingNode;
+//! use ockam::identity::{SecureChannelListenerOptions, SecureChannelOptions};
+//! use ockam::{route, Result, TcpConnectionOptions, TcpListenerOptions};
+//!
+//! fn main() -> Result<()> {
+//!     let node = BlockingNode::start()?;
+//!
+//!     // Accept secure channels over TCP
+//!     let identifier = node.create_identity()?;
+//!     let tcp_listener_options = TcpListenerOptions::new();
+//!     let secure_channel_listener_options = SecureChannelListenerOptions::new()
+//!         .as_consumer(&tcp_listener_options.spawner_flow_control_id());
+//!     node.listen("127.0.0.1:4000", tcp_listener_options)?;
+//!     node.create_secure_channel_listener(&identifier, "listener", secure_channel_listener_options)?;
+//!
+//!     // Create a secure channel to another node, and expose one of its services locally
+//!     let connection = node.connect("10.0.0.1:4000", TcpConnectionOptions::new())?;
+//!     let channel = node.create_secure_channel(
+//!         &identifier,
+//!         route![connection, "listener"],
+//!         SecureChannelOptions::new(),
+//!     )?;
+//!     node.create_inlet("127.0.0.1:5432", route![channel, "outlet"], Default::default())?;
+//!
+//!     node.stop()
+//! }
+//! ```
+//!
+//! The calls must not be made from an async context, since they block the current thread.
+//! Any other operation can be run on the node with [`BlockingNode::block_on`].
+
+use core::future::Future;
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Address, Error, Message, Result, Route, Worker};
+use ockam_identity::models::Identifier;
+use ockam_identity::{
+    SecureChannel, SecureChannelListener, SecureChannelListenerOptions, SecureChannelOptions,
+};
+use ockam_node::tokio::runtime::Handle;
+use ockam_node::{Context, NodeBuilder};
+use ockam_transport_tcp::{
+    TcpConnection, TcpInletOptions, TcpListener, TcpListenerOptions, TcpOutletOptions, TcpTransport,
+};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+use crate::{node, Node, TcpConnectionOptions};
+
+/// An Ockam node which can be used without `async` code.
+///
+/// The node is stopped when [`BlockingNode::stop`] is called, or when it is dropped.
+pub struct BlockingNode {
+    node: Node,
+    tcp: TcpTransport,
+    runtime: Handle,
+    executor: Option<JoinHandle<Result<()>>>,
+}
+
+impl BlockingNode {
+    /// Start a node, with logging enabled
+    pub fn start() -> Result<Self> {
+        Self::start_with(NodeBuilder::new())
+    }
+
+    /// Start a node configured with a [`NodeBuilder`]
+    pub fn start_with(builder: NodeBuilder) -> Result<Self> {
+        // The router of the node runs on a background thread, until the node is stopped
+        let (sender, receiver) = mpsc::channel();
+        let executor = std::thread::spawn(move || {
+            let (ctx, mut executor) = builder.build();
+            let _ = sender.send(ctx);
+            executor.execute(async { Ok(()) })?
+        });
+        let ctx: Context = receiver
+            .recv()
+            .map_err(|e| Error::new(Origin::Node, Kind::Internal, e))?;
+
+        let runtime = ctx.runtime().clone();
+        let tcp = runtime.block_on(TcpTransport::create(&ctx))?;
+        Ok(Self {
+            node: node(ctx),
+            tcp,
+            runtime,
+            executor: Some(executor),
+        })
+    }
+
+    /// Return the underlying [`Node`], to use with [`BlockingNode::block_on`]
+    pub fn node(&self) -> &Node {
+        &self.node
+    }
+
+    /// Return the TCP transport of the node, to use with [`BlockingNode::block_on`]
+    pub fn tcp(&self) -> &TcpTransport {
+        &self.tcp
+    }
+
+    /// Run a future on the runtime of the node, and block until it completes
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Create an Identity
+    pub fn create_identity(&self) -> Result<Identifier> {
+        self.block_on(self.node.create_identity())
+    }
+
+    /// Start a worker at the given address. Default Access Control is AllowAll
+    pub fn start_worker<W>(&self, address: impl Into<Address>, worker: W) -> Result<()>
+    where
+        W: Worker<Context = Context>,
+    {
+        self.block_on(self.node.start_worker(address, worker))
+    }
+
+    /// Listen for incoming TCP connections, on an address like `127.0.0.1:4000`
+    pub fn listen(
+        &self,
+        bind_address: impl AsRef<str>,
+        options: TcpListenerOptions,
+    ) -> Result<TcpListener> {
+        self.block_on(self.tcp.listen(bind_address, options))
+    }
+
+    /// Open a TCP connection to a peer, like `127.0.0.1:4000`
+    pub fn connect(
+        &self,
+        peer: impl Into<String>,
+        options: TcpConnectionOptions,
+    ) -> Result<TcpConnection> {
+        self.block_on(self.tcp.connect(peer, options))
+    }
+
+    /// Start a SecureChannel listener at the given address
+    pub fn create_secure_channel_listener(
+        &self,
+        identifier: &Identifier,
+        address: impl Into<Address>,
+        options: impl Into<SecureChannelListenerOptions>,
+    ) -> Result<SecureChannelListener> {
+        self.block_on(
+            self.node
+                .create_secure_channel_listener(identifier, address, options),
+        )
+    }
+
+    /// Initiate a SecureChannel using a route to a SecureChannel listener
+    pub fn create_secure_channel(
+        &self,
+        identifier: &Identifier,
+        route: impl Into<Route>,
+        options: impl Into<SecureChannelOptions>,
+    ) -> Result<SecureChannel> {
+        self.block_on(self.node.create_secure_channel(identifier, route, options))
+    }
+
+    /// Create a TCP inlet, accepting TCP connections on `bind_address` and forwarding their
+    /// data to the outlet at the end of `outlet_route`.
+    ///
+    /// Return the socket address of the inlet, and the address of its worker
+    pub fn create_inlet(
+        &self,
+        bind_address: impl Into<String>,
+        outlet_route: impl Into<Route>,
+        options: TcpInletOptions,
+    ) -> Result<(SocketAddr, Address)> {
+        self.block_on(self.tcp.create_inlet(bind_address, outlet_route, options))
+    }
+
+    /// Create a TCP outlet at the given address, forwarding the data of the inlets to `peer`
+    pub fn create_outlet(
+        &self,
+        address: impl Into<Address>,
+        peer: impl Into<String>,
+        options: TcpOutletOptions,
+    ) -> Result<()> {
+        self.block_on(self.tcp.create_outlet(address, peer, options))
+    }
+
+    /// Send a message to an address or via a fully-qualified route
+    pub fn send<M>(&self, route: impl Into<Route>, msg: M) -> Result<()>
+    where
+        M: Message + Send + 'static,
+    {
+        self.block_on(self.node.send(route, msg))
+    }
+
+    /// Send a message to an address or via a fully-qualified route and receive a response
+    pub fn send_and_receive<M>(&self, route: impl Into<Route>, msg: impl Message) -> Result<M>
+    where
+        M: Message,
+    {
+        self.block_on(self.node.send_and_receive(route, msg))
+    }
+
+    /// Stop the node, and wait for its runtime to shut down
+    pub fn stop(mut self) -> Result<()> {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        let executor = match self.executor.take() {
+            Some(executor) => executor,
+            None => return Ok(()),
+        };
+        self.block_on(self.node.context().stop())?;
+        executor
+            .join()
+            .map_err(|_| Error::new(Origin::Node, Kind::Internal, "the node thread panicked"))?
+    }
+}
+
+impl Drop for BlockingNode {
+    fn drop(&mut self) {
+        if let Err(e) = self.shutdown() {
+            warn!("Failed to stop the node: {e}");
+        }
+    }
+}
@@ -75,10 +75,15 @@ mod unique;
 
 pub use error::OckamError;
 pub use metadata::OckamMessage;
-pub use relay_service::{RelayService, RelayServiceOptions};
+pub use relay_service::{
+    RelayQuota, RelayQuotas, RelayRegistration, RelayRegistrations, RelayService,
+    RelayServiceOptions, LIST_RELAYS_REQUEST,
+};
 pub use system::{SystemBuilder, SystemHandler, WorkerSystem};
 pub use unique::unique_with_prefix;
 
+#[cfg(all(feature = "std", feature = "ockam_transport_tcp"))]
+pub mod blocking;
 pub mod channel;
 pub mod pipe;
 pub mod pipe2;
@@ -147,8 +152,8 @@ pub mod vault {
 
 #[cfg(feature = "ockam_transport_tcp")]
 pub use ockam_transport_tcp::{
-    TcpConnectionOptions, TcpInletOptions, TcpListenerOptions, TcpOutletOptions, TcpTransport,
-    TcpTransportExtension,
+    TcpConnectionOptions, TcpConnectionTls, TcpInletOptions, TcpListenerOptions, TcpListenerTls,
+    TcpOutletOptions, TcpProxy, TcpTransport, TcpTransportExtension,
 };
 
 /// List of all top-level services
@@ -1,7 +1,9 @@
 mod options;
+mod quota;
 mod relay;
 #[allow(clippy::module_inception)]
 mod relay_service;
 
 pub use options::*;
+pub use quota::*;
 pub use relay_service::*;
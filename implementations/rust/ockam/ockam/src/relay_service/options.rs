@@ -1,3 +1,4 @@
+use crate::RelayQuotas;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::flow_control::{FlowControlId, FlowControls};
@@ -9,6 +10,7 @@ pub struct RelayServiceOptions {
     pub(super) relays_incoming_access_control: Arc<dyn IncomingAccessControl>,
     pub(super) consumer_service: Vec<FlowControlId>,
     pub(super) consumer_relay: Vec<FlowControlId>,
+    pub(super) quotas: RelayQuotas,
 }
 
 impl RelayServiceOptions {
@@ -19,6 +21,7 @@ impl RelayServiceOptions {
             relays_incoming_access_control: Arc::new(AllowAll),
             consumer_service: vec![],
             consumer_relay: vec![],
+            quotas: RelayQuotas::default(),
         }
     }
 
@@ -36,6 +39,12 @@ impl RelayServiceOptions {
         self
     }
 
+    /// Limit the streams and bytes forwarded by the spawned relays
+    pub fn with_quotas(mut self, quotas: RelayQuotas) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
     /// Set Service Incoming Access Control
     pub fn with_service_incoming_access_control_impl(
         mut self,
@@ -0,0 +1,186 @@
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::String;
+use ockam_core::Route;
+use serde::{Deserialize, Serialize};
+
+/// Time after which a stream without messages is not counted anymore, in seconds
+const STREAM_IDLE_TIMEOUT: u64 = 60;
+
+/// Length of the period during which the bytes of a relay are counted, in seconds
+const DAY: u64 = 24 * 60 * 60;
+
+/// Limits applied to the messages forwarded by a relay
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayQuota {
+    /// Maximum number of streams using the relay at the same time.
+    /// A stream is identified by the return route of its messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_streams: Option<usize>,
+    /// Maximum number of payload bytes forwarded by the relay per day
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes_per_day: Option<u64>,
+}
+
+impl RelayQuota {
+    /// Set the maximum number of concurrent streams
+    pub fn with_max_streams(mut self, max_streams: usize) -> Self {
+        self.max_streams = Some(max_streams);
+        self
+    }
+
+    /// Set the maximum number of bytes per day
+    pub fn with_max_bytes_per_day(mut self, max_bytes_per_day: u64) -> Self {
+        self.max_bytes_per_day = Some(max_bytes_per_day);
+        self
+    }
+
+    /// Return true if this quota doesn't limit anything
+    pub fn is_unlimited(&self) -> bool {
+        self.max_streams.is_none() && self.max_bytes_per_day.is_none()
+    }
+}
+
+/// Quotas of the relays registered on a relay service.
+///
+/// A relay uses the quota given for its address, or the default quota otherwise.
+/// They are usually read from a policy file, for example in YAML:
+///
+/// ```yaml
+/// default:
+///   max_streams: 10
+///   max_bytes_per_day: 1000000000
+/// relays:
+///   forward_to_gateway:
+///     max_streams: 100
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayQuotas {
+    /// Quota of the relays without a specific quota
+    #[serde(default)]
+    pub default: RelayQuota,
+    /// Quotas of specific relays, by relay address
+    #[serde(default)]
+    pub relays: BTreeMap<String, RelayQuota>,
+}
+
+impl RelayQuotas {
+    /// Set the default quota
+    pub fn with_default(mut self, quota: RelayQuota) -> Self {
+        self.default = quota;
+        self
+    }
+
+    /// Set the quota of a specific relay
+    pub fn with_relay(mut self, address: impl Into<String>, quota: RelayQuota) -> Self {
+        self.relays.insert(address.into(), quota);
+        self
+    }
+
+    /// Return the quota of the relay with the given address
+    pub fn quota_for(&self, address: &str) -> RelayQuota {
+        self.relays
+            .get(address)
+            .cloned()
+            .unwrap_or_else(|| self.default.clone())
+    }
+}
+
+/// Usage of a relay, checked against its quota for each forwarded message
+pub(super) struct RelayUsage {
+    quota: RelayQuota,
+    streams: BTreeMap<Route, u64>,
+    day: u64,
+    bytes: u64,
+}
+
+/// Reason why a message exceeds the quota of a relay
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum QuotaExceeded {
+    Streams(usize),
+    Bytes(u64),
+}
+
+impl RelayUsage {
+    pub(super) fn new(quota: RelayQuota) -> Self {
+        Self {
+            quota,
+            streams: BTreeMap::new(),
+            day: 0,
+            bytes: 0,
+        }
+    }
+
+    /// Account for a message of `len` bytes received at `now` (in seconds) with the given
+    /// return route, unless it exceeds the quota
+    pub(super) fn record(
+        &mut self,
+        now: u64,
+        return_route: &Route,
+        len: usize,
+    ) -> Result<(), QuotaExceeded> {
+        if self.quota.is_unlimited() {
+            return Ok(());
+        }
+
+        if let Some(max_streams) = self.quota.max_streams {
+            self.streams
+                .retain(|_, last_seen| now.saturating_sub(*last_seen) < STREAM_IDLE_TIMEOUT);
+            if !self.streams.contains_key(return_route) && self.streams.len() >= max_streams {
+                return Err(QuotaExceeded::Streams(max_streams));
+            }
+        }
+
+        if let Some(max_bytes) = self.quota.max_bytes_per_day {
+            let day = now / DAY;
+            if day != self.day {
+                self.day = day;
+                self.bytes = 0;
+            }
+            let bytes = self.bytes.saturating_add(len as u64);
+            if bytes > max_bytes {
+                return Err(QuotaExceeded::Bytes(max_bytes));
+            }
+            self.bytes = bytes;
+        }
+
+        if self.quota.max_streams.is_some() {
+            self.streams.insert(return_route.clone(), now);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::route;
+
+    #[test]
+    fn test_relay_usage() {
+        let quotas = RelayQuotas::default()
+            .with_default(RelayQuota::default().with_max_streams(1))
+            .with_relay(
+                "forward_to_gateway",
+                RelayQuota::default().with_max_bytes_per_day(10),
+            );
+
+        let mut usage = RelayUsage::new(quotas.quota_for("forward_to_other"));
+        assert_eq!(usage.record(0, &route!["a"], 100), Ok(()));
+        assert_eq!(
+            usage.record(1, &route!["b"], 1),
+            Err(QuotaExceeded::Streams(1))
+        );
+        assert_eq!(usage.record(1, &route!["a"], 1), Ok(()));
+        // the first stream is idle after a while
+        assert_eq!(usage.record(70, &route!["b"], 1), Ok(()));
+
+        let mut usage = RelayUsage::new(quotas.quota_for("forward_to_gateway"));
+        assert_eq!(usage.record(0, &route!["a"], 8), Ok(()));
+        assert_eq!(
+            usage.record(1, &route!["b"], 3),
+            Err(QuotaExceeded::Bytes(10))
+        );
+        // the bytes are counted again the next day
+        assert_eq!(usage.record(DAY, &route!["b"], 3), Ok(()));
+    }
+}
@@ -1,12 +1,14 @@
-use crate::Context;
+use crate::relay_service::quota::RelayUsage;
+use crate::{Context, RelayQuota};
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::{boxed::Box, vec::Vec};
 use ockam_core::{
     Address, AllowAll, AllowOnwardAddress, Any, IncomingAccessControl, LocalMessage,
     OutgoingAccessControl, Result, Route, Routed, TransportMessage, Worker,
 };
+use ockam_identity::utils::now;
 use ockam_node::WorkerBuilder;
-use tracing::info;
+use tracing::{info, warn};
 
 pub(super) struct Relay {
     forward_route: Route,
@@ -14,6 +16,7 @@ pub(super) struct Relay {
     // while initializing, the worker will send the payload contained in this
     // field to the `forward_route`, to indicate a successful connection
     payload: Option<Vec<u8>>,
+    usage: RelayUsage,
 }
 
 impl Relay {
@@ -23,6 +26,7 @@ impl Relay {
         forward_route: Route,
         registration_payload: Vec<u8>,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
+        quota: RelayQuota,
     ) -> Result<()> {
         info!("Created new alias {} for {}", address, forward_route);
 
@@ -38,6 +42,7 @@ impl Relay {
         let relay = Self {
             forward_route,
             payload: Some(registration_payload.clone()),
+            usage: RelayUsage::new(quota),
         };
 
         WorkerBuilder::new(relay)
@@ -76,6 +81,21 @@ impl Worker for Relay {
         ctx: &mut Self::Context,
         msg: Routed<Self::Message>,
     ) -> Result<()> {
+        // Without a system time the usage is counted from the creation of the relay
+        let timestamp = now().map(|t| *t).unwrap_or(0);
+        if let Err(exceeded) = self.usage.record(
+            timestamp,
+            &msg.local_message().transport().return_route,
+            msg.payload().len(),
+        ) {
+            warn!(
+                "Dropping a message for relay {}: {:?} exceeded",
+                ctx.address(),
+                exceeded
+            );
+            return Ok(());
+        }
+
         let mut message = msg.into_local_message();
         let transport_message = message.transport_mut();
 
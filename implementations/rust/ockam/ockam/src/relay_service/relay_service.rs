@@ -1,9 +1,33 @@
 use crate::relay_service::relay::Relay;
-use crate::{Context, RelayServiceOptions};
+use crate::{Context, Message, RelayServiceOptions};
 use core::str::from_utf8;
 use ockam_core::compat::boxed::Box;
-use ockam_core::{Address, Any, DenyAll, Result, Routed, Worker};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
+use ockam_core::{Address, AllowOnwardAddress, Any, DenyAll, Result, Route, Routed, Worker};
+use ockam_identity::utils::now;
 use ockam_node::WorkerBuilder;
+use serde::{Deserialize, Serialize};
+
+/// Payload of a request to a relay service for the list of its relays.
+/// The service answers with [`RelayRegistrations`]
+pub const LIST_RELAYS_REQUEST: &str = "list_relays";
+
+/// Relay registered on a relay service
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Message)]
+pub struct RelayRegistration {
+    /// Address of the relay on the node of the relay service
+    pub address: String,
+    /// Route to the worker which registered the relay
+    pub forward_route: String,
+    /// Creation time of the relay, in seconds since the Unix epoch
+    pub created_at: u64,
+}
+
+/// Relays currently registered on a relay service
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Message)]
+pub struct RelayRegistrations(pub Vec<RelayRegistration>);
 
 /// Alias worker to register remote workers under local names.
 ///
@@ -13,6 +37,7 @@ use ockam_node::WorkerBuilder;
 #[non_exhaustive]
 pub struct RelayService {
     options: RelayServiceOptions,
+    relays: BTreeMap<Address, RelayRegistration>,
 }
 
 impl RelayService {
@@ -28,7 +53,10 @@ impl RelayService {
 
         let service_incoming_access_control = options.service_incoming_access_control.clone();
 
-        let s = Self { options };
+        let s = Self {
+            options,
+            relays: BTreeMap::new(),
+        };
 
         WorkerBuilder::new(s)
             .with_address(address)
@@ -54,6 +82,10 @@ impl Worker for RelayService {
         let forward_route = msg.return_route();
         let payload = msg.into_transport_message().payload;
 
+        if payload.get(1..) == Some(LIST_RELAYS_REQUEST.as_bytes()) {
+            return self.send_registrations(ctx, forward_route).await;
+        }
+
         let random_address = Address::random_tagged("Relay.service");
 
         // TODO: assume that the first byte is length, ignore it.
@@ -69,15 +101,50 @@ impl Worker for RelayService {
         self.options
             .setup_flow_control_for_relay(ctx.flow_controls(), &address);
 
+        let quota = self.options.quotas.quota_for(address.address());
+
+        let registration = RelayRegistration {
+            address: address.address().to_string(),
+            forward_route: forward_route.to_string(),
+            // Without a system time the creation time is unknown
+            created_at: now().map(|t| *t).unwrap_or(0),
+        };
+
         Relay::create(
             ctx,
-            address,
+            address.clone(),
             forward_route,
             payload,
             self.options.relays_incoming_access_control.clone(),
+            quota,
         )
         .await?;
+        self.relays.insert(address, registration);
 
         Ok(())
     }
 }
+
+impl RelayService {
+    /// Send the relays which are still running to the sender of a list request
+    async fn send_registrations(&mut self, ctx: &Context, return_route: Route) -> Result<()> {
+        let workers = ctx.list_workers().await?;
+        self.relays.retain(|address, _| workers.contains(address));
+
+        // The service itself can't send messages, a detached context only replies to the sender
+        let next_hop = return_route.next()?.clone();
+        let reply_ctx = ctx
+            .new_detached(
+                Address::random_tagged("RelayService.list"),
+                DenyAll,
+                AllowOnwardAddress(next_hop),
+            )
+            .await?;
+        reply_ctx
+            .send(
+                return_route,
+                RelayRegistrations(self.relays.values().cloned().collect()),
+            )
+            .await
+    }
+}
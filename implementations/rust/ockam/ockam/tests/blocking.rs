@@ -0,0 +1,37 @@
+use ockam::blocking::BlockingNode;
+use ockam::identity::{SecureChannelListenerOptions, SecureChannelOptions};
+use ockam::workers::Echoer;
+use ockam::{NodeBuilder, TcpConnectionOptions, TcpListenerOptions};
+use ockam_core::{route, Result};
+
+#[test]
+fn blocking_node_secure_channel() -> Result<()> {
+    let node = BlockingNode::start_with(NodeBuilder::new().no_logging())?;
+
+    let identifier = node.create_identity()?;
+    let tcp_listener_options = TcpListenerOptions::new();
+    let secure_channel_listener_options = SecureChannelListenerOptions::new()
+        .as_consumer(&tcp_listener_options.spawner_flow_control_id());
+    node.node().flow_controls().add_consumer(
+        "echoer",
+        &secure_channel_listener_options.spawner_flow_control_id(),
+    );
+    node.start_worker("echoer", Echoer)?;
+    let listener = node.listen("127.0.0.1:0", tcp_listener_options)?;
+    node.create_secure_channel_listener(&identifier, "listener", secure_channel_listener_options)?;
+
+    let connection = node.connect(
+        listener.socket_address().to_string(),
+        TcpConnectionOptions::new(),
+    )?;
+    let channel = node.create_secure_channel(
+        &identifier,
+        route![connection, "listener"],
+        SecureChannelOptions::new(),
+    )?;
+
+    let reply: String = node.send_and_receive(route![channel, "echoer"], "Hello".to_string())?;
+    assert_eq!(reply, "Hello");
+
+    node.stop()
+}
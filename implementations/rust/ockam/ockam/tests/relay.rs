@@ -1,7 +1,7 @@
 use ockam::identity::{secure_channels, SecureChannelListenerOptions, SecureChannelOptions};
 use ockam::remote::{RemoteRelay, RemoteRelayOptions};
 use ockam::workers::Echoer;
-use ockam::{RelayService, RelayServiceOptions};
+use ockam::{RelayRegistrations, RelayService, RelayServiceOptions, LIST_RELAYS_REQUEST};
 use ockam_core::{route, AllowAll, Result};
 use ockam_node::{Context, MessageReceiveOptions};
 use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
@@ -253,3 +253,23 @@ async fn test4(ctx: &mut Context) -> Result<()> {
 
     ctx.stop().await
 }
+
+// Node creates a Relay service and a Remote Relay, the Relay is in the list of the relays of the service
+#[ockam_macros::test]
+async fn test_list_relays(ctx: &mut Context) -> Result<()> {
+    RelayService::create(ctx, "forwarding_service", RelayServiceOptions::new()).await?;
+
+    let remote_info = RemoteRelay::create(ctx, route![], RemoteRelayOptions::new()).await?;
+
+    let registrations = ctx
+        .send_and_receive::<RelayRegistrations>(
+            route!["forwarding_service"],
+            LIST_RELAYS_REQUEST.to_string(),
+        )
+        .await?;
+
+    assert_eq!(registrations.0.len(), 1);
+    assert_eq!(registrations.0[0].address, remote_info.remote_address());
+
+    ctx.stop().await
+}
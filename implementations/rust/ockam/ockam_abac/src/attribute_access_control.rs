@@ -10,13 +10,16 @@ use ockam_core::Result;
 use ockam_core::{IncomingAccessControl, RelayMessage};
 use tracing as log;
 
-use crate::expr::str;
+use crate::expr::{int, str};
 use crate::Expr::*;
 use crate::{eval, Env, Expr};
 use ockam_core::compat::format;
 use ockam_core::compat::string::ToString;
 use ockam_core::compat::sync::Arc;
-use ockam_identity::{Identifier, IdentitiesRepository, IdentitySecureChannelLocalInfo};
+use ockam_identity::{
+    parse_attribute_types, AttributeType, Identifier, IdentitiesRepository,
+    IdentitySecureChannelLocalInfo, ATTRIBUTE_TYPES,
+};
 
 /// This AccessControl uses a storage for authenticated attributes in order
 /// to verify if a policy expression is valid
@@ -74,6 +77,14 @@ impl AbacAccessControl {
 
         // Get identity attributes and populate the environment:
         if let Some(attrs) = self.repository.get_attributes(&id).await? {
+            // Types of the attributes which are not strings, as declared by the credential issuer
+            let attribute_types = attrs
+                .attrs()
+                .get(ATTRIBUTE_TYPES)
+                .and_then(|types| from_utf8(types).ok())
+                .map(parse_attribute_types)
+                .unwrap_or_default();
+
             for (key, value) in attrs.attrs() {
                 let key = match from_utf8(key) {
                     Ok(key) => key,
@@ -104,7 +115,21 @@ impl AbacAccessControl {
                                 "attribute already present"
                             }
                         } else {
-                            environment.put(format!("subject.{key}"), str(s.to_string()));
+                            let value = match attribute_types.get(key) {
+                                Some(AttributeType::Int) => s.parse::<i64>().map(int).ok(),
+                                Some(AttributeType::Bool) => s.parse::<bool>().map(Bool).ok(),
+                                _ => Some(str(s.to_string())),
+                            };
+                            let value = value.unwrap_or_else(|| {
+                                log::warn! {
+                                    policy = %self.expression,
+                                    id     = %id,
+                                    key    = %key,
+                                    "typed attribute value interpreted as string"
+                                }
+                                str(s.to_string())
+                            });
+                            environment.put(format!("subject.{key}"), value);
                         }
                     }
                     Err(e) => {
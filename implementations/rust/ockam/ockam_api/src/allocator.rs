@@ -0,0 +1,94 @@
+//! Allocator counting the heap memory used by a program.
+//!
+//! A binary running nodes can register the [`CountingAllocator`] as its global allocator:
+//!
+//! ```rust,ignore
+//! #[global_allocator]
+//! static ALLOCATOR: ockam_api::allocator::CountingAllocator =
+//!     ockam_api::allocator::CountingAllocator;
+//! ```
+//!
+//! The statistics of the allocator are then returned by the heap debug endpoint of its nodes,
+//! which helps diagnosing slow memory leaks on long-running nodes.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static DEALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Wrapper of the system allocator counting the allocations
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        deallocated(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            deallocated(layout.size());
+            allocated(new_size);
+        }
+        new_ptr
+    }
+}
+
+fn allocated(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    let current = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK_ALLOCATED.fetch_max(current, Ordering::Relaxed);
+}
+
+fn deallocated(size: usize) {
+    DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+}
+
+/// Statistics of the [`CountingAllocator`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocatorStatistics {
+    /// Number of bytes currently allocated
+    pub allocated_bytes: u64,
+    /// Highest number of bytes allocated at the same time
+    pub peak_allocated_bytes: u64,
+    /// Number of allocations since the program started
+    pub allocations: u64,
+    /// Number of deallocations since the program started
+    pub deallocations: u64,
+}
+
+/// Return the statistics of the [`CountingAllocator`], or None when it is not the global
+/// allocator of this program
+pub fn statistics() -> Option<AllocatorStatistics> {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    if allocations == 0 {
+        return None;
+    }
+    Some(AllocatorStatistics {
+        allocated_bytes: ALLOCATED.load(Ordering::Relaxed) as u64,
+        peak_allocated_bytes: PEAK_ALLOCATED.load(Ordering::Relaxed) as u64,
+        allocations,
+        deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+    })
+}
@@ -44,6 +44,7 @@ pub struct CreateToken<'a> {
     #[b(1)] attributes: HashMap<CowStr<'a>, CowStr<'a>>,
     #[n(2)] ttl_secs: Option<u64>,
     #[n(3)] ttl_count: Option<u64>,
+    #[n(4)] member_ttl_secs: Option<u64>,
 }
 
 impl<'a> CreateToken<'a> {
@@ -53,6 +54,7 @@ impl<'a> CreateToken<'a> {
             attributes: HashMap::new(),
             ttl_count: None,
             ttl_secs: None,
+            member_ttl_secs: None,
         }
     }
 
@@ -74,6 +76,12 @@ impl<'a> CreateToken<'a> {
         self
     }
 
+    /// Set the time after which the membership obtained with the token expires
+    pub fn with_member_ttl(mut self, duration: Option<Duration>) -> Self {
+        self.member_ttl_secs = duration.map(|d| d.as_secs());
+        self
+    }
+
     pub fn into_owned_attributes(self) -> HashMap<String, String> {
         self.attributes
             .into_iter()
@@ -88,4 +96,8 @@ impl<'a> CreateToken<'a> {
     pub fn ttl_secs(&self) -> Option<u64> {
         self.ttl_secs
     }
+
+    pub fn member_ttl_secs(&self) -> Option<u64> {
+        self.member_ttl_secs
+    }
 }
@@ -3,7 +3,7 @@ use ockam::identity::utils::now;
 use ockam::identity::OneTimeCode;
 use ockam::identity::{secure_channel_required, TRUST_CONTEXT_ID};
 use ockam::identity::{AttributesEntry, IdentityAttributesWriter};
-use ockam::identity::{Identifier, IdentitySecureChannelLocalInfo};
+use ockam::identity::{Identifier, IdentitySecureChannelLocalInfo, TimestampInSeconds};
 use ockam_core::api::{Method, RequestHeader, Response};
 use ockam_core::compat::sync::Arc;
 use ockam_core::{Result, Routed, Worker};
@@ -63,8 +63,11 @@ impl EnrollmentTokenAcceptor {
             .map(|(k, v)| (k.as_bytes().to_vec(), v.as_bytes().to_vec()))
             .chain([(TRUST_CONTEXT_ID.to_owned(), trust_context)])
             .collect();
-        let entry =
-            AttributesEntry::new(attrs, now().unwrap(), None, Some(token.issued_by.clone()));
+        let added = now().unwrap();
+        let expires = token
+            .member_ttl
+            .map(|ttl| added + TimestampInSeconds(ttl.as_secs()));
+        let entry = AttributesEntry::new(attrs, added, expires, Some(token.issued_by.clone()));
 
         if let Err(_err) = self.1.put_attributes(from, entry).await {
             return Ok(Response::internal_error(req, "attributes storage error").to_vec()?);
@@ -27,6 +27,7 @@ impl EnrollmentTokenIssuer {
         attrs: HashMap<String, String>,
         token_duration: Option<Duration>,
         ttl_count: Option<u64>,
+        member_ttl: Option<Duration>,
     ) -> Result<OneTimeCode> {
         let otc = OneTimeCode::new();
         let max_token_duration = token_duration.unwrap_or(MAX_TOKEN_DURATION);
@@ -37,6 +38,7 @@ impl EnrollmentTokenIssuer {
             created_at: Instant::now(),
             ttl: max_token_duration,
             ttl_count,
+            member_ttl,
         };
         self.0
             .tokens
@@ -79,9 +81,16 @@ impl Worker for EnrollmentTokenIssuer {
                     let att: CreateToken = dec.decode()?;
                     let duration = att.ttl_secs().map(Duration::from_secs);
                     let ttl_count = att.ttl_count();
+                    let member_ttl = att.member_ttl_secs().map(Duration::from_secs);
                     // TODO: Use ttl_duration
                     match self
-                        .issue_token(&from, att.into_owned_attributes(), duration, ttl_count)
+                        .issue_token(
+                            &from,
+                            att.into_owned_attributes(),
+                            duration,
+                            ttl_count,
+                            member_ttl,
+                        )
                         .await
                     {
                         Ok(otc) => Response::ok(&req).body(&otc).to_vec()?,
@@ -177,6 +186,7 @@ pub trait TokenIssuer {
         attributes: HashMap<&str, &str>,
         duration: Option<Duration>,
         ttl_count: Option<u64>,
+        member_ttl: Option<Duration>,
     ) -> miette::Result<OneTimeCode>;
 }
 
@@ -188,11 +198,13 @@ impl TokenIssuer for AuthorityNode {
         attributes: HashMap<&str, &str>,
         duration: Option<Duration>,
         ttl_count: Option<u64>,
+        member_ttl: Option<Duration>,
     ) -> miette::Result<OneTimeCode> {
         let body = CreateToken::new()
             .with_attributes(attributes)
             .with_ttl(duration)
-            .with_ttl_count(ttl_count);
+            .with_ttl_count(ttl_count)
+            .with_member_ttl(member_ttl);
 
         let req = Request::post("/").body(body);
         self.0
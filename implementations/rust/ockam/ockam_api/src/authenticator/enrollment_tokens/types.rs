@@ -9,4 +9,6 @@ pub(super) struct Token {
     pub(super) created_at: Instant,
     pub(super) ttl: Duration,
     pub(super) ttl_count: u64,
+    /// Time after which the membership obtained with the token expires
+    pub(super) member_ttl: Option<Duration>,
 }
@@ -2,15 +2,17 @@ use std::path::Path;
 
 use tracing::info;
 
-use ockam::identity::storage::LmdbStorage;
+use ockam::identity::storage::{LmdbStorage, Storage};
 use ockam::identity::Vault;
 use ockam::identity::{
-    CredentialsIssuer, Identifier, Identities, IdentitiesRepository, IdentitiesStorage,
-    IdentityAttributesReader, IdentityAttributesWriter, SecureChannelListenerOptions,
-    SecureChannels, TrustEveryonePolicy,
+    CredentialsCoSigner, CredentialsCoSigners, CredentialsIssuer, DelegatedIdentityAccessControl,
+    Identifier, Identities, IdentitiesRepository, IdentitiesStorage, IdentityAttributesReader,
+    IdentityAttributesWriter, SecureChannelListenerOptions, SecureChannels, TrustEveryonePolicy,
+    TRUST_CONTEXT_ID,
 };
 use ockam_abac::expr::{and, eq, ident, str};
 use ockam_abac::{AbacAccessControl, Env};
+use ockam_core::access_control::AnyIncomingAccessControl;
 use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControlId;
@@ -20,6 +22,9 @@ use ockam_transport_tcp::{TcpListenerOptions, TcpTransport};
 
 use crate::authenticator::enrollment_tokens::EnrollmentTokenAuthenticator;
 use crate::authority_node::authority::EnrollerCheck::{AnyMember, EnrollerOnly};
+use crate::authority_node::revocations::{
+    load_revocation_list, CredentialRevoker, RevocationListServer,
+};
 use crate::authority_node::Configuration;
 use crate::bootstrapped_identities_store::BootstrapedIdentityStore;
 use crate::echoer::Echoer;
@@ -32,9 +37,13 @@ use crate::{actions, DefaultAddress};
 //   - a credential issuer
 //   - an enrollment token issuer
 //   - an enrollment token acceptor
+//   - a credential revoker
+//   - a revocation list server
+//   - a credential co-signer
 pub struct Authority {
     identifier: Identifier,
     secure_channels: Arc<SecureChannels>,
+    storage: Arc<dyn Storage>,
 }
 
 /// Public functions to:
@@ -57,7 +66,8 @@ impl Authority {
     pub async fn create(configuration: &Configuration) -> Result<Authority> {
         debug!(?configuration, "creating the authority");
         let vault = Self::create_secure_channels_vault(configuration).await?;
-        let repository = Self::create_identities_repository(configuration).await?;
+        let storage = Self::create_storage(configuration).await?;
+        let repository = Self::create_identities_repository(storage.clone(), configuration);
         let secure_channels = SecureChannels::builder()
             .with_vault(vault)
             .with_identities_repository(repository)
@@ -66,9 +76,26 @@ impl Authority {
         let identifier = configuration.identifier();
         info!(identifier=%identifier, "retrieved the authority identifier");
 
+        // the credentials revoked by this authority must not be accepted anymore
+        load_revocation_list(
+            storage.clone(),
+            secure_channels.identities().revocations(),
+            &identifier,
+        )
+        .await?;
+
+        // the credentials issued by this authority must be signed by enough co-signers
+        if let Some(authority_threshold) = configuration.authority_threshold()? {
+            secure_channels
+                .identities()
+                .authority_thresholds()
+                .set(&identifier, authority_threshold);
+        }
+
         Ok(Authority {
             identifier,
             secure_channels,
+            storage,
         })
     }
 
@@ -191,24 +218,133 @@ impl Authority {
         configuration: &Configuration,
     ) -> Result<()> {
         // create and start a credential issuer worker
-        let issuer = CredentialsIssuer::new(
+        let mut issuer = CredentialsIssuer::new(
             self.secure_channels.identities().repository(),
             self.secure_channels.identities().credentials(),
             &self.identifier,
             configuration.project_identifier(),
         );
+        if let Some(route_constraints) = configuration.credential_route_constraints() {
+            issuer = issuer.with_route_constraints(route_constraints);
+        }
+        issuer = issuer.with_delegations(self.identities().identities_delegation());
+        if let Some(authority_threshold) = configuration.authority_threshold()? {
+            // this authority signature counts in the threshold
+            let co_signers = CredentialsCoSigners::new(
+                self.secure_channels.clone(),
+                configuration.co_signers.clone(),
+                authority_threshold.threshold - 1,
+            );
+            issuer = issuer.with_co_signers(Arc::new(co_signers));
+        }
 
         let address = DefaultAddress::CREDENTIAL_ISSUER.to_string();
         ctx.flow_controls()
             .add_consumer(address.clone(), secure_channel_flow_control_id);
 
-        self.start(ctx, configuration, address.clone(), AnyMember, issuer)
+        // the devices delegated by a member can also retrieve a credential
+        let delegated_members = DelegatedIdentityAccessControl::new(
+            &[(
+                TRUST_CONTEXT_ID.to_vec(),
+                configuration.project_identifier().as_bytes().to_vec(),
+            )],
+            self.identities().identities_delegation(),
+            self.identities_repository(),
+        );
+        let access_control = AnyIncomingAccessControl::new(vec![
+            self.create_abac_policy(configuration, address.clone(), AnyMember),
+            Arc::new(delegated_members),
+        ]);
+        WorkerBuilder::new(issuer)
+            .with_address(address.clone())
+            .with_incoming_access_control(access_control)
+            .start(ctx)
             .await?;
 
         info!("started a credential issuer at '{address}'");
         Ok(())
     }
 
+    /// Start the services to revoke credentials and to publish the list of revoked credentials
+    pub async fn start_revocation_services(
+        &self,
+        ctx: &Context,
+        secure_channel_flow_control_id: &FlowControlId,
+        configuration: &Configuration,
+    ) -> Result<()> {
+        let revocations = self.identities().revocations();
+
+        // start a credential revoker with an abac policy checking that
+        // the caller is an enroller for the authority project
+        let revoker = CredentialRevoker::new(
+            self.identifier(),
+            revocations.clone(),
+            self.storage.clone(),
+            self.attributes_writer(),
+        );
+        let revoker_address = DefaultAddress::CREDENTIAL_REVOKER.to_string();
+        ctx.flow_controls()
+            .add_consumer(revoker_address.clone(), secure_channel_flow_control_id);
+
+        self.start(
+            ctx,
+            configuration,
+            revoker_address.clone(),
+            EnrollerOnly,
+            revoker,
+        )
+        .await?;
+
+        // start a revocation list server for the project members
+        let server = RevocationListServer::new(self.identifier(), revocations);
+        let server_address = DefaultAddress::REVOCATION_LIST.to_string();
+        ctx.flow_controls()
+            .add_consumer(server_address.clone(), secure_channel_flow_control_id);
+
+        self.start(
+            ctx,
+            configuration,
+            server_address.clone(),
+            AnyMember,
+            server,
+        )
+        .await?;
+
+        info!("started a credential revoker at '{revoker_address}'");
+        info!("started a revocation list server at '{server_address}'");
+        Ok(())
+    }
+
+    /// Start the service co-signing the credentials issued by other authorities
+    pub async fn start_credential_co_signer(
+        &self,
+        ctx: &Context,
+        secure_channel_flow_control_id: &FlowControlId,
+        configuration: &Configuration,
+    ) -> Result<()> {
+        if configuration.co_sign_for.is_empty() {
+            return Ok(());
+        }
+
+        // the co-signer only accepts requests from the configured issuers
+        let co_signer = CredentialsCoSigner::new(
+            self.identities_repository(),
+            self.identities().credentials(),
+            &self.identifier,
+            configuration.co_sign_for.clone(),
+        )
+        .with_delegations(self.identities().identities_delegation());
+
+        let address = DefaultAddress::CREDENTIAL_CO_SIGNER.to_string();
+        ctx.flow_controls()
+            .add_consumer(address.clone(), secure_channel_flow_control_id);
+
+        ctx.start_worker(address.clone(), co_signer).await?;
+
+        info!("started a credential co-signer at '{address}'");
+        Ok(())
+    }
+
     /// Start the Okta service to retrieve attributes authenticated by Okta
     pub async fn start_okta(
         &self,
@@ -279,14 +415,19 @@ impl Authority {
     }
 
     /// Create an authenticated storage backed by a Lmdb database
-    async fn create_identities_repository(
-        configuration: &Configuration,
-    ) -> Result<Arc<dyn IdentitiesRepository>> {
+    async fn create_storage(configuration: &Configuration) -> Result<Arc<dyn Storage>> {
         let storage_path = &configuration.storage_path;
         Self::create_ockam_directory_if_necessary(storage_path)?;
-        let storage = Arc::new(LmdbStorage::new(&storage_path).await?);
+        Ok(Arc::new(LmdbStorage::new(&storage_path).await?))
+    }
+
+    /// Create an identities repository using the authority storage
+    fn create_identities_repository(
+        storage: Arc<dyn Storage>,
+        configuration: &Configuration,
+    ) -> Arc<dyn IdentitiesRepository> {
         let repository = Arc::new(IdentitiesStorage::new(storage));
-        Ok(Self::bootstrap_repository(repository, configuration))
+        Self::bootstrap_repository(repository, configuration)
     }
 
     /// Create a directory to save storage files if they haven't been  created before
@@ -1,8 +1,11 @@
 use crate::bootstrapped_identities_store::PreTrustedIdentities;
 use crate::DefaultAddress;
 
+use ockam::identity::models::CredentialRouteConstraints;
 use ockam::identity::utils::now;
-use ockam::identity::{AttributesEntry, Identifier, TRUST_CONTEXT_ID};
+use ockam::identity::{
+    AttributesEntry, AuthorityThreshold, CredentialsCoSignerInfo, Identifier, TRUST_CONTEXT_ID,
+};
 use ockam_core::compat::collections::HashMap;
 use ockam_core::compat::fmt;
 use ockam_core::compat::fmt::{Display, Formatter};
@@ -47,6 +50,23 @@ pub struct Configuration {
 
     /// optional configuration for the okta service
     pub okta: Option<OktaConfiguration>,
+
+    /// identities of the nodes (for example project relays) via which the issued credentials
+    /// must be presented. If empty, the credentials can be presented via any node
+    pub credential_via: Vec<Identifier>,
+
+    /// If true, the issued credentials can't be presented over a direct transport connection
+    pub credential_deny_direct: bool,
+
+    /// Authorities asked to co-sign the issued credentials
+    pub co_signers: Vec<CredentialsCoSignerInfo>,
+
+    /// Number of distinct authorities, including this one, which must sign each credential.
+    /// If set, the credentials are co-signed by the co-signers until the threshold is reached
+    pub threshold: Option<usize>,
+
+    /// Identities of the authorities whose credentials are co-signed by this authority
+    pub co_sign_for: Vec<Identifier>,
 }
 
 /// Local and private functions for the authority configuration
@@ -79,6 +99,30 @@ impl Configuration {
             .clone()
             .unwrap_or(DefaultAddress::DIRECT_AUTHENTICATOR.to_string())
     }
+    /// Return the route constraints to set on the issued credentials, if any
+    pub(crate) fn credential_route_constraints(&self) -> Option<CredentialRouteConstraints> {
+        if self.credential_via.is_empty() && !self.credential_deny_direct {
+            None
+        } else {
+            Some(CredentialRouteConstraints {
+                via: self.credential_via.clone(),
+                deny_direct: self.credential_deny_direct,
+            })
+        }
+    }
+
+    /// Return the threshold of signatures required for the issued credentials, if any.
+    /// The signers are this authority and its co-signers
+    pub(crate) fn authority_threshold(&self) -> ockam_core::Result<Option<AuthorityThreshold>> {
+        match self.threshold {
+            Some(threshold) => {
+                let mut signers = vec![self.identifier()];
+                signers.extend(self.co_signers.iter().map(|c| c.identifier.clone()));
+                Ok(Some(AuthorityThreshold::new(signers, threshold)?))
+            }
+            None => Ok(None),
+        }
+    }
 }
 
 /// Configuration for the Okta service
@@ -1,7 +1,9 @@
 mod authority;
 mod configuration;
 mod node;
+mod revocations;
 
 pub use authority::*;
 pub use configuration::*;
 pub use node::*;
+pub use revocations::*;
@@ -0,0 +1,202 @@
+use miette::IntoDiagnostic;
+use minicbor::{Decode, Decoder, Encode};
+use tracing::{info, trace};
+
+use ockam::identity::models::RevocationList;
+use ockam::identity::storage::Storage;
+use ockam::identity::utils::now;
+use ockam::identity::{
+    secure_channel_required, Identifier, IdentityAttributesWriter, IdentitySecureChannelLocalInfo,
+    Revocations,
+};
+use ockam_core::api::{Method, Request, RequestHeader, Response};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{async_trait, Result, Routed, Worker};
+use ockam_node::Context;
+
+use crate::cloud::AuthorityNode;
+use crate::DefaultAddress;
+
+/// Storage namespace and key of the revocation list of an authority
+const REVOCATIONS: &str = "revocations";
+const REVOCATION_LIST: &str = "revocation_list";
+
+/// Request to revoke all the credentials issued to a subject so far
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RevokeCredentials {
+    #[n(1)] subject: Identifier,
+}
+
+impl RevokeCredentials {
+    pub fn new(subject: Identifier) -> Self {
+        Self { subject }
+    }
+
+    pub fn subject(&self) -> &Identifier {
+        &self.subject
+    }
+}
+
+/// Load the persisted revocation list of an authority in its revocations cache
+pub(crate) async fn load_revocation_list(
+    storage: Arc<dyn Storage>,
+    revocations: Arc<Revocations>,
+    authority: &Identifier,
+) -> Result<()> {
+    if let Some(bytes) = storage.get(REVOCATIONS, REVOCATION_LIST).await? {
+        let revocation_list: RevocationList = minicbor::decode(&bytes)?;
+        revocations.update(authority, revocation_list);
+    }
+    Ok(())
+}
+
+/// This worker revokes the credentials of a subject. The subject is removed from the members
+/// of the project so that no new credential can be issued to it, and it is added to the
+/// revocation list of the authority
+pub struct CredentialRevoker {
+    authority: Identifier,
+    revocations: Arc<Revocations>,
+    storage: Arc<dyn Storage>,
+    attributes_writer: Arc<dyn IdentityAttributesWriter>,
+}
+
+impl CredentialRevoker {
+    pub fn new(
+        authority: Identifier,
+        revocations: Arc<Revocations>,
+        storage: Arc<dyn Storage>,
+        attributes_writer: Arc<dyn IdentityAttributesWriter>,
+    ) -> Self {
+        Self {
+            authority,
+            revocations,
+            storage,
+            attributes_writer,
+        }
+    }
+
+    async fn revoke(&self, subject: &Identifier) -> Result<()> {
+        self.attributes_writer.delete(subject).await?;
+        self.revocations.revoke(&self.authority, subject, now()?);
+
+        let revocation_list = self.revocations.revocation_list(&self.authority);
+        self.storage
+            .set(
+                REVOCATIONS,
+                REVOCATION_LIST.to_string(),
+                minicbor::to_vec(revocation_list)?,
+            )
+            .await?;
+        info!(%subject, "revoked the credentials of a subject");
+        Ok(())
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for CredentialRevoker {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        if let Ok(i) = IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
+            let from = i.their_identity_id();
+            let mut dec = Decoder::new(m.as_body());
+            let req: RequestHeader = dec.decode()?;
+            trace! {
+                target: "ockam_api::authority_node::revocations::credential_revoker",
+                from   = %from,
+                id     = %req.id(),
+                method = ?req.method(),
+                path   = %req.path(),
+                body   = %req.has_body(),
+                "request"
+            }
+            let res = match (req.method(), req.path()) {
+                (Some(Method::Post), "/") => {
+                    let revoke: RevokeCredentials = dec.decode()?;
+                    match self.revoke(revoke.subject()).await {
+                        Ok(()) => Response::ok(&req).to_vec()?,
+                        Err(error) => {
+                            Response::internal_error(&req, &error.to_string()).to_vec()?
+                        }
+                    }
+                }
+                _ => Response::unknown_path(&req).to_vec()?,
+            };
+            c.send(m.return_route(), res).await
+        } else {
+            secure_channel_required(c, m).await
+        }
+    }
+}
+
+/// This worker returns the revocation list of an authority, so that the nodes of a project
+/// can reject the revoked credentials presented to them
+pub struct RevocationListServer {
+    authority: Identifier,
+    revocations: Arc<Revocations>,
+}
+
+impl RevocationListServer {
+    pub fn new(authority: Identifier, revocations: Arc<Revocations>) -> Self {
+        Self {
+            authority,
+            revocations,
+        }
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for RevocationListServer {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        if IdentitySecureChannelLocalInfo::find_info(m.local_message()).is_ok() {
+            let mut dec = Decoder::new(m.as_body());
+            let req: RequestHeader = dec.decode()?;
+            let res = match (req.method(), req.path()) {
+                (Some(Method::Get), "/") => {
+                    let revocation_list = self.revocations.revocation_list(&self.authority);
+                    Response::ok(&req).body(revocation_list).to_vec()?
+                }
+                _ => Response::unknown_path(&req).to_vec()?,
+            };
+            c.send(m.return_route(), res).await
+        } else {
+            secure_channel_required(c, m).await
+        }
+    }
+}
+
+#[async_trait]
+pub trait CredentialRevocations {
+    async fn revoke_credentials(&self, ctx: &Context, subject: Identifier) -> miette::Result<()>;
+
+    async fn revocation_list(&self, ctx: &Context) -> miette::Result<RevocationList>;
+}
+
+#[async_trait]
+impl CredentialRevocations for AuthorityNode {
+    async fn revoke_credentials(&self, ctx: &Context, subject: Identifier) -> miette::Result<()> {
+        let req = Request::post("/").body(RevokeCredentials::new(subject));
+        self.0
+            .tell(ctx, DefaultAddress::CREDENTIAL_REVOKER, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
+    async fn revocation_list(&self, ctx: &Context) -> miette::Result<RevocationList> {
+        let req = Request::get("/");
+        self.0
+            .ask(ctx, DefaultAddress::REVOCATION_LIST, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+}
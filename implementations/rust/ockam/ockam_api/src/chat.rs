@@ -0,0 +1,215 @@
+//! Text messages exchanged between identities.
+//!
+//! The chat service is started on every node. It accepts messages received over a secure channel,
+//! and keeps them in memory until they are fetched:
+//!
+//!  - a message sent without a recipient is delivered to the identity of the node, and can be
+//!    read with the node API,
+//!  - a message sent for another identity is stored until that identity fetches it, through a
+//!    secure channel to the node. A node reachable by both parties, for example through a relay,
+//!    can then be used as a mailbox.
+//!
+//! Since a chat message goes through the same connections, secure channels, relays and policies
+//! as any other message, sending one is also a way to check the connectivity between two nodes.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+
+use ockam::identity::utils::now;
+use ockam::identity::{Identifier, IdentitySecureChannelLocalInfo};
+use ockam::{Context, Result, Routed, Worker};
+use ockam_core::Route;
+use ockam_node::MessageSendReceiveOptions;
+
+use crate::error::ApiError;
+
+/// Maximum number of messages kept for an identity. The oldest messages are dropped first
+pub const MAX_CHAT_MESSAGES: usize = 100;
+
+/// Request sent to a chat service
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+pub enum ChatRequest {
+    /// Deliver a text, for the identity of the node if no recipient is given
+    #[n(0)] Send(#[n(0)] Option<Identifier>, #[n(1)] String),
+    /// Return the messages stored for the caller, and remove them from the service
+    #[n(1)] Fetch,
+}
+
+/// Response of a chat service
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+pub enum ChatResponse {
+    /// The message was stored for this identity
+    #[n(0)] Delivered(#[n(0)] Identifier),
+    #[n(1)] Messages(#[n(0)] Vec<ChatMessage>),
+    #[n(2)] Rejected(#[n(0)] String),
+}
+
+/// Message stored by a chat service
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ChatMessage {
+    /// Identifier of the sender
+    #[n(1)] pub from: String,
+    #[n(2)] pub text: String,
+    /// Reception time, in seconds since the Unix epoch
+    #[n(3)] pub received_at: u64,
+}
+
+/// Messages stored by a chat service, for each recipient
+#[derive(Clone, Default)]
+pub struct ChatInbox {
+    messages: Arc<Mutex<BTreeMap<Identifier, VecDeque<ChatMessage>>>>,
+}
+
+impl ChatInbox {
+    pub fn store(&self, recipient: Identifier, message: ChatMessage) {
+        let mut messages = self.messages.lock().unwrap();
+        let inbox = messages.entry(recipient).or_default();
+        if inbox.len() >= MAX_CHAT_MESSAGES {
+            inbox.pop_front();
+        }
+        inbox.push_back(message);
+    }
+
+    /// Remove and return the messages stored for an identity, oldest first
+    pub fn take(&self, recipient: &Identifier) -> Vec<ChatMessage> {
+        self.messages
+            .lock()
+            .unwrap()
+            .remove(recipient)
+            .map(Vec::from)
+            .unwrap_or_default()
+    }
+}
+
+pub struct ChatService {
+    /// Identity of the node, receiving the messages sent without a recipient
+    identifier: Identifier,
+    inbox: ChatInbox,
+}
+
+impl ChatService {
+    pub fn new(identifier: Identifier, inbox: ChatInbox) -> Self {
+        Self { identifier, inbox }
+    }
+}
+
+#[ockam::worker]
+impl Worker for ChatService {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Vec<u8>>) -> Result<()> {
+        let return_route = msg.return_route();
+        let caller = match IdentitySecureChannelLocalInfo::find_info(msg.local_message()) {
+            Ok(info) => info.their_identity_id(),
+            Err(_) => {
+                debug!(src = %msg.src_addr(), "chat request rejected: not received over a secure channel");
+                let rejected = ChatResponse::Rejected("a secure channel is required".to_string());
+                return ctx.send(return_route, minicbor::to_vec(rejected)?).await;
+            }
+        };
+
+        let response = match minicbor::decode(msg.as_body())? {
+            ChatRequest::Send(recipient, text) => {
+                let recipient = recipient.unwrap_or_else(|| self.identifier.clone());
+                debug!(%caller, %recipient, "chat message received");
+                let message = ChatMessage {
+                    from: caller.to_string(),
+                    text,
+                    received_at: *now()?,
+                };
+                self.inbox.store(recipient.clone(), message);
+                ChatResponse::Delivered(recipient)
+            }
+            ChatRequest::Fetch => ChatResponse::Messages(self.inbox.take(&caller)),
+        };
+        ctx.send(return_route, minicbor::to_vec(response)?).await
+    }
+}
+
+/// Send a text to the chat service at the end of `route`, for `recipient`, or for the identity
+/// of the node of the service if no recipient is given.
+///
+/// Return the identifier of the recipient once the message is stored by the service
+pub async fn send_chat_message(
+    ctx: &Context,
+    route: Route,
+    recipient: Option<Identifier>,
+    text: String,
+    timeout: Duration,
+) -> Result<Identifier> {
+    match chat_request(ctx, route, ChatRequest::Send(recipient, text), timeout).await? {
+        ChatResponse::Delivered(recipient) => Ok(recipient),
+        response => Err(unexpected_response(response)),
+    }
+}
+
+/// Fetch the messages stored for the caller by the chat service at the end of `route`
+pub async fn fetch_chat_messages(
+    ctx: &Context,
+    route: Route,
+    timeout: Duration,
+) -> Result<Vec<ChatMessage>> {
+    match chat_request(ctx, route, ChatRequest::Fetch, timeout).await? {
+        ChatResponse::Messages(messages) => Ok(messages),
+        response => Err(unexpected_response(response)),
+    }
+}
+
+async fn chat_request(
+    ctx: &Context,
+    route: Route,
+    request: ChatRequest,
+    timeout: Duration,
+) -> Result<ChatResponse> {
+    let response = ctx
+        .send_and_receive_extended::<Vec<u8>>(
+            route,
+            minicbor::to_vec(request)?,
+            MessageSendReceiveOptions::new().with_timeout(timeout),
+        )
+        .await?
+        .body();
+    Ok(minicbor::decode(&response)?)
+}
+
+fn unexpected_response(response: ChatResponse) -> ockam_core::Error {
+    match response {
+        ChatResponse::Rejected(reason) => {
+            ApiError::core(format!("The chat service rejected the request: {reason}"))
+        }
+        _ => ApiError::core("Unexpected response from the chat service"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inbox_keeps_the_latest_messages() {
+        let inbox = ChatInbox::default();
+        let recipient = Identifier([1; 20]);
+        for i in 0..MAX_CHAT_MESSAGES + 1 {
+            let message = ChatMessage {
+                from: "sender".to_string(),
+                text: i.to_string(),
+                received_at: 0,
+            };
+            inbox.store(recipient.clone(), message);
+        }
+
+        let messages = inbox.take(&recipient);
+        assert_eq!(messages.len(), MAX_CHAT_MESSAGES);
+        assert_eq!(messages[0].text, "1");
+        assert!(inbox.take(&recipient).is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 use super::Result;
+use crate::cli_state::traits::{read_item_config, write_item_config};
 use crate::cli_state::CliStateError;
 use ockam::identity::models::CredentialAndPurposeKey;
 use ockam::identity::Identifier;
@@ -84,14 +85,14 @@ mod traits {
 
         fn new(path: PathBuf, config: Self::Config) -> Result<Self> {
             let contents = serde_json::to_string(&config)?;
-            std::fs::write(&path, contents)?;
+            write_item_config(&path, &path, contents)?;
             let name = file_stem(&path)?;
             Ok(Self { name, path, config })
         }
 
         fn load(path: PathBuf) -> Result<Self> {
             let name = file_stem(&path)?;
-            let contents = std::fs::read_to_string(&path)?;
+            let contents = read_item_config(&path, &path)?;
             let config = serde_json::from_str(&contents)?;
             Ok(Self { name, path, config })
         }
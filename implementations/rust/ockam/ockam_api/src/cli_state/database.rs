@@ -0,0 +1,482 @@
+//! SQLite storage of the state items.
+//!
+//! When the `sqlite` feature is enabled, the items of the state directories are stored in the
+//! `state.sqlite` database at the root of the state directory, and the [`StateDirTrait`]
+//! methods read the items, their names and the default items from the database. Changing the
+//! default item or synchronizing a state directory is done in a single transaction.
+//!
+//! The JSON files are still written, so that a version of ockam built without the feature
+//! can use the same state directory. When the state directories are loaded, the items which
+//! were created, changed or deleted in the files since they were recorded are synchronized
+//! in the database.
+//!
+//! The database can also be queried across all the items of a kind, for example all the nodes
+//! using a given project, without reading and parsing every file of the state directory.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::cli_state::traits::StateDirTrait;
+use crate::cli_state::{file_stem, CliState, CliStateError, Result, LOCK_TIMEOUT};
+
+/// Name of the database file, at the root of the state directory
+pub const DATABASE_FILE_NAME: &str = "state.sqlite";
+
+const CREATE_ITEM_TABLE_SQL: &str = "CREATE TABLE IF NOT EXISTS item (
+    kind TEXT NOT NULL,
+    name TEXT NOT NULL,
+    config TEXT NOT NULL,
+    is_default INTEGER NOT NULL DEFAULT 0,
+    updated_at INTEGER NOT NULL,
+    file_modified_at INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (kind, name)
+);";
+
+/// The connections opened by this process, one per state directory
+static DATABASES: Mutex<BTreeMap<PathBuf, Arc<Mutex<StateDatabase>>>> = Mutex::new(BTreeMap::new());
+
+/// Configuration of a state item, as stored in the database
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StateRecord {
+    /// Kind of the item, which is the name of its state directory, e.g. `nodes`
+    pub kind: String,
+    pub name: String,
+    pub config: serde_json::Value,
+    pub is_default: bool,
+    /// Time of the last change of the item, in seconds since the Unix epoch
+    pub updated_at: u64,
+}
+
+pub struct StateDatabase {
+    conn: Connection,
+}
+
+impl StateDatabase {
+    /// Open the database of a state directory, creating it if it doesn't exist
+    pub fn open(root_path: &Path) -> Result<Self> {
+        let conn = Connection::open(Self::path(root_path))?;
+        // Several ockam processes can change the state at the same time
+        conn.busy_timeout(LOCK_TIMEOUT)?;
+        conn.execute_batch(&("PRAGMA encoding = 'UTF-8';".to_owned() + CREATE_ITEM_TABLE_SQL))?;
+        Ok(Self { conn })
+    }
+
+    pub fn path(root_path: &Path) -> PathBuf {
+        root_path.join(DATABASE_FILE_NAME)
+    }
+
+    /// Insert or replace the configuration of an item. `file_modified_at` is the modification
+    /// time of the item file which was written with the same configuration
+    pub fn upsert(
+        &self,
+        kind: &str,
+        name: &str,
+        config: &str,
+        file_modified_at: i64,
+    ) -> Result<()> {
+        upsert(&self.conn, kind, name, config, file_modified_at)
+    }
+
+    pub fn remove(&self, kind: &str, name: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM item WHERE kind = ?1 AND name = ?2",
+            params![kind, name],
+        )?;
+        Ok(())
+    }
+
+    /// Set an item as the default item of its kind, in a single transaction
+    pub fn set_default(&mut self, kind: &str, name: &str) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        set_default(&tx, kind, Some(name))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn contains(&self, kind: &str, name: &str) -> Result<bool> {
+        Ok(self.config(kind, name)?.is_some())
+    }
+
+    /// Return the configuration of an item, serialized as JSON
+    pub fn config(&self, kind: &str, name: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT config FROM item WHERE kind = ?1 AND name = ?2",
+                params![kind, name],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Return the names of the items of a kind, sorted by name
+    pub fn names(&self, kind: &str) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name FROM item WHERE kind = ?1 ORDER BY name")?;
+        let rows = stmt.query_map(params![kind], |row| row.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn default_name(&self, kind: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT name FROM item WHERE kind = ?1 AND is_default = 1",
+                params![kind],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn get(&self, kind: &str, name: &str) -> Result<Option<StateRecord>> {
+        self.conn
+            .query_row(
+                "SELECT kind, name, config, is_default, updated_at FROM item
+                 WHERE kind = ?1 AND name = ?2",
+                params![kind, name],
+                read_row,
+            )
+            .optional()?
+            .map(into_record)
+            .transpose()
+    }
+
+    /// Return all the items of a kind, sorted by name
+    pub fn items(&self, kind: &str) -> Result<Vec<StateRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT kind, name, config, is_default, updated_at FROM item
+             WHERE kind = ?1 ORDER BY name",
+        )?;
+        let rows = stmt.query_map(params![kind], read_row)?;
+        rows.map(|row| into_record(row?)).collect()
+    }
+
+    /// Return the items of a kind having a given value at a JSON path of their configuration,
+    /// e.g. the nodes with the value `p1` at `$.project.name`
+    pub fn find(&self, kind: &str, json_path: &str, value: &str) -> Result<Vec<StateRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT kind, name, config, is_default, updated_at FROM item
+             WHERE kind = ?1 AND json_extract(config, ?2) = ?3 ORDER BY name",
+        )?;
+        let rows = stmt.query_map(params![kind, json_path, value], read_row)?;
+        rows.map(|row| into_record(row?)).collect()
+    }
+
+    /// Record the changes made to the files of a state directory since they were last recorded,
+    /// for example by a version of ockam built without the `sqlite` feature, in a single
+    /// transaction
+    pub fn sync<D: StateDirTrait>(&mut self, dir: &D) -> Result<()> {
+        let kind = dir_kind(dir.dir())?;
+        let tx = self.conn.transaction()?;
+        // The modification time and the size of the file written with the recorded configuration
+        let recorded: BTreeMap<String, (i64, u64)> = {
+            let mut stmt = tx.prepare(
+                "SELECT name, file_modified_at, length(CAST(config AS BLOB)) FROM item
+                 WHERE kind = ?1",
+            )?;
+            let rows = stmt.query_map(params![kind], |row| {
+                Ok((row.get(0)?, (row.get(1)?, row.get(2)?)))
+            })?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        // A node directory without a setup file is an incomplete node, which is not an item
+        let names: BTreeSet<String> = dir
+            .list_item_files_names()?
+            .into_iter()
+            .filter(|name| dir.config_file(name).exists())
+            .collect();
+        for name in &names {
+            let file = dir.config_file(name);
+            let modified_at = modified_at(&file)?;
+            let size = std::fs::metadata(&file)?.len();
+            if recorded.get(name) != Some(&(modified_at, size)) {
+                let config = std::fs::read_to_string(&file)?;
+                upsert(&tx, &kind, name, &config, modified_at)?;
+                debug!(%kind, %name, "state item imported in the state database");
+            }
+        }
+        for name in recorded.keys().filter(|name| !names.contains(*name)) {
+            tx.execute(
+                "DELETE FROM item WHERE kind = ?1 AND name = ?2",
+                params![kind, name],
+            )?;
+            debug!(%kind, %name, "state item removed from the state database");
+        }
+        let default = std::fs::canonicalize(dir.default_path()?)
+            .ok()
+            .map(|path| file_stem(&path))
+            .transpose()?;
+        set_default(&tx, &kind, default.as_deref())?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+impl CliState {
+    /// Run a query on the state database
+    pub fn with_database<T>(&self, f: impl FnOnce(&StateDatabase) -> Result<T>) -> Result<T> {
+        let database = connection(&self.dir)?;
+        let database = database.lock().map_err(|_| poisoned())?;
+        f(&database)
+    }
+}
+
+/// Synchronize the items of a state directory in the database
+pub(crate) fn sync<D: StateDirTrait>(dir: &D) -> Result<()> {
+    let database = connection(root_path(dir.dir())?)?;
+    let mut database = database.lock().map_err(|_| poisoned())?;
+    database.sync(dir)
+}
+
+/// Run a query on the items of a state directory. The files are used instead of the
+/// database if it can't be queried, so the error is only logged
+pub(crate) fn query<T>(dir: &Path, f: impl FnOnce(&StateDatabase, &str) -> Result<T>) -> Option<T> {
+    let run = || {
+        let database = connection(root_path(dir)?)?;
+        let database = database.lock().map_err(|_| poisoned())?;
+        f(&database, &dir_kind(dir)?)
+    };
+    match run() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!(%e, dir = %dir.display(), "the state database could not be queried");
+            None
+        }
+    }
+}
+
+/// Change the items of a state directory
+pub(crate) fn update(
+    dir: &Path,
+    f: impl FnOnce(&mut StateDatabase, &str) -> Result<()>,
+) -> Result<()> {
+    let database = connection(root_path(dir)?)?;
+    let mut database = database.lock().map_err(|_| poisoned())?;
+    f(&mut database, &dir_kind(dir)?)
+}
+
+/// Return the configuration of the item stored at `item_path`
+pub(crate) fn item_config(item_path: &Path) -> Option<String> {
+    let dir = item_path.parent()?;
+    let name = file_stem(item_path).ok()?;
+    query(dir, |db, kind| db.config(kind, &name)).flatten()
+}
+
+/// Store the configuration of the item stored at `item_path`, once it has been written in `file`
+pub(crate) fn set_item_config(item_path: &Path, file: &Path, config: &str) -> Result<()> {
+    let dir = item_path
+        .parent()
+        .ok_or_else(|| CliStateError::InvalidPath(item_path.display().to_string()))?;
+    let name = file_stem(item_path)?;
+    let modified_at = modified_at(file)?;
+    update(dir, |db, kind| db.upsert(kind, &name, config, modified_at))
+}
+
+/// Close the connection to the database of a state directory and delete the database
+pub(crate) fn delete(root_path: &Path) -> Result<()> {
+    if let Ok(mut databases) = DATABASES.lock() {
+        databases.remove(root_path);
+    }
+    std::fs::remove_file(StateDatabase::path(root_path))?;
+    Ok(())
+}
+
+/// Return the connection to the database of a state directory, which is opened once per process
+fn connection(root_path: &Path) -> Result<Arc<Mutex<StateDatabase>>> {
+    let mut databases = DATABASES.lock().map_err(|_| poisoned())?;
+    if let Some(database) = databases.get(root_path) {
+        return Ok(database.clone());
+    }
+    let database = Arc::new(Mutex::new(StateDatabase::open(root_path)?));
+    databases.insert(root_path.to_path_buf(), database.clone());
+    Ok(database)
+}
+
+fn upsert(
+    conn: &Connection,
+    kind: &str,
+    name: &str,
+    config: &str,
+    file_modified_at: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO item (kind, name, config, updated_at, file_modified_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (kind, name) DO UPDATE SET config = ?3, updated_at = ?4, file_modified_at = ?5",
+        params![kind, name, config, now(), file_modified_at],
+    )?;
+    Ok(())
+}
+
+fn set_default(conn: &Connection, kind: &str, name: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE item SET is_default = (name IS ?2) WHERE kind = ?1",
+        params![kind, name],
+    )?;
+    Ok(())
+}
+
+fn root_path(dir: &Path) -> Result<&Path> {
+    dir.parent()
+        .ok_or_else(|| CliStateError::InvalidPath(dir.display().to_string()))
+}
+
+/// The kind of the items of a state directory is the name of the directory
+fn dir_kind(dir: &Path) -> Result<String> {
+    dir.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| CliStateError::InvalidPath(dir.display().to_string()))
+}
+
+/// Modification time of a file, in nanoseconds since the Unix epoch
+fn modified_at(file: &Path) -> Result<i64> {
+    let modified = std::fs::metadata(file)?.modified()?;
+    Ok(modified
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as i64)
+        .unwrap_or_default())
+}
+
+fn poisoned() -> CliStateError {
+    CliStateError::InvalidOperation("the state database connection is poisoned".to_string())
+}
+
+type Row = (String, String, String, bool, u64);
+
+fn read_row(row: &rusqlite::Row) -> rusqlite::Result<Row> {
+    Ok((
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+    ))
+}
+
+fn into_record((kind, name, config, is_default, updated_at): Row) -> Result<StateRecord> {
+    Ok(StateRecord {
+        kind,
+        name,
+        config: serde_json::from_str(&config)?,
+        is_default,
+        updated_at,
+    })
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli_state::{SpaceConfig, StateItemTrait};
+    use serde_json::json;
+
+    #[test]
+    fn items_can_be_queried_by_kind_and_configuration() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = StateDatabase::open(dir.path()).unwrap();
+        db.upsert("nodes", "n1", r#"{"project": {"name": "p1"}}"#, 0)
+            .unwrap();
+        db.upsert("nodes", "n2", r#"{"project": {"name": "p2"}}"#, 0)
+            .unwrap();
+        db.upsert("spaces", "s1", r#"{"name": "s1"}"#, 0).unwrap();
+        db.set_default("nodes", "n2").unwrap();
+
+        let nodes = db.items("nodes").unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert!(!nodes[0].is_default && nodes[1].is_default);
+        assert_eq!(nodes[0].config, json!({"project": {"name": "p1"}}));
+        assert_eq!(db.default_name("nodes").unwrap(), Some("n2".to_string()));
+
+        let found = db.find("nodes", "$.project.name", "p1").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "n1");
+
+        db.remove("nodes", "n1").unwrap();
+        assert!(db.get("nodes", "n1").unwrap().is_none());
+        assert_eq!(db.names("nodes").unwrap(), vec!["n2"]);
+    }
+
+    #[test]
+    fn state_items_are_read_from_the_database() {
+        let state = CliState::test().unwrap();
+        let space = |name: &str| SpaceConfig {
+            id: format!("{name}-id"),
+            name: name.to_string(),
+        };
+        state.spaces.create("s1", space("s1")).unwrap();
+        state.spaces.create("s2", space("s2")).unwrap();
+        state.spaces.set_default("s2").unwrap();
+
+        // The items, their configuration and the default item are recorded in the database
+        let records = state.with_database(|db| db.items("spaces")).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].config, json!({"id": "s1-id", "name": "s1"}));
+        assert!(!records[0].is_default && records[1].is_default);
+
+        // And they are read from the database: an item only present in the files is not listed
+        std::fs::write(state.spaces.path("s3"), "{}").unwrap();
+        assert_eq!(state.spaces.list_items_names().unwrap(), vec!["s1", "s2"]);
+        assert!(!state.spaces.exists("s3"));
+        assert_eq!(state.spaces.default().unwrap().config().name, "s2");
+        assert!(state.spaces.is_default("s2").unwrap());
+
+        state.spaces.delete("s2").unwrap();
+        assert!(state
+            .with_database(|db| db.get("spaces", "s2"))
+            .unwrap()
+            .is_none());
+        assert!(state.spaces.get("s2").is_err());
+
+        CliState::delete_at(&state.dir).unwrap();
+    }
+
+    #[test]
+    fn changes_made_to_the_files_are_synchronized_when_the_state_is_loaded() {
+        let state = CliState::test().unwrap();
+        let space = |name: &str| SpaceConfig {
+            id: format!("{name}-id"),
+            name: name.to_string(),
+        };
+        state.spaces.create("s1", space("s1")).unwrap();
+        state.spaces.create("s2", space("s2")).unwrap();
+
+        // Change the files, as a version of ockam built without the sqlite feature would
+        std::fs::write(
+            state.spaces.path("s1"),
+            serde_json::to_string(&space("s1-changed")).unwrap(),
+        )
+        .unwrap();
+        std::fs::remove_file(state.spaces.path("s2")).unwrap();
+        std::fs::write(
+            state.spaces.path("s3"),
+            serde_json::to_string(&space("s3")).unwrap(),
+        )
+        .unwrap();
+        let _ = std::fs::remove_file(state.spaces.default_path().unwrap());
+        crate::cli_state::symlink(
+            state.spaces.path("s3"),
+            state.spaces.default_path().unwrap(),
+        )
+        .unwrap();
+
+        let state = CliState::new(&state.dir).unwrap();
+        assert_eq!(state.spaces.list_items_names().unwrap(), vec!["s1", "s3"]);
+        assert_eq!(state.spaces.get("s1").unwrap().config().name, "s1-changed");
+        assert!(state.spaces.is_default("s3").unwrap());
+        assert!(!state.spaces.exists("s2"));
+
+        CliState::delete_at(&state.dir).unwrap();
+    }
+}
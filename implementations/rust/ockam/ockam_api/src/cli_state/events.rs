@@ -0,0 +1,356 @@
+//! Events of the local environment: the changes made to the nodes, identities and projects
+//! of the CLI state, and to the inlets of the running nodes.
+//!
+//! The events are computed by comparing two [`EnvironmentSnapshot`]s, so that a program
+//! can follow the environment by capturing a snapshot at regular intervals, whatever
+//! process made the changes.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli_state::traits::StateDirTrait;
+use crate::cli_state::{CliState, Result};
+
+/// Change of the local environment
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EnvironmentEvent {
+    NodeCreated {
+        node: String,
+    },
+    NodeDeleted {
+        node: String,
+    },
+    NodeStarted {
+        node: String,
+        pid: Option<i32>,
+    },
+    NodeStopped {
+        node: String,
+    },
+    /// The resources of a node have been changed, see its history
+    NodeChanged {
+        node: String,
+        revision: u64,
+        changes: Vec<String>,
+    },
+    IdentityCreated {
+        identity: String,
+        identifier: String,
+    },
+    IdentityDeleted {
+        identity: String,
+    },
+    ProjectCreated {
+        project: String,
+    },
+    ProjectDeleted {
+        project: String,
+    },
+    /// The default node, identity or project has been changed, or unset
+    DefaultChanged {
+        resource: String,
+        name: Option<String>,
+    },
+    /// An inlet has been created, or its status has changed
+    InletStatusChanged {
+        node: String,
+        alias: String,
+        status: String,
+        bind_address: String,
+    },
+    /// An inlet has been deleted, or its node is not running anymore
+    InletRemoved {
+        node: String,
+        alias: String,
+    },
+}
+
+/// Event with the time at which it was observed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TimedEnvironmentEvent {
+    /// Time of the observation, in seconds since the Unix epoch
+    pub at: u64,
+    #[serde(flatten)]
+    pub event: EnvironmentEvent,
+}
+
+impl TimedEnvironmentEvent {
+    /// Timestamp an event with the current time
+    pub fn now(event: EnvironmentEvent) -> Self {
+        Self {
+            at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            event,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NodeSnapshot {
+    running: bool,
+    pid: Option<i32>,
+    revision: u64,
+    changes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct InletSnapshot {
+    status: String,
+    bind_address: String,
+}
+
+/// State of the local environment at a given time
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvironmentSnapshot {
+    nodes: BTreeMap<String, NodeSnapshot>,
+    identities: BTreeMap<String, String>,
+    projects: BTreeSet<String>,
+    defaults: BTreeMap<String, Option<String>>,
+    inlets: BTreeMap<(String, String), InletSnapshot>,
+}
+
+impl EnvironmentSnapshot {
+    /// Capture the nodes, identities and projects of the CLI state.
+    /// The inlets of the running nodes must be added with [`EnvironmentSnapshot::set_inlet`]
+    pub fn capture(state: &CliState) -> Result<Self> {
+        let mut snapshot = Self::default();
+        for node in state.node_infos()? {
+            let last_change = state
+                .nodes
+                .get(node.name())
+                .and_then(|n| n.history())
+                .ok()
+                .and_then(|mut history| history.pop());
+            snapshot.nodes.insert(
+                node.name().to_string(),
+                NodeSnapshot {
+                    running: node.is_running(),
+                    pid: node.pid(),
+                    revision: last_change.as_ref().map_or(0, |e| e.revision),
+                    changes: last_change.map(|e| e.changes).unwrap_or_default(),
+                },
+            );
+        }
+        for identity in state.identity_infos()? {
+            snapshot.identities.insert(
+                identity.name().to_string(),
+                identity.identifier().to_string(),
+            );
+        }
+        for project in state.project_infos()? {
+            snapshot.projects.insert(project.name().to_string());
+        }
+        snapshot.defaults.insert(
+            "node".to_string(),
+            state.nodes.default().ok().map(|n| n.name().to_string()),
+        );
+        snapshot.defaults.insert(
+            "identity".to_string(),
+            state
+                .identities
+                .default()
+                .ok()
+                .map(|i| i.name().to_string()),
+        );
+        snapshot.defaults.insert(
+            "project".to_string(),
+            state.projects.default().ok().map(|p| p.name().to_string()),
+        );
+        Ok(snapshot)
+    }
+
+    /// Names of the running nodes
+    pub fn running_nodes(&self) -> Vec<String> {
+        self.nodes
+            .iter()
+            .filter(|(_, node)| node.running)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Add the status of an inlet of a running node
+    pub fn set_inlet(&mut self, node: &str, alias: &str, status: &str, bind_address: &str) {
+        self.inlets.insert(
+            (node.to_string(), alias.to_string()),
+            InletSnapshot {
+                status: status.to_string(),
+                bind_address: bind_address.to_string(),
+            },
+        );
+    }
+
+    /// Keep the inlets of a node from a previous snapshot, when they can't be retrieved
+    pub fn keep_inlets(&mut self, node: &str, previous: &EnvironmentSnapshot) {
+        for ((inlet_node, alias), inlet) in &previous.inlets {
+            if inlet_node == node {
+                self.inlets
+                    .insert((inlet_node.clone(), alias.clone()), inlet.clone());
+            }
+        }
+    }
+
+    /// Return the events which turn a previous snapshot into this one
+    pub fn events_since(&self, previous: &EnvironmentSnapshot) -> Vec<EnvironmentEvent> {
+        let mut events = vec![];
+
+        for (name, node) in &self.nodes {
+            match previous.nodes.get(name) {
+                None => {
+                    events.push(EnvironmentEvent::NodeCreated { node: name.clone() });
+                    if node.running {
+                        events.push(EnvironmentEvent::NodeStarted {
+                            node: name.clone(),
+                            pid: node.pid,
+                        });
+                    }
+                }
+                Some(before) => {
+                    if node.running && (!before.running || before.pid != node.pid) {
+                        events.push(EnvironmentEvent::NodeStarted {
+                            node: name.clone(),
+                            pid: node.pid,
+                        });
+                    } else if !node.running && before.running {
+                        events.push(EnvironmentEvent::NodeStopped { node: name.clone() });
+                    }
+                    if node.revision > before.revision {
+                        events.push(EnvironmentEvent::NodeChanged {
+                            node: name.clone(),
+                            revision: node.revision,
+                            changes: node.changes.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        for name in previous.nodes.keys() {
+            if !self.nodes.contains_key(name) {
+                events.push(EnvironmentEvent::NodeDeleted { node: name.clone() });
+            }
+        }
+
+        for (name, identifier) in &self.identities {
+            if previous.identities.get(name) != Some(identifier) {
+                events.push(EnvironmentEvent::IdentityCreated {
+                    identity: name.clone(),
+                    identifier: identifier.clone(),
+                });
+            }
+        }
+        for name in previous.identities.keys() {
+            if !self.identities.contains_key(name) {
+                events.push(EnvironmentEvent::IdentityDeleted {
+                    identity: name.clone(),
+                });
+            }
+        }
+
+        for name in self.projects.difference(&previous.projects) {
+            events.push(EnvironmentEvent::ProjectCreated {
+                project: name.clone(),
+            });
+        }
+        for name in previous.projects.difference(&self.projects) {
+            events.push(EnvironmentEvent::ProjectDeleted {
+                project: name.clone(),
+            });
+        }
+
+        for (resource, name) in &self.defaults {
+            let changed = match previous.defaults.get(resource) {
+                Some(before) => before != name,
+                None => name.is_some(),
+            };
+            if changed {
+                events.push(EnvironmentEvent::DefaultChanged {
+                    resource: resource.clone(),
+                    name: name.clone(),
+                });
+            }
+        }
+
+        for ((node, alias), inlet) in &self.inlets {
+            if previous.inlets.get(&(node.clone(), alias.clone())) != Some(inlet) {
+                events.push(EnvironmentEvent::InletStatusChanged {
+                    node: node.clone(),
+                    alias: alias.clone(),
+                    status: inlet.status.clone(),
+                    bind_address: inlet.bind_address.clone(),
+                });
+            }
+        }
+        for (node, alias) in previous.inlets.keys() {
+            if !self.inlets.contains_key(&(node.clone(), alias.clone())) {
+                events.push(EnvironmentEvent::InletRemoved {
+                    node: node.clone(),
+                    alias: alias.clone(),
+                });
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_between_snapshots() {
+        let mut before = EnvironmentSnapshot::default();
+        before.nodes.insert(
+            "n1".to_string(),
+            NodeSnapshot {
+                running: true,
+                pid: Some(10),
+                revision: 1,
+                changes: vec![],
+            },
+        );
+        before.set_inlet("n1", "db", "up", "127.0.0.1:5432");
+
+        let mut after = before.clone();
+        after.nodes.get_mut("n1").unwrap().running = false;
+        after.inlets.clear();
+        after
+            .defaults
+            .insert("node".to_string(), Some("n1".to_string()));
+        after.projects.insert("p1".to_string());
+
+        assert_eq!(
+            after.events_since(&before),
+            vec![
+                EnvironmentEvent::NodeStopped {
+                    node: "n1".to_string()
+                },
+                EnvironmentEvent::ProjectCreated {
+                    project: "p1".to_string()
+                },
+                EnvironmentEvent::DefaultChanged {
+                    resource: "node".to_string(),
+                    name: Some("n1".to_string())
+                },
+                EnvironmentEvent::InletRemoved {
+                    node: "n1".to_string(),
+                    alias: "db".to_string()
+                },
+            ]
+        );
+        assert!(after.events_since(&after).is_empty());
+
+        let json = serde_json::to_string(&TimedEnvironmentEvent {
+            at: 1,
+            event: EnvironmentEvent::NodeStopped {
+                node: "n1".to_string(),
+            },
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"at":1,"event":"node_stopped","node":"n1"}"#);
+    }
+}
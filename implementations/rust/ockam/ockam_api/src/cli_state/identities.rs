@@ -1,16 +1,18 @@
 use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
 use time::format_description::well_known::Iso8601;
 use time::OffsetDateTime;
 
 use ockam::identity::storage::LmdbStorage;
-use ockam::identity::{Identifier, IdentitiesRepository, IdentitiesStorage};
+use ockam::identity::{AuthorityStatus, Identifier, IdentitiesRepository, IdentitiesStorage};
 
-use crate::cli_state::traits::{StateDirTrait, StateItemTrait};
+use crate::cli_state::traits::{
+    read_item_config, write_item_config, StateDirTrait, StateItemTrait,
+};
 use crate::cli_state::{CliStateError, DATA_DIR_NAME};
 
 use super::Result;
@@ -40,10 +42,23 @@ impl IdentitiesState {
     }
 
     pub async fn identities_repository(&self) -> Result<Arc<dyn IdentitiesRepository>> {
+        self.identities_repository_with_grace_period(Duration::ZERO, AuthorityStatus::default())
+            .await
+    }
+
+    /// Return the identities repository, where the attributes attested by an authority
+    /// can still be used during a grace period once they have expired, while the requests
+    /// recorded in `authority_status` fail
+    pub async fn identities_repository_with_grace_period(
+        &self,
+        grace_period: Duration,
+        authority_status: AuthorityStatus,
+    ) -> Result<Arc<dyn IdentitiesRepository>> {
         let lmdb_path = self.identities_repository_path()?;
-        Ok(Arc::new(IdentitiesStorage::new(Arc::new(
-            LmdbStorage::new(lmdb_path).await?,
-        ))))
+        Ok(Arc::new(
+            IdentitiesStorage::new(Arc::new(LmdbStorage::new(lmdb_path).await?))
+                .with_grace_period(grace_period, authority_status),
+        ))
     }
 
     pub fn identities_repository_path(&self) -> Result<PathBuf> {
@@ -84,6 +99,19 @@ impl IdentityState {
         &self.name
     }
 
+    pub fn ephemeral(&self) -> Option<&EphemeralIdentity> {
+        self.config.ephemeral.as_ref()
+    }
+
+    /// Return true if the identity is ephemeral and has expired
+    pub fn is_expired(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        self.ephemeral().map_or(false, |e| e.expires_at <= now)
+    }
+
     pub fn is_enrolled(&self) -> bool {
         self.config
             .enrollment_status
@@ -102,6 +130,9 @@ impl Display for IdentityState {
         )?;
         writeln!(f, "State Path: {}", self.path.clone().to_str().unwrap())?;
         writeln!(f, "Config Identifier: {}", self.config.identifier())?;
+        if let Some(ephemeral) = &self.config.ephemeral {
+            writeln!(f, "Ephemeral: expires at {}", ephemeral.expires_at)?;
+        }
         match &self.config.enrollment_status {
             Some(enrollment) => {
                 writeln!(f, "Enrollment Status:")?;
@@ -119,6 +150,9 @@ impl Display for IdentityState {
 pub struct IdentityConfig {
     pub identifier: Identifier,
     pub enrollment_status: Option<EnrollmentStatus>,
+    /// Set for the identities which are automatically deleted once expired
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ephemeral: Option<EphemeralIdentity>,
 }
 
 impl PartialEq for IdentityConfig {
@@ -134,12 +168,32 @@ impl IdentityConfig {
         Self {
             identifier: identifier.clone(),
             enrollment_status: None,
+            ephemeral: None,
         }
     }
 
     pub fn identifier(&self) -> Identifier {
         self.identifier.clone()
     }
+
+    /// Make the identity ephemeral. Its key is stored in the given vault
+    pub fn set_ephemeral(mut self, vault: &str, expires_at: u64) -> Self {
+        self.ephemeral = Some(EphemeralIdentity {
+            vault: vault.to_string(),
+            expires_at,
+        });
+        self
+    }
+}
+
+/// Identity created for a short-lived job, for example in a CI pipeline.
+/// It is deleted, with its key, as soon as it has expired
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct EphemeralIdentity {
+    /// Name of the vault storing the identity key
+    pub vault: String,
+    /// Expiration time, in seconds since the Unix epoch
+    pub expires_at: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -234,6 +288,7 @@ mod traits {
         }
 
         fn delete(&self, name: impl AsRef<str>) -> Result<()> {
+            let _lock = self.lock()?;
             // Retrieve identity. If doesn't exist do nothing.
             let identity = match self.get(&name) {
                 Ok(i) => i,
@@ -247,8 +302,13 @@ mod traits {
                     let _ = std::fs::remove_file(self.default_path()?);
                 }
             }
-            // Remove identity file
+            // Remove identity file, and the file recording its usage
             identity.delete()?;
+            let _ = std::fs::remove_file(identity.usage_path());
+            #[cfg(feature = "sqlite")]
+            crate::cli_state::database::update(self.dir(), |db, kind| {
+                db.remove(kind, name.as_ref())
+            })?;
             Ok(())
         }
 
@@ -277,7 +337,7 @@ mod traits {
 
         fn new(path: PathBuf, config: Self::Config) -> Result<Self> {
             let contents = serde_json::to_string(&config)?;
-            std::fs::write(&path, contents)?;
+            write_item_config(&path, &path, contents)?;
             let name = file_stem(&path)?;
             let data_path = IdentityState::build_data_path(&path);
             Ok(Self {
@@ -290,7 +350,7 @@ mod traits {
 
         fn load(path: PathBuf) -> Result<Self> {
             let name = file_stem(&path)?;
-            let contents = std::fs::read_to_string(&path)?;
+            let contents = read_item_config(&path, &path)?;
             let config = serde_json::from_str(&contents)?;
             let data_path = IdentityState::build_data_path(&path);
             Ok(Self {
@@ -338,6 +398,7 @@ mod tests {
                 is_enrolled: true,
                 created_at: SystemTime::from(OffsetDateTime::from_unix_timestamp(0).unwrap()),
             }),
+            ephemeral: None,
         }
     }
 
@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Iso8601;
+use time::OffsetDateTime;
+
+use crate::cli_state::traits::StateItemTrait;
+use crate::cli_state::{write_atomically, IdentityState, Result};
+
+/// Extension of the file, next to the identity configuration file, containing the json
+/// [`IdentityUsage`] of the identity. It is not a json extension, so that the file is not
+/// listed as an identity
+const USAGE_FILE_EXTENSION: &str = "usage";
+
+/// Operation for which an identity can be used
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdentityUsageKind {
+    /// Establishment of a secure channel
+    SecureChannel,
+    /// Presentation of a credential issued to the identity
+    CredentialPresentation,
+}
+
+impl Display for IdentityUsageKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IdentityUsageKind::SecureChannel => write!(f, "secure channel"),
+            IdentityUsageKind::CredentialPresentation => write!(f, "credential presentation"),
+        }
+    }
+}
+
+/// Last use of an identity for a given operation
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct IdentityUse {
+    /// Time of the use, in seconds since the Unix epoch
+    pub used_at: u64,
+    /// Name of the node which used the identity
+    pub node: String,
+}
+
+impl Display for IdentityUse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let used_at = OffsetDateTime::from_unix_timestamp(self.used_at as i64)
+            .ok()
+            .and_then(|t| t.format(&Iso8601::DEFAULT).ok())
+            .unwrap_or_else(|| self.used_at.to_string());
+        write!(f, "{used_at} by the node {}", self.node)
+    }
+}
+
+/// Last uses of an identity, for each operation
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct IdentityUsage {
+    #[serde(default)]
+    pub last_uses: BTreeMap<IdentityUsageKind, IdentityUse>,
+}
+
+impl IdentityUsage {
+    /// Return the most recent use of the identity, for any operation
+    pub fn last_use(&self) -> Option<&IdentityUse> {
+        self.last_uses.values().max_by_key(|u| u.used_at)
+    }
+}
+
+impl Display for IdentityUsage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.last_use() {
+            Some(last_use) => writeln!(f, "Last used: {last_use}")?,
+            None => writeln!(f, "Last used: never")?,
+        }
+        for (kind, last_use) in &self.last_uses {
+            writeln!(f, "{:2}Last {kind}: {last_use}", "")?;
+        }
+        Ok(())
+    }
+}
+
+impl IdentityState {
+    /// Return the last uses of the identity
+    pub fn usage(&self) -> Result<IdentityUsage> {
+        match std::fs::read_to_string(self.usage_path()) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(IdentityUsage::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Record that the identity has just been used by a node
+    pub fn record_usage(&self, kind: IdentityUsageKind, node: &str) -> Result<()> {
+        let mut usage = self.usage().unwrap_or_default();
+        usage.last_uses.insert(
+            kind,
+            IdentityUse {
+                used_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default(),
+                node: node.to_string(),
+            },
+        );
+        // The file is replaced atomically since several nodes can use the same identity
+        let path = self.usage_path();
+        write_atomically(&path, serde_json::to_string(&usage)?)?;
+        Ok(())
+    }
+
+    /// Path of the file containing the last uses of the identity
+    pub(super) fn usage_path(&self) -> PathBuf {
+        self.path().with_extension(USAGE_FILE_EXTENSION)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli_state::{CliState, IdentityConfig, StateDirTrait};
+    use ockam::identity::Identifier;
+
+    #[tokio::test]
+    async fn the_usage_of_an_identity_is_recorded() {
+        let state = CliState::test().unwrap();
+        let identifier = Identifier::try_from("Ifa804b7fca12a19eed206ae180b5b576860ae651").unwrap();
+        let identity = state
+            .identities
+            .create("i", IdentityConfig::new(&identifier).await)
+            .unwrap();
+        assert_eq!(identity.usage().unwrap(), IdentityUsage::default());
+
+        identity
+            .record_usage(IdentityUsageKind::SecureChannel, "n1")
+            .unwrap();
+        let usage = identity.usage().unwrap();
+        assert_eq!(usage.last_use().unwrap().node, "n1");
+
+        // the usage file is not an identity
+        assert_eq!(state.identities.list_items_names().unwrap(), vec!["i"]);
+
+        state.identities.delete("i").unwrap();
+        assert!(!identity.usage_path().exists());
+    }
+}
@@ -0,0 +1,224 @@
+//! Concurrent access to the state directory.
+//!
+//! Several ockam processes can use the same state directory at the same time, for example
+//! parallel CI steps, or commands run in several terminals while nodes are running. To prevent
+//! them from corrupting the state:
+//!
+//!  - the changes of a directory (creations, deletions, defaults, updates of an item) are made
+//!    while holding an advisory lock on the `.lock` file of that directory. A process waiting
+//!    for the lock retries with an exponential backoff, and fails after [`LOCK_TIMEOUT`].
+//!    When it waits on a worker thread of a multi-threaded tokio runtime, the thread is handed
+//!    over to the runtime so that the other tasks of the process keep running,
+//!  - the files are replaced atomically, by renaming a temporary file, so that a reader never
+//!    sees a partially written file.
+//!
+//! The lock is reentrant for a thread: a change can call another change of the same directory.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+use super::{CliStateError, Result};
+
+/// Name of the lock file of a state directory. Hidden files are not state items
+pub const LOCK_FILE_NAME: &str = ".lock";
+
+/// Maximum time to wait for a lock held by another process
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(10);
+const MAX_BACKOFF: Duration = Duration::from_millis(500);
+
+thread_local! {
+    /// Directories locked by the current thread, with their number of guards
+    static LOCKED_DIRS: RefCell<HashMap<PathBuf, usize>> = RefCell::new(HashMap::new());
+}
+
+/// Exclusive lock of a state directory, released when it is dropped
+#[derive(Debug)]
+pub struct StateLock {
+    dir: PathBuf,
+    /// Lock file, only opened by the outermost guard of a thread.
+    /// The lock is released when the file is closed
+    _file: Option<File>,
+}
+
+impl StateLock {
+    /// Lock a state directory, waiting with an exponential backoff while another process
+    /// or another thread holds the lock
+    pub fn acquire(dir: &Path) -> Result<StateLock> {
+        let dir = dir.to_path_buf();
+        let nested = LOCKED_DIRS.with(|locked| match locked.borrow_mut().get_mut(&dir) {
+            Some(count) => {
+                *count += 1;
+                true
+            }
+            None => false,
+        });
+        if nested {
+            return Ok(StateLock { dir, _file: None });
+        }
+
+        std::fs::create_dir_all(&dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(dir.join(LOCK_FILE_NAME))?;
+        if !try_lock(&file)? {
+            without_blocking_the_runtime(|| wait_for_lock(&dir, &file))?;
+        }
+        LOCKED_DIRS.with(|locked| locked.borrow_mut().insert(dir.clone(), 1));
+        Ok(StateLock {
+            dir,
+            _file: Some(file),
+        })
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        LOCKED_DIRS.with(|locked| {
+            let mut locked = locked.borrow_mut();
+            if let Some(count) = locked.get_mut(&self.dir) {
+                *count -= 1;
+                if *count == 0 {
+                    locked.remove(&self.dir);
+                }
+            }
+        });
+    }
+}
+
+/// Retry to take the lock of a directory with an exponential backoff, until [`LOCK_TIMEOUT`]
+fn wait_for_lock(dir: &Path, file: &File) -> Result<()> {
+    let started_at = Instant::now();
+    let mut backoff = INITIAL_BACKOFF;
+    while !try_lock(file)? {
+        if started_at.elapsed() > LOCK_TIMEOUT {
+            return Err(CliStateError::InvalidOperation(format!(
+                "The state directory {} is locked by another ockam process",
+                dir.display()
+            )));
+        }
+        debug!(dir = %dir.display(), "waiting for the lock of the state directory");
+        std::thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+    Ok(())
+}
+
+/// Run a blocking function. On a worker thread of a multi-threaded tokio runtime, the other
+/// tasks of the worker are moved to another thread while the function runs.
+/// The function runs on the current thread in any case, which keeps the lock reentrant
+fn without_blocking_the_runtime<T>(f: impl FnOnce() -> T) -> T {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+/// Take the lock of a file without blocking. Return false if it is held by another file handle
+#[cfg(unix)]
+fn try_lock(file: &File) -> Result<bool> {
+    use nix::errno::Errno;
+    use nix::fcntl::{flock, FlockArg};
+    use std::os::unix::io::AsRawFd;
+
+    match flock(file.as_raw_fd(), FlockArg::LockExclusiveNonblock) {
+        Ok(()) => Ok(true),
+        Err(e) if e == Errno::EWOULDBLOCK => Ok(false),
+        Err(e) => Err(std::io::Error::from(e).into()),
+    }
+}
+
+/// The advisory locks are only supported on Unix systems. On the other systems, the state
+/// is only protected by the atomic replacement of its files
+#[cfg(not(unix))]
+fn try_lock(_file: &File) -> Result<bool> {
+    Ok(true)
+}
+
+/// Replace the contents of a file atomically, by renaming a temporary file of the same
+/// directory. The temporary file is hidden, so that it is never listed as a state item
+pub fn write_atomically(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let dir = path.parent().ok_or(CliStateError::EmptyPath)?;
+    std::fs::create_dir_all(dir)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| CliStateError::InvalidPath(path.display().to_string()))?
+        .to_string_lossy();
+    let tmp_path = dir.join(format!(".{file_name}.{}.tmp", std::process::id()));
+    std::fs::write(&tmp_path, contents)?;
+    if let Err(e) = std::fs::rename(&tmp_path, path) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_lock_is_reentrant_and_exclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock = StateLock::acquire(dir.path()).unwrap();
+        // A nested change of the same thread doesn't wait
+        drop(StateLock::acquire(dir.path()).unwrap());
+
+        let path = dir.path().to_path_buf();
+        let waiter = std::thread::spawn(move || {
+            let started_at = Instant::now();
+            let _lock = StateLock::acquire(&path).unwrap();
+            started_at.elapsed()
+        });
+        std::thread::sleep(Duration::from_millis(200));
+        drop(lock);
+        assert!(waiter.join().unwrap() >= Duration::from_millis(150));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn waiting_for_the_lock_does_not_block_the_runtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let path = dir.path().to_path_buf();
+        let holder = std::thread::spawn(move || {
+            let _lock = StateLock::acquire(&path).unwrap();
+            locked_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        // The only worker thread of the runtime waits for the lock
+        let path = dir.path().to_path_buf();
+        let waiter = tokio::spawn(async move { StateLock::acquire(&path).map(|_| ()) });
+        std::thread::sleep(Duration::from_millis(100));
+
+        // Another task still runs
+        let (ran_tx, ran_rx) = std::sync::mpsc::channel();
+        tokio::spawn(async move { ran_tx.send(()).unwrap() });
+        assert!(ran_rx.recv_timeout(Duration::from_secs(2)).is_ok());
+
+        release_tx.send(()).unwrap();
+        holder.join().unwrap();
+        waiter.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn files_are_replaced_without_leaving_temporary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("item.json");
+        write_atomically(&path, "1").unwrap();
+        write_atomically(&path, "2").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "2");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -1,23 +1,42 @@
 pub mod credentials;
+#[cfg(feature = "sqlite")]
+pub mod database;
+pub mod events;
 pub mod identities;
+pub mod identity_usage;
+mod lock;
+pub mod node_history;
 pub mod nodes;
 pub mod projects;
+mod relocation;
+pub mod schema;
+pub mod settings;
 pub mod spaces;
+pub mod stable;
 pub mod traits;
 pub mod trust_contexts;
+pub mod trust_pins;
 pub mod user_info;
 pub mod vaults;
 
 pub use crate::cli_state::credentials::*;
+#[cfg(feature = "sqlite")]
+pub use crate::cli_state::database::{StateDatabase, StateRecord, DATABASE_FILE_NAME};
+pub use crate::cli_state::events::*;
 pub use crate::cli_state::identities::*;
+pub use crate::cli_state::identity_usage::*;
+pub use crate::cli_state::lock::*;
+pub use crate::cli_state::node_history::*;
 pub use crate::cli_state::nodes::*;
 pub use crate::cli_state::projects::*;
+pub use crate::cli_state::settings::*;
 pub use crate::cli_state::spaces::*;
+pub use crate::cli_state::stable::*;
 pub use crate::cli_state::traits::*;
 pub use crate::cli_state::trust_contexts::*;
+pub use crate::cli_state::trust_pins::*;
 use crate::cli_state::user_info::UsersInfoState;
 pub use crate::cli_state::vaults::*;
-use crate::config::cli::LegacyCliConfig;
 use miette::Diagnostic;
 use ockam::identity::Identifier;
 use ockam::identity::Identities;
@@ -72,12 +91,24 @@ pub enum CliStateError {
     #[diagnostic(code("OCK500"))]
     InvalidOperation(String),
 
+    #[error("The state directory has the version {version}, this version of ockam only supports the versions up to {supported}")]
+    #[diagnostic(
+        code("OCK500"),
+        help("Please upgrade ockam, or use another state directory with the OCKAM_HOME environment variable")
+    )]
+    UnsupportedSchemaVersion { version: u32, supported: u32 },
+
     #[error("Invalid configuration version '{0}'")]
     #[diagnostic(
         code("OCK500"),
         help("Please try running 'ockam reset' to reset your local configuration")
     )]
     InvalidVersion(String),
+
+    #[cfg(feature = "sqlite")]
+    #[error(transparent)]
+    #[diagnostic(code("OCK500"))]
+    Sqlite(#[from] rusqlite::Error),
 }
 
 impl From<&str> for CliStateError {
@@ -109,6 +140,8 @@ pub struct CliState {
     pub credentials: CredentialsState,
     pub trust_contexts: TrustContextsState,
     pub users_info: UsersInfoState,
+    pub settings: SettingsState,
+    pub trust_pins: TrustPinsState,
     pub dir: PathBuf,
 }
 
@@ -119,15 +152,16 @@ impl CliState {
     pub fn initialize() -> Result<Self> {
         let dir = Self::default_dir()?;
         std::fs::create_dir_all(dir.join("defaults"))?;
-        Executor::execute_future(Self::initialize_cli_state())?
+        Executor::execute_future(async move { Self::initialize_cli_state(&dir).await })?
     }
 
     /// Create a new CliState by initializing all of its components
     /// The calls to 'init(dir)' are loading each piece of configuration and possibly doing some
     /// configuration migration if necessary
-    async fn initialize_cli_state() -> Result<CliState> {
-        let default = Self::default_dir()?;
-        let dir = default.as_path();
+    async fn initialize_cli_state(dir: &Path) -> Result<CliState> {
+        if let Some(backup) = schema::migrate_schema(dir)? {
+            info!(backup = %backup.display(), "the state directory has been migrated");
+        }
         let state = Self {
             vaults: VaultsState::init(dir).await?,
             identities: IdentitiesState::init(dir).await?,
@@ -137,16 +171,24 @@ impl CliState {
             credentials: CredentialsState::init(dir).await?,
             trust_contexts: TrustContextsState::init(dir).await?,
             users_info: UsersInfoState::init(dir).await?,
+            settings: SettingsState::new(dir),
+            trust_pins: TrustPinsState::new(dir),
             dir: dir.to_path_buf(),
         };
-        state.migrate()?;
+        match state.delete_expired_identities().await {
+            Ok(deleted) if !deleted.is_empty() => {
+                info!(?deleted, "deleted the expired ephemeral identities")
+            }
+            Ok(_) => (),
+            Err(e) => warn!(%e, "the expired ephemeral identities could not be deleted"),
+        }
         Ok(state)
     }
 
     /// Reset all directories and return a new CliState
     pub async fn reset(&self) -> Result<CliState> {
         Self::delete_at(&self.dir)?;
-        Self::initialize_cli_state().await
+        Self::initialize_cli_state(&self.dir).await
     }
 
     pub fn backup_and_reset() -> Result<CliState> {
@@ -172,30 +214,6 @@ impl CliState {
         Self::initialize()
     }
 
-    fn migrate(&self) -> Result<()> {
-        // If there is a `config.json` file, migrate its contents to the spaces and project states.
-        let legacy_config_path = self.dir.join("config.json");
-        if legacy_config_path.exists() {
-            let contents = std::fs::read_to_string(&legacy_config_path)?;
-            let legacy_config: LegacyCliConfig = serde_json::from_str(&contents)?;
-            let spaces = self.spaces.list()?;
-            for (name, lookup) in legacy_config.lookup.spaces() {
-                if !spaces.iter().any(|s| s.name() == name) {
-                    let config = SpaceConfig::from_lookup(&name, lookup);
-                    self.spaces.create(name, config)?;
-                }
-            }
-            let projects = self.projects.list()?;
-            for (name, lookup) in legacy_config.lookup.projects() {
-                if !projects.iter().any(|p| p.name() == name) {
-                    self.projects.create(name, lookup.into())?;
-                }
-            }
-            std::fs::remove_file(legacy_config_path)?;
-        }
-        Ok(())
-    }
-
     pub fn delete_at(root_path: &PathBuf) -> Result<()> {
         // Delete nodes' state and processes, if possible
         let nodes_state = NodesState::new(root_path);
@@ -223,6 +241,12 @@ impl CliState {
         // Delete config files located at the root of the state directory
         let config_file = root_path.join("config.json");
         let _ = std::fs::remove_file(config_file);
+        let _ = SettingsState::new(root_path).delete();
+        let _ = TrustPinsState::new(root_path).delete();
+        let _ = std::fs::remove_file(root_path.join(schema::SCHEMA_VERSION_FILE_NAME));
+        let _ = std::fs::remove_file(root_path.join(LOCK_FILE_NAME));
+        #[cfg(feature = "sqlite")]
+        let _ = database::delete(root_path);
 
         // If the state directory is now empty, delete it
         let is_empty = std::fs::read_dir(root_path)
@@ -253,6 +277,44 @@ impl CliState {
         identity_state.delete()
     }
 
+    /// Delete the ephemeral identities which have expired, together with their keys.
+    /// The identities used by a node are kept until the node is deleted.
+    /// Return the names of the deleted identities
+    pub async fn delete_expired_identities(&self) -> Result<Vec<String>> {
+        let mut deleted = vec![];
+        for identity_state in self.identities.list()? {
+            let ephemeral = match identity_state.ephemeral() {
+                Some(ephemeral) if identity_state.is_expired() => ephemeral.clone(),
+                _ => continue,
+            };
+            let identifier = identity_state.identifier();
+            let used_by_node = self
+                .nodes
+                .list()?
+                .iter()
+                .any(|n| n.config().identifier().ok().as_ref() == Some(&identifier));
+            if used_by_node {
+                continue;
+            }
+            if let Ok(vault_state) = self.vaults.get(&ephemeral.vault) {
+                let identities = self.get_identities(vault_state.get().await?).await?;
+                if let Ok(identity) = identities.get_identity(&identifier).await {
+                    let keys = identities.identities_creation().identities_keys();
+                    if let Ok(key) = keys.get_secret_key(&identity).await {
+                        identities
+                            .vault()
+                            .identity_vault
+                            .delete_signing_secret_key(key)
+                            .await?;
+                    }
+                }
+            }
+            self.identities.delete(identity_state.name())?;
+            deleted.push(identity_state.name().to_string());
+        }
+        Ok(deleted)
+    }
+
     /// Returns the default directory for the CLI state.
     pub fn default_dir() -> Result<PathBuf> {
         Ok(get_env_with_default::<PathBuf>(
@@ -372,6 +434,7 @@ impl CliState {
     /// Initialize CliState at the given directory
     async fn initialize_at(dir: &Path) -> Result<Self> {
         std::fs::create_dir_all(dir.join("defaults"))?;
+        schema::migrate_schema(dir)?;
         let state = Self {
             vaults: VaultsState::init(dir).await?,
             identities: IdentitiesState::init(dir).await?,
@@ -381,9 +444,10 @@ impl CliState {
             credentials: CredentialsState::init(dir).await?,
             trust_contexts: TrustContextsState::init(dir).await?,
             users_info: UsersInfoState::init(dir).await?,
+            settings: SettingsState::new(dir),
+            trust_pins: TrustPinsState::new(dir),
             dir: dir.to_path_buf(),
         };
-        state.migrate()?;
         Ok(state)
     }
 
@@ -399,6 +463,8 @@ impl CliState {
             credentials: CredentialsState::load(dir)?,
             trust_contexts: TrustContextsState::load(dir)?,
             users_info: UsersInfoState::load(dir)?,
+            settings: SettingsState::new(dir),
+            trust_pins: TrustPinsState::new(dir),
             dir: dir.to_path_buf(),
         })
     }
@@ -431,11 +497,31 @@ fn file_stem(path: &Path) -> Result<String> {
         .ok_or(CliStateError::InvalidPath(path_str.to_string()))
 }
 
+/// Create a symbolic link to a state file
+#[cfg(unix)]
+fn symlink(original: impl AsRef<Path>, link: impl AsRef<Path>) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(link_target(original.as_ref(), link.as_ref()), link)
+}
+
+/// Create a symbolic link to a state file
+#[cfg(windows)]
+fn symlink(original: impl AsRef<Path>, link: impl AsRef<Path>) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_file(link_target(original.as_ref(), link.as_ref()), link)
+}
+
+/// Return the target of a link to a state file. The target is relative to the directory of
+/// the link when possible, so that the state directory can be relocated
+fn link_target(original: &Path, link: &Path) -> PathBuf {
+    link.parent()
+        .and_then(|dir| relocation::relative_path(original, dir))
+        .unwrap_or_else(|| original.to_path_buf())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::cloud::enroll::auth0::UserInfo;
-    use crate::config::cli::TrustContextConfig;
+    use crate::config::cli::{LegacyCliConfig, TrustContextConfig};
     use crate::config::lookup::{ConfigLookup, LookupValue, ProjectLookup, SpaceLookup};
     use ockam_core::compat::rand::random_string;
     use ockam_multiaddr::MultiAddr;
@@ -491,6 +577,27 @@ mod tests {
         assert_eq!(identity1.path(), identity2.path());
     }
 
+    #[tokio::test]
+    async fn test_delete_expired_identities() {
+        let state = CliState::test().unwrap();
+        let identifier: Identifier = "Ie92f183eb4c324804ef4d62962dea94cf095a265"
+            .try_into()
+            .unwrap();
+        let expired = IdentityConfig::new(&identifier)
+            .await
+            .set_ephemeral("vault", 1);
+        state.identities.create("expired", expired).unwrap();
+        let valid = IdentityConfig::new(&identifier)
+            .await
+            .set_ephemeral("vault", u64::MAX);
+        state.identities.create("valid", valid).unwrap();
+
+        let deleted = state.delete_expired_identities().await.unwrap();
+        assert_eq!(deleted, vec!["expired".to_string()]);
+        assert!(state.identities.get("expired").is_err());
+        assert!(state.identities.get("valid").unwrap().ephemeral().is_some());
+    }
+
     #[tokio::test]
     async fn migrate_legacy_cli_config() {
         // Before this migration, there was a `config.json` file in the root $OCKAM_HOME directory
@@ -0,0 +1,147 @@
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli_state::traits::StateItemTrait;
+use crate::cli_state::{CliStateError, NodeSetupConfig, NodeState, Result};
+
+/// Name of the file, in the node directory, containing the history of the node resources.
+/// Each line is a json [`NodeHistoryEntry`], and lines are only ever appended
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// Change made to the resources of a node
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct NodeHistoryEntry {
+    /// Revision number, starting at 1 for the creation of the node
+    pub revision: u64,
+    /// Time of the change, in seconds since the Unix epoch
+    pub changed_at: u64,
+    /// Name of the user who made the change
+    pub actor: String,
+    /// Command line of the process which made the change
+    pub command: String,
+    /// Names of the resources which have been changed
+    pub changes: Vec<String>,
+    /// Resources before the change, missing for the creation of the node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<NodeSetupConfig>,
+    /// Resources after the change
+    pub setup: NodeSetupConfig,
+}
+
+impl NodeState {
+    /// Return the history of the changes made to the node resources, oldest first
+    pub fn history(&self) -> Result<Vec<NodeHistoryEntry>> {
+        let contents = match std::fs::read_to_string(self.path().join(HISTORY_FILE)) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let mut entries = vec![];
+        for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+            entries.push(serde_json::from_str(line)?);
+        }
+        Ok(entries)
+    }
+
+    /// Re-apply the resources of a previous revision. The rollback is recorded as a new revision
+    pub fn rollback(&self, revision: u64) -> Result<NodeHistoryEntry> {
+        let entry = self
+            .history()?
+            .into_iter()
+            .find(|e| e.revision == revision)
+            .ok_or_else(|| CliStateError::ResourceNotFound {
+                resource: "revision".to_string(),
+                name: revision.to_string(),
+            })?;
+        self.set_setup(&entry.setup)?;
+        self.history()?.pop().ok_or_else(|| {
+            CliStateError::InvalidData("the node history could not be read".to_string())
+        })
+    }
+
+    /// Append an entry to the node history if the resources have been changed
+    pub(super) fn record_setup_change(
+        &self,
+        previous: Option<NodeSetupConfig>,
+        setup: &NodeSetupConfig,
+    ) -> Result<()> {
+        let changes = changed_resources(previous.as_ref(), setup)?;
+        if previous.is_some() && changes.is_empty() {
+            return Ok(());
+        }
+        let revision = self.history()?.last().map(|e| e.revision).unwrap_or(0) + 1;
+        let entry = NodeHistoryEntry {
+            revision,
+            changed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            actor: actor(),
+            command: std::env::args().collect::<Vec<_>>().join(" "),
+            changes,
+            previous,
+            setup: setup.clone(),
+        };
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path().join(HISTORY_FILE))?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+}
+
+/// Return the names of the top-level resources which differ between two setups
+fn changed_resources(
+    previous: Option<&NodeSetupConfig>,
+    setup: &NodeSetupConfig,
+) -> Result<Vec<String>> {
+    let previous = match previous {
+        Some(previous) => serde_json::to_value(previous)?,
+        None => serde_json::Value::Null,
+    };
+    let setup = serde_json::to_value(setup)?;
+    let null = serde_json::Value::Null;
+    let mut names: Vec<String> = setup
+        .as_object()
+        .into_iter()
+        .chain(previous.as_object())
+        .flat_map(|o| o.keys().cloned())
+        .collect();
+    names.sort();
+    names.dedup();
+    Ok(names
+        .into_iter()
+        .filter(|name| previous.get(name).unwrap_or(&null) != setup.get(name).unwrap_or(&null))
+        .collect())
+}
+
+/// Name of the user running the current process
+fn actor() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cloud::project_node::NodeLabels;
+
+    #[test]
+    fn test_changed_resources() {
+        let setup = NodeSetupConfig::default();
+        let mut labels = NodeLabels::default();
+        labels.insert("env".to_string(), "prod".to_string());
+        let changed = setup.clone().set_verbose(1).set_labels(labels);
+
+        assert!(changed_resources(Some(&setup), &setup).unwrap().is_empty());
+        assert_eq!(
+            changed_resources(Some(&setup), &changed).unwrap(),
+            vec!["labels".to_string(), "verbose".to_string()]
+        );
+        assert!(!changed_resources(None, &setup).unwrap().is_empty());
+    }
+}
@@ -1,22 +1,28 @@
 use super::Result;
+use crate::cli_state::traits::{read_item_config, write_item_config};
 use crate::cli_state::{
-    CliState, CliStateError, IdentityConfig, IdentityState, ProjectConfig, ProjectConfigCompact,
-    StateDirTrait, StateItemTrait, VaultState,
+    write_atomically, CliState, CliStateError, IdentityConfig, IdentityState, ProjectConfig,
+    ProjectConfigCompact, StateDirTrait, StateItemTrait, VaultState,
 };
+use crate::cloud::project_node::NodeLabels;
 use crate::config::lookup::ProjectLookup;
+use crate::logs::LogSink;
 use crate::nodes::models::transport::CreateTransportJson;
+use crate::nodes::pairing::PairingApproval;
 use backwards_compatibility::*;
 use miette::{IntoDiagnostic, WrapErr};
+#[cfg(unix)]
 use nix::errno::Errno;
 use ockam::identity::Identifier;
 use ockam::identity::Vault;
 use ockam::LmdbStorage;
-use ockam_core::compat::collections::HashSet;
+use ockam_core::compat::collections::{BTreeMap, HashSet};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use sysinfo::{Pid, ProcessExt, ProcessStatus, System, SystemExt};
+use std::time::{Duration, UNIX_EPOCH};
+use sysinfo::{Pid, PidExt, Process, ProcessExt, ProcessStatus, Signal, System, SystemExt};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct NodesState {
@@ -30,11 +36,18 @@ impl NodesState {
         Ok(NodePaths::new(&dir).stdout())
     }
 
+    pub fn stderr_logs(&self, name: &str) -> Result<PathBuf> {
+        let dir = self.path(name);
+        std::fs::create_dir_all(&dir)?;
+        Ok(NodePaths::new(&dir).stderr())
+    }
+
     pub fn delete_sigkill(&self, name: &str, sigkill: bool) -> Result<()> {
         self._delete(name, sigkill)
     }
 
     fn _delete(&self, name: impl AsRef<str>, sigkill: bool) -> Result<()> {
+        let _lock = self.lock()?;
         // If doesn't exist do nothing
         if !self.exists(&name) {
             return Ok(());
@@ -53,8 +66,236 @@ impl NodesState {
         }
         // Remove node directory
         node.delete_sigkill(sigkill)?;
+        #[cfg(feature = "sqlite")]
+        super::database::update(self.dir(), |db, kind| db.remove(kind, name.as_ref()))?;
+        Ok(())
+    }
+
+    /// Return a summary of all the nodes.
+    ///
+    /// The summaries are cached in an index file, and a node is only read again when its files
+    /// have been modified since its summary was computed
+    pub fn summaries(&self) -> Result<Vec<NodeSummary>> {
+        let index_path = self.dir().join(NODES_INDEX_FILE);
+        let index: BTreeMap<String, NodeSummary> = std::fs::read_to_string(&index_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        let mut updated = false;
+        let mut summaries = BTreeMap::new();
+        for name in self.list_items_names()? {
+            if !self.exists(&name) {
+                continue;
+            }
+            let modified_at = NodePaths::new(&self.path(&name)).modified_at();
+            let summary = match index.get(&name) {
+                Some(summary) if summary.modified_at == modified_at => summary.clone(),
+                _ => match self.get(&name) {
+                    Ok(node) => {
+                        updated = true;
+                        NodeSummary {
+                            name: name.clone(),
+                            pid: node.pid().ok().flatten(),
+                            identifier: node.config().identifier().ok().map(|i| i.to_string()),
+                            modified_at,
+                        }
+                    }
+                    Err(_) => continue,
+                },
+            };
+            summaries.insert(name, summary);
+        }
+
+        if updated || summaries.len() != index.len() {
+            let contents = serde_json::to_string(&summaries)?;
+            if let Err(e) = write_atomically(&index_path, contents) {
+                warn!(%e, "the nodes index could not be updated");
+            }
+        }
+        Ok(summaries.into_values().collect())
+    }
+
+    /// Return the process ids which belong to running processes.
+    /// The processes are all probed at once, which is much faster than probing each node
+    pub fn running_pids(pids: impl IntoIterator<Item = i32>) -> HashSet<i32> {
+        let mut sys = System::new();
+        sys.refresh_processes();
+        pids.into_iter()
+            .filter(|pid| match sys.process(Pid::from(*pid as usize)) {
+                Some(p) => !matches!(p.status(), ProcessStatus::Dead | ProcessStatus::Zombie),
+                None => false,
+            })
+            .collect()
+    }
+}
+
+impl NodesState {
+    /// Return the inconsistencies between the nodes state and the node processes, which are
+    /// usually left behind by crashes and forced deletions
+    pub fn inconsistencies(&self) -> Result<Vec<NodeInconsistency>> {
+        let mut inconsistencies = vec![];
+        let mut sys = System::new();
+        sys.refresh_processes();
+
+        // Node processes started for this state whose node has been deleted
+        for (pid, process) in sys.processes() {
+            if let Some(node_name) = self.node_process_name(process) {
+                if !self.exists(&node_name) {
+                    inconsistencies.push(NodeInconsistency::OrphanProcess {
+                        node_name,
+                        pid: pid.as_u32() as i32,
+                    });
+                }
+            }
+        }
+
+        for node_name in self.list_item_files_names()? {
+            if !self.exists(&node_name) {
+                if self.path(&node_name).is_dir() {
+                    inconsistencies.push(NodeInconsistency::IncompleteNode { node_name });
+                }
+                continue;
+            }
+            if let Ok(Some(pid)) = self.get(&node_name).and_then(|n| n.pid()) {
+                let running = sys
+                    .process(Pid::from(pid as usize))
+                    .map(|p| !matches!(p.status(), ProcessStatus::Dead | ProcessStatus::Zombie))
+                    .unwrap_or(false);
+                if !running {
+                    inconsistencies.push(NodeInconsistency::StalePid { node_name, pid });
+                }
+            }
+        }
+
+        let default_path = self.default_path()?;
+        if default_path.symlink_metadata().is_ok() && std::fs::canonicalize(&default_path).is_err()
+        {
+            inconsistencies.push(NodeInconsistency::DanglingDefault);
+        }
+        Ok(inconsistencies)
+    }
+
+    /// Fix an inconsistency: kill an orphan process, or delete the dangling state
+    pub fn prune(&self, inconsistency: &NodeInconsistency, sigkill: bool) -> Result<()> {
+        match inconsistency {
+            NodeInconsistency::OrphanProcess { node_name, pid } => {
+                let mut sys = System::new();
+                sys.refresh_processes();
+                // Check again that the process is an orphan node process, since the pid
+                // could have been reused in the meantime
+                if let Some(process) = sys.process(Pid::from(*pid as usize)) {
+                    if self.node_process_name(process).as_ref() == Some(node_name) {
+                        let killed = if sigkill {
+                            process.kill()
+                        } else {
+                            process
+                                .kill_with(Signal::Term)
+                                .unwrap_or_else(|| process.kill())
+                        };
+                        if !killed {
+                            return Err(CliStateError::Io(std::io::Error::new(
+                                std::io::ErrorKind::Other,
+                                format!("failed to stop PID `{pid}`"),
+                            )));
+                        }
+                        info!(name = %node_name, %pid, "orphan node process killed");
+                    }
+                }
+            }
+            NodeInconsistency::StalePid { node_name, .. } => {
+                let _ = std::fs::remove_file(NodePaths::new(&self.path(node_name)).pid());
+                info!(name = %node_name, "stale node pid removed");
+            }
+            NodeInconsistency::IncompleteNode { node_name } => {
+                std::fs::remove_dir_all(self.path(node_name))?;
+                info!(name = %node_name, "incomplete node deleted");
+            }
+            NodeInconsistency::DanglingDefault => {
+                let _ = std::fs::remove_file(self.default_path()?);
+                if let Some(node) = self.list()?.first() {
+                    self.set_default(node.name())?;
+                }
+                info!("dangling default node removed");
+            }
+        }
         Ok(())
     }
+
+    /// Return the name of the node run by a process, if it is a node process started by the
+    /// command line for this state
+    fn node_process_name(&self, process: &Process) -> Option<String> {
+        let cmd = process.cmd();
+        let is_node_process = cmd.iter().any(|arg| arg == "--child-process")
+            && cmd
+                .windows(2)
+                .any(|args| args[0] == "node" && args[1] == "create");
+        if !is_node_process {
+            return None;
+        }
+
+        // The state directory is inherited from the environment of the command line
+        let environ = process.environ();
+        let ockam_home = match environ.iter().find_map(|e| e.strip_prefix("OCKAM_HOME=")) {
+            Some(ockam_home) => PathBuf::from(ockam_home),
+            None => {
+                PathBuf::from(environ.iter().find_map(|e| e.strip_prefix("HOME="))?).join(".ockam")
+            }
+        };
+        let state_dir = self.dir().parent()?;
+        if std::fs::canonicalize(ockam_home).ok()? != std::fs::canonicalize(state_dir).ok()? {
+            return None;
+        }
+        cmd.last().cloned()
+    }
+}
+
+/// Inconsistency between the nodes state and the node processes
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NodeInconsistency {
+    /// A node process is running but its node has been deleted
+    OrphanProcess { node_name: String, pid: i32 },
+    /// The node records a process which is not running anymore
+    StalePid { node_name: String, pid: i32 },
+    /// The node directory was only partially created
+    IncompleteNode { node_name: String },
+    /// The default node doesn't exist anymore
+    DanglingDefault,
+}
+
+impl Display for NodeInconsistency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeInconsistency::OrphanProcess { node_name, pid } => write!(
+                f,
+                "the process {pid} runs the node {node_name} which has been deleted"
+            ),
+            NodeInconsistency::StalePid { node_name, pid } => write!(
+                f,
+                "the node {node_name} records the process {pid} which is not running"
+            ),
+            NodeInconsistency::IncompleteNode { node_name } => {
+                write!(f, "the node {node_name} was not completely created")
+            }
+            NodeInconsistency::DanglingDefault => {
+                write!(f, "the default node does not exist anymore")
+            }
+        }
+    }
+}
+
+/// Name of the file caching a summary of each node, in the nodes directory
+const NODES_INDEX_FILE: &str = ".index.json";
+
+/// Summary of a node, as cached in the nodes index file
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct NodeSummary {
+    pub name: String,
+    pub pid: Option<i32>,
+    pub identifier: Option<String>,
+    /// Last modification time of the node files when the summary was computed
+    modified_at: u64,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -80,37 +321,81 @@ impl NodeState {
 
     pub fn kill_process(&self, sigkill: bool) -> Result<()> {
         if let Some(pid) = self.pid()? {
-            nix::sys::signal::kill(
-                nix::unistd::Pid::from_raw(pid),
-                if sigkill {
-                    nix::sys::signal::Signal::SIGKILL
-                } else {
-                    nix::sys::signal::Signal::SIGTERM
-                },
-            )
-            .or_else(|e| {
-                if e == Errno::ESRCH {
-                    tracing::warn!(node = %self.name(), %pid, "No such process");
-                    Ok(())
-                } else {
-                    Err(e)
-                }
-            })
-            .map_err(|e| {
-                CliStateError::Io(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("failed to stop PID `{pid}` with error `{e}`"),
-                ))
-            })?;
+            self.stop_process(pid, sigkill)?;
             std::fs::remove_file(self.paths.pid())?;
         }
         info!(name = %self.name(), "node process killed");
         Ok(())
     }
 
+    #[cfg(unix)]
+    fn stop_process(&self, pid: i32, sigkill: bool) -> Result<()> {
+        nix::sys::signal::kill(
+            nix::unistd::Pid::from_raw(pid),
+            if sigkill {
+                nix::sys::signal::Signal::SIGKILL
+            } else {
+                nix::sys::signal::Signal::SIGTERM
+            },
+        )
+        .or_else(|e| {
+            if e == Errno::ESRCH {
+                tracing::warn!(node = %self.name(), %pid, "No such process");
+                Ok(())
+            } else {
+                Err(e)
+            }
+        })
+        .map_err(|e| {
+            CliStateError::Io(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("failed to stop PID `{pid}` with error `{e}`"),
+            ))
+        })
+    }
+
+    /// There is no SIGTERM signal on Windows: a graceful stop is requested with a file
+    /// watched by the node, while a forced stop terminates the process
+    #[cfg(windows)]
+    fn stop_process(&self, pid: i32, sigkill: bool) -> Result<()> {
+        if !sigkill {
+            return self.request_stop();
+        }
+        let mut sys = System::new();
+        sys.refresh_processes();
+        match sys.process(Pid::from(pid as usize)) {
+            Some(process) if !process.kill() => Err(CliStateError::Io(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("failed to stop PID `{pid}`"),
+            ))),
+            Some(_) => Ok(()),
+            None => {
+                tracing::warn!(node = %self.name(), %pid, "No such process");
+                Ok(())
+            }
+        }
+    }
+
+    /// Ask the node to stop gracefully
+    pub fn request_stop(&self) -> Result<()> {
+        std::fs::write(self.paths.stop_request(), "")?;
+        info!(name = %self.name(), "node stop requested");
+        Ok(())
+    }
+
+    /// Return true if the node was asked to stop, and clear the request
+    pub fn take_stop_request(&self) -> bool {
+        std::fs::remove_file(self.paths.stop_request()).is_ok()
+    }
+
+    /// Update the node resources. The change is recorded in the node history
     pub fn set_setup(&self, setup: &NodeSetupConfig) -> Result<()> {
+        let previous = read_item_config(&self.path, &self.paths.setup())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok());
         let contents = serde_json::to_string(setup)?;
-        std::fs::write(self.paths.setup(), contents)?;
+        write_item_config(&self.path, &self.paths.setup(), contents)?;
+        self.record_setup_change(previous, setup)?;
         info!(name = %self.name(), "setup config updated");
         Ok(())
     }
@@ -128,7 +413,7 @@ impl NodeState {
     }
 
     pub fn set_pid(&self, pid: i32) -> Result<()> {
-        std::fs::write(self.paths.pid(), pid.to_string())?;
+        write_atomically(&self.paths.pid(), pid.to_string())?;
         Ok(())
     }
 
@@ -149,6 +434,11 @@ impl NodeState {
         }
     }
 
+    /// File storing the pairing decisions of the node
+    pub fn pairings(&self) -> PathBuf {
+        self.paths.pairings()
+    }
+
     pub fn stdout_log(&self) -> PathBuf {
         self.paths.stdout()
     }
@@ -309,6 +599,45 @@ pub struct NodeSetupConfig {
     pub authority_node: Option<bool>,
     pub project: Option<ProjectLookup>,
     pub api_transport: Option<CreateTransportJson>,
+    /// Labels published to the project inventory when the node starts
+    #[serde(default, skip_serializing_if = "NodeLabels::is_empty")]
+    pub labels: NodeLabels,
+    /// Environment file defining the secrets loaded when the node starts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<PathBuf>,
+    /// Approval of the secure channels opened by unknown identities on the default listener
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pairing: Option<PairingApproval>,
+    /// Destinations of the logs of the node. The logs are written to files when it is empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub log_sinks: Vec<LogSink>,
+    /// Endpoint of the OpenTelemetry collector receiving the logs of the node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub otlp_logs_endpoint: Option<String>,
+    /// Local address on which the node accepts QUIC connections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quic_listener_address: Option<String>,
+    /// Local address on which the node accepts WebSocket connections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub websocket_listener_address: Option<String>,
+    /// URL of the proxy used to open the outgoing TCP connections of the node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// DNS servers used to resolve the host names of the peers of the node
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dns_servers: Vec<String>,
+    /// Time during which the identities with expired credentials are still authorized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_grace_period: Option<Duration>,
+    /// Answer the discovery queries of the local network
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub advertise: Option<bool>,
+    /// Enable the endpoints used to debug the node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debug_endpoints: Option<bool>,
+    /// Policy file defining the quotas of the relays registered on the node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relay_quotas: Option<PathBuf>,
 }
 
 impl NodeSetupConfig {
@@ -332,6 +661,71 @@ impl NodeSetupConfig {
         self
     }
 
+    pub fn set_labels(mut self, labels: NodeLabels) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn set_env_file(mut self, env_file: PathBuf) -> Self {
+        self.env_file = Some(env_file);
+        self
+    }
+
+    pub fn set_pairing(mut self, pairing: PairingApproval) -> Self {
+        self.pairing = Some(pairing);
+        self
+    }
+
+    pub fn set_log_sinks(mut self, log_sinks: Vec<LogSink>) -> Self {
+        self.log_sinks = log_sinks;
+        self
+    }
+
+    pub fn set_otlp_logs_endpoint(mut self, endpoint: String) -> Self {
+        self.otlp_logs_endpoint = Some(endpoint);
+        self
+    }
+
+    pub fn set_quic_listener_address(mut self, address: String) -> Self {
+        self.quic_listener_address = Some(address);
+        self
+    }
+
+    pub fn set_websocket_listener_address(mut self, address: String) -> Self {
+        self.websocket_listener_address = Some(address);
+        self
+    }
+
+    pub fn set_proxy(mut self, proxy: String) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn set_dns_servers(mut self, dns_servers: Vec<String>) -> Self {
+        self.dns_servers = dns_servers;
+        self
+    }
+
+    pub fn set_credential_grace_period(mut self, grace_period: Duration) -> Self {
+        self.credential_grace_period = Some(grace_period);
+        self
+    }
+
+    pub fn set_advertise(mut self) -> Self {
+        self.advertise = Some(true);
+        self
+    }
+
+    pub fn set_debug_endpoints(mut self) -> Self {
+        self.debug_endpoints = Some(true);
+        self
+    }
+
+    pub fn set_relay_quotas(mut self, relay_quotas: PathBuf) -> Self {
+        self.relay_quotas = Some(relay_quotas);
+        self
+    }
+
     pub fn api_transport(&self) -> Result<&CreateTransportJson> {
         self.api_transport.as_ref().ok_or_else(|| {
             CliStateError::InvalidOperation(
@@ -381,9 +775,29 @@ impl NodePaths {
         self.path.join("stderr.log")
     }
 
+    fn stop_request(&self) -> PathBuf {
+        self.path.join("stop")
+    }
+
+    fn pairings(&self) -> PathBuf {
+        self.path.join("pairings.json")
+    }
+
     fn policies_storage(&self) -> PathBuf {
         self.path.join("policies_storage.lmdb")
     }
+
+    /// Latest modification time, in milliseconds, of the files describing the node.
+    /// Creating or deleting a file in the node directory also modifies the directory
+    fn modified_at(&self) -> u64 {
+        [self.path.clone(), self.setup(), self.pid()]
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .filter_map(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_millis() as u64)
+            .max()
+            .unwrap_or_default()
+    }
 }
 
 mod backwards_compatibility {
@@ -439,8 +853,8 @@ mod backwards_compatibility {
 
 mod traits {
     use super::*;
-    use crate::cli_state::file_stem;
     use crate::cli_state::traits::*;
+    use crate::cli_state::{file_stem, symlink};
     use crate::nodes::models::transport::{TransportMode, TransportType};
     use ockam_core::async_trait;
 
@@ -465,11 +879,11 @@ mod traits {
             self.dir().join(name.as_ref())
         }
 
+        /// The configuration of a node is stored in its setup file.
         /// A node contains several files, and the existence of the main directory is not not enough
         /// to determine if a node exists as it could be created but empty.
-        fn exists(&self, name: impl AsRef<str>) -> bool {
-            let paths = NodePaths::new(&self.path(&name));
-            paths.setup().exists()
+        fn config_file(&self, name: impl AsRef<str>) -> PathBuf {
+            NodePaths::new(&self.path(&name)).setup()
         }
 
         fn delete(&self, name: impl AsRef<str>) -> Result<()> {
@@ -492,6 +906,19 @@ mod traits {
                         authority_node: setup.authority_node,
                         project: setup.project,
                         api_transport: None,
+                        labels: NodeLabels::default(),
+                        env_file: None,
+                        pairing: None,
+                        log_sinks: vec![],
+                        otlp_logs_endpoint: None,
+                        quic_listener_address: None,
+                        websocket_listener_address: None,
+                        proxy: None,
+                        dns_servers: vec![],
+                        credential_grace_period: None,
+                        advertise: None,
+                        debug_endpoints: None,
+                        relay_quotas: None,
                     };
                     if let Some(t) = setup
                         .transports
@@ -516,27 +943,33 @@ mod traits {
             std::fs::create_dir_all(&path)?;
             let paths = NodePaths::new(&path);
             let name = file_stem(&path)?;
-            std::fs::write(paths.setup(), serde_json::to_string(config.setup())?)?;
-            std::fs::write(paths.version(), config.version.to_string())?;
+            write_item_config(
+                &path,
+                &paths.setup(),
+                serde_json::to_string(config.setup())?,
+            )?;
+            write_atomically(&paths.version(), config.version.to_string())?;
             let _ = std::fs::remove_file(paths.vault());
-            std::os::unix::fs::symlink(&config.default_vault, paths.vault())?;
+            symlink(&config.default_vault, paths.vault())?;
             config.default_vault = paths.vault();
             let _ = std::fs::remove_file(paths.identity());
-            std::os::unix::fs::symlink(&config.default_identity, paths.identity())?;
+            symlink(&config.default_identity, paths.identity())?;
             config.default_identity = paths.identity();
-            Ok(Self {
+            let state = Self {
                 name,
                 path,
                 paths,
                 config,
-            })
+            };
+            state.record_setup_change(None, state.config.setup())?;
+            Ok(state)
         }
 
         fn load(path: PathBuf) -> Result<Self> {
             let paths = NodePaths::new(&path);
             let name = file_stem(&path)?;
             let setup = {
-                let contents = std::fs::read_to_string(paths.setup())?;
+                let contents = read_item_config(&path, &paths.setup())?;
                 serde_json::from_str(&contents)?
             };
             let version = {
@@ -736,4 +1169,46 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn nodes_summaries_are_cached_in_an_index() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let nodes_state = NodesState::new(tmp_dir.path());
+        let node_dir = nodes_state.path("n");
+        std::fs::create_dir_all(&node_dir).unwrap();
+        let setup = serde_json::to_string(&NodeSetupConfig::default()).unwrap();
+        std::fs::write(node_dir.join("setup.json"), setup).unwrap();
+        std::fs::write(node_dir.join("version"), "1").unwrap();
+        std::fs::write(node_dir.join("pid"), "123").unwrap();
+
+        let summaries = nodes_state.summaries().unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].name, "n");
+        assert_eq!(summaries[0].pid, Some(123));
+
+        // The cached summary is used as long as the node files are not modified
+        let index_path = nodes_state.dir().join(NODES_INDEX_FILE);
+        let mut index: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&index_path).unwrap()).unwrap();
+        index["n"]["pid"] = 456.into();
+        std::fs::write(&index_path, index.to_string()).unwrap();
+        let summaries = nodes_state.summaries().unwrap();
+        assert_eq!(summaries[0].pid, Some(456));
+    }
+
+    #[test]
+    fn incomplete_nodes_are_pruned() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let nodes_state = NodesState::new(tmp_dir.path());
+        std::fs::create_dir_all(nodes_state.path("n")).unwrap();
+
+        let inconsistencies = nodes_state.inconsistencies().unwrap();
+        let expected = NodeInconsistency::IncompleteNode {
+            node_name: "n".to_string(),
+        };
+        assert!(inconsistencies.contains(&expected));
+
+        nodes_state.prune(&expected, false).unwrap();
+        assert!(!nodes_state.path("n").exists());
+    }
 }
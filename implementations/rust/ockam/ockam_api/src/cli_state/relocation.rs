@@ -0,0 +1,240 @@
+use std::path::{Component, Path, PathBuf};
+
+use tracing::{debug, info};
+
+use crate::cli_state::traits::{StateDirTrait, StateItemTrait};
+use crate::cli_state::{symlink, CliState, CliStateError, Result};
+
+impl CliState {
+    /// Move the state directory to a new location and return the state loaded from there.
+    ///
+    /// All the nodes must be stopped. The symbolic links and the paths of the configuration
+    /// files referencing the previous location are rewritten to reference the new location
+    pub fn move_to(&self, new_dir: &Path) -> Result<CliState> {
+        if let Some(node) = self.nodes.list()?.iter().find(|n| n.is_running()) {
+            return Err(CliStateError::InvalidOperation(format!(
+                "The node {} is running. Please stop all the nodes before moving the state directory",
+                node.name()
+            )));
+        }
+        let new_dir = absolute(new_dir)?;
+        if new_dir.starts_with(&self.dir) {
+            return Err(CliStateError::InvalidPath(format!(
+                "{} is inside the current state directory",
+                new_dir.display()
+            )));
+        }
+        if std::fs::read_dir(&new_dir).map_or(false, |mut d| d.next().is_some()) {
+            return Err(CliStateError::InvalidPath(format!(
+                "{} is not empty",
+                new_dir.display()
+            )));
+        }
+
+        // The paths referencing the previous location might have been written with or without
+        // resolving the symbolic links of its parent directories
+        let mut old_dirs = vec![self.dir.clone()];
+        if let Ok(canonical) = std::fs::canonicalize(&self.dir) {
+            if canonical != self.dir {
+                old_dirs.push(canonical);
+            }
+        }
+
+        if let Some(parent) = new_dir.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let _ = std::fs::remove_dir(&new_dir);
+        // A rename is not possible across file systems, for example to a USB drive
+        if let Err(e) = std::fs::rename(&self.dir, &new_dir) {
+            debug!(%e, "the state directory can't be renamed, copying it instead");
+            copy_dir(&self.dir, &new_dir)?;
+            std::fs::remove_dir_all(&self.dir)?;
+        }
+
+        rewrite_paths(&new_dir, &old_dirs, &new_dir)?;
+        info!(from = %self.dir.display(), to = %new_dir.display(), "moved the state directory");
+        CliState::new(&new_dir)
+    }
+}
+
+/// Return an absolute path, relative paths being resolved from the current directory
+fn absolute(path: &Path) -> Result<PathBuf> {
+    if path.is_absolute() {
+        Ok(path.to_path_buf())
+    } else {
+        Ok(std::env::current_dir()?.join(path))
+    }
+}
+
+/// Copy a directory recursively. Symbolic links are copied as links
+pub(crate) fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = to.join(entry.file_name());
+        if file_type.is_symlink() {
+            symlink(std::fs::read_link(entry.path())?, &target)?;
+        } else if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Rewrite the symbolic links and the paths found in the json and json lines files of a directory, when they
+/// reference one of the old state directories
+fn rewrite_paths(dir: &Path, old_dirs: &[PathBuf], new_dir: &Path) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            let target = std::fs::read_link(&path)?;
+            if let Some(target) = relocate(&target, old_dirs, new_dir) {
+                std::fs::remove_file(&path)?;
+                symlink(target, &path)?;
+            }
+        } else if file_type.is_dir() {
+            rewrite_paths(&path, old_dirs, new_dir)?;
+        } else if path.extension().map_or(false, |e| e == "json") {
+            let contents = std::fs::read_to_string(&path)?;
+            let mut value: serde_json::Value = match serde_json::from_str(&contents) {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+            if rewrite_json_paths(&mut value, old_dirs, new_dir) {
+                std::fs::write(&path, serde_json::to_string(&value)?)?;
+            }
+        } else if path.extension().map_or(false, |e| e == "jsonl") {
+            let contents = std::fs::read_to_string(&path)?;
+            let mut changed = false;
+            let mut lines = vec![];
+            for line in contents.lines() {
+                match serde_json::from_str::<serde_json::Value>(line) {
+                    Ok(mut value) if rewrite_json_paths(&mut value, old_dirs, new_dir) => {
+                        changed = true;
+                        lines.push(serde_json::to_string(&value)?);
+                    }
+                    _ => lines.push(line.to_string()),
+                }
+            }
+            if changed {
+                std::fs::write(&path, lines.join("\n") + "\n")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Rewrite the string values which are paths referencing one of the old state directories.
+/// Return true if a value was rewritten
+fn rewrite_json_paths(value: &mut serde_json::Value, old_dirs: &[PathBuf], new_dir: &Path) -> bool {
+    match value {
+        serde_json::Value::String(s) => match relocate(Path::new(s), old_dirs, new_dir) {
+            Some(path) => {
+                *s = path.to_string_lossy().to_string();
+                true
+            }
+            None => false,
+        },
+        serde_json::Value::Array(values) => values.iter_mut().fold(false, |changed, v| {
+            rewrite_json_paths(v, old_dirs, new_dir) || changed
+        }),
+        serde_json::Value::Object(values) => values.values_mut().fold(false, |changed, v| {
+            rewrite_json_paths(v, old_dirs, new_dir) || changed
+        }),
+        _ => false,
+    }
+}
+
+/// Return the new path of a path referencing one of the old state directories
+fn relocate(path: &Path, old_dirs: &[PathBuf], new_dir: &Path) -> Option<PathBuf> {
+    if !path.is_absolute() {
+        return None;
+    }
+    old_dirs
+        .iter()
+        .find_map(|old_dir| path.strip_prefix(old_dir).ok())
+        .map(|relative| new_dir.join(relative))
+}
+
+/// Return the path of `original` relative to the directory `dir`, if both paths are absolute
+/// and have a common ancestor which is not the root of the file system
+pub(super) fn relative_path(original: &Path, dir: &Path) -> Option<PathBuf> {
+    if !original.is_absolute() || !dir.is_absolute() {
+        return None;
+    }
+    let original: Vec<Component> = original.components().collect();
+    let dir: Vec<Component> = dir.components().collect();
+    let roots = original
+        .iter()
+        .take_while(|c| matches!(c, Component::Prefix(_) | Component::RootDir))
+        .count();
+    let common = original
+        .iter()
+        .zip(dir.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common <= roots {
+        return None;
+    }
+    let mut relative = PathBuf::new();
+    for _ in common..dir.len() {
+        relative.push("..");
+    }
+    for component in &original[common..] {
+        relative.push(component.as_os_str());
+    }
+    Some(relative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli_state::VaultConfig;
+
+    #[tokio::test]
+    async fn the_state_directory_can_be_moved() {
+        let state = CliState::test().unwrap();
+        let vault = state
+            .vaults
+            .create_async("v", VaultConfig::default())
+            .await
+            .unwrap();
+
+        // simulate a link created with an absolute path by a previous version
+        let default_path = state.vaults.default_path().unwrap();
+        std::fs::remove_file(&default_path).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(vault.path(), &default_path).unwrap();
+        #[cfg(windows)]
+        std::os::windows::fs::symlink_file(vault.path(), &default_path).unwrap();
+
+        let new_dir = CliState::test_dir().unwrap();
+        let moved = state.move_to(&new_dir).unwrap();
+        assert!(!state.dir.exists());
+        assert_eq!(moved.dir, new_dir);
+        assert_eq!(moved.vaults.default().unwrap().name(), "v");
+        assert!(std::fs::read_link(moved.vaults.default_path().unwrap())
+            .unwrap()
+            .is_relative());
+    }
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path(
+                Path::new("/home/u/.ockam/vaults/v.json"),
+                Path::new("/home/u/.ockam/defaults")
+            ),
+            Some(PathBuf::from("../vaults/v.json"))
+        );
+        assert_eq!(
+            relative_path(Path::new("/tmp/v.json"), Path::new("/home/u/.ockam")),
+            None
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Versions of the layout of the state directory.
+//!
+//! The version of the layout is written in the `schema_version` file of the state directory.
+//! When a newer version of ockam finds an older layout, the pending migrations are applied
+//! in order, after a copy of the state directory is made in a sibling `.v<version>.bak`
+//! directory. A state directory without a version file has the version 0.
+//!
+//! A state directory written by a newer version of ockam is never modified.
+
+use std::path::{Path, PathBuf};
+
+use crate::cli_state::relocation::copy_dir;
+use crate::cli_state::traits::{is_hidden, StateDirTrait, StateItemTrait};
+use crate::cli_state::{
+    write_atomically, CliStateError, CredentialsState, IdentitiesState, NodesState, ProjectsState,
+    Result, SpaceConfig, SpacesState, StateLock, TrustContextsState, UsersInfoState, VaultsState,
+    DATA_DIR_NAME,
+};
+use crate::config::cli::LegacyCliConfig;
+
+/// Name of the file containing the version of the layout of the state directory
+pub const SCHEMA_VERSION_FILE_NAME: &str = "schema_version";
+
+/// Change of the layout of the state directory, upgrading it to `version`
+#[derive(Debug)]
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    apply: fn(&Path) -> Result<()>,
+}
+
+/// The migrations, in the order in which they must be applied
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Import the spaces and projects of the legacy config.json file",
+        apply: import_legacy_config,
+    },
+    Migration {
+        version: 2,
+        description: "Remove the temporary files left by interrupted writes",
+        apply: remove_temporary_files,
+    },
+];
+
+/// Version of the layout of the state directory written by this version of ockam
+pub fn latest_schema_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or_default()
+}
+
+/// Return the version of the layout of a state directory
+pub fn schema_version(root_path: &Path) -> Result<u32> {
+    let path = root_path.join(SCHEMA_VERSION_FILE_NAME);
+    if !path.exists() {
+        return Ok(0);
+    }
+    let contents = std::fs::read_to_string(&path)?;
+    contents
+        .trim()
+        .parse()
+        .map_err(|_| CliStateError::InvalidVersion(contents.trim().to_string()))
+}
+
+/// Return the migrations which must be applied to a state directory. Fail if the state directory
+/// was written by a newer version of ockam
+pub fn pending_migrations(root_path: &Path) -> Result<Vec<&'static Migration>> {
+    let version = schema_version(root_path)?;
+    if version > latest_schema_version() {
+        return Err(CliStateError::UnsupportedSchemaVersion {
+            version,
+            supported: latest_schema_version(),
+        });
+    }
+    Ok(MIGRATIONS.iter().filter(|m| m.version > version).collect())
+}
+
+/// Apply the pending migrations of a state directory and return the path of the backup made
+/// before the first migration, if the directory was not empty
+pub fn migrate_schema(root_path: &Path) -> Result<Option<PathBuf>> {
+    let _lock = StateLock::acquire(root_path)?;
+    let migrations = pending_migrations(root_path)?;
+    if migrations.is_empty() {
+        return Ok(None);
+    }
+    let from = schema_version(root_path)?;
+    let backup = if has_state(root_path)? {
+        let backup = backup_path(root_path, from)?;
+        // A backup left by an interrupted migration contains the state before that migration
+        if !backup.exists() {
+            copy_dir(root_path, &backup)?;
+        }
+        Some(backup)
+    } else {
+        None
+    };
+    for migration in migrations {
+        (migration.apply)(root_path)?;
+        write_atomically(
+            &root_path.join(SCHEMA_VERSION_FILE_NAME),
+            migration.version.to_string(),
+        )?;
+        info!(
+            version = migration.version,
+            description = migration.description,
+            "state directory migrated"
+        );
+    }
+    Ok(backup)
+}
+
+/// Path of the copy of a state directory made before migrating it from a version
+pub fn backup_path(root_path: &Path, version: u32) -> Result<PathBuf> {
+    let name = root_path
+        .file_name()
+        .ok_or_else(|| CliStateError::InvalidPath(root_path.display().to_string()))?
+        .to_string_lossy();
+    Ok(root_path.with_file_name(format!("{name}.v{version}.bak")))
+}
+
+/// Return true if a state directory contains some state. The other files, like the `env` file
+/// and the `bin` directory of the installer, are not modified by the migrations
+fn has_state(root_path: &Path) -> Result<bool> {
+    if root_path.join("config.json").exists() {
+        return Ok(true);
+    }
+    for dir_name in [
+        VaultsState::DIR_NAME,
+        IdentitiesState::DIR_NAME,
+        NodesState::DIR_NAME,
+        SpacesState::DIR_NAME,
+        ProjectsState::DIR_NAME,
+        CredentialsState::DIR_NAME,
+        TrustContextsState::DIR_NAME,
+        UsersInfoState::DIR_NAME,
+    ] {
+        let dir = root_path.join(dir_name);
+        if dir.exists() && contains_files(&dir)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Return true if a directory or one of its subdirectories contains a file.
+/// The hidden files, like the lock files, are not part of the state
+fn contains_files(dir: &Path) -> Result<bool> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if is_hidden(&entry.path()) {
+            continue;
+        }
+        if !entry.file_type()?.is_dir() || contains_files(&entry.path())? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Before the spaces and projects directories, the spaces and projects were stored in a
+/// `config.json` file at the root of the state directory
+fn import_legacy_config(root_path: &Path) -> Result<()> {
+    let legacy_config_path = root_path.join("config.json");
+    if !legacy_config_path.exists() {
+        return Ok(());
+    }
+    let contents = std::fs::read_to_string(&legacy_config_path)?;
+    let legacy_config: LegacyCliConfig = serde_json::from_str(&contents)?;
+    let spaces_state = SpacesState::load(root_path)?;
+    let spaces = spaces_state.list()?;
+    for (name, lookup) in legacy_config.lookup.spaces() {
+        if !spaces.iter().any(|s| s.name() == name) {
+            let config = SpaceConfig::from_lookup(&name, lookup);
+            spaces_state.create(name, config)?;
+        }
+    }
+    let projects_state = ProjectsState::load(root_path)?;
+    let projects = projects_state.list()?;
+    for (name, lookup) in legacy_config.lookup.projects() {
+        if !projects.iter().any(|p| p.name() == name) {
+            projects_state.create(name, lookup.into())?;
+        }
+    }
+    std::fs::remove_file(legacy_config_path)?;
+    Ok(())
+}
+
+/// The secrets of the vaults were written to a `.tmp` file before being renamed, and the usage files of the
+/// identities to a `.usage.<pid>` file. Those files were left behind when a write was interrupted
+fn remove_temporary_files(root_path: &Path) -> Result<()> {
+    for dir in [
+        VaultsState::build_dir(root_path).join(DATA_DIR_NAME),
+        IdentitiesState::build_dir(root_path),
+    ] {
+        if !dir.exists() {
+            continue;
+        }
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let is_temporary = name.ends_with(".tmp")
+                || name
+                    .rsplit_once(".usage.")
+                    .map(|(_, pid)| pid.parse::<u32>().is_ok())
+                    .unwrap_or(false);
+            if is_temporary && path.is_file() {
+                std::fs::remove_file(&path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_pending_migrations_are_applied_after_a_backup() {
+        let home = tempfile::tempdir().unwrap();
+        let root = home.path().join("state");
+        let vaults_data = root.join("vaults").join("data");
+        std::fs::create_dir_all(&vaults_data).unwrap();
+        std::fs::write(vaults_data.join("v1-storage.tmp"), "").unwrap();
+
+        assert_eq!(pending_migrations(&root).unwrap().len(), MIGRATIONS.len());
+        let backup = migrate_schema(&root).unwrap().unwrap();
+        assert_eq!(backup, home.path().join("state.v0.bak"));
+        assert!(backup.join("vaults/data/v1-storage.tmp").exists());
+        assert!(!vaults_data.join("v1-storage.tmp").exists());
+        assert_eq!(schema_version(&root).unwrap(), latest_schema_version());
+        assert!(pending_migrations(&root).unwrap().is_empty());
+
+        // A state directory written by a newer version is not migrated
+        std::fs::write(root.join(SCHEMA_VERSION_FILE_NAME), "1000").unwrap();
+        assert!(matches!(
+            migrate_schema(&root),
+            Err(CliStateError::UnsupportedSchemaVersion { .. })
+        ));
+    }
+}
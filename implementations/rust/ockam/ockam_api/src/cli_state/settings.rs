@@ -0,0 +1,117 @@
+use super::Result;
+use crate::cli_state::{write_atomically, StateLock};
+use crate::port_range::PortRange;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Settings shared by all the commands using the same state directory.
+///
+/// Contrary to the other states, the settings are not a directory of items but a single
+/// `settings.json` file stored at the root of the state directory. The file is only created
+/// once a setting has been explicitly set.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SettingsState {
+    path: PathBuf,
+}
+
+impl SettingsState {
+    const FILE_NAME: &'static str = "settings.json";
+
+    pub fn new(root_path: &Path) -> Self {
+        Self {
+            path: root_path.join(Self::FILE_NAME),
+        }
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Return the current settings, or the default settings if none were set
+    pub fn get(&self) -> Result<SettingsConfig> {
+        if !self.path.exists() {
+            return Ok(SettingsConfig::default());
+        }
+        let contents = std::fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn set(&self, config: &SettingsConfig) -> Result<()> {
+        let contents = serde_json::to_string(config)?;
+        write_atomically(&self.path, contents)?;
+        info!(path = %self.path.display(), "settings updated");
+        Ok(())
+    }
+
+    /// Apply a modification to the current settings and persist them
+    pub fn update(&self, f: impl FnOnce(&mut SettingsConfig)) -> Result<SettingsConfig> {
+        let _lock = StateLock::acquire(self.dir())?;
+        let mut config = self.get()?;
+        f(&mut config);
+        self.set(&config)?;
+        Ok(config)
+    }
+
+    /// Directory of the settings file, locked while the settings are updated
+    fn dir(&self) -> &Path {
+        self.path.parent().expect("Should have parent")
+    }
+
+    pub fn delete(&self) -> Result<()> {
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct SettingsConfig {
+    /// Range of ports used to select a port when a TCP inlet is created
+    /// without an explicit address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inlet_port_range: Option<PortRange>,
+
+    /// Template used to generate the alias of inlets and outlets created without an alias
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias_template: Option<String>,
+
+    /// Refuse the secure channels to a peer presenting another identifier than the pinned one,
+    /// instead of only warning about the change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strict_pins: Option<bool>,
+
+    /// Shortcuts for commands, expanded before the arguments are parsed.
+    /// Each alias maps a name to the arguments of a command, e.g. `pg-tunnel` to
+    /// `["tcp-inlet", "create", "--from", "127.0.0.1:5432"]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, Vec<String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_default_settings_when_no_file_exists() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = SettingsState::new(dir.path());
+        assert_eq!(state.get().unwrap(), SettingsConfig::default());
+        assert!(!state.path().exists());
+    }
+
+    #[test]
+    fn update_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = SettingsState::new(dir.path());
+        let range = PortRange::new(41000, 41999).unwrap();
+        state
+            .update(|settings| settings.inlet_port_range = Some(range))
+            .unwrap();
+        assert_eq!(state.get().unwrap().inlet_port_range, Some(range));
+
+        state.delete().unwrap();
+        assert_eq!(state.get().unwrap(), SettingsConfig::default());
+    }
+}
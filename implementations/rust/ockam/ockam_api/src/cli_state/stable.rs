@@ -0,0 +1,271 @@
+//! Stable API to read and modify the CLI state from other programs.
+//!
+//! Programs integrating with Ockam, like a GUI or a provisioning system, can use the methods of
+//! this module instead of running the `ockam` command or reading the files of the state
+//! directory, whose layout can change between versions:
+//!
+//! ```rust,no_run
+//! use ockam_api::CliState;
+//!
+//! # fn main() -> Result<(), ockam_api::CliStateError> {
+//! let state = CliState::open("/var/lib/ockam")?;
+//! for node in state.node_infos()? {
+//!     println!("{} running: {}", node.name(), node.is_running());
+//! }
+//! state.set_default_identity("alice")?;
+//! # Ok(()) }
+//! ```
+//!
+//! The types returned by this module only expose getters and are `#[non_exhaustive]`, so that
+//! information can be added to them in a minor release. Their existing methods are only
+//! changed in a major release of this crate.
+
+use crate::cli_state::traits::{StateDirTrait, StateItemTrait};
+use crate::cli_state::{CliState, NodesState, Result};
+use ockam::identity::Identifier;
+use ockam_node::Executor;
+use std::path::Path;
+
+/// Information about a node of the CLI state
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct NodeInfo {
+    name: String,
+    identifier: Option<String>,
+    pid: Option<i32>,
+    running: bool,
+    default: bool,
+}
+
+impl NodeInfo {
+    /// Name of the node
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Identifier of the identity of the node, as a string
+    pub fn identifier(&self) -> Option<&str> {
+        self.identifier.as_deref()
+    }
+
+    /// Process id of the node, when it was started
+    pub fn pid(&self) -> Option<i32> {
+        self.pid
+    }
+
+    /// True if the process of the node is running
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// True if this is the default node, used when a command doesn't specify a node
+    pub fn is_default(&self) -> bool {
+        self.default
+    }
+}
+
+/// Information about an identity of the CLI state
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct IdentityInfo {
+    name: String,
+    identifier: Identifier,
+    enrolled: bool,
+    default: bool,
+}
+
+impl IdentityInfo {
+    /// Name of the identity
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Identifier of the identity
+    pub fn identifier(&self) -> &Identifier {
+        &self.identifier
+    }
+
+    /// True if the identity is enrolled with Ockam Orchestrator
+    pub fn is_enrolled(&self) -> bool {
+        self.enrolled
+    }
+
+    /// True if this is the default identity
+    pub fn is_default(&self) -> bool {
+        self.default
+    }
+}
+
+/// Information about a project of the CLI state
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ProjectInfo {
+    name: String,
+    id: String,
+    space_name: String,
+    default: bool,
+}
+
+impl ProjectInfo {
+    /// Name of the project
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Id of the project in Ockam Orchestrator
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Name of the space of the project
+    pub fn space_name(&self) -> &str {
+        &self.space_name
+    }
+
+    /// True if this is the default project
+    pub fn is_default(&self) -> bool {
+        self.default
+    }
+}
+
+impl CliState {
+    /// Open the CLI state stored in a directory, creating it if necessary.
+    /// The state of the `ockam` command is opened with [`CliState::initialize`] instead
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(dir.join("defaults"))?;
+        Executor::execute_future(async move { Self::initialize_cli_state(&dir).await })?
+    }
+
+    /// Return the information about all the nodes
+    pub fn node_infos(&self) -> Result<Vec<NodeInfo>> {
+        let summaries = self.nodes.summaries()?;
+        let running = NodesState::running_pids(summaries.iter().filter_map(|s| s.pid));
+        Ok(summaries
+            .into_iter()
+            .map(|summary| NodeInfo {
+                default: self.nodes.is_default(&summary.name).unwrap_or(false),
+                running: summary.pid.map_or(false, |pid| running.contains(&pid)),
+                name: summary.name,
+                identifier: summary.identifier,
+                pid: summary.pid,
+            })
+            .collect())
+    }
+
+    /// Return the information about a node
+    pub fn node_info(&self, name: &str) -> Result<NodeInfo> {
+        let node = self.nodes.get(name)?;
+        let pid = node.pid()?;
+        Ok(NodeInfo {
+            name: node.name().to_string(),
+            identifier: node.config().identifier().ok().map(|i| i.to_string()),
+            pid,
+            running: node.is_running(),
+            default: self.nodes.is_default(name).unwrap_or(false),
+        })
+    }
+
+    /// Set the default node
+    pub fn set_default_node(&self, name: &str) -> Result<()> {
+        self.nodes.set_default(name)
+    }
+
+    /// Stop a node if it is running, and delete its state
+    pub fn delete_node(&self, name: &str) -> Result<()> {
+        self.nodes.delete_sigkill(name, false)
+    }
+
+    /// Return the information about all the identities
+    pub fn identity_infos(&self) -> Result<Vec<IdentityInfo>> {
+        let mut infos = vec![];
+        for identity in self.identities.list()? {
+            infos.push(self.identity_info(identity.name())?);
+        }
+        Ok(infos)
+    }
+
+    /// Return the information about an identity
+    pub fn identity_info(&self, name: &str) -> Result<IdentityInfo> {
+        let identity = self.identities.get(name)?;
+        Ok(IdentityInfo {
+            name: identity.name().to_string(),
+            identifier: identity.identifier(),
+            enrolled: identity.is_enrolled(),
+            default: self.identities.is_default(name).unwrap_or(false),
+        })
+    }
+
+    /// Set the default identity
+    pub fn set_default_identity(&self, name: &str) -> Result<()> {
+        self.identities.set_default(name)
+    }
+
+    /// Delete an identity. An identity used by a node can't be deleted
+    pub fn delete_identity_by_name(&self, name: &str) -> Result<()> {
+        let identity = self.identities.get(name)?;
+        self.delete_identity(identity)
+    }
+
+    /// Return the information about all the projects
+    pub fn project_infos(&self) -> Result<Vec<ProjectInfo>> {
+        let mut infos = vec![];
+        for project in self.projects.list()? {
+            infos.push(self.project_info(project.name())?);
+        }
+        Ok(infos)
+    }
+
+    /// Return the information about a project
+    pub fn project_info(&self, name: &str) -> Result<ProjectInfo> {
+        let project = self.projects.get(name)?;
+        Ok(ProjectInfo {
+            name: project.name().to_string(),
+            id: project.id().to_string(),
+            space_name: project.config().space_name.clone(),
+            default: self.projects.is_default(name).unwrap_or(false),
+        })
+    }
+
+    /// Set the default project
+    pub fn set_default_project(&self, name: &str) -> Result<()> {
+        self.projects.set_default(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn identities_are_listed_and_modified() {
+        let state = CliState::test().unwrap();
+        let alice: Identifier = "Ie92f183eb4c324804ef4d62962dea94cf095a265"
+            .try_into()
+            .unwrap();
+        let bob: Identifier = "Ie92f183eb4c324804ef4d62962dea94cf095a266"
+            .try_into()
+            .unwrap();
+        state
+            .create_identity_state(&alice, Some("alice"))
+            .await
+            .unwrap();
+        state
+            .create_identity_state(&bob, Some("bob"))
+            .await
+            .unwrap();
+
+        let infos = state.identity_infos().unwrap();
+        assert_eq!(infos.len(), 2);
+        assert!(state.identity_info("alice").unwrap().is_default());
+
+        state.set_default_identity("bob").unwrap();
+        let bob_info = state.identity_info("bob").unwrap();
+        assert!(bob_info.is_default());
+        assert_eq!(bob_info.identifier(), &bob);
+
+        state.delete_identity_by_name("alice").unwrap();
+        assert!(state.identity_info("alice").is_err());
+        assert!(state.node_infos().unwrap().is_empty());
+    }
+}
@@ -1,4 +1,6 @@
-use crate::cli_state::{file_stem, CliState, CliStateError};
+#[cfg(feature = "sqlite")]
+use crate::cli_state::database;
+use crate::cli_state::{file_stem, symlink, write_atomically, CliState, CliStateError, StateLock};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, Error};
 use serde::{Deserialize, Serialize};
@@ -34,10 +36,13 @@ pub trait StateDirTrait: Sized + Send + Sync {
     /// Load the root configuration
     /// and migrate each entry if necessary
     async fn init(root_path: &Path) -> Result<Self> {
-        let root = Self::load(root_path)?;
-        for path in root.list_items_paths()? {
-            root.migrate(path.as_path()).await?;
+        Self::create_dirs(root_path)?;
+        let root = Self::new(root_path);
+        for name in root.list_item_files_names()? {
+            root.migrate(root.path(name).as_path()).await?;
         }
+        #[cfg(feature = "sqlite")]
+        database::sync(&root)?;
         Ok(root)
     }
 
@@ -48,7 +53,10 @@ pub trait StateDirTrait: Sized + Send + Sync {
 
     fn load(root_path: &Path) -> Result<Self> {
         Self::create_dirs(root_path)?;
-        Ok(Self::new(root_path))
+        let root = Self::new(root_path);
+        #[cfg(feature = "sqlite")]
+        database::sync(&root)?;
+        Ok(root)
     }
 
     /// Recreate all the state directories
@@ -76,11 +84,22 @@ pub trait StateDirTrait: Sized + Send + Sync {
         self.dir().join(format!("{}.json", name.as_ref()))
     }
 
+    /// Path of the file containing the configuration of an item
+    fn config_file(&self, name: impl AsRef<str>) -> PathBuf {
+        self.path(name)
+    }
+
+    /// Lock the directory while it is changed, since other processes can change it concurrently
+    fn lock(&self) -> Result<StateLock> {
+        StateLock::acquire(self.dir())
+    }
+
     fn overwrite(
         &self,
         name: impl AsRef<str>,
         config: <<Self as StateDirTrait>::Item as StateItemTrait>::Config,
     ) -> Result<Self::Item> {
+        let _lock = self.lock()?;
         let path = self.path(&name);
         let state = Self::Item::new(path, config)?;
         if !self.default_path()?.exists() {
@@ -95,6 +114,7 @@ pub trait StateDirTrait: Sized + Send + Sync {
         config: <<Self as StateDirTrait>::Item as StateItemTrait>::Config,
     ) -> Result<Self::Item> {
         debug!(name = %name.as_ref(), "Creating new config resource");
+        let _lock = self.lock()?;
         if self.exists(&name) {
             return Err(CliStateError::AlreadyExists {
                 resource: Self::default_filename().to_string(),
@@ -131,6 +151,15 @@ pub trait StateDirTrait: Sized + Send + Sync {
     }
 
     fn list_items_names(&self) -> Result<Vec<String>> {
+        #[cfg(feature = "sqlite")]
+        if let Some(names) = database::query(self.dir(), |db, kind| db.names(kind)) {
+            return Ok(names);
+        }
+        self.list_item_files_names()
+    }
+
+    /// Return the names of the items found in the state directory
+    fn list_item_files_names(&self) -> Result<Vec<String>> {
         let mut items = Vec::default();
         let iter = std::fs::read_dir(self.dir()).map_err(|e| {
             let dir = self.dir().as_path().to_string_lossy();
@@ -139,6 +168,10 @@ pub trait StateDirTrait: Sized + Send + Sync {
         })?;
         for entry in iter {
             let entry_path = entry?.path();
+            // The lock file and the temporary files are hidden
+            if is_hidden(&entry_path) {
+                continue;
+            }
             if self.is_item_path(&entry_path)? {
                 items.push(file_stem(&entry_path)?);
             }
@@ -164,6 +197,7 @@ pub trait StateDirTrait: Sized + Send + Sync {
 
     // TODO: move to StateItemTrait
     fn delete(&self, name: impl AsRef<str>) -> Result<()> {
+        let _lock = self.lock()?;
         // Retrieve state. If doesn't exist do nothing.
         let s = match self.get(&name) {
             Ok(project) => project,
@@ -177,7 +211,10 @@ pub trait StateDirTrait: Sized + Send + Sync {
             }
         }
         // Remove state data
-        s.delete()
+        s.delete()?;
+        #[cfg(feature = "sqlite")]
+        database::update(self.dir(), |db, kind| db.remove(kind, name.as_ref()))?;
+        Ok(())
     }
 
     fn default_path(&self) -> Result<PathBuf> {
@@ -186,12 +223,23 @@ pub trait StateDirTrait: Sized + Send + Sync {
     }
 
     fn default(&self) -> Result<Self::Item> {
+        #[cfg(feature = "sqlite")]
+        if let Some(name) = database::query(self.dir(), |db, kind| db.default_name(kind)) {
+            return match name {
+                Some(name) => self.get(name),
+                None => Err(CliStateError::ResourceNotFound {
+                    resource: Self::default_filename().to_string(),
+                    name: "default".to_string(),
+                }),
+            };
+        }
         let path = std::fs::canonicalize(self.default_path()?)?;
         Self::Item::load(path)
     }
 
     fn set_default(&self, name: impl AsRef<str>) -> Result<()> {
         debug!(name = %name.as_ref(), "Setting default item");
+        let _lock = self.lock()?;
         if !self.exists(&name) {
             return Err(CliStateError::ResourceNotFound {
                 resource: Self::default_filename().to_string(),
@@ -207,7 +255,9 @@ pub trait StateDirTrait: Sized + Send + Sync {
         // Create link to the default item
         std::fs::create_dir_all(link.parent().unwrap())
             .map_err(|e| Error::new(Origin::Node, Kind::Io, e))?;
-        std::os::unix::fs::symlink(original, link)?;
+        symlink(original, link)?;
+        #[cfg(feature = "sqlite")]
+        database::update(self.dir(), |db, kind| db.set_default(kind, name.as_ref()))?;
         info!(name = %name.as_ref(), "Set default item");
         Ok(())
     }
@@ -216,6 +266,10 @@ pub trait StateDirTrait: Sized + Send + Sync {
         if !self.exists(&name) {
             return Ok(false);
         }
+        #[cfg(feature = "sqlite")]
+        if let Some(default_name) = database::query(self.dir(), |db, kind| db.default_name(kind)) {
+            return Ok(default_name.as_deref() == Some(name.as_ref()));
+        }
         let default_name = {
             let path = std::fs::canonicalize(self.default_path()?)?;
             file_stem(&path)?
@@ -224,6 +278,10 @@ pub trait StateDirTrait: Sized + Send + Sync {
     }
 
     fn is_empty(&self) -> Result<bool> {
+        #[cfg(feature = "sqlite")]
+        if let Some(names) = database::query(self.dir(), |db, kind| db.names(kind)) {
+            return Ok(names.is_empty());
+        }
         for entry in std::fs::read_dir(self.dir())? {
             let name = file_stem(&entry?.path())?;
             if self.get(name).is_ok() {
@@ -234,7 +292,13 @@ pub trait StateDirTrait: Sized + Send + Sync {
     }
 
     fn exists(&self, name: impl AsRef<str>) -> bool {
-        self.path(&name).exists()
+        #[cfg(feature = "sqlite")]
+        if let Some(exists) =
+            database::query(self.dir(), |db, kind| db.contains(kind, name.as_ref()))
+        {
+            return exists;
+        }
+        self.config_file(&name).exists()
     }
 }
 
@@ -253,8 +317,8 @@ pub trait StateItemTrait: Sized + Send {
     /// Persist the item to disk after updating the config.
     fn persist(&self) -> Result<()> {
         let contents = serde_json::to_string(self.config())?;
-        std::fs::write(self.path(), contents)?;
-        Ok(())
+        let _lock = StateLock::acquire(self.path().parent().expect("Should have parent"))?;
+        write_item_config(self.path(), self.path(), contents)
     }
     fn delete(&self) -> Result<()> {
         std::fs::remove_file(self.path())?;
@@ -264,6 +328,33 @@ pub trait StateItemTrait: Sized + Send {
     fn config(&self) -> &Self::Config;
 }
 
+/// Read the configuration of the item stored at `item_path`, from the state database with the
+/// `sqlite` feature, or from `file`
+#[cfg_attr(not(feature = "sqlite"), allow(unused_variables))]
+pub(crate) fn read_item_config(item_path: &Path, file: &Path) -> Result<String> {
+    #[cfg(feature = "sqlite")]
+    if let Some(config) = database::item_config(item_path) {
+        return Ok(config);
+    }
+    Ok(std::fs::read_to_string(file)?)
+}
+
+/// Write the configuration of the item stored at `item_path` in `file`, and in the state
+/// database with the `sqlite` feature
+#[cfg_attr(not(feature = "sqlite"), allow(unused_variables))]
+pub(crate) fn write_item_config(item_path: &Path, file: &Path, contents: String) -> Result<()> {
+    write_atomically(file, &contents)?;
+    #[cfg(feature = "sqlite")]
+    database::set_item_config(item_path, file, &contents)?;
+    Ok(())
+}
+
+pub(crate) fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy().starts_with('.'))
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::cli_state::{StateDirTrait, StateItemTrait};
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use ockam::identity::Identifier;
+
+use super::Result;
+use crate::cli_state::{write_atomically, StateLock};
+
+/// Identifiers pinned the first time a secure channel was created to a peer.
+///
+/// Like the `known_hosts` file of SSH, the pins are stored in a single `trust_pins.json` file at
+/// the root of the state directory, and they are shared by all the nodes using this directory.
+/// A pin associates the name of a peer, which is the address used to reach it, with the
+/// identifier presented by that peer.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TrustPinsState {
+    path: PathBuf,
+}
+
+impl TrustPinsState {
+    const FILE_NAME: &'static str = "trust_pins.json";
+
+    pub fn new(root_path: &Path) -> Self {
+        Self {
+            path: root_path.join(Self::FILE_NAME),
+        }
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Return all the pins, indexed by peer name
+    pub fn list(&self) -> Result<BTreeMap<String, TrustPin>> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let contents = std::fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<TrustPin>> {
+        Ok(self.list()?.remove(name))
+    }
+
+    /// Compare an identifier with the pin of a peer. The identifier is pinned if the peer
+    /// had no pin yet
+    pub fn check(&self, name: &str, identifier: &Identifier) -> Result<PinCheck> {
+        let _lock = StateLock::acquire(self.dir())?;
+        let mut pins = self.list()?;
+        match pins.get(name) {
+            Some(pin) if &pin.identifier == identifier => Ok(PinCheck::Matching),
+            Some(pin) => Ok(PinCheck::Changed {
+                pinned: pin.identifier.clone(),
+            }),
+            None => {
+                pins.insert(name.to_string(), TrustPin::new(identifier.clone()));
+                self.write(&pins)?;
+                info!(%name, %identifier, "identifier pinned");
+                Ok(PinCheck::New)
+            }
+        }
+    }
+
+    /// Remove the pin of a peer. Return false if there was no pin for this peer
+    pub fn remove(&self, name: &str) -> Result<bool> {
+        let _lock = StateLock::acquire(self.dir())?;
+        let mut pins = self.list()?;
+        if pins.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.write(&pins)?;
+        info!(%name, "pin removed");
+        Ok(true)
+    }
+
+    /// Directory of the pins file, locked while the pins are updated
+    fn dir(&self) -> &Path {
+        self.path.parent().expect("Should have parent")
+    }
+
+    pub fn delete(&self) -> Result<()> {
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+
+    fn write(&self, pins: &BTreeMap<String, TrustPin>) -> Result<()> {
+        let contents = serde_json::to_string_pretty(pins)?;
+        write_atomically(&self.path, contents)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct TrustPin {
+    pub identifier: Identifier,
+    /// Time of the first secure channel to the peer, in seconds since the Unix epoch
+    pub pinned_at: u64,
+}
+
+impl TrustPin {
+    fn new(identifier: Identifier) -> Self {
+        let pinned_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self {
+            identifier,
+            pinned_at,
+        }
+    }
+}
+
+/// Result of the comparison of an identifier with the pin of a peer
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PinCheck {
+    /// The peer had no pin, the identifier is now pinned
+    New,
+    /// The identifier is the pinned one
+    Matching,
+    /// The peer presented a different identifier than the pinned one
+    Changed { pinned: Identifier },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    #[test]
+    fn pin_identifiers() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = TrustPinsState::new(dir.path());
+        let alice = Identifier::from_str("Ie92f183eb4c324804ef4d62962dea94cf095a265").unwrap();
+        let bob = Identifier::from_str("Ibb37445cacb3ca7a20040a9b36469e321a57d2cd").unwrap();
+        let name = "/dnsaddr/alice.example.com/tcp/4000/secure/api";
+
+        assert_eq!(state.check(name, &alice).unwrap(), PinCheck::New);
+        assert_eq!(state.check(name, &alice).unwrap(), PinCheck::Matching);
+        assert_eq!(
+            state.check(name, &bob).unwrap(),
+            PinCheck::Changed {
+                pinned: alice.clone()
+            }
+        );
+        assert_eq!(state.get(name).unwrap().unwrap().identifier, alice);
+
+        assert!(state.remove(name).unwrap());
+        assert!(!state.remove(name).unwrap());
+        assert_eq!(state.check(name, &bob).unwrap(), PinCheck::New);
+    }
+}
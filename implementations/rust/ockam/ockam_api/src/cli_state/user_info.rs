@@ -1,4 +1,5 @@
 use super::Result;
+use crate::cli_state::traits::{read_item_config, write_item_config};
 use crate::cloud::enroll::auth0::UserInfo;
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
@@ -53,12 +54,12 @@ mod traits {
 
         fn new(path: PathBuf, config: Self::Config) -> Result<Self> {
             let contents = serde_json::to_string(&config)?;
-            std::fs::write(&path, contents)?;
+            write_item_config(&path, &path, contents)?;
             Ok(Self { path, config })
         }
 
         fn load(path: PathBuf) -> Result<Self> {
-            let contents = std::fs::read_to_string(&path)?;
+            let contents = read_item_config(&path, &path)?;
             let config = serde_json::from_str(&contents)?;
             Ok(Self { path, config })
         }
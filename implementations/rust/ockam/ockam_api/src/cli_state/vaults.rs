@@ -1,17 +1,56 @@
 use std::fmt::{Display, Formatter};
+#[cfg(feature = "vault-encryption")]
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
+#[cfg(feature = "vault-encryption")]
+use age::secrecy::Secret;
 use serde::{Deserialize, Serialize};
 
 use ockam::identity::Vault;
+#[cfg(feature = "vault-encryption")]
+use ockam_vault::storage::{EncryptedStorage, StorageEncryption};
+#[cfg(feature = "ssh-agent")]
+use ockam_vault::ExternalSigningVault;
+use ockam_vault::SigningSecretKeyHandle;
 use ockam_vault_aws::AwsSigningVault;
+#[cfg(feature = "pkcs11")]
+use ockam_vault_pkcs11::{Pkcs11Config, Pkcs11SigningVault};
+#[cfg(feature = "ssh-agent")]
+use ockam_vault_ssh_agent::SshAgentSigner;
 
 use crate::cli_state::traits::StateItemTrait;
+use crate::cli_state::traits::{read_item_config, write_item_config};
+#[cfg(feature = "vault-encryption")]
+use crate::cli_state::write_atomically;
 use crate::cli_state::{CliStateError, StateDirTrait, DATA_DIR_NAME};
 
 use super::Result;
 
+/// Environment variable containing the PIN of the PKCS#11 token of a vault.
+/// The PIN is never stored with the vault configuration
+pub const PKCS11_PIN_ENV: &str = "OCKAM_PKCS11_PIN";
+
+/// Environment variable containing the passphrase used to export or import a vault,
+/// or to use a vault locked with a passphrase
+pub const VAULT_PASSPHRASE_ENV: &str = "OCKAM_VAULT_PASSPHRASE";
+
+/// Service of the OS keychain entries storing the passphrases of the locked vaults
+#[cfg(feature = "keychain")]
+const KEYCHAIN_SERVICE: &str = "ockam";
+
+/// Function asking the user for the passphrase of a locked vault, given the vault name
+pub type VaultPassphrasePrompt = fn(&str) -> Option<String>;
+
+static VAULT_PASSPHRASE_PROMPT: OnceLock<VaultPassphrasePrompt> = OnceLock::new();
+
+/// Set the function used to ask for the passphrase of a locked vault when
+/// the [`VAULT_PASSPHRASE_ENV`] environment variable is not set
+pub fn set_vault_passphrase_prompt(prompt: VaultPassphrasePrompt) {
+    let _ = VAULT_PASSPHRASE_PROMPT.set(prompt);
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct VaultsState {
     dir: PathBuf,
@@ -32,6 +71,98 @@ impl VaultsState {
         }
         Ok(state)
     }
+
+    /// Create a vault from an archive made by [`VaultState::export`]
+    #[cfg(feature = "vault-encryption")]
+    pub async fn import(&self, name: &str, archive: &[u8], passphrase: &str) -> Result<VaultState> {
+        if self.exists(name) {
+            return Err(CliStateError::AlreadyExists {
+                resource: Self::default_filename().to_string(),
+                name: name.to_string(),
+            });
+        }
+        let archive: VaultArchive = serde_json::from_slice(&decrypt(archive, passphrase)?)?;
+        let state = VaultState::new(self.path(name), archive.config)?;
+        if let Some(data_dir) = state.data_path.parent() {
+            std::fs::create_dir_all(data_dir)?;
+        }
+        write_atomically(&state.data_path, archive.storage)?;
+        state.get().await?;
+        if !self.default_path()?.exists() {
+            self.set_default(name)?;
+        }
+        Ok(state)
+    }
+}
+
+/// Content of an exported vault, before its encryption
+#[cfg(feature = "vault-encryption")]
+#[derive(Serialize, Deserialize)]
+struct VaultArchive {
+    config: VaultConfig,
+    storage: String,
+}
+
+/// Encrypt some data with a passphrase, using the age format
+#[cfg(feature = "vault-encryption")]
+fn encrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let encryptor = age::Encryptor::with_user_passphrase(Secret::new(passphrase.to_string()));
+    let mut encrypted = vec![];
+    let mut writer = encryptor.wrap_output(&mut encrypted)?;
+    writer.write_all(data)?;
+    writer.finish()?;
+    Ok(encrypted)
+}
+
+/// Decrypt some data encrypted with [`encrypt`]
+#[cfg(feature = "vault-encryption")]
+fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let decryptor = match age::Decryptor::new(data)
+        .map_err(|e| CliStateError::InvalidData(format!("Invalid vault data: {e}")))?
+    {
+        age::Decryptor::Passphrase(decryptor) => decryptor,
+        _ => {
+            return Err(CliStateError::InvalidData(
+                "The vault data is not encrypted with a passphrase".to_string(),
+            ))
+        }
+    };
+    let mut reader = decryptor
+        .decrypt(&Secret::new(passphrase.to_string()), None)
+        .map_err(|e| CliStateError::InvalidData(format!("Cannot decrypt the vault data: {e}")))?;
+    let mut decrypted = vec![];
+    reader.read_to_end(&mut decrypted)?;
+    Ok(decrypted)
+}
+
+/// Encryption of the secrets of a locked vault with its passphrase
+#[cfg(feature = "vault-encryption")]
+struct PassphraseEncryption {
+    passphrase: String,
+}
+
+#[cfg(feature = "vault-encryption")]
+impl StorageEncryption for PassphraseEncryption {
+    fn encrypt(&self, data: &[u8]) -> ockam_core::Result<Vec<u8>> {
+        Ok(encrypt(data, &self.passphrase)?)
+    }
+
+    fn decrypt(&self, data: &[u8]) -> ockam_core::Result<Vec<u8>> {
+        Ok(decrypt(data, &self.passphrase)?)
+    }
+}
+
+/// Remove a file, if it exists
+fn remove_file_if_exists(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(feature = "keychain")]
+fn keychain_error(e: keyring::Error) -> CliStateError {
+    CliStateError::InvalidOperation(format!("Cannot access the OS keychain: {e}"))
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize)]
@@ -52,12 +183,56 @@ impl VaultState {
             vault.credential_vault = aws_vault;
 
             Ok(vault)
+        } else if let Some(pkcs11) = &self.config.pkcs11 {
+            self.pkcs11_vault(pkcs11).await
+        } else if let Some(ssh_agent) = &self.config.ssh_agent {
+            self.ssh_agent_vault(ssh_agent)
         } else {
-            let vault =
-                Vault::create_with_persistent_storage_path(self.vault_file_path().as_path())
-                    .await?;
-            Ok(vault)
+            self.vault().await
+        }
+    }
+
+    #[cfg(feature = "pkcs11")]
+    async fn pkcs11_vault(&self, pkcs11: &Pkcs11VaultConfig) -> Result<Vault> {
+        let mut config = Pkcs11Config::new(&pkcs11.module);
+        if let Some(token_label) = &pkcs11.token_label {
+            config = config.with_token_label(token_label);
+        }
+        if let Ok(pin) = std::env::var(PKCS11_PIN_ENV) {
+            config = config.with_pin(pin);
         }
+        let mut vault = Vault::create();
+        let pkcs11_vault = Arc::new(Pkcs11SigningVault::create(config).await?);
+        vault.identity_vault = pkcs11_vault.clone();
+        vault.credential_vault = pkcs11_vault;
+
+        Ok(vault)
+    }
+
+    #[cfg(not(feature = "pkcs11"))]
+    async fn pkcs11_vault(&self, _pkcs11: &Pkcs11VaultConfig) -> Result<Vault> {
+        Err(self.unsupported("this build doesn't support the PKCS#11 vaults"))
+    }
+
+    #[cfg(feature = "ssh-agent")]
+    fn ssh_agent_vault(&self, ssh_agent: &SshAgentVaultConfig) -> Result<Vault> {
+        let mut vault = Vault::create();
+        let ssh_agent_vault = Arc::new(ExternalSigningVault::new(Arc::new(ssh_agent.signer()?)));
+        vault.identity_vault = ssh_agent_vault.clone();
+        vault.credential_vault = ssh_agent_vault;
+
+        Ok(vault)
+    }
+
+    #[cfg(not(feature = "ssh-agent"))]
+    fn ssh_agent_vault(&self, _ssh_agent: &SshAgentVaultConfig) -> Result<Vault> {
+        Err(self.unsupported("this build doesn't support the ssh-agent vaults"))
+    }
+
+    /// Error returned when the vault can't be used by this build
+    #[allow(dead_code)]
+    fn unsupported(&self, reason: &str) -> CliStateError {
+        CliStateError::InvalidOperation(format!("The vault {} can't be used: {reason}", self.name))
     }
 
     fn build_data_path(name: &str, path: &Path) -> PathBuf {
@@ -72,11 +247,170 @@ impl VaultState {
     }
 
     pub async fn vault(&self) -> Result<Vault> {
+        if self.is_locked() {
+            return self.locked_vault().await;
+        }
         let path = self.vault_file_path().clone();
         let vault = Vault::create_with_persistent_storage_path(path.as_path()).await?;
         Ok(vault)
     }
 
+    /// Return a vault decrypting its secrets with the passphrase of the vault
+    #[cfg(feature = "vault-encryption")]
+    async fn locked_vault(&self) -> Result<Vault> {
+        let encryption = Arc::new(PassphraseEncryption {
+            passphrase: self.passphrase()?,
+        });
+        let storage = EncryptedStorage::create(&self.encrypted_data_path(), encryption).await?;
+        Ok(Vault::create_with_persistent_storage(storage))
+    }
+
+    #[cfg(not(feature = "vault-encryption"))]
+    async fn locked_vault(&self) -> Result<Vault> {
+        Err(self.unsupported("this build doesn't support the locked vaults"))
+    }
+
+    /// Path of the encrypted secrets of a locked vault, next to the plain text storage file
+    fn encrypted_data_path(&self) -> PathBuf {
+        self.data_path.with_extension("age")
+    }
+
+    /// Return true if the secrets of the vault are encrypted at rest
+    pub fn is_locked(&self) -> bool {
+        self.config.encryption.is_some()
+    }
+
+    /// Encrypt the secrets of the vault at rest with a passphrase
+    #[cfg(feature = "vault-encryption")]
+    pub fn lock_with_passphrase(&self, passphrase: &str) -> Result<VaultState> {
+        self.lock(VaultEncryption::Passphrase, passphrase)
+    }
+
+    /// Encrypt the secrets of the vault at rest with a random passphrase stored in the OS keychain
+    #[cfg(feature = "keychain")]
+    pub fn lock_with_keychain(&self) -> Result<VaultState> {
+        let passphrase = hex::encode(rand::random::<[u8; 32]>());
+        self.keychain_entry()?
+            .set_password(&passphrase)
+            .map_err(keychain_error)?;
+        self.lock(VaultEncryption::Keychain, &passphrase)
+    }
+
+    #[cfg(feature = "vault-encryption")]
+    fn lock(&self, encryption: VaultEncryption, passphrase: &str) -> Result<VaultState> {
+        if self.is_aws() || self.is_pkcs11() || self.is_ssh_agent() {
+            return Err(CliStateError::InvalidOperation(format!(
+                "The keys of the {} vault {} are not stored by Ockam and can't be locked",
+                self.config.kind(),
+                self.name
+            )));
+        }
+        if self.is_locked() {
+            return Err(CliStateError::InvalidOperation(format!(
+                "The vault {} is already locked",
+                self.name
+            )));
+        }
+        let storage = match std::fs::read(&self.data_path) {
+            Ok(storage) => storage,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => b"[]".to_vec(),
+            Err(e) => return Err(e.into()),
+        };
+        write_atomically(&self.encrypted_data_path(), &encrypt(&storage, passphrase)?)?;
+        let config = VaultConfig {
+            encryption: Some(encryption),
+            ..self.config.clone()
+        };
+        let state = VaultState::new(self.path.clone(), config)?;
+        remove_file_if_exists(&self.data_path)?;
+        remove_file_if_exists(&self.data_path.with_extension("json.lock"))?;
+        Ok(state)
+    }
+
+    /// Decrypt the secrets of a locked vault, and store them in plain text again
+    #[cfg(feature = "vault-encryption")]
+    pub fn unlock(&self) -> Result<VaultState> {
+        let encryption = self.config.encryption.ok_or_else(|| {
+            CliStateError::InvalidOperation(format!("The vault {} is not locked", self.name))
+        })?;
+        write_atomically(&self.data_path, &self.decrypted_storage()?)?;
+        let config = VaultConfig {
+            encryption: None,
+            ..self.config.clone()
+        };
+        let state = VaultState::new(self.path.clone(), config)?;
+        remove_file_if_exists(&self.encrypted_data_path())?;
+        if encryption == VaultEncryption::Keychain {
+            self.delete_keychain_entry()?;
+        }
+        Ok(state)
+    }
+
+    /// Return the secrets of a locked vault, in the format of the plain text storage file
+    #[cfg(feature = "vault-encryption")]
+    fn decrypted_storage(&self) -> Result<Vec<u8>> {
+        let encrypted = std::fs::read(self.encrypted_data_path())?;
+        decrypt(&encrypted, &self.passphrase()?)
+    }
+
+    /// Return the passphrase of a locked vault. It is read from the OS keychain, from the
+    /// [`VAULT_PASSPHRASE_ENV`] environment variable, or asked to the user
+    #[cfg(feature = "vault-encryption")]
+    fn passphrase(&self) -> Result<String> {
+        if self.config.encryption == Some(VaultEncryption::Keychain) {
+            return self.keychain_passphrase();
+        }
+        if let Ok(passphrase) = std::env::var(VAULT_PASSPHRASE_ENV) {
+            return Ok(passphrase);
+        }
+        match VAULT_PASSPHRASE_PROMPT
+            .get()
+            .and_then(|prompt| prompt(&self.name))
+        {
+            Some(passphrase) => {
+                // The nodes started in the background by this process inherit the passphrase
+                std::env::set_var(VAULT_PASSPHRASE_ENV, &passphrase);
+                Ok(passphrase)
+            }
+            None => Err(CliStateError::InvalidOperation(format!(
+                "The vault {} is locked. Its passphrase must be set with the {VAULT_PASSPHRASE_ENV} environment variable",
+                self.name
+            ))),
+        }
+    }
+
+    #[cfg(feature = "keychain")]
+    fn keychain_entry(&self) -> Result<keyring::Entry> {
+        keyring::Entry::new(KEYCHAIN_SERVICE, &format!("vault:{}", self.name))
+            .map_err(keychain_error)
+    }
+
+    #[cfg(all(feature = "vault-encryption", feature = "keychain"))]
+    fn keychain_passphrase(&self) -> Result<String> {
+        self.keychain_entry()?
+            .get_password()
+            .map_err(keychain_error)
+    }
+
+    #[cfg(all(feature = "vault-encryption", not(feature = "keychain")))]
+    fn keychain_passphrase(&self) -> Result<String> {
+        Err(self.unsupported("this build can't read its passphrase from the OS keychain"))
+    }
+
+    /// Remove the passphrase of the vault from the OS keychain. Its entry may already be gone
+    #[cfg(feature = "keychain")]
+    fn delete_keychain_entry(&self) -> Result<()> {
+        let _ = self.keychain_entry()?.delete_password();
+        Ok(())
+    }
+
+    /// Without the OS keychain support, the entry of the vault is left in the keychain
+    #[cfg(not(feature = "keychain"))]
+    fn delete_keychain_entry(&self) -> Result<()> {
+        warn!(vault = %self.name, "the passphrase of the vault is left in the OS keychain");
+        Ok(())
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -84,19 +418,84 @@ impl VaultState {
     pub fn is_aws(&self) -> bool {
         self.config.is_aws()
     }
+
+    pub fn is_pkcs11(&self) -> bool {
+        self.config.is_pkcs11()
+    }
+
+    pub fn is_ssh_agent(&self) -> bool {
+        self.config.is_ssh_agent()
+    }
+
+    /// Return the handle of a key of an ssh-agent vault, designated by its comment,
+    /// its fingerprint or its OpenSSH public key
+    #[cfg(feature = "ssh-agent")]
+    pub async fn ssh_agent_key_handle(&self, key: &str) -> Result<SigningSecretKeyHandle> {
+        let ssh_agent = self.config.ssh_agent.as_ref().ok_or_else(|| {
+            CliStateError::InvalidOperation(format!(
+                "The vault {} is not an ssh-agent vault",
+                self.name
+            ))
+        })?;
+        let key = ssh_agent.signer()?.find_key(key).await?;
+        Ok(ExternalSigningVault::secret_key_handle(&key.public_key))
+    }
+
+    #[cfg(not(feature = "ssh-agent"))]
+    pub async fn ssh_agent_key_handle(&self, _key: &str) -> Result<SigningSecretKeyHandle> {
+        Err(self.unsupported("this build doesn't support the ssh-agent vaults"))
+    }
+
+    /// Export the configuration and the secrets of the vault, encrypted with a passphrase.
+    /// The keys of AWS KMS, PKCS#11 and ssh-agent vaults never leave their storage and can't be exported
+    #[cfg(feature = "vault-encryption")]
+    pub fn export(&self, passphrase: &str) -> Result<Vec<u8>> {
+        if self.is_aws() || self.is_pkcs11() || self.is_ssh_agent() {
+            return Err(CliStateError::InvalidOperation(format!(
+                "The keys of the {} vault {} can't be exported",
+                self.config.kind(),
+                self.name
+            )));
+        }
+        // The exported vault is not locked, the archive is already encrypted
+        let storage = if self.is_locked() {
+            String::from_utf8(self.decrypted_storage()?)
+                .map_err(|e| CliStateError::InvalidData(e.to_string()))?
+        } else {
+            std::fs::read_to_string(&self.data_path)?
+        };
+        let archive = VaultArchive {
+            config: VaultConfig {
+                encryption: None,
+                ..self.config.clone()
+            },
+            storage,
+        };
+        encrypt(&serde_json::to_vec(&archive)?, passphrase)
+    }
 }
 
 impl Display for VaultState {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Name: {}", self.name)?;
-        writeln!(
-            f,
-            "Type: {}",
-            match self.config.is_aws() {
-                true => "AWS KMS",
-                false => "OCKAM",
+        writeln!(f, "Type: {}", self.config.kind())?;
+        if let Some(pkcs11) = &self.config.pkcs11 {
+            writeln!(f, "Module: {}", pkcs11.module.display())?;
+            if let Some(token_label) = &pkcs11.token_label {
+                writeln!(f, "Token: {token_label}")?;
             }
-        )?;
+        }
+        if let Some(socket) = self
+            .config
+            .ssh_agent
+            .as_ref()
+            .and_then(|s| s.socket.as_ref())
+        {
+            writeln!(f, "Socket: {}", socket.display())?;
+        }
+        if let Some(encryption) = &self.config.encryption {
+            writeln!(f, "Locked: with a {encryption}")?;
+        }
         Ok(())
     }
 }
@@ -105,16 +504,114 @@ impl Display for VaultState {
 pub struct VaultConfig {
     #[serde(default)]
     aws_kms: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pkcs11: Option<Pkcs11VaultConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ssh_agent: Option<SshAgentVaultConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encryption: Option<VaultEncryption>,
 }
 
 impl VaultConfig {
     pub fn new(aws_kms: bool) -> Result<Self> {
-        Ok(Self { aws_kms })
+        Ok(Self {
+            aws_kms,
+            ..Default::default()
+        })
+    }
+
+    /// Configuration of a vault storing its keys in a PKCS#11 token
+    pub fn pkcs11(module: PathBuf, token_label: Option<String>) -> Result<Self> {
+        if !module.is_file() {
+            return Err(CliStateError::InvalidPath(module.display().to_string()));
+        }
+        Ok(Self {
+            pkcs11: Some(Pkcs11VaultConfig {
+                module,
+                token_label,
+            }),
+            ..Default::default()
+        })
+    }
+
+    /// Configuration of a vault delegating its signatures to an ssh-agent.
+    /// The agent referenced by the `SSH_AUTH_SOCK` environment variable is used if no socket is given
+    pub fn ssh_agent(socket: Option<PathBuf>) -> Result<Self> {
+        Ok(Self {
+            ssh_agent: Some(SshAgentVaultConfig { socket }),
+            ..Default::default()
+        })
     }
 
     pub fn is_aws(&self) -> bool {
         self.aws_kms
     }
+
+    pub fn is_pkcs11(&self) -> bool {
+        self.pkcs11.is_some()
+    }
+
+    pub fn is_ssh_agent(&self) -> bool {
+        self.ssh_agent.is_some()
+    }
+
+    /// Name of the kind of storage used by the vault
+    pub fn kind(&self) -> &'static str {
+        if self.is_aws() {
+            "AWS KMS"
+        } else if self.is_pkcs11() {
+            "PKCS#11"
+        } else if self.is_ssh_agent() {
+            "SSH AGENT"
+        } else {
+            "OCKAM"
+        }
+    }
+}
+
+/// How the secrets of a locked vault are encrypted at rest
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum VaultEncryption {
+    /// With a passphrase given by the user
+    Passphrase,
+    /// With a random passphrase stored in the OS keychain
+    Keychain,
+}
+
+impl Display for VaultEncryption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VaultEncryption::Passphrase => write!(f, "passphrase"),
+            VaultEncryption::Keychain => write!(f, "passphrase stored in the OS keychain"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct Pkcs11VaultConfig {
+    /// Path of the PKCS#11 module
+    module: PathBuf,
+    /// Label of the token storing the keys
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token_label: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct SshAgentVaultConfig {
+    /// Path of the ssh-agent socket
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    socket: Option<PathBuf>,
+}
+
+#[cfg(feature = "ssh-agent")]
+impl SshAgentVaultConfig {
+    fn signer(&self) -> Result<SshAgentSigner> {
+        match &self.socket {
+            Some(socket) => Ok(SshAgentSigner::new(socket)),
+            None => Ok(SshAgentSigner::from_env()?),
+        }
+    }
 }
 
 mod traits {
@@ -151,6 +648,7 @@ mod traits {
         }
 
         fn delete(&self, name: impl AsRef<str>) -> Result<()> {
+            let _lock = self.lock()?;
             // If doesn't exist do nothing.
             if !self.exists(&name) {
                 return Ok(());
@@ -164,6 +662,10 @@ mod traits {
             }
             // Remove vault files
             vault.delete()?;
+            #[cfg(feature = "sqlite")]
+            crate::cli_state::database::update(self.dir(), |db, kind| {
+                db.remove(kind, name.as_ref())
+            })?;
             Ok(())
         }
     }
@@ -175,7 +677,7 @@ mod traits {
         fn new(path: PathBuf, config: Self::Config) -> Result<Self> {
             let contents = serde_json::to_string(&config)?;
             std::fs::create_dir_all(path.parent().unwrap())?;
-            std::fs::write(&path, contents)?;
+            write_item_config(&path, &path, contents)?;
             let name = file_stem(&path)?;
             let data_path = VaultState::build_data_path(&name, &path);
             Ok(Self {
@@ -188,7 +690,7 @@ mod traits {
 
         fn load(path: PathBuf) -> Result<Self> {
             let name = file_stem(&path)?;
-            let contents = std::fs::read_to_string(&path)?;
+            let contents = read_item_config(&path, &path)?;
             let config = serde_json::from_str(&contents)?;
             let data_path = VaultState::build_data_path(&name, &path);
             Ok(Self {
@@ -201,8 +703,12 @@ mod traits {
 
         fn delete(&self) -> Result<()> {
             std::fs::remove_file(&self.path)?;
-            std::fs::remove_file(&self.data_path)?;
-            std::fs::remove_file(self.data_path.with_extension("json.lock"))?;
+            remove_file_if_exists(&self.data_path)?;
+            remove_file_if_exists(&self.data_path.with_extension("json.lock"))?;
+            remove_file_if_exists(&self.encrypted_data_path())?;
+            if self.config.encryption == Some(VaultEncryption::Keychain) {
+                self.delete_keychain_entry()?;
+            }
             Ok(())
         }
 
@@ -215,3 +721,96 @@ mod traits {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli_state::CliState;
+    use ockam_vault::SigningKeyType;
+
+    #[cfg(feature = "vault-encryption")]
+    #[tokio::test]
+    async fn export_import_vault() {
+        let state = CliState::test().unwrap();
+        let alice = state
+            .vaults
+            .create_async("alice", VaultConfig::default())
+            .await
+            .unwrap();
+        let key = alice
+            .get()
+            .await
+            .unwrap()
+            .identity_vault
+            .generate_signing_secret_key(SigningKeyType::EdDSACurve25519)
+            .await
+            .unwrap();
+
+        let archive = alice.export("passphrase").unwrap();
+        assert!(state
+            .vaults
+            .import("bob", &archive, "wrong passphrase")
+            .await
+            .is_err());
+
+        let bob = state
+            .vaults
+            .import("bob", &archive, "passphrase")
+            .await
+            .unwrap();
+        assert!(bob
+            .get()
+            .await
+            .unwrap()
+            .identity_vault
+            .get_verifying_public_key(&key)
+            .await
+            .is_ok());
+    }
+
+    #[cfg(feature = "vault-encryption")]
+    #[tokio::test]
+    async fn lock_unlock_vault() {
+        let state = CliState::test().unwrap();
+        let vault = state
+            .vaults
+            .create_async("locked", VaultConfig::default())
+            .await
+            .unwrap();
+        let key = vault
+            .get()
+            .await
+            .unwrap()
+            .identity_vault
+            .generate_signing_secret_key(SigningKeyType::EdDSACurve25519)
+            .await
+            .unwrap();
+
+        let locked = vault.lock_with_passphrase("passphrase").unwrap();
+        assert!(locked.is_locked());
+        assert!(!locked.vault_file_path().exists());
+        assert!(state.vaults.get("locked").unwrap().is_locked());
+
+        std::env::set_var(VAULT_PASSPHRASE_ENV, "passphrase");
+        assert!(locked
+            .get()
+            .await
+            .unwrap()
+            .identity_vault
+            .get_verifying_public_key(&key)
+            .await
+            .is_ok());
+
+        let unlocked = locked.unlock().unwrap();
+        std::env::remove_var(VAULT_PASSPHRASE_ENV);
+        assert!(!unlocked.is_locked());
+        assert!(unlocked
+            .get()
+            .await
+            .unwrap()
+            .identity_vault
+            .get_verifying_public_key(&key)
+            .await
+            .is_ok());
+    }
+}
@@ -5,6 +5,7 @@ pub mod enroll;
 pub mod lease_manager;
 pub mod operation;
 pub mod project;
+pub mod project_node;
 pub mod secure_clients;
 pub mod share;
 pub mod space;
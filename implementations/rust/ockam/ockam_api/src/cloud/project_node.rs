@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use miette::IntoDiagnostic;
+use minicbor::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+use ockam::identity::Identifier;
+use ockam_core::api::Request;
+use ockam_core::async_trait;
+use ockam_node::Context;
+
+use crate::cloud::Controller;
+
+const TARGET: &str = "ockam_api::cloud::project_node";
+const API_SERVICE: &str = "projects";
+
+/// Labels defined by an operator to describe a node, for example `region=eu-west` or `role=gateway`
+pub type NodeLabels = BTreeMap<String, String>;
+
+/// Parse a label given as `key=value`
+pub fn parse_label(s: &str) -> Result<(String, String), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("the label '{s}' must have the format key=value"))?;
+    let key = key.trim();
+    if key.is_empty() || !key.chars().all(is_label_char) {
+        return Err(format!(
+            "the label key '{key}' can only contain alphanumeric characters, '-', '_', '.' and '/'"
+        ));
+    }
+    Ok((key.to_string(), value.trim().to_string()))
+}
+
+fn is_label_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/')
+}
+
+/// Node registered in the inventory of a project
+#[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cbor(map)]
+pub struct ProjectNode {
+    #[n(1)]
+    pub name: String,
+    #[n(2)]
+    pub identifier: Identifier,
+    #[n(3)]
+    #[serde(default)]
+    pub labels: NodeLabels,
+    #[n(4)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_seen_at: Option<String>,
+}
+
+/// Request sent by a node to register itself, with its labels, in the inventory of a project
+#[derive(Encode, Decode, Debug)]
+#[cfg_attr(test, derive(Clone))]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RegisterProjectNode {
+    #[n(1)] pub name: String,
+    #[n(2)] pub identifier: Identifier,
+    #[n(3)] pub labels: NodeLabels,
+}
+
+impl RegisterProjectNode {
+    pub fn new(name: impl Into<String>, identifier: Identifier, labels: NodeLabels) -> Self {
+        Self {
+            name: name.into(),
+            identifier,
+            labels,
+        }
+    }
+}
+
+/// A list of requirements on the labels of a node, separated by commas.
+///
+/// Each requirement is either:
+///  - `key=value`: the node has the label `key` with the value `value`
+///  - `key!=value`: the node doesn't have the label `key` with the value `value`
+///  - `key`: the node has the label `key`, whatever its value
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LabelSelector(Vec<LabelRequirement>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LabelRequirement {
+    Equals(String, String),
+    NotEquals(String, String),
+    Exists(String),
+}
+
+impl LabelSelector {
+    /// Return true if the labels satisfy all the requirements of this selector
+    pub fn matches(&self, labels: &NodeLabels) -> bool {
+        self.0.iter().all(|requirement| match requirement {
+            LabelRequirement::Equals(k, v) => labels.get(k) == Some(v),
+            LabelRequirement::NotEquals(k, v) => labels.get(k) != Some(v),
+            LabelRequirement::Exists(k) => labels.contains_key(k),
+        })
+    }
+}
+
+impl FromStr for LabelSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut requirements = vec![];
+        for requirement in s.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+            let requirement = if let Some((k, v)) = requirement.split_once("!=") {
+                LabelRequirement::NotEquals(k.trim().to_string(), v.trim().to_string())
+            } else if requirement.contains('=') {
+                let (k, v) = parse_label(requirement)?;
+                LabelRequirement::Equals(k, v)
+            } else {
+                LabelRequirement::Exists(requirement.to_string())
+            };
+            match &requirement {
+                LabelRequirement::Equals(k, _)
+                | LabelRequirement::NotEquals(k, _)
+                | LabelRequirement::Exists(k) => {
+                    if k.is_empty() || !k.chars().all(is_label_char) {
+                        return Err(format!("the selector '{s}' contains an invalid label key"));
+                    }
+                }
+            }
+            requirements.push(requirement);
+        }
+        Ok(Self(requirements))
+    }
+}
+
+impl Display for LabelSelector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let requirements: Vec<String> = self
+            .0
+            .iter()
+            .map(|r| match r {
+                LabelRequirement::Equals(k, v) => format!("{k}={v}"),
+                LabelRequirement::NotEquals(k, v) => format!("{k}!={v}"),
+                LabelRequirement::Exists(k) => k.clone(),
+            })
+            .collect();
+        f.write_str(&requirements.join(","))
+    }
+}
+
+#[async_trait]
+pub trait ProjectNodes {
+    async fn register_project_node(
+        &self,
+        ctx: &Context,
+        project_id: String,
+        node: RegisterProjectNode,
+    ) -> miette::Result<()>;
+
+    async fn list_project_nodes(
+        &self,
+        ctx: &Context,
+        project_id: String,
+    ) -> miette::Result<Vec<ProjectNode>>;
+}
+
+#[async_trait]
+impl ProjectNodes for Controller {
+    async fn register_project_node(
+        &self,
+        ctx: &Context,
+        project_id: String,
+        node: RegisterProjectNode,
+    ) -> miette::Result<()> {
+        trace!(target: TARGET, %project_id, node_name = %node.name, "registering project node");
+        let req = Request::put(format!("/v0/{project_id}/nodes/{}", node.name)).body(node);
+        self.0
+            .tell(ctx, API_SERVICE, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
+    async fn list_project_nodes(
+        &self,
+        ctx: &Context,
+        project_id: String,
+    ) -> miette::Result<Vec<ProjectNode>> {
+        trace!(target: TARGET, %project_id, "listing project nodes");
+        let req = Request::get(format!("/v0/{project_id}/nodes"));
+        self.0
+            .ask(ctx, API_SERVICE, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(labels: &[(&str, &str)]) -> NodeLabels {
+        labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_labels() {
+        assert_eq!(
+            parse_label("role=gateway"),
+            Ok(("role".to_string(), "gateway".to_string()))
+        );
+        assert!(parse_label("role").is_err());
+        assert!(parse_label("=gateway").is_err());
+    }
+
+    #[test]
+    fn select_nodes_by_label() {
+        let node = labels(&[("role", "gateway"), ("region", "eu-west")]);
+
+        let selector = LabelSelector::from_str("role=gateway").unwrap();
+        assert!(selector.matches(&node));
+
+        let selector = LabelSelector::from_str("role=gateway,region!=eu-west").unwrap();
+        assert!(!selector.matches(&node));
+
+        let selector = LabelSelector::from_str("owner").unwrap();
+        assert!(!selector.matches(&node));
+
+        assert!(LabelSelector::default().matches(&node));
+        assert!(LabelSelector::from_str("ro le=gateway").is_err());
+    }
+}
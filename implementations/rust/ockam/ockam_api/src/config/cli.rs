@@ -6,9 +6,9 @@ use crate::config::{lookup::ConfigLookup, ConfigValues};
 use crate::error::ApiError;
 use crate::{cli_state, multiaddr_to_transport_route, DefaultAddress, HexByteVec};
 use ockam::identity::{
-    identities, AuthorityService, CredentialsMemoryRetriever, CredentialsRetriever, Identifier,
-    Identities, Identity, RemoteCredentialsRetriever, RemoteCredentialsRetrieverInfo,
-    SecureChannels, TrustContext,
+    identities, AuthorityService, AuthorityStatus, AuthorityThreshold, CredentialsMemoryRetriever,
+    CredentialsRetriever, Identifier, Identities, Identity, RemoteCredentialsRetriever,
+    RemoteCredentialsRetrieverInfo, SecureChannels, TrustContext,
 };
 use ockam_core::compat::sync::Arc;
 use ockam_core::{Result, Route};
@@ -109,18 +109,32 @@ impl TrustContextConfig {
             .ok_or_else(|| ApiError::core("Missing authority on trust context config"))
     }
 
+    /// Create the trust context. The requests sent to the authority to retrieve credentials
+    /// are recorded in `authority_status`
     pub async fn to_trust_context(
         &self,
         secure_channels: Arc<SecureChannels>,
         tcp_transport: Option<TcpTransport>,
+        authority_status: AuthorityStatus,
     ) -> Result<TrustContext> {
         let authority = if let Some(authority_config) = self.authority.as_ref() {
             let identity = authority_config.identity().await?;
+            if let Some(threshold) = &authority_config.threshold {
+                threshold.validate()?;
+                secure_channels
+                    .identities()
+                    .authority_thresholds()
+                    .set(identity.identifier(), threshold.clone());
+            }
             let credential_retriever =
                 if let Some(retriever_type) = &authority_config.own_credential {
                     Some(
                         retriever_type
-                            .to_credential_retriever(secure_channels.clone(), tcp_transport)
+                            .to_credential_retriever(
+                                secure_channels.clone(),
+                                tcp_transport,
+                                authority_status,
+                            )
                             .await?,
                     )
                 } else {
@@ -222,6 +236,9 @@ impl TryFrom<ProjectLookup> for TrustContextConfig {
 pub struct TrustAuthorityConfig {
     identity: String,
     own_credential: Option<CredentialRetrieverConfig>,
+    /// Signatures required for the credentials issued by the authority
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    threshold: Option<AuthorityThreshold>,
 }
 
 impl TrustAuthorityConfig {
@@ -229,9 +246,18 @@ impl TrustAuthorityConfig {
         Self {
             identity,
             own_credential,
+            threshold: None,
         }
     }
 
+    pub fn set_threshold(&mut self, threshold: AuthorityThreshold) {
+        self.threshold = Some(threshold);
+    }
+
+    pub fn threshold(&self) -> Option<&AuthorityThreshold> {
+        self.threshold.as_ref()
+    }
+
     pub fn identity_str(&self) -> &str {
         &self.identity
     }
@@ -270,6 +296,7 @@ impl CredentialRetrieverConfig {
         &self,
         secure_channels: Arc<SecureChannels>,
         tcp_transport: Option<TcpTransport>,
+        authority_status: AuthorityStatus,
     ) -> Result<Arc<dyn CredentialsRetriever>> {
         match self {
             CredentialRetrieverConfig::FromMemory(credential) => Ok(Arc::new(
@@ -286,10 +313,10 @@ impl CredentialRetrieverConfig {
                     DefaultAddress::CREDENTIAL_ISSUER.into(),
                 );
 
-                Ok(Arc::new(RemoteCredentialsRetriever::new(
-                    secure_channels,
-                    credential_issuer_info,
-                )))
+                Ok(Arc::new(
+                    RemoteCredentialsRetriever::new(secure_channels, credential_issuer_info)
+                        .with_authority_status(authority_status),
+                ))
             }
         }
     }
@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
     fmt,
-    net::{SocketAddr, SocketAddrV4, SocketAddrV6},
+    net::{Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
     str::FromStr,
 };
 
@@ -191,6 +191,21 @@ impl InternetAddress {
             Self::V6(v6) => v6.port(),
         }
     }
+
+    /// Address to connect to a node of this machine listening on this address.
+    /// A listener bound to the loopback or to the unspecified address is reached on the
+    /// loopback interface of the same address family
+    pub fn local_peer(&self) -> String {
+        match self {
+            Self::V4(v4) if v4.ip().is_loopback() || v4.ip().is_unspecified() => {
+                format!("localhost:{}", v4.port())
+            }
+            Self::V6(v6) if v6.ip().is_loopback() || v6.ip().is_unspecified() => {
+                format!("[{}]:{}", Ipv6Addr::LOCALHOST, v6.port())
+            }
+            _ => self.to_string(),
+        }
+    }
 }
 
 impl From<SocketAddr> for InternetAddress {
@@ -1,6 +1,8 @@
 use core::fmt;
 use miette::Diagnostic;
 
+use ockam::identity::IdentityError;
+use ockam_core::api::ApiErrorCode;
 use ockam_core::errcode::{Kind, Origin};
 
 /// Potential API errors
@@ -49,3 +51,30 @@ impl ApiError {
         ockam_core::Error::new(Origin::Application, Kind::Unknown, m.to_string())
     }
 }
+
+/// Return the code sent to the client of a failed request for the given error,
+/// if its cause or its kind is specific enough
+pub fn api_error_code(e: &ockam_core::Error) -> Option<ApiErrorCode> {
+    let identity_error =
+        std::error::Error::source(e).and_then(|s| s.downcast_ref::<IdentityError>());
+    if let Some(identity_error) = identity_error {
+        match identity_error {
+            IdentityError::CredentialExpired => return Some(ApiErrorCode::CredentialExpired),
+            IdentityError::CredentialVerificationFailed
+            | IdentityError::CredentialRevoked
+            | IdentityError::SecureChannelVerificationFailedIncorrectCredential
+            | IdentityError::SecureChannelTrustCheckFailed
+            | IdentityError::SecureChannelRequiredAttributeMissing => {
+                return Some(ApiErrorCode::IdentityNotAuthorized)
+            }
+            _ => {}
+        }
+    }
+    match e.code().kind {
+        Kind::Timeout => Some(ApiErrorCode::Timeout),
+        Kind::NotFound => Some(ApiErrorCode::NotFound),
+        Kind::AlreadyExists => Some(ApiErrorCode::AlreadyExists),
+        Kind::Invalid => Some(ApiErrorCode::InvalidRequest),
+        _ => None,
+    }
+}
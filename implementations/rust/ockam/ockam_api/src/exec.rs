@@ -0,0 +1,307 @@
+//! Remote command execution.
+//!
+//! The exec service runs commands on the machine of its node, on behalf of the identities which
+//! are authorized by the policy of the service. It is never started by default, and it only
+//! runs the commands which were explicitly allowed when it was started. Commands are executed
+//! directly, without a shell, and their output is streamed back to the caller while they run.
+//!
+//! Every request is written to the node logs with the target [`AUDIT_TARGET`]: the identifier of
+//! the caller, the command, and whether it was rejected or its exit code.
+
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use minicbor::{Decode, Encode};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::mpsc;
+
+use ockam::identity::{Identifier, IdentitySecureChannelLocalInfo};
+use ockam::{Address, Context, Result, Routed, Worker};
+use ockam_core::{AllowAll, AllowOnwardAddress, DenyAll, Mailbox, Mailboxes, Route};
+use ockam_node::MessageReceiveOptions;
+
+use crate::error::ApiError;
+
+/// Target of the log events recording the requests received by an exec service
+pub const AUDIT_TARGET: &str = "ockam_api::exec::audit";
+
+/// Maximum time a command can run before being killed
+pub const DEFAULT_EXEC_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Request sent to an exec service
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ExecRequest {
+    /// Program to run, followed by its arguments
+    #[n(1)] pub command: Vec<String>,
+}
+
+impl ExecRequest {
+    pub fn new(command: Vec<String>) -> Self {
+        Self { command }
+    }
+}
+
+/// Messages sent back by an exec service while a command runs
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+pub enum ExecOutput {
+    #[n(0)] Stdout(#[n(0)] Vec<u8>),
+    #[n(1)] Stderr(#[n(0)] Vec<u8>),
+    /// The command terminated. There is no exit code if it was killed
+    #[n(2)] Exit(#[n(0)] Option<i32>),
+    /// The command was not run
+    #[n(3)] Rejected(#[n(0)] String),
+}
+
+/// Command which can be run by an exec service.
+///
+/// A requested command is allowed if it starts with the program and the arguments of an allowed
+/// command: `systemctl restart` allows `systemctl restart app`, but not `systemctl stop app`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllowedCommand(Vec<String>);
+
+impl AllowedCommand {
+    pub fn allows(&self, command: &[String]) -> bool {
+        command.starts_with(&self.0)
+    }
+}
+
+impl FromStr for AllowedCommand {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+        let words: Vec<String> = s.split_whitespace().map(|w| w.to_string()).collect();
+        if words.is_empty() {
+            return Err(ApiError::message("An allowed command can't be empty"));
+        }
+        Ok(Self(words))
+    }
+}
+
+impl Display for AllowedCommand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0.join(" "))
+    }
+}
+
+pub struct ExecService {
+    allowed_commands: Vec<AllowedCommand>,
+    timeout: Duration,
+}
+
+impl ExecService {
+    pub fn new(allowed_commands: Vec<AllowedCommand>, timeout: Duration) -> Self {
+        Self {
+            allowed_commands,
+            timeout,
+        }
+    }
+
+    fn is_allowed(&self, command: &[String]) -> bool {
+        self.allowed_commands.iter().any(|c| c.allows(command))
+    }
+}
+
+#[ockam::worker]
+impl Worker for ExecService {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Vec<u8>>) -> Result<()> {
+        let return_route = msg.return_route();
+        let caller = match IdentitySecureChannelLocalInfo::find_info(msg.local_message()) {
+            Ok(info) => info.their_identity_id(),
+            Err(_) => {
+                warn!(target: AUDIT_TARGET, src = %msg.src_addr(), "exec request rejected: not received over a secure channel");
+                let rejected = ExecOutput::Rejected("a secure channel is required".to_string());
+                return ctx.send(return_route, minicbor::to_vec(rejected)?).await;
+            }
+        };
+
+        let request: ExecRequest = minicbor::decode(msg.as_body())?;
+        let command = request.command;
+        if command.is_empty() || !self.is_allowed(&command) {
+            warn!(target: AUDIT_TARGET, %caller, ?command, "exec request rejected: command not allowed");
+            let rejected = ExecOutput::Rejected("this command is not allowed".to_string());
+            return ctx.send(return_route, minicbor::to_vec(rejected)?).await;
+        }
+        info!(target: AUDIT_TARGET, %caller, ?command, "exec request accepted");
+
+        // The command runs in its own task so that long running commands
+        // don't prevent the service from handling other requests
+        let session = ctx
+            .new_detached(
+                Address::random_tagged("ExecService.session"),
+                DenyAll,
+                AllowAll,
+            )
+            .await?;
+        let timeout = self.timeout;
+        tokio::spawn(async move {
+            if let Err(err) = run_command(&session, &caller, &command, return_route, timeout).await
+            {
+                error!(%caller, ?command, %err, "failed to send the output of a command");
+            }
+        });
+        Ok(())
+    }
+}
+
+async fn run_command(
+    ctx: &Context,
+    caller: &Identifier,
+    command: &[String],
+    return_route: Route,
+    timeout: Duration,
+) -> Result<()> {
+    let started = Instant::now();
+    let mut child = match tokio::process::Command::new(&command[0])
+        .args(&command[1..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => {
+            warn!(target: AUDIT_TARGET, %caller, ?command, %err, "exec command could not be started");
+            let rejected = ExecOutput::Rejected(format!("the command could not be started: {err}"));
+            return ctx.send(return_route, minicbor::to_vec(rejected)?).await;
+        }
+    };
+
+    let (sender, mut receiver) = mpsc::channel(16);
+    if let Some(stdout) = child.stdout.take() {
+        tokio::spawn(read_output(stdout, sender.clone(), ExecOutput::Stdout));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tokio::spawn(read_output(stderr, sender, ExecOutput::Stderr));
+    } else {
+        drop(sender);
+    }
+
+    let result = tokio::time::timeout(timeout, async {
+        while let Some(output) = receiver.recv().await {
+            ctx.send(return_route.clone(), minicbor::to_vec(output)?)
+                .await?;
+        }
+        child
+            .wait()
+            .await
+            .map_err(|e| ApiError::core(e.to_string()))
+    })
+    .await;
+
+    let exit_code = match result {
+        Ok(status) => status?.code(),
+        Err(_) => {
+            let _ = child.kill().await;
+            warn!(target: AUDIT_TARGET, %caller, ?command, timeout_secs = timeout.as_secs(), "exec command killed after its timeout");
+            None
+        }
+    };
+    info!(target: AUDIT_TARGET, %caller, ?command, ?exit_code, duration_ms = started.elapsed().as_millis() as u64, "exec command terminated");
+    ctx.send(return_route, minicbor::to_vec(ExecOutput::Exit(exit_code))?)
+        .await
+}
+
+async fn read_output(
+    mut reader: impl AsyncRead + Unpin,
+    sender: mpsc::Sender<ExecOutput>,
+    output: fn(Vec<u8>) -> ExecOutput,
+) {
+    let mut buffer = vec![0; 4096];
+    loop {
+        match reader.read(&mut buffer).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                if sender.send(output(buffer[..n].to_vec())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Run a command with the exec service at the end of `route`.
+///
+/// `on_output` is called with the output of the command as soon as it is received, and the exit
+/// code of the command is returned once it terminates. `idle_timeout` is the maximum time to
+/// wait for the next message from the service.
+pub async fn exec(
+    ctx: &Context,
+    route: Route,
+    command: Vec<String>,
+    idle_timeout: Duration,
+    mut on_output: impl FnMut(ExecOutput) + Send,
+) -> Result<Option<i32>> {
+    let next = route.next()?.clone();
+    let address = Address::random_tagged("ExecClient");
+    let mailboxes = Mailboxes::new(
+        Mailbox::new(
+            address.clone(),
+            Arc::new(AllowAll),
+            Arc::new(AllowOnwardAddress(next.clone())),
+        ),
+        vec![],
+    );
+    if let Some(flow_control_id) = ctx
+        .flow_controls()
+        .find_flow_control_with_producer_address(&next)
+        .map(|x| x.flow_control_id().clone())
+    {
+        // To be able to receive the output of the command
+        ctx.flow_controls().add_consumer(address, &flow_control_id);
+    }
+    let mut child_ctx = ctx.new_detached_with_mailboxes(mailboxes).await?;
+
+    child_ctx
+        .send(route, minicbor::to_vec(ExecRequest::new(command))?)
+        .await?;
+    loop {
+        let msg = child_ctx
+            .receive_extended::<Vec<u8>>(MessageReceiveOptions::new().with_timeout(idle_timeout))
+            .await?;
+        match minicbor::decode(msg.as_body())? {
+            ExecOutput::Exit(exit_code) => return Ok(exit_code),
+            ExecOutput::Rejected(reason) => {
+                return Err(ApiError::core(format!(
+                    "The command was rejected: {reason}"
+                )))
+            }
+            output => on_output(output),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn allowed_commands() {
+        let service = ExecService::new(
+            vec![
+                "systemctl restart".parse().unwrap(),
+                "uptime".parse().unwrap(),
+            ],
+            DEFAULT_EXEC_TIMEOUT,
+        );
+        assert!(service.is_allowed(&command("systemctl restart app")));
+        assert!(service.is_allowed(&command("uptime")));
+        assert!(!service.is_allowed(&command("systemctl stop app")));
+        assert!(!service.is_allowed(&command("systemctl")));
+        assert!(!service.is_allowed(&command("rm -rf /")));
+        assert!("  ".parse::<AllowedCommand>().is_err());
+    }
+}
@@ -1,3 +1,5 @@
+mod credential_jwt;
 mod enrollment_ticket;
 
+pub use credential_jwt::*;
 pub use enrollment_ticket::*;
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde_json::{json, Map, Value};
+
+use minicbor::bytes::ByteVec;
+use ockam::identity::models::{CredentialAndPurposeKey, PurposePublicKey};
+use ockam::identity::{
+    parse_attribute_types, AttributeType, Identifier, Identities, ATTRIBUTE_TYPES,
+    ATTRIBUTE_TYPES_UTF8,
+};
+use ockam_core::Result;
+use ockam_vault::{Signature, VerifyingPublicKey};
+
+use crate::error::ApiError;
+
+/// Re-encode the attributes of a credential as a JWT, so that they can be consumed by systems
+/// which don't understand Ockam credentials, like OPA or API gateways.
+///
+/// The credential is verified first. The JWT is then signed with the purpose key of the issuer
+/// which signed the credential, so the vault of `identities` must contain that key. The public
+/// key is added in JWK form to the `jwk` header of the token
+pub async fn credential_to_jwt(
+    identities: Arc<Identities>,
+    issuer: &Identifier,
+    credential: &CredentialAndPurposeKey,
+) -> Result<String> {
+    let data = identities
+        .credentials()
+        .credentials_verification()
+        .verify_credential(None, &[issuer.clone()], credential)
+        .await?;
+    let public_key: VerifyingPublicKey = match data.purpose_key_data.public_key {
+        PurposePublicKey::CredentialSigning(public_key) => public_key.into(),
+        PurposePublicKey::SecureChannelStatic(_) => {
+            return Err(ApiError::core(
+                "The credential is not signed with a credential key",
+            ))
+        }
+    };
+
+    let header = json!({
+        "alg": jwt_algorithm(&public_key),
+        "typ": "JWT",
+        "kid": issuer.to_string(),
+        "jwk": jwk(&public_key),
+    });
+    let credential_data = data.credential_data;
+    let mut claims = json!({
+        "iss": issuer.to_string(),
+        "iat": credential_data.created_at.0,
+        "nbf": credential_data.created_at.0,
+        "exp": credential_data.expires_at.0,
+        "attributes": attributes_claim(&credential_data.subject_attributes.map),
+    });
+    if let Some(subject) = credential_data.subject {
+        claims["sub"] = Value::String(subject.to_string());
+    }
+
+    let signing_input = format!(
+        "{}.{}",
+        base64_url::encode(&serde_json::to_vec(&header).map_err(ApiError::core)?),
+        base64_url::encode(&serde_json::to_vec(&claims).map_err(ApiError::core)?)
+    );
+    let vault = identities.vault().credential_vault;
+    let handle = vault.get_secret_key_handle(&public_key).await.map_err(|_| {
+        ApiError::core(format!(
+            "The key of the issuer {issuer} is not available in this vault, the credential can't be signed as a JWT"
+        ))
+    })?;
+    let signature = match vault.sign(&handle, signing_input.as_bytes()).await? {
+        Signature::EdDSACurve25519(signature) => signature.0,
+        // The JWS signature of ES256 is the concatenation of r and s, like the Ockam signature
+        Signature::ECDSASHA256CurveP256(signature) => signature.0,
+    };
+    Ok(format!(
+        "{signing_input}.{}",
+        base64_url::encode(&signature)
+    ))
+}
+
+/// Return a public key in the JWK format of RFC 7517
+pub fn jwk(public_key: &VerifyingPublicKey) -> Value {
+    match public_key {
+        VerifyingPublicKey::EdDSACurve25519(public_key) => json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": base64_url::encode(&public_key.0),
+        }),
+        // The key is stored in the uncompressed form: 0x04 | x | y
+        VerifyingPublicKey::ECDSASHA256CurveP256(public_key) => json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "x": base64_url::encode(&public_key.0[1..33]),
+            "y": base64_url::encode(&public_key.0[33..65]),
+        }),
+    }
+}
+
+fn jwt_algorithm(public_key: &VerifyingPublicKey) -> &'static str {
+    match public_key {
+        VerifyingPublicKey::EdDSACurve25519(_) => "EdDSA",
+        VerifyingPublicKey::ECDSASHA256CurveP256(_) => "ES256",
+    }
+}
+
+/// Return the attributes of a credential as a JSON object.
+/// The attributes typed with [`ATTRIBUTE_TYPES`] are converted to JSON numbers and booleans
+fn attributes_claim(attributes: &BTreeMap<ByteVec, ByteVec>) -> Value {
+    let types = attributes
+        .iter()
+        .find(|(k, _)| k.as_slice() == ATTRIBUTE_TYPES)
+        .map(|(_, v)| parse_attribute_types(&String::from_utf8_lossy(v)))
+        .unwrap_or_default();
+    let mut claim = Map::new();
+    for (key, value) in attributes {
+        let key = String::from_utf8_lossy(key).to_string();
+        if key == ATTRIBUTE_TYPES_UTF8 {
+            continue;
+        }
+        let value = String::from_utf8_lossy(value).to_string();
+        let value = match types.get(&key) {
+            Some(AttributeType::Int) => value.parse::<i64>().map(Value::from).ok(),
+            Some(AttributeType::Bool) => value.parse::<bool>().map(Value::from).ok(),
+            _ => None,
+        }
+        .unwrap_or(Value::String(value));
+        claim.insert(key, value);
+    }
+    Value::Object(claim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attributes_claim() {
+        let mut attributes = BTreeMap::new();
+        for (key, value) in [
+            (b"role".as_slice(), b"admin".as_slice()),
+            (b"level", b"3"),
+            (b"enabled", b"true"),
+            (ATTRIBUTE_TYPES, b"level:int,enabled:bool"),
+        ] {
+            attributes.insert(key.to_vec().into(), value.to_vec().into());
+        }
+        assert_eq!(
+            attributes_claim(&attributes),
+            json!({"role": "admin", "level": 3, "enabled": true})
+        );
+    }
+}
@@ -16,6 +16,7 @@ use ockam_core::Address;
 pub(crate) use outlet_service::prefix_relay::PrefixRelayService;
 pub(crate) use outlet_service::OutletManagerService;
 pub(crate) use portal_listener::KafkaPortalListener;
+pub(crate) use protocol_aware::SaslPlainCredentials;
 pub(crate) use secure_channel_map::ConsumerNodeAddr;
 pub(crate) use secure_channel_map::KafkaSecureChannelControllerImpl;
 
@@ -1,6 +1,6 @@
 use crate::kafka::outlet_controller::KafkaOutletController;
 use crate::kafka::portal_worker::KafkaPortalWorker;
-use crate::kafka::protocol_aware::OutletInterceptorImpl;
+use crate::kafka::protocol_aware::{OutletInterceptorImpl, SaslPlainCredentials};
 use crate::kafka::{KAFKA_OUTLET_BOOTSTRAP_ADDRESS, KAFKA_OUTLET_INTERCEPTOR_ADDRESS};
 use ockam::identity::{SecureChannels, TRUST_CONTEXT_ID_UTF8};
 use ockam::{Any, Context, Result, Routed, Worker};
@@ -20,6 +20,7 @@ pub(crate) struct OutletManagerService {
     flow_control_id: FlowControlId,
     spawner_flow_control_id: FlowControlId,
     outgoing_access_control: Arc<FlowControlOutgoingAccessControl>,
+    sasl: Option<Arc<SaslPlainCredentials>>,
 }
 
 impl OutletManagerService {
@@ -28,6 +29,7 @@ impl OutletManagerService {
         secure_channels: Arc<SecureChannels>,
         trust_context_id: &str,
         default_secure_channel_listener_flow_control_id: FlowControlId,
+        sasl: Option<SaslPlainCredentials>,
     ) -> Result<()> {
         let flow_controls = context.flow_controls();
 
@@ -59,6 +61,7 @@ impl OutletManagerService {
                 Some(spawner_flow_control_id.clone()),
             )),
             spawner_flow_control_id,
+            sasl: sasl.map(Arc::new),
         };
 
         let incoming = worker.incoming_access_control.clone();
@@ -103,6 +106,7 @@ impl Worker for OutletManagerService {
             Arc::new(OutletInterceptorImpl::new(
                 self.outlet_controller.clone(),
                 self.flow_control_id.clone(),
+                self.sasl.clone(),
             )),
             &context.flow_controls().clone(),
             secure_channel_flow_control_id,
@@ -3,7 +3,7 @@ use alloc::sync::Arc;
 use bytes::BytesMut;
 
 use kafka_protocol::messages::request_header::RequestHeader;
-use kafka_protocol::messages::{ApiKey, MetadataResponse, ResponseHeader};
+use kafka_protocol::messages::{ApiKey, MetadataResponse, ResponseHeader, SaslAuthenticateRequest};
 use kafka_protocol::protocol::buf::ByteBuf;
 use kafka_protocol::protocol::Decodable;
 
@@ -22,27 +22,35 @@ use tinyvec::alloc;
 use tracing::warn;
 
 use crate::kafka::portal_worker::InterceptError;
-use crate::kafka::protocol_aware::utils::decode_body;
-use crate::kafka::protocol_aware::{CorrelationId, KafkaMessageInterceptor, RequestInfo};
+use crate::kafka::protocol_aware::utils::{decode_body, encode_request};
+use crate::kafka::protocol_aware::{
+    CorrelationId, KafkaMessageInterceptor, RequestInfo, SaslPlainCredentials,
+};
 
 /// Intercepts responses of type `Metadata` to extract the list of brokers
-/// then creates an outlet for each of them through [`KafkaOutletController`]
+/// then creates an outlet for each of them through [`KafkaOutletController`].
+///
+/// When SASL/PLAIN credentials are configured, they replace the credentials of the
+/// `SaslAuthenticate` requests sent by the clients
 #[derive(Clone)]
 pub(crate) struct OutletInterceptorImpl {
     request_map: Arc<Mutex<HashMap<CorrelationId, RequestInfo>>>,
     outlet_controller: KafkaOutletController,
     flow_control_id: FlowControlId,
+    sasl: Option<Arc<SaslPlainCredentials>>,
 }
 
 impl OutletInterceptorImpl {
     pub(crate) fn new(
         outlet_controller: KafkaOutletController,
         flow_control_id: FlowControlId,
+        sasl: Option<Arc<SaslPlainCredentials>>,
     ) -> Self {
         Self {
             request_map: Arc::new(Mutex::new(HashMap::new())),
             outlet_controller,
             flow_control_id,
+            sasl,
         }
     }
 }
@@ -94,6 +102,20 @@ impl KafkaMessageInterceptor for OutletInterceptorImpl {
             api_key
         );
 
+        if api_key == ApiKey::SaslAuthenticateKey {
+            if let Some(sasl) = &self.sasl {
+                let mut request: SaslAuthenticateRequest =
+                    decode_body(&mut buffer, header.request_api_version)?;
+                request.auth_bytes = sasl.auth_bytes();
+                return encode_request(
+                    &header,
+                    &request,
+                    header.request_api_version,
+                    ApiKey::SaslAuthenticateKey,
+                );
+            }
+        }
+
         if api_key == ApiKey::MetadataKey {
             self.request_map.lock().unwrap().insert(
                 header.correlation_id,
@@ -15,10 +15,12 @@ use ockam_node::Context;
 mod metadata_interceptor;
 mod request;
 mod response;
+mod sasl;
 mod tests;
 
 pub(super) mod utils;
 pub(crate) use metadata_interceptor::OutletInterceptorImpl;
+pub(crate) use sasl::SaslPlainCredentials;
 
 #[derive(Clone, Debug)]
 struct RequestInfo {
@@ -0,0 +1,28 @@
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::nodes::secrets::Secret;
+
+/// SASL/PLAIN credentials used by a Kafka outlet to authenticate to the brokers,
+/// in place of the credentials sent by the Kafka clients
+pub(crate) struct SaslPlainCredentials {
+    username: Secret,
+    password: Secret,
+}
+
+impl SaslPlainCredentials {
+    pub(crate) fn new(username: Secret, password: Secret) -> Self {
+        Self { username, password }
+    }
+
+    /// Authentication bytes of the PLAIN mechanism (RFC 4616), without authorization identity
+    pub(crate) fn auth_bytes(&self) -> Bytes {
+        let username = self.username.expose().as_bytes();
+        let password = self.password.expose().as_bytes();
+        let mut bytes = BytesMut::with_capacity(username.len() + password.len() + 2);
+        bytes.put_u8(0);
+        bytes.put_slice(username);
+        bytes.put_u8(0);
+        bytes.put_slice(password);
+        bytes.freeze()
+    }
+}
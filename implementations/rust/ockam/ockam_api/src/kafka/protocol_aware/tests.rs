@@ -1,17 +1,23 @@
 #[cfg(test)]
 mod test {
     use crate::kafka::inlet_controller::KafkaInletController;
+    use crate::kafka::outlet_controller::KafkaOutletController;
     use crate::kafka::protocol_aware::utils::{encode_request, encode_response};
     use crate::kafka::protocol_aware::InletInterceptorImpl;
     use crate::kafka::protocol_aware::KafkaMessageInterceptor;
+    use crate::kafka::protocol_aware::{OutletInterceptorImpl, SaslPlainCredentials};
     use crate::kafka::secure_channel_map::{KafkaEncryptedContent, KafkaSecureChannelController};
+    use crate::nodes::secrets::Secret;
     use crate::port_range::PortRange;
+    use bytes::Bytes;
     use kafka_protocol::messages::ApiKey;
     use kafka_protocol::messages::BrokerId;
+    use kafka_protocol::messages::SaslAuthenticateRequest;
     use kafka_protocol::messages::{ApiVersionsRequest, MetadataRequest, MetadataResponse};
     use kafka_protocol::messages::{ApiVersionsResponse, RequestHeader, ResponseHeader};
-    use kafka_protocol::protocol::{Builder, StrBytes};
+    use kafka_protocol::protocol::{Builder, Decodable, StrBytes};
     use ockam_core::compat::sync::Arc;
+    use ockam_core::flow_control::FlowControls;
     use ockam_core::route;
     use ockam_core::{async_trait, Address};
     use ockam_multiaddr::MultiAddr;
@@ -204,4 +210,55 @@ mod test {
 
         context.stop().await
     }
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test(timeout = 5_000)]
+    async fn outlet_interceptor__sasl_authenticate_request__credentials_replaced(
+        context: &mut Context,
+    ) -> ockam::Result<()> {
+        let interceptor = OutletInterceptorImpl::new(
+            KafkaOutletController::new(),
+            FlowControls::generate_flow_control_id(),
+            Some(Arc::new(SaslPlainCredentials::new(
+                Secret::new("ockam"),
+                Secret::new("p@ssword"),
+            ))),
+        );
+
+        let api_version = 2;
+        let request = encode_request(
+            &RequestHeader::builder()
+                .request_api_version(api_version)
+                .correlation_id(1)
+                .request_api_key(ApiKey::SaslAuthenticateKey as i16)
+                .unknown_tagged_fields(Default::default())
+                .client_id(None)
+                .build()
+                .unwrap(),
+            &SaslAuthenticateRequest::builder()
+                .auth_bytes(Bytes::from_static(b"\0client\0client-password"))
+                .unknown_tagged_fields(Default::default())
+                .build()
+                .unwrap(),
+            api_version,
+            ApiKey::SaslAuthenticateKey,
+        )
+        .unwrap();
+
+        let mut intercepted = interceptor
+            .intercept_request(context, request)
+            .await
+            .unwrap()
+            .freeze();
+        let header = RequestHeader::decode(
+            &mut intercepted,
+            ApiKey::SaslAuthenticateKey.request_header_version(api_version),
+        )
+        .unwrap();
+        assert_eq!(header.correlation_id, 1);
+        let request = SaslAuthenticateRequest::decode(&mut intercepted, api_version).unwrap();
+        assert_eq!(request.auth_bytes.as_ref(), b"\0ockam\0p@ssword");
+
+        context.stop().await
+    }
 }
@@ -124,22 +124,27 @@
 //! channels to sign or encrypt data involved in the handshake.
 //!
 pub mod address;
+pub mod allocator;
 pub mod auth;
 pub mod authenticator;
 pub mod bootstrapped_identities_store;
+pub mod chat;
 pub mod cli_state;
 pub mod cloud;
 pub mod config;
 pub mod echoer;
 pub mod enroll;
 pub mod error;
+pub mod exec;
 pub mod hop;
 pub mod identity;
+#[cfg(feature = "kafka")]
 pub mod kafka;
 pub mod minicbor_url;
 pub mod nodes;
 pub mod okta;
 pub mod port_range;
+pub mod provisioner;
 pub mod trust_context;
 pub mod uppercase;
 
@@ -151,6 +156,7 @@ mod schema;
 mod session;
 mod util;
 
+pub use cli_state::{CliState, CliStateError};
 pub use influxdb_token_lease::*;
 pub use util::*;
 
@@ -164,11 +170,18 @@ impl DefaultAddress {
     pub const RELAY_SERVICE: &'static str = "forwarding_service";
     pub const UPPERCASE_SERVICE: &'static str = "uppercase";
     pub const ECHO_SERVICE: &'static str = "echo";
+    pub const CHAT_SERVICE: &'static str = "chat";
     pub const HOP_SERVICE: &'static str = "hop";
+    pub const RENDEZVOUS_SERVICE: &'static str = "rendezvous";
+    pub const EXEC_SERVICE: &'static str = "exec";
+    pub const PROVISIONER_SERVICE: &'static str = "provisioner";
     pub const CREDENTIALS_SERVICE: &'static str = "credentials";
     pub const SECURE_CHANNEL_LISTENER: &'static str = "api";
     pub const DIRECT_AUTHENTICATOR: &'static str = "direct_authenticator";
     pub const CREDENTIAL_ISSUER: &'static str = "credential_issuer";
+    pub const CREDENTIAL_REVOKER: &'static str = "credential_revoker";
+    pub const CREDENTIAL_CO_SIGNER: &'static str = "credential_co_signer";
+    pub const REVOCATION_LIST: &'static str = "revocation_list";
     pub const ENROLLMENT_TOKEN_ISSUER: &'static str = "enrollment_token_issuer";
     pub const ENROLLMENT_TOKEN_ACCEPTOR: &'static str = "enrollment_token_acceptor";
     pub const OKTA_IDENTITY_PROVIDER: &'static str = "okta";
@@ -184,11 +197,18 @@ impl DefaultAddress {
                 | Self::RELAY_SERVICE
                 | Self::UPPERCASE_SERVICE
                 | Self::ECHO_SERVICE
+                | Self::CHAT_SERVICE
                 | Self::HOP_SERVICE
+                | Self::RENDEZVOUS_SERVICE
+                | Self::EXEC_SERVICE
+                | Self::PROVISIONER_SERVICE
                 | Self::CREDENTIALS_SERVICE
                 | Self::SECURE_CHANNEL_LISTENER
                 | Self::DIRECT_AUTHENTICATOR
                 | Self::CREDENTIAL_ISSUER
+                | Self::CREDENTIAL_REVOKER
+                | Self::CREDENTIAL_CO_SIGNER
+                | Self::REVOCATION_LIST
                 | Self::ENROLLMENT_TOKEN_ISSUER
                 | Self::ENROLLMENT_TOKEN_ACCEPTOR
                 | Self::OKTA_IDENTITY_PROVIDER
@@ -205,11 +225,18 @@ impl DefaultAddress {
             Self::RELAY_SERVICE,
             Self::UPPERCASE_SERVICE,
             Self::ECHO_SERVICE,
+            Self::CHAT_SERVICE,
             Self::HOP_SERVICE,
+            Self::RENDEZVOUS_SERVICE,
+            Self::EXEC_SERVICE,
+            Self::PROVISIONER_SERVICE,
             Self::CREDENTIALS_SERVICE,
             Self::SECURE_CHANNEL_LISTENER,
             Self::DIRECT_AUTHENTICATOR,
             Self::CREDENTIAL_ISSUER,
+            Self::CREDENTIAL_REVOKER,
+            Self::CREDENTIAL_CO_SIGNER,
+            Self::REVOCATION_LIST,
             Self::ENROLLMENT_TOKEN_ISSUER,
             Self::ENROLLMENT_TOKEN_ACCEPTOR,
             Self::OKTA_IDENTITY_PROVIDER,
@@ -305,6 +332,11 @@ mod test {
         assert!(DefaultAddress::is_valid(DefaultAddress::UPPERCASE_SERVICE));
         assert!(DefaultAddress::is_valid(DefaultAddress::ECHO_SERVICE));
         assert!(DefaultAddress::is_valid(DefaultAddress::HOP_SERVICE));
+        assert!(DefaultAddress::is_valid(DefaultAddress::RENDEZVOUS_SERVICE));
+        assert!(DefaultAddress::is_valid(DefaultAddress::EXEC_SERVICE));
+        assert!(DefaultAddress::is_valid(
+            DefaultAddress::PROVISIONER_SERVICE
+        ));
         assert!(DefaultAddress::is_valid(
             DefaultAddress::CREDENTIALS_SERVICE
         ));
@@ -315,6 +347,11 @@ mod test {
             DefaultAddress::DIRECT_AUTHENTICATOR
         ));
         assert!(DefaultAddress::is_valid(DefaultAddress::CREDENTIAL_ISSUER));
+        assert!(DefaultAddress::is_valid(DefaultAddress::CREDENTIAL_REVOKER));
+        assert!(DefaultAddress::is_valid(
+            DefaultAddress::CREDENTIAL_CO_SIGNER
+        ));
+        assert!(DefaultAddress::is_valid(DefaultAddress::REVOCATION_LIST));
         assert!(DefaultAddress::is_valid(
             DefaultAddress::ENROLLMENT_TOKEN_ISSUER
         ));
@@ -15,6 +15,11 @@ pub fn log_max_files() -> usize {
     get_env_with_default("OCKAM_LOG_MAX_FILES", default).unwrap_or(default) as usize
 }
 
+pub fn otlp_logs_endpoint() -> String {
+    let default = "http://localhost:4318/v1/logs".to_string();
+    get_env_with_default("OCKAM_OTLP_LOGS_ENDPOINT", default.clone()).unwrap_or(default)
+}
+
 pub fn log_format() -> LogFormat {
     let default = LogFormat::Default;
     get_env_with_default("OCKAM_LOG_FORMAT", default.clone()).unwrap_or(default)
@@ -1,4 +1,6 @@
 use ockam_core::env::FromString;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 pub mod env;
 #[allow(unused, clippy::enum_variant_names)]
@@ -21,6 +23,43 @@ impl FromString for LogFormat {
     }
 }
 
+/// Destination of the logs of a node
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogSink {
+    /// Rotating files in the directory of the node
+    File,
+    /// The systemd journal. Only available on Linux
+    Journald,
+    /// An OpenTelemetry collector, receiving the logs with the OTLP protocol
+    Otlp,
+}
+
+impl FromStr for LogSink {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "file" => Ok(LogSink::File),
+            "journald" => Ok(LogSink::Journald),
+            "otlp" => Ok(LogSink::Otlp),
+            _ => Err(format!(
+                "unknown log sink {s}, expected one of: file, journald, otlp"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for LogSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LogSink::File => write!(f, "file"),
+            LogSink::Journald => write!(f, "journald"),
+            LogSink::Otlp => write!(f, "otlp"),
+        }
+    }
+}
+
 impl std::fmt::Display for LogFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
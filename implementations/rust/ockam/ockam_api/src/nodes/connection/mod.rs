@@ -1,4 +1,5 @@
 mod plain_tcp;
+mod probe;
 mod project;
 mod secure;
 
@@ -13,8 +14,10 @@ use ockam_transport_tcp::{TcpConnection, TcpTransport};
 
 use crate::error::ApiError;
 use crate::nodes::NodeManager;
+use crate::util::{ble_address, router_address};
 use crate::{multiaddr_to_route, DefaultAddress};
 pub(crate) use plain_tcp::PlainTcpInstantiator;
+pub use probe::{RouteSelection, RttEstimate};
 pub(crate) use project::ProjectInstantiator;
 pub(crate) use secure::SecureChannelInstantiator;
 use std::fmt::{Debug, Formatter};
@@ -254,6 +257,17 @@ impl ConnectionBuilder {
         let mut route = Route::new();
         let mut peekable = current_before.iter().peekable();
         while let Some(protocol) = peekable.next() {
+            // a UDP, QUIC or WebSocket peer is reached via its transport router, without a connection worker
+            if let Some(address) = router_address(&protocol, peekable.peek()) {
+                route = route.append(address);
+                let _ = peekable.next();
+                continue;
+            }
+            // a BLE peripheral is reached via the BLE transport router
+            if let Some(address) = ble_address(&protocol) {
+                route = route.append(address);
+                continue;
+            }
             if protocol.code() == Service::CODE {
                 if let Some(service) = protocol.cast::<Service>() {
                     let address = Address::new(LOCAL, &*service);
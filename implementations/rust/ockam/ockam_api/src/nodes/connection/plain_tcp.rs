@@ -5,7 +5,7 @@ use std::sync::Arc;
 
 use crate::nodes::NodeManager;
 use ockam_core::{async_trait, Error, Route};
-use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Tcp};
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Tcp, Tls};
 use ockam_multiaddr::{Match, MultiAddr, Protocol};
 use ockam_node::Context;
 
@@ -22,9 +22,9 @@ impl PlainTcpInstantiator {
 impl Instantiator for PlainTcpInstantiator {
     fn matches(&self) -> Vec<Match> {
         vec![
-            // matches any tcp address followed by a tcp protocol
+            // matches any tcp address followed by a tcp or tls protocol
             Match::any([DnsAddr::CODE, Ip4::CODE, Ip6::CODE]),
-            Tcp::CODE.into(),
+            Match::any([Tcp::CODE, Tls::CODE]),
         ]
     }
 
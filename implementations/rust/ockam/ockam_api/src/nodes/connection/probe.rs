@@ -0,0 +1,244 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ockam::identity::Identifier;
+use ockam_core::{route, Result, Route};
+use ockam_multiaddr::MultiAddr;
+use ockam_node::{Context, MessageSendReceiveOptions};
+
+use crate::error::ApiError;
+use crate::nodes::connection::Connection;
+use crate::nodes::NodeManager;
+use crate::DefaultAddress;
+
+/// Number of pings sent on each candidate route when selecting a route
+const PROBES_COUNT: usize = 3;
+
+/// Time after which a ping is considered lost
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The selected route is only replaced by a route which is faster by this ratio,
+/// so that routes with similar latencies don't alternate
+const SWITCH_RATIO: f64 = 0.8;
+
+/// Rolling estimate of the round-trip time of a route, smoothed as in TCP (RFC 6298)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RttEstimate {
+    srtt: Duration,
+    rttvar: Duration,
+}
+
+impl RttEstimate {
+    pub fn new(sample: Duration) -> Self {
+        Self {
+            srtt: sample,
+            rttvar: sample / 2,
+        }
+    }
+
+    /// Add a new sample to the estimate
+    pub fn update(&mut self, sample: Duration) {
+        let delta = if sample > self.srtt {
+            sample - self.srtt
+        } else {
+            self.srtt - sample
+        };
+        self.rttvar = (self.rttvar * 3 + delta) / 4;
+        self.srtt = (self.srtt * 7 + sample) / 8;
+    }
+
+    /// Smoothed round-trip time
+    pub fn srtt(&self) -> Duration {
+        self.srtt
+    }
+
+    /// Variation of the round-trip time
+    pub fn rttvar(&self) -> Duration {
+        self.rttvar
+    }
+}
+
+/// Candidate addresses of the same destination, for example via a relay, directly,
+/// or via another project, with the round-trip time estimated for each of them
+#[derive(Debug, Clone)]
+pub struct RouteSelection {
+    candidates: Vec<(MultiAddr, Option<RttEstimate>)>,
+    selected: Option<usize>,
+}
+
+impl RouteSelection {
+    pub fn new(addresses: Vec<MultiAddr>) -> Self {
+        Self {
+            candidates: addresses.into_iter().map(|a| (a, None)).collect(),
+            selected: None,
+        }
+    }
+
+    pub fn addresses(&self) -> Vec<MultiAddr> {
+        self.candidates.iter().map(|(a, _)| a.clone()).collect()
+    }
+
+    pub fn estimate(&self, index: usize) -> Option<RttEstimate> {
+        self.candidates.get(index).and_then(|(_, e)| *e)
+    }
+
+    /// Record the round-trip time of a ping sent on a candidate route
+    pub fn record_rtt(&mut self, index: usize, rtt: Duration) {
+        if let Some((_, estimate)) = self.candidates.get_mut(index) {
+            match estimate {
+                Some(estimate) => estimate.update(rtt),
+                None => *estimate = Some(RttEstimate::new(rtt)),
+            }
+        }
+    }
+
+    /// Forget the estimate of a candidate route which could not be reached
+    pub fn record_failure(&mut self, index: usize) {
+        if let Some((_, estimate)) = self.candidates.get_mut(index) {
+            *estimate = None;
+        }
+    }
+
+    /// Select the reachable candidate with the lowest round-trip time.
+    /// The current selection is kept unless another candidate is significantly faster
+    pub fn select(&mut self) -> Option<usize> {
+        let fastest = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, e))| e.map(|e| (i, e.srtt())))
+            .min_by_key(|(_, srtt)| *srtt);
+        let current = self
+            .selected
+            .and_then(|i| self.estimate(i).map(|e| (i, e.srtt())));
+        self.selected = match (current, fastest) {
+            (Some((current, current_srtt)), Some((_, fastest_srtt)))
+                if fastest_srtt.as_secs_f64() > current_srtt.as_secs_f64() * SWITCH_RATIO =>
+            {
+                Some(current)
+            }
+            (_, fastest) => fastest.map(|(i, _)| i),
+        };
+        self.selected
+    }
+
+    pub fn selected(&self) -> Option<&MultiAddr> {
+        self.selected
+            .and_then(|i| self.candidates.get(i))
+            .map(|(a, _)| a)
+    }
+}
+
+impl NodeManager {
+    /// Connect to each candidate address of a [`RouteSelection`], probe the connections with
+    /// pings to their echo service and keep the connection with the lowest round-trip time.
+    /// The other connections are closed
+    pub(crate) async fn make_fastest_connection(
+        &self,
+        ctx: Arc<Context>,
+        selection: &mut RouteSelection,
+        authorized: Option<Identifier>,
+        timeout: Option<Duration>,
+    ) -> Result<(MultiAddr, Connection)> {
+        let mut connections = vec![];
+        for (index, addr) in selection.addresses().into_iter().enumerate() {
+            match self
+                .make_connection(ctx.clone(), &addr, None, authorized.clone(), None, timeout)
+                .await
+            {
+                Ok(connection) => {
+                    for _ in 0..PROBES_COUNT {
+                        match probe(&ctx, connection.transport_route()).await {
+                            Ok(rtt) => selection.record_rtt(index, rtt),
+                            Err(e) => {
+                                debug!(%addr, %e, "the route could not be probed");
+                                selection.record_failure(index);
+                                break;
+                            }
+                        }
+                    }
+                    connections.push((index, addr, connection));
+                }
+                Err(e) => {
+                    debug!(%addr, %e, "the route could not be connected");
+                    selection.record_failure(index);
+                }
+            }
+        }
+
+        let selected = selection.select();
+        let mut fastest = None;
+        for (index, addr, connection) in connections {
+            if Some(index) == selected {
+                info!(
+                    %addr,
+                    rtt = ?selection.estimate(index).map(|e| e.srtt()),
+                    "selected the route with the lowest latency"
+                );
+                fastest = Some((addr, connection));
+            } else {
+                self.close_connection(&ctx, &connection).await;
+            }
+        }
+        fastest.ok_or_else(|| ApiError::core("None of the routes could be reached"))
+    }
+
+    /// Delete the secure channels and the TCP connection created for a connection
+    pub(crate) async fn close_connection(&self, ctx: &Context, connection: &Connection) {
+        for encryptor in &connection.secure_channel_encryptors {
+            if let Err(error) = self.delete_secure_channel(ctx, encryptor).await {
+                debug!("cannot delete secure channel `{encryptor}`: {error}");
+            }
+        }
+        if let Some(tcp_connection) = connection.tcp_connection.as_ref() {
+            if let Err(error) = self
+                .tcp_transport
+                .disconnect(tcp_connection.sender_address().clone())
+                .await
+            {
+                debug!("cannot stop tcp worker `{tcp_connection}`: {error}");
+            }
+        }
+    }
+}
+
+/// Return the round-trip time of a small ping sent to the echo service at the end of a route
+async fn probe(ctx: &Context, transport_route: Route) -> Result<Duration> {
+    let start = Instant::now();
+    ctx.send_and_receive_extended::<Vec<u8>>(
+        route![transport_route, DefaultAddress::ECHO_SERVICE],
+        vec![0u8; 8],
+        MessageSendReceiveOptions::new().with_timeout(PROBE_TIMEOUT),
+    )
+    .await?;
+    Ok(start.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_route_selection() {
+        let relay = MultiAddr::from_str("/project/p/service/forward_to_n/secure/api").unwrap();
+        let direct = MultiAddr::from_str("/dnsaddr/n/tcp/4000/secure/api").unwrap();
+        let mut selection = RouteSelection::new(vec![relay.clone(), direct.clone()]);
+        assert_eq!(selection.select(), None);
+
+        selection.record_rtt(0, Duration::from_millis(100));
+        selection.record_rtt(1, Duration::from_millis(90));
+        assert_eq!(selection.select(), Some(1));
+
+        // a slightly faster route doesn't replace the selected one
+        selection.record_rtt(0, Duration::from_millis(20));
+        assert_eq!(selection.selected(), Some(&direct));
+        selection.record_rtt(1, Duration::from_millis(110));
+        assert_eq!(selection.select(), Some(1));
+
+        // an unreachable route is replaced
+        selection.record_failure(1);
+        assert_eq!(selection.select(), Some(0));
+        assert_eq!(selection.selected(), Some(&relay));
+    }
+}
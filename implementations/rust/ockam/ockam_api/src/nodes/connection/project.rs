@@ -10,7 +10,7 @@ use ockam_multiaddr::{Match, MultiAddr, Protocol};
 use ockam_node::Context;
 
 use ockam::identity::models::CredentialAndPurposeKey;
-use ockam::identity::Identifier;
+use ockam::identity::{HandshakeRetryPolicy, Identifier};
 use std::time::Duration;
 
 /// Creates a secure connection to the project using provided credential
@@ -78,6 +78,9 @@ impl Instantiator for ProjectInstantiator {
                 Some(vec![project_identifier]),
                 self.timeout,
                 self.credential.clone(),
+                None,
+                false,
+                HandshakeRetryPolicy::default(),
             )
             .await?;
 
@@ -6,7 +6,7 @@ use crate::nodes::NodeManager;
 use crate::{local_multiaddr_to_route, try_address_to_multiaddr};
 
 use ockam::identity::models::CredentialAndPurposeKey;
-use ockam::identity::Identifier;
+use ockam::identity::{HandshakeRetryPolicy, Identifier};
 use ockam_core::{async_trait, route, AsyncTryClone, Error, Route};
 use ockam_multiaddr::proto::Secure;
 use ockam_multiaddr::{Match, MultiAddr, Protocol};
@@ -64,6 +64,9 @@ impl Instantiator for SecureChannelInstantiator {
                 self.authorized_identities.clone(),
                 self.timeout,
                 self.credential.clone(),
+                None,
+                false,
+                HandshakeRetryPolicy::default(),
             )
             .await?;
 
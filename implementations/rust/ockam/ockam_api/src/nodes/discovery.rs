@@ -0,0 +1,215 @@
+//! Discovery of the nodes advertising themselves on the local network.
+//!
+//! A node created with `ockam node create --advertise` answers the queries sent to the multicast
+//! group [`DISCOVERY_GROUP`], on the port [`DISCOVERY_PORT`], with its name, its identifier, the
+//! port of its TCP listener and the addresses of its services. The queries are only sent on the
+//! local network, since multicast datagrams are not forwarded by routers.
+//!
+//! Advertisements are not authenticated. The identifier advertised by a node is only verified
+//! when a secure channel is created to it, and it is then pinned in the trust pins.
+
+use core::str::FromStr;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::UdpSocket;
+
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_multiaddr::MultiAddr;
+
+use crate::error::ApiError;
+use crate::nodes::{NodeManager, NodeManagerWorker};
+
+/// Multicast group receiving the discovery queries
+pub const DISCOVERY_GROUP: Ipv4Addr = Ipv4Addr::new(239, 255, 79, 77);
+
+/// UDP port receiving the discovery queries
+pub const DISCOVERY_PORT: u16 = 6252;
+
+const MAX_DATAGRAM_SIZE: usize = 8192;
+
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+enum DiscoveryMessage {
+    #[n(0)] Query,
+    #[n(1)] Advertisement(#[n(0)] Advertisement),
+}
+
+/// Description of a node, sent in response to a discovery query
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Advertisement {
+    #[n(1)] pub name: String,
+    #[n(2)] pub identifier: String,
+    /// Port of the TCP listener of the node
+    #[n(3)] pub tcp_port: u16,
+    /// Addresses of the services of the node
+    #[n(4)] pub services: Vec<String>,
+}
+
+/// Node which answered a discovery query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredNode {
+    /// Address from which the node answered
+    pub ip: IpAddr,
+    pub advertisement: Advertisement,
+}
+
+impl DiscoveredNode {
+    /// Address of the TCP listener of the node, like `/ip4/192.168.1.12/tcp/4000`
+    pub fn address(&self) -> Result<MultiAddr> {
+        let address = match self.ip {
+            IpAddr::V4(ip) => format!("/ip4/{ip}/tcp/{}", self.advertisement.tcp_port),
+            IpAddr::V6(ip) => format!("/ip6/{ip}/tcp/{}", self.advertisement.tcp_port),
+        };
+        MultiAddr::from_str(&address)
+            .map_err(|e| ApiError::core(format!("Invalid address {address}: {e}")))
+    }
+}
+
+/// Send a discovery query to the local network, and return the nodes which answered it
+/// within `timeout`
+pub async fn discover(timeout: Duration) -> Result<Vec<DiscoveredNode>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .await
+        .map_err(io_error)?;
+    socket
+        .send_to(
+            &minicbor::to_vec(DiscoveryMessage::Query)?,
+            (DISCOVERY_GROUP, DISCOVERY_PORT),
+        )
+        .await
+        .map_err(io_error)?;
+
+    let mut nodes: Vec<DiscoveredNode> = vec![];
+    let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+    let deadline = tokio::time::Instant::now() + timeout;
+    while let Ok(received) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buffer)).await
+    {
+        let (length, peer) = received.map_err(io_error)?;
+        if let Ok(DiscoveryMessage::Advertisement(advertisement)) =
+            minicbor::decode(&buffer[..length])
+        {
+            let node = DiscoveredNode {
+                ip: peer.ip(),
+                advertisement,
+            };
+            if !nodes.contains(&node) {
+                nodes.push(node);
+            }
+        }
+    }
+    Ok(nodes)
+}
+
+impl NodeManager {
+    /// Answer the discovery queries of the local network until the node is stopped.
+    ///
+    /// `tcp_port` is the port of the TCP listener advertised to the other nodes
+    pub fn start_advertising(self: &Arc<Self>, tcp_port: u16) -> Result<()> {
+        let socket = responder_socket().map_err(io_error)?;
+        let node_manager = Arc::downgrade(self);
+        tokio::spawn(answer_queries(socket, node_manager, tcp_port));
+        info!(group = %DISCOVERY_GROUP, port = DISCOVERY_PORT, "advertising the node on the local network");
+        Ok(())
+    }
+
+    async fn advertisement(&self, tcp_port: u16) -> Advertisement {
+        let services = NodeManagerWorker::list_services_impl(&self.registry)
+            .await
+            .into_iter()
+            .map(|s| s.addr)
+            .collect();
+        Advertisement {
+            name: self.node_name(),
+            identifier: self.identifier().to_string(),
+            tcp_port,
+            services,
+        }
+    }
+}
+
+async fn answer_queries(socket: UdpSocket, node_manager: Weak<NodeManager>, tcp_port: u16) {
+    let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let (length, peer) = match socket.recv_from(&mut buffer).await {
+            Ok(received) => received,
+            Err(e) => {
+                warn!(%e, "the node stopped answering the discovery queries");
+                return;
+            }
+        };
+        if !matches!(
+            minicbor::decode(&buffer[..length]),
+            Ok(DiscoveryMessage::Query)
+        ) {
+            continue;
+        }
+        let advertisement = match node_manager.upgrade() {
+            Some(node_manager) => node_manager.advertisement(tcp_port).await,
+            None => return,
+        };
+        debug!(%peer, "answering a discovery query");
+        match minicbor::to_vec(DiscoveryMessage::Advertisement(advertisement)) {
+            Ok(message) => {
+                if let Err(e) = socket.send_to(&message, peer).await {
+                    debug!(%peer, %e, "the discovery query could not be answered");
+                }
+            }
+            Err(e) => warn!(%e, "the advertisement could not be encoded"),
+        }
+    }
+}
+
+/// Create a socket receiving the discovery queries. It can be shared by all the nodes
+/// of a machine
+fn responder_socket() -> std::io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT)).into())?;
+    socket.join_multicast_v4(&DISCOVERY_GROUP, &Ipv4Addr::UNSPECIFIED)?;
+    socket.set_nonblocking(true)?;
+    UdpSocket::from_std(socket.into())
+}
+
+fn io_error(e: std::io::Error) -> Error {
+    Error::new(Origin::Api, Kind::Io, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discovered_node_addresses() {
+        let advertisement = Advertisement {
+            name: "n1".to_string(),
+            identifier: "I0000000000000000000000000000000000000000".to_string(),
+            tcp_port: 4000,
+            services: vec!["api".to_string(), "echo".to_string()],
+        };
+        let message =
+            minicbor::to_vec(DiscoveryMessage::Advertisement(advertisement.clone())).unwrap();
+        match minicbor::decode(&message).unwrap() {
+            DiscoveryMessage::Advertisement(decoded) => assert_eq!(decoded, advertisement),
+            DiscoveryMessage::Query => panic!("an advertisement was expected"),
+        }
+
+        let node = DiscoveredNode {
+            ip: "192.168.1.12".parse().unwrap(),
+            advertisement,
+        };
+        assert_eq!(
+            node.address().unwrap().to_string(),
+            "/ip4/192.168.1.12/tcp/4000"
+        );
+    }
+}
@@ -1,7 +1,10 @@
 pub mod config;
 pub(crate) mod connection;
+pub mod discovery;
 pub mod models;
+pub mod pairing;
 pub mod registry;
+pub mod secrets;
 pub mod service;
 pub use service::background_node::*;
 pub use service::in_memory_node::*;
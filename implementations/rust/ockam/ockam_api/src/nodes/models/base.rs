@@ -13,6 +13,8 @@ pub struct NodeStatus {
     #[n(2)] pub status: String,
     #[n(3)] pub workers: u32,
     #[n(4)] pub pid: i32,
+    #[n(5)] pub identifier: Option<String>,
+    #[n(6)] pub version: Option<String>,
 }
 
 impl NodeStatus {
@@ -27,6 +29,27 @@ impl NodeStatus {
             status: status.into(),
             workers,
             pid,
+            identifier: None,
+            version: None,
         }
     }
+
+    pub fn with_identifier(mut self, identifier: impl Into<String>) -> Self {
+        self.identifier = Some(identifier.into());
+        self
+    }
+
+    pub fn with_version(mut self, version: Option<String>) -> Self {
+        self.version = version;
+        self
+    }
+}
+
+/// Response body for a node reload
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeReloaded {
+    #[n(1)] pub trust_context_id: String,
+    #[n(2)] pub authority_identifier: Option<String>,
 }
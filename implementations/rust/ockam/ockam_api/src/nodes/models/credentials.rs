@@ -25,6 +25,21 @@ impl GetCredentialRequest {
     }
 }
 
+/// Request to retrieve a new credential for the node, before the current one expires
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RefreshCredentialRequest {
+    /// If true, the node keeps refreshing its credential in the background
+    #[n(1)] pub auto: bool,
+}
+
+impl RefreshCredentialRequest {
+    pub fn new(auto: bool) -> Self {
+        Self { auto }
+    }
+}
+
 #[derive(Clone, Debug, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -7,8 +7,10 @@ pub mod credentials;
 pub mod flow_controls;
 pub mod policy;
 pub mod portal;
+pub mod quota;
 pub mod relay;
 pub mod secure_channel;
 pub mod services;
+pub mod stats;
 pub mod transport;
 pub mod workers;
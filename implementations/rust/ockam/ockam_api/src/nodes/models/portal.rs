@@ -1,6 +1,7 @@
 //! Inlets and outlet request/response types
 
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str::FromStr;
 use std::time::Duration;
 
 use minicbor::{Decode, Encode};
@@ -8,9 +9,12 @@ use ockam::identity::Identifier;
 use ockam::route;
 use ockam_core::{Address, Route};
 use ockam_multiaddr::MultiAddr;
+use ockam_transport_tcp::{CanaryStickiness, OutletTls, PortalDataFlow, PortalProtocol};
 use serde::{Deserialize, Serialize};
 
 use crate::error::ApiError;
+use crate::nodes::models::quota::SessionQuota;
+use crate::nodes::secrets::SecretStore;
 use crate::route_to_multiaddr;
 
 /// Request body to create an inlet
@@ -38,6 +42,15 @@ pub struct CreateInlet {
     #[n(6)] pub(crate) suffix_route: Route,
     /// The maximum duration to wait for an outlet to be available
     #[n(7)] pub(crate) wait_for_outlet_duration: Option<Duration>,
+    /// The direction in which data can flow through the inlet
+    #[n(8)] pub(crate) data_flow: DataFlow,
+    /// The quota of each connection accepted by the inlet
+    #[n(9)] pub(crate) quota: SessionQuota,
+    /// The protocol expected at the beginning of each connection accepted by the inlet
+    #[n(10)] pub(crate) expected_protocol: Option<InletProtocol>,
+    /// Other addresses of the same outlet, for example directly instead of via a relay.
+    /// The address with the lowest latency is used
+    #[n(11)] pub(crate) alternate_outlet_addrs: Option<Vec<MultiAddr>>,
 }
 
 impl CreateInlet {
@@ -55,6 +68,10 @@ impl CreateInlet {
             prefix_route,
             suffix_route,
             wait_for_outlet_duration: None,
+            data_flow: DataFlow::default(),
+            quota: SessionQuota::default(),
+            expected_protocol: None,
+            alternate_outlet_addrs: None,
         }
     }
 
@@ -73,6 +90,10 @@ impl CreateInlet {
             prefix_route,
             suffix_route,
             wait_for_outlet_duration: None,
+            data_flow: DataFlow::default(),
+            quota: SessionQuota::default(),
+            expected_protocol: None,
+            alternate_outlet_addrs: None,
         }
     }
 
@@ -84,6 +105,22 @@ impl CreateInlet {
         self.wait_for_outlet_duration = Some(Duration::from_millis(ms))
     }
 
+    pub fn set_data_flow(&mut self, data_flow: DataFlow) {
+        self.data_flow = data_flow
+    }
+
+    pub fn set_quota(&mut self, quota: SessionQuota) {
+        self.quota = quota
+    }
+
+    pub fn set_expected_protocol(&mut self, expected_protocol: Option<InletProtocol>) {
+        self.expected_protocol = expected_protocol
+    }
+
+    pub fn set_alternate_outlet_addrs(&mut self, alternate_outlet_addrs: Vec<MultiAddr>) {
+        self.alternate_outlet_addrs = Some(alternate_outlet_addrs)
+    }
+
     pub fn listen_addr(&self) -> String {
         self.listen_addr.clone()
     }
@@ -111,6 +148,18 @@ impl CreateInlet {
     pub fn wait_for_outlet_duration(&self) -> Option<Duration> {
         self.wait_for_outlet_duration
     }
+
+    pub fn data_flow(&self) -> DataFlow {
+        self.data_flow
+    }
+
+    pub fn quota(&self) -> SessionQuota {
+        self.quota
+    }
+
+    pub fn expected_protocol(&self) -> Option<InletProtocol> {
+        self.expected_protocol
+    }
 }
 
 /// Request body to create an outlet
@@ -127,6 +176,12 @@ pub struct CreateOutlet {
     /// Allow the outlet to be reachable from the default secure channel, useful when we want to
     /// tighten the flow control
     #[n(4)] pub reachable_from_default_secure_channel: bool,
+    /// The direction in which data can flow through the outlet
+    #[n(5)] pub data_flow: DataFlow,
+    /// The quota of each connection created by the outlet
+    #[n(6)] pub quota: SessionQuota,
+    /// The TLS configuration used to wrap the connections to the target in TLS
+    #[n(7)] pub tls: Option<TlsOrigination>,
 }
 
 impl CreateOutlet {
@@ -141,10 +196,175 @@ impl CreateOutlet {
             worker_addr,
             alias: alias.into(),
             reachable_from_default_secure_channel,
+            data_flow: DataFlow::default(),
+            quota: SessionQuota::default(),
+            tls: None,
+        }
+    }
+
+    pub fn set_data_flow(&mut self, data_flow: DataFlow) {
+        self.data_flow = data_flow
+    }
+
+    pub fn set_quota(&mut self, quota: SessionQuota) {
+        self.quota = quota
+    }
+
+    pub fn set_tls(&mut self, tls: TlsOrigination) {
+        self.tls = Some(tls)
+    }
+}
+
+/// TLS configuration of an outlet wrapping the connections to its target in TLS
+#[derive(Clone, Debug, Default, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TlsOrigination {
+    /// PEM bundle of the CA certificates verifying the certificate of the target.
+    /// The native root certificates are used if missing
+    #[n(1)] pub ca_certificates: Option<String>,
+    /// Name sent with SNI and verified against the certificate of the target.
+    /// The IP address of the target is verified if missing
+    #[n(2)] pub server_name: Option<String>,
+    /// PEM certificate chain used to authenticate to the target,
+    /// or a reference to a node secret: `secret:NAME`
+    #[n(3)] pub client_certificate: Option<String>,
+    /// PEM private key of the client certificate, or a reference to a node secret: `secret:NAME`
+    #[n(4)] pub client_key: Option<String>,
+}
+
+impl TlsOrigination {
+    /// Replace the references to node secrets of the client certificate and key
+    /// with their PEM contents
+    pub fn resolve_secrets(mut self, secrets: &SecretStore) -> ockam_core::Result<Self> {
+        for value in [&mut self.client_certificate, &mut self.client_key]
+            .into_iter()
+            .flatten()
+        {
+            *value = secrets
+                .resolve_pem(value)
+                .map_err(ApiError::core)?
+                .expose()
+                .to_string();
+        }
+        Ok(self)
+    }
+
+    /// Create the TLS configuration of the transport outlet
+    pub fn outlet_tls(&self) -> ockam_core::Result<OutletTls> {
+        let client_certificate = match (&self.client_certificate, &self.client_key) {
+            (Some(certificate), Some(key)) => Some((certificate.as_bytes(), key.as_bytes())),
+            (None, None) => None,
+            _ => {
+                return Err(ApiError::core(
+                    "a client certificate and its private key must be provided together",
+                ))
+            }
+        };
+        let tls = OutletTls::new(
+            self.ca_certificates.as_ref().map(|c| c.as_bytes()),
+            client_certificate,
+        )?;
+        match &self.server_name {
+            Some(server_name) => tls.with_server_name(server_name),
+            None => Ok(tls),
+        }
+    }
+}
+
+/// Direction in which data can flow through a portal
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+#[serde(rename_all = "kebab-case")]
+pub enum DataFlow {
+    #[default]
+    #[n(0)] Bidirectional,
+    /// Data only flows from the clients of the inlet to the server of the outlet
+    #[n(1)] ClientToServer,
+    /// Data only flows from the server of the outlet to the clients of the inlet
+    #[n(2)] ServerToClient,
+}
+
+impl From<DataFlow> for PortalDataFlow {
+    fn from(data_flow: DataFlow) -> Self {
+        match data_flow {
+            DataFlow::Bidirectional => PortalDataFlow::Bidirectional,
+            DataFlow::ClientToServer => PortalDataFlow::ClientToServer,
+            DataFlow::ServerToClient => PortalDataFlow::ServerToClient,
+        }
+    }
+}
+
+impl From<PortalDataFlow> for DataFlow {
+    fn from(data_flow: PortalDataFlow) -> Self {
+        match data_flow {
+            PortalDataFlow::Bidirectional => DataFlow::Bidirectional,
+            PortalDataFlow::ClientToServer => DataFlow::ClientToServer,
+            PortalDataFlow::ServerToClient => DataFlow::ServerToClient,
+        }
+    }
+}
+
+impl FromStr for DataFlow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PortalDataFlow::from_str(s).map(DataFlow::from)
+    }
+}
+
+impl std::fmt::Display for DataFlow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        PortalDataFlow::from(*self).fmt(f)
+    }
+}
+
+/// Protocol expected at the beginning of the connections accepted by an inlet
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+#[serde(rename_all = "kebab-case")]
+pub enum InletProtocol {
+    #[n(0)] Http,
+    #[n(1)] Tls,
+    #[n(2)] Ssh,
+}
+
+impl From<InletProtocol> for PortalProtocol {
+    fn from(protocol: InletProtocol) -> Self {
+        match protocol {
+            InletProtocol::Http => PortalProtocol::Http,
+            InletProtocol::Tls => PortalProtocol::Tls,
+            InletProtocol::Ssh => PortalProtocol::Ssh,
         }
     }
 }
 
+impl From<PortalProtocol> for InletProtocol {
+    fn from(protocol: PortalProtocol) -> Self {
+        match protocol {
+            PortalProtocol::Http => InletProtocol::Http,
+            PortalProtocol::Tls => InletProtocol::Tls,
+            PortalProtocol::Ssh => InletProtocol::Ssh,
+        }
+    }
+}
+
+impl FromStr for InletProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PortalProtocol::from_str(s).map(InletProtocol::from)
+    }
+}
+
+impl std::fmt::Display for InletProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        PortalProtocol::from(*self).fmt(f)
+    }
+}
+
 /// Response body when interacting with a portal endpoint
 #[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize)]
 #[rustfmt::skip]
@@ -267,3 +487,130 @@ impl OutletList {
         Self { list }
     }
 }
+
+/// Request body to send a fraction of the new connections of an inlet to a canary outlet
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartInletCanary {
+    /// Address of the canary outlet
+    #[n(1)] pub(crate) outlet_addr: MultiAddr,
+    /// Percentage of the new connections sent to the canary outlet
+    #[n(2)] pub(crate) percentage: u8,
+    /// Percentage of failed canary connections above which the canary is rolled back
+    #[n(3)] pub(crate) error_threshold: u8,
+    /// Number of canary connections after which the canary is promoted, if its error rate is
+    /// below the threshold
+    #[n(4)] pub(crate) promote_after: Option<u64>,
+    /// An authorised identity for the secure channels to the canary outlet
+    #[n(5)] pub(crate) authorized: Option<Identifier>,
+    /// The maximum duration to wait for the canary outlet to be available
+    #[n(6)] pub(crate) wait_for_outlet_duration: Option<Duration>,
+    /// How the connections of a client are spread between the current and the canary outlets
+    #[n(7)] pub(crate) stickiness: InletStickiness,
+}
+
+impl StartInletCanary {
+    pub fn new(
+        outlet_addr: MultiAddr,
+        percentage: u8,
+        error_threshold: u8,
+        promote_after: Option<u64>,
+        authorized: Option<Identifier>,
+        wait_for_outlet_duration: Option<Duration>,
+        stickiness: InletStickiness,
+    ) -> Self {
+        Self {
+            outlet_addr,
+            percentage,
+            error_threshold,
+            promote_after,
+            authorized,
+            wait_for_outlet_duration,
+            stickiness,
+        }
+    }
+}
+
+/// Request body to change the share of the new connections of an inlet sent to its canary outlet,
+/// from the relative weights of the current outlet and of the canary outlet
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SetInletCanaryWeights {
+    /// Weight of the current outlet
+    #[n(1)] pub(crate) outlet_weight: u32,
+    /// Weight of the canary outlet
+    #[n(2)] pub(crate) canary_weight: u32,
+}
+
+impl SetInletCanaryWeights {
+    pub fn new(outlet_weight: u32, canary_weight: u32) -> Self {
+        Self {
+            outlet_weight,
+            canary_weight,
+        }
+    }
+}
+
+/// How the connections of a client are spread between the current and the canary outlets of an inlet
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+#[serde(rename_all = "kebab-case")]
+pub enum InletStickiness {
+    #[default]
+    #[n(0)] None,
+    /// All the connections coming from the same IP address are sent to the same outlet
+    #[n(1)] SourceIp,
+}
+
+impl From<InletStickiness> for CanaryStickiness {
+    fn from(stickiness: InletStickiness) -> Self {
+        match stickiness {
+            InletStickiness::None => CanaryStickiness::None,
+            InletStickiness::SourceIp => CanaryStickiness::SourceIp,
+        }
+    }
+}
+
+impl From<CanaryStickiness> for InletStickiness {
+    fn from(stickiness: CanaryStickiness) -> Self {
+        match stickiness {
+            CanaryStickiness::None => InletStickiness::None,
+            CanaryStickiness::SourceIp => InletStickiness::SourceIp,
+        }
+    }
+}
+
+impl FromStr for InletStickiness {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CanaryStickiness::from_str(s).map(InletStickiness::from)
+    }
+}
+
+impl std::fmt::Display for InletStickiness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        CanaryStickiness::from(*self).fmt(f)
+    }
+}
+
+/// Response body describing the canary of an inlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InletCanaryStatus {
+    #[n(1)] pub alias: String,
+    #[n(2)] pub outlet_addr: String,
+    #[n(3)] pub percentage: u8,
+    /// running, promoted or rolled back
+    #[n(4)] pub status: String,
+    /// Number of connections sent to the canary outlet
+    #[n(5)] pub connections: u64,
+    /// Number of canary connections which ended before receiving any data from the outlet
+    #[n(6)] pub failures: u64,
+    /// none or source-ip
+    #[n(7)] pub stickiness: String,
+}
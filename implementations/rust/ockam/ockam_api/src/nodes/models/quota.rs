@@ -0,0 +1,44 @@
+//! Quota request types
+
+use std::time::Duration;
+
+use minicbor::{Decode, Encode};
+use ockam_core::Quota;
+
+/// Limits on the traffic of each secure channel or portal connection accepted by a listener,
+/// an inlet or an outlet. A connection is closed as soon as one of its limits is exceeded
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SessionQuota {
+    /// Maximum number of bytes exchanged in both directions
+    #[n(1)] pub max_bytes: Option<u64>,
+    /// Maximum number of messages exchanged in both directions
+    #[n(2)] pub max_messages: Option<u64>,
+    /// Maximum lifetime of a session, in seconds
+    #[n(3)] pub max_duration_secs: Option<u64>,
+}
+
+impl SessionQuota {
+    pub fn new(
+        max_bytes: Option<u64>,
+        max_messages: Option<u64>,
+        max_duration: Option<Duration>,
+    ) -> Self {
+        Self {
+            max_bytes,
+            max_messages,
+            max_duration_secs: max_duration.map(|d| d.as_secs()),
+        }
+    }
+}
+
+impl From<SessionQuota> for Quota {
+    fn from(quota: SessionQuota) -> Self {
+        Quota {
+            max_bytes: quota.max_bytes,
+            max_messages: quota.max_messages,
+            max_duration: quota.max_duration_secs.map(Duration::from_secs),
+        }
+    }
+}
@@ -3,11 +3,13 @@ use minicbor::{Decode, Encode};
 use ockam::identity::Identifier;
 use ockam::remote::RemoteRelayInfo;
 use ockam::route;
+use ockam::RelayRegistration;
 use ockam_core::flow_control::FlowControlId;
 use ockam_multiaddr::MultiAddr;
 
 use crate::error::ApiError;
 use crate::route_to_multiaddr;
+use crate::session::sessions::SessionEvent;
 
 /// Request body when instructing a node to create a relay
 #[derive(Debug, Clone, Decode, Encode)]
@@ -67,9 +69,26 @@ pub struct RelayInfo {
     #[n(2)] remote_address: String,
     #[n(3)] worker_address: String,
     #[n(4)] flow_control_id: Option<FlowControlId>,
+    /// Most recent status changes of the relay, when its registration is monitored
+    #[n(5)] events: Option<Vec<RelayEvent>>,
+    /// Creation time of the relay, in seconds since the Unix epoch, when it is monitored
+    #[n(6)] created_at: Option<u64>,
+    /// True if the relay answers the pings of the node, when it is monitored
+    #[n(7)] up: Option<bool>,
 }
 
 impl RelayInfo {
+    pub fn with_events(mut self, events: Vec<RelayEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn with_liveness(mut self, created_at: Option<u64>, up: Option<bool>) -> Self {
+        self.created_at = created_at;
+        self.up = up;
+        self
+    }
+
     pub fn forwarding_route(&self) -> &str {
         &self.forwarding_route
     }
@@ -78,10 +97,26 @@ impl RelayInfo {
         &self.remote_address
     }
 
+    pub fn worker_address(&self) -> &str {
+        &self.worker_address
+    }
+
     pub fn flow_control_id(&self) -> &Option<FlowControlId> {
         &self.flow_control_id
     }
 
+    pub fn events(&self) -> &[RelayEvent] {
+        self.events.as_deref().unwrap_or_default()
+    }
+
+    pub fn created_at(&self) -> Option<u64> {
+        self.created_at
+    }
+
+    pub fn is_up(&self) -> Option<bool> {
+        self.up
+    }
+
     pub fn remote_address_ma(&self) -> Result<MultiAddr, ockam_core::Error> {
         route_to_multiaddr(&route![self.remote_address.to_string()])
             .ok_or_else(|| ApiError::core("Invalid Remote Address"))
@@ -100,6 +135,54 @@ impl From<RemoteRelayInfo> for RelayInfo {
             remote_address: inner.remote_address().into(),
             worker_address: inner.worker_address().to_string(),
             flow_control_id: inner.flow_control_id().clone(),
+            events: None,
+            created_at: None,
+            up: None,
+        }
+    }
+}
+
+/// Relay registered on the relay service of a node, like the relay service of a project
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize, serde::Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RelayServiceEntry {
+    /// Address of the relay on the node of the relay service
+    #[n(1)] pub remote_address: String,
+    /// Route from the relay service to the worker which registered the relay
+    #[n(2)] pub forward_route: String,
+    /// Creation time of the relay, in seconds since the Unix epoch
+    #[n(3)] pub created_at: u64,
+}
+
+impl From<RelayRegistration> for RelayServiceEntry {
+    fn from(registration: RelayRegistration) -> Self {
+        Self {
+            remote_address: registration.address,
+            forward_route: registration.forward_route,
+            created_at: registration.created_at,
+        }
+    }
+}
+
+/// Change of the status of a relay: its registration was lost, or renewed
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize, serde::Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RelayEvent {
+    /// Time of the event, in seconds since the Unix epoch
+    #[n(1)] pub timestamp: u64,
+    /// Status of the relay after the event: up, degraded or down
+    #[n(2)] pub status: String,
+    #[n(3)] pub message: String,
+}
+
+impl From<SessionEvent> for RelayEvent {
+    fn from(event: SessionEvent) -> Self {
+        Self {
+            timestamp: *event.timestamp(),
+            status: event.status().to_string(),
+            message: event.message().to_string(),
         }
     }
 }
@@ -1,14 +1,19 @@
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 use minicbor::{Decode, Encode};
 use serde::Serialize;
 
-use ockam::identity::{Identifier, DEFAULT_TIMEOUT};
+use ockam::identity::{
+    HandshakePattern, Identifier, RekeyPolicy, SecureChannelRegistryEntry, DEFAULT_TIMEOUT,
+};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{route, Address, Result};
 use ockam_multiaddr::MultiAddr;
 
 use crate::error::ApiError;
+use crate::nodes::models::quota::SessionQuota;
+use crate::nodes::pairing::PairingApproval;
 use crate::nodes::registry::{SecureChannelInfo, SecureChannelListenerInfo};
 use crate::route_to_multiaddr;
 
@@ -22,6 +27,9 @@ pub struct CreateSecureChannelRequest {
     #[n(4)] pub timeout: Option<Duration>,
     #[n(5)] pub identity_name: Option<String>,
     #[n(6)] pub credential_name: Option<String>,
+    #[n(7)] pub pq_hybrid: Option<bool>,
+    #[n(8)] pub handshake_retries: Option<u32>,
+    #[n(9)] pub handshake_backoff: Option<Duration>,
 }
 
 impl CreateSecureChannelRequest {
@@ -38,8 +46,28 @@ impl CreateSecureChannelRequest {
             timeout: Some(DEFAULT_TIMEOUT),
             identity_name,
             credential_name,
+            pq_hybrid: None,
+            handshake_retries: None,
+            handshake_backoff: None,
         }
     }
+
+    /// Use the post-quantum hybrid key agreement for the handshake
+    pub fn set_pq_hybrid(&mut self) {
+        self.pq_hybrid = Some(true)
+    }
+
+    /// Set the timeout of the handshake, instead of [`DEFAULT_TIMEOUT`]
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout)
+    }
+
+    /// Retry a timed out handshake up to `retries` times, waiting for `backoff` before the first
+    /// retry, then doubling it after each retry
+    pub fn set_handshake_retries(&mut self, retries: u32, backoff: Option<Duration>) {
+        self.handshake_retries = Some(retries);
+        self.handshake_backoff = backoff;
+    }
 }
 
 /// Response body when instructing a node to create a Secure Channel
@@ -49,6 +77,8 @@ impl CreateSecureChannelRequest {
 pub struct CreateSecureChannelResponse {
     #[n(1)] pub addr: Address,
     #[n(2)] pub flow_control_id: FlowControlId,
+    /// Identifier pinned for the peer, when the peer presented a different one
+    #[n(3)] pub pinned_identifier: Option<String>,
 }
 
 impl CreateSecureChannelResponse {
@@ -56,9 +86,15 @@ impl CreateSecureChannelResponse {
         Self {
             addr: addr.to_string().into(),
             flow_control_id: flow_control_id.clone(),
+            pinned_identifier: None,
         }
     }
 
+    pub fn with_pinned_identifier(mut self, pinned_identifier: Identifier) -> Self {
+        self.pinned_identifier = Some(pinned_identifier.to_string());
+        self
+    }
+
     pub fn flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
     }
@@ -78,6 +114,20 @@ pub struct CreateSecureChannelListenerRequest {
     #[n(2)] pub authorized_identifiers: Option<Vec<String>>,
     #[n(3)] pub vault_name: Option<String>,
     #[n(4)] pub identity_name: Option<String>,
+    #[n(5)] pub quota: SessionQuota,
+    #[n(6)] pub pairing: Option<PairingApproval>,
+    #[n(7)] pub hide_identity: Option<bool>,
+    #[n(8)] pub handshake_patterns: Option<Vec<HandshakePattern>>,
+    /// Hex encoded pre-shared key
+    #[n(9)] pub pre_shared_key: Option<String>,
+    /// Renew the keys of the channels after this number of bytes
+    #[n(10)] pub rekey_after_bytes: Option<u64>,
+    /// Renew the keys of the channels after this number of seconds
+    #[n(11)] pub rekey_after_secs: Option<u64>,
+    /// Attributes which the credentials of the initiators must give them
+    #[n(12)] pub required_attributes: Option<BTreeMap<String, String>>,
+    /// Accept the initiators without the required attributes, with a low trust level
+    #[n(13)] pub progressive_trust: Option<bool>,
 }
 
 impl CreateSecureChannelListenerRequest {
@@ -93,8 +143,50 @@ impl CreateSecureChannelListenerRequest {
                 .map(|x| x.into_iter().map(|y| y.to_string()).collect()),
             vault_name,
             identity_name,
+            quota: SessionQuota::default(),
+            pairing: None,
+            hide_identity: None,
+            handshake_patterns: None,
+            pre_shared_key: None,
+            rekey_after_bytes: None,
+            rekey_after_secs: None,
+            required_attributes: None,
+            progressive_trust: None,
         }
     }
+
+    pub fn set_quota(&mut self, quota: SessionQuota) {
+        self.quota = quota
+    }
+
+    pub fn set_pairing(&mut self, pairing: PairingApproval) {
+        self.pairing = Some(pairing)
+    }
+
+    pub fn set_hide_identity(&mut self) {
+        self.hide_identity = Some(true)
+    }
+
+    pub fn set_handshake_patterns(&mut self, handshake_patterns: Vec<HandshakePattern>) {
+        self.handshake_patterns = Some(handshake_patterns)
+    }
+
+    pub fn set_pre_shared_key(&mut self, pre_shared_key: String) {
+        self.pre_shared_key = Some(pre_shared_key)
+    }
+
+    pub fn set_rekey_policy(&mut self, rekey_policy: RekeyPolicy) {
+        self.rekey_after_bytes = rekey_policy.after_bytes;
+        self.rekey_after_secs = rekey_policy.after_duration.map(|d| d.as_secs())
+    }
+
+    pub fn set_required_attributes(&mut self, required_attributes: BTreeMap<String, String>) {
+        self.required_attributes = Some(required_attributes)
+    }
+
+    pub fn set_progressive_trust(&mut self) {
+        self.progressive_trust = Some(true)
+    }
 }
 
 /// Request body when deleting a Secure Channel Listener
@@ -236,6 +328,41 @@ impl ShowSecureChannelResponse {
     }
 }
 
+/// Live state of a Secure Channel, either initiated or accepted by a node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SecureChannelState {
+    #[n(1)] pub channel: String,
+    #[n(2)] pub is_initiator: bool,
+    #[n(3)] pub their_identifier: String,
+    #[n(4)] pub route: String,
+    /// Creation time, in seconds since the UNIX epoch
+    #[n(5)] pub created_at: u64,
+    #[n(6)] pub bytes_in: u64,
+    #[n(7)] pub bytes_out: u64,
+    /// Time of the last message sent or received, in seconds since the UNIX epoch
+    #[n(8)] pub last_activity: Option<u64>,
+    #[n(9)] pub rekeys: u64,
+}
+
+impl SecureChannelState {
+    pub fn new(entry: &SecureChannelRegistryEntry) -> Self {
+        let statistics = entry.statistics();
+        Self {
+            channel: entry.encryptor_messaging_address().to_string(),
+            is_initiator: entry.is_initiator(),
+            their_identifier: entry.their_id().to_string(),
+            route: entry.remote_route().to_string(),
+            created_at: *statistics.created_at(),
+            bytes_in: statistics.bytes_in(),
+            bytes_out: statistics.bytes_out(),
+            last_activity: statistics.last_activity().map(|t| *t),
+            rekeys: statistics.rekeys(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
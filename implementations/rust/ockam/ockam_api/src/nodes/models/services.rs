@@ -1,3 +1,6 @@
+use core::fmt::{Debug, Formatter};
+use core::time::Duration;
+
 use minicbor::{Decode, Encode};
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::Address;
@@ -5,6 +8,13 @@ use ockam_multiaddr::MultiAddr;
 
 use serde::Serialize;
 
+#[cfg(feature = "kafka")]
+use crate::error::ApiError;
+#[cfg(feature = "kafka")]
+use crate::kafka::SaslPlainCredentials;
+#[cfg(feature = "kafka")]
+use crate::nodes::secrets::SecretStore;
+
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -52,20 +62,67 @@ impl DeleteServiceRequest {
 #[cbor(map)]
 pub struct StartKafkaOutletRequest {
     #[n(1)] pub bootstrap_server_addr: SocketAddr,
+    #[n(2)] pub sasl: Option<KafkaSaslPlain>,
 }
 
 impl StartKafkaOutletRequest {
     pub fn new(bootstrap_server_addr: SocketAddr) -> Self {
         Self {
             bootstrap_server_addr,
+            sasl: None,
         }
     }
 
+    pub fn with_sasl(mut self, sasl: KafkaSaslPlain) -> Self {
+        self.sasl = Some(sasl);
+        self
+    }
+
     pub fn bootstrap_server_addr(&self) -> &SocketAddr {
         &self.bootstrap_server_addr
     }
 }
 
+/// SASL/PLAIN credentials used by a Kafka outlet to authenticate to the brokers.
+/// Each value is either given directly, or as a reference to a node secret: `secret:NAME`
+#[derive(Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct KafkaSaslPlain {
+    #[n(1)] pub username: String,
+    #[n(2)] pub password: String,
+}
+
+impl KafkaSaslPlain {
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+
+    /// Return the credentials, with the values of the referenced node secrets
+    #[cfg(feature = "kafka")]
+    pub(crate) fn resolve(
+        &self,
+        secrets: &SecretStore,
+    ) -> ockam_core::Result<SaslPlainCredentials> {
+        Ok(SaslPlainCredentials::new(
+            secrets.resolve(&self.username).map_err(ApiError::core)?,
+            secrets.resolve(&self.password).map_err(ApiError::core)?,
+        ))
+    }
+}
+
+impl Debug for KafkaSaslPlain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("KafkaSaslPlain")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .finish()
+    }
+}
+
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -227,6 +284,57 @@ impl StartEchoerServiceRequest {
     }
 }
 
+/// Request body when instructing a node to start an Exec service
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartExecServiceRequest {
+    #[n(1)] pub addr: String,
+    /// Commands which can be run, each one with the format `PROGRAM [ARGS...]`
+    #[n(2)] pub allowed_commands: Vec<String>,
+    #[n(3)] pub timeout_secs: Option<u64>,
+}
+
+impl StartExecServiceRequest {
+    pub fn new(
+        addr: impl Into<String>,
+        allowed_commands: Vec<String>,
+        timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            addr: addr.into(),
+            allowed_commands,
+            timeout_secs: timeout.map(|t| t.as_secs()),
+        }
+    }
+}
+
+/// Request body when instructing a node to start a Provisioner service
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartProvisionerServiceRequest {
+    #[n(1)] pub addr: String,
+    /// Attribute of the peers used to select their target
+    #[n(2)] pub attribute: String,
+    /// Targets of the provisioned outlets, each one with the format `VALUE=IP:PORT`
+    #[n(3)] pub targets: Vec<String>,
+}
+
+impl StartProvisionerServiceRequest {
+    pub fn new(
+        addr: impl Into<String>,
+        attribute: impl Into<String>,
+        targets: Vec<String>,
+    ) -> Self {
+        Self {
+            addr: addr.into(),
+            attribute: attribute.into(),
+            targets,
+        }
+    }
+}
+
 /// Request body when instructing a node to start a Hop service
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
@@ -241,6 +349,20 @@ impl StartHopServiceRequest {
     }
 }
 
+/// Request body when instructing a node to start a UDP Rendezvous service
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartRendezvousServiceRequest {
+    #[n(1)] pub addr: String,
+}
+
+impl StartRendezvousServiceRequest {
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self { addr: addr.into() }
+    }
+}
+
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -0,0 +1,124 @@
+//! Statistics used to monitor the activity of a node
+
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+
+/// Response body for the statistics of a node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeStats {
+    #[n(1)] pub workers: Vec<WorkerStats>,
+    #[n(2)] pub secure_channels: u32,
+    #[n(3)] pub secure_channel_listeners: u32,
+    #[n(4)] pub portals: Vec<PortalStats>,
+}
+
+/// Response body for the heap usage of a node, returned by its debug endpoint
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HeapStats {
+    /// Statistics of the allocator, when the node counts its allocations
+    #[n(1)] pub allocator: Option<AllocatorStats>,
+    #[n(2)] pub workers: u32,
+    #[n(3)] pub processors: u32,
+    #[n(4)] pub secure_channels: u32,
+    #[n(5)] pub secure_channel_listeners: u32,
+    #[n(6)] pub sessions: u32,
+    #[n(7)] pub relays: u32,
+    #[n(8)] pub inlets: u32,
+    #[n(9)] pub outlets: u32,
+    /// Number of messages waiting in the mailboxes of all the workers
+    #[n(10)] pub buffered_messages: u64,
+}
+
+/// Statistics of the allocator of a node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AllocatorStats {
+    /// Number of bytes currently allocated
+    #[n(1)] pub allocated_bytes: u64,
+    /// Highest number of bytes allocated at the same time
+    #[n(2)] pub peak_allocated_bytes: u64,
+    /// Number of allocations since the node started
+    #[n(3)] pub allocations: u64,
+    /// Number of deallocations since the node started
+    #[n(4)] pub deallocations: u64,
+}
+
+impl From<crate::allocator::AllocatorStatistics> for AllocatorStats {
+    fn from(statistics: crate::allocator::AllocatorStatistics) -> Self {
+        Self {
+            allocated_bytes: statistics.allocated_bytes,
+            peak_allocated_bytes: statistics.peak_allocated_bytes,
+            allocations: statistics.allocations,
+            deallocations: statistics.deallocations,
+        }
+    }
+}
+
+/// Statistics of a worker or a processor
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct WorkerStats {
+    #[n(1)] pub address: String,
+    #[n(2)] pub processor: bool,
+    /// Number of messages waiting to be handled by the worker
+    #[n(3)] pub mailbox_len: u64,
+    /// Number of messages routed to the worker since it was started
+    #[n(4)] pub messages: u64,
+}
+
+impl From<ockam_node::WorkerStats> for WorkerStats {
+    fn from(stats: ockam_node::WorkerStats) -> Self {
+        Self {
+            address: stats.address.address().to_string(),
+            processor: stats.processor,
+            mailbox_len: stats.mailbox_len as u64,
+            messages: stats.messages as u64,
+        }
+    }
+}
+
+/// Statistics of an inlet or an outlet
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PortalStats {
+    #[n(1)] pub alias: String,
+    #[n(2)] pub kind: PortalKind,
+    /// Number of bytes read from the TCP connections of the portal
+    #[n(3)] pub bytes_received: u64,
+    /// Number of bytes written to the TCP connections of the portal
+    #[n(4)] pub bytes_sent: u64,
+    /// Number of TCP connections currently open
+    #[n(5)] pub connections: u64,
+}
+
+impl PortalStats {
+    pub(crate) fn new(
+        alias: impl Into<String>,
+        kind: PortalKind,
+        statistics: &ockam_transport_tcp::PortalStatistics,
+    ) -> Self {
+        Self {
+            alias: alias.into(),
+            kind,
+            bytes_received: statistics.bytes_received(),
+            bytes_sent: statistics.bytes_sent(),
+            connections: statistics.connections() as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+#[serde(rename_all = "lowercase")]
+pub enum PortalKind {
+    #[n(0)] Inlet,
+    #[n(1)] Outlet,
+}
@@ -15,6 +15,8 @@ pub enum TransportType {
     #[n(1)] Ble,
     /// Websocket transport
     #[n(2)] WebSocket,
+    /// Ockam UDP transport
+    #[n(3)] Udp,
 }
 
 impl Display for TransportType {
@@ -23,6 +25,7 @@ impl Display for TransportType {
             Self::Tcp => "TCP",
             Self::Ble => "BLE",
             Self::WebSocket => "Websocket",
+            Self::Udp => "UDP",
         })
     }
 }
@@ -22,14 +22,67 @@ impl CreateTcpConnection {
 pub struct CreateTcpListener {
     /// The address payload for the transport
     #[n(1)] pub addr: String,
+    /// PEM certificate chain presented when the connections are wrapped in TLS
+    #[n(2)] pub tls_certificate: Option<String>,
+    /// PEM private key of the TLS certificate
+    #[n(3)] pub tls_key: Option<String>,
 }
 
 impl CreateTcpListener {
+    pub fn new(addr: String) -> Self {
+        Self {
+            addr,
+            tls_certificate: None,
+            tls_key: None,
+        }
+    }
+
+    /// Wrap the accepted connections in TLS, with a PEM certificate chain and its PEM private key
+    pub fn with_tls(mut self, certificate: String, key: String) -> Self {
+        self.tls_certificate = Some(certificate);
+        self.tls_key = Some(key);
+        self
+    }
+}
+
+/// Request body when instructing a node to create a UDP listener
+#[derive(Debug, Clone, Decode, Encode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateUdpListener {
+    /// The address payload for the transport
+    #[n(1)] pub addr: String,
+}
+
+impl CreateUdpListener {
     pub fn new(addr: String) -> Self {
         Self { addr }
     }
 }
 
+/// Request body when instructing a node to create a UDP hole puncher
+#[derive(Debug, Clone, Decode, Encode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateUdpPuncher {
+    /// Name of the puncher, used by the peer puncher to find it
+    #[n(1)] pub name: String,
+    /// Name of the peer puncher
+    #[n(2)] pub peer_name: String,
+    /// Address of the rendezvous service, for example /ip4/1.2.3.4/udp/4000/service/rendezvous
+    #[n(3)] pub rendezvous: String,
+}
+
+impl CreateUdpPuncher {
+    pub fn new(name: String, peer_name: String, rendezvous: String) -> Self {
+        Self {
+            name,
+            peer_name,
+            rendezvous,
+        }
+    }
+}
+
 /// Request to delete a transport
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
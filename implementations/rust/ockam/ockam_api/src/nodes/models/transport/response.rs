@@ -70,3 +70,59 @@ impl TransportList {
         Self { list }
     }
 }
+
+/// Response body when interacting with a UDP hole puncher
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UdpPuncherStatus {
+    /// Name of the puncher
+    #[n(1)] pub name: String,
+    /// Name of the peer puncher
+    #[n(2)] pub peer_name: String,
+    /// Address of the rendezvous service
+    #[n(3)] pub rendezvous: String,
+    /// Address of the puncher worker, the messages sent to this address are sent to the peer
+    #[n(4)] pub worker_addr: String,
+}
+
+impl UdpPuncherStatus {
+    pub fn new(
+        name: impl Into<String>,
+        peer_name: impl Into<String>,
+        rendezvous: impl Into<String>,
+        worker_addr: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            peer_name: peer_name.into(),
+            rendezvous: rendezvous.into(),
+            worker_addr: worker_addr.into(),
+        }
+    }
+
+    /// Address of the peer node, via the puncher worker
+    pub fn multiaddr(&self) -> Result<MultiAddr> {
+        let mut m = MultiAddr::default();
+        let worker_address = self
+            .worker_addr
+            .strip_prefix("0#")
+            .unwrap_or(self.worker_addr.as_ref());
+        m.push_back(Worker::new(worker_address))?;
+        Ok(m)
+    }
+}
+
+/// Response body when listing UDP hole punchers
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UdpPuncherList {
+    #[n(1)] pub list: Vec<UdpPuncherStatus>
+}
+
+impl UdpPuncherList {
+    pub fn new(list: Vec<UdpPuncherStatus>) -> Self {
+        Self { list }
+    }
+}
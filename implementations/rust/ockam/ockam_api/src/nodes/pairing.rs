@@ -0,0 +1,249 @@
+//! Approval of the secure channels opened by previously unseen identities.
+//!
+//! On a personal device there is usually no authority deciding which identities can connect to
+//! a node. With pairing, the first secure channel from an identifier must be approved by the
+//! operator of the node, either on the terminal of a foreground node or by a hook program.
+//! The decision is then pinned in the node directory: the next channels from the same identifier
+//! are accepted or rejected without asking again.
+//!
+//! On the other side, [`PinnedPeerTrustPolicy`] pins the identifier presented by a peer the first
+//! time a secure channel is created to it, and detects when that peer later presents a
+//! different identifier.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex as SyncMutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use minicbor::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use ockam::identity::{Identifier, SecureChannelTrustInfo, TrustPolicy};
+use ockam_core::{async_trait, Result};
+
+use crate::cli_state::{PinCheck, TrustPinsState};
+use crate::error::ApiError;
+
+/// How the first secure channel from an unknown identifier is approved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Decode, Encode)]
+#[serde(rename_all = "snake_case")]
+#[rustfmt::skip]
+pub enum PairingApproval {
+    /// Ask the operator on the terminal of the node. This requires a foreground node
+    #[n(0)] Prompt,
+    /// Run a program with the identifier as its only argument. The channel is approved if the
+    /// program exits successfully
+    #[n(1)] Hook(#[n(0)] String),
+}
+
+/// Decision taken for an identifier
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PairingDecision {
+    pub approved: bool,
+    /// Time of the decision, in seconds since the Unix epoch
+    pub decided_at: u64,
+}
+
+/// Trust policy approving the first secure channel of each identifier with a [`PairingApproval`]
+/// and pinning the decisions in a file
+#[derive(Clone)]
+pub struct PairingTrustPolicy {
+    approval: PairingApproval,
+    path: PathBuf,
+    // Approvals are requested one at a time, and the file is only accessed under this lock
+    lock: Arc<Mutex<()>>,
+}
+
+impl PairingTrustPolicy {
+    pub fn new(approval: PairingApproval, path: PathBuf) -> Self {
+        Self {
+            approval,
+            path,
+            lock: Default::default(),
+        }
+    }
+
+    fn read_decisions(&self) -> Result<BTreeMap<String, PairingDecision>> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let contents = std::fs::read_to_string(&self.path)
+            .map_err(|e| ApiError::core(format!("cannot read the pairing decisions: {e}")))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| ApiError::core(format!("invalid pairing decisions: {e}")))
+    }
+
+    fn write_decisions(&self, decisions: &BTreeMap<String, PairingDecision>) -> Result<()> {
+        let contents =
+            serde_json::to_string_pretty(decisions).map_err(|e| ApiError::core(e.to_string()))?;
+        std::fs::write(&self.path, contents)
+            .map_err(|e| ApiError::core(format!("cannot write the pairing decisions: {e}")))
+    }
+
+    /// Ask for the approval of an identifier.
+    /// `None` is returned if no decision could be taken, in which case nothing is pinned
+    async fn ask(&self, identifier: &Identifier) -> Option<bool> {
+        match &self.approval {
+            PairingApproval::Prompt => {
+                let identifier = identifier.to_string();
+                tokio::task::spawn_blocking(move || prompt(&identifier))
+                    .await
+                    .ok()
+                    .flatten()
+            }
+            PairingApproval::Hook(program) => {
+                match tokio::process::Command::new(program)
+                    .arg(identifier.to_string())
+                    .status()
+                    .await
+                {
+                    Ok(status) => Some(status.success()),
+                    Err(err) => {
+                        warn!(%identifier, %program, %err, "the pairing hook could not be run");
+                        None
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn prompt(identifier: &str) -> Option<bool> {
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        warn!(%identifier, "a pairing approval can't be prompted: the node has no terminal");
+        return None;
+    }
+    let mut stderr = std::io::stderr();
+    write!(
+        stderr,
+        "Accept the secure channels from the identity {identifier}? [y/N] "
+    )
+    .ok()?;
+    stderr.flush().ok()?;
+    let mut answer = String::new();
+    stdin.lock().read_line(&mut answer).ok()?;
+    Some(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+#[async_trait]
+impl TrustPolicy for PairingTrustPolicy {
+    async fn check(&self, trust_info: &SecureChannelTrustInfo) -> Result<bool> {
+        let identifier = trust_info.their_identity_id();
+        let _guard = self.lock.lock().await;
+
+        let mut decisions = self.read_decisions()?;
+        if let Some(decision) = decisions.get(&identifier.to_string()) {
+            debug!(%identifier, approved = decision.approved, "pinned pairing decision");
+            return Ok(decision.approved);
+        }
+
+        let approved = match self.ask(identifier).await {
+            Some(approved) => approved,
+            None => return Ok(false),
+        };
+        let decided_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        decisions.insert(
+            identifier.to_string(),
+            PairingDecision {
+                approved,
+                decided_at,
+            },
+        );
+        self.write_decisions(&decisions)?;
+        info!(%identifier, approved, "pairing decision pinned");
+        Ok(approved)
+    }
+}
+
+/// Trust policy pinning the identifier of a peer on the first secure channel created to it.
+///
+/// When the peer presents a different identifier than the pinned one, the channel is refused in
+/// strict mode. Otherwise it is accepted, and the change can be retrieved with
+/// [`PinnedPeerTrustPolicy::outcome`] to warn the user.
+#[derive(Clone)]
+pub struct PinnedPeerTrustPolicy {
+    name: String,
+    pins: TrustPinsState,
+    strict: bool,
+    outcome: Arc<SyncMutex<Option<PinCheck>>>,
+}
+
+impl PinnedPeerTrustPolicy {
+    pub fn new(name: impl Into<String>, pins: TrustPinsState, strict: bool) -> Self {
+        Self {
+            name: name.into(),
+            pins,
+            strict,
+            outcome: Default::default(),
+        }
+    }
+
+    /// Result of the comparison with the pinned identifier, once the identifier of the peer
+    /// has been checked
+    pub fn outcome(&self) -> Option<PinCheck> {
+        self.outcome.lock().ok().and_then(|o| o.clone())
+    }
+}
+
+#[async_trait]
+impl TrustPolicy for PinnedPeerTrustPolicy {
+    async fn check(&self, trust_info: &SecureChannelTrustInfo) -> Result<bool> {
+        let identifier = trust_info.their_identity_id();
+        let check = self
+            .pins
+            .check(&self.name, identifier)
+            .map_err(|e| ApiError::core(e.to_string()))?;
+        let trusted = match &check {
+            PinCheck::Changed { pinned } => {
+                warn!(name = %self.name, %pinned, %identifier, strict = self.strict, "the identifier of the peer changed");
+                !self.strict
+            }
+            _ => true,
+        };
+        if let Ok(mut outcome) = self.outcome.lock() {
+            *outcome = Some(check);
+        }
+        Ok(trusted)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    #[tokio::test]
+    async fn pairing_decisions_are_pinned() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pairings.json");
+        let alice = Identifier::from_str("Ie92f183eb4c324804ef4d62962dea94cf095a265").unwrap();
+        let bob = Identifier::from_str("Ibb37445cacb3ca7a20040a9b36469e321a57d2cd").unwrap();
+
+        let approve = PairingTrustPolicy::new(PairingApproval::Hook("true".into()), path.clone());
+        assert!(approve
+            .check(&SecureChannelTrustInfo::new(alice.clone()))
+            .await
+            .unwrap());
+
+        // The decision for alice is pinned, while bob is rejected by the new hook
+        let reject = PairingTrustPolicy::new(PairingApproval::Hook("false".into()), path.clone());
+        assert!(reject
+            .check(&SecureChannelTrustInfo::new(alice))
+            .await
+            .unwrap());
+        assert!(!reject
+            .check(&SecureChannelTrustInfo::new(bob.clone()))
+            .await
+            .unwrap());
+        assert!(!approve
+            .check(&SecureChannelTrustInfo::new(bob))
+            .await
+            .unwrap());
+    }
+}
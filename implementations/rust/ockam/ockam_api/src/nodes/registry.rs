@@ -1,13 +1,21 @@
+use crate::chat::ChatInbox;
 use crate::nodes::service::Alias;
 use ockam::identity::Identifier;
 use ockam::identity::{SecureChannel, SecureChannelListener};
 use ockam::remote::RemoteRelayInfo;
 use ockam_core::compat::collections::BTreeMap;
+use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Address, Route};
+use ockam_multiaddr::MultiAddr;
 use ockam_node::compat::asynchronous::RwLock;
+use ockam_transport_tcp::{CanaryStatus, InletCanary, PortalStatistics};
+#[cfg(feature = "udp")]
+use ockam_transport_udp::{UdpHolePuncher, UdpListener};
 use std::borrow::Borrow;
+#[cfg(feature = "kafka")]
 use std::fmt::Display;
 use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
 
 #[derive(Default)]
 pub(crate) struct SecureChannelRegistry {
@@ -108,15 +116,30 @@ pub(crate) struct UppercaseServiceInfo {}
 #[derive(Default, Clone)]
 pub(crate) struct EchoerServiceInfo {}
 
+#[derive(Default, Clone)]
+pub(crate) struct ChatServiceInfo {
+    pub(crate) inbox: ChatInbox,
+}
+
 #[derive(Default, Clone)]
 pub(crate) struct HopServiceInfo {}
 
+#[derive(Default, Clone)]
+pub(crate) struct RendezvousServiceInfo {}
+
+#[derive(Default, Clone)]
+pub(crate) struct ExecServiceInfo {}
+
+#[derive(Default, Clone)]
+pub(crate) struct ProvisionerServiceInfo {}
+
 #[derive(Default, Clone)]
 pub(crate) struct VerifierServiceInfo {}
 
 #[derive(Default, Clone)]
 pub(crate) struct CredentialsServiceInfo {}
 
+#[cfg(feature = "kafka")]
 #[derive(Eq, PartialEq, Clone)]
 pub(crate) enum KafkaServiceKind {
     Consumer,
@@ -125,6 +148,7 @@ pub(crate) enum KafkaServiceKind {
     Direct,
 }
 
+#[cfg(feature = "kafka")]
 impl Display for KafkaServiceKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -136,11 +160,13 @@ impl Display for KafkaServiceKind {
     }
 }
 
+#[cfg(feature = "kafka")]
 #[derive(Clone)]
 pub(crate) struct KafkaServiceInfo {
     kind: KafkaServiceKind,
 }
 
+#[cfg(feature = "kafka")]
 impl KafkaServiceInfo {
     pub fn new(kind: KafkaServiceKind) -> Self {
         Self { kind }
@@ -156,6 +182,8 @@ pub(crate) struct InletInfo {
     pub(crate) bind_addr: String,
     pub(crate) worker_addr: Address,
     pub(crate) outlet_route: Route,
+    pub(crate) statistics: PortalStatistics,
+    pub(crate) canary: InletCanaryInfo,
 }
 
 impl InletInfo {
@@ -163,6 +191,8 @@ impl InletInfo {
         bind_addr: &str,
         worker_addr: Option<&Address>,
         outlet_route: &Route,
+        statistics: PortalStatistics,
+        canary: InletCanaryInfo,
     ) -> Self {
         let worker_addr = match worker_addr {
             Some(addr) => addr.clone(),
@@ -172,18 +202,61 @@ impl InletInfo {
             bind_addr: bind_addr.to_owned(),
             worker_addr,
             outlet_route: outlet_route.to_owned(),
+            statistics,
+            canary,
         }
     }
 }
 
+/// Canary of an inlet. It is kept when the inlet is recreated
+#[derive(Clone, Default)]
+pub(crate) struct InletCanaryInfo {
+    pub(crate) canary: InletCanary,
+    /// Address of the canary outlet
+    canary_addr: Arc<Mutex<Option<MultiAddr>>>,
+    /// Address of the canary outlet once it has been promoted. It is used instead of the
+    /// original outlet address when the inlet is recreated
+    promoted_addr: Arc<Mutex<Option<MultiAddr>>>,
+}
+
+impl InletCanaryInfo {
+    pub(crate) fn set_canary_addr(&self, addr: MultiAddr) {
+        *self.canary_addr.lock().unwrap() = Some(addr);
+    }
+
+    pub(crate) fn canary_addr(&self) -> Option<MultiAddr> {
+        self.canary_addr.lock().unwrap().clone()
+    }
+
+    /// Return the outlet address to use when the inlet is recreated, if a canary was promoted.
+    /// The route of a promoted canary is only valid for the current inlet, so the canary is
+    /// then cleared
+    pub(crate) fn outlet_addr(&self) -> Option<MultiAddr> {
+        let promoted = self
+            .canary
+            .summary()
+            .map_or(false, |s| s.status == CanaryStatus::Promoted);
+        if promoted {
+            self.canary.clear();
+            *self.promoted_addr.lock().unwrap() = self.canary_addr.lock().unwrap().take();
+        }
+        self.promoted_addr.lock().unwrap().clone()
+    }
+}
+
 #[derive(Clone)]
 pub struct OutletInfo {
     pub(crate) socket_addr: SocketAddr,
     pub(crate) worker_addr: Address,
+    pub(crate) statistics: PortalStatistics,
 }
 
 impl OutletInfo {
-    pub(crate) fn new(socket_addr: &SocketAddr, worker_addr: Option<&Address>) -> Self {
+    pub(crate) fn new(
+        socket_addr: &SocketAddr,
+        worker_addr: Option<&Address>,
+        statistics: PortalStatistics,
+    ) -> Self {
         let worker_addr = match worker_addr {
             Some(addr) => addr.clone(),
             None => Address::from_string(""),
@@ -191,6 +264,45 @@ impl OutletInfo {
         Self {
             socket_addr: *socket_addr,
             worker_addr,
+            statistics,
+        }
+    }
+}
+
+#[cfg(feature = "udp")]
+#[derive(Clone)]
+pub(crate) struct UdpListenerInfo {
+    pub(crate) listener: UdpListener,
+    pub(crate) flow_control_id: FlowControlId,
+}
+
+#[cfg(feature = "udp")]
+impl UdpListenerInfo {
+    pub(crate) fn new(listener: UdpListener, flow_control_id: FlowControlId) -> Self {
+        Self {
+            listener,
+            flow_control_id,
+        }
+    }
+}
+
+/// A UDP hole puncher. The puncher keeps trying to open a hole to its peer
+/// as long as its handle is kept
+#[cfg(feature = "udp")]
+#[derive(Clone)]
+pub(crate) struct UdpPuncherInfo {
+    pub(crate) peer_name: String,
+    pub(crate) rendezvous: MultiAddr,
+    pub(crate) puncher: Arc<UdpHolePuncher>,
+}
+
+#[cfg(feature = "udp")]
+impl UdpPuncherInfo {
+    pub(crate) fn new(peer_name: &str, rendezvous: &MultiAddr, puncher: UdpHolePuncher) -> Self {
+        Self {
+            peer_name: peer_name.to_string(),
+            rendezvous: rendezvous.clone(),
+            puncher: Arc::new(puncher),
         }
     }
 }
@@ -202,12 +314,21 @@ pub(crate) struct Registry {
     pub(crate) authenticated_services: RegistryOf<Address, AuthenticatedServiceInfo>,
     pub(crate) uppercase_services: RegistryOf<Address, UppercaseServiceInfo>,
     pub(crate) echoer_services: RegistryOf<Address, EchoerServiceInfo>,
+    pub(crate) chat_services: RegistryOf<Address, ChatServiceInfo>,
+    #[cfg(feature = "kafka")]
     pub(crate) kafka_services: RegistryOf<Address, KafkaServiceInfo>,
     pub(crate) hop_services: RegistryOf<Address, HopServiceInfo>,
+    pub(crate) rendezvous_services: RegistryOf<Address, RendezvousServiceInfo>,
+    pub(crate) exec_services: RegistryOf<Address, ExecServiceInfo>,
+    pub(crate) provisioner_services: RegistryOf<Address, ProvisionerServiceInfo>,
     pub(crate) credentials_services: RegistryOf<Address, CredentialsServiceInfo>,
     pub(crate) relays: RegistryOf<String, RemoteRelayInfo>,
     pub(crate) inlets: RegistryOf<Alias, InletInfo>,
     pub(crate) outlets: RegistryOf<Alias, OutletInfo>,
+    #[cfg(feature = "udp")]
+    pub(crate) udp_listeners: RegistryOf<Address, UdpListenerInfo>,
+    #[cfg(feature = "udp")]
+    pub(crate) udp_punchers: RegistryOf<String, UdpPuncherInfo>,
 }
 
 pub(crate) struct RegistryOf<K, V> {
@@ -272,4 +393,20 @@ impl<K: Clone, V: Clone> RegistryOf<K, V> {
         let map = self.map.read().await;
         map.contains_key(key)
     }
+
+    /// Insert a value with the first key which is not used yet and return that key.
+    /// The keys are checked and the value inserted while holding the lock
+    pub async fn insert_with_first_vacant_key(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+        v: V,
+    ) -> Option<K>
+    where
+        K: Ord,
+    {
+        let mut map = self.map.write().await;
+        let key = keys.into_iter().find(|k| !map.contains_key(k))?;
+        map.insert(key.clone(), v);
+        Some(key)
+    }
 }
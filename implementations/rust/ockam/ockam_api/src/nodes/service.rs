@@ -5,6 +5,7 @@ use std::collections::BTreeMap;
 use std::error::Error as _;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::RwLock;
 use std::time::Duration;
 
 use minicbor::{Decoder, Encode};
@@ -14,12 +15,13 @@ use ockam::identity::models::CredentialAndPurposeKey;
 use ockam::identity::CredentialsServerModule;
 use ockam::identity::TrustContext;
 use ockam::identity::Vault;
+use ockam::identity::{AuthorityStatus, Identifier, RekeyPolicy, SecureChannels};
 use ockam::identity::{
     Credentials, CredentialsServer, Identities, IdentitiesRepository, IdentityAttributesReader,
 };
-use ockam::identity::{Identifier, SecureChannels};
 use ockam::{
-    Address, Context, RelayService, RelayServiceOptions, Result, Routed, TcpTransport, Worker,
+    Address, Context, RelayQuotas, RelayService, RelayServiceOptions, Result, Routed, TcpTransport,
+    Worker,
 };
 use ockam_abac::expr::{eq, ident, str};
 use ockam_abac::{Action, Env, Expr, PolicyAccessControl, PolicyStorage, Resource};
@@ -28,7 +30,17 @@ use ockam_core::compat::{string::String, sync::Arc};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::IncomingAccessControl;
 use ockam_core::{AllowAll, AsyncTryClone};
-use ockam_multiaddr::MultiAddr;
+use ockam_multiaddr::proto::{Ble, Quic, Udp, Ws, Wss};
+use ockam_multiaddr::{Code, MultiAddr, Protocol};
+#[cfg(feature = "ble")]
+use ockam_transport_ble::{driver::btleplug::BleAdapter, BleClient, BleTransport};
+#[cfg(feature = "quic")]
+use ockam_transport_quic::{QuicListener, QuicListenerOptions, QuicTransport};
+#[cfg(feature = "udp")]
+use ockam_transport_udp::UdpTransport;
+#[cfg(feature = "websocket")]
+use ockam_transport_websocket::{WebSocketListenerOptions, WebSocketTransport};
+pub use portal_alias::{AliasTemplate, DEFAULT_ALIAS_TEMPLATE};
 
 use crate::bootstrapped_identities_store::BootstrapedIdentityStore;
 use crate::bootstrapped_identities_store::PreTrustedIdentities;
@@ -43,30 +55,54 @@ use crate::nodes::connection::{
 };
 use crate::nodes::models::base::NodeStatus;
 use crate::nodes::models::portal::{OutletList, OutletStatus};
+use crate::nodes::models::quota::SessionQuota;
 use crate::nodes::models::transport::{TransportMode, TransportType};
 use crate::nodes::models::workers::{WorkerList, WorkerStatus};
+#[cfg(feature = "kafka")]
 use crate::nodes::registry::KafkaServiceKind;
+use crate::nodes::secrets::SecretStore;
 use crate::nodes::{InMemoryNode, NODEMANAGER_ADDR};
 use crate::session::MedicHandle;
 use crate::DefaultAddress;
+use credential_refresher::CredentialRefresherHandle;
+use revocation_list_refresher::RevocationListRefresherHandle;
 
 use super::registry::Registry;
 
 pub(crate) mod background_node;
+mod credential_refresher;
 pub(crate) mod credentials;
 mod flow_controls;
 pub(crate) mod in_memory_node;
+#[cfg(feature = "kafka")]
+mod kafka_services;
 pub mod message;
 mod node_identities;
 mod node_services;
 mod policy;
+mod portal_alias;
 pub mod portals;
 pub mod relay;
+mod reload;
+mod revocation_list_refresher;
 mod secure_channel;
+mod stats;
 mod transport;
 
 const TARGET: &str = "ockam_api::nodemanager::service";
 
+/// Protocols of the transports which were left out of this build, with their prefix
+const MISSING_TRANSPORTS: &[(Code, &str)] = &[
+    #[cfg(not(feature = "udp"))]
+    (Udp::CODE, Udp::PREFIX),
+    #[cfg(not(feature = "quic"))]
+    (Quic::CODE, Quic::PREFIX),
+    #[cfg(not(feature = "websocket"))]
+    (Ws::CODE, Ws::PREFIX),
+    #[cfg(not(feature = "websocket"))]
+    (Wss::CODE, Wss::PREFIX),
+];
+
 pub(crate) type Alias = String;
 
 /// Generate a new alias for some user created extension
@@ -96,13 +132,34 @@ pub struct NodeManager {
     node_name: String,
     api_transport_flow_control_id: FlowControlId,
     pub(crate) tcp_transport: TcpTransport,
+    #[cfg(feature = "udp")]
+    udp_transport: tokio::sync::OnceCell<UdpTransport>,
+    #[cfg(feature = "quic")]
+    quic_transport: tokio::sync::OnceCell<QuicTransport>,
+    #[cfg(feature = "websocket")]
+    websocket_transport: tokio::sync::OnceCell<WebSocketTransport>,
+    #[cfg(feature = "ble")]
+    ble_transport: tokio::sync::OnceCell<BleTransport>,
+    /// Names of the BLE peripherals this node is connected to, with the address of the
+    /// worker sending their messages
+    #[cfg(feature = "ble")]
+    ble_peripherals: tokio::sync::Mutex<BTreeMap<String, Address>>,
     enable_credential_checks: bool,
     identifier: Identifier,
     pub(crate) secure_channels: Arc<SecureChannels>,
-    trust_context: Option<TrustContext>,
+    trust_context_config: RwLock<Option<TrustContextConfig>>,
+    trust_context: RwLock<Option<TrustContext>>,
+    /// Result of the last requests sent to the authority of the trust context
+    authority_status: AuthorityStatus,
     pub(crate) registry: Registry,
     policies: Arc<dyn PolicyStorage>,
     pub(crate) medic_handle: MedicHandle,
+    pub(crate) credential_refresher: CredentialRefresherHandle,
+    pub(crate) revocation_list_refresher: RevocationListRefresherHandle,
+    secrets: SecretStore,
+    version: Option<String>,
+    debug_endpoints: bool,
+    relay_quotas: RelayQuotas,
 }
 
 impl NodeManager {
@@ -114,6 +171,16 @@ impl NodeManager {
         self.node_name.clone()
     }
 
+    /// Version of the program running this node, if it was given when the node was created
+    pub fn version(&self) -> Option<String> {
+        self.version.clone()
+    }
+
+    /// Secrets available to the services of this node
+    pub fn secrets(&self) -> &SecretStore {
+        &self.secrets
+    }
+
     pub(super) fn identities(&self) -> Arc<Identities> {
         self.secure_channels.identities()
     }
@@ -142,6 +209,151 @@ impl NodeManager {
         &self.tcp_transport
     }
 
+    /// Return the UDP transport of the node. The transport is only started when it is first used,
+    /// by a UDP listener, a hole puncher or a connection to a /udp address
+    #[cfg(feature = "udp")]
+    pub async fn udp_transport(&self, ctx: &Context) -> Result<&UdpTransport> {
+        self.udp_transport
+            .get_or_try_init(|| UdpTransport::create(ctx))
+            .await
+    }
+
+    /// Return the QUIC transport of the node. The transport is only started when it is first used,
+    /// by a QUIC listener or a connection to a /quic address
+    #[cfg(feature = "quic")]
+    pub async fn quic_transport(&self, ctx: &Context) -> Result<&QuicTransport> {
+        self.quic_transport
+            .get_or_try_init(|| QuicTransport::create(ctx))
+            .await
+    }
+
+    /// Start accepting QUIC connections on a local address.
+    /// Secure channels can be created via the listener
+    #[cfg(feature = "quic")]
+    pub async fn create_quic_listener(&self, ctx: &Context, address: &str) -> Result<QuicListener> {
+        let options = QuicListenerOptions::new();
+        ctx.flow_controls().add_consumer(
+            DefaultAddress::SECURE_CHANNEL_LISTENER,
+            &options.flow_control_id(),
+        );
+        self.quic_transport(ctx)
+            .await?
+            .listen_with_options(address, options)
+            .await
+    }
+
+    /// Return the WebSocket transport of the node. The transport is only started when it is
+    /// first used, by a WebSocket listener or a connection to a /ws or /wss address
+    #[cfg(feature = "websocket")]
+    pub async fn websocket_transport(&self, ctx: &Context) -> Result<&WebSocketTransport> {
+        self.websocket_transport
+            .get_or_try_init(|| WebSocketTransport::create(ctx))
+            .await
+    }
+
+    /// Start accepting WebSocket connections on a local address, and return the address
+    /// it is bound to. Secure channels can be created via the listener
+    #[cfg(feature = "websocket")]
+    pub async fn create_websocket_listener(
+        &self,
+        ctx: &Context,
+        address: &str,
+    ) -> Result<SocketAddr> {
+        let options = WebSocketListenerOptions::new();
+        ctx.flow_controls().add_consumer(
+            DefaultAddress::SECURE_CHANNEL_LISTENER,
+            &options.flow_control_id(),
+        );
+        self.websocket_transport(ctx)
+            .await?
+            .listen_with_options(address, options)
+            .await
+    }
+
+    /// Start the transports used to reach an address, or fail if one of them was left out
+    /// of this build
+    #[cfg_attr(
+        not(any(feature = "udp", feature = "quic", feature = "websocket")),
+        allow(unused_variables)
+    )]
+    async fn start_transports(&self, ctx: &Context, addr: &MultiAddr) -> Result<()> {
+        for code in addr.iter().map(|p| p.code()) {
+            if let Some((_, prefix)) = MISSING_TRANSPORTS.iter().find(|(c, _)| *c == code) {
+                return Err(ApiError::core(format!(
+                    "Can't connect to {addr}: this node was built without the /{prefix} transport"
+                )));
+            }
+        }
+        #[cfg(feature = "udp")]
+        if addr.iter().any(|p| p.code() == Udp::CODE) {
+            self.udp_transport(ctx).await?;
+        }
+        #[cfg(feature = "quic")]
+        if addr.iter().any(|p| p.code() == Quic::CODE) {
+            self.quic_transport(ctx).await?;
+        }
+        #[cfg(feature = "websocket")]
+        if addr
+            .iter()
+            .any(|p| p.code() == Ws::CODE || p.code() == Wss::CODE)
+        {
+            self.websocket_transport(ctx).await?;
+        }
+        Ok(())
+    }
+
+    /// Return the BLE transport of the node. The transport is only started when it is first used,
+    /// by a connection to a /ble address
+    #[cfg(feature = "ble")]
+    pub async fn ble_transport(&self, ctx: &Context) -> Result<&BleTransport> {
+        self.ble_transport
+            .get_or_try_init(|| BleTransport::create(ctx))
+            .await
+    }
+
+    /// Connect, as a central, to the BLE peripherals named by the /ble parts of an address.
+    /// Each peripheral is only connected once, then the messages are routed to it by name.
+    /// A peripheral is connected again after it disconnected, which stops its sender worker.
+    ///
+    /// The peripheral role is only supported on embedded devices, see the documentation of
+    /// the BLE transport
+    #[cfg(feature = "ble")]
+    async fn connect_ble_peripherals(&self, ctx: &Context, addr: &MultiAddr) -> Result<()> {
+        let mut peripherals = self.ble_peripherals.lock().await;
+        let workers = ctx.list_workers().await?;
+        peripherals.retain(|name, sender| {
+            let connected = workers.contains(sender);
+            if !connected {
+                debug!(%name, "the BLE peripheral was disconnected");
+            }
+            connected
+        });
+        let names: Vec<String> = addr
+            .iter()
+            .filter_map(|p| p.cast::<Ble>().map(|name| name.to_string()))
+            .collect();
+        for name in names {
+            if peripherals.contains_key(&name) {
+                continue;
+            }
+            let client = BleClient::with_adapter(BleAdapter::try_new().await?);
+            let sender = self
+                .ble_transport(ctx)
+                .await?
+                .connect(client, &name)
+                .await?;
+            peripherals.insert(name, sender);
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "ble"))]
+    async fn connect_ble_peripherals(&self, _ctx: &Context, addr: &MultiAddr) -> Result<()> {
+        Err(ApiError::core(format!(
+            "Can't connect to {addr}: this node was built without the BLE transport"
+        )))
+    }
+
     pub async fn list_outlets(&self) -> OutletList {
         OutletList::new(
             self.registry
@@ -265,9 +477,43 @@ impl NodeManager {
         }
     }
 
-    pub(crate) fn trust_context(&self) -> Result<&TrustContext> {
+    /// Return the access control of a service which must never be reachable by any identity.
+    ///
+    /// With a trust context, the default policy only authorizes the members of the trust context.
+    /// Without a trust context, an explicit policy must have been set for the resource and action
+    async fn restricted_access_control(
+        &self,
+        r: &Resource,
+        a: &Action,
+    ) -> Result<Arc<dyn IncomingAccessControl>> {
+        if let Ok(trust_context) = self.trust_context() {
+            return self
+                .access_control(r, a, Some(trust_context.id()), None)
+                .await;
+        }
+        if self.policies.get_policy(r, a).await?.is_none() {
+            return Err(ApiError::core(format!(
+                "The node has no trust context and no policy is set for the resource {r}"
+            )));
+        }
+        let mut env = Env::new();
+        env.put("resource.id", str(r.as_str()));
+        env.put("action.id", str(a.as_str()));
+        Ok(Arc::new(PolicyAccessControl::new(
+            self.policies.clone(),
+            self.identities_repository(),
+            r.clone(),
+            a.clone(),
+            env,
+        )))
+    }
+
+    /// Return the current trust context of the node. It can change when the node is reloaded
+    pub(crate) fn trust_context(&self) -> Result<TrustContext> {
         self.trust_context
-            .as_ref()
+            .read()
+            .unwrap()
+            .clone()
             .ok_or_else(|| ApiError::core("Trust context doesn't exist"))
     }
 }
@@ -278,6 +524,10 @@ pub struct NodeManagerGeneralOptions {
     pre_trusted_identities: Option<PreTrustedIdentities>,
     start_default_services: bool,
     persistent: bool,
+    version: Option<String>,
+    credential_grace_period: Option<Duration>,
+    debug_endpoints: bool,
+    relay_quotas: RelayQuotas,
 }
 
 impl NodeManagerGeneralOptions {
@@ -294,8 +544,37 @@ impl NodeManagerGeneralOptions {
             pre_trusted_identities,
             start_default_services,
             persistent,
+            version: None,
+            credential_grace_period: None,
+            debug_endpoints: false,
+            relay_quotas: RelayQuotas::default(),
         }
     }
+
+    /// Set the version of the program running the node, which is reported by the node status
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Keep authorizing the identities with expired credentials for some time,
+    /// once a request to their authority has failed and until it answers again
+    pub fn with_credential_grace_period(mut self, grace_period: Duration) -> Self {
+        self.credential_grace_period = Some(grace_period);
+        self
+    }
+
+    /// Enable the endpoints used to debug the node, like the heap statistics
+    pub fn with_debug_endpoints(mut self, debug_endpoints: bool) -> Self {
+        self.debug_endpoints = debug_endpoints;
+        self
+    }
+
+    /// Limit the streams and bytes forwarded by the relays registered on this node
+    pub fn with_relay_quotas(mut self, relay_quotas: RelayQuotas) -> Self {
+        self.relay_quotas = relay_quotas;
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -361,8 +640,22 @@ impl NodeManager {
         let cli_state = general_options.cli_state;
         let node_state = cli_state.nodes.get(&general_options.node_name)?;
 
-        let repository: Arc<dyn IdentitiesRepository> =
-            cli_state.identities.identities_repository().await?;
+        let authority_status = AuthorityStatus::default();
+        let repository: Arc<dyn IdentitiesRepository> = match general_options
+            .credential_grace_period
+        {
+            Some(grace_period) => {
+                info!(
+                        ?grace_period,
+                        "expired credentials are accepted during a grace period, while the authority can't be reached"
+                    );
+                cli_state
+                    .identities
+                    .identities_repository_with_grace_period(grace_period, authority_status.clone())
+                    .await?
+            }
+            None => cli_state.identities.identities_repository().await?,
+        };
 
         //TODO: fix this.  Either don't require it to be a bootstrappedidentitystore (and use the
         //trait instead),  or pass it from the general_options always.
@@ -390,6 +683,16 @@ impl NodeManager {
             node_name: general_options.node_name,
             api_transport_flow_control_id: transport_options.api_transport_flow_control_id,
             tcp_transport: transport_options.tcp_transport,
+            #[cfg(feature = "udp")]
+            udp_transport: Default::default(),
+            #[cfg(feature = "quic")]
+            quic_transport: Default::default(),
+            #[cfg(feature = "websocket")]
+            websocket_transport: Default::default(),
+            #[cfg(feature = "ble")]
+            ble_transport: Default::default(),
+            #[cfg(feature = "ble")]
+            ble_peripherals: Default::default(),
             enable_credential_checks: trust_options.trust_context_config.is_some()
                 && trust_options
                     .trust_context_config
@@ -399,10 +702,18 @@ impl NodeManager {
                     .is_ok(),
             identifier: node_state.config().identifier()?,
             secure_channels,
-            trust_context: None,
+            trust_context_config: RwLock::new(None),
+            trust_context: RwLock::new(None),
+            authority_status,
             registry: Default::default(),
             policies,
             medic_handle,
+            credential_refresher: Default::default(),
+            revocation_list_refresher: Default::default(),
+            secrets: Default::default(),
+            version: general_options.version,
+            debug_endpoints: general_options.debug_endpoints,
+            relay_quotas: general_options.relay_quotas,
         };
 
         if let Some(tc) = trust_options.trust_context_config {
@@ -410,6 +721,15 @@ impl NodeManager {
             s.configure_trust_context(&tc).await?;
         }
 
+        // a persistent node regularly retrieves the credentials revoked by its authority
+        if general_options.persistent {
+            if let Ok(authority) = s.trust_context().and_then(|tc| tc.authority().cloned()) {
+                s.revocation_list_refresher
+                    .start(ctx, authority, s.identifier.clone())
+                    .await?;
+            }
+        }
+
         s.initialize_services(ctx, general_options.start_default_services)
             .await?;
         info!("created a node manager for the node: {}", s.node_name);
@@ -417,14 +737,16 @@ impl NodeManager {
         Ok(s)
     }
 
-    async fn configure_trust_context(&mut self, tc: &TrustContextConfig) -> Result<()> {
-        self.trust_context = Some(
-            tc.to_trust_context(
+    pub(super) async fn configure_trust_context(&self, tc: &TrustContextConfig) -> Result<()> {
+        let trust_context = tc
+            .to_trust_context(
                 self.secure_channels.clone(),
                 Some(self.tcp_transport.async_try_clone().await?),
+                self.authority_status.clone(),
             )
-            .await?,
-        );
+            .await?;
+        *self.trust_context_config.write().unwrap() = Some(tc.clone());
+        *self.trust_context.write().unwrap() = Some(trust_context);
 
         info!("NodeManager::configure_trust_context: trust context configured");
 
@@ -447,7 +769,8 @@ impl NodeManager {
             DefaultAddress::RELAY_SERVICE,
             RelayServiceOptions::new()
                 .service_as_consumer(api_flow_control_id)
-                .relay_as_consumer(api_flow_control_id),
+                .relay_as_consumer(api_flow_control_id)
+                .with_quotas(self.relay_quotas.clone()),
         )
         .await?;
 
@@ -456,6 +779,20 @@ impl NodeManager {
             None, // Not checking identifiers here in favor of credential check
             None,
             None,
+            SessionQuota::default(),
+            self.cli_state
+                .nodes
+                .get(&self.node_name)?
+                .config()
+                .setup()
+                .pairing
+                .clone(),
+            false,
+            None,
+            None,
+            RekeyPolicy::default(),
+            BTreeMap::new(),
+            false,
             ctx,
         )
         .await?;
@@ -464,7 +801,7 @@ impl NodeManager {
         if let Ok(tc) = self.trust_context() {
             self.start_credentials_service_impl(
                 ctx,
-                tc.clone(),
+                tc,
                 DefaultAddress::CREDENTIALS_SERVICE.into(),
                 false,
             )
@@ -493,6 +830,11 @@ impl NodeManager {
         self.start_echoer_service_impl(ctx, DefaultAddress::ECHO_SERVICE.into())
             .await?;
 
+        // The chat service only accepts messages received over a secure channel, and the
+        // secure channel listeners add it as a consumer
+        self.start_chat_service_impl(ctx, DefaultAddress::CHAT_SERVICE.into())
+            .await?;
+
         Ok(())
     }
 
@@ -526,6 +868,10 @@ impl NodeManager {
         timeout: Option<Duration>,
     ) -> Result<Connection> {
         debug!(?timeout, "connecting to {}", &addr);
+        self.start_transports(&ctx, addr).await?;
+        if addr.iter().any(|p| p.code() == Ble::CODE) {
+            self.connect_ble_peripherals(&ctx, addr).await?;
+        }
         let connection = ConnectionBuilder::new(addr.clone())
             .instantiate(
                 ctx.clone(),
@@ -602,12 +948,16 @@ impl NodeManagerWorker {
             (Get, ["node"]) => {
                 let node_name = &self.node_manager.node_name();
                 Response::ok(req)
-                    .body(NodeStatus::new(
-                        node_name,
-                        "Running",
-                        ctx.list_workers().await?.len() as u32,
-                        std::process::id() as i32,
-                    ))
+                    .body(
+                        NodeStatus::new(
+                            node_name,
+                            "Running",
+                            ctx.list_workers().await?.len() as u32,
+                            std::process::id() as i32,
+                        )
+                        .with_identifier(self.node_manager.identifier().to_string())
+                        .with_version(self.node_manager.version()),
+                    )
                     .to_vec()?
             }
 
@@ -635,6 +985,26 @@ impl NodeManagerWorker {
                 encode_response(self.delete_tcp_listener(req, dec).await)?
             }
 
+            // ==*== Udp Listeners ==*==
+            #[cfg(feature = "udp")]
+            (Get, ["node", "udp", "listener"]) => self.get_udp_listeners(req).await.to_vec()?,
+            #[cfg(feature = "udp")]
+            (Post, ["node", "udp", "listener"]) => {
+                encode_response(self.create_udp_listener(req, dec, ctx).await)?
+            }
+            #[cfg(feature = "udp")]
+            (Delete, ["node", "udp", "listener"]) => {
+                encode_response(self.delete_udp_listener(req, dec, ctx).await)?
+            }
+
+            // ==*== Udp Hole Punchers ==*==
+            #[cfg(feature = "udp")]
+            (Get, ["node", "udp", "puncher"]) => self.get_udp_punchers(req).await.to_vec()?,
+            #[cfg(feature = "udp")]
+            (Post, ["node", "udp", "puncher"]) => {
+                encode_response(self.create_udp_puncher(req, dec, ctx).await)?
+            }
+
             // ==*== Credential ==*==
             (Post, ["node", "credentials", "actions", "get"]) => self
                 .get_credential(req, dec, ctx)
@@ -643,9 +1013,15 @@ impl NodeManagerWorker {
             (Post, ["node", "credentials", "actions", "present"]) => {
                 encode_response(self.present_credential(req, dec, ctx).await)?
             }
+            (Post, ["node", "credentials", "actions", "refresh"]) => {
+                encode_response(self.refresh_credential(req, dec, ctx).await)?
+            }
 
             // ==*== Secure channels ==*==
             (Get, ["node", "secure_channel"]) => self.list_secure_channels(req).await.to_vec()?,
+            (Get, ["node", "secure_channel_state"]) => {
+                self.list_secure_channels_state(req).await.to_vec()?
+            }
             (Get, ["node", "secure_channel_listener"]) => {
                 self.list_secure_channel_listener(req).await.to_vec()?
             }
@@ -682,38 +1058,58 @@ impl NodeManagerWorker {
             (Post, ["node", "services", DefaultAddress::HOP_SERVICE]) => {
                 encode_response(self.start_hop_service(ctx, req, dec).await)?
             }
+            (Post, ["node", "services", DefaultAddress::RENDEZVOUS_SERVICE]) => {
+                encode_response(self.start_rendezvous_service(ctx, req, dec).await)?
+            }
+            (Post, ["node", "services", DefaultAddress::EXEC_SERVICE]) => {
+                encode_response(self.start_exec_service(ctx, req, dec).await)?
+            }
+            (Post, ["node", "services", DefaultAddress::PROVISIONER_SERVICE]) => {
+                encode_response(self.start_provisioner_service(ctx, req, dec).await)?
+            }
             (Post, ["node", "services", DefaultAddress::CREDENTIALS_SERVICE]) => {
                 encode_response(self.start_credentials_service(ctx, req, dec).await)?
             }
+            #[cfg(feature = "kafka")]
             (Post, ["node", "services", DefaultAddress::KAFKA_OUTLET]) => {
                 self.start_kafka_outlet_service(ctx, req, dec).await?
             }
+            #[cfg(feature = "kafka")]
             (Delete, ["node", "services", DefaultAddress::KAFKA_OUTLET]) => encode_response(
                 self.delete_kafka_service(ctx, req, dec, KafkaServiceKind::Outlet)
                     .await,
             )?,
+            #[cfg(feature = "kafka")]
             (Post, ["node", "services", DefaultAddress::KAFKA_CONSUMER]) => {
                 self.start_kafka_consumer_service(ctx, req, dec).await?
             }
+            #[cfg(feature = "kafka")]
             (Delete, ["node", "services", DefaultAddress::KAFKA_CONSUMER]) => encode_response(
                 self.delete_kafka_service(ctx, req, dec, KafkaServiceKind::Consumer)
                     .await,
             )?,
+            #[cfg(feature = "kafka")]
             (Post, ["node", "services", DefaultAddress::KAFKA_PRODUCER]) => {
                 self.start_kafka_producer_service(ctx, req, dec).await?
             }
+            #[cfg(feature = "kafka")]
             (Delete, ["node", "services", DefaultAddress::KAFKA_PRODUCER]) => encode_response(
                 self.delete_kafka_service(ctx, req, dec, KafkaServiceKind::Producer)
                     .await,
             )?,
+            #[cfg(feature = "kafka")]
             (Post, ["node", "services", DefaultAddress::KAFKA_DIRECT]) => {
                 self.start_kafka_direct_service(ctx, req, dec).await?
             }
+            #[cfg(feature = "kafka")]
             (Delete, ["node", "services", DefaultAddress::KAFKA_DIRECT]) => encode_response(
                 self.delete_kafka_service(ctx, req, dec, KafkaServiceKind::Direct)
                     .await,
             )?,
             (Get, ["node", "services"]) => self.list_services(req).await?,
+            (Get, ["node", "chat", "messages"]) => {
+                encode_response(self.get_chat_messages(req).await)?
+            }
             (Get, ["node", "services", service_type]) => {
                 self.list_services_of_type(req, service_type).await?
             }
@@ -724,6 +1120,10 @@ impl NodeManagerWorker {
                 encode_response(self.show_relay(req, remote_address).await)?
             }
             (Get, ["node", "forwarder"]) => encode_response(self.get_relays(req).await)?,
+            (Post, ["node", "relay_service"]) => encode_response(
+                self.get_relay_service_entries(ctx, req, dec.decode()?)
+                    .await,
+            )?,
             (Delete, ["node", "forwarder", remote_address]) => {
                 encode_response(self.delete_relay(ctx, req, remote_address).await)?
             }
@@ -748,6 +1148,23 @@ impl NodeManagerWorker {
             (Delete, ["node", "inlet", alias]) => {
                 encode_response(self.delete_inlet(req, alias).await)?
             }
+            (Get, ["node", "inlet", alias, "canary"]) => {
+                encode_response(self.show_inlet_canary(req, alias).await)?
+            }
+            (Put, ["node", "inlet", alias, "canary"]) => encode_response(
+                self.start_inlet_canary(ctx, req, alias, dec.decode()?)
+                    .await,
+            )?,
+            (Put, ["node", "inlet", alias, "canary", "weights"]) => encode_response(
+                self.set_inlet_canary_weights(req, alias, dec.decode()?)
+                    .await,
+            )?,
+            (Post, ["node", "inlet", alias, "canary", "promote"]) => {
+                encode_response(self.decide_inlet_canary(req, alias, true).await)?
+            }
+            (Post, ["node", "inlet", alias, "canary", "rollback"]) => {
+                encode_response(self.decide_inlet_canary(req, alias, false).await)?
+            }
             (Delete, ["node", "portal"]) => todo!(),
 
             // ==*== Flow Controls ==*==
@@ -766,6 +1183,16 @@ impl NodeManagerWorker {
 
                 Response::ok(req).body(WorkerList::new(list)).to_vec()?
             }
+            (Get, ["node", "stats"]) => encode_response(self.get_stats(ctx, req).await)?,
+            (Get, ["node", "debug", "heap"]) => {
+                encode_response(self.get_heap_stats(ctx, req).await)?
+            }
+            (Post, ["node", "reload"]) => encode_response(self.reload(req).await)?,
+
+            // ==*== Secrets ==*==
+            (Get, ["node", "secrets"]) => Response::ok(req)
+                .body(self.node_manager.secrets().names())
+                .to_vec()?,
             (Post, ["policy", resource, action]) => encode_response(
                 self.node_manager
                     .add_policy(resource, action, req, dec)
@@ -785,6 +1212,12 @@ impl NodeManagerWorker {
 
             // ==*== Messages ==*==
             (Post, ["v0", "message"]) => self.send_message(ctx, req, dec).await?,
+            (Post, ["v0", "message", "ping"]) => {
+                encode_response(self.ping_route(ctx, req, dec).await)?
+            }
+            (Post, ["v0", "message", "trace"]) => {
+                encode_response(self.trace_route(ctx, req, dec).await)?
+            }
 
             // ==*== Catch-all for Unimplemented APIs ==*==
             _ => {
@@ -803,6 +1236,8 @@ impl Worker for NodeManagerWorker {
     type Context = Context;
 
     async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.node_manager.credential_refresher.stop();
+        self.node_manager.revocation_list_refresher.stop();
         self.node_manager.medic_handle.stop_medic(ctx).await
     }
 
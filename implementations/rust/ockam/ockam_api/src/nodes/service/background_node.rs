@@ -155,8 +155,12 @@ impl BackgroundNode {
     async fn create_route(&self) -> miette::Result<Route> {
         let mut route = self.to.clone();
         let node_state = self.cli_state.nodes.get(&self.node_name)?;
-        let port = node_state.config().setup().api_transport()?.addr.port();
-        let addr_str = format!("localhost:{port}");
+        let addr_str = node_state
+            .config()
+            .setup()
+            .api_transport()?
+            .addr
+            .local_peer();
         let addr = self
             .tcp_transport
             .connect(addr_str, TcpConnectionOptions::new())
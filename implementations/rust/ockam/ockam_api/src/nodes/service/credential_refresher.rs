@@ -0,0 +1,124 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use ockam::identity::utils::{add_seconds, now};
+use ockam::identity::{AuthorityService, Identifier};
+use ockam::{Address, Context, Result};
+use ockam_core::{AllowAll, DenyAll};
+
+/// Delay between two checks of the credential expiration
+const CHECK_DELAY: Duration = Duration::from_secs(30);
+
+/// Number of seconds before its expiration when a credential gets refreshed
+const REFRESH_BEFORE_EXPIRATION: u64 = 5 * 60;
+
+/// Handle on a background task which tracks the expiration of the credential cached by an authority
+/// and retrieves a new one before it lapses, so that the credentials presented or attached
+/// to secure channels stay valid
+#[derive(Default)]
+pub(crate) struct CredentialRefresherHandle {
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl CredentialRefresherHandle {
+    /// Return true if the credential refresher is running
+    pub(crate) fn is_running(&self) -> bool {
+        self.handle
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|h| !h.is_finished())
+            .unwrap_or(false)
+    }
+
+    /// Start refreshing the credential of the subject. A previously started refresher is stopped
+    pub(crate) async fn start(
+        &self,
+        ctx: &Context,
+        authority: AuthorityService,
+        subject: Identifier,
+    ) -> Result<()> {
+        let ctx = ctx
+            .new_detached(
+                Address::random_tagged("CredentialRefresher.ctx"),
+                DenyAll,
+                AllowAll,
+            )
+            .await?;
+        let handle = tokio::spawn(refresh_credential(ctx, authority, subject));
+        if let Some(previous) = self.handle.lock().unwrap().replace(handle) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    /// Stop the credential refresher
+    pub(crate) fn stop(&self) {
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+/// Continuously check the expiration of the cached credential.
+///
+/// This function never returns. Failed refreshes are retried on the next check
+async fn refresh_credential(ctx: Context, authority: AuthorityService, subject: Identifier) {
+    loop {
+        tokio::time::sleep(CHECK_DELAY).await;
+
+        // there is nothing to refresh until a credential has been retrieved
+        let expires_at = match authority.cached_credential_expires_at() {
+            Some(expires_at) => expires_at,
+            None => continue,
+        };
+        let now = match now() {
+            Ok(now) => now,
+            Err(e) => {
+                warn!("cannot check the credential expiration: {e}");
+                continue;
+            }
+        };
+        if expires_at > add_seconds(&now, REFRESH_BEFORE_EXPIRATION) {
+            continue;
+        }
+
+        debug!("the credential of {subject} expires at {expires_at:?}, refreshing it");
+        match authority.refresh_credential(&ctx, &subject).await {
+            Ok(_) => info!("refreshed the credential of {subject}"),
+            Err(e) => warn!("the credential of {subject} could not be refreshed: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::start_manager_for_tests;
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test]
+    async fn credential_refresher__refreshed_credential__is_tracked(
+        context: &mut Context,
+    ) -> Result<()> {
+        let handler = start_manager_for_tests(context).await?;
+        let authority = handler.node_manager.trust_context()?.authority()?.clone();
+        let identifier = handler.node_manager.identifier().clone();
+
+        authority.refresh_credential(context, &identifier).await?;
+        let expires_at = authority.cached_credential_expires_at().unwrap();
+        assert!(expires_at > now()?);
+
+        let refresher = CredentialRefresherHandle::default();
+        assert!(!refresher.is_running());
+        refresher.start(context, authority, identifier).await?;
+        assert!(refresher.is_running());
+        refresher.stop();
+        assert!(!refresher.is_running());
+
+        context.stop().await
+    }
+}
@@ -12,10 +12,13 @@ use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 
 use crate::cli_state::traits::StateDirTrait;
+use crate::cli_state::IdentityUsageKind;
 use crate::cloud::AuthorityNode;
 use crate::error::ApiError;
 use crate::local_multiaddr_to_route;
-use crate::nodes::models::credentials::{GetCredentialRequest, PresentCredentialRequest};
+use crate::nodes::models::credentials::{
+    GetCredentialRequest, PresentCredentialRequest, RefreshCredentialRequest,
+};
 use crate::nodes::BackgroundNode;
 
 use super::NodeManagerWorker;
@@ -44,6 +47,12 @@ pub trait Credentials {
         to: &MultiAddr,
         oneway: bool,
     ) -> miette::Result<()>;
+
+    async fn refresh_credential(
+        &self,
+        ctx: &Context,
+        auto: bool,
+    ) -> miette::Result<CredentialAndPurposeKey>;
 }
 
 #[async_trait]
@@ -79,6 +88,21 @@ impl Credentials for AuthorityNode {
             .success()
             .into_diagnostic()
     }
+
+    async fn refresh_credential(
+        &self,
+        ctx: &Context,
+        auto: bool,
+    ) -> miette::Result<CredentialAndPurposeKey> {
+        let body = RefreshCredentialRequest::new(auto);
+        let req = Request::post("/node/credentials/actions/refresh").body(body);
+        self.0
+            .ask(ctx, "", req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
 }
 
 #[async_trait]
@@ -110,6 +134,19 @@ impl Credentials for BackgroundNode {
         )
         .await
     }
+
+    async fn refresh_credential(
+        &self,
+        ctx: &Context,
+        auto: bool,
+    ) -> miette::Result<CredentialAndPurposeKey> {
+        let body = RefreshCredentialRequest::new(auto);
+        self.ask(
+            ctx,
+            Request::post("/node/credentials/actions/refresh").body(body),
+        )
+        .await
+    }
 }
 
 impl NodeManagerWorker {
@@ -193,8 +230,36 @@ impl NodeManagerWorker {
                 )
                 .await?;
         }
+        self.node_manager.record_identity_usage(
+            self.node_manager.identifier(),
+            IdentityUsageKind::CredentialPresentation,
+        );
 
         let response = Response::ok(req);
         Ok(response)
     }
+
+    /// Retrieve a new credential for the node from its authority, and optionally keep
+    /// refreshing it in the background before it expires
+    pub(super) async fn refresh_credential(
+        &self,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+        ctx: &Context,
+    ) -> Result<Response<CredentialAndPurposeKey>, Response<Error>> {
+        let request: RefreshCredentialRequest = dec.decode()?;
+
+        let authority = self.node_manager.trust_context()?.authority()?.clone();
+        let identifier = self.node_manager.identifier().clone();
+        let credential = authority.refresh_credential(ctx, &identifier).await?;
+
+        if request.auto && !self.node_manager.credential_refresher.is_running() {
+            self.node_manager
+                .credential_refresher
+                .start(ctx, authority, identifier)
+                .await?;
+        }
+
+        Ok(Response::ok(req).body(credential))
+    }
 }
@@ -142,6 +142,8 @@ impl InMemoryNode {
     }
 
     pub async fn stop(&self, ctx: &Context) -> Result<()> {
+        self.credential_refresher.stop();
+        self.revocation_list_refresher.stop();
         self.medic_handle.stop_medic(ctx).await?;
         for addr in DefaultAddress::iter() {
             let result = ctx.stop_worker(addr).await;
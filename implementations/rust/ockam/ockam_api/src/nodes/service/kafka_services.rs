@@ -0,0 +1,448 @@
+use std::net::IpAddr;
+
+use minicbor::Decoder;
+
+use ockam::{Address, Context, Result};
+use ockam_abac::expr::{eq, ident, str};
+use ockam_core::api::{Error, RequestHeader, Response};
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::route;
+use ockam_multiaddr::MultiAddr;
+
+use crate::error::ApiError;
+use crate::kafka::{
+    ConsumerNodeAddr, KafkaInletController, KafkaPortalListener, KafkaSecureChannelControllerImpl,
+    KAFKA_OUTLET_BOOTSTRAP_ADDRESS, KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
+};
+use crate::kafka::{OutletManagerService, PrefixRelayService};
+use crate::nodes::models::portal::DataFlow;
+use crate::nodes::models::quota::SessionQuota;
+use crate::nodes::models::services::{
+    DeleteServiceRequest, StartKafkaConsumerRequest, StartKafkaDirectRequest,
+    StartKafkaOutletRequest, StartKafkaProducerRequest, StartServiceRequest,
+};
+use crate::nodes::registry::{KafkaServiceInfo, KafkaServiceKind};
+use crate::port_range::PortRange;
+use crate::DefaultAddress;
+use crate::{actions, resources};
+
+use super::NodeManagerWorker;
+
+impl NodeManagerWorker {
+    pub(super) async fn start_kafka_outlet_service(
+        &self,
+        context: &Context,
+        request: &RequestHeader,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Vec<u8>> {
+        let body: StartServiceRequest<StartKafkaOutletRequest> = dec.decode()?;
+
+        let default_secure_channel_listener_flow_control_id = context
+            .flow_controls()
+            .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
+            .ok_or_else(|| {
+                ApiError::core("Unable to get flow control for secure channel listener")
+            })?;
+
+        // The secrets are only resolved here, the request only contains their references
+        let sasl = body
+            .request()
+            .sasl
+            .as_ref()
+            .map(|sasl| sasl.resolve(self.node_manager.secrets()))
+            .transpose()?;
+
+        PrefixRelayService::create(
+            context,
+            default_secure_channel_listener_flow_control_id.clone(),
+        )
+        .await?;
+
+        {
+            OutletManagerService::create(
+                context,
+                self.node_manager.secure_channels.clone(),
+                self.node_manager.trust_context()?.id(),
+                default_secure_channel_listener_flow_control_id,
+                sasl,
+            )
+            .await?;
+        }
+
+        if let Err(e) = self
+            .node_manager
+            .create_outlet(
+                context,
+                body.request().bootstrap_server_addr,
+                KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into(),
+                Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.to_string()),
+                false,
+                DataFlow::default(),
+                SessionQuota::default(),
+                None,
+            )
+            .await
+        {
+            return Ok(e.to_string().into_bytes());
+        };
+
+        {
+            self.node_manager
+                .registry
+                .kafka_services
+                .insert(
+                    body.address().into(),
+                    KafkaServiceInfo::new(KafkaServiceKind::Outlet),
+                )
+                .await;
+        }
+
+        Ok(Response::ok(request).to_vec()?)
+    }
+
+    pub(super) async fn start_kafka_direct_service(
+        &self,
+        context: &Context,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Vec<u8>> {
+        let body: StartServiceRequest<StartKafkaDirectRequest> = dec.decode()?;
+        let listener_address: Address = body.address().into();
+        let body_req = body.request();
+
+        let consumer_route: Option<MultiAddr> =
+            if let Some(consumer_route) = body_req.consumer_route() {
+                Some(consumer_route.parse()?)
+            } else {
+                None
+            };
+
+        if let Err(e) = self
+            .start_direct_kafka_service_impl(
+                context,
+                listener_address,
+                body_req.bind_address().ip(),
+                body_req.bind_address().port(),
+                body_req.brokers_port_range(),
+                *body_req.bootstrap_server_addr(),
+                consumer_route,
+            )
+            .await
+        {
+            return Ok(e.to_vec()?);
+        };
+
+        Ok(Response::ok(req).to_vec()?)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn start_direct_kafka_service_impl(
+        &self,
+        context: &Context,
+        local_interceptor_address: Address,
+        bind_ip: IpAddr,
+        server_bootstrap_port: u16,
+        brokers_port_range: (u16, u16),
+        bootstrap_server_addr: SocketAddr,
+        consumer_route: Option<MultiAddr>,
+    ) -> Result<(), Response<Error>> {
+        let default_secure_channel_listener_flow_control_id = context
+            .flow_controls()
+            .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
+            .ok_or_else(|| {
+                ApiError::core("Unable to get flow control for secure channel listener")
+            })?;
+
+        {
+            OutletManagerService::create(
+                context,
+                self.node_manager.secure_channels.clone(),
+                self.node_manager.trust_context()?.id(),
+                default_secure_channel_listener_flow_control_id,
+                None,
+            )
+            .await?;
+        }
+
+        self.node_manager
+            .create_outlet(
+                context,
+                bootstrap_server_addr,
+                KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into(),
+                Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.to_string()),
+                false,
+                DataFlow::default(),
+                SessionQuota::default(),
+                None,
+            )
+            .await?;
+
+        let trust_context_id;
+        let secure_channels;
+        {
+            trust_context_id = self.node_manager.trust_context()?.id().to_string();
+            secure_channels = self.node_manager.secure_channels.clone();
+        }
+
+        let secure_channel_controller = KafkaSecureChannelControllerImpl::new(
+            secure_channels,
+            ConsumerNodeAddr::Direct(consumer_route.clone()),
+            trust_context_id,
+        );
+
+        let inlet_controller = KafkaInletController::new(
+            "/secure/api".parse().unwrap(),
+            route![local_interceptor_address.clone()],
+            route![KAFKA_OUTLET_INTERCEPTOR_ADDRESS],
+            bind_ip,
+            PortRange::try_from(brokers_port_range)
+                .map_err(|_| ApiError::core("invalid port range"))?,
+        );
+
+        // since we cannot call APIs of node manager via message due to the read/write lock
+        // we need to call it directly
+        self.node_manager
+            .create_inlet(
+                context,
+                SocketAddr::new(bind_ip, server_bootstrap_port).to_string(),
+                None,
+                route![local_interceptor_address.clone()],
+                route![
+                    KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
+                    KAFKA_OUTLET_BOOTSTRAP_ADDRESS
+                ],
+                "/secure/api".parse().unwrap(),
+                vec![],
+                None,
+                None,
+                DataFlow::default(),
+                SessionQuota::default(),
+                None,
+            )
+            .await?;
+
+        KafkaPortalListener::create(
+            context,
+            inlet_controller,
+            secure_channel_controller.into_trait(),
+            local_interceptor_address.clone(),
+        )
+        .await?;
+
+        {
+            self.node_manager
+                .registry
+                .kafka_services
+                .insert(
+                    local_interceptor_address,
+                    KafkaServiceInfo::new(KafkaServiceKind::Direct),
+                )
+                .await;
+        }
+
+        Ok(())
+    }
+
+    pub(super) async fn start_kafka_consumer_service(
+        &self,
+        context: &Context,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Vec<u8>> {
+        let body: StartServiceRequest<StartKafkaConsumerRequest> = dec.decode()?;
+        let listener_address: Address = body.address().into();
+        let body_req = body.request();
+        let outlet_node_multiaddr = body_req.project_route().to_string().parse()?;
+
+        if let Err(e) = self
+            .start_kafka_service_impl(
+                context,
+                listener_address,
+                body_req.bootstrap_server_addr.ip(),
+                body_req.bootstrap_server_addr.port(),
+                body_req.brokers_port_range(),
+                outlet_node_multiaddr,
+                KafkaServiceKind::Consumer,
+            )
+            .await
+        {
+            return Ok(e.to_vec()?);
+        };
+
+        Ok(Response::ok(req).to_vec()?)
+    }
+
+    pub(super) async fn start_kafka_producer_service(
+        &mut self,
+        context: &Context,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Vec<u8>> {
+        let body: StartServiceRequest<StartKafkaProducerRequest> = dec.decode()?;
+        let listener_address: Address = body.address().into();
+        let body_req = body.request();
+        let outlet_node_multiaddr = body_req.project_route().to_string().parse()?;
+
+        if let Err(e) = self
+            .start_kafka_service_impl(
+                context,
+                listener_address,
+                body_req.bootstrap_server_addr.ip(),
+                body_req.bootstrap_server_addr.port(),
+                body_req.brokers_port_range(),
+                outlet_node_multiaddr,
+                KafkaServiceKind::Producer,
+            )
+            .await
+        {
+            return Ok(e.to_vec()?);
+        };
+
+        Ok(Response::ok(req).to_vec()?)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn start_kafka_service_impl(
+        &self,
+        context: &Context,
+        local_interceptor_address: Address,
+        bind_ip: IpAddr,
+        server_bootstrap_port: u16,
+        brokers_port_range: (u16, u16),
+        outlet_node_multiaddr: MultiAddr,
+        kind: KafkaServiceKind,
+    ) -> Result<(), Response<Error>> {
+        debug!(
+            "outlet_node_multiaddr: {}",
+            outlet_node_multiaddr.to_string()
+        );
+
+        let trust_context_id;
+        let secure_channels;
+        {
+            trust_context_id = self.node_manager.trust_context()?.id().to_string();
+            secure_channels = self.node_manager.secure_channels.clone();
+
+            if let Some(project) = outlet_node_multiaddr.first().and_then(|value| {
+                value
+                    .cast::<ockam_multiaddr::proto::Project>()
+                    .map(|p| p.to_string())
+            }) {
+                let (_, project_identifier) = self.node_manager.resolve_project(&project).await?;
+                // if we are using the project we need to allow safe communication based on the
+                // project identifier
+                self.node_manager
+                    .policies
+                    .set_policy(
+                        &resources::INLET,
+                        &actions::HANDLE_MESSAGE,
+                        &eq([ident("subject.identifier"), str(project_identifier)]),
+                    )
+                    .await?;
+            }
+        }
+
+        let secure_channel_controller = KafkaSecureChannelControllerImpl::new(
+            secure_channels,
+            ConsumerNodeAddr::Relay(outlet_node_multiaddr.clone()),
+            trust_context_id,
+        );
+
+        let inlet_controller = KafkaInletController::new(
+            outlet_node_multiaddr.clone(),
+            route![local_interceptor_address.clone()],
+            route![KAFKA_OUTLET_INTERCEPTOR_ADDRESS],
+            bind_ip,
+            PortRange::try_from(brokers_port_range)
+                .map_err(|_| ApiError::core("invalid port range"))?,
+        );
+
+        // since we cannot call APIs of node manager via message due to the read/write lock
+        // we need to call it directly
+        self.node_manager
+            .create_inlet(
+                context,
+                SocketAddr::new(bind_ip, server_bootstrap_port).to_string(),
+                None,
+                route![local_interceptor_address.clone()],
+                route![
+                    KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
+                    KAFKA_OUTLET_BOOTSTRAP_ADDRESS
+                ],
+                outlet_node_multiaddr,
+                vec![],
+                None,
+                None,
+                DataFlow::default(),
+                SessionQuota::default(),
+                None,
+            )
+            .await?;
+
+        KafkaPortalListener::create(
+            context,
+            inlet_controller,
+            secure_channel_controller.into_trait(),
+            local_interceptor_address.clone(),
+        )
+        .await?;
+
+        {
+            self.node_manager
+                .registry
+                .kafka_services
+                .insert(local_interceptor_address, KafkaServiceInfo::new(kind))
+                .await;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn delete_kafka_service(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+        kind: KafkaServiceKind,
+    ) -> Result<Response, Response<Error>> {
+        let body: DeleteServiceRequest = match dec.decode() {
+            Ok(it) => it,
+            Err(err) => {
+                return Err(Response::bad_request(req, &err.to_string()));
+            }
+        };
+        let address = body.address();
+        let res = match self
+            .node_manager
+            .registry
+            .kafka_services
+            .get(&address)
+            .await
+        {
+            None => {
+                return Err(Response::not_found(
+                    req,
+                    &format!("Service at address '{}' not found", address),
+                ));
+            }
+            Some(e) => {
+                if kind.eq(e.kind()) {
+                    ctx.stop_worker(address.clone()).await?;
+                    self.node_manager
+                        .registry
+                        .kafka_services
+                        .remove(&address)
+                        .await;
+                    Response::ok(req)
+                } else {
+                    error!(address = %address, "Service is not a kafka {}", kind.to_string());
+                    return Err(Response::internal_error(
+                        req,
+                        &format!("Service at address '{}' is not a kafka {}", address, kind),
+                    ));
+                }
+            }
+        };
+        Ok(res)
+    }
+}
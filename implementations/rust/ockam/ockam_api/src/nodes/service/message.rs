@@ -1,18 +1,20 @@
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tracing::trace;
 
 use minicbor::Decoder;
 use minicbor::{Decode, Encode};
 
-use ockam_core::api::{RequestHeader, Response};
-use ockam_core::{self, async_trait, AsyncTryClone, Result};
+use ockam_core::api::{Error, RequestHeader, Response};
+use ockam_core::{self, async_trait, route, AsyncTryClone, Result, Route};
+use ockam_multiaddr::proto::Service;
 use ockam_multiaddr::MultiAddr;
 use ockam_node::{Context, MessageSendReceiveOptions};
 
 use crate::error::ApiError;
 use crate::nodes::{NodeManager, NodeManagerWorker};
+use crate::DefaultAddress;
 
 const TARGET: &str = "ockam_api::message";
 
@@ -39,7 +41,110 @@ impl SendMessage {
     }
 }
 
+/// Request to measure the round-trip time of a route with pings to the echo service
+/// of the node at the end of the route
+#[derive(Encode, Decode, Debug)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PingRoute {
+    #[n(1)] pub route: String,
+    /// Number of pings
+    #[n(2)] pub count: u32,
+    /// Time after which a ping is considered lost
+    #[n(3)] pub timeout: Duration,
+}
+
+impl PingRoute {
+    pub fn new(route: &MultiAddr, count: u32, timeout: Duration) -> Self {
+        Self {
+            route: route.to_string(),
+            count,
+            timeout,
+        }
+    }
+}
+
+/// Request to measure the round-trip time to each hop of a route
+#[derive(Encode, Decode, Debug)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceRoute {
+    #[n(1)] pub route: String,
+    /// Maximum number of hops which are probed
+    #[n(2)] pub max_hops: u8,
+    /// Time after which a hop is considered unreachable
+    #[n(3)] pub timeout: Duration,
+}
+
+impl TraceRoute {
+    pub fn new(route: &MultiAddr, max_hops: u8, timeout: Duration) -> Self {
+        Self {
+            route: route.to_string(),
+            max_hops,
+            timeout,
+        }
+    }
+}
+
+/// Round-trip times of the pings sent to a route. A lost ping has no round-trip time
+#[derive(Encode, Decode, Debug, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PingReport {
+    #[n(1)] pub route: String,
+    #[n(2)] pub rtts: Vec<Option<Duration>>,
+}
+
+/// Hop of a traced route, with the round-trip time to the node reached after this hop
+#[derive(Encode, Decode, Debug, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceHop {
+    #[n(1)] pub address: String,
+    #[n(2)] pub rtt: Option<Duration>,
+}
+
 impl NodeManagerWorker {
+    pub(crate) async fn ping_route(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Response<PingReport>, Response<Error>> {
+        let request: PingRoute = dec.decode()?;
+        let addr = MultiAddr::from_str(&request.route).map_err(|_| {
+            Response::bad_request(req, &format!("Invalid route: {}", request.route))
+        })?;
+        match self
+            .node_manager
+            .ping_route(ctx, &addr, request.count, request.timeout)
+            .await
+        {
+            Ok(report) => Ok(Response::ok(req).body(report)),
+            Err(e) => Err(Response::internal_error(req, &e.to_string())),
+        }
+    }
+
+    pub(crate) async fn trace_route(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Response<Vec<TraceHop>>, Response<Error>> {
+        let request: TraceRoute = dec.decode()?;
+        let addr = MultiAddr::from_str(&request.route).map_err(|_| {
+            Response::bad_request(req, &format!("Invalid route: {}", request.route))
+        })?;
+        match self
+            .node_manager
+            .trace_route(ctx, &addr, request.max_hops, request.timeout)
+            .await
+        {
+            Ok(hops) => Ok(Response::ok(req).body(hops)),
+            Err(e) => Err(Response::internal_error(req, &e.to_string())),
+        }
+    }
+
     pub(crate) async fn send_message(
         &self,
         ctx: &Context,
@@ -93,6 +198,96 @@ impl MessageSender for NodeManager {
     }
 }
 
+impl NodeManager {
+    /// Send pings to the echo service of the node at the end of a route.
+    /// The connection to the node is only made once, so that it is not included in the
+    /// round-trip times
+    pub async fn ping_route(
+        &self,
+        ctx: &Context,
+        addr: &MultiAddr,
+        count: u32,
+        timeout: Duration,
+    ) -> Result<PingReport> {
+        let route = self.echo_route(ctx, addr, timeout).await?;
+        let mut rtts = vec![];
+        for _ in 0..count {
+            rtts.push(ping(ctx, route.clone(), timeout).await.ok());
+        }
+        Ok(PingReport {
+            route: route.to_string(),
+            rtts,
+        })
+    }
+
+    /// Probe each hop of a route, like traceroute.
+    ///
+    /// A hop limit of `n` keeps the `n` first addresses of the route and sends a ping to the
+    /// echo service of the node where the message arrives after these hops: the remote node of a
+    /// transport connection, the other end of a secure channel, or the node registered on a relay
+    pub async fn trace_route(
+        &self,
+        ctx: &Context,
+        addr: &MultiAddr,
+        max_hops: u8,
+        timeout: Duration,
+    ) -> Result<Vec<TraceHop>> {
+        let route = self.echo_route(ctx, addr, timeout).await?;
+        let addresses: Vec<_> = route.iter().cloned().collect();
+        let mut hops = vec![];
+        for hop_limit in 1..=addresses.len().min(max_hops as usize) {
+            let address = &addresses[hop_limit - 1];
+            let probed_route = if hop_limit == addresses.len() {
+                route.clone()
+            } else {
+                let prefix = addresses[..hop_limit]
+                    .iter()
+                    .fold(Route::new(), |r, a| r.append(a.clone()));
+                route![prefix, DefaultAddress::ECHO_SERVICE]
+            };
+            let rtt = ping(ctx, probed_route, timeout).await.ok();
+            trace!(target: TARGET, %address, ?rtt, "hop probed");
+            hops.push(TraceHop {
+                address: address.to_string(),
+                rtt,
+            });
+        }
+        Ok(hops)
+    }
+
+    /// Connect to the node at the end of a route and return the route to its echo service
+    async fn echo_route(
+        &self,
+        ctx: &Context,
+        addr: &MultiAddr,
+        timeout: Duration,
+    ) -> Result<Route> {
+        let mut addr = addr.clone();
+        if addr.last().and_then(|p| p.cast::<Service>()).as_deref()
+            != Some(DefaultAddress::ECHO_SERVICE)
+        {
+            addr.push_back(Service::new(DefaultAddress::ECHO_SERVICE))?;
+        }
+        let connection_ctx = Arc::new(ctx.async_try_clone().await?);
+        let connection = self
+            .make_connection(connection_ctx, &addr, None, None, None, Some(timeout))
+            .await?;
+        connection.route(self.tcp_transport()).await
+    }
+}
+
+/// Return the round-trip time of a small message sent to an echo service
+async fn ping(ctx: &Context, route: Route, timeout: Duration) -> Result<Duration> {
+    let start = Instant::now();
+    ctx.send_and_receive_extended::<Vec<u8>>(
+        route,
+        b"ping".to_vec(),
+        MessageSendReceiveOptions::new().with_timeout(timeout),
+    )
+    .await?;
+    Ok(start.elapsed())
+}
+
 #[async_trait]
 pub trait MessageSender {
     async fn send_message(
@@ -1,40 +1,34 @@
-use std::net::IpAddr;
+use std::time::Duration;
 
 use minicbor::Decoder;
 
 use ockam::identity::{identities, AuthorityService, TrustContext};
 use ockam::{Address, Context, Result};
-use ockam_abac::expr::{eq, ident, str};
 use ockam_abac::Resource;
 use ockam_core::api::{Error, RequestHeader, Response};
-use ockam_core::compat::net::SocketAddr;
-use ockam_core::route;
-use ockam_multiaddr::MultiAddr;
 use ockam_node::WorkerBuilder;
+#[cfg(feature = "udp")]
+use ockam_transport_udp::UdpRendezvousService;
 
+use crate::actions;
 use crate::auth::Server;
+use crate::chat::{ChatMessage, ChatService};
 use crate::echoer::Echoer;
 use crate::error::ApiError;
+use crate::exec::{AllowedCommand, ExecService, DEFAULT_EXEC_TIMEOUT};
 use crate::hop::Hop;
-use crate::kafka::{
-    ConsumerNodeAddr, KafkaInletController, KafkaPortalListener, KafkaSecureChannelControllerImpl,
-    KAFKA_OUTLET_BOOTSTRAP_ADDRESS, KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
-};
-use crate::kafka::{OutletManagerService, PrefixRelayService};
 use crate::nodes::models::services::{
-    DeleteServiceRequest, ServiceList, ServiceStatus, StartAuthenticatedServiceRequest,
-    StartCredentialsService, StartEchoerServiceRequest, StartHopServiceRequest,
-    StartKafkaConsumerRequest, StartKafkaDirectRequest, StartKafkaOutletRequest,
-    StartKafkaProducerRequest, StartServiceRequest, StartUppercaseServiceRequest,
-};
-use crate::nodes::registry::{
-    CredentialsServiceInfo, KafkaServiceInfo, KafkaServiceKind, Registry,
+    ServiceList, ServiceStatus, StartAuthenticatedServiceRequest, StartCredentialsService,
+    StartEchoerServiceRequest, StartExecServiceRequest, StartHopServiceRequest,
+    StartProvisionerServiceRequest, StartRendezvousServiceRequest, StartUppercaseServiceRequest,
 };
+#[cfg(feature = "kafka")]
+use crate::nodes::registry::KafkaServiceKind;
+use crate::nodes::registry::{ChatServiceInfo, CredentialsServiceInfo, Registry};
 use crate::nodes::NodeManager;
-use crate::port_range::PortRange;
+use crate::provisioner::{ProvisionerService, ProvisioningTarget};
 use crate::uppercase::Uppercase;
 use crate::DefaultAddress;
-use crate::{actions, resources};
 
 use super::NodeManagerWorker;
 
@@ -123,13 +117,13 @@ impl NodeManager {
             return Err(ApiError::core("Echoer service exists at this address"));
         }
 
-        let maybe_trust_context_id = self.trust_context.as_ref().map(|c| c.id());
+        let maybe_trust_context_id = self.trust_context().ok().map(|c| c.id().to_string());
         let resource = Resource::assert_inline(addr.address());
         let ac = self
             .access_control(
                 &resource,
                 &actions::HANDLE_MESSAGE,
-                maybe_trust_context_id,
+                maybe_trust_context_id.as_deref(),
                 None,
             )
             .await?;
@@ -148,6 +142,151 @@ impl NodeManager {
         Ok(())
     }
 
+    pub(super) async fn start_chat_service_impl(&self, ctx: &Context, addr: Address) -> Result<()> {
+        if self.registry.chat_services.contains_key(&addr).await {
+            return Err(ApiError::core("Chat service exists at this address"));
+        }
+
+        let maybe_trust_context_id = self.trust_context().ok().map(|c| c.id().to_string());
+        let resource = Resource::assert_inline(addr.address());
+        let ac = self
+            .access_control(
+                &resource,
+                &actions::HANDLE_MESSAGE,
+                maybe_trust_context_id.as_deref(),
+                None,
+            )
+            .await?;
+
+        let info = ChatServiceInfo::default();
+        WorkerBuilder::new(ChatService::new(
+            self.identifier().clone(),
+            info.inbox.clone(),
+        ))
+        .with_address(addr.clone())
+        .with_incoming_access_control_arc(ac)
+        .start(ctx)
+        .await?;
+
+        self.registry.chat_services.insert(addr, info).await;
+
+        Ok(())
+    }
+
+    /// Remove and return the chat messages received for the identity of the node
+    pub async fn take_chat_messages(&self) -> Vec<ChatMessage> {
+        self.registry
+            .chat_services
+            .values()
+            .await
+            .iter()
+            .flat_map(|info| info.inbox.take(self.identifier()))
+            .collect()
+    }
+
+    pub(super) async fn start_exec_service_impl(
+        &self,
+        ctx: &Context,
+        addr: Address,
+        allowed_commands: Vec<AllowedCommand>,
+        timeout: Duration,
+    ) -> Result<()> {
+        if self.registry.exec_services.contains_key(&addr).await {
+            return Err(ApiError::core("Exec service exists at this address"));
+        }
+        if allowed_commands.is_empty() {
+            return Err(ApiError::core(
+                "At least one command must be allowed to start an Exec service",
+            ));
+        }
+
+        // The commands must never be run on behalf of any identity
+        let resource = Resource::assert_inline(addr.address());
+        let ac = self
+            .restricted_access_control(&resource, &actions::HANDLE_MESSAGE)
+            .await?;
+
+        info!(
+            target: crate::exec::AUDIT_TARGET,
+            %addr,
+            allowed_commands = ?allowed_commands.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+            "exec service started"
+        );
+        for listener in self.registry.secure_channel_listeners.values().await {
+            ctx.flow_controls()
+                .add_consumer(addr.clone(), listener.listener().flow_control_id());
+        }
+        WorkerBuilder::new(ExecService::new(allowed_commands, timeout))
+            .with_address(addr.clone())
+            .with_incoming_access_control_arc(ac)
+            .start(ctx)
+            .await?;
+
+        self.registry
+            .exec_services
+            .insert(addr, Default::default())
+            .await;
+
+        Ok(())
+    }
+
+    pub(super) async fn start_provisioner_service_impl(
+        &self,
+        ctx: &Context,
+        addr: Address,
+        attribute: String,
+        targets: Vec<ProvisioningTarget>,
+    ) -> Result<()> {
+        if self.registry.provisioner_services.contains_key(&addr).await {
+            return Err(ApiError::core("Provisioner service exists at this address"));
+        }
+        if targets.is_empty() {
+            return Err(ApiError::core(
+                "At least one target must be given to start a Provisioner service",
+            ));
+        }
+
+        let maybe_trust_context_id = self.trust_context().ok().map(|c| c.id().to_string());
+        let resource = Resource::assert_inline(addr.address());
+        let ac = self
+            .access_control(
+                &resource,
+                &actions::HANDLE_MESSAGE,
+                maybe_trust_context_id.as_deref(),
+                None,
+            )
+            .await?;
+
+        info!(
+            %addr,
+            %attribute,
+            targets = ?targets.iter().map(|t| t.to_string()).collect::<Vec<_>>(),
+            "provisioner service started"
+        );
+        for listener in self.registry.secure_channel_listeners.values().await {
+            ctx.flow_controls()
+                .add_consumer(addr.clone(), listener.listener().flow_control_id());
+        }
+        let service = ProvisionerService::new(
+            attribute,
+            targets,
+            self.attributes_reader(),
+            self.secure_channels.secure_channel_registry(),
+        );
+        WorkerBuilder::new(service)
+            .with_address(addr.clone())
+            .with_incoming_access_control_arc(ac)
+            .start(ctx)
+            .await?;
+
+        self.registry
+            .provisioner_services
+            .insert(addr, Default::default())
+            .await;
+
+        Ok(())
+    }
+
     pub(super) async fn start_hop_service_impl(&self, ctx: &Context, addr: Address) -> Result<()> {
         if self.registry.hop_services.contains_key(&addr).await {
             return Err(ApiError::core("Hop service exists at this address"));
@@ -165,6 +304,45 @@ impl NodeManager {
 
         Ok(())
     }
+
+    #[cfg(feature = "udp")]
+    pub(super) async fn start_rendezvous_service_impl(
+        &self,
+        ctx: &Context,
+        addr: Address,
+    ) -> Result<()> {
+        if self.registry.rendezvous_services.contains_key(&addr).await {
+            return Err(ApiError::core("Rendezvous service exists at this address"));
+        }
+
+        self.udp_transport(ctx).await?;
+
+        // The punchers reach the service via the UDP listeners
+        for listener in self.registry.udp_listeners.values().await {
+            ctx.flow_controls()
+                .add_consumer(addr.clone(), &listener.flow_control_id);
+        }
+
+        UdpRendezvousService::start(ctx, addr.clone()).await?;
+
+        self.registry
+            .rendezvous_services
+            .insert(addr, Default::default())
+            .await;
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "udp"))]
+    pub(super) async fn start_rendezvous_service_impl(
+        &self,
+        _ctx: &Context,
+        addr: Address,
+    ) -> Result<()> {
+        Err(ApiError::core(format!(
+            "Can't start the rendezvous service {addr}: this node was built without the UDP transport"
+        )))
+    }
 }
 
 impl NodeManagerWorker {
@@ -210,6 +388,57 @@ impl NodeManagerWorker {
         Ok(Response::ok(req))
     }
 
+    pub(super) async fn get_chat_messages(
+        &self,
+        req: &RequestHeader,
+    ) -> Result<Response<Vec<ChatMessage>>, Response<Error>> {
+        Ok(Response::ok(req).body(self.node_manager.take_chat_messages().await))
+    }
+
+    pub(super) async fn start_exec_service(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Response, Response<Error>> {
+        let req_body: StartExecServiceRequest = dec.decode()?;
+        let addr = req_body.addr.to_string().into();
+        let allowed_commands = req_body
+            .allowed_commands
+            .iter()
+            .map(|c| c.parse())
+            .collect::<Result<Vec<AllowedCommand>, _>>()
+            .map_err(|e| Response::bad_request(req, &e.to_string()))?;
+        let timeout = req_body
+            .timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_EXEC_TIMEOUT);
+        self.node_manager
+            .start_exec_service_impl(ctx, addr, allowed_commands, timeout)
+            .await?;
+        Ok(Response::ok(req))
+    }
+
+    pub(super) async fn start_provisioner_service(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Response, Response<Error>> {
+        let req_body: StartProvisionerServiceRequest = dec.decode()?;
+        let addr = req_body.addr.to_string().into();
+        let targets = req_body
+            .targets
+            .iter()
+            .map(|t| t.parse())
+            .collect::<Result<Vec<ProvisioningTarget>, _>>()
+            .map_err(|e| Response::bad_request(req, &e.to_string()))?;
+        self.node_manager
+            .start_provisioner_service_impl(ctx, addr, req_body.attribute, targets)
+            .await?;
+        Ok(Response::ok(req))
+    }
+
     pub(super) async fn start_hop_service(
         &self,
         ctx: &Context,
@@ -222,6 +451,20 @@ impl NodeManagerWorker {
         Ok(Response::ok(req))
     }
 
+    pub(super) async fn start_rendezvous_service(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Response, Response<Error>> {
+        let req_body: StartRendezvousServiceRequest = dec.decode()?;
+        let addr = req_body.addr.to_string().into();
+        self.node_manager
+            .start_rendezvous_service_impl(ctx, addr)
+            .await?;
+        Ok(Response::ok(req))
+    }
+
     pub(super) async fn start_credentials_service(
         &self,
         ctx: &Context,
@@ -255,399 +498,6 @@ impl NodeManagerWorker {
 
         Ok(Response::ok(req))
     }
-    pub(super) async fn start_kafka_outlet_service(
-        &self,
-        context: &Context,
-        request: &RequestHeader,
-        dec: &mut Decoder<'_>,
-    ) -> Result<Vec<u8>> {
-        let body: StartServiceRequest<StartKafkaOutletRequest> = dec.decode()?;
-
-        let default_secure_channel_listener_flow_control_id = context
-            .flow_controls()
-            .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
-            .ok_or_else(|| {
-                ApiError::core("Unable to get flow control for secure channel listener")
-            })?;
-
-        PrefixRelayService::create(
-            context,
-            default_secure_channel_listener_flow_control_id.clone(),
-        )
-        .await?;
-
-        {
-            OutletManagerService::create(
-                context,
-                self.node_manager.secure_channels.clone(),
-                self.node_manager.trust_context()?.id(),
-                default_secure_channel_listener_flow_control_id,
-            )
-            .await?;
-        }
-
-        if let Err(e) = self
-            .node_manager
-            .create_outlet(
-                context,
-                body.request().bootstrap_server_addr,
-                KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into(),
-                Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.to_string()),
-                false,
-            )
-            .await
-        {
-            return Ok(e.to_string().into_bytes());
-        };
-
-        {
-            self.node_manager
-                .registry
-                .kafka_services
-                .insert(
-                    body.address().into(),
-                    KafkaServiceInfo::new(KafkaServiceKind::Outlet),
-                )
-                .await;
-        }
-
-        Ok(Response::ok(request).to_vec()?)
-    }
-
-    pub(super) async fn start_kafka_direct_service(
-        &self,
-        context: &Context,
-        req: &RequestHeader,
-        dec: &mut Decoder<'_>,
-    ) -> Result<Vec<u8>> {
-        let body: StartServiceRequest<StartKafkaDirectRequest> = dec.decode()?;
-        let listener_address: Address = body.address().into();
-        let body_req = body.request();
-
-        let consumer_route: Option<MultiAddr> =
-            if let Some(consumer_route) = body_req.consumer_route() {
-                Some(consumer_route.parse()?)
-            } else {
-                None
-            };
-
-        if let Err(e) = self
-            .start_direct_kafka_service_impl(
-                context,
-                listener_address,
-                body_req.bind_address().ip(),
-                body_req.bind_address().port(),
-                body_req.brokers_port_range(),
-                *body_req.bootstrap_server_addr(),
-                consumer_route,
-            )
-            .await
-        {
-            return Ok(e.to_vec()?);
-        };
-
-        Ok(Response::ok(req).to_vec()?)
-    }
-
-    #[allow(clippy::too_many_arguments)]
-    pub(super) async fn start_direct_kafka_service_impl(
-        &self,
-        context: &Context,
-        local_interceptor_address: Address,
-        bind_ip: IpAddr,
-        server_bootstrap_port: u16,
-        brokers_port_range: (u16, u16),
-        bootstrap_server_addr: SocketAddr,
-        consumer_route: Option<MultiAddr>,
-    ) -> Result<(), Response<Error>> {
-        let default_secure_channel_listener_flow_control_id = context
-            .flow_controls()
-            .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
-            .ok_or_else(|| {
-                ApiError::core("Unable to get flow control for secure channel listener")
-            })?;
-
-        {
-            OutletManagerService::create(
-                context,
-                self.node_manager.secure_channels.clone(),
-                self.node_manager.trust_context()?.id(),
-                default_secure_channel_listener_flow_control_id,
-            )
-            .await?;
-        }
-
-        self.node_manager
-            .create_outlet(
-                context,
-                bootstrap_server_addr,
-                KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into(),
-                Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.to_string()),
-                false,
-            )
-            .await?;
-
-        let trust_context_id;
-        let secure_channels;
-        {
-            trust_context_id = self.node_manager.trust_context()?.id().to_string();
-            secure_channels = self.node_manager.secure_channels.clone();
-        }
-
-        let secure_channel_controller = KafkaSecureChannelControllerImpl::new(
-            secure_channels,
-            ConsumerNodeAddr::Direct(consumer_route.clone()),
-            trust_context_id,
-        );
-
-        let inlet_controller = KafkaInletController::new(
-            "/secure/api".parse().unwrap(),
-            route![local_interceptor_address.clone()],
-            route![KAFKA_OUTLET_INTERCEPTOR_ADDRESS],
-            bind_ip,
-            PortRange::try_from(brokers_port_range)
-                .map_err(|_| ApiError::core("invalid port range"))?,
-        );
-
-        // since we cannot call APIs of node manager via message due to the read/write lock
-        // we need to call it directly
-        self.node_manager
-            .create_inlet(
-                context,
-                SocketAddr::new(bind_ip, server_bootstrap_port).to_string(),
-                None,
-                route![local_interceptor_address.clone()],
-                route![
-                    KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
-                    KAFKA_OUTLET_BOOTSTRAP_ADDRESS
-                ],
-                "/secure/api".parse().unwrap(),
-                None,
-                None,
-            )
-            .await?;
-
-        KafkaPortalListener::create(
-            context,
-            inlet_controller,
-            secure_channel_controller.into_trait(),
-            local_interceptor_address.clone(),
-        )
-        .await?;
-
-        {
-            self.node_manager
-                .registry
-                .kafka_services
-                .insert(
-                    local_interceptor_address,
-                    KafkaServiceInfo::new(KafkaServiceKind::Direct),
-                )
-                .await;
-        }
-
-        Ok(())
-    }
-
-    pub(super) async fn start_kafka_consumer_service(
-        &self,
-        context: &Context,
-        req: &RequestHeader,
-        dec: &mut Decoder<'_>,
-    ) -> Result<Vec<u8>> {
-        let body: StartServiceRequest<StartKafkaConsumerRequest> = dec.decode()?;
-        let listener_address: Address = body.address().into();
-        let body_req = body.request();
-        let outlet_node_multiaddr = body_req.project_route().to_string().parse()?;
-
-        if let Err(e) = self
-            .start_kafka_service_impl(
-                context,
-                listener_address,
-                body_req.bootstrap_server_addr.ip(),
-                body_req.bootstrap_server_addr.port(),
-                body_req.brokers_port_range(),
-                outlet_node_multiaddr,
-                KafkaServiceKind::Consumer,
-            )
-            .await
-        {
-            return Ok(e.to_vec()?);
-        };
-
-        Ok(Response::ok(req).to_vec()?)
-    }
-
-    pub(super) async fn start_kafka_producer_service(
-        &mut self,
-        context: &Context,
-        req: &RequestHeader,
-        dec: &mut Decoder<'_>,
-    ) -> Result<Vec<u8>> {
-        let body: StartServiceRequest<StartKafkaProducerRequest> = dec.decode()?;
-        let listener_address: Address = body.address().into();
-        let body_req = body.request();
-        let outlet_node_multiaddr = body_req.project_route().to_string().parse()?;
-
-        if let Err(e) = self
-            .start_kafka_service_impl(
-                context,
-                listener_address,
-                body_req.bootstrap_server_addr.ip(),
-                body_req.bootstrap_server_addr.port(),
-                body_req.brokers_port_range(),
-                outlet_node_multiaddr,
-                KafkaServiceKind::Producer,
-            )
-            .await
-        {
-            return Ok(e.to_vec()?);
-        };
-
-        Ok(Response::ok(req).to_vec()?)
-    }
-
-    #[allow(clippy::too_many_arguments)]
-    pub(super) async fn start_kafka_service_impl(
-        &self,
-        context: &Context,
-        local_interceptor_address: Address,
-        bind_ip: IpAddr,
-        server_bootstrap_port: u16,
-        brokers_port_range: (u16, u16),
-        outlet_node_multiaddr: MultiAddr,
-        kind: KafkaServiceKind,
-    ) -> Result<(), Response<Error>> {
-        debug!(
-            "outlet_node_multiaddr: {}",
-            outlet_node_multiaddr.to_string()
-        );
-
-        let trust_context_id;
-        let secure_channels;
-        {
-            trust_context_id = self.node_manager.trust_context()?.id().to_string();
-            secure_channels = self.node_manager.secure_channels.clone();
-
-            if let Some(project) = outlet_node_multiaddr.first().and_then(|value| {
-                value
-                    .cast::<ockam_multiaddr::proto::Project>()
-                    .map(|p| p.to_string())
-            }) {
-                let (_, project_identifier) = self.node_manager.resolve_project(&project).await?;
-                // if we are using the project we need to allow safe communication based on the
-                // project identifier
-                self.node_manager
-                    .policies
-                    .set_policy(
-                        &resources::INLET,
-                        &actions::HANDLE_MESSAGE,
-                        &eq([ident("subject.identifier"), str(project_identifier)]),
-                    )
-                    .await?;
-            }
-        }
-
-        let secure_channel_controller = KafkaSecureChannelControllerImpl::new(
-            secure_channels,
-            ConsumerNodeAddr::Relay(outlet_node_multiaddr.clone()),
-            trust_context_id,
-        );
-
-        let inlet_controller = KafkaInletController::new(
-            outlet_node_multiaddr.clone(),
-            route![local_interceptor_address.clone()],
-            route![KAFKA_OUTLET_INTERCEPTOR_ADDRESS],
-            bind_ip,
-            PortRange::try_from(brokers_port_range)
-                .map_err(|_| ApiError::core("invalid port range"))?,
-        );
-
-        // since we cannot call APIs of node manager via message due to the read/write lock
-        // we need to call it directly
-        self.node_manager
-            .create_inlet(
-                context,
-                SocketAddr::new(bind_ip, server_bootstrap_port).to_string(),
-                None,
-                route![local_interceptor_address.clone()],
-                route![
-                    KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
-                    KAFKA_OUTLET_BOOTSTRAP_ADDRESS
-                ],
-                outlet_node_multiaddr,
-                None,
-                None,
-            )
-            .await?;
-
-        KafkaPortalListener::create(
-            context,
-            inlet_controller,
-            secure_channel_controller.into_trait(),
-            local_interceptor_address.clone(),
-        )
-        .await?;
-
-        {
-            self.node_manager
-                .registry
-                .kafka_services
-                .insert(local_interceptor_address, KafkaServiceInfo::new(kind))
-                .await;
-        }
-
-        Ok(())
-    }
-
-    pub(crate) async fn delete_kafka_service(
-        &self,
-        ctx: &Context,
-        req: &RequestHeader,
-        dec: &mut Decoder<'_>,
-        kind: KafkaServiceKind,
-    ) -> Result<Response, Response<Error>> {
-        let body: DeleteServiceRequest = match dec.decode() {
-            Ok(it) => it,
-            Err(err) => {
-                return Err(Response::bad_request(req, &err.to_string()));
-            }
-        };
-        let address = body.address();
-        let res = match self
-            .node_manager
-            .registry
-            .kafka_services
-            .get(&address)
-            .await
-        {
-            None => {
-                return Err(Response::not_found(
-                    req,
-                    &format!("Service at address '{}' not found", address),
-                ));
-            }
-            Some(e) => {
-                if kind.eq(e.kind()) {
-                    ctx.stop_worker(address.clone()).await?;
-                    self.node_manager
-                        .registry
-                        .kafka_services
-                        .remove(&address)
-                        .await;
-                    Response::ok(req)
-                } else {
-                    error!(address = %address, "Service is not a kafka {}", kind.to_string());
-                    return Err(Response::internal_error(
-                        req,
-                        &format!("Service at address '{}' is not a kafka {}", address, kind),
-                    ));
-                }
-            }
-        };
-        Ok(res)
-    }
-
     pub(super) async fn list_services_of_type(
         &self,
         req: &RequestHeader,
@@ -677,7 +527,7 @@ impl NodeManagerWorker {
             .to_vec()?)
     }
 
-    async fn list_services_impl(registry: &Registry) -> Vec<ServiceStatus> {
+    pub(crate) async fn list_services_impl(registry: &Registry) -> Vec<ServiceStatus> {
         let mut list = Vec::new();
         registry
             .authenticated_services
@@ -712,12 +562,46 @@ impl NodeManagerWorker {
                     DefaultAddress::ECHO_SERVICE,
                 ))
             });
+        registry.chat_services.keys().await.iter().for_each(|addr| {
+            list.push(ServiceStatus::new(
+                addr.address(),
+                DefaultAddress::CHAT_SERVICE,
+            ))
+        });
+        registry.exec_services.keys().await.iter().for_each(|addr| {
+            list.push(ServiceStatus::new(
+                addr.address(),
+                DefaultAddress::EXEC_SERVICE,
+            ))
+        });
+        registry
+            .provisioner_services
+            .keys()
+            .await
+            .iter()
+            .for_each(|addr| {
+                list.push(ServiceStatus::new(
+                    addr.address(),
+                    DefaultAddress::PROVISIONER_SERVICE,
+                ))
+            });
         registry.hop_services.keys().await.iter().for_each(|addr| {
             list.push(ServiceStatus::new(
                 addr.address(),
                 DefaultAddress::HOP_SERVICE,
             ))
         });
+        registry
+            .rendezvous_services
+            .keys()
+            .await
+            .iter()
+            .for_each(|addr| {
+                list.push(ServiceStatus::new(
+                    addr.address(),
+                    DefaultAddress::RENDEZVOUS_SERVICE,
+                ))
+            });
         registry
             .credentials_services
             .keys()
@@ -729,6 +613,7 @@ impl NodeManagerWorker {
                     DefaultAddress::CREDENTIALS_SERVICE,
                 ))
             });
+        #[cfg(feature = "kafka")]
         registry
             .kafka_services
             .entries()
@@ -749,3 +634,78 @@ impl NodeManagerWorker {
         list
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ockam::identity::{Identifier, IdentitySecureChannelLocalInfo};
+    use ockam_core::{route, IncomingAccessControl, LocalMessage, RelayMessage, TransportMessage};
+
+    use super::*;
+    use crate::test_utils::start_manager_for_tests;
+
+    fn exec_command() -> Vec<AllowedCommand> {
+        vec!["echo".parse().unwrap()]
+    }
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test]
+    async fn exec_service__unauthorized_identity__is_rejected(context: &mut Context) -> Result<()> {
+        let handler = start_manager_for_tests(context).await?;
+        let node_manager = &handler.node_manager;
+
+        // A message sent by an identity which is not a member of the trust context
+        let identifier = Identifier::try_from("Ie92f183eb4c324804ef4d62962dea94cf095a265")?;
+        let local_info = IdentitySecureChannelLocalInfo::mark(vec![], identifier, None, None)?;
+        let msg = RelayMessage::new(
+            Address::random_local(),
+            "exec".into(),
+            LocalMessage::new(TransportMessage::v1(route![], route![], vec![]), local_info),
+        );
+
+        let resource = Resource::assert_inline("exec");
+        let ac = node_manager
+            .restricted_access_control(&resource, &actions::HANDLE_MESSAGE)
+            .await?;
+        assert!(!ac.is_authorized(&msg).await?);
+        node_manager
+            .start_exec_service_impl(context, "exec".into(), exec_command(), DEFAULT_EXEC_TIMEOUT)
+            .await?;
+
+        // Without a trust context the service only starts once a policy is set
+        *node_manager.trust_context.write().unwrap() = None;
+        let resource = Resource::assert_inline("exec_without_trust_context");
+        assert!(node_manager
+            .start_exec_service_impl(
+                context,
+                "exec_without_trust_context".into(),
+                exec_command(),
+                DEFAULT_EXEC_TIMEOUT,
+            )
+            .await
+            .is_err());
+        assert!(!context
+            .list_workers()
+            .await?
+            .contains(&"exec_without_trust_context".into()));
+
+        let member = eq([ident("subject.role"), str("admin")]);
+        node_manager
+            .policies
+            .set_policy(&resource, &actions::HANDLE_MESSAGE, &member)
+            .await?;
+        let ac = node_manager
+            .restricted_access_control(&resource, &actions::HANDLE_MESSAGE)
+            .await?;
+        assert!(!ac.is_authorized(&msg).await?);
+        node_manager
+            .start_exec_service_impl(
+                context,
+                "exec_without_trust_context".into(),
+                exec_command(),
+                DEFAULT_EXEC_TIMEOUT,
+            )
+            .await?;
+
+        context.stop().await
+    }
+}
@@ -0,0 +1,232 @@
+//! Generation of default aliases for inlets and outlets.
+//!
+//! When no alias is provided for a portal, an alias is derived from the portal target, using a
+//! template which can be configured with `ockam configuration set alias-template`.
+//! For example an inlet listening on port 5432 and sending traffic to the relay `dbprod`
+//! gets the alias `in-5432-dbprod`.
+
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use std::net::SocketAddr;
+
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Service};
+use ockam_multiaddr::{MultiAddr, Protocol};
+
+use crate::nodes::registry::RegistryOf;
+use crate::nodes::service::Alias;
+
+/// Prefix of the service name used by relays
+const RELAY_SERVICE_PREFIX: &str = "forward_to_";
+
+/// Template used when no template has been configured
+pub const DEFAULT_ALIAS_TEMPLATE: &str = "{kind}-{port}-{target}";
+
+const KIND_VARIABLE: &str = "{kind}";
+const PORT_VARIABLE: &str = "{port}";
+const TARGET_VARIABLE: &str = "{target}";
+
+/// Template for portal aliases. The following variables are replaced:
+///  - `{kind}`: `in` for an inlet, `out` for an outlet
+///  - `{port}`: the inlet listening port or the outlet target port
+///  - `{target}`: the relay, node or host the portal is connected to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasTemplate(String);
+
+impl AliasTemplate {
+    fn render(&self, kind: PortalKind, port: u16, target: &str) -> Alias {
+        let alias = self
+            .0
+            .replace(KIND_VARIABLE, kind.as_str())
+            .replace(PORT_VARIABLE, &port.to_string())
+            .replace(TARGET_VARIABLE, target);
+        sanitize(&alias)
+    }
+}
+
+impl Default for AliasTemplate {
+    fn default() -> Self {
+        Self(DEFAULT_ALIAS_TEMPLATE.to_string())
+    }
+}
+
+impl Display for AliasTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for AliasTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let without_variables = s
+            .replace(KIND_VARIABLE, "")
+            .replace(PORT_VARIABLE, "")
+            .replace(TARGET_VARIABLE, "");
+        if without_variables.contains(['{', '}']) {
+            return Err(format!(
+                "the alias template '{s}' can only contain the variables \
+                {KIND_VARIABLE}, {PORT_VARIABLE} and {TARGET_VARIABLE}"
+            ));
+        }
+        if sanitize(&s.replace(['{', '}'], "")).is_empty() {
+            return Err("the alias template must not be empty".to_string());
+        }
+        Ok(Self(s.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PortalKind {
+    Inlet,
+    Outlet,
+}
+
+impl PortalKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            PortalKind::Inlet => "in",
+            PortalKind::Outlet => "out",
+        }
+    }
+}
+
+/// Return the alias of an inlet listening on `bind_addr` and sending traffic to `outlet_addr`
+pub(crate) fn inlet_alias(
+    template: &AliasTemplate,
+    bind_addr: &SocketAddr,
+    outlet_addr: &MultiAddr,
+) -> Alias {
+    template.render(
+        PortalKind::Inlet,
+        bind_addr.port(),
+        &inlet_target(outlet_addr),
+    )
+}
+
+/// Return the alias of an outlet sending traffic to `socket_addr`
+pub(crate) fn outlet_alias(template: &AliasTemplate, socket_addr: &SocketAddr) -> Alias {
+    template.render(
+        PortalKind::Outlet,
+        socket_addr.port(),
+        &socket_addr.ip().to_string(),
+    )
+}
+
+/// Insert a value in the registry with `alias` if it is not already used, otherwise with the
+/// first alias `alias-<n>`, with n >= 2, which is not used yet. Return the alias of the value
+pub(crate) async fn insert_with_unique_alias<V: Clone>(
+    registry: &RegistryOf<Alias, V>,
+    alias: Alias,
+    value: V,
+) -> Alias {
+    let candidates =
+        core::iter::once(alias.clone()).chain((2..).map(move |n: u64| format!("{alias}-{n}")));
+    registry
+        .insert_with_first_vacant_key(candidates, value)
+        .await
+        .expect("there is always an unused alias")
+}
+
+/// Return a name for the target of an inlet:
+///  - the relay name if the route goes through a relay
+///  - otherwise the first node, host or project name found in the route
+fn inlet_target(outlet_addr: &MultiAddr) -> String {
+    let mut target = None;
+    for p in outlet_addr.iter() {
+        let value = match p.code() {
+            Service::CODE => p.cast::<Service>().and_then(|s| {
+                s.strip_prefix(RELAY_SERVICE_PREFIX)
+                    .map(|relay| relay.to_string())
+            }),
+            Node::CODE => p.cast::<Node>().map(|n| n.to_string()),
+            DnsAddr::CODE => p.cast::<DnsAddr>().map(|h| h.to_string()),
+            Ip4::CODE => p.cast::<Ip4>().map(|ip| ip.to_string()),
+            Ip6::CODE => p.cast::<Ip6>().map(|ip| ip.to_string()),
+            Project::CODE => p.cast::<Project>().map(|p| p.to_string()),
+            _ => None,
+        };
+        if let Some(value) = value {
+            // A relay name is the most specific target so we stop looking
+            if p.code() == Service::CODE {
+                return value;
+            }
+            target.get_or_insert(value);
+        }
+    }
+    target.unwrap_or_else(|| "outlet".to_string())
+}
+
+/// Only keep lowercase alphanumeric characters and single dashes
+fn sanitize(alias: &str) -> Alias {
+    let mut sanitized = String::with_capacity(alias.len());
+    for c in alias.chars() {
+        if c.is_ascii_alphanumeric() {
+            sanitized.push(c.to_ascii_lowercase());
+        } else if !sanitized.is_empty() && !sanitized.ends_with('-') {
+            sanitized.push('-');
+        }
+    }
+    sanitized.trim_end_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn inlet_alias_uses_the_relay_name() {
+        let template = AliasTemplate::default();
+        let bind_addr = SocketAddr::from_str("127.0.0.1:5432").unwrap();
+        let outlet_addr = MultiAddr::from_str(
+            "/project/default/service/forward_to_dbprod/secure/api/service/outlet",
+        )
+        .unwrap();
+        assert_eq!(
+            inlet_alias(&template, &bind_addr, &outlet_addr),
+            "in-5432-dbprod"
+        );
+
+        let outlet_addr = MultiAddr::from_str("/node/n1/service/outlet").unwrap();
+        assert_eq!(
+            inlet_alias(&template, &bind_addr, &outlet_addr),
+            "in-5432-n1"
+        );
+    }
+
+    #[test]
+    fn outlet_alias_uses_the_target_address() {
+        let template = AliasTemplate::from_str("{target}_{port}").unwrap();
+        let socket_addr = SocketAddr::from_str("10.0.0.1:6379").unwrap();
+        assert_eq!(outlet_alias(&template, &socket_addr), "10-0-0-1-6379");
+    }
+
+    #[tokio::test]
+    async fn concurrent_default_aliases_are_unique() {
+        let registry: Arc<RegistryOf<Alias, ()>> = Default::default();
+        let mut handles = vec![];
+        for _ in 0..10 {
+            let registry = registry.clone();
+            handles.push(tokio::spawn(async move {
+                insert_with_unique_alias(&registry, "in-5432-n1".to_string(), ()).await
+            }));
+        }
+        let mut aliases = vec![];
+        for handle in handles {
+            aliases.push(handle.await.unwrap());
+        }
+        aliases.sort();
+        aliases.dedup();
+        assert_eq!(aliases.len(), 10);
+        assert!(aliases.contains(&"in-5432-n1".to_string()));
+        assert!(aliases.contains(&"in-5432-n1-10".to_string()));
+    }
+
+    #[test]
+    fn invalid_templates() {
+        assert!(AliasTemplate::from_str("{kind}-{unknown}").is_err());
+        assert!(AliasTemplate::from_str("--").is_err());
+        assert!(AliasTemplate::from_str("portal-{port}").is_ok());
+    }
+}
@@ -7,24 +7,30 @@ use tokio::time::timeout;
 use ockam::identity::Identifier;
 use ockam::{Address, Result};
 use ockam_abac::Resource;
-use ockam_core::api::{Error, Reply, Request, RequestHeader, Response};
+use ockam_core::api::{ApiErrorCode, Error, Reply, Request, RequestHeader, Response};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, route, AsyncTryClone, IncomingAccessControl, Route};
 use ockam_multiaddr::proto::Project;
 use ockam_multiaddr::{MultiAddr, Protocol};
 use ockam_node::Context;
-use ockam_transport_tcp::{TcpInletOptions, TcpOutletOptions};
+use ockam_transport_tcp::{
+    CanaryPolicy, CanarySummary, PortalStatistics, TcpInletOptions, TcpOutletOptions,
+};
 
 use crate::cli_state::StateDirTrait;
 use crate::config::lookup::ProjectLookup;
-use crate::error::ApiError;
-use crate::nodes::connection::Connection;
+use crate::error::{api_error_code, ApiError};
+use crate::nodes::connection::{Connection, RouteSelection};
 use crate::nodes::models::portal::{
-    CreateInlet, CreateOutlet, InletList, InletStatus, OutletList, OutletStatus,
+    CreateInlet, CreateOutlet, DataFlow, InletCanaryStatus, InletList, InletProtocol, InletStatus,
+    OutletList, OutletStatus, SetInletCanaryWeights, StartInletCanary, TlsOrigination,
 };
-use crate::nodes::registry::{InletInfo, OutletInfo};
+use crate::nodes::models::quota::SessionQuota;
+use crate::nodes::registry::{InletCanaryInfo, InletInfo, OutletInfo};
 use crate::nodes::service::policy::Policies;
-use crate::nodes::service::random_alias;
+use crate::nodes::service::portal_alias::{
+    inlet_alias, insert_with_unique_alias, outlet_alias, AliasTemplate,
+};
 use crate::nodes::{BackgroundNode, InMemoryNode};
 use crate::session::sessions::{Replacer, Session, Status, MAX_CONNECT_TIME, MAX_RECOVERY_TIME};
 use crate::{actions, resources, DefaultAddress};
@@ -53,6 +59,10 @@ impl NodeManagerWorker {
             prefix_route,
             suffix_route,
             wait_for_outlet_duration,
+            data_flow,
+            quota,
+            expected_protocol,
+            alternate_outlet_addrs,
         } = create_inlet_req;
         match self
             .node_manager
@@ -63,13 +73,26 @@ impl NodeManagerWorker {
                 prefix_route,
                 suffix_route,
                 outlet_addr,
+                alternate_outlet_addrs.unwrap_or_default(),
                 wait_for_outlet_duration,
                 authorized,
+                data_flow,
+                quota,
+                expected_protocol,
             )
             .await
         {
             Ok(status) => Ok(Response::ok(req).body(status)),
-            Err(e) => Err(Response::bad_request(req, &format!("{e:?}"))),
+            Err(e) => {
+                // The outlet is considered unreachable unless the error is more specific
+                let code = api_error_code(&e).unwrap_or(ApiErrorCode::PortalDestinationUnreachable);
+                Err(Response::error_with_code(
+                    req,
+                    &e.to_string(),
+                    ockam_core::api::Status::BadRequest,
+                    code,
+                ))
+            }
         }
     }
 
@@ -97,6 +120,62 @@ impl NodeManagerWorker {
             )),
         }
     }
+
+    pub(super) async fn start_inlet_canary(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        alias: &str,
+        request: StartInletCanary,
+    ) -> Result<Response<InletCanaryStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .start_inlet_canary(ctx, alias, request)
+            .await
+        {
+            Ok(status) => Ok(Response::ok(req).body(status)),
+            Err(e) => Err(Response::bad_request(req, &format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn show_inlet_canary(
+        &self,
+        req: &RequestHeader,
+        alias: &str,
+    ) -> Result<Response<InletCanaryStatus>, Response<Error>> {
+        match self.node_manager.show_inlet_canary(alias).await {
+            Ok(status) => Ok(Response::ok(req).body(status)),
+            Err(e) => Err(Response::not_found(req, &e.to_string())),
+        }
+    }
+
+    pub(super) async fn set_inlet_canary_weights(
+        &self,
+        req: &RequestHeader,
+        alias: &str,
+        request: SetInletCanaryWeights,
+    ) -> Result<Response<InletCanaryStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .set_inlet_canary_weights(alias, request)
+            .await
+        {
+            Ok(status) => Ok(Response::ok(req).body(status)),
+            Err(e) => Err(Response::bad_request(req, &e.to_string())),
+        }
+    }
+
+    pub(super) async fn decide_inlet_canary(
+        &self,
+        req: &RequestHeader,
+        alias: &str,
+        promote: bool,
+    ) -> Result<Response<InletCanaryStatus>, Response<Error>> {
+        match self.node_manager.decide_inlet_canary(alias, promote).await {
+            Ok(status) => Ok(Response::ok(req).body(status)),
+            Err(e) => Err(Response::bad_request(req, &e.to_string())),
+        }
+    }
 }
 
 /// OUTLETS
@@ -112,7 +191,9 @@ impl NodeManagerWorker {
             worker_addr,
             alias,
             reachable_from_default_secure_channel,
-            ..
+            data_flow,
+            quota,
+            tls,
         } = create_outlet;
 
         match self
@@ -123,6 +204,9 @@ impl NodeManagerWorker {
                 worker_addr,
                 alias,
                 reachable_from_default_secure_channel,
+                data_flow,
+                quota,
+                tls,
             )
             .await
         {
@@ -181,6 +265,9 @@ impl NodeManager {
         worker_addr: Address,
         alias: Option<String>,
         reachable_from_default_secure_channel: bool,
+        data_flow: DataFlow,
+        quota: SessionQuota,
+        tls: Option<TlsOrigination>,
     ) -> Result<OutletStatus> {
         info!(
             "Handling request to create outlet portal at {:?}",
@@ -191,30 +278,44 @@ impl NodeManager {
             .map(Resource::new)
             .unwrap_or(resources::OUTLET);
 
-        let alias = alias.unwrap_or_else(random_alias);
-
         // Check that there is no entry in the registry with the same alias
-        if self.registry.outlets.contains_key(&alias).await {
-            let message = format!("A TCP outlet with alias '{alias}' already exists");
-            return Err(ockam_core::Error::new(
-                Origin::Node,
-                Kind::AlreadyExists,
-                message,
-            ));
+        if let Some(alias) = &alias {
+            if self.registry.outlets.contains_key(alias).await {
+                let message = format!("A TCP outlet with alias '{alias}' already exists");
+                return Err(ockam_core::Error::new(
+                    Origin::Node,
+                    Kind::AlreadyExists,
+                    message,
+                ));
+            }
         }
 
         let check_credential = self.enable_credential_checks;
         let trust_context_id = if check_credential {
-            Some(self.trust_context()?.id())
+            Some(self.trust_context()?.id().to_string())
         } else {
             None
         };
 
         let access_control = self
-            .access_control(&resource, &actions::HANDLE_MESSAGE, trust_context_id, None)
+            .access_control(
+                &resource,
+                &actions::HANDLE_MESSAGE,
+                trust_context_id.as_deref(),
+                None,
+            )
             .await?;
 
-        let options = TcpOutletOptions::new().with_incoming_access_control(access_control);
+        let statistics = PortalStatistics::default();
+        let options = TcpOutletOptions::new()
+            .with_incoming_access_control(access_control)
+            .with_statistics(statistics.clone())
+            .with_data_flow(data_flow.into())
+            .with_quota(quota.into());
+        let options = match tls {
+            Some(tls) => options.with_tls(tls.resolve_secrets(self.secrets())?.outlet_tls()?),
+            None => options,
+        };
         let options = if !check_credential {
             options.as_consumer(&self.api_transport_flow_control_id)
         } else {
@@ -243,13 +344,18 @@ impl NodeManager {
         Ok(match res {
             Ok(_) => {
                 // TODO: Use better way to store outlets?
-                self.registry
-                    .outlets
-                    .insert(
-                        alias.clone(),
-                        OutletInfo::new(&socket_addr, Some(&worker_addr)),
-                    )
-                    .await;
+                let info = OutletInfo::new(&socket_addr, Some(&worker_addr), statistics);
+                // The default alias is checked and registered atomically
+                let alias = match alias {
+                    Some(alias) => {
+                        self.registry.outlets.insert(alias.clone(), info).await;
+                        alias
+                    }
+                    None => {
+                        let alias = outlet_alias(&self.alias_template(), &socket_addr);
+                        insert_with_unique_alias(&self.registry.outlets, alias, info).await
+                    }
+                };
 
                 OutletStatus::new(socket_addr, worker_addr, alias, None)
             }
@@ -301,8 +407,22 @@ impl NodeManager {
     }
 }
 
+impl NodeManager {
+    /// Return the template used to generate the alias of a portal when no alias is provided
+    fn alias_template(&self) -> AliasTemplate {
+        match self.cli_state.settings.get().map(|s| s.alias_template) {
+            Ok(Some(template)) => template.parse().unwrap_or_else(|e| {
+                warn!(%template, %e, "Invalid alias template, using the default template");
+                AliasTemplate::default()
+            }),
+            _ => AliasTemplate::default(),
+        }
+    }
+}
+
 /// INLETS
 impl NodeManager {
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_inlet(
         &self,
         connection: Connection,
@@ -311,16 +431,21 @@ impl NodeManager {
         prefix_route: Route,
         suffix_route: Route,
         outlet_addr: MultiAddr,
+        data_flow: DataFlow,
+        quota: SessionQuota,
+        expected_protocol: Option<InletProtocol>,
     ) -> Result<(InletStatus, Arc<dyn IncomingAccessControl>)> {
         info!("Handling request to create inlet portal");
 
-        let alias = requested_alias.clone().unwrap_or_else(random_alias);
         debug! {
             listen_addr = %listen_addr,
             prefix = %prefix_route,
             suffix = %suffix_route,
             outlet_addr = %outlet_addr,
-            %alias,
+            alias = ?requested_alias,
+            data_flow = %data_flow,
+            quota = ?quota,
+            expected_protocol = ?expected_protocol,
             "Creating inlet portal"
         }
 
@@ -328,13 +453,15 @@ impl NodeManager {
             let registry = &self.registry.inlets;
 
             // Check that there is no entry in the registry with the same alias
-            if registry.contains_key(&alias).await {
-                let message = format!("A TCP inlet with alias '{alias}' already exists");
-                return Err(ockam_core::Error::new(
-                    Origin::Node,
-                    Kind::AlreadyExists,
-                    message,
-                ));
+            if let Some(alias) = &requested_alias {
+                if registry.contains_key(alias).await {
+                    let message = format!("A TCP inlet with alias '{alias}' already exists");
+                    return Err(ockam_core::Error::new(
+                        Origin::Node,
+                        Kind::AlreadyExists,
+                        message,
+                    ));
+                }
             }
 
             // Check that there is no entry in the registry with the same TCP bind address
@@ -367,12 +494,12 @@ impl NodeManager {
                 .first()
                 .and_then(|p| {
                     if let Some(p) = p.cast::<Project>() {
-                        projects.get(&*p).map(|info| &*info.id)
+                        projects.get(&*p).map(|info| info.id.clone())
                     } else {
                         None
                     }
                 })
-                .or_else(|| Some(self.trust_context().ok()?.id()));
+                .or_else(|| Some(self.trust_context().ok()?.id().to_string()));
             if pid.is_none() {
                 let message = "Credential check requires a project or trust context";
                 return Err(ockam_core::Error::new(Origin::Node, Kind::Invalid, message));
@@ -383,13 +510,30 @@ impl NodeManager {
         };
 
         let resource = requested_alias
-            .map(|a| Resource::new(a.as_str()))
+            .as_deref()
+            .map(Resource::new)
             .unwrap_or(resources::INLET);
         let access_control = self
-            .access_control(&resource, &actions::HANDLE_MESSAGE, project_id, None)
+            .access_control(
+                &resource,
+                &actions::HANDLE_MESSAGE,
+                project_id.as_deref(),
+                None,
+            )
             .await?;
 
-        let options = TcpInletOptions::new().with_incoming_access_control(access_control.clone());
+        let statistics = PortalStatistics::default();
+        let canary = InletCanaryInfo::default();
+        let options = TcpInletOptions::new()
+            .with_incoming_access_control(access_control.clone())
+            .with_statistics(statistics.clone())
+            .with_canary(canary.canary.clone())
+            .with_data_flow(data_flow.into())
+            .with_quota(quota.into());
+        let options = match expected_protocol {
+            Some(protocol) => options.with_expected_protocol(protocol.into()),
+            None => options,
+        };
         let res = self
             .tcp_transport
             .create_inlet(listen_addr.clone(), outlet_route.clone(), options)
@@ -402,13 +546,26 @@ impl NodeManager {
                 let listen_addr = socket_address.to_string();
 
                 // TODO: Use better way to store inlets?
-                self.registry
-                    .inlets
-                    .insert(
-                        alias.clone(),
-                        InletInfo::new(&listen_addr, Some(&worker_addr), &outlet_route),
-                    )
-                    .await;
+                let info = InletInfo::new(
+                    &listen_addr,
+                    Some(&worker_addr),
+                    &outlet_route,
+                    statistics,
+                    canary,
+                );
+                // The default alias is generated once the port is known, and is checked and
+                // registered atomically
+                let alias = match requested_alias {
+                    Some(alias) => {
+                        self.registry.inlets.insert(alias.clone(), info).await;
+                        alias
+                    }
+                    None => {
+                        let alias =
+                            inlet_alias(&self.alias_template(), &socket_address, &outlet_addr);
+                        insert_with_unique_alias(&self.registry.inlets, alias, info).await
+                    }
+                };
                 (
                     InletStatus::new(
                         listen_addr,
@@ -526,6 +683,136 @@ impl NodeManager {
     }
 }
 
+/// INLET CANARIES
+impl NodeManager {
+    /// Return the canary of an inlet
+    pub async fn show_inlet_canary(&self, alias: &str) -> Result<InletCanaryStatus> {
+        let canary = self.inlet_canary(alias).await?;
+        let summary = canary.canary.summary().ok_or_else(|| {
+            ockam_core::Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                format!("The inlet {alias} has no canary"),
+            )
+        })?;
+        Ok(Self::inlet_canary_status(alias, &canary, summary))
+    }
+
+    /// Shift gradually the new connections of an inlet between its current outlet and its
+    /// canary outlet
+    pub async fn set_inlet_canary_weights(
+        &self,
+        alias: &str,
+        request: SetInletCanaryWeights,
+    ) -> Result<InletCanaryStatus> {
+        let canary = self.inlet_canary(alias).await?;
+        let summary = canary
+            .canary
+            .set_weights(request.outlet_weight, request.canary_weight)?;
+        info!(
+            %alias,
+            outlet_weight = request.outlet_weight,
+            canary_weight = request.canary_weight,
+            percentage = summary.percentage,
+            "inlet canary weights updated"
+        );
+        Ok(Self::inlet_canary_status(alias, &canary, summary))
+    }
+
+    /// Promote the canary of an inlet, so that all its new connections use the canary outlet,
+    /// or roll it back
+    pub async fn decide_inlet_canary(
+        &self,
+        alias: &str,
+        promote: bool,
+    ) -> Result<InletCanaryStatus> {
+        let canary = self.inlet_canary(alias).await?;
+        let summary = if promote {
+            canary.canary.promote()?
+        } else {
+            canary.canary.rollback()?
+        };
+        info!(%alias, status = %summary.status, "inlet canary decided");
+        Ok(Self::inlet_canary_status(alias, &canary, summary))
+    }
+
+    async fn inlet_canary(&self, alias: &str) -> Result<InletCanaryInfo> {
+        self.registry
+            .inlets
+            .get(alias)
+            .await
+            .map(|info| info.canary)
+            .ok_or_else(|| {
+                ockam_core::Error::new(
+                    Origin::Node,
+                    Kind::NotFound,
+                    format!("Inlet with alias {alias} not found"),
+                )
+            })
+    }
+
+    fn inlet_canary_status(
+        alias: &str,
+        canary: &InletCanaryInfo,
+        summary: CanarySummary,
+    ) -> InletCanaryStatus {
+        InletCanaryStatus {
+            alias: alias.to_string(),
+            outlet_addr: canary
+                .canary_addr()
+                .map(|a| a.to_string())
+                .unwrap_or_else(|| summary.route.to_string()),
+            percentage: summary.percentage,
+            status: summary.status.to_string(),
+            connections: summary.connections,
+            failures: summary.failures,
+            stickiness: summary.stickiness.to_string(),
+        }
+    }
+}
+
+impl InMemoryNode {
+    /// Send a fraction of the new connections of an inlet to a canary outlet
+    pub async fn start_inlet_canary(
+        &self,
+        ctx: &Context,
+        alias: &str,
+        request: StartInletCanary,
+    ) -> Result<InletCanaryStatus> {
+        let canary = self.node_manager.inlet_canary(alias).await?;
+        let duration = request
+            .wait_for_outlet_duration
+            .unwrap_or(Duration::from_secs(5));
+        let connection = self
+            .make_connection(
+                Arc::new(ctx.async_try_clone().await?),
+                &request.outlet_addr,
+                None,
+                request.authorized,
+                None,
+                Some(duration),
+            )
+            .await?;
+        let route = connection.route(self.tcp_transport()).await?;
+        let policy = CanaryPolicy {
+            error_threshold: request.error_threshold,
+            promote_after: request.promote_after,
+        };
+        canary
+            .canary
+            .start(route, request.percentage, policy, request.stickiness.into())?;
+        canary.set_canary_addr(request.outlet_addr.clone());
+        info!(
+            %alias,
+            outlet_addr = %request.outlet_addr,
+            percentage = request.percentage,
+            stickiness = %request.stickiness,
+            "inlet canary started"
+        );
+        self.node_manager.show_inlet_canary(alias).await
+    }
+}
+
 impl InMemoryNode {
     #[allow(clippy::too_many_arguments)]
     pub async fn create_inlet(
@@ -536,8 +823,12 @@ impl InMemoryNode {
         prefix_route: Route,
         suffix_route: Route,
         outlet_addr: MultiAddr,
+        alternate_outlet_addrs: Vec<MultiAddr>,
         wait_for_outlet_duration: Option<Duration>,
         authorized: Option<Identifier>,
+        data_flow: DataFlow,
+        quota: SessionQuota,
+        expected_protocol: Option<InletProtocol>,
     ) -> Result<InletStatus> {
         // The addressing scheme is very flexible. Typically the node connects to
         // the cloud via secure channel and the with another secure channel via
@@ -546,16 +837,33 @@ impl InMemoryNode {
         // to another node.
         let duration = wait_for_outlet_duration.unwrap_or(Duration::from_secs(5));
         let connection_ctx = Arc::new(ctx.async_try_clone().await?);
-        let connection = self
-            .make_connection(
-                connection_ctx.clone(),
-                &outlet_addr,
-                None,
-                authorized.clone(),
-                None,
-                Some(duration),
-            )
-            .await?;
+        // When several addresses of the outlet are given, the one with the lowest latency is
+        // used, and it is selected again each time the inlet is recreated
+        let (outlet_addr, connection, route_selection) = if alternate_outlet_addrs.is_empty() {
+            let connection = self
+                .make_connection(
+                    connection_ctx.clone(),
+                    &outlet_addr,
+                    None,
+                    authorized.clone(),
+                    None,
+                    Some(duration),
+                )
+                .await?;
+            (outlet_addr, connection, None)
+        } else {
+            let mut route_selection =
+                RouteSelection::new([vec![outlet_addr], alternate_outlet_addrs].concat());
+            let (outlet_addr, connection) = self
+                .make_fastest_connection(
+                    connection_ctx.clone(),
+                    &mut route_selection,
+                    authorized.clone(),
+                    Some(duration),
+                )
+                .await?;
+            (outlet_addr, connection, Some(route_selection))
+        };
 
         let (inlet, access_control) = self
             .node_manager
@@ -566,6 +874,9 @@ impl InMemoryNode {
                 prefix_route.clone(),
                 suffix_route.clone(),
                 outlet_addr.clone(),
+                data_flow,
+                quota,
+                expected_protocol,
             )
             .await?;
         if !connection.route(self.tcp_transport()).await?.is_empty() {
@@ -581,6 +892,15 @@ impl InMemoryNode {
                 format!("inlet-{}", inlet.alias),
             );
 
+            // The statistics and the canary are kept when the inlet is recreated
+            let (statistics, canary) = self
+                .node_manager
+                .registry
+                .inlets
+                .get(&inlet.alias)
+                .await
+                .map(|info| (info.statistics, info.canary))
+                .unwrap_or_default();
             let repl = Self::portal_replacer(
                 self.node_manager.clone(),
                 connection_ctx,
@@ -592,6 +912,12 @@ impl InMemoryNode {
                 suffix_route,
                 authorized,
                 access_control,
+                statistics,
+                canary,
+                data_flow,
+                quota,
+                expected_protocol,
+                route_selection,
             );
             session.set_replacer(repl);
             self.add_session(session);
@@ -616,16 +942,28 @@ impl InMemoryNode {
         suffix_route: Route,
         authorized: Option<Identifier>,
         access: Arc<dyn IncomingAccessControl>,
+        statistics: PortalStatistics,
+        canary: InletCanaryInfo,
+        data_flow: DataFlow,
+        quota: SessionQuota,
+        expected_protocol: Option<InletProtocol>,
+        route_selection: Option<RouteSelection>,
     ) -> Replacer {
         let connection_arc = Arc::new(Mutex::new(connection.clone()));
+        let route_selection_arc = Arc::new(Mutex::new(route_selection));
         let inlet_address_arc = Arc::new(Mutex::new(inlet_address));
         let node_manager = node_manager.clone();
 
         Box::new(move |previous_addr| {
-            let addr = addr.clone();
+            // A promoted canary replaces the original outlet
+            let canary_outlet_addr = canary.outlet_addr();
+            let promoted_canary = canary_outlet_addr.is_some();
+            let addr = canary_outlet_addr.unwrap_or_else(|| addr.clone());
+            let canary = canary.canary.clone();
             let authorized = authorized.clone();
             let bind = bind.clone();
             let access = access.clone();
+            let statistics = statistics.clone();
             let ctx = ctx.clone();
             let connection_arc = connection_arc.clone();
             let inlet_address_arc = inlet_address_arc.clone();
@@ -633,6 +971,8 @@ impl InMemoryNode {
             let prefix_route = prefix_route.clone();
             let suffix_route = suffix_route.clone();
             let previous_connection = connection_arc.lock().unwrap().clone();
+            let route_selection_arc = route_selection_arc.clone();
+            let route_selection = route_selection_arc.lock().unwrap().clone();
             let node_manager = node_manager.clone();
             Box::pin(async move {
                 debug!(%previous_addr, %addr, "creating new tcp inlet");
@@ -665,24 +1005,50 @@ impl InMemoryNode {
                         debug!("cannot stop inlet `{inlet_address}`: {error}");
                     }
 
-                    // Now a connection attempt is made
-                    let new_connection = node_manager
-                        .make_connection(
-                            ctx.clone(),
-                            &addr,
-                            None,
-                            authorized,
-                            None,
-                            Some(MAX_CONNECT_TIME),
-                        )
-                        .await?;
+                    // Now a connection attempt is made, on the route with the lowest latency
+                    // when there are several routes to the outlet
+                    let new_connection = match route_selection {
+                        Some(mut route_selection) if !promoted_canary => {
+                            let (_, new_connection) = node_manager
+                                .make_fastest_connection(
+                                    ctx.clone(),
+                                    &mut route_selection,
+                                    authorized,
+                                    Some(MAX_CONNECT_TIME),
+                                )
+                                .await?;
+                            *route_selection_arc.lock().unwrap() = Some(route_selection);
+                            new_connection
+                        }
+                        _ => {
+                            node_manager
+                                .make_connection(
+                                    ctx.clone(),
+                                    &addr,
+                                    None,
+                                    authorized,
+                                    None,
+                                    Some(MAX_CONNECT_TIME),
+                                )
+                                .await?
+                        }
+                    };
                     *connection_arc.lock().unwrap() = new_connection.clone();
                     let connection_route =
                         new_connection.route(node_manager.tcp_transport()).await?;
 
                     //we expect a fully normalized MultiAddr
                     let normalized_route = route![prefix_route, connection_route, suffix_route];
-                    let options = TcpInletOptions::new().with_incoming_access_control(access);
+                    let options = TcpInletOptions::new()
+                        .with_incoming_access_control(access)
+                        .with_statistics(statistics)
+                        .with_canary(canary)
+                        .with_data_flow(data_flow.into())
+                        .with_quota(quota.into());
+                    let options = match expected_protocol {
+                        Some(protocol) => options.with_expected_protocol(protocol.into()),
+                        None => options,
+                    };
 
                     // Finally attempt to create a new inlet using the new route:
                     let new_inlet_address = node_manager
@@ -719,9 +1085,13 @@ pub trait Inlets {
         ctx: &Context,
         listen_addr: &str,
         outlet_addr: &MultiAddr,
+        alternate_outlet_addrs: &[MultiAddr],
         alias: &Option<String>,
         authorized_identifier: &Option<Identifier>,
         wait_for_outlet_timeout: Duration,
+        data_flow: DataFlow,
+        quota: SessionQuota,
+        expected_protocol: Option<InletProtocol>,
     ) -> miette::Result<Reply<InletStatus>>;
 
     async fn show_inlet(
@@ -731,6 +1101,38 @@ pub trait Inlets {
     ) -> miette::Result<Reply<InletStatus>>;
 
     async fn delete_inlet(&self, ctx: &Context, inlet_alias: &str) -> miette::Result<Reply<()>>;
+
+    async fn start_inlet_canary(
+        &self,
+        ctx: &Context,
+        inlet_alias: &str,
+        request: StartInletCanary,
+    ) -> miette::Result<Reply<InletCanaryStatus>>;
+
+    async fn show_inlet_canary(
+        &self,
+        ctx: &Context,
+        inlet_alias: &str,
+    ) -> miette::Result<Reply<InletCanaryStatus>>;
+
+    async fn set_inlet_canary_weights(
+        &self,
+        ctx: &Context,
+        inlet_alias: &str,
+        request: SetInletCanaryWeights,
+    ) -> miette::Result<Reply<InletCanaryStatus>>;
+
+    async fn promote_inlet_canary(
+        &self,
+        ctx: &Context,
+        inlet_alias: &str,
+    ) -> miette::Result<Reply<InletCanaryStatus>>;
+
+    async fn rollback_inlet_canary(
+        &self,
+        ctx: &Context,
+        inlet_alias: &str,
+    ) -> miette::Result<Reply<InletCanaryStatus>>;
 }
 
 #[async_trait]
@@ -740,9 +1142,13 @@ impl Inlets for BackgroundNode {
         ctx: &Context,
         listen_addr: &str,
         outlet_addr: &MultiAddr,
+        alternate_outlet_addrs: &[MultiAddr],
         alias: &Option<String>,
         authorized_identifier: &Option<Identifier>,
         wait_for_outlet_timeout: Duration,
+        data_flow: DataFlow,
+        quota: SessionQuota,
+        expected_protocol: Option<InletProtocol>,
     ) -> miette::Result<Reply<InletStatus>> {
         self.add_policy_to_project(ctx, "tcp-inlet").await?;
         let request = {
@@ -767,6 +1173,12 @@ impl Inlets for BackgroundNode {
                 payload.set_alias(a.to_string())
             }
             payload.set_wait_ms(wait_for_outlet_timeout.as_millis() as u64);
+            payload.set_data_flow(data_flow);
+            payload.set_quota(quota);
+            payload.set_expected_protocol(expected_protocol);
+            if !alternate_outlet_addrs.is_empty() {
+                payload.set_alternate_outlet_addrs(alternate_outlet_addrs.to_vec());
+            }
             Request::post("/node/inlet").body(payload)
         };
         self.ask_and_get_reply(ctx, request).await
@@ -785,4 +1197,52 @@ impl Inlets for BackgroundNode {
         let request = Request::delete(format!("/node/inlet/{inlet_alias}"));
         self.tell_and_get_reply(ctx, request).await
     }
+
+    async fn start_inlet_canary(
+        &self,
+        ctx: &Context,
+        inlet_alias: &str,
+        request: StartInletCanary,
+    ) -> miette::Result<Reply<InletCanaryStatus>> {
+        let request = Request::put(format!("/node/inlet/{inlet_alias}/canary")).body(request);
+        self.ask_and_get_reply(ctx, request).await
+    }
+
+    async fn show_inlet_canary(
+        &self,
+        ctx: &Context,
+        inlet_alias: &str,
+    ) -> miette::Result<Reply<InletCanaryStatus>> {
+        let request = Request::get(format!("/node/inlet/{inlet_alias}/canary"));
+        self.ask_and_get_reply(ctx, request).await
+    }
+
+    async fn set_inlet_canary_weights(
+        &self,
+        ctx: &Context,
+        inlet_alias: &str,
+        request: SetInletCanaryWeights,
+    ) -> miette::Result<Reply<InletCanaryStatus>> {
+        let request =
+            Request::put(format!("/node/inlet/{inlet_alias}/canary/weights")).body(request);
+        self.ask_and_get_reply(ctx, request).await
+    }
+
+    async fn promote_inlet_canary(
+        &self,
+        ctx: &Context,
+        inlet_alias: &str,
+    ) -> miette::Result<Reply<InletCanaryStatus>> {
+        let request = Request::post(format!("/node/inlet/{inlet_alias}/canary/promote"));
+        self.ask_and_get_reply(ctx, request).await
+    }
+
+    async fn rollback_inlet_canary(
+        &self,
+        ctx: &Context,
+        inlet_alias: &str,
+    ) -> miette::Result<Reply<InletCanaryStatus>> {
+        let request = Request::post(format!("/node/inlet/{inlet_alias}/canary/rollback"));
+        self.ask_and_get_reply(ctx, request).await
+    }
 }
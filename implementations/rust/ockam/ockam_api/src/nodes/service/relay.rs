@@ -3,26 +3,27 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use ockam::compat::sync::Mutex;
-use ockam::identity::Identifier;
+use ockam::identity::{HandshakeRetryPolicy, Identifier};
 use ockam::remote::{RemoteRelay, RemoteRelayOptions};
 use ockam::Result;
+use ockam::{RelayRegistrations, LIST_RELAYS_REQUEST};
 use ockam_core::api::{Error, Request, RequestHeader, Response};
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{async_trait, Address, AsyncTryClone};
+use ockam_core::{async_trait, route, Address, AsyncTryClone};
 use ockam_multiaddr::proto::Project;
 use ockam_multiaddr::{MultiAddr, Protocol};
 use ockam_node::tokio::time::timeout;
-use ockam_node::Context;
+use ockam_node::{Context, MessageSendReceiveOptions};
 
-use crate::error::ApiError;
+use crate::error::{api_error_code, ApiError};
 use crate::nodes::connection::Connection;
-use crate::nodes::models::relay::{CreateRelay, RelayInfo};
+use crate::nodes::models::relay::{CreateRelay, RelayEvent, RelayInfo, RelayServiceEntry};
 use crate::nodes::models::secure_channel::{
     CreateSecureChannelRequest, CreateSecureChannelResponse,
 };
 use crate::nodes::service::in_memory_node::InMemoryNode;
 use crate::nodes::BackgroundNode;
-use crate::session::sessions::{Replacer, Session};
+use crate::session::sessions::{Replacer, Session, Status};
 use crate::session::sessions::{MAX_CONNECT_TIME, MAX_RECOVERY_TIME};
 
 use super::{NodeManager, NodeManagerWorker};
@@ -46,10 +47,18 @@ impl NodeManagerWorker {
             .await
         {
             Ok(body) => Ok(Response::ok(req).body(body)),
-            Err(err) => Err(Response::internal_error(
-                req,
-                &format!("Failed to create relay: {}", err),
-            )),
+            Err(err) => {
+                let message = format!("Failed to create relay: {}", err);
+                Err(match api_error_code(&err) {
+                    Some(code) => Response::error_with_code(
+                        req,
+                        &message,
+                        ockam_core::api::Status::InternalServerError,
+                        code,
+                    ),
+                    None => Response::internal_error(req, &message),
+                })
+            }
         }
     }
 
@@ -96,11 +105,31 @@ impl NodeManagerWorker {
         debug!("Handling GetRelays request");
         Ok(Response::ok(req).body(self.node_manager.get_relays().await))
     }
+
+    /// Return the relays registered on the relay service at the end of a route
+    pub async fn get_relay_service_entries(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        address: MultiAddr,
+    ) -> Result<Response<Vec<RelayServiceEntry>>, Response<Error>> {
+        match self
+            .node_manager
+            .get_relay_service_entries(ctx, &address, MAX_CONNECT_TIME)
+            .await
+        {
+            Ok(entries) => Ok(Response::ok(req).body(entries)),
+            Err(err) => Err(Response::internal_error(
+                req,
+                &format!("Failed to list the relays of the relay service at {address}: {err}"),
+            )),
+        }
+    }
 }
 
 impl NodeManager {
     /// This function returns a representation of the relays currently
-    /// registered on this node
+    /// registered on this node, with their liveness when they are monitored
     pub async fn get_relays(&self) -> Vec<RelayInfo> {
         let relays = self
             .registry
@@ -108,12 +137,66 @@ impl NodeManager {
             .entries()
             .await
             .iter()
-            .map(|(_, registry_info)| RelayInfo::from(registry_info.to_owned()))
+            .map(|(_, registry_info)| {
+                let relay = RelayInfo::from(registry_info.to_owned());
+                let created_at = self
+                    .medic_handle
+                    .created_at_of(&format!("relay-{}", relay.remote_address()))
+                    .map(|t| *t);
+                let up = self.relay_is_up(&relay);
+                relay.with_liveness(created_at, up)
+            })
             .collect();
         trace!(?relays, "Relays retrieved");
         relays
     }
 
+    /// Return the relays registered on the relay service at the end of a route, for example
+    /// `/project/<name>/service/forwarding_service`
+    pub async fn get_relay_service_entries(
+        &self,
+        ctx: &Context,
+        addr: &MultiAddr,
+        timeout: Duration,
+    ) -> Result<Vec<RelayServiceEntry>> {
+        let connection_ctx = Arc::new(ctx.async_try_clone().await?);
+        let connection = self
+            .make_connection(connection_ctx, addr, None, None, None, Some(timeout))
+            .await?;
+        let route = connection.route(self.tcp_transport()).await?;
+        let registrations = ctx
+            .send_and_receive_extended::<RelayRegistrations>(
+                route,
+                LIST_RELAYS_REQUEST.to_string(),
+                MessageSendReceiveOptions::new().with_timeout(timeout),
+            )
+            .await?
+            .body();
+        Ok(registrations
+            .0
+            .into_iter()
+            .map(RelayServiceEntry::from)
+            .collect())
+    }
+
+    /// Return the most recent status changes of a relay, as monitored by the medic
+    fn relay_events(&self, remote_address: &str) -> Vec<RelayEvent> {
+        self.medic_handle
+            .events_of(&format!("relay-{remote_address}"))
+            .unwrap_or_default()
+            .into_iter()
+            .map(RelayEvent::from)
+            .collect()
+    }
+
+    /// Return true if the session of a relay is up, as monitored by the medic,
+    /// or None if the relay is not monitored
+    pub fn relay_is_up(&self, relay: &RelayInfo) -> Option<bool> {
+        self.medic_handle
+            .status_of(&format!("relay-{}", relay.remote_address()))
+            .map(|status| status == Status::Up)
+    }
+
     /// Create a new Relay
     /// The Connection encapsulates the list of workers required on the relay route.
     /// This route is monitored in the `InMemoryNode` and the workers are restarted if necessary
@@ -206,8 +289,9 @@ impl NodeManager {
     ) -> Result<Response<Option<RelayInfo>>, Response<Error>> {
         debug!("Handling ShowRelay request");
         if let Some(relay) = self.registry.relays.get(remote_address).await {
-            debug!(%remote_address, "Relay not found in node registry");
-            Ok(Response::ok(req).body(Some(RelayInfo::from(relay.to_owned()))))
+            let relay_info =
+                RelayInfo::from(relay.to_owned()).with_events(self.relay_events(remote_address));
+            Ok(Response::ok(req).body(Some(relay_info)))
         } else {
             error!(%remote_address, "Relay not found in the node registry");
             Err(Response::not_found(
@@ -258,12 +342,19 @@ impl InMemoryNode {
             .await?;
 
         if !at_rust_node && !connection.transport_route().is_empty() {
-            let ping_route = connection.transport_route().clone();
+            // A relay with an alias is pinged through its registration at the remote node,
+            // so that it is registered again if that registration is dropped
+            let ping_route = if alias.is_some() {
+                route![connection.transport_route(), relay.remote_address()]
+            } else {
+                connection.transport_route()
+            };
             let repl = Self::relay_replacer(
                 self.node_manager.clone(),
                 Arc::new(ctx.async_try_clone().await?),
                 connection,
                 address.clone(),
+                relay.remote_address().to_string(),
                 alias,
                 authorized,
             );
@@ -290,12 +381,14 @@ impl InMemoryNode {
     ///
     /// This returns a function that accepts the previous ping address (e.g.
     /// the secure channel worker address) and constructs the whole route
-    /// again.
+    /// again. The new relay replaces the previous one in the node registry, under the same
+    /// remote address.
     fn relay_replacer(
         node_manager: Arc<NodeManager>,
         ctx: Arc<Context>,
         connection: Connection,
         addr: MultiAddr,
+        remote_address: String,
         alias: Option<String>,
         authorized: Option<Identifier>,
     ) -> Replacer {
@@ -304,6 +397,7 @@ impl InMemoryNode {
         Box::new(move |prev_route| {
             let ctx = ctx.clone();
             let addr = addr.clone();
+            let remote_address = remote_address.clone();
             let alias = alias.clone();
             let authorized = authorized.clone();
             let connection_arc = connection_arc.clone();
@@ -314,6 +408,15 @@ impl InMemoryNode {
                 debug!(%prev_route, %addr, "creating new remote relay");
 
                 let f = async {
+                    // The previous relay worker is replaced under the same remote address
+                    if let Some(previous_relay) =
+                        node_manager.registry.relays.get(&remote_address).await
+                    {
+                        let worker_address = previous_relay.worker_address().clone();
+                        if let Err(error) = ctx.stop_worker(worker_address.clone()).await {
+                            debug!("cannot stop relay worker `{worker_address}`: {error}");
+                        }
+                    }
                     for encryptor in &previous_connection.secure_channel_encryptors {
                         if let Err(error) = node_manager
                             .delete_secure_channel(&ctx.clone(), encryptor)
@@ -349,12 +452,22 @@ impl InMemoryNode {
                     let route = connection.route(node_manager.tcp_transport()).await?;
 
                     let options = RemoteRelayOptions::new();
-                    if let Some(alias) = &alias {
-                        RemoteRelay::create_static(&ctx, route, alias, options).await?;
+                    let (relay, ping_route) = if let Some(alias) = &alias {
+                        let relay = RemoteRelay::create_static(&ctx, route, alias, options).await?;
+                        let ping_route =
+                            route![connection.transport_route(), relay.remote_address()];
+                        (relay, ping_route)
                     } else {
-                        RemoteRelay::create(&ctx, route, options).await?;
-                    }
-                    Ok(connection.transport_route())
+                        let relay = RemoteRelay::create(&ctx, route, options).await?;
+                        (relay, connection.transport_route())
+                    };
+                    info!(%addr, remote_address = %relay.remote_address(), "relay registered again");
+                    node_manager
+                        .registry
+                        .relays
+                        .insert(remote_address, relay)
+                        .await;
+                    Ok(ping_route)
                 };
                 match timeout(MAX_RECOVERY_TIME, f).await {
                     Err(_) => {
@@ -381,6 +494,13 @@ pub trait Relays {
         alias: Option<String>,
         authorized: Option<Identifier>,
     ) -> miette::Result<RelayInfo>;
+
+    /// Return the relays registered on the relay service at the end of a route
+    async fn get_relay_service_entries(
+        &self,
+        ctx: &Context,
+        address: &MultiAddr,
+    ) -> miette::Result<Vec<RelayServiceEntry>>;
 }
 
 #[async_trait]
@@ -397,6 +517,18 @@ impl Relays for BackgroundNode {
         self.ask(ctx, Request::post("/node/forwarder").body(body))
             .await
     }
+
+    async fn get_relay_service_entries(
+        &self,
+        ctx: &Context,
+        address: &MultiAddr,
+    ) -> miette::Result<Vec<RelayServiceEntry>> {
+        self.ask(
+            ctx,
+            Request::post("/node/relay_service").body(address.clone()),
+        )
+        .await
+    }
 }
 
 #[async_trait]
@@ -431,6 +563,9 @@ impl SecureChannelsCreation for InMemoryNode {
                 Some(vec![authorized]),
                 credential_name,
                 timeout,
+                None,
+                false,
+                HandshakeRetryPolicy::default(),
             )
             .await
             .into_diagnostic()
@@ -0,0 +1,122 @@
+use ockam::Result;
+use ockam_core::api::{Error, RequestHeader, Response};
+
+use crate::cli_state::{StateDirTrait, StateItemTrait};
+use crate::config::cli::TrustContextConfig;
+use crate::config::lookup::ProjectLookup;
+use crate::error::ApiError;
+use crate::nodes::models::base::NodeReloaded;
+
+use super::{NodeManager, NodeManagerWorker};
+
+impl NodeManagerWorker {
+    pub(super) async fn reload(
+        &self,
+        req: &RequestHeader,
+    ) -> Result<Response<NodeReloaded>, Response<Error>> {
+        match self.node_manager.reload_trust_context().await {
+            Ok(reloaded) => Ok(Response::ok(req).body(reloaded)),
+            Err(err) => Err(Response::internal_error(
+                req,
+                &format!("Failed to reload the node: {}", err),
+            )),
+        }
+    }
+}
+
+impl NodeManager {
+    /// Read the trust context of this node from the CLI state again, and use it from now on.
+    ///
+    /// The trust context is read from the file it was loaded from, or from the project of the
+    /// node. The portals, relays and secure channels which already exist are kept. The new secure
+    /// channels, credential checks and credential requests use the reloaded trust context.
+    pub async fn reload_trust_context(&self) -> Result<NodeReloaded> {
+        let current = self
+            .trust_context_config
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| {
+                ApiError::core("The node was started without a trust context, it can't be reloaded")
+            })?;
+
+        let node_state = self.cli_state.nodes.get(&self.node_name)?;
+        let config = if let Some(path) = current.path() {
+            self.cli_state
+                .trust_contexts
+                .read_config_from_path(&path.to_string_lossy())?
+        } else if let Some(project) = node_state.config().setup().project.clone() {
+            // The project may have been updated since the node started
+            let project = self.cli_state.projects.get(&project.name)?;
+            let lookup = ProjectLookup::from_project(project.config()).await?;
+            node_state.set_setup(node_state.config().setup_mut().set_project(lookup))?;
+            TrustContextConfig::try_from(project.config().clone())?
+        } else {
+            current
+        };
+
+        // The authority may have rotated its keys: its latest change history must be known
+        // by the node to verify the credentials it issues from now on
+        let authority_identifier = match config.authority() {
+            Ok(authority) => {
+                let change_history = hex::decode(authority.identity_str())
+                    .map_err(|_| ApiError::core("Unable to decode the authority identity"))?;
+                let identity = self
+                    .identities()
+                    .identities_creation()
+                    .import(None, &change_history)
+                    .await?;
+                Some(identity.identifier().to_string())
+            }
+            Err(_) => None,
+        };
+
+        self.configure_trust_context(&config).await?;
+        info!(
+            node = %self.node_name,
+            trust_context = %config.id(),
+            "the trust context of the node was reloaded"
+        );
+
+        Ok(NodeReloaded {
+            trust_context_id: config.id().to_string(),
+            authority_identifier,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ockam_node::Context;
+
+    use super::*;
+    use crate::test_utils::start_manager_for_tests;
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test]
+    async fn reload_trust_context__same_config__authority_is_kept(
+        context: &mut Context,
+    ) -> Result<()> {
+        let handler = start_manager_for_tests(context).await?;
+        let authority = handler
+            .node_manager
+            .trust_context()?
+            .authority()?
+            .identifier()
+            .to_string();
+
+        let reloaded = handler.node_manager.reload_trust_context().await?;
+        assert_eq!(reloaded.trust_context_id, "test_trust_context");
+        assert_eq!(reloaded.authority_identifier, Some(authority.clone()));
+
+        // The new secure channels use the reloaded trust context
+        let trust_context = handler.node_manager.trust_context()?;
+        assert_eq!(trust_context.id(), "test_trust_context");
+        assert_eq!(
+            trust_context.authority()?.identifier().to_string(),
+            authority
+        );
+
+        context.stop().await
+    }
+}
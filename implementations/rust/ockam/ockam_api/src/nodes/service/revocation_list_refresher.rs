@@ -0,0 +1,72 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use ockam::identity::{AuthorityService, Identifier};
+use ockam::{Address, Context, Result};
+use ockam_core::{AllowAll, DenyAll};
+
+/// Delay between two retrievals of the revocation list
+const REFRESH_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// Handle on a background task which periodically retrieves the revocation list of an authority,
+/// so that the revoked credentials presented to the node are rejected
+#[derive(Default)]
+pub(crate) struct RevocationListRefresherHandle {
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl RevocationListRefresherHandle {
+    /// Start refreshing the revocation list of the authority. A previously started refresher is stopped
+    pub(crate) async fn start(
+        &self,
+        ctx: &Context,
+        authority: AuthorityService,
+        identifier: Identifier,
+    ) -> Result<()> {
+        let ctx = ctx
+            .new_detached(
+                Address::random_tagged("RevocationListRefresher.ctx"),
+                DenyAll,
+                AllowAll,
+            )
+            .await?;
+        let handle = tokio::spawn(refresh_revocation_list(ctx, authority, identifier));
+        if let Some(previous) = self.handle.lock().unwrap().replace(handle) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    /// Stop the revocation list refresher
+    pub(crate) fn stop(&self) {
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+/// Retrieve the revocation list of the authority, then retrieve it again periodically.
+///
+/// This function never returns. Failed retrievals are retried after the refresh delay
+async fn refresh_revocation_list(
+    ctx: Context,
+    authority: AuthorityService,
+    identifier: Identifier,
+) {
+    loop {
+        match authority.refresh_revocation_list(&ctx, &identifier).await {
+            Ok(()) => debug!(
+                "refreshed the revocation list of the authority {}",
+                authority.identifier()
+            ),
+            Err(e) => warn!(
+                "the revocation list of the authority {} could not be refreshed: {e}",
+                authority.identifier()
+            ),
+        }
+        tokio::time::sleep(REFRESH_DELAY).await;
+    }
+}
@@ -1,18 +1,20 @@
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::time::Duration;
 
 use minicbor::Decoder;
 
 use ockam::identity::models::CredentialAndPurposeKey;
-use ockam::identity::TrustEveryonePolicy;
 use ockam::identity::Vault;
+use ockam::identity::{AllTrustPolicy, AnyTrustPolicy, TrustEveryonePolicy};
 use ockam::identity::{
-    Identifier, Identities, SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
+    HandshakePattern, HandshakeRetryPolicy, Identifier, Identities, PreSharedKey, RekeyPolicy,
+    SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
     TrustMultiIdentifiersPolicy,
 };
 use ockam::identity::{SecureChannel, SecureChannelListener};
 use ockam::{Address, Result, Route};
-use ockam_core::api::{Error, RequestHeader, Response};
+use ockam_core::api::{Error, RequestHeader, Response, Status};
 use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::AsyncTryClone;
@@ -21,13 +23,17 @@ use ockam_node::Context;
 
 use crate::cli_state::traits::StateDirTrait;
 use crate::cli_state::StateItemTrait;
+use crate::cli_state::{IdentityUsageKind, PinCheck};
+use crate::error::api_error_code;
+use crate::nodes::models::quota::SessionQuota;
 use crate::nodes::models::secure_channel::{
     CreateSecureChannelListenerRequest, CreateSecureChannelRequest, CreateSecureChannelResponse,
     DeleteSecureChannelListenerRequest, DeleteSecureChannelListenerResponse,
     DeleteSecureChannelRequest, DeleteSecureChannelResponse, SecureChannelListenersList,
-    ShowSecureChannelListenerRequest, ShowSecureChannelListenerResponse, ShowSecureChannelRequest,
-    ShowSecureChannelResponse,
+    SecureChannelState, ShowSecureChannelListenerRequest, ShowSecureChannelListenerResponse,
+    ShowSecureChannelRequest, ShowSecureChannelResponse,
 };
+use crate::nodes::pairing::{PairingApproval, PairingTrustPolicy, PinnedPeerTrustPolicy};
 use crate::nodes::registry::{SecureChannelInfo, SecureChannelListenerInfo};
 use crate::nodes::service::NodeIdentities;
 use crate::nodes::{NodeManager, NodeManagerWorker};
@@ -45,6 +51,13 @@ impl NodeManagerWorker {
         )
     }
 
+    pub async fn list_secure_channels_state(
+        &self,
+        req: &RequestHeader,
+    ) -> Response<Vec<SecureChannelState>> {
+        Response::ok(req).body(self.node_manager.list_secure_channels_state())
+    }
+
     pub(super) async fn create_secure_channel(
         &mut self,
         req: &RequestHeader,
@@ -57,7 +70,9 @@ impl NodeManagerWorker {
             timeout,
             identity_name: identity,
             credential_name,
-            ..
+            pq_hybrid,
+            handshake_retries,
+            handshake_backoff,
         } = dec.decode()?;
 
         // credential retrieved from request
@@ -83,6 +98,31 @@ impl NodeManagerWorker {
                 ))
             }
         };
+        // Without authorized identifiers, the identifier of the peer is pinned on first use
+        let peer_pin = match authorized_identifiers {
+            Some(_) => None,
+            None => {
+                let strict = self
+                    .node_manager
+                    .cli_state
+                    .settings
+                    .get()
+                    .map(|s| s.strict_pins.unwrap_or(false))
+                    .unwrap_or(false);
+                Some(PinnedPeerTrustPolicy::new(
+                    addr.to_string(),
+                    self.node_manager.cli_state.trust_pins.clone(),
+                    strict,
+                ))
+            }
+        };
+        let mut retry_policy = HandshakeRetryPolicy::default();
+        if let Some(retries) = handshake_retries {
+            retry_policy = retry_policy.with_max_retries(retries);
+        }
+        if let Some(backoff) = handshake_backoff {
+            retry_policy = retry_policy.with_initial_backoff(backoff);
+        }
         let sc = self
             .node_manager
             .create_secure_channel(
@@ -92,13 +132,27 @@ impl NodeManagerWorker {
                 authorized_identifiers,
                 credential_name,
                 timeout,
+                peer_pin.clone(),
+                pq_hybrid.unwrap_or(false),
+                retry_policy,
             )
-            .await?;
-
-        let response = Response::ok(req).body(CreateSecureChannelResponse::new(
-            sc.encryptor_address(),
-            sc.flow_control_id(),
-        ));
+            .await
+            .map_err(|e| match api_error_code(&e) {
+                Some(code) => Response::error_with_code(
+                    req,
+                    &e.to_string(),
+                    Status::InternalServerError,
+                    code,
+                ),
+                None => Response::internal_error(req, &e.to_string()),
+            })?;
+
+        let mut body =
+            CreateSecureChannelResponse::new(sc.encryptor_address(), sc.flow_control_id());
+        if let Some(PinCheck::Changed { pinned }) = peer_pin.and_then(|p| p.outcome()) {
+            body = body.with_pinned_identifier(pinned);
+        }
+        let response = Response::ok(req).body(body);
 
         Ok(response)
     }
@@ -150,7 +204,15 @@ impl NodeManagerWorker {
             authorized_identifiers,
             vault_name,
             identity_name,
-            ..
+            quota,
+            pairing,
+            hide_identity,
+            handshake_patterns,
+            pre_shared_key,
+            rekey_after_bytes,
+            rekey_after_secs,
+            required_attributes,
+            progressive_trust,
         } = dec.decode()?;
 
         let authorized_identifiers = match authorized_identifiers {
@@ -173,12 +235,41 @@ impl NodeManagerWorker {
             ));
         }
 
+        let pre_shared_key = match pre_shared_key {
+            Some(key) => {
+                let key = hex::decode(key)
+                    .ok()
+                    .and_then(|key| <[u8; 32]>::try_from(key).ok())
+                    .ok_or_else(|| {
+                        Response::bad_request(
+                            req,
+                            "The pre-shared key must be 32 hex encoded bytes",
+                        )
+                    })?;
+                Some(PreSharedKey::new(key))
+            }
+            None => None,
+        };
+
+        let rekey_policy = RekeyPolicy {
+            after_bytes: rekey_after_bytes,
+            after_duration: rekey_after_secs.map(Duration::from_secs),
+        };
+
         self.node_manager
             .create_secure_channel_listener(
                 addr,
                 authorized_identifiers,
                 vault_name,
                 identity_name,
+                quota,
+                pairing,
+                hide_identity.unwrap_or(false),
+                handshake_patterns,
+                pre_shared_key,
+                rekey_policy,
+                required_attributes.unwrap_or_default(),
+                progressive_trust.unwrap_or(false),
                 ctx,
             )
             .await?;
@@ -257,6 +348,13 @@ impl NodeManagerWorker {
 
 /// SECURE CHANNELS
 impl NodeManager {
+    /// Create a secure channel to the node at `addr`.
+    ///
+    /// When `peer_pin` is set, the identifier of the peer is checked against the identifier
+    /// pinned for that address, in addition to the authorized identifiers.
+    /// When `pq_hybrid` is set, the handshake uses the post-quantum hybrid key agreement.
+    /// A handshake which times out is retried according to the `retry_policy`
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_secure_channel(
         &self,
         ctx: &Context,
@@ -265,6 +363,9 @@ impl NodeManager {
         authorized_identifiers: Option<Vec<Identifier>>,
         credential_name: Option<String>,
         timeout: Option<Duration>,
+        peer_pin: Option<PinnedPeerTrustPolicy>,
+        pq_hybrid: bool,
+        retry_policy: HandshakeRetryPolicy,
     ) -> Result<SecureChannel> {
         let identifier = self.get_identifier(identity_name.clone()).await?;
         let credential = self
@@ -290,6 +391,9 @@ impl NodeManager {
                 authorized_identifiers,
                 timeout,
                 credential,
+                peer_pin,
+                pq_hybrid,
+                retry_policy,
             )
             .await?;
 
@@ -336,6 +440,7 @@ impl NodeManager {
         Ok(credential)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn create_secure_channel_internal(
         &self,
         ctx: &Context,
@@ -344,31 +449,46 @@ impl NodeManager {
         authorized_identifiers: Option<Vec<Identifier>>,
         timeout: Option<Duration>,
         credential: Option<CredentialAndPurposeKey>,
+        peer_pin: Option<PinnedPeerTrustPolicy>,
+        pq_hybrid: bool,
+        retry_policy: HandshakeRetryPolicy,
     ) -> Result<SecureChannel> {
         debug!(%sc_route, "Creating secure channel");
-        let options = SecureChannelOptions::new();
+        let options = SecureChannelOptions::new().with_retry_policy(retry_policy);
 
-        let options = if let Some(timeout) = timeout {
-            options.with_timeout(timeout)
+        let options = if pq_hybrid {
+            options.with_pq_hybrid()
         } else {
             options
         };
 
-        let options = if let Some(credential) = credential {
-            options.with_credential(credential)
-        } else if let Some(credential) = self.get_credential(ctx, identifier, None, timeout).await?
-        {
-            options.with_credential(credential)
+        let options = if let Some(timeout) = timeout {
+            options.with_timeout(timeout)
         } else {
             options
         };
 
-        let options = match authorized_identifiers.clone() {
-            Some(ids) => options.with_trust_policy(TrustMultiIdentifiersPolicy::new(ids)),
-            None => options.with_trust_policy(TrustEveryonePolicy),
+        let credential = match credential {
+            Some(credential) => Some(credential),
+            None => self.get_credential(ctx, identifier, None, timeout).await?,
+        };
+        let presents_credential = credential.is_some();
+        let options = match credential {
+            Some(credential) => options.with_credential(credential),
+            None => options,
+        };
+
+        let options = match (authorized_identifiers.clone(), peer_pin) {
+            (Some(ids), Some(peer_pin)) => options.with_trust_policy(AllTrustPolicy::new(
+                TrustMultiIdentifiersPolicy::new(ids),
+                peer_pin,
+            )),
+            (Some(ids), None) => options.with_trust_policy(TrustMultiIdentifiersPolicy::new(ids)),
+            (None, Some(peer_pin)) => options.with_trust_policy(peer_pin),
+            (None, None) => options.with_trust_policy(TrustEveryonePolicy),
         };
 
-        let options = match self.trust_context.clone() {
+        let options = match self.trust_context().ok() {
             Some(trust_context) => options.with_trust_context(trust_context),
             None => options,
         };
@@ -379,6 +499,10 @@ impl NodeManager {
             .await?;
 
         debug!(%sc_route, %sc, "Created secure channel");
+        self.record_identity_usage(identifier, IdentityUsageKind::SecureChannel);
+        if presents_credential {
+            self.record_identity_usage(identifier, IdentityUsageKind::CredentialPresentation);
+        }
 
         self.registry
             .secure_channels
@@ -390,7 +514,26 @@ impl NodeManager {
 
     pub async fn delete_secure_channel(&self, ctx: &Context, addr: &Address) -> Result<()> {
         debug!(%addr, "deleting secure channel");
+        // Only stop established channels, either initiated or accepted by the node,
+        // and not any other worker
+        let entry = self
+            .secure_channels
+            .secure_channel_registry()
+            .get_channel_by_encryptor_address(addr)
+            .ok_or_else(|| {
+                ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::NotFound,
+                    format!("There is no secure channel at {addr}"),
+                )
+            })?;
         self.secure_channels.stop_secure_channel(ctx, addr).await?;
+        info!(
+            %addr,
+            their_identifier = %entry.their_id(),
+            is_initiator = entry.is_initiator(),
+            "secure channel closed"
+        );
         self.registry.secure_channels.remove_by_addr(addr).await;
         Ok(())
     }
@@ -404,16 +547,36 @@ impl NodeManager {
         let registry = &self.registry.secure_channels;
         registry.list().await
     }
+
+    /// Return the live state of all the secure channels of the node, including the channels
+    /// accepted by its listeners
+    pub fn list_secure_channels_state(&self) -> Vec<SecureChannelState> {
+        self.secure_channels
+            .secure_channel_registry()
+            .get_channel_list()
+            .iter()
+            .map(SecureChannelState::new)
+            .collect()
+    }
 }
 
 /// SECURE CHANNEL LISTENERS
 impl NodeManager {
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_secure_channel_listener(
         &self,
         address: Address,
         authorized_identifiers: Option<Vec<Identifier>>,
         vault_name: Option<String>,
         identity_name: Option<String>,
+        quota: SessionQuota,
+        pairing: Option<PairingApproval>,
+        hide_identity: bool,
+        handshake_patterns: Option<Vec<HandshakePattern>>,
+        pre_shared_key: Option<PreSharedKey>,
+        rekey_policy: RekeyPolicy,
+        required_attributes: BTreeMap<String, String>,
+        progressive_trust: bool,
         ctx: &Context,
     ) -> Result<SecureChannelListener> {
         debug!(
@@ -424,16 +587,55 @@ impl NodeManager {
         let secure_channels = self.build_secure_channels(vault_name.clone()).await?;
         let identifier = self.get_identifier(identity_name.clone()).await?;
 
-        let options =
-            SecureChannelListenerOptions::new().as_consumer(&self.api_transport_flow_control_id);
-
-        let options = match authorized_identifiers {
-            Some(ids) => options.with_trust_policy(TrustMultiIdentifiersPolicy::new(ids)),
-            None => options.with_trust_policy(TrustEveryonePolicy),
+        let options = SecureChannelListenerOptions::new()
+            .as_consumer(&self.api_transport_flow_control_id)
+            .with_quota(quota.into())
+            .with_rekey_policy(rekey_policy);
+
+        // With pairing, the authorized identifiers are accepted without asking for an approval
+        let options = match (authorized_identifiers, pairing) {
+            (Some(ids), None) => options.with_trust_policy(TrustMultiIdentifiersPolicy::new(ids)),
+            (None, None) => options.with_trust_policy(TrustEveryonePolicy),
+            (ids, Some(approval)) => {
+                let path = self.cli_state.nodes.get(&self.node_name)?.pairings();
+                let pairing = PairingTrustPolicy::new(approval, path);
+                options.with_trust_policy(AnyTrustPolicy::new(
+                    TrustMultiIdentifiersPolicy::new(ids.unwrap_or_default()),
+                    pairing,
+                ))
+            }
         };
 
         let options = if let Ok(trust_context) = self.trust_context() {
-            options.with_trust_context(trust_context.clone())
+            options.with_trust_context(trust_context)
+        } else {
+            options
+        };
+
+        let options = if hide_identity {
+            options.with_identity_hiding()
+        } else {
+            options
+        };
+
+        let options = match pre_shared_key {
+            Some(pre_shared_key) => options.with_pre_shared_key(pre_shared_key),
+            None => options,
+        };
+
+        let options = match handshake_patterns {
+            Some(handshake_patterns) => options.with_handshake_patterns(handshake_patterns),
+            None => options,
+        };
+
+        let options = required_attributes
+            .into_iter()
+            .fold(options, |options, (name, value)| {
+                options.with_required_attribute(name, value)
+            });
+
+        let options = if progressive_trust {
+            options.with_progressive_trust()
         } else {
             options
         };
@@ -460,11 +662,20 @@ impl NodeManager {
             listener.flow_control_id(),
         );
 
+        ctx.flow_controls()
+            .add_consumer(DefaultAddress::CHAT_SERVICE, listener.flow_control_id());
+
         ctx.flow_controls().add_consumer(
             DefaultAddress::CREDENTIALS_SERVICE,
             listener.flow_control_id(),
         );
 
+        // Exec services only accept messages received over a secure channel
+        for address in self.registry.exec_services.keys().await {
+            ctx.flow_controls()
+                .add_consumer(address, listener.flow_control_id());
+        }
+
         Ok(listener)
     }
 
@@ -524,6 +735,19 @@ impl NodeManager {
         }
     }
 
+    /// Record the use of a stored identity by this node.
+    /// A failure is only logged since it must not prevent the identity from being used
+    pub(crate) fn record_identity_usage(&self, identifier: &Identifier, kind: IdentityUsageKind) {
+        if let Err(e) = self
+            .cli_state
+            .identities
+            .get_by_identifier(identifier)
+            .and_then(|identity| identity.record_usage(kind, &self.node_name()))
+        {
+            debug!(%identifier, %e, "the usage of the identity could not be recorded");
+        }
+    }
+
     async fn get_identities(&self, vault_name: Option<String>) -> Result<Arc<Identities>> {
         self.node_identities().get_identities(vault_name).await
     }
@@ -537,3 +761,34 @@ impl NodeManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ockam::workers::Echoer;
+
+    use super::*;
+    use crate::test_utils::start_manager_for_tests;
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test]
+    async fn delete_secure_channel__unknown_channel__not_found(
+        context: &mut Context,
+    ) -> Result<()> {
+        let handler = start_manager_for_tests(context).await?;
+
+        // A worker which is not a secure channel is not stopped
+        context.start_worker("not_a_channel", Echoer).await?;
+        let err = handler
+            .node_manager
+            .delete_secure_channel(context, &Address::from_string("not_a_channel"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code().kind, Kind::NotFound);
+        assert!(context
+            .list_workers()
+            .await?
+            .contains(&Address::from_string("not_a_channel")));
+
+        context.stop().await
+    }
+}
@@ -0,0 +1,121 @@
+use ockam::Result;
+use ockam_core::api::{Error, RequestHeader, Response};
+use ockam_node::Context;
+
+use crate::allocator;
+use crate::nodes::models::stats::{HeapStats, NodeStats, PortalKind, PortalStats, WorkerStats};
+
+use super::{NodeManager, NodeManagerWorker};
+
+impl NodeManagerWorker {
+    pub(super) async fn get_stats(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+    ) -> Result<Response<NodeStats>, Response<Error>> {
+        match self.node_manager.stats(ctx).await {
+            Ok(stats) => Ok(Response::ok(req).body(stats)),
+            Err(err) => Err(Response::internal_error(
+                req,
+                &format!("Failed to collect the node statistics: {}", err),
+            )),
+        }
+    }
+
+    pub(super) async fn get_heap_stats(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+    ) -> Result<Response<HeapStats>, Response<Error>> {
+        if !self.node_manager.debug_endpoints {
+            return Err(Response::forbidden(
+                req,
+                "The debug endpoints of this node are disabled",
+            ));
+        }
+        match self.node_manager.heap_stats(ctx).await {
+            Ok(stats) => Ok(Response::ok(req).body(stats)),
+            Err(err) => Err(Response::internal_error(
+                req,
+                &format!("Failed to collect the heap statistics: {}", err),
+            )),
+        }
+    }
+}
+
+impl NodeManager {
+    /// Return the current statistics of the workers, secure channels and portals of this node
+    pub async fn stats(&self, ctx: &Context) -> Result<NodeStats> {
+        let workers = ctx
+            .worker_stats()
+            .await?
+            .into_iter()
+            .map(WorkerStats::from)
+            .collect();
+
+        let inlets = self.registry.inlets.entries().await;
+        let outlets = self.registry.outlets.entries().await;
+        let portals =
+            inlets
+                .iter()
+                .map(|(alias, info)| PortalStats::new(alias, PortalKind::Inlet, &info.statistics))
+                .chain(outlets.iter().map(|(alias, info)| {
+                    PortalStats::new(alias, PortalKind::Outlet, &info.statistics)
+                }))
+                .collect();
+
+        Ok(NodeStats {
+            workers,
+            secure_channels: self.registry.secure_channels.list().await.len() as u32,
+            secure_channel_listeners: self.registry.secure_channel_listeners.keys().await.len()
+                as u32,
+            portals,
+        })
+    }
+
+    /// Return the allocator statistics of this node, and the number of objects of each kind
+    /// which retain some memory
+    pub async fn heap_stats(&self, ctx: &Context) -> Result<HeapStats> {
+        let workers = ctx.worker_stats().await?;
+        let processors = workers.iter().filter(|w| w.processor).count();
+        let buffered_messages = workers.iter().map(|w| w.mailbox_len as u64).sum();
+
+        Ok(HeapStats {
+            allocator: allocator::statistics().map(Into::into),
+            workers: (workers.len() - processors) as u32,
+            processors: processors as u32,
+            secure_channels: self.registry.secure_channels.list().await.len() as u32,
+            secure_channel_listeners: self.registry.secure_channel_listeners.keys().await.len()
+                as u32,
+            sessions: self.medic_handle.sessions_count() as u32,
+            relays: self.registry.relays.keys().await.len() as u32,
+            inlets: self.registry.inlets.keys().await.len() as u32,
+            outlets: self.registry.outlets.keys().await.len() as u32,
+            buffered_messages,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ockam::workers::Echoer;
+
+    use super::*;
+    use crate::test_utils::start_manager_for_tests;
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test]
+    async fn heap_stats__new_worker__is_counted(context: &mut Context) -> Result<()> {
+        let handler = start_manager_for_tests(context).await?;
+        let before = handler.node_manager.heap_stats(context).await?;
+
+        context.start_worker("heap_stats_echoer", Echoer).await?;
+        let after = handler.node_manager.heap_stats(context).await?;
+        assert_eq!(after.workers, before.workers + 1);
+        assert_eq!(after.processors, before.processors);
+        // The tests don't use the counting allocator
+        assert!(after.allocator.is_none());
+
+        context.stop().await
+    }
+}
@@ -5,16 +5,33 @@ use minicbor::Decoder;
 use ockam::Result;
 use ockam_core::api::{Error, RequestHeader, Response};
 use ockam_core::Address;
+#[cfg(feature = "udp")]
+use ockam_core::AsyncTryClone;
+#[cfg(feature = "udp")]
+use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 use ockam_transport_tcp::{
-    TcpConnectionOptions, TcpListenerInfo, TcpListenerOptions, TcpSenderInfo, TcpTransport,
+    TcpConnectionOptions, TcpListenerInfo, TcpListenerOptions, TcpListenerTls, TcpSenderInfo,
+    TcpTransport,
 };
+#[cfg(feature = "udp")]
+use ockam_transport_udp::{UdpHolePuncher, UdpListenerOptions};
 
+#[cfg(feature = "udp")]
+use crate::multiaddr_to_transport_route;
 use crate::nodes::models::transport::{
     CreateTcpConnection, CreateTcpListener, DeleteTransport, TransportList, TransportMode,
     TransportStatus, TransportType,
 };
+#[cfg(feature = "udp")]
+use crate::nodes::models::transport::{
+    CreateUdpListener, CreateUdpPuncher, UdpPuncherList, UdpPuncherStatus,
+};
+#[cfg(feature = "udp")]
+use crate::nodes::registry::{UdpListenerInfo, UdpPuncherInfo};
 use crate::nodes::service::ApiTransport;
+#[cfg(feature = "udp")]
+use crate::DefaultAddress;
 
 use super::NodeManagerWorker;
 
@@ -232,13 +249,28 @@ impl NodeManagerWorker {
         req: &RequestHeader,
         dec: &mut Decoder<'_>,
     ) -> Result<Response<TransportStatus>, Response<Error>> {
-        let CreateTcpListener { addr, .. } = dec.decode()?;
+        let CreateTcpListener {
+            addr,
+            tls_certificate,
+            tls_key,
+        } = dec.decode()?;
 
         use {super::TransportType::*, TransportMode::*};
 
         info!("Handling request to create a new tcp listener: {}", addr);
 
-        let options = TcpListenerOptions::new();
+        let mut options = TcpListenerOptions::new();
+        if let (Some(certificate), Some(key)) = (tls_certificate, tls_key) {
+            match TcpListenerTls::new(certificate.as_bytes(), key.as_bytes()) {
+                Ok(tls) => options = options.with_tls(tls),
+                Err(e) => {
+                    return Err(Response::bad_request(
+                        req,
+                        &format!("Invalid TLS certificate or key: {e}"),
+                    ))
+                }
+            }
+        }
         let res = self.node_manager.tcp_transport.listen(&addr, options).await;
 
         let response = match res {
@@ -357,3 +389,198 @@ impl NodeManagerWorker {
         }
     }
 }
+
+#[cfg(feature = "udp")]
+impl NodeManagerWorker {
+    fn udp_listener_status(info: &UdpListenerInfo) -> TransportStatus {
+        TransportStatus::new(ApiTransport {
+            tt: TransportType::Udp,
+            tm: TransportMode::Listen,
+            socket_address: info.listener.socket_address(),
+            worker_address: info.listener.sender_address().to_string(),
+            processor_address: info.listener.processor_address().to_string(),
+            flow_control_id: info.flow_control_id.clone(),
+        })
+    }
+
+    pub(super) async fn get_udp_listeners(&self, req: &RequestHeader) -> Response<TransportList> {
+        let listeners = self.node_manager.registry.udp_listeners.values().await;
+        Response::ok(req).body(TransportList::new(
+            listeners.iter().map(Self::udp_listener_status).collect(),
+        ))
+    }
+
+    pub(super) async fn create_udp_listener(
+        &self,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+        ctx: &Context,
+    ) -> Result<Response<TransportStatus>, Response<Error>> {
+        let CreateUdpListener { addr } = dec.decode()?;
+
+        info!("Handling request to create a new udp listener: {}", addr);
+
+        let options = UdpListenerOptions::new();
+        let flow_control_id = options.flow_control_id();
+
+        // Secure channels can be created, and punchers can meet, via the listener
+        ctx.flow_controls()
+            .add_consumer(DefaultAddress::SECURE_CHANNEL_LISTENER, &flow_control_id);
+        for rendezvous in self.node_manager.registry.rendezvous_services.keys().await {
+            ctx.flow_controls()
+                .add_consumer(rendezvous.clone(), &flow_control_id);
+        }
+
+        match self
+            .node_manager
+            .udp_transport(ctx)
+            .await?
+            .listen_with_options(&addr, options)
+            .await
+        {
+            Ok(listener) => {
+                let info = UdpListenerInfo::new(listener, flow_control_id);
+                let status = Self::udp_listener_status(&info);
+                self.node_manager
+                    .registry
+                    .udp_listeners
+                    .insert(info.listener.processor_address().clone(), info)
+                    .await;
+                Ok(Response::ok(req).body(status))
+            }
+            Err(msg) => {
+                error!("{}", msg.to_string());
+                Err(Response::bad_request(
+                    req,
+                    &format!("Unable to listen on {}: {}", addr, msg),
+                ))
+            }
+        }
+    }
+
+    pub(super) async fn delete_udp_listener(
+        &self,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+        ctx: &Context,
+    ) -> Result<Response<()>, Response<Error>> {
+        let body: DeleteTransport = dec.decode()?;
+
+        info!("Handling request to stop udp listener: {}", body.address);
+
+        let registry = &self.node_manager.registry.udp_listeners;
+        let info = match body.address.parse::<SocketAddr>() {
+            Ok(socket_address) => registry
+                .values()
+                .await
+                .into_iter()
+                .find(|x| x.listener.socket_address() == socket_address),
+            Err(_err) => registry.get(&Address::from(body.address.clone())).await,
+        };
+        let info = match info {
+            Some(info) => info,
+            None => {
+                return Err(Response::bad_request(
+                    req,
+                    &format!("Listener {} was not found in the registry.", body.address),
+                ));
+            }
+        };
+
+        match self
+            .node_manager
+            .udp_transport(ctx)
+            .await?
+            .stop_listener(&info.listener)
+            .await
+        {
+            Ok(_) => {
+                registry.remove(info.listener.processor_address()).await;
+                Ok(Response::ok(req))
+            }
+            Err(err) => Err(Response::bad_request(
+                req,
+                &format!("Unable to stop listener {}: {}", body.address, err),
+            )),
+        }
+    }
+
+    pub(super) async fn get_udp_punchers(&self, req: &RequestHeader) -> Response<UdpPuncherList> {
+        let punchers = self.node_manager.registry.udp_punchers.entries().await;
+        Response::ok(req).body(UdpPuncherList::new(
+            punchers
+                .iter()
+                .map(|(name, info)| {
+                    UdpPuncherStatus::new(
+                        name,
+                        &info.peer_name,
+                        info.rendezvous.to_string(),
+                        info.puncher.address().to_string(),
+                    )
+                })
+                .collect(),
+        ))
+    }
+
+    pub(super) async fn create_udp_puncher(
+        &self,
+        req: &RequestHeader,
+        dec: &mut Decoder<'_>,
+        ctx: &Context,
+    ) -> Result<Response<UdpPuncherStatus>, Response<Error>> {
+        let CreateUdpPuncher {
+            name,
+            peer_name,
+            rendezvous,
+        } = dec.decode()?;
+
+        info!(
+            "Handling request to create a new udp puncher {} for the peer {}",
+            name, peer_name
+        );
+
+        let registry = &self.node_manager.registry.udp_punchers;
+        if registry.contains_key(&name).await {
+            return Err(Response::bad_request(
+                req,
+                &format!("A puncher named {name} already exists"),
+            ));
+        }
+        let rendezvous_route = MultiAddr::try_from(rendezvous.as_str())
+            .ok()
+            .and_then(|ma| multiaddr_to_transport_route(&ma).map(|route| (ma, route)));
+        let (rendezvous, rendezvous_route) = match rendezvous_route {
+            Some(r) => r,
+            None => {
+                return Err(Response::bad_request(
+                    req,
+                    &format!("Invalid rendezvous service address {rendezvous}"),
+                ))
+            }
+        };
+
+        self.node_manager.udp_transport(ctx).await?;
+        let mut puncher_ctx = ctx.async_try_clone().await?;
+        match UdpHolePuncher::create(&mut puncher_ctx, &name, &peer_name, rendezvous_route).await {
+            Ok(puncher) => {
+                let status = UdpPuncherStatus::new(
+                    &name,
+                    &peer_name,
+                    rendezvous.to_string(),
+                    puncher.address().to_string(),
+                );
+                registry
+                    .insert(name, UdpPuncherInfo::new(&peer_name, &rendezvous, puncher))
+                    .await;
+                Ok(Response::ok(req).body(status))
+            }
+            Err(msg) => {
+                error!("{}", msg.to_string());
+                Err(Response::bad_request(
+                    req,
+                    &format!("Unable to create the puncher {}: {}", name, msg),
+                ))
+            }
+        }
+    }
+}
@@ -1,10 +1,12 @@
 use core::str::FromStr;
 use ockam_core::compat::fmt::Formatter;
 use ockam_core::compat::io::{Error, ErrorKind};
+use serde::{Deserialize, Serialize};
 
 /// Represents a range of port, inclusive start, inclusive end.
 /// Always guarantee that end is bigger than start and at least 1 port in the range
-#[derive(PartialEq, Debug, Clone, Copy, Eq, Hash)]
+#[derive(PartialEq, Debug, Clone, Copy, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub struct PortRange {
     start: u16,
     end: u16,
@@ -34,6 +36,16 @@ impl PortRange {
     pub fn end(&self) -> u16 {
         self.end
     }
+
+    /// Return true if the port is part of this range
+    pub fn contains(&self, port: u16) -> bool {
+        self.start <= port && port <= self.end
+    }
+
+    /// Iterate over all the ports of this range
+    pub fn ports(&self) -> impl Iterator<Item = u16> {
+        self.start..=self.end
+    }
 }
 
 impl TryFrom<(u16, u16)> for PortRange {
@@ -76,6 +88,20 @@ impl TryFrom<&str> for PortRange {
     }
 }
 
+impl TryFrom<String> for PortRange {
+    type Error = Error;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        Self::from_str(&text)
+    }
+}
+
+impl From<PortRange> for String {
+    fn from(val: PortRange) -> Self {
+        val.to_string()
+    }
+}
+
 #[test]
 fn port_range_parse() {
     assert_eq!(
@@ -88,3 +114,12 @@ fn port_range_parse() {
     assert!(PortRange::try_from("10-").is_err());
     assert!(PortRange::try_from("10,10,30,40").is_err());
 }
+
+#[test]
+fn port_range_serde() {
+    let range = PortRange::new(41000, 41999).unwrap();
+    let json = serde_json::to_string(&range).unwrap();
+    assert_eq!(json, "\"41000-41999\"");
+    assert_eq!(serde_json::from_str::<PortRange>(&json).unwrap(), range);
+    assert!(serde_json::from_str::<PortRange>("\"20-10\"").is_err());
+}
@@ -0,0 +1,316 @@
+//! Portals provisioned for each peer, from the attributes of its credential.
+//!
+//! The provisioner service is started on a gateway node with a list of targets, each one named
+//! after a value of an attribute. For example the target `postgres=127.0.0.1:5432` is used for
+//! the peers presenting a credential with the attribute `service=postgres`.
+//!
+//! A peer creates a TCP inlet to the provisioner service, over a secure channel. When the first
+//! message of the inlet is received, the provisioner:
+//!
+//!  - creates a TCP outlet to the target corresponding to the attribute of the peer. The outlet
+//!    has a policy only accepting the identifier of the peer, with the same attribute value,
+//!  - forwards that message to the outlet, so that the inlet is then connected to it directly.
+//!
+//! The outlet and its policy are deleted once the peer has no secure channel left with the node.
+//! Every provisioning and teardown is written to the node logs.
+
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use minicbor::{Decoder, Encode};
+use tokio::task::JoinHandle;
+
+use ockam::identity::{
+    Identifier, IdentityAttributesReader, IdentitySecureChannelLocalInfo, SecureChannelRegistry,
+};
+use ockam::{Address, Context, Result, Routed, Worker};
+use ockam_abac::expr::{and, eq, ident, str};
+use ockam_core::api::{Request, ResponseHeader, Status};
+use ockam_core::{route, AllowAll, Any, DenyAll};
+
+use crate::actions;
+use crate::error::ApiError;
+use crate::nodes::models::policy::Policy;
+use crate::nodes::models::portal::CreateOutlet;
+use crate::nodes::NODEMANAGER_ADDR;
+
+/// Attribute used to select the target of a peer, when no other attribute is given
+pub const DEFAULT_PROVISIONING_ATTRIBUTE: &str = "service";
+
+/// Delay between two checks of the secure channels of the provisioned peers
+const TEARDOWN_CHECK_DELAY: Duration = Duration::from_secs(10);
+
+/// Target of the outlets provisioned for the peers having a given attribute value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvisioningTarget {
+    value: String,
+    socket_addr: SocketAddr,
+}
+
+impl ProvisioningTarget {
+    /// Alias, and worker address, of the outlet provisioned for a peer
+    fn outlet_alias(&self, peer: &Identifier) -> String {
+        format!("provisioned_{}_{}", self.value, peer)
+    }
+}
+
+impl FromStr for ProvisioningTarget {
+    type Err = ApiError;
+
+    /// Parse a target with the format `VALUE=IP:PORT`
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+        let (value, socket_addr) = s.split_once('=').ok_or_else(|| {
+            ApiError::message(format!("The target {s} must have the format VALUE=IP:PORT"))
+        })?;
+        // The value is part of the alias of the outlets, which is used in the node API paths
+        if value.is_empty()
+            || !value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(ApiError::message(format!(
+                "The attribute value {value} can only contain letters, digits, '-' and '_'"
+            )));
+        }
+        let socket_addr = socket_addr
+            .parse()
+            .map_err(|_| ApiError::message(format!("Invalid socket address: {socket_addr}")))?;
+        Ok(Self {
+            value: value.to_string(),
+            socket_addr,
+        })
+    }
+}
+
+impl Display for ProvisioningTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}={}", self.value, self.socket_addr)
+    }
+}
+
+/// Aliases of the outlets provisioned for each peer
+#[derive(Clone, Default)]
+struct ProvisionedOutlets {
+    outlets: Arc<Mutex<BTreeMap<Identifier, String>>>,
+}
+
+pub struct ProvisionerService {
+    attribute: String,
+    targets: Vec<ProvisioningTarget>,
+    attributes_reader: Arc<dyn IdentityAttributesReader>,
+    secure_channels: SecureChannelRegistry,
+    provisioned: ProvisionedOutlets,
+    teardown: Option<JoinHandle<()>>,
+}
+
+impl ProvisionerService {
+    pub fn new(
+        attribute: String,
+        targets: Vec<ProvisioningTarget>,
+        attributes_reader: Arc<dyn IdentityAttributesReader>,
+        secure_channels: SecureChannelRegistry,
+    ) -> Self {
+        Self {
+            attribute,
+            targets,
+            attributes_reader,
+            secure_channels,
+            provisioned: ProvisionedOutlets::default(),
+            teardown: None,
+        }
+    }
+
+    /// Return the target for a value of the provisioning attribute
+    fn target(&self, value: &str) -> Option<&ProvisioningTarget> {
+        self.targets.iter().find(|t| t.value == value)
+    }
+
+    /// Return the alias of the outlet provisioned for a peer, after creating it if necessary.
+    /// Return `None` if the attributes of the peer don't correspond to any target
+    async fn provision(&self, ctx: &Context, peer: &Identifier) -> Result<Option<String>> {
+        if let Some(alias) = self.provisioned.outlets.lock().unwrap().get(peer) {
+            return Ok(Some(alias.clone()));
+        }
+
+        let value = self
+            .attributes_reader
+            .get_attributes(peer)
+            .await?
+            .and_then(|entry| entry.attrs().get(self.attribute.as_bytes()).cloned())
+            .and_then(|value| String::from_utf8(value).ok());
+        let target = match value.as_deref().and_then(|value| self.target(value)) {
+            Some(target) => target,
+            None => {
+                warn!(%peer, attribute = %self.attribute, ?value, "no target can be provisioned for this peer");
+                return Ok(None);
+            }
+        };
+
+        let alias = target.outlet_alias(peer);
+        let policy = and([
+            eq([ident("subject.identifier"), str(peer.to_string())]),
+            eq([
+                ident(format!("subject.{}", self.attribute)),
+                str(target.value.clone()),
+            ]),
+        ]);
+        send_request(
+            ctx,
+            Request::post(format!(
+                "/policy/{alias}/{}",
+                actions::HANDLE_MESSAGE.as_str()
+            ))
+            .body(Policy::new(policy)),
+        )
+        .await?;
+        send_request(
+            ctx,
+            Request::post("/node/outlet").body(CreateOutlet::new(
+                target.socket_addr,
+                Address::from_string(&alias),
+                alias.clone(),
+                true,
+            )),
+        )
+        .await?;
+        info!(%peer, %alias, target = %target, "outlet provisioned");
+
+        self.provisioned
+            .outlets
+            .lock()
+            .unwrap()
+            .insert(peer.clone(), alias.clone());
+        Ok(Some(alias))
+    }
+}
+
+#[ockam::worker]
+impl Worker for ProvisionerService {
+    type Context = Context;
+    type Message = Any;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        let ctx = ctx
+            .new_detached(
+                Address::random_tagged("ProvisionerService.teardown"),
+                DenyAll,
+                AllowAll,
+            )
+            .await?;
+        self.teardown = Some(tokio::spawn(teardown(
+            ctx,
+            self.provisioned.clone(),
+            self.secure_channels.clone(),
+        )));
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, _ctx: &mut Context) -> Result<()> {
+        if let Some(teardown) = self.teardown.take() {
+            teardown.abort();
+        }
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let peer = match IdentitySecureChannelLocalInfo::find_info(msg.local_message()) {
+            Ok(info) => info.their_identity_id(),
+            Err(_) => {
+                warn!(src = %msg.src_addr(), "provisioning rejected: not received over a secure channel");
+                return Ok(());
+            }
+        };
+        let alias = match self.provision(ctx, &peer).await? {
+            Some(alias) => alias,
+            None => return Ok(()),
+        };
+
+        // The first message of the inlet is sent to the outlet, which then replies directly
+        let mut local_message = msg.into_local_message();
+        local_message
+            .transport_mut()
+            .onward_route
+            .modify()
+            .replace(Address::from_string(alias));
+        ctx.forward(local_message).await
+    }
+}
+
+/// Delete the outlets of the peers which don't have any secure channel left with the node.
+///
+/// This function never returns. Failed deletions are retried after the check delay
+async fn teardown(
+    ctx: Context,
+    provisioned: ProvisionedOutlets,
+    secure_channels: SecureChannelRegistry,
+) {
+    loop {
+        tokio::time::sleep(TEARDOWN_CHECK_DELAY).await;
+        let channels = secure_channels.get_channel_list();
+        let disconnected: Vec<(Identifier, String)> = provisioned
+            .outlets
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(peer, _)| channels.iter().all(|c| c.their_id() != *peer))
+            .map(|(peer, alias)| (peer.clone(), alias.clone()))
+            .collect();
+
+        for (peer, alias) in disconnected {
+            let deleted = async {
+                send_request(&ctx, Request::delete(format!("/node/outlet/{alias}"))).await?;
+                send_request(
+                    &ctx,
+                    Request::delete(format!(
+                        "/policy/{alias}/{}",
+                        actions::HANDLE_MESSAGE.as_str()
+                    )),
+                )
+                .await
+            };
+            match deleted.await {
+                Ok(()) => {
+                    info!(%peer, %alias, "provisioned outlet deleted");
+                    provisioned.outlets.lock().unwrap().remove(&peer);
+                }
+                Err(e) => warn!(%peer, %alias, %e, "the provisioned outlet could not be deleted"),
+            }
+        }
+    }
+}
+
+/// Send a request to the node manager and check that it succeeded
+async fn send_request<T: Encode<()>>(ctx: &Context, request: Request<T>) -> Result<()> {
+    let response: Vec<u8> = ctx
+        .send_and_receive(route![NODEMANAGER_ADDR], request.to_vec()?)
+        .await?;
+    let header: ResponseHeader = Decoder::new(&response).decode()?;
+    match header.status() {
+        Some(Status::Ok) => Ok(()),
+        status => Err(ApiError::core(format!(
+            "the node manager request failed: {status:?}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_targets() {
+        let target: ProvisioningTarget = "postgres=127.0.0.1:5432".parse().unwrap();
+        assert_eq!(target.value, "postgres");
+        assert_eq!(target.socket_addr, "127.0.0.1:5432".parse().unwrap());
+        assert_eq!(target.to_string(), "postgres=127.0.0.1:5432");
+
+        assert!("postgres".parse::<ProvisioningTarget>().is_err());
+        assert!("=127.0.0.1:5432".parse::<ProvisioningTarget>().is_err());
+        assert!("pg/1=127.0.0.1:5432".parse::<ProvisioningTarget>().is_err());
+        assert!("postgres=localhost".parse::<ProvisioningTarget>().is_err());
+    }
+}
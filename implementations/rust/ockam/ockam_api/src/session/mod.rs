@@ -2,6 +2,7 @@ use minicbor::{Decode, Encode};
 use tokio::task::JoinHandle;
 use tracing as log;
 
+use ockam::identity::TimestampInSeconds;
 use ockam::{LocalMessage, Route, TransportMessage, Worker};
 use ockam_core::compat::sync::{Arc, Mutex};
 use ockam_core::{
@@ -13,7 +14,7 @@ use ockam_node::tokio::time::{sleep, timeout, Duration};
 use ockam_node::Context;
 use ockam_node::{tokio, WorkerBuilder};
 
-use crate::session::sessions::{Ping, Session, Status};
+use crate::session::sessions::{Ping, Session, SessionEvent, Status};
 use crate::DefaultAddress;
 
 pub(crate) mod sessions;
@@ -127,6 +128,7 @@ impl Medic {
                                 log::warn!(%key, "session unresponsive");
                                 let f = session.replacement(session.ping_route().clone());
                                 session.set_status(Status::Degraded);
+                                session.add_event(Status::Degraded, "unresponsive, replacing");
                                 log::info!(%key, "replacing session");
                                 let retry_delay = self.retry_delay;
                                 self.replacements.spawn(async move {
@@ -163,6 +165,7 @@ impl Medic {
                         let mut sessions = self.sessions.lock().unwrap();
                         if let Some(s) = sessions.iter_mut().find(|s| s.key() == k) {
                            s.set_status(Status::Down);
+                           s.add_event(Status::Down, format!("replacement failed: {e}"));
                         }
                     }
                     Some(Ok((k, Ok(ping_route)))) => {
@@ -170,6 +173,7 @@ impl Medic {
                         if let Some(s) = sessions.iter_mut().find(|s| s.key() == k) {
                             log::info!(key = %k, ping_route = %ping_route, "replacement is up");
                             s.set_status(Status::Up);
+                            s.add_event(Status::Up, "replaced");
                             s.set_ping_address(ping_route);
                             s.clear_pings();
                         }
@@ -279,10 +283,30 @@ impl MedicHandle {
         sessions.retain(|s| s.key() != key)
     }
 
+    /// Number of sessions monitored by the medic
+    pub fn sessions_count(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
     pub fn status_of(&self, key: &str) -> Option<Status> {
         let sessions = self.sessions.lock().unwrap();
         sessions.iter().find(|s| s.key() == key).map(|s| s.status())
     }
+
+    /// Return the most recent status changes of a session, or None if it is not monitored
+    pub fn events_of(&self, key: &str) -> Option<Vec<SessionEvent>> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions.iter().find(|s| s.key() == key).map(|s| s.events())
+    }
+
+    /// Return the creation time of a session, or None if it is not monitored
+    pub fn created_at_of(&self, key: &str) -> Option<TimestampInSeconds> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions
+            .iter()
+            .find(|s| s.key() == key)
+            .map(|s| s.created_at())
+    }
 }
 
 #[cfg(test)]
@@ -374,6 +398,12 @@ mod tests {
                 let session = guard.iter().next().unwrap();
                 if session.status() == Status::Up {
                     assert_eq!(session.ping_route(), &route!["hop"]);
+                    // The replacement is recorded in the events of the session
+                    let statuses: Vec<Status> =
+                        session.events().iter().map(|e| e.status()).collect();
+                    assert_eq!(statuses, vec![Status::Up, Status::Degraded, Status::Up]);
+                    // The session keeps its creation time when it is replaced
+                    assert!(session.created_at() <= session.events()[0].timestamp());
                     break;
                 }
             }
@@ -1,11 +1,14 @@
 use core::fmt;
 use core::future::Future;
 use core::pin::Pin;
+use std::collections::VecDeque;
 use std::fmt::Formatter;
 use std::time::Duration;
 
 use minicbor::{Decode, Encode};
 
+use ockam::identity::utils::now;
+use ockam::identity::TimestampInSeconds;
 use ockam_core::compat::rand;
 use ockam_core::{Error, Route};
 
@@ -15,6 +18,9 @@ use ockam_core::{Error, Route};
 pub const MAX_RECOVERY_TIME: Duration = Duration::from_secs(30);
 pub const MAX_CONNECT_TIME: Duration = Duration::from_secs(15);
 
+/// Maximum number of events kept for each session
+const MAX_EVENTS: usize = 20;
+
 pub type Replacement = Pin<Box<dyn Future<Output = Result<Route, Error>> + Send>>;
 pub type Replacer = Box<dyn FnMut(Route) -> Replacement + Send>;
 
@@ -24,6 +30,8 @@ pub struct Session {
     status: Status,
     replace: Replacer,
     pings: Vec<Ping>,
+    events: VecDeque<SessionEvent>,
+    created_at: TimestampInSeconds,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -50,6 +58,7 @@ impl fmt::Debug for Session {
             .field("ping_route", &self.ping_route)
             .field("status", &self.status)
             .field("pings", &self.pings)
+            .field("events", &self.events)
             .finish()
     }
 }
@@ -63,13 +72,17 @@ impl Session {
     /// * `key` - The key to identify the session, usually adding the kind of the session
     ///           with the key used within the service registry is the way to gos
     pub fn new(ping_route: Route, key: String) -> Self {
-        Self {
+        let mut session = Self {
             key,
             ping_route,
             status: Status::Up,
             replace: Box::new(move |r| Box::pin(async move { Ok(r) })),
             pings: Vec::new(),
-        }
+            events: VecDeque::new(),
+            created_at: now().unwrap_or(TimestampInSeconds(0)),
+        };
+        session.add_event(Status::Up, "created");
+        session
     }
 
     pub fn key(&self) -> &str {
@@ -111,6 +124,50 @@ impl Session {
     pub fn clear_pings(&mut self) {
         self.pings.clear()
     }
+
+    /// Time at which the session was created. It is kept when the session is replaced
+    pub fn created_at(&self) -> TimestampInSeconds {
+        self.created_at
+    }
+
+    /// Most recent changes of the status of the session, from the oldest to the newest
+    pub fn events(&self) -> Vec<SessionEvent> {
+        self.events.iter().cloned().collect()
+    }
+
+    /// Record a change of the status of the session, dropping the oldest events if necessary
+    pub fn add_event(&mut self, status: Status, message: impl Into<String>) {
+        if self.events.len() == MAX_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(SessionEvent {
+            timestamp: now().unwrap_or(TimestampInSeconds(0)),
+            status,
+            message: message.into(),
+        });
+    }
+}
+
+/// Change of the status of a session, as observed by the medic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionEvent {
+    timestamp: TimestampInSeconds,
+    status: Status,
+    message: String,
+}
+
+impl SessionEvent {
+    pub fn timestamp(&self) -> TimestampInSeconds {
+        self.timestamp
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
 }
 
 #[derive(Debug, Default, Copy, Clone, Encode, Decode, PartialEq, Eq)]
@@ -6,10 +6,19 @@ use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Address, Error, Result, Route, TransportType, LOCAL};
 use ockam_multiaddr::proto::{
-    DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Worker,
+    Ble, DnsAddr, Ip4, Ip6, Node, Project, Quic, Secure, Service, Space, Tcp, Tls, Udp, Worker, Ws,
+    Wss,
 };
-use ockam_multiaddr::{Code, MultiAddr, Protocol};
-use ockam_transport_tcp::{TcpConnection, TcpConnectionOptions, TCP};
+use ockam_multiaddr::{Code, MultiAddr, ProtoValue, Protocol};
+#[cfg(feature = "ble")]
+use ockam_transport_ble::BLE;
+#[cfg(feature = "quic")]
+use ockam_transport_quic::QUIC;
+use ockam_transport_tcp::{TcpConnection, TcpConnectionOptions, TCP, TLS_PREFIX};
+#[cfg(feature = "udp")]
+use ockam_transport_udp::UDP;
+#[cfg(feature = "websocket")]
+use ockam_transport_websocket::WS;
 
 use crate::error::ApiError;
 
@@ -92,6 +101,15 @@ pub async fn multiaddr_to_route(
     let mut tcp_connection = None;
 
     while let Some(p) = it.next() {
+        if let Some(address) = router_address(&p, it.peek()) {
+            rb = rb.append(address);
+            let _ = it.next();
+            continue;
+        }
+        if let Some(address) = ble_address(&p) {
+            rb = rb.append(address);
+            continue;
+        }
         match p.code() {
             Ip4::CODE => {
                 if number_of_tcp_hops >= 1 {
@@ -99,13 +117,13 @@ pub async fn multiaddr_to_route(
                 }
 
                 let ip4 = p.cast::<Ip4>()?;
-                let port = it.next()?.cast::<Tcp>()?;
-                let socket_addr = SocketAddrV4::new(*ip4, *port);
+                let (prefix, port) = tcp_port(&it.next()?)?;
+                let socket_addr = format!("{prefix}{}", SocketAddrV4::new(*ip4, port));
 
                 let options = TcpConnectionOptions::new();
                 flow_control_id = Some(options.flow_control_id().clone());
 
-                let connection = match tcp.connect(socket_addr.to_string(), options).await {
+                let connection = match tcp.connect(&socket_addr, options).await {
                     Ok(c) => c,
                     Err(error) => {
                         error!(%error, %socket_addr, "Couldn't connect to Ip4 address");
@@ -124,13 +142,13 @@ pub async fn multiaddr_to_route(
                 }
 
                 let ip6 = p.cast::<Ip6>()?;
-                let port = it.next()?.cast::<Tcp>()?;
-                let socket_addr = SocketAddrV6::new(*ip6, *port, 0, 0);
+                let (prefix, port) = tcp_port(&it.next()?)?;
+                let socket_addr = format!("{prefix}{}", SocketAddrV6::new(*ip6, port, 0, 0));
 
                 let options = TcpConnectionOptions::new();
                 flow_control_id = Some(options.flow_control_id().clone());
 
-                let connection = match tcp.connect(socket_addr.to_string(), options).await {
+                let connection = match tcp.connect(&socket_addr, options).await {
                     Ok(c) => c,
                     Err(error) => {
                         error!(%error, %socket_addr, "Couldn't connect to Ip6 address");
@@ -149,31 +167,27 @@ pub async fn multiaddr_to_route(
                 }
 
                 let host = p.cast::<DnsAddr>()?;
-                if let Some(p) = it.peek() {
-                    if p.code() == Tcp::CODE {
-                        let port = p.cast::<Tcp>()?;
-
-                        let options = TcpConnectionOptions::new();
-                        flow_control_id = Some(options.flow_control_id().clone());
-                        let peer = format!("{}:{}", &*host, *port);
+                if let Some((prefix, port)) = it.peek().and_then(tcp_port) {
+                    let options = TcpConnectionOptions::new();
+                    flow_control_id = Some(options.flow_control_id().clone());
+                    let peer = format!("{prefix}{}:{port}", &*host);
 
-                        let connection = match tcp.connect(&peer, options).await {
-                            Ok(c) => c,
-                            Err(error) => {
-                                error!(%error, %peer, "Couldn't connect to DNS address");
-                                return None;
-                            }
-                        };
+                    let connection = match tcp.connect(&peer, options).await {
+                        Ok(c) => c,
+                        Err(error) => {
+                            error!(%error, %peer, "Couldn't connect to DNS address");
+                            return None;
+                        }
+                    };
 
-                        number_of_tcp_hops += 1;
-                        rb = rb.append(connection.sender_address().clone());
+                    number_of_tcp_hops += 1;
+                    rb = rb.append(connection.sender_address().clone());
 
-                        tcp_connection = Some(connection);
+                    tcp_connection = Some(connection);
 
-                        let _ = it.next();
+                    let _ = it.next();
 
-                        continue;
-                    }
+                    continue;
                 }
             }
             Worker::CODE => {
@@ -211,29 +225,38 @@ pub fn multiaddr_to_transport_route(ma: &MultiAddr) -> Option<Route> {
     let mut it = ma.iter().peekable();
 
     while let Some(p) = it.next() {
+        if let Some(address) = router_address(&p, it.peek()) {
+            route = route.append(address);
+            let _ = it.next();
+            continue;
+        }
+        if let Some(address) = ble_address(&p) {
+            route = route.append(address);
+            continue;
+        }
         match p.code() {
             Ip4::CODE => {
                 let ip4 = p.cast::<Ip4>()?;
-                let port = it.next()?.cast::<Tcp>()?;
-                let socket_addr = SocketAddrV4::new(*ip4, *port);
-                route = route.append(Address::new(TCP, socket_addr.to_string()))
+                let (prefix, port) = tcp_port(&it.next()?)?;
+                let socket_addr = SocketAddrV4::new(*ip4, port);
+                route = route.append(Address::new(TCP, format!("{prefix}{socket_addr}")))
             }
             Ip6::CODE => {
                 let ip6 = p.cast::<Ip6>()?;
-                let port = it.next()?.cast::<Tcp>()?;
-                let socket_addr = SocketAddrV6::new(*ip6, *port, 0, 0);
-                route = route.append(Address::new(TransportType::new(1), socket_addr.to_string()))
+                let (prefix, port) = tcp_port(&it.next()?)?;
+                let socket_addr = SocketAddrV6::new(*ip6, port, 0, 0);
+                route = route.append(Address::new(
+                    TransportType::new(1),
+                    format!("{prefix}{socket_addr}"),
+                ))
             }
             DnsAddr::CODE => {
                 let host = p.cast::<DnsAddr>()?;
-                if let Some(p) = it.peek() {
-                    if p.code() == Tcp::CODE {
-                        let port = p.cast::<Tcp>()?;
-                        let addr = format!("{}:{}", &*host, *port);
-                        route = route.append(Address::new(TransportType::new(1), addr));
-                        let _ = it.next();
-                        continue;
-                    }
+                if let Some((prefix, port)) = it.peek().and_then(tcp_port) {
+                    let addr = format!("{prefix}{}:{port}", &*host);
+                    route = route.append(Address::new(TransportType::new(1), addr));
+                    let _ = it.next();
+                    continue;
                 }
             }
             Worker::CODE => {
@@ -257,6 +280,66 @@ pub fn multiaddr_to_transport_route(ma: &MultiAddr) -> Option<Route> {
     Some(route.into())
 }
 
+/// Return the address of a host followed by a UDP, QUIC or WebSocket port, for the transport
+/// routing the messages to that host. For example:
+///  - /ip4/127.0.0.1/udp/4000 is transformed to the Address (UDP, "127.0.0.1:4000"),
+///  - /ip4/127.0.0.1/quic/4000 to the Address (QUIC, "127.0.0.1:4000"),
+///  - /ip4/127.0.0.1/ws/4000 to the Address (WS, "127.0.0.1:4000"),
+///  - /dnsaddr/relay.example.com/wss/443 to the Address (WS, "wss://relay.example.com:443").
+///
+/// The UDP and QUIC transports only support IPv4. The transports left out of this build are
+/// not routed.
+#[cfg_attr(
+    not(any(feature = "udp", feature = "quic", feature = "websocket")),
+    allow(unreachable_code, unused_variables)
+)]
+pub(crate) fn router_address(host: &ProtoValue, port: Option<&ProtoValue>) -> Option<Address> {
+    let (transport_type, scheme, port): (TransportType, &str, u16) = match port? {
+        #[cfg(feature = "udp")]
+        p if p.code() == Udp::CODE => (UDP, "", *p.cast::<Udp>()?),
+        #[cfg(feature = "quic")]
+        p if p.code() == Quic::CODE => (QUIC, "", *p.cast::<Quic>()?),
+        #[cfg(feature = "websocket")]
+        p if p.code() == Ws::CODE => (WS, "", *p.cast::<Ws>()?),
+        #[cfg(feature = "websocket")]
+        p if p.code() == Wss::CODE => (WS, "wss://", *p.cast::<Wss>()?),
+        _ => return None,
+    };
+    let host = match host.code() {
+        Ip4::CODE => host.cast::<Ip4>()?.to_string(),
+        DnsAddr::CODE => host.cast::<DnsAddr>()?.to_string(),
+        _ => return None,
+    };
+    Some(Address::new(
+        transport_type,
+        format!("{scheme}{host}:{port}"),
+    ))
+}
+
+/// Return the address of a BLE peripheral, routed by the BLE transport router.
+/// For example /ble/ockam_ble_1 is transformed to the Address (BLE, "ockam_ble_1")
+#[cfg(feature = "ble")]
+pub(crate) fn ble_address(p: &ProtoValue) -> Option<Address> {
+    let name = p.cast::<Ble>()?;
+    Some(Address::new(BLE, &*name))
+}
+
+#[cfg(not(feature = "ble"))]
+pub(crate) fn ble_address(_p: &ProtoValue) -> Option<Address> {
+    None
+}
+
+/// Return the port of a TCP connection following a host, with the prefix of the peer address:
+///  - /tcp/4000 is transformed to ("", 4000),
+///  - /tls/443 to ("tls://", 443), for a TCP connection wrapped in TLS.
+fn tcp_port(p: &ProtoValue) -> Option<(&'static str, u16)> {
+    match p.code() {
+        Tcp::CODE => Some(("", *p.cast::<Tcp>()?)),
+        Tls::CODE => Some((TLS_PREFIX, *p.cast::<Tls>()?)),
+        _ => None,
+    }
+}
+
 /// Try to convert a multiaddr to an Ockam Address
 pub fn multiaddr_to_addr(ma: &MultiAddr) -> Option<Address> {
     let mut it = ma.iter().peekable();
@@ -364,6 +447,12 @@ pub fn local_worker(code: &Code) -> Result<bool> {
         | Ip4::CODE
         | Ip6::CODE
         | Tcp::CODE
+        | Tls::CODE
+        | Udp::CODE
+        | Quic::CODE
+        | Ws::CODE
+        | Wss::CODE
+        | Ble::CODE
         | Secure::CODE => Ok(false),
         Worker::CODE | Service::CODE => Ok(true),
 
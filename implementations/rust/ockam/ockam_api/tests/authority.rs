@@ -348,6 +348,11 @@ async fn default_configuration() -> Result<Configuration> {
         no_direct_authentication: true,
         no_token_enrollment: true,
         okta: None,
+        credential_via: vec![],
+        credential_deny_direct: false,
+        co_signers: vec![],
+        threshold: None,
+        co_sign_for: vec![],
     };
 
     // Hack to create Authority Identity using the same vault and storage
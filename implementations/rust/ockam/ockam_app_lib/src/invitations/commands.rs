@@ -13,6 +13,8 @@ use ockam_api::cli_state::{CliState, StateDirTrait};
 use ockam_api::cloud::project::Project;
 use ockam_api::cloud::share::InvitationListKind;
 use ockam_api::cloud::share::{CreateServiceInvitation, InvitationWithAccess, Invitations};
+use ockam_api::nodes::models::portal::DataFlow;
+use ockam_api::nodes::models::quota::SessionQuota;
 use ockam_api::nodes::service::portals::Inlets;
 use ockam_api::nodes::BackgroundNode;
 use ockam_multiaddr::MultiAddr;
@@ -460,9 +462,13 @@ impl AppState {
                 &self.context(),
                 &from.to_string(),
                 &MultiAddr::from_str(service_route).into_diagnostic()?,
+                &[],
                 &Some(service_name.to_string()),
                 &None,
                 Duration::from_secs(5),
+                DataFlow::default(),
+                SessionQuota::default(),
+                None,
             )
             .await?;
         Ok(from)
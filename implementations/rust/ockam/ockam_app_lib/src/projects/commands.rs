@@ -45,6 +45,7 @@ impl AppState {
                 HashMap::new(),
                 Some(Duration::from_secs(60 * 60 * 24 * 14)),
                 None,
+                None,
             )
             .await?;
         let project_lookup = ProjectLookup::from_project(&project).await.ok();
@@ -2,6 +2,8 @@ use crate::state::AppState;
 use crate::Error;
 use miette::{IntoDiagnostic, WrapErr};
 use ockam_api::address::extract_address_value;
+use ockam_api::nodes::models::portal::DataFlow;
+use ockam_api::nodes::models::quota::SessionQuota;
 use ockam_transport_tcp::resolve_peer;
 use tracing::{debug, info};
 
@@ -34,6 +36,9 @@ impl AppState {
                 worker_addr.clone().into(),
                 Some(worker_addr),
                 true,
+                DataFlow::default(),
+                SessionQuota::default(),
+                None,
             )
             .await
         {
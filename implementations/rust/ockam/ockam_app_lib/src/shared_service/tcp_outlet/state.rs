@@ -1,5 +1,6 @@
 use crate::state::{AppState, ModelState};
-use ockam_api::nodes::models::portal::OutletStatus;
+use ockam_api::nodes::models::portal::{DataFlow, OutletStatus};
+use ockam_api::nodes::models::quota::SessionQuota;
 use tracing::{debug, error};
 
 impl ModelState {
@@ -34,6 +35,9 @@ impl AppState {
                     tcp_outlet.worker_addr.clone(),
                     Some(tcp_outlet.alias.clone()),
                     true,
+                    DataFlow::default(),
+                    SessionQuota::default(),
+                    None,
                 )
                 .await
                 .map_err(|e| {
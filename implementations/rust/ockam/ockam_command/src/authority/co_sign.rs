@@ -0,0 +1,122 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use miette::{miette, IntoDiagnostic, WrapErr};
+
+use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+
+use crate::identity::{get_identity_name, initialize_identity_if_default};
+use crate::output::{CredentialAndPurposeKeyDisplay, EncodeFormat};
+use crate::util::node_rpc;
+use crate::util::parsers::identity_identifier_parser;
+use crate::vault::default_vault_name;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/co_sign/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/co_sign/after_long_help.txt");
+
+/// Co-sign a credential issued by another authority
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct CoSignCommand {
+    /// Name of the Identity co-signing the credential
+    #[arg(long = "as", value_name = "IDENTITY_NAME")]
+    as_identity: Option<String>,
+
+    /// Identifier of the authority which issued the credential
+    #[arg(long, value_name = "IDENTIFIER", value_parser = identity_identifier_parser)]
+    issuer: Identifier,
+
+    /// Hex encoded credential
+    #[arg(group = "credential_value", value_name = "CREDENTIAL_STRING", long)]
+    credential: Option<String>,
+
+    /// Path of a file containing a hex encoded credential
+    #[arg(group = "credential_value", value_name = "CREDENTIAL_FILE", long)]
+    credential_path: Option<PathBuf>,
+
+    /// Name of the Vault containing the key of the co-signing Identity
+    #[arg(long, value_name = "VAULT_NAME")]
+    vault: Option<String>,
+
+    /// Encoding Format
+    #[arg(long = "encoding", value_enum, default_value = "hex")]
+    encode_format: EncodeFormat,
+}
+
+impl CoSignCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_identity_if_default(&opts, &self.as_identity);
+        node_rpc(run_impl, (opts, self));
+    }
+
+    fn credential(&self) -> miette::Result<CredentialAndPurposeKey> {
+        let encoded = match (&self.credential, &self.credential_path) {
+            (_, Some(path)) => {
+                std::fs::read_to_string(path)
+                    .into_diagnostic()
+                    .wrap_err(format!(
+                        "Failed to read the credential file {}",
+                        path.display()
+                    ))?
+            }
+            (Some(credential), _) => credential.clone(),
+            _ => {
+                return Err(miette!(
+                    "A credential or a credential path must be provided"
+                ))
+            }
+        };
+        let bytes = hex::decode(encoded.trim())
+            .into_diagnostic()
+            .wrap_err("The credential is not hex encoded")?;
+        minicbor::decode(&bytes)
+            .into_diagnostic()
+            .wrap_err("Invalid credential")
+    }
+}
+
+async fn run_impl(
+    _ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, CoSignCommand),
+) -> miette::Result<()> {
+    let identity_name = get_identity_name(&opts.state, &cmd.as_identity);
+    let co_signer = opts.state.identities.get(&identity_name)?.identifier();
+
+    let vault_name = cmd
+        .vault
+        .clone()
+        .unwrap_or_else(|| default_vault_name(&opts.state));
+    let vault = opts.state.vaults.get(&vault_name)?.get().await?;
+    let identities = opts.state.get_identities(vault).await?;
+    let credentials = identities.credentials();
+
+    // only a valid credential, issued by the expected authority, is co-signed
+    let mut credential = cmd.credential()?;
+    credentials
+        .credentials_verification()
+        .verify_issued_credential(None, &[cmd.issuer.clone()], &credential)
+        .await
+        .into_diagnostic()
+        .wrap_err("The credential can't be verified")?;
+
+    let co_signature = credentials
+        .credentials_creation()
+        .co_sign_credential(&co_signer, &credential)
+        .await
+        .into_diagnostic()?;
+    credential
+        .co_signatures
+        .get_or_insert_with(Vec::new)
+        .push(co_signature);
+
+    cmd.encode_format
+        .println_value(&CredentialAndPurposeKeyDisplay(credential))?;
+    Ok(())
+}
@@ -5,7 +5,7 @@ use crate::{docs, identity, CommandGlobalOpts, Result};
 use clap::{ArgGroup, Args};
 use miette::Context as _;
 use miette::{miette, IntoDiagnostic};
-use ockam::identity::{AttributesEntry, Identifier};
+use ockam::identity::{AttributesEntry, CredentialsCoSignerInfo, Identifier};
 use ockam::Context;
 use ockam_api::authority_node;
 use ockam_api::authority_node::{OktaConfiguration, TrustedIdentity};
@@ -16,6 +16,8 @@ use ockam_api::nodes::models::transport::{CreateTransportJson, TransportMode, Tr
 use ockam_api::DefaultAddress;
 use ockam_core::compat::collections::HashMap;
 use ockam_core::compat::fmt;
+use ockam_core::{route, Address};
+use ockam_transport_tcp::TCP;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
@@ -88,6 +90,31 @@ pub struct CreateCommand {
     #[arg(long, value_name = "ATTRIBUTE_NAMES", default_value = None)]
     attributes: Option<Vec<String>>,
 
+    /// Identifier of a node (for example a project relay) via which the issued credentials must be
+    /// presented. This option can be repeated
+    #[arg(long, value_name = "IDENTIFIER")]
+    credential_via: Vec<Identifier>,
+
+    /// Set this option if the issued credentials must not be presented over a direct
+    /// transport connection
+    #[arg(long, default_value_t = false)]
+    credential_deny_direct: bool,
+
+    /// Authority asked to co-sign the issued credentials, with the format
+    /// <IDENTIFIER>@<HOST>:<PORT>. This option can be repeated
+    #[arg(long, value_name = "CO_SIGNER", value_parser = parse_co_signer, requires = "threshold")]
+    co_signer: Vec<CredentialsCoSignerInfo>,
+
+    /// Number of distinct authorities, this one included, which must sign each issued credential.
+    /// The nodes verifying the credentials must be configured with the same threshold
+    #[arg(long, value_name = "NUMBER", requires = "co_signer")]
+    threshold: Option<usize>,
+
+    /// Identifier of an authority whose credentials are co-signed by this authority, when
+    /// their attributes are the attributes known by this authority. This option can be repeated
+    #[arg(long, value_name = "IDENTIFIER")]
+    co_sign_for: Vec<Identifier>,
+
     /// Run the node in foreground.
     #[arg(long, short, value_name = "BOOL", default_value_t = false)]
     foreground: bool,
@@ -176,6 +203,38 @@ async fn spawn_background_node(
         });
     }
 
+    cmd.credential_via.iter().for_each(|identifier| {
+        args.push("--credential-via".to_string());
+        args.push(identifier.to_string());
+    });
+
+    if cmd.credential_deny_direct {
+        args.push("--credential-deny-direct".to_string());
+    }
+
+    cmd.co_signer.iter().for_each(|co_signer| {
+        args.push("--co-signer".to_string());
+        args.push(format!(
+            "{}@{}",
+            co_signer.identifier,
+            co_signer
+                .route
+                .recipient()
+                .map(|a| a.address().to_string())
+                .unwrap_or_default()
+        ));
+    });
+
+    if let Some(threshold) = cmd.threshold {
+        args.push("--threshold".to_string());
+        args.push(threshold.to_string());
+    }
+
+    cmd.co_sign_for.iter().for_each(|identifier| {
+        args.push("--co-sign-for".to_string());
+        args.push(identifier.to_string());
+    });
+
     if let Some(vault) = &cmd.vault {
         args.push("--vault".to_string());
         args.push(vault.clone());
@@ -333,7 +392,7 @@ async fn start_authority_node(
     let configuration = authority_node::Configuration {
         identifier,
         storage_path: opts.state.identities.identities_repository_path()?,
-        vault_path: opts.state.vaults.default()?.vault_file_path().clone(),
+        vault_path: authority_vault_path(&opts)?,
         project_identifier: cmd.project_identifier,
         tcp_listener_address: cmd.tcp_listener_address,
         secure_channel_listener_name: None,
@@ -342,6 +401,11 @@ async fn start_authority_node(
         no_direct_authentication: cmd.no_direct_authentication,
         no_token_enrollment: cmd.no_token_enrollment,
         okta: okta_configuration,
+        credential_via: cmd.credential_via,
+        credential_deny_direct: cmd.credential_deny_direct,
+        co_signers: cmd.co_signer,
+        threshold: cmd.threshold,
+        co_sign_for: cmd.co_sign_for,
     };
     authority_node::start_node(&ctx, &configuration)
         .await
@@ -350,6 +414,38 @@ async fn start_authority_node(
     Ok(())
 }
 
+/// Return the path of the storage file of the default vault, used by the authority node.
+/// The authority node reads that file directly, so the vault can't be locked
+fn authority_vault_path(opts: &CommandGlobalOpts) -> miette::Result<PathBuf> {
+    let vault = opts.state.vaults.default()?;
+    if vault.is_locked() {
+        return Err(miette!(
+            "The vault {} is locked and can't be used by an authority node",
+            vault.name()
+        ));
+    }
+    Ok(vault.vault_file_path().clone())
+}
+
+/// Return a co-signer passed as <IDENTIFIER>@<HOST>:<PORT> on the command line
+fn parse_co_signer(value: &str) -> Result<CredentialsCoSignerInfo> {
+    let (identifier, address) = value.split_once('@').ok_or_else(|| {
+        crate::Error::new(
+            exitcode::USAGE,
+            miette!("Invalid co-signer {value}, the format is <IDENTIFIER>@<HOST>:<PORT>"),
+        )
+    })?;
+    Ok(CredentialsCoSignerInfo {
+        identifier: Identifier::try_from(identifier).map_err(|e| {
+            crate::Error::new(
+                exitcode::USAGE,
+                miette!("Invalid co-signer identifier: {e}"),
+            )
+        })?,
+        route: route![Address::new(TCP, address)],
+    })
+}
+
 /// Return a list of trusted identities passed as a JSON string on the command line
 fn parse_trusted_identities(values: &str) -> Result<TrustedIdentities> {
     serde_json::from_str::<TrustedIdentities>(values).map_err(|e| {
@@ -1,7 +1,9 @@
+use crate::authority::co_sign::CoSignCommand;
 use crate::authority::create::CreateCommand;
 use crate::{docs, CommandGlobalOpts};
 use clap::Args;
 use clap::Subcommand;
+mod co_sign;
 mod create;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -22,6 +24,7 @@ impl AuthorityCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
             AuthoritySubcommand::Create(c) => c.run(options),
+            AuthoritySubcommand::CoSign(c) => c.run(options),
         }
     }
 }
@@ -30,4 +33,6 @@ impl AuthorityCommand {
 pub enum AuthoritySubcommand {
     #[command(display_order = 800)]
     Create(CreateCommand),
+    #[command(display_order = 800)]
+    CoSign(CoSignCommand),
 }
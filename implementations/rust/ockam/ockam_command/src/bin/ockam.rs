@@ -2,6 +2,10 @@
 // binary names. The issue is that we need to avoid the `ockam` binary colliding
 // with the `ockam` crate.
 
+// The allocations are counted to report the heap usage of the nodes, see `ockam debug heap`
+#[global_allocator]
+static ALLOCATOR: ockam_api::allocator::CountingAllocator = ockam_api::allocator::CountingAllocator;
+
 fn main() {
     ockam_command::run()
 }
@@ -0,0 +1,108 @@
+use core::time::Duration;
+use std::sync::Arc;
+
+use clap::{Args, Subcommand};
+use miette::{Context as _, IntoDiagnostic};
+
+use ockam::Context;
+use ockam_api::nodes::InMemoryNode;
+use ockam_core::{AsyncTryClone, Route};
+use ockam_multiaddr::MultiAddr;
+
+pub(crate) use receive::ReceiveCommand;
+pub(crate) use send::SendCommand;
+
+use crate::identity::get_identity_name;
+use crate::project::util::{
+    clean_projects_multiaddr, get_projects_secure_channels_from_config_lookup,
+};
+use crate::util::api::{CloudOpts, TrustContextOpts};
+use crate::util::clean_nodes_multiaddr;
+use crate::{docs, CommandGlobalOpts};
+
+mod receive;
+mod send;
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Exchange text messages between identities
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ChatCommand {
+    #[command(subcommand)]
+    subcommand: ChatSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum ChatSubcommand {
+    #[command(display_order = 800)]
+    Send(SendCommand),
+    #[command(display_order = 800)]
+    Receive(ReceiveCommand),
+}
+
+impl ChatCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            ChatSubcommand::Send(c) => c.run(options),
+            ChatSubcommand::Receive(c) => c.run(options),
+        }
+    }
+}
+
+/// Start an in-memory node with the identity of the command, and return the route
+/// to the chat service at the end of `to`, through the connections it requires
+async fn route_to_chat_service(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    to: &MultiAddr,
+    cloud_opts: &CloudOpts,
+    trust_context_opts: &TrustContextOpts,
+    timeout: Duration,
+) -> miette::Result<(InMemoryNode, Route)> {
+    let (to, meta) =
+        clean_nodes_multiaddr(to, &opts.state).context("Argument '--to' is invalid")?;
+
+    let identity_name = get_identity_name(&opts.state, &cloud_opts.identity);
+    let trust_context_config = trust_context_opts.to_config(&opts.state)?.build();
+    let node_manager = InMemoryNode::start_node(
+        ctx,
+        &opts.state,
+        None,
+        Some(identity_name.clone()),
+        trust_context_opts.project_path.as_ref(),
+        trust_context_config,
+    )
+    .await?;
+
+    // Replace `/project/<name>` occurrences with their respective secure channel addresses
+    let projects_sc = get_projects_secure_channels_from_config_lookup(
+        opts,
+        ctx,
+        &node_manager,
+        &meta,
+        Some(identity_name),
+        Some(timeout),
+    )
+    .await?;
+    let to = clean_projects_multiaddr(to, projects_sc)?;
+
+    let connection_ctx = Arc::new(ctx.async_try_clone().await.into_diagnostic()?);
+    let connection = node_manager
+        .make_connection(connection_ctx, &to, None, None, None, Some(timeout))
+        .await
+        .into_diagnostic()?;
+    let route = connection
+        .route(node_manager.tcp_transport())
+        .await
+        .into_diagnostic()?;
+    Ok((node_manager, route))
+}
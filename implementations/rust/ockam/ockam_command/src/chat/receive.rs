@@ -0,0 +1,127 @@
+use core::time::Duration;
+use std::fmt::Write;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::identity::TimestampInSeconds;
+use ockam::Context;
+use ockam_api::address::extract_address_value;
+use ockam_api::chat::{fetch_chat_messages, ChatMessage};
+use ockam_api::nodes::BackgroundNode;
+use ockam_core::api::Request;
+use ockam_multiaddr::MultiAddr;
+
+use crate::chat::route_to_chat_service;
+use crate::identity::initialize_identity_if_default;
+use crate::node::{get_node_name, initialize_node_if_default};
+use crate::output::human_readable_time;
+use crate::terminal::OckamColor;
+use crate::util::api::{CloudOpts, TrustContextOpts};
+use crate::util::duration::duration_parser;
+use crate::util::node_rpc;
+use crate::{docs, fmt_info, fmt_log, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/receive/after_long_help.txt");
+
+/// Read the text messages received by a node, or stored for you in a mailbox
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct ReceiveCommand {
+    /// Node which received the messages
+    #[arg(long, value_name = "NODE", conflicts_with = "from")]
+    at: Option<String>,
+
+    /// Route to a chat service storing messages for your identity, through a secure channel
+    #[arg(long, value_name = "ROUTE")]
+    from: Option<MultiAddr>,
+
+    /// Maximum time to wait for the messages
+    #[arg(long, value_name = "TIMEOUT", default_value = "10s", value_parser = duration_parser)]
+    timeout: Duration,
+
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+
+    #[command(flatten)]
+    trust_context_opts: TrustContextOpts,
+}
+
+impl ReceiveCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        if self.from.is_some() {
+            initialize_identity_if_default(&opts, &self.cloud_opts.identity);
+        } else {
+            initialize_node_if_default(&opts, &self.at);
+        }
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, ReceiveCommand),
+) -> miette::Result<()> {
+    // The messages are removed from the chat service once they are returned
+    let messages: Vec<ChatMessage> = match &cmd.from {
+        Some(from) => {
+            let (_node, route) = route_to_chat_service(
+                &ctx,
+                &opts,
+                from,
+                &cmd.cloud_opts,
+                &cmd.trust_context_opts,
+                cmd.timeout,
+            )
+            .await?;
+            fetch_chat_messages(&ctx, route, cmd.timeout)
+                .await
+                .into_diagnostic()?
+        }
+        None => {
+            let node_name = extract_address_value(&get_node_name(&opts.state, &cmd.at))?;
+            BackgroundNode::create(&ctx, &opts.state, &node_name)
+                .await?
+                .set_timeout(cmd.timeout)
+                .ask(&ctx, Request::get("/node/chat/messages"))
+                .await?
+        }
+    };
+
+    let plain = if messages.is_empty() {
+        fmt_info!("There are no new messages")
+    } else {
+        let mut plain = String::new();
+        for message in &messages {
+            writeln!(
+                plain,
+                "{}",
+                fmt_log!(
+                    "From {} at {}",
+                    message
+                        .from
+                        .to_string()
+                        .color(OckamColor::PrimaryResource.color()),
+                    human_readable_time(TimestampInSeconds(message.received_at))
+                )
+            )
+            .into_diagnostic()?;
+            writeln!(plain, "{}", fmt_log!("    {}", message.text)).into_diagnostic()?;
+        }
+        plain.trim_end().to_string()
+    };
+    let machine = messages
+        .iter()
+        .map(|m| m.text.clone())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .machine(machine)
+        .json(serde_json::to_string_pretty(&messages).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
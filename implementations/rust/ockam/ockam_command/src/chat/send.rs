@@ -0,0 +1,107 @@
+use core::time::Duration;
+use std::time::Instant;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_api::chat::send_chat_message;
+use ockam_multiaddr::MultiAddr;
+
+use crate::chat::route_to_chat_service;
+use crate::identity::initialize_identity_if_default;
+use crate::terminal::OckamColor;
+use crate::util::api::{CloudOpts, TrustContextOpts};
+use crate::util::duration::duration_parser;
+use crate::util::node_rpc;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/send/after_long_help.txt");
+
+/// Send a text message over a secure channel
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct SendCommand {
+    /// Route to the chat service receiving the message, through a secure channel
+    #[arg(long, value_name = "ROUTE")]
+    to: MultiAddr,
+
+    /// Identifier of the recipient, when the chat service is used as a mailbox.
+    /// By default the message is delivered to the identity of the node of the service
+    #[arg(long = "for", value_name = "IDENTIFIER")]
+    recipient: Option<Identifier>,
+
+    /// Maximum time to wait for the delivery of the message
+    #[arg(long, value_name = "TIMEOUT", default_value = "10s", value_parser = duration_parser)]
+    timeout: Duration,
+
+    text: String,
+
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+
+    #[command(flatten)]
+    trust_context_opts: TrustContextOpts,
+}
+
+impl SendCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_identity_if_default(&opts, &self.cloud_opts.identity);
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, SendCommand),
+) -> miette::Result<()> {
+    let (_node, route) = route_to_chat_service(
+        &ctx,
+        &opts,
+        &cmd.to,
+        &cmd.cloud_opts,
+        &cmd.trust_context_opts,
+        cmd.timeout,
+    )
+    .await?;
+
+    // The round trip time covers the delivery of the message and its acknowledgement,
+    // once the connections and the secure channels to the chat service are established
+    let started = Instant::now();
+    let recipient = send_chat_message(&ctx, route.clone(), cmd.recipient, cmd.text, cmd.timeout)
+        .await
+        .into_diagnostic()?;
+    let delivery = Delivery {
+        recipient: recipient.to_string(),
+        route: route.to_string(),
+        round_trip_ms: started.elapsed().as_millis() as u64,
+    };
+
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!(
+            "Message delivered to {} in {}ms",
+            delivery
+                .recipient
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            delivery.round_trip_ms
+        ))
+        .machine(&delivery.recipient)
+        .json(serde_json::to_string_pretty(&delivery).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Delivery {
+    recipient: String,
+    route: String,
+    round_trip_ms: u64,
+}
@@ -0,0 +1,255 @@
+use clap::{Arg, Command, ValueEnum};
+use clap_complete::Shell;
+use std::fmt::Write;
+
+use ockam_api::cli_state::{CliState, StateDirTrait};
+use ockam_api::nodes::models::portal::InletList;
+use ockam_api::nodes::BackgroundNode;
+use ockam_core::api::Request;
+use ockam_node::Context;
+
+use crate::node::get_default_node_name;
+use crate::CommandGlobalOpts;
+
+/// Kinds of argument values which are completed with the current state of the CLI
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DynamicValues {
+    Node,
+    Inlet,
+    Identity,
+    Project,
+    Vault,
+}
+
+impl DynamicValues {
+    /// Return the kind of values of an argument of a command, given the path of the command
+    fn of(path: &[&str], arg: &Arg) -> Option<DynamicValues> {
+        let name = arg
+            .get_value_names()
+            .and_then(|names| names.first())
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| arg.get_id().to_string())
+            .to_uppercase();
+        let positional = arg.is_positional();
+        match (path.first().copied(), name.as_str()) {
+            (_, "NODE" | "NODE_NAME") => Some(DynamicValues::Node),
+            (Some("node"), "NAME") if positional => Some(DynamicValues::Node),
+            (_, "IDENTITY" | "IDENTITY_NAME") => Some(DynamicValues::Identity),
+            (Some("identity"), "NAME") if positional => Some(DynamicValues::Identity),
+            (_, "PROJECT_NAME") => Some(DynamicValues::Project),
+            (Some("project"), "NAME") if positional => Some(DynamicValues::Project),
+            (_, "VAULT_NAME") => Some(DynamicValues::Vault),
+            (Some("vault"), "NAME") if positional => Some(DynamicValues::Vault),
+            (Some("tcp-inlet"), "ALIAS") => Some(DynamicValues::Inlet),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            DynamicValues::Node => "node",
+            DynamicValues::Inlet => "inlet",
+            DynamicValues::Identity => "identity",
+            DynamicValues::Project => "project",
+            DynamicValues::Vault => "vault",
+        }
+    }
+
+    /// Return the names stored in the CLI state for this kind of values
+    pub fn names(&self, state: &CliState) -> Vec<String> {
+        let names = match self {
+            DynamicValues::Node => state.nodes.list_items_names(),
+            DynamicValues::Identity => state.identities.list_items_names(),
+            DynamicValues::Project => state.projects.list_items_names(),
+            DynamicValues::Vault => state.vaults.list_items_names(),
+            DynamicValues::Inlet => Ok(vec![]),
+        };
+        names.unwrap_or_default()
+    }
+}
+
+/// Return the aliases of the inlets of the default node, if it is running
+pub async fn inlet_aliases(ctx: Context, opts: CommandGlobalOpts) -> miette::Result<Vec<String>> {
+    let node_name = get_default_node_name(&opts.state);
+    if !opts.state.nodes.get(&node_name)?.is_running() {
+        return Ok(vec![]);
+    }
+    let node = BackgroundNode::create(&ctx, &opts.state, &node_name).await?;
+    let inlets: InletList = node.ask(&ctx, Request::get("/node/inlet")).await?;
+    Ok(inlets.list.into_iter().map(|i| i.alias).collect())
+}
+
+/// An argument of a command whose values are completed dynamically
+#[derive(Debug, PartialEq, Eq)]
+pub struct DynamicArg {
+    /// Names of the subcommands leading to the command, separated by spaces
+    path: String,
+    /// Flags of the argument, or an empty string for a positional argument
+    flags: Vec<String>,
+    values: DynamicValues,
+}
+
+/// Return the arguments of the command tree which are completed dynamically
+pub fn dynamic_args(command: &Command) -> Vec<DynamicArg> {
+    let mut args = vec![];
+    collect_dynamic_args(command, &mut vec![], &mut args);
+    args
+}
+
+fn collect_dynamic_args<'a>(
+    command: &'a Command,
+    path: &mut Vec<&'a str>,
+    args: &mut Vec<DynamicArg>,
+) {
+    for arg in command.get_arguments().filter(|a| !a.is_hide_set()) {
+        if let Some(values) = DynamicValues::of(path, arg) {
+            let flags = if arg.is_positional() {
+                vec![String::new()]
+            } else {
+                let longs = arg.get_long().map(|l| format!("--{l}"));
+                let shorts = arg.get_short().map(|s| format!("-{s}"));
+                longs.into_iter().chain(shorts).collect()
+            };
+            args.push(DynamicArg {
+                path: path.join(" "),
+                flags,
+                values,
+            });
+        }
+    }
+    for subcommand in command.get_subcommands().filter(|c| !c.is_hide_set()) {
+        path.push(subcommand.get_name());
+        collect_dynamic_args(subcommand, path, args);
+        path.pop();
+    }
+}
+
+/// Extend a completion script generated by clap so that the given arguments are completed
+/// with the values returned by `ockam completion --values`.
+/// The dynamic values are only supported for bash, zsh and fish
+pub fn with_dynamic_values(shell: Shell, script: &str, args: &[DynamicArg]) -> String {
+    match shell {
+        Shell::Bash => format!("{script}\n{}{BASH_COMPLETION}", shell_case_function(args)),
+        Shell::Zsh => {
+            // The dynamic completion is used when the script is sourced or autoloaded
+            let (first_line, rest) = script.split_once('\n').unwrap_or((script, ""));
+            let rest = rest
+                .replace("compdef _ockam ockam", "compdef _ockam_dynamic ockam")
+                .replace("    _ockam \"$@\"", "    _ockam_dynamic \"$@\"");
+            format!(
+                "{first_line}\n\n{}{ZSH_COMPLETION}\n{rest}",
+                shell_case_function(args)
+            )
+        }
+        Shell::Fish => {
+            let mut script = script.to_string();
+            for arg in args {
+                let condition = arg
+                    .path
+                    .split(' ')
+                    .map(|c| format!("__fish_seen_subcommand_from {c}"))
+                    .collect::<Vec<_>>()
+                    .join("; and ");
+                for flag in &arg.flags {
+                    let flag = match flag.strip_prefix("--") {
+                        Some(long) => format!(" -l {long}"),
+                        None => match flag.strip_prefix('-') {
+                            Some(short) => format!(" -s {short}"),
+                            None => String::new(),
+                        },
+                    };
+                    let _ = writeln!(
+                        script,
+                        "complete -c ockam -n \"{condition}\"{flag} -f -a \"(ockam completion --values {})\"",
+                        arg.values.as_str()
+                    );
+                }
+            }
+            script
+        }
+        _ => script.to_string(),
+    }
+}
+
+/// Shell function returning the kind of values of an argument, given the path of its
+/// command and its flag, as `path|flag`
+fn shell_case_function(args: &[DynamicArg]) -> String {
+    let mut function = String::from("_ockam_dynamic_values() {\n    case \"$1\" in\n");
+    for arg in args {
+        for flag in &arg.flags {
+            let _ = writeln!(
+                function,
+                "        \"{}|{}\") echo {} ;;",
+                arg.path,
+                flag,
+                arg.values.as_str()
+            );
+        }
+    }
+    function.push_str("    esac\n}\n");
+    function
+}
+
+const BASH_COMPLETION: &str = r#"
+_ockam_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local cmd_path="" key values i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        [[ "${COMP_WORDS[i]}" == -* ]] && break
+        cmd_path="${cmd_path:+$cmd_path }${COMP_WORDS[i]}"
+    done
+    key="$cmd_path|"
+    [[ "$prev" == -* ]] && key="$cmd_path|$prev"
+    values="$(_ockam_dynamic_values "$key")"
+    if [[ -n "$values" ]]; then
+        COMPREPLY=($(compgen -W "$(ockam completion --values "$values" 2>/dev/null)" -- "$cur"))
+        return 0
+    fi
+    _ockam "$@"
+}
+
+complete -F _ockam_dynamic -o bashdefault -o default ockam
+"#;
+
+const ZSH_COMPLETION: &str = r#"
+_ockam_dynamic() {
+    local cmd_path="" key values i
+    for ((i = 2; i < CURRENT; i++)); do
+        [[ "${words[i]}" == -* ]] && break
+        cmd_path="${cmd_path:+$cmd_path }${words[i]}"
+    done
+    key="$cmd_path|"
+    [[ "${words[CURRENT-1]}" == -* ]] && key="$cmd_path|${words[CURRENT-1]}"
+    values="$(_ockam_dynamic_values "$key")"
+    if [[ -n "$values" ]]; then
+        local -a names
+        names=(${(f)"$(ockam completion --values "$values" 2>/dev/null)"})
+        compadd -a names
+        return
+    fi
+    _ockam "$@"
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OckamCommand;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_dynamic_args() {
+        let args = dynamic_args(&OckamCommand::command());
+        assert!(args.contains(&DynamicArg {
+            path: "tcp-inlet show".to_string(),
+            flags: vec![String::new()],
+            values: DynamicValues::Inlet,
+        }));
+        assert!(args
+            .iter()
+            .any(|a| a.path == "node show" && a.values == DynamicValues::Node));
+
+        let script = with_dynamic_values(Shell::Bash, "", &args);
+        assert!(script.contains("\"tcp-inlet show|\") echo inlet ;;"));
+    }
+}
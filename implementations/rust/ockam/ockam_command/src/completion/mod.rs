@@ -1,7 +1,12 @@
-use crate::{docs, OckamCommand};
+mod dynamic;
+
+use crate::util::embedded_node;
+use crate::{docs, CommandGlobalOpts, OckamCommand};
 use clap::{Args, CommandFactory};
 use clap_complete::{generate, Shell};
+use dynamic::{dynamic_args, DynamicValues};
 use std::io;
+use std::io::Write;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
@@ -17,17 +22,38 @@ const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
 )]
 pub struct CompletionCommand {
     /// The type of shell
-    #[arg(display_order = 900, long, short)]
-    shell: Shell,
+    #[arg(display_order = 900, long, short, required_unless_present = "values")]
+    shell: Option<Shell>,
+
+    /// Print the current values of a kind of argument, one per line.
+    /// This is used by the completion scripts to complete node names, inlet aliases, etc...
+    #[arg(long, value_enum, hide = true, conflicts_with = "shell")]
+    values: Option<DynamicValues>,
 }
 
 impl CompletionCommand {
-    pub fn run(self) {
-        generate(
-            self.shell,
-            &mut OckamCommand::command(),
-            "ockam",
-            &mut io::stdout(),
-        )
+    pub fn run(self, opts: CommandGlobalOpts) {
+        if let Some(values) = self.values {
+            // The completion must not fail, the values are just not completed
+            let values = match values {
+                DynamicValues::Inlet => {
+                    embedded_node(dynamic::inlet_aliases, opts).unwrap_or_default()
+                }
+                values => values.names(&opts.state),
+            };
+            for value in values {
+                println!("{value}");
+            }
+            return;
+        }
+
+        if let Some(shell) = self.shell {
+            let mut command = OckamCommand::command();
+            let mut script = vec![];
+            generate(shell, &mut command, "ockam", &mut script);
+            let script = String::from_utf8_lossy(&script);
+            let script = dynamic::with_dynamic_values(shell, &script, &dynamic_args(&command));
+            let _ = io::stdout().write_all(script.as_bytes());
+        }
     }
 }
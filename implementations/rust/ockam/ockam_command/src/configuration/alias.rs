@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use clap::{Args, CommandFactory};
+use colorful::Colorful;
+use miette::miette;
+
+use ockam_api::cli_state::{CliState, SettingsState};
+
+use crate::util::local_cmd;
+use crate::{fmt_ok, CommandGlobalOpts, OckamCommand};
+
+/// Define a shortcut for a command, with default arguments.
+/// The arguments given after the alias are appended to the command, and replace
+/// the options of the command which have the same name
+#[derive(Clone, Debug, Args)]
+pub struct SetAliasCommand {
+    /// Name of the alias, e.g. pg-tunnel
+    pub name: String,
+
+    /// Command run by the alias, e.g. tcp-inlet create --from 127.0.0.1:5432 --to /project/default/service/forward_to_db/secure/api/service/outlet
+    #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+    pub command: Vec<String>,
+}
+
+impl SetAliasCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        local_cmd(set_alias(options, self));
+    }
+}
+
+fn set_alias(opts: CommandGlobalOpts, cmd: SetAliasCommand) -> miette::Result<()> {
+    if cmd.name.starts_with('-') || OckamCommand::command().find_subcommand(&cmd.name).is_some() {
+        return Err(miette!(
+            "The alias '{}' can't replace an ockam command or option",
+            cmd.name
+        ));
+    }
+    opts.state.settings.update(|settings| {
+        settings
+            .aliases
+            .insert(cmd.name.clone(), cmd.command.clone());
+    })?;
+
+    let command = cmd.command.join(" ");
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!(
+            "The alias '{}' now runs 'ockam {command}'",
+            cmd.name
+        ))
+        .machine(&cmd.name)
+        .json(serde_json::json!({ "name": cmd.name, "command": cmd.command }))
+        .write_line()?;
+    Ok(())
+}
+
+/// Remove a command alias
+#[derive(Clone, Debug, Args)]
+pub struct UnsetAliasCommand {
+    /// Name of the alias
+    pub name: String,
+}
+
+impl UnsetAliasCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        local_cmd(unset_alias(options, self));
+    }
+}
+
+fn unset_alias(opts: CommandGlobalOpts, cmd: UnsetAliasCommand) -> miette::Result<()> {
+    let mut removed = false;
+    opts.state.settings.update(|settings| {
+        removed = settings.aliases.remove(&cmd.name).is_some();
+    })?;
+    if !removed {
+        return Err(miette!("There is no alias named '{}'", cmd.name));
+    }
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!("The alias '{}' has been removed", cmd.name))
+        .machine(&cmd.name)
+        .json(serde_json::json!({ "name": cmd.name }))
+        .write_line()?;
+    Ok(())
+}
+
+/// Return the aliases defined in the settings of the default state directory
+pub fn configured_aliases() -> BTreeMap<String, Vec<String>> {
+    CliState::default_dir()
+        .ok()
+        .and_then(|dir| SettingsState::new(&dir).get().ok())
+        .map(|settings| settings.aliases)
+        .unwrap_or_default()
+}
+
+/// If the first argument of a command line is an alias, replace it with the arguments
+/// of its command. The options following the alias replace the options of the command
+/// which have the same long name
+pub fn expand_alias(args: Vec<String>, aliases: &BTreeMap<String, Vec<String>>) -> Vec<String> {
+    let command = match args.get(1).and_then(|name| aliases.get(name)) {
+        Some(command) => command,
+        None => return args,
+    };
+    let user_args = &args[2..];
+    let user_options: Vec<&str> = user_args.iter().filter_map(|a| long_option(a)).collect();
+
+    let mut expanded = vec![args[0].clone()];
+    let mut command_args = command.iter().peekable();
+    while let Some(arg) = command_args.next() {
+        match long_option(arg) {
+            Some(option) if user_options.contains(&option) => {
+                // Also skip the value of the replaced option
+                if !arg.contains('=')
+                    && matches!(command_args.peek(), Some(v) if !v.starts_with('-'))
+                {
+                    command_args.next();
+                }
+            }
+            _ => expanded.push(arg.clone()),
+        }
+    }
+    expanded.extend(user_args.iter().cloned());
+    expanded
+}
+
+/// Return the name of a long option like `--from` or `--from=value`
+fn long_option(arg: &str) -> Option<&str> {
+    arg.strip_prefix("--")
+        .filter(|name| !name.is_empty())
+        .and_then(|name| name.split('=').next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split(' ').map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        let mut aliases = BTreeMap::new();
+        aliases.insert(
+            "pg-tunnel".to_string(),
+            args("tcp-inlet create --from 127.0.0.1:5432 --to /node/n1/service/outlet"),
+        );
+
+        assert_eq!(
+            expand_alias(args("ockam pg-tunnel"), &aliases),
+            args("ockam tcp-inlet create --from 127.0.0.1:5432 --to /node/n1/service/outlet")
+        );
+        assert_eq!(
+            expand_alias(args("ockam pg-tunnel --from=127.0.0.1:6000 -q"), &aliases),
+            args("ockam tcp-inlet create --to /node/n1/service/outlet --from=127.0.0.1:6000 -q")
+        );
+        assert_eq!(
+            expand_alias(args("ockam node list"), &aliases),
+            args("ockam node list")
+        );
+    }
+}
@@ -16,5 +16,9 @@ fn run_impl(opts: CommandGlobalOpts, _cmd: ListCommand) -> miette::Result<()> {
     for node in opts.state.nodes.list()? {
         opts.terminal.write(format!("Node: {}\n", node.name()))?;
     }
+    for (name, command) in opts.state.settings.get()?.aliases {
+        opts.terminal
+            .write(format!("Alias: {name} = {}\n", command.join(" ")))?;
+    }
     Ok(())
 }
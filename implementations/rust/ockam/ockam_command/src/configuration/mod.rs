@@ -1,12 +1,19 @@
+mod alias;
 mod get;
 mod get_default_node;
 mod list;
+mod set;
 mod set_default_node;
+mod unset;
 
+pub use alias::{configured_aliases, expand_alias};
+use alias::{SetAliasCommand, UnsetAliasCommand};
 use get::GetCommand;
 use get_default_node::GetDefaultNodeCommand;
 use list::ListCommand;
+use set::SetCommand;
 use set_default_node::SetDefaultNodeCommand;
+use unset::UnsetCommand;
 
 use crate::docs;
 use crate::CommandGlobalOpts;
@@ -26,7 +33,11 @@ pub enum ConfigurationSubcommand {
     Get(GetCommand),
     GetDefaultNode(GetDefaultNodeCommand),
     List(ListCommand),
+    Set(SetCommand),
+    SetAlias(SetAliasCommand),
     SetDefaultNode(SetDefaultNodeCommand),
+    Unset(UnsetCommand),
+    UnsetAlias(UnsetAliasCommand),
 }
 
 impl ConfigurationCommand {
@@ -35,7 +46,11 @@ impl ConfigurationCommand {
             ConfigurationSubcommand::Get(c) => c.run(options),
             ConfigurationSubcommand::GetDefaultNode(c) => c.run(options),
             ConfigurationSubcommand::List(c) => c.run(options),
+            ConfigurationSubcommand::Set(c) => c.run(options),
+            ConfigurationSubcommand::SetAlias(c) => c.run(options),
             ConfigurationSubcommand::SetDefaultNode(c) => c.run(options),
+            ConfigurationSubcommand::Unset(c) => c.run(options),
+            ConfigurationSubcommand::UnsetAlias(c) => c.run(options),
         }
     }
 }
@@ -0,0 +1,96 @@
+use std::str::FromStr;
+
+use clap::{Args, ValueEnum};
+use colorful::Colorful;
+use miette::miette;
+
+use ockam_api::cli_state::SettingsConfig;
+use ockam_api::nodes::service::AliasTemplate;
+use ockam_api::port_range::PortRange;
+
+use crate::util::local_cmd;
+use crate::{fmt_ok, CommandGlobalOpts};
+
+/// Set a configuration value
+#[derive(Clone, Debug, Args)]
+pub struct SetCommand {
+    /// Name of the setting
+    pub setting: Setting,
+
+    /// Value of the setting
+    pub value: String,
+}
+
+/// Settings which can be modified with `ockam configuration set/unset`
+#[derive(Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Setting {
+    /// Range of ports used for TCP inlets created without an explicit `--from` address, e.g. 41000-41999
+    InletPortRange,
+    /// Template for the alias of inlets and outlets created without `--alias`, e.g. {kind}-{port}-{target}
+    AliasTemplate,
+    /// Refuse the secure channels to an address presenting another identifier than the pinned one: true or false
+    StrictPins,
+}
+
+impl Setting {
+    pub fn name(&self) -> String {
+        self.to_possible_value()
+            .map(|v| v.get_name().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Parse the value and store it in the settings
+    pub fn set(&self, settings: &mut SettingsConfig, value: &str) -> miette::Result<()> {
+        match self {
+            Setting::InletPortRange => {
+                let range = PortRange::try_from(value)
+                    .map_err(|e| miette!("Invalid port range '{value}': {e}"))?;
+                settings.inlet_port_range = Some(range);
+            }
+            Setting::AliasTemplate => {
+                let template = AliasTemplate::from_str(value).map_err(|e| miette!(e))?;
+                settings.alias_template = Some(template.to_string());
+            }
+            Setting::StrictPins => {
+                let strict = value
+                    .parse::<bool>()
+                    .map_err(|_| miette!("Invalid value '{value}': expected true or false"))?;
+                settings.strict_pins = Some(strict);
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the setting value
+    pub fn unset(&self, settings: &mut SettingsConfig) {
+        match self {
+            Setting::InletPortRange => settings.inlet_port_range = None,
+            Setting::AliasTemplate => settings.alias_template = None,
+            Setting::StrictPins => settings.strict_pins = None,
+        }
+    }
+}
+
+impl SetCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        local_cmd(run_impl(options, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: SetCommand) -> miette::Result<()> {
+    let mut settings = opts.state.settings.get()?;
+    cmd.setting.set(&mut settings, &cmd.value)?;
+    opts.state.settings.set(&settings)?;
+
+    let name = cmd.setting.name();
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!(
+            "The setting '{name}' is now set to '{}'",
+            cmd.value
+        ))
+        .machine(&cmd.value)
+        .json(serde_json::json!({ "name": name, "value": cmd.value }))
+        .write_line()?;
+    Ok(())
+}
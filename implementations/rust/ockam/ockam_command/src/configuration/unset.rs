@@ -0,0 +1,34 @@
+use clap::Args;
+use colorful::Colorful;
+
+use super::set::Setting;
+use crate::util::local_cmd;
+use crate::{fmt_ok, CommandGlobalOpts};
+
+/// Remove a configuration value
+#[derive(Clone, Debug, Args)]
+pub struct UnsetCommand {
+    /// Name of the setting
+    pub setting: Setting,
+}
+
+impl UnsetCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        local_cmd(run_impl(options, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: UnsetCommand) -> miette::Result<()> {
+    opts.state
+        .settings
+        .update(|settings| cmd.setting.unset(settings))?;
+
+    let name = cmd.setting.name();
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!("The setting '{name}' has been removed"))
+        .machine(&name)
+        .json(serde_json::json!({ "name": name }))
+        .write_line()?;
+    Ok(())
+}
@@ -1,4 +1,5 @@
-use ockam_core::compat::collections::HashMap;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 use crate::identity::{get_identity_name, initialize_identity_if_default};
 use crate::{
@@ -9,10 +10,13 @@ use crate::{
 use clap::Args;
 
 use crate::output::{CredentialAndPurposeKeyDisplay, EncodeFormat};
-use miette::{miette, IntoDiagnostic};
+use miette::{miette, IntoDiagnostic, WrapErr};
 use ockam::identity::utils::AttributesBuilder;
 use ockam::identity::Identifier;
-use ockam::identity::{MAX_CREDENTIAL_VALIDITY, PROJECT_MEMBER_SCHEMA, TRUST_CONTEXT_ID};
+use ockam::identity::{
+    AttributesSchema, ATTRIBUTE_TYPES, MAX_CREDENTIAL_VALIDITY, PROJECT_MEMBER_SCHEMA,
+    TRUST_CONTEXT_ID,
+};
 use ockam::Context;
 use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
 
@@ -29,6 +33,11 @@ pub struct IssueCommand {
     #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
     pub attributes: Vec<String>,
 
+    /// Path to a YAML or JSON file defining the accepted attributes, their types
+    /// (string, int or bool) and whether they are required
+    #[arg(long = "schema", value_name = "SCHEMA_PATH")]
+    pub schema_path: Option<PathBuf>,
+
     /// Name of the Vault that will be used to issue the credential.
     #[arg(value_name = "VAULT_NAME")]
     pub vault: Option<String>,
@@ -44,8 +53,8 @@ impl IssueCommand {
         node_rpc(run_impl, (opts, self));
     }
 
-    fn attributes(&self) -> Result<HashMap<String, String>> {
-        let mut attributes = HashMap::new();
+    fn attributes(&self) -> Result<BTreeMap<String, String>> {
+        let mut attributes = BTreeMap::new();
         for attr in &self.attributes {
             let mut parts = attr.splitn(2, '=');
             let key = parts.next().ok_or(miette!("key expected"))?;
@@ -55,6 +64,21 @@ impl IssueCommand {
         Ok(attributes)
     }
 
+    fn schema(&self) -> miette::Result<Option<AttributesSchema>> {
+        match &self.schema_path {
+            Some(path) => {
+                let contents = std::fs::read_to_string(path)
+                    .into_diagnostic()
+                    .wrap_err(format!("Failed to read the schema file {}", path.display()))?;
+                let schema = serde_yaml::from_str(&contents)
+                    .into_diagnostic()
+                    .wrap_err(format!("Invalid schema file {}", path.display()))?;
+                Ok(Some(schema))
+            }
+            None => Ok(None),
+        }
+    }
+
     pub fn identity_identifier(&self) -> &Identifier {
         &self.identity_identifier
     }
@@ -81,7 +105,18 @@ async fn run_impl(
             TRUST_CONTEXT_ID.to_vec(),
             auth_identity_identifier.to_string(),
         );
-    for (key, value) in cmd.attributes()? {
+    let attributes = cmd.attributes()?;
+    if let Some(schema) = cmd.schema()? {
+        schema
+            .validate(&attributes)
+            .into_diagnostic()
+            .wrap_err("The attributes don't match the schema")?;
+        if let Some(attribute_types) = schema.attribute_types(&attributes) {
+            attributes_builder =
+                attributes_builder.with_attribute(ATTRIBUTE_TYPES.to_vec(), attribute_types);
+        }
+    }
+    for (key, value) in attributes {
         attributes_builder =
             attributes_builder.with_attribute(key.as_bytes().to_vec(), value.as_bytes().to_vec());
     }
@@ -2,6 +2,8 @@ pub(crate) mod get;
 pub(crate) mod issue;
 pub(crate) mod list;
 pub(crate) mod present;
+pub(crate) mod refresh;
+pub(crate) mod revoke;
 pub(crate) mod show;
 pub(crate) mod store;
 pub(crate) mod verify;
@@ -13,6 +15,8 @@ pub(crate) use list::ListCommand;
 use ockam::identity::{Identifier, Identities, Identity};
 use ockam_api::cli_state::{CredentialState, StateItemTrait};
 pub(crate) use present::PresentCommand;
+pub(crate) use refresh::RefreshCommand;
+pub(crate) use revoke::RevokeCommand;
 pub(crate) use show::ShowCommand;
 use std::sync::Arc;
 pub(crate) use store::StoreCommand;
@@ -40,6 +44,8 @@ pub enum CredentialSubcommand {
     Issue(IssueCommand),
     List(ListCommand),
     Present(PresentCommand),
+    Refresh(RefreshCommand),
+    Revoke(RevokeCommand),
     Show(ShowCommand),
     Store(StoreCommand),
     Verify(VerifyCommand),
@@ -52,6 +58,8 @@ impl CredentialCommand {
             CredentialSubcommand::Issue(c) => c.run(options),
             CredentialSubcommand::List(c) => c.run(options),
             CredentialSubcommand::Present(c) => c.run(options),
+            CredentialSubcommand::Refresh(c) => c.run(options),
+            CredentialSubcommand::Revoke(c) => c.run(options),
             CredentialSubcommand::Show(c) => c.run(options),
             CredentialSubcommand::Store(c) => c.run(options),
             CredentialSubcommand::Verify(c) => c.run(options),
@@ -0,0 +1,59 @@
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::nodes::{BackgroundNode, Credentials};
+
+use crate::node::{get_node_name, initialize_node_if_default, NodeOpts};
+use crate::output::CredentialAndPurposeKeyDisplay;
+use crate::terminal::OckamColor;
+use crate::util::node_rpc;
+use crate::{fmt_log, fmt_ok, CommandGlobalOpts};
+
+/// Retrieve a new credential for a node from its authority
+#[derive(Clone, Debug, Args)]
+pub struct RefreshCommand {
+    #[command(flatten)]
+    pub node_opts: NodeOpts,
+
+    /// Keep refreshing the credential in the background, before it expires
+    #[arg(long)]
+    pub auto: bool,
+}
+
+impl RefreshCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_node_if_default(&opts, &self.node_opts.at_node);
+        node_rpc(rpc, (opts, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, RefreshCommand)) -> miette::Result<()> {
+    run_impl(&ctx, opts, cmd).await
+}
+
+async fn run_impl(
+    ctx: &Context,
+    opts: CommandGlobalOpts,
+    cmd: RefreshCommand,
+) -> miette::Result<()> {
+    let node_name = get_node_name(&opts.state, &cmd.node_opts.at_node);
+    let node = BackgroundNode::create(ctx, &opts.state, &node_name).await?;
+    let credential = node.refresh_credential(ctx, cmd.auto).await?;
+
+    let mut plain = fmt_ok!(
+        "Refreshed the credential of the node {}\n",
+        node_name
+            .to_string()
+            .color(OckamColor::PrimaryResource.color())
+    );
+    if cmd.auto {
+        plain.push_str(&fmt_log!(
+            "The node will refresh its credential before it expires\n"
+        ));
+    }
+    plain.push_str(&format!("{}", CredentialAndPurposeKeyDisplay(credential)));
+
+    opts.terminal.stdout().plain(plain).write_line()?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_api::authority_node::CredentialRevocations;
+use ockam_api::nodes::InMemoryNode;
+use ockam_multiaddr::MultiAddr;
+
+use crate::identity::{get_identity_name, initialize_identity_if_default};
+use crate::project::util::get_project;
+use crate::terminal::OckamColor;
+use crate::util::api::CloudOpts;
+use crate::util::node_rpc;
+use crate::{fmt_ok, CommandGlobalOpts};
+
+/// Revoke the credentials issued by a project authority to an identity, as an authorised enroller
+#[derive(Clone, Debug, Args)]
+pub struct RevokeCommand {
+    /// Identifier of the identity whose credentials are revoked
+    #[arg(value_name = "IDENTIFIER")]
+    pub identifier: Identifier,
+
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+
+    /// Route to the project whose authority issued the credentials
+    #[arg(long, short, default_value = "/project/default")]
+    to: MultiAddr,
+}
+
+impl RevokeCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_identity_if_default(&opts, &self.cloud_opts.identity);
+        node_rpc(run_impl, (opts, self));
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, RevokeCommand),
+) -> miette::Result<()> {
+    let authority = match get_project(&opts.state, &cmd.to).await? {
+        (_, Some(authority)) => authority,
+        _ => {
+            return Err(miette!(
+                "Cannot revoke credentials. Please specify a route to your project"
+            ))
+        }
+    };
+
+    let node = InMemoryNode::start(&ctx, &opts.state).await?;
+    let identity = get_identity_name(&opts.state, &cmd.cloud_opts.identity);
+    let authority_node = node
+        .create_authority_client(authority.identity_id(), authority.address(), Some(identity))
+        .await?;
+    authority_node
+        .revoke_credentials(&ctx, cmd.identifier.clone())
+        .await?;
+
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!(
+            "Revoked the credentials of {}",
+            cmd.identifier
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        ))
+        .json(serde_json::json!({ "identifier": cmd.identifier.to_string() }))
+        .write_line()?;
+    Ok(())
+}
@@ -4,13 +4,19 @@ use indoc::formatdoc;
 use miette::IntoDiagnostic;
 use ockam::Context;
 use ockam_api::cli_state::{StateDirTrait, StateItemTrait};
+use ockam_api::identity::credential_to_jwt;
 
 use crate::credential::identities;
-use crate::output::CredentialAndPurposeKeyDisplay;
+use crate::output::{CredentialAndPurposeKeyDisplay, OutputFormat};
 use crate::{
     credential::validate_encoded_cred, util::node_rpc, vault::default_vault_name, CommandGlobalOpts,
 };
 
+/// Show a credential.
+///
+/// With `--output jwt`, the verified attributes of the credential are printed as a JWT signed by
+/// the credential issuer, whose public key is added to the `jwk` header. The vault must contain the
+/// key of the issuer
 #[derive(Clone, Debug, Args)]
 pub struct ShowCommand {
     #[arg()]
@@ -52,7 +58,7 @@ async fn run_impl(
 
     let is_verified = match validate_encoded_cred(
         &cred_config.encoded_credential,
-        identities,
+        identities.clone(),
         &cred_config.issuer_identifier,
     )
     .await
@@ -62,6 +68,13 @@ async fn run_impl(
     };
 
     let cred = cred_config.credential()?;
+    let mut output = opts.terminal.stdout();
+    if opts.global_args.output_format == OutputFormat::Jwt {
+        let jwt = credential_to_jwt(identities, &cred_config.issuer_identifier, &cred)
+            .await
+            .into_diagnostic()?;
+        output = output.jwt(jwt);
+    }
     let plain = formatdoc!(
         r#"
         Credential: {cred_name} {is_verified}
@@ -70,7 +83,7 @@ async fn run_impl(
         CredentialAndPurposeKeyDisplay(cred)
     );
 
-    opts.terminal.stdout().plain(plain).write_line()?;
+    output.plain(plain).write_line()?;
 
     Ok(())
 }
@@ -0,0 +1,103 @@
+use std::fmt::Write;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+
+use ockam_api::cli_state::StateDirTrait;
+use ockam_api::nodes::models::stats::HeapStats;
+use ockam_api::nodes::BackgroundNode;
+use ockam_node::Context;
+
+use crate::node::get_node_name;
+use crate::node::top::format_bytes;
+use crate::terminal::OckamColor;
+use crate::util::{api, node_rpc};
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/heap/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/heap/after_long_help.txt");
+
+/// Show the heap usage of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct HeapCommand {
+    /// Name of the node
+    #[arg()]
+    node_name: Option<String>,
+}
+
+impl HeapCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, HeapCommand),
+) -> miette::Result<()> {
+    let node_name = get_node_name(&opts.state, &cmd.node_name);
+    if !opts.state.nodes.get(&node_name)?.is_running() {
+        return Err(miette!("The node '{}' is not running", node_name));
+    }
+
+    let node = BackgroundNode::create(&ctx, &opts.state, &node_name).await?;
+    let stats: HeapStats = node.ask(&ctx, api::node_heap_stats()).await?;
+    opts.terminal
+        .stdout()
+        .plain(render(&node_name, &stats))
+        .json(serde_json::to_string(&stats).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+fn render(node_name: &str, stats: &HeapStats) -> String {
+    let mut view = String::new();
+    let _ = writeln!(
+        view,
+        "Node {}",
+        node_name.color(OckamColor::PrimaryResource.color())
+    );
+    match &stats.allocator {
+        Some(allocator) => {
+            let _ = writeln!(
+                view,
+                "  Allocated: {} (peak: {})",
+                format_bytes(allocator.allocated_bytes as f64),
+                format_bytes(allocator.peak_allocated_bytes as f64)
+            );
+            let _ = writeln!(
+                view,
+                "  Allocations: {}, deallocations: {}",
+                allocator.allocations, allocator.deallocations
+            );
+        }
+        None => {
+            let _ = writeln!(view, "  Allocator statistics: not available");
+        }
+    }
+    let counts = [
+        ("Workers", stats.workers as u64),
+        ("Processors", stats.processors as u64),
+        ("Secure channels", stats.secure_channels as u64),
+        (
+            "Secure channel listeners",
+            stats.secure_channel_listeners as u64,
+        ),
+        ("Sessions", stats.sessions as u64),
+        ("Relays", stats.relays as u64),
+        ("Inlets", stats.inlets as u64),
+        ("Outlets", stats.outlets as u64),
+        ("Buffered messages", stats.buffered_messages),
+    ];
+    for (name, count) in counts {
+        let _ = writeln!(view, "  {name}: {count}");
+    }
+    view
+}
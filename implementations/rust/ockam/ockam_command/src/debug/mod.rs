@@ -0,0 +1,29 @@
+use clap::{Args, Subcommand};
+
+pub use heap::HeapCommand;
+
+use crate::CommandGlobalOpts;
+
+mod heap;
+
+/// Debug the nodes
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, subcommand_required = true)]
+pub struct DebugCommand {
+    #[command(subcommand)]
+    subcommand: DebugSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum DebugSubcommand {
+    #[command(display_order = 800)]
+    Heap(HeapCommand),
+}
+
+impl DebugCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            DebugSubcommand::Heap(c) => c.run(options),
+        }
+    }
+}
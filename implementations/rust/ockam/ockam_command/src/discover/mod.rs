@@ -0,0 +1,216 @@
+use core::time::Duration;
+use std::fmt::Write;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+
+use ockam::{route, Context};
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::discovery::{discover, DiscoveredNode};
+use ockam_api::nodes::models::secure_channel::{
+    CreateSecureChannelRequest, CreateSecureChannelResponse,
+};
+use ockam_api::nodes::BackgroundNode;
+use ockam_api::route_to_multiaddr;
+use ockam_core::api::Request;
+use ockam_multiaddr::MultiAddr;
+
+use crate::identity::get_identity_name;
+use crate::node::{get_node_name, initialize_node_if_default};
+use crate::terminal::OckamColor;
+use crate::util::api::CloudOpts;
+use crate::util::duration::duration_parser;
+use crate::util::node_rpc;
+use crate::{docs, fmt_info, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Find the nodes advertising themselves on the local network
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct DiscoverCommand {
+    /// Time during which the answers of the nodes are collected
+    #[arg(long, value_name = "TIMEOUT", default_value = "2s", value_parser = duration_parser)]
+    timeout: Duration,
+
+    /// Create a secure channel to the discovered node with this name or identifier
+    #[arg(long, value_name = "NAME_OR_IDENTIFIER")]
+    connect: Option<String>,
+
+    /// Node from which the secure channel is created
+    #[arg(long, value_name = "NODE", requires = "connect")]
+    from: Option<String>,
+
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+}
+
+impl DiscoverCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        if self.connect.is_some() {
+            initialize_node_if_default(&opts, &self.from);
+        }
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, DiscoverCommand),
+) -> miette::Result<()> {
+    let nodes = discover(cmd.timeout).await.into_diagnostic()?;
+    match &cmd.connect {
+        Some(peer) => connect(&ctx, &opts, &cmd, &nodes, peer).await,
+        None => list(&opts, &nodes),
+    }
+}
+
+fn list(opts: &CommandGlobalOpts, nodes: &[DiscoveredNode]) -> miette::Result<()> {
+    let nodes = nodes
+        .iter()
+        .map(NodeOutput::try_from)
+        .collect::<miette::Result<Vec<_>>>()?;
+
+    let mut plain = String::new();
+    if nodes.is_empty() {
+        plain.push_str(&fmt_info!("No node answered on the local network"));
+    }
+    for node in &nodes {
+        writeln!(
+            plain,
+            "{}",
+            fmt_log!(
+                "Node {} at {}",
+                node.name.clone().color(OckamColor::PrimaryResource.color()),
+                node.address
+                    .clone()
+                    .color(OckamColor::PrimaryResource.color())
+            )
+        )
+        .into_diagnostic()?;
+        writeln!(plain, "{}", fmt_log!("    Identifier: {}", node.identifier)).into_diagnostic()?;
+        writeln!(
+            plain,
+            "{}",
+            fmt_log!("    Services: {}", node.services.join(", "))
+        )
+        .into_diagnostic()?;
+    }
+
+    opts.terminal
+        .stdout()
+        .plain(plain.trim_end())
+        .machine(
+            nodes
+                .iter()
+                .map(|n| n.address.clone())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+        .json(serde_json::to_string_pretty(&nodes).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+/// Create a secure channel to the secure channel listener of a discovered node.
+///
+/// The identifier presented by the node is pinned the first time, like with
+/// `ockam secure-channel create`, so that a node impersonating it later on is detected
+async fn connect(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    cmd: &DiscoverCommand,
+    nodes: &[DiscoveredNode],
+    peer: &str,
+) -> miette::Result<()> {
+    let matching: Vec<&DiscoveredNode> = nodes
+        .iter()
+        .filter(|n| n.advertisement.name == peer || n.advertisement.identifier == peer)
+        .collect();
+    let node = match matching.as_slice() {
+        [node] => *node,
+        [] => {
+            return Err(miette!(
+                "No node named {peer} answered on the local network"
+            ))
+        }
+        _ => {
+            return Err(miette!(
+            "Several nodes named {peer} answered on the local network. Use an identifier instead"
+        ))
+        }
+    };
+    let to = node
+        .address()
+        .into_diagnostic()?
+        .concat(&MultiAddr::try_from("/service/api").into_diagnostic()?)
+        .into_diagnostic()?;
+
+    let from = extract_address_value(&get_node_name(&opts.state, &cmd.from))?;
+    let background_node = BackgroundNode::create(ctx, &opts.state, &from).await?;
+    let identity_name = get_identity_name(&opts.state, &cmd.cloud_opts.identity);
+    let request = Request::post("/node/secure_channel").body(CreateSecureChannelRequest::new(
+        &to,
+        None,
+        Some(identity_name),
+        None,
+    ));
+    let response: CreateSecureChannelResponse = background_node.ask(ctx, request).await?;
+    if let Some(pinned) = &response.pinned_identifier {
+        opts.terminal.write_line(&fmt_warn!(
+            "The identifier of {} changed since the first secure channel to it: {pinned} was pinned. \
+            Run `ockam trust pin remove` if this change is expected",
+            to.to_string().color(OckamColor::PrimaryResource.color())
+        ))?;
+    }
+
+    let route = route![response.addr.to_string()];
+    let multi_addr = route_to_multiaddr(&route)
+        .ok_or_else(|| miette!("Failed to convert route {route} to multi-address"))?;
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!(
+            "Secure Channel at {} created from {} to the node {}",
+            multi_addr
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            format!("/node/{from}").color(OckamColor::PrimaryResource.color()),
+            node.advertisement
+                .name
+                .clone()
+                .color(OckamColor::PrimaryResource.color())
+        ))
+        .machine(multi_addr.to_string())
+        .json(serde_json::json!([{ "address": multi_addr.to_string() }]))
+        .write_line()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct NodeOutput {
+    name: String,
+    identifier: String,
+    address: String,
+    services: Vec<String>,
+}
+
+impl TryFrom<&DiscoveredNode> for NodeOutput {
+    type Error = miette::Report;
+
+    fn try_from(node: &DiscoveredNode) -> miette::Result<Self> {
+        Ok(Self {
+            name: node.advertisement.name.clone(),
+            identifier: node.advertisement.identifier.clone(),
+            address: node.address().into_diagnostic()?.to_string(),
+            services: node.advertisement.services.clone(),
+        })
+    }
+}
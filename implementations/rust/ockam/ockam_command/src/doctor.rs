@@ -0,0 +1,415 @@
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::nodes::NodeManager;
+use ockam_multiaddr::MultiAddr;
+use ockam_transport_tcp::TcpProxy;
+
+use crate::util::duration::duration_parser;
+use crate::util::{exitcode, local_cmd};
+use crate::{fmt_err, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts};
+
+const DEFAULT_NTP_SERVER: &str = "pool.ntp.org:123";
+
+/// Clock skew above which the credentials and purpose keys created by another host are rejected
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5);
+
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970)
+const NTP_UNIX_OFFSET: f64 = 2_208_988_800.0;
+
+/// Check the environment for the common problems preventing the nodes from working:
+/// clock skew, permissions of the state directory, ports in use, DNS resolution of
+/// the Orchestrator and projects addresses, and proxy variables.
+/// A fix is printed for each problem, and the command exits with a non-zero code
+/// when a problem is found
+#[derive(Clone, Debug, Args)]
+pub struct DoctorCommand {
+    /// Local port which must be free, for example the port of an inlet to create.
+    /// Can be repeated
+    #[arg(long = "port", value_name = "PORT")]
+    ports: Vec<u16>,
+
+    /// NTP server used to measure the skew of the local clock
+    #[arg(long, value_name = "HOST:PORT", default_value = DEFAULT_NTP_SERVER)]
+    ntp_server: String,
+
+    /// How long to wait for each network check
+    #[arg(long, value_name = "DURATION", default_value = "5s", value_parser = duration_parser)]
+    timeout: Duration,
+}
+
+impl DoctorCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// Result of a check of the environment
+#[derive(Debug, Serialize)]
+struct Check {
+    /// Part of the environment which is checked: clock, state, ports, dns or proxy
+    topic: &'static str,
+    status: CheckStatus,
+    message: String,
+    /// How to fix the problem, if there is one
+    fix: Option<String>,
+}
+
+impl Check {
+    fn ok(topic: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            topic,
+            status: CheckStatus::Ok,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(topic: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            topic,
+            status: CheckStatus::Warning,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn error(topic: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            topic,
+            status: CheckStatus::Error,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DoctorReport {
+    healthy: bool,
+    checks: Vec<Check>,
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: DoctorCommand) -> miette::Result<()> {
+    let mut checks = vec![check_clock(&cmd.ntp_server, cmd.timeout)];
+    checks.extend(check_state_dir(&opts.state.dir));
+    checks.extend(check_ports(&opts, &cmd.ports)?);
+    checks.extend(check_dns(&opts)?);
+    checks.extend(check_proxy(cmd.timeout));
+
+    let healthy = checks.iter().all(|c| c.status != CheckStatus::Error);
+    let report = DoctorReport { healthy, checks };
+    opts.terminal
+        .stdout()
+        .plain(build_plain_output(&report))
+        .machine(if healthy { "healthy" } else { "unhealthy" })
+        .json(serde_json::to_string_pretty(&report).into_diagnostic()?)
+        .write_line()?;
+    if !healthy {
+        std::process::exit(exitcode::CONFIG);
+    }
+    Ok(())
+}
+
+fn build_plain_output(report: &DoctorReport) -> String {
+    let mut plain = String::new();
+    for check in &report.checks {
+        plain.push_str(&match check.status {
+            CheckStatus::Ok => fmt_ok!("{}\n", check.message),
+            CheckStatus::Warning => fmt_warn!("{}\n", check.message),
+            CheckStatus::Error => fmt_err!("{}\n", check.message),
+        });
+        if let Some(fix) = &check.fix {
+            plain.push_str(&fmt_log!("Fix: {fix}\n"));
+        }
+    }
+    plain
+}
+
+/// The credentials are rejected when the clocks of their issuer and verifier differ too much
+fn check_clock(ntp_server: &str, timeout: Duration) -> Check {
+    match clock_offset(ntp_server, timeout) {
+        Ok(offset) if offset.abs() > MAX_CLOCK_SKEW.as_secs_f64() => Check::error(
+            "clock",
+            format!("The local clock is {offset:+.1}s off the time of {ntp_server}, the credentials may be rejected"),
+            "Synchronize the clock with NTP, for example with `timedatectl set-ntp true` or `sntp -sS pool.ntp.org`",
+        ),
+        Ok(offset) => Check::ok(
+            "clock",
+            format!("The local clock is {offset:+.1}s off the time of {ntp_server}"),
+        ),
+        Err(e) => Check::warning(
+            "clock",
+            format!("The clock skew can't be measured with {ntp_server}: {e}"),
+            "Allow the outgoing UDP traffic to port 123, or use another server with --ntp-server",
+        ),
+    }
+}
+
+/// Return the difference, in seconds, between the time of an NTP server and the local time,
+/// with a SNTP request (RFC 4330)
+fn clock_offset(server: &str, timeout: Duration) -> std::io::Result<f64> {
+    let address = server
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| std::io::Error::new(ErrorKind::NotFound, "no address found"))?;
+    let local_address = if address.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = UdpSocket::bind(local_address)?;
+    socket.set_read_timeout(Some(timeout))?;
+    socket.connect(address)?;
+
+    let mut request = [0u8; 48];
+    // No leap indicator, version 4, client mode
+    request[0] = 0x23;
+    let sent_at = unix_time(SystemTime::now());
+    socket.send(&request)?;
+    let mut response = [0u8; 48];
+    if socket.recv(&mut response)? < response.len() {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            "incomplete NTP response",
+        ));
+    }
+    let received_at = unix_time(SystemTime::now());
+    // The server time is compared to the local time in the middle of the round trip
+    Ok(ntp_time(&response[40..48]) - (sent_at + received_at) / 2.0)
+}
+
+fn unix_time(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default()
+}
+
+/// Convert a 64 bits NTP timestamp to seconds since the Unix epoch
+fn ntp_time(timestamp: &[u8]) -> f64 {
+    let seconds = u32::from_be_bytes([timestamp[0], timestamp[1], timestamp[2], timestamp[3]]);
+    let fraction = u32::from_be_bytes([timestamp[4], timestamp[5], timestamp[6], timestamp[7]]);
+    seconds as f64 - NTP_UNIX_OFFSET + fraction as f64 / 2f64.powi(32)
+}
+
+/// The state directory must be writable, and the files created in it, with the umask
+/// of the user, must be readable and writable by their owner
+fn check_state_dir(dir: &Path) -> Vec<Check> {
+    let dir_name = dir.display();
+    let probe = dir.join(".doctor");
+    if let Err(e) = std::fs::write(&probe, b"") {
+        return vec![Check::error(
+            "state",
+            format!("The state directory {dir_name} is not writable: {e}"),
+            format!("Run `chmod u+rwx {dir_name}`, or set OCKAM_HOME to a writable directory"),
+        )];
+    }
+    let file_mode = mode(&probe);
+    let _ = std::fs::remove_file(&probe);
+
+    let mut checks = vec![];
+    match file_mode {
+        Some(mode) if mode & 0o600 != 0o600 => checks.push(Check::error(
+            "state",
+            format!("The umask prevents the owner of the files created in {dir_name} from reading or writing them"),
+            "Run `umask 077` before the ockam commands, and `chmod -R u+rw` on the state directory",
+        )),
+        _ => checks.push(Check::ok(
+            "state",
+            format!("The state directory {dir_name} is writable"),
+        )),
+    }
+    if matches!(mode(dir), Some(mode) if mode & 0o077 != 0) {
+        checks.push(Check::warning(
+            "state",
+            format!("The state directory {dir_name}, which stores the keys of the identities, can be accessed by other users"),
+            format!("Run `chmod 700 {dir_name}`"),
+        ));
+    }
+    checks
+}
+
+#[cfg(unix)]
+fn mode(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .ok()
+        .map(|m| m.permissions().mode() & 0o777)
+}
+
+#[cfg(not(unix))]
+fn mode(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// The ports given by the user, and the ports of the API of the stopped nodes,
+/// must not be used by other processes
+fn check_ports(opts: &CommandGlobalOpts, ports: &[u16]) -> miette::Result<Vec<Check>> {
+    let mut checks = vec![];
+    for node in opts.state.nodes.list()? {
+        if node.is_running() {
+            continue;
+        }
+        let address = match &node.config().setup().api_transport {
+            Some(transport) if transport.addr.port() != 0 => transport.addr.to_string(),
+            _ => continue,
+        };
+        if is_in_use(&address) {
+            checks.push(Check::error(
+                "ports",
+                format!(
+                    "The address {address} of the node {} is used by another process, the node can't be started",
+                    node.name()
+                ),
+                format!(
+                    "Stop the process listening on {address}, or delete the node and create it again"
+                ),
+            ));
+        }
+    }
+    for port in ports {
+        let address = format!("127.0.0.1:{port}");
+        if is_in_use(&address) {
+            checks.push(Check::error(
+                "ports",
+                format!("The port {port} is already in use"),
+                format!(
+                    "Use another port, or stop the process listening on it (see `lsof -i :{port}`)"
+                ),
+            ));
+        } else {
+            checks.push(Check::ok("ports", format!("The port {port} is free")));
+        }
+    }
+    Ok(checks)
+}
+
+fn is_in_use(address: &str) -> bool {
+    matches!(TcpListener::bind(address), Err(e) if e.kind() == ErrorKind::AddrInUse)
+}
+
+/// The addresses of Ockam Orchestrator and of the projects must be resolved by the DNS
+fn check_dns(opts: &CommandGlobalOpts) -> miette::Result<Vec<Check>> {
+    let mut targets = vec![(
+        "Ockam Orchestrator".to_string(),
+        NodeManager::controller_multiaddr(),
+    )];
+    for project in opts.state.projects.list()? {
+        if let Ok(route) = project.config().access_route() {
+            targets.push((format!("the project {}", project.name()), route));
+        }
+    }
+    Ok(targets
+        .iter()
+        .filter_map(|(description, route)| check_resolution(description, route))
+        .collect())
+}
+
+fn check_resolution(description: &str, route: &MultiAddr) -> Option<Check> {
+    let host_port = route.to_socket_addr().ok()?;
+    let host = host_port
+        .rsplit_once(':')
+        .map(|(host, _)| host)
+        .unwrap_or(&host_port);
+    Some(match host_port.to_socket_addrs() {
+        Ok(mut addresses) => match addresses.next() {
+            Some(address) => Check::ok(
+                "dns",
+                format!(
+                    "The address {host} of {description} resolves to {}",
+                    address.ip()
+                ),
+            ),
+            None => dns_error(description, host, "no address found"),
+        },
+        Err(e) => dns_error(description, host, &e.to_string()),
+    })
+}
+
+fn dns_error(description: &str, host: &str, error: &str) -> Check {
+    Check::error(
+        "dns",
+        format!("The address {host} of {description} can't be resolved: {error}"),
+        "Check the DNS servers of the system, or create the nodes with --dns-server",
+    )
+}
+
+/// The outgoing TCP connections of the nodes use the proxy of the HTTPS_PROXY or ALL_PROXY
+/// variables, which must be reachable
+fn check_proxy(timeout: Duration) -> Vec<Check> {
+    let proxy = match TcpProxy::from_env() {
+        Ok(proxy) => proxy,
+        Err(e) => {
+            return vec![Check::error(
+                "proxy",
+                format!("The proxy of the HTTPS_PROXY or ALL_PROXY variable is invalid: {e}"),
+                "Use a URL like http://proxy.example.com:3128 or socks5://proxy.example.com:1080",
+            )]
+        }
+    };
+    match proxy {
+        Some(proxy) => {
+            let reachable = proxy
+                .address()
+                .to_socket_addrs()
+                .ok()
+                .into_iter()
+                .flatten()
+                .any(|address: SocketAddr| TcpStream::connect_timeout(&address, timeout).is_ok());
+            if reachable {
+                vec![Check::ok(
+                    "proxy",
+                    format!(
+                        "The outgoing TCP connections use the proxy {}",
+                        proxy.address()
+                    ),
+                )]
+            } else {
+                vec![Check::error(
+                    "proxy",
+                    format!("The proxy {} can't be reached", proxy.address()),
+                    "Fix the HTTPS_PROXY or ALL_PROXY variable, or add the Orchestrator and projects hosts to NO_PROXY",
+                )]
+            }
+        }
+        None if ["HTTP_PROXY", "http_proxy"]
+            .iter()
+            .any(|v| std::env::var(v).is_ok()) =>
+        {
+            vec![Check::warning(
+                "proxy",
+                "HTTP_PROXY is set, but the TCP connections of the nodes only use HTTPS_PROXY or ALL_PROXY",
+                "Set HTTPS_PROXY if the outgoing connections must go through the proxy",
+            )]
+        }
+        None => vec![Check::ok("proxy", "No proxy is configured")],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ntp_timestamps_are_converted_to_unix_time() {
+        let mut timestamp = (NTP_UNIX_OFFSET as u32 + 1_000).to_be_bytes().to_vec();
+        timestamp.extend(0x8000_0000u32.to_be_bytes());
+        assert_eq!(ntp_time(&timestamp), 1_000.5);
+    }
+}
@@ -0,0 +1,101 @@
+use miette::IntoDiagnostic;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::CommandGlobalOpts;
+
+/// Changes that a command makes, printed instead of being executed when `--dry-run` is used.
+/// The steps are listed in the order in which they would be executed
+#[derive(Debug, Serialize)]
+pub struct Plan {
+    command: String,
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Step {
+    /// A change of the local state, stored in the Ockam home directory
+    StateChange {
+        action: String,
+        resource: String,
+        name: String,
+    },
+    /// A node process started on this machine
+    StartNode { name: String, foreground: bool },
+    /// A request sent to the API of a node
+    ApiCall {
+        node: String,
+        method: String,
+        path: String,
+        body: Value,
+    },
+}
+
+impl Step {
+    pub fn state_change(action: &str, resource: &str, name: impl Into<String>) -> Self {
+        Step::StateChange {
+            action: action.to_string(),
+            resource: resource.to_string(),
+            name: name.into(),
+        }
+    }
+
+    pub fn api_call(node: &str, method: &str, path: impl Into<String>, body: Value) -> Self {
+        Step::ApiCall {
+            node: node.to_string(),
+            method: method.to_string(),
+            path: path.into(),
+            body,
+        }
+    }
+}
+
+impl Plan {
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            steps: vec![],
+        }
+    }
+
+    pub fn push(&mut self, step: Step) {
+        self.steps.push(step)
+    }
+
+    pub fn into_steps(self) -> Vec<Step> {
+        self.steps
+    }
+
+    /// Print the plan as JSON, unless another structured output is requested
+    pub fn print(&self, opts: &CommandGlobalOpts) -> miette::Result<()> {
+        let json = serde_json::to_string_pretty(self).into_diagnostic()?;
+        opts.terminal
+            .stdout()
+            .plain(&json)
+            .json(&json)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_plan_json() {
+        let mut plan = Plan::new("policy create");
+        plan.push(Step::api_call(
+            "n1",
+            "POST",
+            "/policy/r1/handle_message",
+            json!({"expression": "(= subject.role \"admin\")"}),
+        ));
+        let json = serde_json::to_value(&plan).unwrap();
+        assert_eq!(json["command"], "policy create");
+        assert_eq!(json["steps"][0]["kind"], "api_call");
+        assert_eq!(json["steps"][0]["path"], "/policy/r1/handle_message");
+    }
+}
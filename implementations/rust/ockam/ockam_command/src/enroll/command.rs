@@ -2,7 +2,7 @@ use std::process;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use clap::Args;
+use clap::{Args, Subcommand};
 use colorful::Colorful;
 use miette::{IntoDiagnostic, WrapErr};
 use tokio::sync::Mutex;
@@ -22,10 +22,9 @@ use ockam_api::enroll::enrollment::{EnrollStatus, Enrollment};
 use ockam_api::enroll::oidc_service::OidcService;
 use ockam_api::nodes::InMemoryNode;
 
-use crate::enroll::OidcServiceExt;
+use crate::enroll::{EnrollStatusCommand, OidcServiceExt};
 use crate::identity::initialize_identity_if_default;
 use crate::operation::util::check_for_completion;
-use crate::output::OutputFormat;
 use crate::project::util::check_project_readiness;
 use crate::terminal::OckamColor;
 use crate::util::node_rpc;
@@ -48,19 +47,30 @@ pub struct EnrollCommand {
     /// Use PKCE authorization flow
     #[arg(long)]
     pub authorization_code_flow: bool,
+
+    #[command(subcommand)]
+    pub subcommand: Option<EnrollSubcommand>,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum EnrollSubcommand {
+    Status(EnrollStatusCommand),
 }
 
 impl EnrollCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
+        if let Some(EnrollSubcommand::Status(c)) = self.subcommand {
+            return c.run(opts, self.identity);
+        }
         initialize_identity_if_default(&opts, &self.identity);
         node_rpc(rpc, (opts, self));
     }
 }
 
 async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, EnrollCommand)) -> miette::Result<()> {
-    if opts.global_args.output_format == OutputFormat::Json {
+    if opts.global_args.output_format.is_structured() {
         return Err(miette::miette!(
-            "The flag --output json is invalid for this command."
+            "Only the plain output is available for this command."
         ));
     }
 
@@ -112,6 +122,11 @@ async fn run_impl(
         oidc_service.get_token_interactively(&opts).await?
     };
 
+    opts.terminal.progress_event(
+        "verify-email",
+        "Waiting for the verification of your email address",
+        Some(25),
+    )?;
     let user_info = oidc_service
         .wait_for_email_verification(&token, Some(&opts.terminal))
         .await?;
@@ -122,11 +137,23 @@ async fn run_impl(
     let node = InMemoryNode::start(ctx, &opts.state).await?;
     let controller = node.create_controller().await?;
 
+    opts.terminal.progress_event(
+        "enroll",
+        "Enrolling your identity with Ockam Orchestrator",
+        Some(50),
+    )?;
     enroll_with_node(&controller, ctx, token)
         .await
         .wrap_err("Failed to enroll your local identity with Ockam Orchestrator")?;
 
+    opts.terminal.progress_event(
+        "default-project",
+        "Retrieving your default space and project",
+        Some(75),
+    )?;
     let identifier = retrieve_user_project(&opts, ctx, &node).await?;
+    opts.terminal
+        .progress_event("done", format!("Enrolled {identifier}"), Some(100))?;
 
     opts.terminal.write_line(&fmt_ok!(
         "Enrolled {} as one of the Ockam identities of your Orchestrator account {}.",
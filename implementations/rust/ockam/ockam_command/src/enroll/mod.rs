@@ -1,5 +1,7 @@
 pub use command::*;
 pub use oidc_service::*;
+pub use status::*;
 
 mod command;
 mod oidc_service;
+mod status;
@@ -64,8 +64,21 @@ impl OidcServiceExt for OidcService {
                 .plain(dc.user_code.to_string())
                 .write_line()?;
         }
+        // With JSON progress events, the program reading them displays the instructions
+        else if opts.terminal.is_json_progress() {
+            opts.terminal.progress_event(
+                "authenticate",
+                format!(
+                    "Enter the one-time code {} at {}",
+                    dc.user_code, dc.verification_uri
+                ),
+                Some(0),
+            )?;
+        }
         // Otherwise, write the instructions at stderr as normal
         else {
+            opts.terminal
+                .fail_if_non_interactive("Press ENTER to open the browser")?;
             clipboard = Clipboard::new();
             let otc_string = clipboard
                 .as_mut()
@@ -0,0 +1,276 @@
+use std::fmt::Write;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam::identity::models::CredentialData;
+use ockam::identity::TimestampInSeconds;
+use ockam::Context;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::cloud::project::Project;
+use ockam_api::enroll::enrollment::Enrollment;
+use ockam_api::nodes::InMemoryNode;
+
+use crate::output::human_readable_time;
+use crate::terminal::OckamColor;
+use crate::util::node_rpc;
+use crate::{docs, fmt_err, fmt_info, fmt_log, fmt_ok, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/status/after_long_help.txt");
+
+/// Show which steps of the enrollment are complete
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct EnrollStatusCommand {}
+
+impl EnrollStatusCommand {
+    pub fn run(self, opts: CommandGlobalOpts, identity: Option<String>) {
+        node_rpc(rpc, (opts, identity));
+    }
+}
+
+async fn rpc(
+    ctx: Context,
+    (opts, identity): (CommandGlobalOpts, Option<String>),
+) -> miette::Result<()> {
+    let status = get_enrollment_status(&ctx, &opts, identity).await?;
+
+    let mut plain = String::new();
+    for step in &status.steps {
+        let line = match (&step.status, &step.detail) {
+            (StepStatus::Complete, Some(detail)) => fmt_ok!("{}: {detail}\n", step.name),
+            (StepStatus::Complete, None) => fmt_ok!("{}\n", step.name),
+            (StepStatus::Incomplete, _) => fmt_err!("{}\n", step.name),
+            (StepStatus::Skipped, _) => fmt_log!("{}: not checked\n", step.name),
+        };
+        plain.push_str(&line);
+        if step.status == StepStatus::Incomplete {
+            if let Some(detail) = &step.detail {
+                plain.push_str(&fmt_log!("{detail}\n"));
+            }
+            if let Some(hint) = &step.hint {
+                plain.push_str(&fmt_info!("{hint}\n"));
+            }
+        }
+    }
+    if status.complete {
+        write!(plain, "\n{}", fmt_ok!("The enrollment is complete")).into_diagnostic()?;
+    } else {
+        write!(
+            plain,
+            "\n{}",
+            fmt_err!(
+                "The enrollment is incomplete. Run {} to resume it",
+                "ockam enroll".color(OckamColor::PrimaryResource.color())
+            )
+        )
+        .into_diagnostic()?;
+    }
+
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .machine(if status.complete {
+            "complete"
+        } else {
+            "incomplete"
+        })
+        .json(serde_json::to_string_pretty(&status).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+/// Check each step of the enrollment, in the order in which `ockam enroll` performs them.
+///
+/// The local state is checked first. The credential is then requested from the authority of
+/// the default project, so that its expiration can be reported
+async fn get_enrollment_status(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    identity: Option<String>,
+) -> miette::Result<EnrollmentStatus> {
+    let mut steps = vec![];
+
+    let identity_state = opts
+        .state
+        .identities
+        .get_or_default(identity.as_deref())
+        .ok();
+    steps.push(match &identity_state {
+        Some(state) => EnrollmentStep::complete(
+            "Identity created",
+            format!("{} ({})", state.name(), state.identifier()),
+        ),
+        None => EnrollmentStep::incomplete(
+            "Identity created",
+            None,
+            match &identity {
+                Some(name) => format!("Run `ockam identity create {name}`"),
+                None => "Run `ockam enroll` to create a default identity".to_string(),
+            },
+        ),
+    });
+
+    steps.push(match opts.state.users_info.default() {
+        Ok(user_info) => EnrollmentStep::complete(
+            "Orchestrator account linked",
+            user_info.config().email.clone(),
+        ),
+        Err(_) => EnrollmentStep::incomplete(
+            "Orchestrator account linked",
+            None,
+            "Run `ockam enroll` to authenticate with your Ockam Orchestrator account",
+        ),
+    });
+
+    steps.push(match opts.state.spaces.default() {
+        Ok(space) => EnrollmentStep::complete("Space available", space.name().to_string()),
+        Err(_) => EnrollmentStep::incomplete(
+            "Space available",
+            None,
+            "Run `ockam enroll` to create a space, or `ockam space list` to check your spaces",
+        ),
+    });
+
+    let project = opts
+        .state
+        .projects
+        .default()
+        .ok()
+        .map(|p| p.config().clone());
+    steps.push(match &project {
+        Some(project) if project.is_ready() => {
+            EnrollmentStep::complete("Project available", project.name.clone())
+        }
+        Some(project) => EnrollmentStep::incomplete(
+            "Project available",
+            format!("The project {} is not ready yet", project.name),
+            "Wait a few minutes for the project to be created, then run `ockam enroll` again",
+        ),
+        None => EnrollmentStep::incomplete(
+            "Project available",
+            None,
+            "Run `ockam enroll` to create a project, or `ockam project list` to check your projects",
+        ),
+    });
+
+    steps.push(match &identity_state {
+        Some(state) if state.is_enrolled() => EnrollmentStep::complete("Identity enrolled", None),
+        Some(_) => EnrollmentStep::incomplete(
+            "Identity enrolled",
+            None,
+            "Run `ockam enroll` to finish the enrollment of the identity",
+        ),
+        None => EnrollmentStep::skipped("Identity enrolled"),
+    });
+
+    steps.push(match (&identity_state, &project) {
+        (Some(_), Some(project)) if project.is_ready() => {
+            match get_credential_expiration(ctx, opts, identity, project).await {
+                Ok(expires_at) => EnrollmentStep {
+                    expires_at: Some(*expires_at),
+                    ..EnrollmentStep::complete(
+                        "Credential valid",
+                        format!("until {}", human_readable_time(expires_at)),
+                    )
+                },
+                Err(e) => EnrollmentStep::incomplete(
+                    "Credential valid",
+                    format!("The project authority did not issue a credential: {e}"),
+                    "Run `ockam enroll`, or `ockam project enroll` with an enrollment ticket",
+                ),
+            }
+        }
+        _ => EnrollmentStep::skipped("Credential valid"),
+    });
+
+    let complete = steps.iter().all(|s| s.status == StepStatus::Complete);
+    Ok(EnrollmentStatus { complete, steps })
+}
+
+/// Request a credential from the authority of a project, and return its expiration time,
+/// in seconds since the Unix epoch
+async fn get_credential_expiration(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    identity: Option<String>,
+    project: &Project,
+) -> miette::Result<TimestampInSeconds> {
+    let authority = project
+        .authority()
+        .await
+        .into_diagnostic()?
+        .ok_or_else(|| miette::miette!("the project has no authority"))?;
+    let node = InMemoryNode::start_with_trust_context(ctx, &opts.state, None, None).await?;
+    let credential = node
+        .create_authority_client(authority.identity_id(), authority.address(), identity)
+        .await?
+        .issue_credential(ctx)
+        .await?;
+    let versioned_data = credential
+        .credential
+        .get_versioned_data()
+        .into_diagnostic()?;
+    let data = CredentialData::get_data(&versioned_data).into_diagnostic()?;
+    Ok(data.expires_at)
+}
+
+#[derive(Serialize)]
+struct EnrollmentStatus {
+    complete: bool,
+    steps: Vec<EnrollmentStep>,
+}
+
+#[derive(Serialize)]
+struct EnrollmentStep {
+    name: String,
+    status: StepStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    /// How to complete the step
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+    /// Expiration time of the credential, in seconds since the Unix epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_at: Option<u64>,
+}
+
+impl EnrollmentStep {
+    fn complete(name: &str, detail: impl Into<Option<String>>) -> Self {
+        Self::new(name, StepStatus::Complete, detail.into(), None)
+    }
+
+    fn incomplete(name: &str, detail: impl Into<Option<String>>, hint: impl Into<String>) -> Self {
+        Self::new(
+            name,
+            StepStatus::Incomplete,
+            detail.into(),
+            Some(hint.into()),
+        )
+    }
+
+    /// The step can't be checked because a previous step is incomplete
+    fn skipped(name: &str) -> Self {
+        Self::new(name, StepStatus::Skipped, None, None)
+    }
+
+    fn new(name: &str, status: StepStatus, detail: Option<String>, hint: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail,
+            hint,
+            expires_at: None,
+        }
+    }
+}
+
+#[derive(Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum StepStatus {
+    Complete,
+    Incomplete,
+    Skipped,
+}
@@ -1,6 +1,7 @@
 use colorful::Colorful;
 use miette::miette;
 use miette::Diagnostic;
+use ockam_core::api::{ApiErrorCode, ApiFailure};
 use std::fmt::Debug;
 
 use crate::{exitcode, fmt_log, ExitCode, Version};
@@ -42,6 +43,16 @@ pub enum Error {
         resource: String,
         resource_name: String,
     },
+    // Prompt in the non-interactive mode
+    #[diagnostic(
+        code(OCK428),
+        help("Pass the missing value with an argument of the command, or use --yes to confirm"),
+        url("https://docs.ockam.io/errors/OCK428")
+    )]
+    #[error(
+        "The command needs an answer to '{prompt}', which can't be asked with --non-interactive"
+    )]
+    NonInteractive { prompt: String },
     // ==== End 4xx Errors =====
 
     // ==== 5xx Errors ====
@@ -69,6 +80,19 @@ pub enum Error {
         resource_name: String,
     },
     // ==== End 5xx Errors ====
+
+    // A request to a node failed with a known error code
+    #[diagnostic(
+        code(OCK400),
+        help("{help}"),
+        url("https://docs.ockam.io/errors/OCK400")
+    )]
+    #[error("{message} ({code})")]
+    Api {
+        code: ApiErrorCode,
+        message: String,
+        help: String,
+    },
 }
 
 impl Error {
@@ -90,8 +114,83 @@ impl Error {
             Error::NotFound { .. } => exitcode::SOFTWARE,
             Error::Unauthorized { .. } => exitcode::NOPERM,
             Error::Conflict { .. } => exitcode::SOFTWARE,
+            Error::NonInteractive { .. } => exitcode::NOINPUT,
             Error::InternalError { exit_code, .. } => *exit_code,
             Error::Unavailable { .. } => exitcode::UNAVAILABLE,
+            Error::Api { code, .. } => match code {
+                ApiErrorCode::PortalDestinationUnreachable => exitcode::UNAVAILABLE,
+                ApiErrorCode::CredentialExpired => exitcode::NOPERM,
+                ApiErrorCode::IdentityNotAuthorized => exitcode::NOUSER,
+                ApiErrorCode::Timeout => exitcode::TEMPFAIL,
+                ApiErrorCode::NotFound => exitcode::NOINPUT,
+                ApiErrorCode::AlreadyExists => exitcode::CANTCREAT,
+                ApiErrorCode::InvalidRequest => exitcode::DATAERR,
+                _ => exitcode::SOFTWARE,
+            },
+        }
+    }
+
+    /// Return the error to display when a command fails with the given report.
+    /// The failed requests with a known error code are displayed with an actionable message
+    pub fn from_report(report: miette::ErrReport) -> miette::ErrReport {
+        if report.downcast_ref::<Error>().is_some() {
+            return report;
+        }
+        let failure = report
+            .chain()
+            .find_map(|e| e.downcast_ref::<ApiFailure>())
+            .cloned();
+        match failure {
+            Some(failure) => Error::from(failure).into(),
+            None => report,
+        }
+    }
+
+    /// Return the exit code of a command which failed with the given report
+    pub fn exit_code(report: &miette::ErrReport) -> ExitCode {
+        report
+            .downcast_ref::<Error>()
+            .map(|e| e.code())
+            .unwrap_or(exitcode::SOFTWARE)
+    }
+}
+
+impl From<ApiFailure> for Error {
+    fn from(failure: ApiFailure) -> Self {
+        let help = match failure.code() {
+            ApiErrorCode::PortalDestinationUnreachable => {
+                "Check that the node of the outlet is running and that the outlet address is correct, or wait longer for the outlet with --connection-wait"
+            }
+            ApiErrorCode::CredentialExpired => {
+                "The credential has expired. Run `ockam project enroll` to get a new credential and try again"
+            }
+            ApiErrorCode::IdentityNotAuthorized => {
+                "Check that the identity is authorized by the other node, with --authorized or a project credential with the required attributes"
+            }
+            ApiErrorCode::Timeout => {
+                "Check that the node is running and try again, with a longer --timeout if needed"
+            }
+            ApiErrorCode::NotFound => "Please check the spelling and try again",
+            ApiErrorCode::AlreadyExists => {
+                "Use another name or address, or delete the existing one before trying again"
+            }
+            ApiErrorCode::InvalidRequest => "Please check the arguments of the command and try again",
+            _ => "Please report this issue, with a copy of your logs, to https://github.com/build-trust/ockam/issues",
+        };
+        Error::Api {
+            code: failure.code(),
+            message: failure.message().to_string(),
+            help: help.to_string(),
+        }
+    }
+}
+
+impl From<ockam::Error> for Error {
+    fn from(e: ockam::Error) -> Self {
+        let failure = std::error::Error::source(&e).and_then(|s| s.downcast_ref::<ApiFailure>());
+        match failure {
+            Some(failure) => failure.clone().into(),
+            None => Error::new(exitcode::SOFTWARE, miette!(e.to_string())),
         }
     }
 }
@@ -174,7 +273,6 @@ gen_from_impl!(serde_json::Error, DATAERR);
 gen_from_impl!(serde_yaml::Error, DATAERR);
 gen_from_impl!(minicbor::encode::Error<std::convert::Infallible>, DATAERR);
 gen_from_impl!(minicbor::decode::Error, DATAERR);
-gen_from_impl!(ockam::Error, SOFTWARE);
 gen_from_impl!(ockam_api::cli_state::CliStateError, SOFTWARE);
 gen_from_impl!(ockam_api::error::ApiError, SOFTWARE);
 gen_from_impl!(ockam_multiaddr::Error, SOFTWARE);
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use clap::Args;
+use miette::{miette, IntoDiagnostic};
+use tracing::debug;
+
+use ockam::TcpTransport;
+use ockam_api::cli_state::{EnvironmentSnapshot, TimedEnvironmentEvent};
+use ockam_api::nodes::models::portal::InletList;
+use ockam_api::nodes::BackgroundNode;
+use ockam_node::Context;
+
+use crate::util::duration::duration_parser;
+use crate::util::{api, node_rpc};
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Maximum time to wait for the inlets of a node
+const INLETS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Show the events of the local environment
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct EventsCommand {
+    /// Keep running, and report the new events as they happen
+    #[arg(long, short)]
+    follow: bool,
+
+    /// Time between two checks of the environment
+    #[arg(long, default_value = "1s", value_parser = duration_parser)]
+    interval: Duration,
+}
+
+impl EventsCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, EventsCommand),
+) -> miette::Result<()> {
+    if cmd.interval.is_zero() {
+        return Err(miette!(
+            "The interval between two checks must be greater than 0"
+        ));
+    }
+
+    let tcp = TcpTransport::create(&ctx).await.into_diagnostic()?;
+    let mut nodes: HashMap<String, BackgroundNode> = HashMap::new();
+    let mut previous = EnvironmentSnapshot::default();
+    loop {
+        let mut snapshot = EnvironmentSnapshot::capture(&opts.state)?;
+        for node_name in snapshot.running_nodes() {
+            if !nodes.contains_key(&node_name) {
+                let node = BackgroundNode::new(&tcp, &opts.state, &node_name).await?;
+                nodes.insert(node_name.clone(), node);
+            }
+            let inlets: miette::Result<InletList> = nodes[&node_name]
+                .ask_with_timeout(&ctx, api::list_inlets(), INLETS_TIMEOUT)
+                .await;
+            match inlets {
+                Ok(inlets) => {
+                    for inlet in inlets.list {
+                        snapshot.set_inlet(
+                            &node_name,
+                            &inlet.alias,
+                            &inlet.status,
+                            &inlet.bind_addr,
+                        );
+                    }
+                }
+                Err(e) => {
+                    debug!(node = %node_name, %e, "the inlets of the node could not be retrieved");
+                    snapshot.keep_inlets(&node_name, &previous);
+                }
+            }
+        }
+
+        for event in snapshot.events_since(&previous) {
+            let line =
+                serde_json::to_string(&TimedEnvironmentEvent::now(event)).into_diagnostic()?;
+            opts.terminal
+                .clone()
+                .stdout()
+                .plain(&line)
+                .json(&line)
+                .write_line()?;
+        }
+        previous = snapshot;
+
+        if !cmd.follow {
+            break;
+        }
+        tokio::time::sleep(cmd.interval).await;
+    }
+    Ok(())
+}
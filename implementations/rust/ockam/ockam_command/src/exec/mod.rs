@@ -0,0 +1,126 @@
+use core::time::Duration;
+use std::io::Write;
+use std::sync::Arc;
+
+use clap::Args;
+use miette::{Context as _, IntoDiagnostic};
+
+use ockam::Context;
+use ockam_api::exec::{exec, ExecOutput};
+use ockam_api::nodes::InMemoryNode;
+use ockam_core::AsyncTryClone;
+use ockam_multiaddr::MultiAddr;
+use tracing::error;
+
+use crate::identity::{get_identity_name, initialize_identity_if_default};
+use crate::project::util::{
+    clean_projects_multiaddr, get_projects_secure_channels_from_config_lookup,
+};
+use crate::util::api::{CloudOpts, TrustContextOpts};
+use crate::util::duration::duration_parser;
+use crate::util::{clean_nodes_multiaddr, embedded_node, exitcode};
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Run a command on a remote node
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ExecCommand {
+    /// Route to the exec service of the remote node
+    #[arg(long, value_name = "ROUTE")]
+    at: MultiAddr,
+
+    /// Maximum time to wait for some output from the command
+    #[arg(long, value_name = "TIMEOUT", default_value = "60s", value_parser = duration_parser)]
+    timeout: Duration,
+
+    /// Program to run, followed by its arguments
+    #[arg(last = true, required = true, value_name = "COMMAND")]
+    command: Vec<String>,
+
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+
+    #[command(flatten)]
+    trust_context_opts: TrustContextOpts,
+}
+
+impl ExecCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_identity_if_default(&opts, &self.cloud_opts.identity);
+        // Exit with the exit code of the remote command
+        match embedded_node(run_impl, (opts, self)) {
+            Ok(Some(0)) => {}
+            Ok(exit_code) => std::process::exit(exit_code.unwrap_or(exitcode::SOFTWARE)),
+            Err(e) => {
+                error!(%e, "Failed to run command");
+                eprintln!("{:?}", e);
+                std::process::exit(exitcode::SOFTWARE);
+            }
+        }
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, ExecCommand),
+) -> miette::Result<Option<i32>> {
+    let (to, meta) =
+        clean_nodes_multiaddr(&cmd.at, &opts.state).context("Argument '--at' is invalid")?;
+
+    let identity_name = get_identity_name(&opts.state, &cmd.cloud_opts.identity);
+    let trust_context_config = cmd.trust_context_opts.to_config(&opts.state)?.build();
+    let node_manager = InMemoryNode::start_node(
+        &ctx,
+        &opts.state,
+        None,
+        Some(identity_name.clone()),
+        cmd.trust_context_opts.project_path.as_ref(),
+        trust_context_config,
+    )
+    .await?;
+
+    // Replace `/project/<name>` occurrences with their respective secure channel addresses
+    let projects_sc = get_projects_secure_channels_from_config_lookup(
+        &opts,
+        &ctx,
+        &node_manager,
+        &meta,
+        Some(identity_name),
+        Some(cmd.timeout),
+    )
+    .await?;
+    let to = clean_projects_multiaddr(to, projects_sc)?;
+
+    let connection_ctx = Arc::new(ctx.async_try_clone().await.into_diagnostic()?);
+    let connection = node_manager
+        .make_connection(connection_ctx, &to, None, None, None, Some(cmd.timeout))
+        .await
+        .into_diagnostic()?;
+    let route = connection
+        .route(node_manager.tcp_transport())
+        .await
+        .into_diagnostic()?;
+
+    let exit_code = exec(&ctx, route, cmd.command, cmd.timeout, |output| {
+        // The output of the command is written as is, to preserve its format
+        let _ = match output {
+            ExecOutput::Stdout(bytes) => std::io::stdout()
+                .write_all(&bytes)
+                .and_then(|_| std::io::stdout().flush()),
+            ExecOutput::Stderr(bytes) => std::io::stderr().write_all(&bytes),
+            _ => Ok(()),
+        };
+    })
+    .await
+    .into_diagnostic()?;
+    Ok(exit_code)
+}
@@ -0,0 +1,107 @@
+//! Some commands can be left out of a build with cargo features, for example to produce a smaller
+//! binary for an embedded gateway with `--no-default-features`:
+//!
+//!  - "orchestrator": the commands managing the spaces, projects and subscriptions of Ockam
+//!    Orchestrator, and the enrollment,
+//!  - "kafka": the commands creating Kafka inlets, outlets and portals,
+//!  - "udp": the commands managing the UDP listeners and hole punchers,
+//!  - "vault-encryption": the commands exporting, importing, locking and unlocking the vaults,
+//!  - "tui": the interactive lists used to select the items of a command.
+//!
+//! The "quic", "websocket", "pkcs11", "ssh-agent" and "keychain" features don't add commands,
+//! the nodes and vaults using them fail with an error in a build without them.
+//!
+//! A command which is not compiled in is reported as such, instead of being an unknown command.
+
+/// Commands added by the "orchestrator" feature
+const ORCHESTRATOR_COMMANDS: &[&str] = &[
+    "enroll",
+    "space",
+    "project",
+    "sidecar",
+    "admin",
+    "share",
+    "subscription",
+    "lease",
+];
+
+/// Commands added by the "kafka" feature
+const KAFKA_COMMANDS: &[&str] = &[
+    "kafka-outlet",
+    "kafka-consumer",
+    "kafka-direct",
+    "kafka-producer",
+];
+
+/// Commands added by the "udp" feature
+const UDP_COMMANDS: &[&str] = &["udp"];
+
+/// Global options followed by a value, which must be skipped to find the command
+const OPTIONS_WITH_VALUE: &[&str] = &["--output"];
+
+/// Command which is not compiled in this build
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DisabledCommand {
+    pub(crate) name: String,
+    pub(crate) feature: &'static str,
+}
+
+/// Return the command of the arguments if it was left out of this build
+pub(crate) fn disabled_command(args: &[String]) -> Option<DisabledCommand> {
+    let name = command_name(args)?;
+    disabled_commands()
+        .into_iter()
+        .find(|(command, _)| *command == name)
+        .map(|(_, feature)| DisabledCommand {
+            name: name.to_string(),
+            feature,
+        })
+}
+
+/// Return the commands which are not compiled in, with the feature adding them
+fn disabled_commands() -> Vec<(&'static str, &'static str)> {
+    let mut commands = vec![];
+    if !cfg!(feature = "orchestrator") {
+        commands.extend(ORCHESTRATOR_COMMANDS.iter().map(|c| (*c, "orchestrator")));
+    }
+    if !cfg!(feature = "kafka") {
+        commands.extend(KAFKA_COMMANDS.iter().map(|c| (*c, "kafka")));
+    }
+    if !cfg!(feature = "udp") {
+        commands.extend(UDP_COMMANDS.iter().map(|c| (*c, "udp")));
+    }
+    commands
+}
+
+/// Return the first argument which is not an option, after the name of the binary
+fn command_name(args: &[String]) -> Option<&str> {
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        if OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            return Some(arg);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_names() {
+        let args = |s: &str| s.split(' ').map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            command_name(&args("ockam -vv node create n1")),
+            Some("node")
+        );
+        assert_eq!(
+            command_name(&args("ockam --output json project list")),
+            Some("project")
+        );
+        assert_eq!(command_name(&args("ockam --help")), None);
+        assert_eq!(disabled_command(&args("ockam node list")), None);
+    }
+}
@@ -1,17 +1,22 @@
 use crate::terminal::OckamColor;
+use crate::util::duration::duration_parser;
 use crate::util::node_rpc;
 use crate::{docs, fmt_log, fmt_ok, CommandGlobalOpts};
 use clap::Args;
 use colorful::Colorful;
-use miette::miette;
+use miette::{miette, IntoDiagnostic};
 use ockam::identity::Identifier;
 use ockam::Context;
-use ockam_api::cli_state::random_name;
 use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::cli_state::{random_name, IdentityConfig};
 use ockam_vault::{HandleToSecret, SigningSecretKeyHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
 use tokio::try_join;
 
+/// Default lifetime of an ephemeral identity
+const DEFAULT_EPHEMERAL_TTL: Duration = Duration::from_secs(60 * 60);
+
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
 
@@ -32,6 +37,14 @@ pub struct CreateCommand {
     /// Key ID to use for the identity creation
     #[arg(short, long)]
     key_id: Option<String>,
+
+    /// Create a short-lived identity, which is deleted with its key once it has expired
+    #[arg(long, conflicts_with = "key_id")]
+    ephemeral: bool,
+
+    /// Lifetime of an ephemeral identity
+    #[arg(long, value_name = "DURATION", requires = "ephemeral", default_value = "1h", value_parser = duration_parser)]
+    ttl: Duration,
 }
 
 impl CreateCommand {
@@ -40,6 +53,8 @@ impl CreateCommand {
             name,
             vault,
             key_id,
+            ephemeral: false,
+            ttl: DEFAULT_EPHEMERAL_TTL,
         }
     }
 
@@ -89,15 +104,31 @@ impl CreateCommand {
 
             // Create an identity using the KMS key, if provided.
             let identity = match &self.key_id {
+                Some(key_id) if vault_state.is_ssh_agent() => {
+                    // The keys of an ssh-agent are designated by their comment,
+                    // their fingerprint or their OpenSSH public key
+                    let handle = vault_state.ssh_agent_key_handle(key_id).await?;
+                    Ok(identities_creation
+                        .identity_builder()
+                        .with_existing_key(handle)
+                        .build()
+                        .await?)
+                }
                 Some(key_id) => {
-                    if !vault_state.config().is_aws() {
+                    if !vault_state.config().is_aws() && !vault_state.config().is_pkcs11() {
                         Err(miette!(
-                            "Vault {} is not an AWS KMS vault",
+                            "Vault {} is not an AWS KMS, a PKCS#11 or an ssh-agent vault",
                             self.vault.clone().unwrap_or("default".to_string()),
                         ))
                     } else {
+                        // The keys of a PKCS#11 token are identified by their hex encoded CKA_ID
+                        let key_id = if vault_state.config().is_pkcs11() {
+                            hex::decode(key_id).into_diagnostic()?
+                        } else {
+                            key_id.as_bytes().to_vec()
+                        };
                         let handle = SigningSecretKeyHandle::ECDSASHA256CurveP256(
-                            HandleToSecret::new(key_id.as_bytes().to_vec()),
+                            HandleToSecret::new(key_id),
                         );
 
                         Ok(identities_creation
@@ -107,12 +138,29 @@ impl CreateCommand {
                             .await?)
                     }
                 }
+                None if self.ephemeral => Ok(identities_creation
+                    .identity_builder()
+                    .with_ttl(self.ttl.as_secs())
+                    .build()
+                    .await?),
                 None => Ok(identities_creation.create_identity().await?),
             }?;
 
-            opts.state
-                .create_identity_state(identity.identifier(), Some(&self.name))
-                .await?;
+            if self.ephemeral {
+                let expires_at = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .into_diagnostic()?
+                    .as_secs()
+                    + self.ttl.as_secs();
+                let config = IdentityConfig::new(identity.identifier())
+                    .await
+                    .set_ephemeral(vault_state.name(), expires_at);
+                opts.state.identities.create(&self.name, config)?;
+            } else {
+                opts.state
+                    .create_identity_state(identity.identifier(), Some(&self.name))
+                    .await?;
+            }
 
             let identifier = identity.identifier().clone();
 
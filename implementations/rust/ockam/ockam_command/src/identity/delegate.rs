@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam::Context;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+
+use crate::credential::identities;
+use crate::identity::get_identity_name;
+use crate::terminal::OckamColor;
+use crate::util::duration::duration_parser;
+use crate::util::node_rpc;
+use crate::vault::default_vault_name;
+use crate::{docs, fmt_log, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/delegate/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/delegate/after_long_help.txt");
+
+/// Delegate an identity to a device
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct DelegateCommand {
+    /// Name of the identity to delegate. The default identity is used if no name is given
+    name: Option<String>,
+
+    /// Name of the device receiving the delegation
+    #[arg(long, value_name = "DEVICE_NAME")]
+    to_device: String,
+
+    /// Duration of the delegation, for example 12h or 30d
+    #[arg(long, value_name = "DURATION", default_value = "30d", value_parser = duration_parser)]
+    expires_in: Duration,
+
+    /// Vault storing the keys of the delegating identity. The key of the device identity is created in the same vault
+    #[arg(long, value_name = "VAULT_NAME")]
+    vault: Option<String>,
+
+    /// Name of the created device identity. The device name is used if no name is given
+    #[arg(long, value_name = "IDENTITY_NAME")]
+    device_identity: Option<String>,
+}
+
+impl DelegateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+async fn run_impl(
+    _ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, DelegateCommand),
+) -> miette::Result<()> {
+    let identity_name = get_identity_name(&opts.state, &cmd.name);
+    let delegator = opts.state.identities.get(&identity_name)?.identifier();
+
+    let device_identity_name = cmd
+        .device_identity
+        .clone()
+        .unwrap_or_else(|| cmd.to_device.clone());
+    if opts.state.identities.exists(&device_identity_name) {
+        return Err(miette!(
+            "An identity named {device_identity_name} already exists, use --device-identity to choose another name"
+        ));
+    }
+
+    let vault_name = cmd
+        .vault
+        .clone()
+        .unwrap_or_else(|| default_vault_name(&opts.state));
+    let identities = identities(&vault_name, &opts).await?;
+    let device_identity = identities
+        .identities_delegation()
+        .delegate_to_device(
+            &delegator,
+            &cmd.to_device,
+            TimestampInSeconds(cmd.expires_in.as_secs()),
+        )
+        .await
+        .into_diagnostic()?;
+    opts.state
+        .create_identity_state(device_identity.identifier(), Some(&device_identity_name))
+        .await?;
+
+    let output = DelegateOutput {
+        delegator,
+        device: cmd.to_device.clone(),
+        identifier: device_identity.identifier().clone(),
+        identity_name: device_identity_name,
+        expires_at: device_identity
+            .get_latest_change()
+            .into_diagnostic()?
+            .data()
+            .expires_at
+            .0,
+    };
+    let plain = fmt_ok!(
+        "Identity {} delegated to the device {}\n",
+        output
+            .delegator
+            .to_string()
+            .color(OckamColor::PrimaryResource.color()),
+        output
+            .device
+            .clone()
+            .color(OckamColor::PrimaryResource.color())
+    ) + &fmt_log!(
+        "The device identity {} was created as {}",
+        output
+            .identifier
+            .to_string()
+            .color(OckamColor::PrimaryResource.color()),
+        output
+            .identity_name
+            .clone()
+            .color(OckamColor::PrimaryResource.color())
+    );
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .machine(output.identifier.to_string())
+        .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct DelegateOutput {
+    delegator: Identifier,
+    device: String,
+    identifier: Identifier,
+    identity_name: String,
+    expires_at: u64,
+}
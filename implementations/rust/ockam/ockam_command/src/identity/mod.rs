@@ -1,13 +1,19 @@
 mod create;
 mod default;
+mod delegate;
 mod delete;
 mod list;
+mod rotate;
 mod show;
+mod verify;
 
 pub use create::CreateCommand;
+pub(crate) use delegate::DelegateCommand;
 pub(crate) use delete::DeleteCommand;
 pub(crate) use list::ListCommand;
+pub(crate) use rotate::RotateCommand;
 pub(crate) use show::ShowCommand;
+pub(crate) use verify::VerifyCommand;
 
 use crate::identity::default::DefaultCommand;
 use crate::{docs, CommandGlobalOpts};
@@ -36,6 +42,9 @@ pub enum IdentitySubcommand {
     List(ListCommand),
     Default(DefaultCommand),
     Delete(DeleteCommand),
+    Rotate(RotateCommand),
+    Delegate(DelegateCommand),
+    Verify(VerifyCommand),
 }
 
 impl IdentityCommand {
@@ -46,6 +55,9 @@ impl IdentityCommand {
             IdentitySubcommand::List(c) => c.run(options),
             IdentitySubcommand::Delete(c) => c.run(options),
             IdentitySubcommand::Default(c) => c.run(options),
+            IdentitySubcommand::Rotate(c) => c.run(options),
+            IdentitySubcommand::Delegate(c) => c.run(options),
+            IdentitySubcommand::Verify(c) => c.run(options),
         }
     }
 }
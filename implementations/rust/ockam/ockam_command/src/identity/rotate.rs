@@ -0,0 +1,195 @@
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::enroll::enrollment::Enrollment;
+use ockam_api::nodes::models::base::NodeReloaded;
+use ockam_api::nodes::{BackgroundNode, Credentials, InMemoryNode};
+
+use crate::credential::identities;
+use crate::identity::get_identity_name;
+use crate::terminal::OckamColor;
+use crate::util::{api, node_rpc};
+use crate::vault::default_vault_name;
+use crate::{docs, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/rotate/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/rotate/after_long_help.txt");
+
+/// Rotate the key of an identity
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RotateCommand {
+    /// Name of the identity to rotate. The default identity is used if no name is given
+    name: Option<String>,
+
+    /// Vault storing the keys of the identity
+    #[arg(long, value_name = "VAULT_NAME")]
+    vault: Option<String>,
+
+    /// Only rotate the key locally, without updating the running nodes and the projects
+    #[arg(long)]
+    local_only: bool,
+}
+
+impl RotateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, RotateCommand),
+) -> miette::Result<()> {
+    let identity_name = get_identity_name(&opts.state, &cmd.name);
+    let identity_state = opts.state.identities.get(&identity_name)?;
+    let identifier = identity_state.identifier();
+
+    let vault_name = cmd
+        .vault
+        .clone()
+        .unwrap_or_else(|| default_vault_name(&opts.state));
+    let identities = identities(&vault_name, &opts).await?;
+    identities
+        .identities_creation()
+        .rotate_identity(&identifier)
+        .await
+        .into_diagnostic()?;
+    let identity = identities
+        .get_identity(&identifier)
+        .await
+        .into_diagnostic()?;
+
+    let mut output = RotateOutput {
+        identifier: identifier.clone(),
+        changes: identity.changes().len(),
+        nodes: vec![],
+        projects: vec![],
+    };
+    if !cmd.local_only {
+        output.nodes = update_running_nodes(&ctx, &opts, &identifier).await?;
+        if identity_state.is_enrolled() {
+            output.projects = update_projects(&ctx, &opts, &identity_name).await?;
+        }
+    }
+
+    let mut plain = fmt_ok!(
+        "Identity {} rotated, its change history now has {} changes\n",
+        identifier
+            .to_string()
+            .color(OckamColor::PrimaryResource.color()),
+        output.changes
+    );
+    for node_name in &output.nodes {
+        plain.push_str(&fmt_log!(
+            "Node {} reloaded with a fresh credential\n",
+            node_name.color(OckamColor::PrimaryResource.color())
+        ));
+    }
+    for project_name in &output.projects {
+        plain.push_str(&fmt_log!(
+            "Project {} updated with the new change history\n",
+            project_name.color(OckamColor::PrimaryResource.color())
+        ));
+    }
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .machine(identifier.to_string())
+        .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+/// Reload the running nodes using the rotated identity, so that they drop the credential
+/// retrieved with the previous key, and get a fresh credential from their authority.
+///
+/// Return the names of the nodes which were updated.
+async fn update_running_nodes(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    identifier: &Identifier,
+) -> miette::Result<Vec<String>> {
+    let mut updated = vec![];
+    for node_state in opts.state.nodes.list()? {
+        if !node_state.is_running()
+            || node_state.config().identifier().ok().as_ref() != Some(identifier)
+        {
+            continue;
+        }
+        let node_name = node_state.name().to_string();
+        let node = BackgroundNode::create(ctx, &opts.state, &node_name).await?;
+        let result = async {
+            let _: NodeReloaded = node.ask(ctx, api::reload_node()).await?;
+            node.get_credential(ctx, true, None).await
+        }
+        .await;
+        match result {
+            Ok(_) => updated.push(node_name),
+            Err(e) => opts.terminal.write_line(&fmt_warn!(
+                "The node {node_name} could not retrieve a fresh credential: {e}"
+            ))?,
+        }
+    }
+    Ok(updated)
+}
+
+/// Send the new change history of the identity to the authority of each known project, by
+/// requesting a fresh credential over a new secure channel.
+///
+/// Return the names of the projects which were updated.
+async fn update_projects(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    identity_name: &str,
+) -> miette::Result<Vec<String>> {
+    let projects = opts.state.projects.list()?;
+    if projects.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let node = InMemoryNode::start_with_trust_context(ctx, &opts.state, None, None).await?;
+    let mut updated = vec![];
+    for project_state in projects {
+        let project = project_state.config();
+        let authority = match project.authority().await.into_diagnostic()? {
+            Some(authority) => authority,
+            None => continue,
+        };
+        let result = async {
+            node.create_authority_client(
+                authority.identity_id(),
+                authority.address(),
+                Some(identity_name.to_string()),
+            )
+            .await?
+            .issue_credential(ctx)
+            .await
+        }
+        .await;
+        match result {
+            Ok(_) => updated.push(project.name.clone()),
+            Err(e) => opts.terminal.write_line(&fmt_warn!(
+                "The project {} could not be updated: {e}",
+                project.name
+            ))?,
+        }
+    }
+    Ok(updated)
+}
+
+#[derive(Serialize)]
+struct RotateOutput {
+    identifier: Identifier,
+    changes: usize,
+    nodes: Vec<String>,
+    projects: Vec<String>,
+}
@@ -33,6 +33,11 @@ pub struct ShowCommand {
     #[arg(short, long)]
     full: bool,
 
+    /// Show when the identity was last used, and by which node, to establish a secure channel
+    /// or to present a credential
+    #[arg(long, conflicts_with = "full")]
+    usage: bool,
+
     //TODO: see if it make sense to have a --encoding argument shared across commands.
     //      note the only reason this is here right now is that project.json expect the
     //      authority' identity change history to be in hex format.  This only applies
@@ -54,7 +59,7 @@ impl ShowCommand {
 
         if cmd.name.is_some() || !opts.terminal.can_ask_for_user_input() {
             let name = get_identity_name(&opts.state, &cmd.name);
-            Self::show_single_identity(&opts, &name, cmd.full, cmd.encoding).await?;
+            Self::show_single_identity(&opts, &name, cmd.full, cmd.usage, cmd.encoding).await?;
             return Ok(());
         }
 
@@ -67,13 +72,14 @@ impl ShowCommand {
                     .write_line()?;
             }
             1 => {
-                Self::show_single_identity(&opts, &id_names[0], cmd.full, cmd.encoding).await?;
+                Self::show_single_identity(&opts, &id_names[0], cmd.full, cmd.usage, cmd.encoding)
+                    .await?;
             }
             _ => {
                 let selected_names = opts.terminal.select_multiple(
                     "Select one or more identities that you want to show".to_string(),
                     id_names,
-                );
+                )?;
 
                 if selected_names.is_empty() {
                     opts.terminal
@@ -86,7 +92,7 @@ impl ShowCommand {
                 if opts.terminal.confirm_interactively(format!(
                     "Would you like to show these items : {:?}?",
                     selected_names
-                )) {
+                ))? {
                     Self::show_identity_list(&opts, selected_names).await?;
                 }
             }
@@ -99,6 +105,7 @@ impl ShowCommand {
         opts: &CommandGlobalOpts,
         name: &str,
         full: bool,
+        usage: bool,
         encoding: Option<EncodeFormat>,
     ) -> miette::Result<()> {
         let state = opts.state.identities.get(name)?;
@@ -129,6 +136,15 @@ impl ShowCommand {
 
                 (identity.to_string(), to_string_pretty(&identity))
             }
+        } else if usage {
+            let usage = state.usage()?;
+            let identifier_display = IdentifierDisplay(identifier);
+            (
+                format!("Identifier: {identifier_display}\n{usage}")
+                    .trim_end()
+                    .to_string(),
+                to_string_pretty(&json!({"identifier": &identifier_display, "usage": &usage})),
+            )
         } else {
             let identifier_display = IdentifierDisplay(identifier);
             (
@@ -0,0 +1,345 @@
+use std::fmt::{Display, Formatter, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+
+use ockam::identity::models::DeviceDelegationData;
+use ockam::identity::verified_change::VerifiedChange;
+use ockam::identity::{Identifier, Identity, IdentityHistoryComparison, Vault};
+use ockam::Context;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+
+use crate::output::{human_readable_time, VerifyingPublicKeyDisplay};
+use crate::util::node_rpc;
+use crate::{docs, fmt_err, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/verify/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/verify/after_long_help.txt");
+
+/// Verify an exported identity change history
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct VerifyCommand {
+    /// Hex encoded change history
+    #[arg(
+        value_name = "CHANGE_HISTORY",
+        required_unless_present = "file",
+        conflicts_with = "file"
+    )]
+    change_history: Option<String>,
+
+    /// File containing the hex encoded or binary change history
+    #[arg(long, value_name = "PATH")]
+    file: Option<PathBuf>,
+
+    /// Identifier which the change history must have
+    #[arg(long, value_name = "IDENTIFIER")]
+    identifier: Option<Identifier>,
+
+    /// Name or identifier of an identity known locally, to compare with the verified change history
+    #[arg(long, value_name = "IDENTITY")]
+    compare: Option<String>,
+}
+
+impl VerifyCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(run_impl, (opts, self))
+    }
+
+    /// Return the binary change history
+    fn change_history(&self) -> miette::Result<Vec<u8>> {
+        match (&self.change_history, &self.file) {
+            (Some(change_history), _) => hex::decode(change_history.trim()).into_diagnostic(),
+            (None, Some(file)) => {
+                let contents = std::fs::read(file).into_diagnostic()?;
+                let decoded = std::str::from_utf8(&contents)
+                    .ok()
+                    .and_then(|s| hex::decode(s.trim()).ok());
+                Ok(decoded.unwrap_or(contents))
+            }
+            (None, None) => Err(miette!("A change history or a file must be provided")),
+        }
+    }
+}
+
+async fn run_impl(
+    _ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, VerifyCommand),
+) -> miette::Result<()> {
+    let identities = opts.state.get_identities(Vault::create()).await?;
+    let identity = identities
+        .verify_change_history(cmd.identifier.as_ref(), &cmd.change_history()?)
+        .await
+        .map_err(|e| miette!("The change history is invalid: {e}"))?;
+
+    let comparison = match &cmd.compare {
+        Some(compare) => {
+            let known_identifier = match Identifier::from_str(compare) {
+                Ok(identifier) => identifier,
+                Err(_) => opts.state.identities.get(compare)?.identifier(),
+            };
+            let comparison = if &known_identifier != identity.identifier() {
+                ComparisonOutput::different(known_identifier)
+            } else {
+                match identities
+                    .compare_with_known_identity(&identity)
+                    .await
+                    .into_diagnostic()?
+                {
+                    Some(comparison) => {
+                        let known_identity = identities
+                            .get_identity(&known_identifier)
+                            .await
+                            .into_diagnostic()?;
+                        ComparisonOutput::new(
+                            known_identifier,
+                            comparison,
+                            &identity,
+                            &known_identity,
+                        )
+                    }
+                    None => ComparisonOutput::unknown(known_identifier),
+                }
+            };
+            Some(comparison)
+        }
+        None => None,
+    };
+
+    let output = VerifyOutput {
+        identifier: identity.identifier().clone(),
+        changes: identity
+            .changes()
+            .iter()
+            .enumerate()
+            .map(|(index, change)| ChangeEvent::new(index, change))
+            .collect(),
+        comparison,
+    };
+    opts.terminal
+        .stdout()
+        .plain(&output)
+        .machine(output.identifier.to_string())
+        .json(serde_json::to_string_pretty(&output).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct VerifyOutput {
+    identifier: Identifier,
+    changes: Vec<ChangeEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comparison: Option<ComparisonOutput>,
+}
+
+impl Display for VerifyOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut plain = fmt_ok!(
+            "The change history of the identity {} is valid, it has {} changes\n",
+            self.identifier,
+            self.changes.len()
+        );
+        for change in &self.changes {
+            writeln!(plain, "  Change[{}]: {}", change.index, change.change_hash)?;
+            writeln!(
+                plain,
+                "    primary_public_key:      {}",
+                change.primary_public_key
+            )?;
+            writeln!(
+                plain,
+                "    created_at:              {}",
+                human_readable_time(change.created_at.into())
+            )?;
+            writeln!(
+                plain,
+                "    expires_at:              {}",
+                human_readable_time(change.expires_at.into())
+            )?;
+            writeln!(
+                plain,
+                "    revoke_all_purpose_keys: {}",
+                change.revoke_all_purpose_keys
+            )?;
+            if let Some(delegation) = &change.delegation {
+                writeln!(
+                    plain,
+                    "    delegation:              device {} delegated by {}",
+                    delegation.device_name, delegation.delegator
+                )?;
+            }
+        }
+        if let Some(comparison) = &self.comparison {
+            let message = match comparison.status {
+                ComparisonStatus::Equal => format!(
+                    "The change history is the same as the known identity {}",
+                    comparison.known_identifier
+                ),
+                ComparisonStatus::Newer => format!(
+                    "The change history is newer than the known identity {}",
+                    comparison.known_identifier
+                ),
+                ComparisonStatus::Older => format!(
+                    "The change history is older than the known identity {}",
+                    comparison.known_identifier
+                ),
+                ComparisonStatus::Conflict => format!(
+                    "The change history conflicts with the known identity {}",
+                    comparison.known_identifier
+                ),
+                ComparisonStatus::Unknown => format!(
+                    "The identity {} is not known locally",
+                    comparison.known_identifier
+                ),
+                ComparisonStatus::Different => format!(
+                    "The change history is not a change history of the identity {}",
+                    comparison.known_identifier
+                ),
+            };
+            match comparison.status {
+                ComparisonStatus::Equal | ComparisonStatus::Newer | ComparisonStatus::Unknown => {
+                    plain.push_str(&fmt_ok!("{message}\n"))
+                }
+                _ => plain.push_str(&fmt_err!("{message}\n")),
+            }
+            for change_hash in &comparison.only_verified {
+                writeln!(
+                    plain,
+                    "  only in the verified change history: {change_hash}"
+                )?;
+            }
+            for change_hash in &comparison.only_known {
+                writeln!(
+                    plain,
+                    "  only in the known change history:    {change_hash}"
+                )?;
+            }
+        }
+        write!(f, "{}", plain.trim_end())
+    }
+}
+
+#[derive(Serialize)]
+struct ChangeEvent {
+    index: usize,
+    change_hash: String,
+    primary_public_key: VerifyingPublicKeyDisplay,
+    created_at: u64,
+    expires_at: u64,
+    revoke_all_purpose_keys: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delegation: Option<DelegationOutput>,
+}
+
+impl ChangeEvent {
+    fn new(index: usize, change: &VerifiedChange) -> Self {
+        let data = change.data();
+        let delegation = data
+            .delegation
+            .as_ref()
+            .and_then(|d| d.get_versioned_data().ok())
+            .and_then(|v| DeviceDelegationData::get_data(&v).ok())
+            .map(|d| DelegationOutput {
+                delegator: d.delegator,
+                device_name: d.device_name,
+            });
+        Self {
+            index,
+            change_hash: hex::encode(change.change_hash()),
+            primary_public_key: VerifyingPublicKeyDisplay(change.primary_public_key().clone()),
+            created_at: data.created_at.0,
+            expires_at: data.expires_at.0,
+            revoke_all_purpose_keys: data.revoke_all_purpose_keys,
+            delegation,
+        }
+    }
+}
+
+/// Delegation recorded in the first change of a device identity. Its signature can only be
+/// verified once the delegating identity is known
+#[derive(Serialize)]
+struct DelegationOutput {
+    delegator: Identifier,
+    device_name: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ComparisonStatus {
+    Equal,
+    Newer,
+    Older,
+    Conflict,
+    Unknown,
+    Different,
+}
+
+#[derive(Serialize)]
+struct ComparisonOutput {
+    known_identifier: Identifier,
+    status: ComparisonStatus,
+    /// Hashes of the changes which are only present in the verified change history
+    only_verified: Vec<String>,
+    /// Hashes of the changes which are only present in the known change history
+    only_known: Vec<String>,
+}
+
+impl ComparisonOutput {
+    fn new(
+        known_identifier: Identifier,
+        comparison: IdentityHistoryComparison,
+        identity: &Identity,
+        known_identity: &Identity,
+    ) -> Self {
+        let status = match comparison {
+            IdentityHistoryComparison::Equal => ComparisonStatus::Equal,
+            IdentityHistoryComparison::Newer => ComparisonStatus::Newer,
+            IdentityHistoryComparison::Older => ComparisonStatus::Older,
+            IdentityHistoryComparison::Conflict => ComparisonStatus::Conflict,
+        };
+        let common = identity
+            .changes()
+            .iter()
+            .zip(known_identity.changes())
+            .take_while(|(a, b)| a.change_hash() == b.change_hash())
+            .count();
+        let hashes = |changes: &[VerifiedChange]| {
+            changes
+                .iter()
+                .map(|c| hex::encode(c.change_hash()))
+                .collect()
+        };
+        Self {
+            known_identifier,
+            status,
+            only_verified: hashes(&identity.changes()[common..]),
+            only_known: hashes(&known_identity.changes()[common..]),
+        }
+    }
+
+    fn unknown(known_identifier: Identifier) -> Self {
+        Self {
+            known_identifier,
+            status: ComparisonStatus::Unknown,
+            only_verified: vec![],
+            only_known: vec![],
+        }
+    }
+
+    fn different(known_identifier: Identifier) -> Self {
+        Self {
+            known_identifier,
+            status: ComparisonStatus::Different,
+            only_verified: vec![],
+            only_known: vec![],
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use std::net::SocketAddr;
+
+use clap::{Args, Parser};
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam_api::cli_state::traits::StateDirTrait;
+
+use crate::terminal::{ConfirmResult, OckamColor};
+use crate::util::local_cmd;
+use crate::util::parsers::socket_addr_parser;
+use crate::{docs, fmt_info, fmt_log, fmt_ok, identity, node, tcp, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Set up Ockam on this machine, step by step
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct InitCommand {
+    /// Name of the identity to create, when there is no default identity
+    #[arg(long, value_name = "IDENTITY_NAME", default_value = "default")]
+    identity: String,
+
+    /// Name of the node to create, when there is no default node
+    #[arg(long, value_name = "NODE_NAME", default_value = "default")]
+    node: String,
+
+    /// Don't enroll with Ockam Orchestrator, to only use Ockam on local and private networks
+    #[arg(long)]
+    offline: bool,
+
+    /// Address of a TCP service running on this machine, to create a first portal to it
+    #[arg(long, value_name = "SOCKET_ADDRESS", value_parser = socket_addr_parser)]
+    portal_to: Option<SocketAddr>,
+
+    /// Don't ask any question, and use the values of the arguments
+    #[arg(long, short)]
+    yes: bool,
+}
+
+impl InitCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self))
+    }
+
+    /// Return true if the user can be asked questions
+    fn is_interactive(&self, opts: &CommandGlobalOpts) -> bool {
+        !self.yes && opts.terminal.can_ask_for_user_input()
+    }
+
+    /// Ask a yes/no question, or return the default answer when the user can't be asked
+    fn ask(&self, opts: &CommandGlobalOpts, question: &str, default: bool) -> miette::Result<bool> {
+        if !self.yes {
+            opts.terminal.fail_if_non_interactive(question)?;
+        }
+        if !self.is_interactive(opts) {
+            return Ok(default);
+        }
+        Ok(matches!(
+            opts.terminal.confirm(question)?,
+            ConfirmResult::Yes
+        ))
+    }
+
+    /// Ask for a value, or return the default value when the user can't be asked
+    fn input(
+        &self,
+        opts: &CommandGlobalOpts,
+        prompt: &str,
+        default: &str,
+    ) -> miette::Result<String> {
+        if !self.yes {
+            opts.terminal.fail_if_non_interactive(prompt)?;
+        }
+        if !self.is_interactive(opts) {
+            return Ok(default.to_string());
+        }
+        Ok(opts.terminal.input(prompt, default)?)
+    }
+}
+
+/// What has been set up by the command
+#[derive(Serialize)]
+struct InitSummary {
+    identity: String,
+    enrolled: bool,
+    node: String,
+    portal: Option<PortalSummary>,
+}
+
+#[derive(Serialize)]
+struct PortalSummary {
+    inlet: SocketAddr,
+    service: SocketAddr,
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: InitCommand) -> miette::Result<()> {
+    let identity = init_identity(&opts, &cmd)?;
+    let enrolled = init_enrollment(&opts, &cmd, &identity)?;
+    let node = init_node(&opts, &cmd)?;
+    let portal = init_portal(&opts, &cmd, &node)?;
+
+    let mut plain = fmt_ok!("Ockam is set up on this machine\n");
+    plain.push_str(&fmt_log!(
+        "Default identity: {}\n",
+        identity.as_str().color(OckamColor::PrimaryResource.color())
+    ));
+    plain.push_str(&fmt_log!(
+        "Default node: {}\n",
+        node.as_str().color(OckamColor::PrimaryResource.color())
+    ));
+    if let Some(portal) = &portal {
+        plain.push_str(&fmt_log!(
+            "The service {} can be reached at {}\n",
+            portal
+                .service
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            portal
+                .inlet
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        ));
+    }
+    plain.push_str(&fmt_info!(
+        "Run {} to see the status of your environment",
+        "ockam status".color(OckamColor::PrimaryResource.color())
+    ));
+    let summary = InitSummary {
+        identity,
+        enrolled,
+        node,
+        portal,
+    };
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .json(serde_json::to_string_pretty(&summary).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+/// Use the default identity, or create it
+fn init_identity(opts: &CommandGlobalOpts, cmd: &InitCommand) -> miette::Result<String> {
+    if let Ok(identity) = opts.state.identities.default() {
+        opts.terminal.write_line(&fmt_log!(
+            "Using the default identity {}\n",
+            identity.name().color(OckamColor::PrimaryResource.color())
+        ))?;
+        return Ok(identity.name().to_string());
+    }
+    let name = cmd.input(opts, "Name of your identity", &cmd.identity)?;
+    identity::CreateCommand::new(name.clone(), None, None).run(opts.clone());
+    opts.state.identities.set_default(&name)?;
+    Ok(name)
+}
+
+/// Enroll the identity, unless the offline mode is chosen.
+/// Return true if the identity is enrolled
+#[cfg(feature = "orchestrator")]
+fn init_enrollment(
+    opts: &CommandGlobalOpts,
+    cmd: &InitCommand,
+    identity: &str,
+) -> miette::Result<bool> {
+    if opts.state.identities.get(identity)?.is_enrolled() {
+        opts.terminal.write_line(&fmt_log!(
+            "The identity {} is enrolled with Ockam Orchestrator\n",
+            identity.color(OckamColor::PrimaryResource.color())
+        ))?;
+        return Ok(true);
+    }
+    if cmd.offline
+        || !cmd.ask(
+            opts,
+            "Do you want to enroll with Ockam Orchestrator? Otherwise Ockam is used offline",
+            true,
+        )?
+    {
+        opts.terminal.write_line(&fmt_log!(
+            "Using Ockam offline, you can enroll later with {}\n",
+            "ockam enroll".color(OckamColor::PrimaryResource.color())
+        ))?;
+        return Ok(false);
+    }
+    crate::enroll::EnrollCommand {
+        identity: Some(identity.to_string()),
+        authorization_code_flow: false,
+        subcommand: None,
+    }
+    .run(opts.clone());
+    Ok(true)
+}
+
+/// Ockam Orchestrator is not available in this build
+#[cfg(not(feature = "orchestrator"))]
+fn init_enrollment(
+    opts: &CommandGlobalOpts,
+    _cmd: &InitCommand,
+    _identity: &str,
+) -> miette::Result<bool> {
+    opts.terminal
+        .write_line(&fmt_log!("Using Ockam offline\n"))?;
+    Ok(false)
+}
+
+/// Use the default node, or create it. The default node is started if it is not running
+fn init_node(opts: &CommandGlobalOpts, cmd: &InitCommand) -> miette::Result<String> {
+    let (name, running) = match opts.state.nodes.default() {
+        Ok(node) => {
+            opts.terminal.write_line(&fmt_log!(
+                "Using the default node {}\n",
+                node.name().color(OckamColor::PrimaryResource.color())
+            ))?;
+            (node.name().to_string(), node.is_running())
+        }
+        Err(_) => (cmd.input(opts, "Name of your node", &cmd.node)?, false),
+    };
+    if !running {
+        // Creating an existing node starts it again
+        let mut create_command = node::CreateCommand::default();
+        create_command.node_name = name.clone();
+        create_command.run(opts.clone());
+        opts.state.nodes.set_default(&name)?;
+    }
+    Ok(name)
+}
+
+/// Create a TCP outlet to a local service and a TCP inlet to that outlet, if requested
+fn init_portal(
+    opts: &CommandGlobalOpts,
+    cmd: &InitCommand,
+    node: &str,
+) -> miette::Result<Option<PortalSummary>> {
+    let service = match cmd.portal_to {
+        Some(service) => service,
+        None if cmd.ask(
+            opts,
+            "Do you want to create a first portal to a TCP service running on this machine?",
+            false,
+        )? =>
+        {
+            let service = cmd.input(opts, "Address of the service", "127.0.0.1:5000")?;
+            socket_addr_parser(&service)?
+        }
+        None => return Ok(None),
+    };
+    let inlet = cmd.input(
+        opts,
+        "Address where the service will be reached",
+        &tcp::inlet::create::default_from_addr()?.to_string(),
+    )?;
+    let inlet = socket_addr_parser(&inlet)?;
+
+    parse_args::<tcp::outlet::create::CreateCommand>(&[
+        "--at",
+        node,
+        "--to",
+        &service.to_string(),
+    ])?
+    .run(opts.clone());
+    parse_args::<tcp::inlet::create::CreateCommand>(&[
+        "--at",
+        node,
+        "--from",
+        &inlet.to_string(),
+        "--to",
+        &format!("/node/{node}/service/outlet"),
+    ])?
+    .run(opts.clone());
+    Ok(Some(PortalSummary { inlet, service }))
+}
+
+/// Arguments of a command, parsed as if they were typed by the user, to get their default values
+#[derive(Parser)]
+struct CommandArgs<T: Args> {
+    #[command(flatten)]
+    command: T,
+}
+
+pub(crate) fn parse_args<T: Args>(args: &[&str]) -> miette::Result<T> {
+    let args = std::iter::once("ockam").chain(args.iter().copied());
+    Ok(CommandArgs::<T>::try_parse_from(args)
+        .into_diagnostic()?
+        .command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn portal_commands_are_parsed() {
+        assert!(parse_args::<tcp::outlet::create::CreateCommand>(&[
+            "--at",
+            "n1",
+            "--to",
+            "127.0.0.1:5000"
+        ])
+        .is_ok());
+        assert!(parse_args::<tcp::outlet::create::CreateCommand>(&["--at", "n1"]).is_err());
+    }
+}
@@ -5,8 +5,8 @@ use colorful::Colorful;
 use tokio::{sync::Mutex, try_join};
 
 use ockam::Context;
-use ockam_api::nodes::models::services::StartKafkaOutletRequest;
 use ockam_api::nodes::models::services::StartServiceRequest;
+use ockam_api::nodes::models::services::{KafkaSaslPlain, StartKafkaOutletRequest};
 use ockam_api::nodes::BackgroundNode;
 use ockam_core::api::Request;
 
@@ -32,6 +32,14 @@ pub struct CreateCommand {
     /// The address of the kafka bootstrap broker
     #[arg(long, default_value_t = kafka_default_outlet_server())]
     bootstrap_server: SocketAddr,
+    /// User name used to authenticate to the brokers with SASL/PLAIN, in place of the
+    /// credentials sent by the Kafka clients. It can be a reference to a node secret: `secret:NAME`
+    #[arg(long, value_name = "USERNAME", requires = "sasl_password")]
+    sasl_username: Option<String>,
+    /// Password used to authenticate to the brokers with SASL/PLAIN. Use a reference to a node
+    /// secret, `secret:NAME`, so that the password is not visible in the list of processes
+    #[arg(long, value_name = "PASSWORD", requires = "sasl_username")]
+    sasl_password: Option<String>,
 }
 
 impl CreateCommand {
@@ -47,10 +55,18 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> m
         node_opts,
         addr,
         bootstrap_server,
+        sasl_username,
+        sasl_password,
     } = cmd;
     let is_finished = Mutex::new(false);
     let send_req = async {
         let payload = StartKafkaOutletRequest::new(bootstrap_server);
+        let payload = match (sasl_username, sasl_password) {
+            (Some(username), Some(password)) => {
+                payload.with_sasl(KafkaSaslPlain::new(username, password))
+            }
+            _ => payload,
+        };
         let payload = StartServiceRequest::new(payload, &addr);
         let req = Request::post("/node/services/kafka_outlet").body(payload);
         let node_name = get_node_name(&opts.state, &node_opts.at_node);
@@ -16,20 +16,39 @@
 //!     ```bash
 //!     cd implementations/rust/ockam/ockam_command && cargo install --path .
 //!     ```
+//!
+//! 1. To build a smaller binary, for example for an embedded gateway, leave out the Orchestrator
+//!    and Kafka commands, the optional transports and vaults, and the interactive lists:
+//!
+//!     ```bash
+//!     cd implementations/rust/ockam/ockam_command && cargo install --path . --no-default-features
+//!     ```
 
+#[cfg(feature = "orchestrator")]
 mod admin;
 mod authenticated;
 mod authority;
+mod chat;
 mod completion;
 mod configuration;
 mod credential;
+mod debug;
+mod discover;
 mod docs;
+mod doctor;
+mod dry_run;
 pub mod enroll;
 mod environment;
 pub mod error;
+mod events;
+mod exec;
+mod features;
 mod flow_control;
 pub mod identity;
+mod init;
+#[cfg(feature = "kafka")]
 mod kafka;
+#[cfg(feature = "orchestrator")]
 mod lease;
 mod logs;
 mod manpages;
@@ -40,79 +59,115 @@ mod operation;
 mod output;
 mod pager;
 mod policy;
+// The utilities of the project module are also used to resolve the project addresses
+#[cfg_attr(not(feature = "orchestrator"), allow(dead_code))]
 mod project;
 mod relay;
 mod reset;
 mod run;
 mod secure_channel;
 mod service;
+mod setup;
 #[cfg(feature = "orchestrator")]
 mod share;
 pub mod shutdown;
+#[cfg(feature = "orchestrator")]
 mod sidecar;
+#[cfg(feature = "orchestrator")]
 mod space;
+mod state;
 mod status;
+#[cfg(feature = "orchestrator")]
 mod subscription;
 pub mod tcp;
 mod terminal;
+mod trust;
 mod trust_context;
+#[cfg(feature = "udp")]
+mod udp;
 mod upgrade;
 pub mod util;
 mod vault;
 mod version;
 mod worker;
 
+#[cfg(feature = "orchestrator")]
 use crate::admin::AdminCommand;
 use crate::authority::AuthorityCommand;
 use crate::flow_control::FlowControlCommand;
-use crate::logs::setup_logging;
+use crate::logs::{setup_logging, LoggingSink};
 use crate::node::NodeSubcommand;
 use crate::run::RunCommand;
+#[cfg(feature = "orchestrator")]
 use crate::subscription::SubscriptionCommand;
-pub use crate::terminal::{OckamColor, Terminal, TerminalStream};
+pub use crate::terminal::{OckamColor, ProgressFormat, Terminal, TerminalStream};
 use authenticated::AuthenticatedCommand;
+use chat::ChatCommand;
 use clap::{ArgAction, Args, Parser, Subcommand};
 
+#[cfg(feature = "kafka")]
 use crate::kafka::direct::KafkaDirectCommand;
+#[cfg(feature = "kafka")]
 use crate::kafka::outlet::KafkaOutletCommand;
-use crate::output::{Output, OutputFormat};
+use crate::output::{Output, OutputFormat, OUTPUT_SCHEMA_VERSION};
+#[cfg(feature = "orchestrator")]
 use crate::sidecar::SidecarCommand;
 use colorful::Colorful;
 use completion::CompletionCommand;
 use configuration::ConfigurationCommand;
 use console::Term;
 use credential::CredentialCommand;
+use debug::DebugCommand;
+use discover::DiscoverCommand;
+use doctor::DoctorCommand;
+#[cfg(feature = "orchestrator")]
 use enroll::EnrollCommand;
 use environment::EnvironmentCommand;
 use error::{Error, Result};
+use events::EventsCommand;
+use exec::ExecCommand;
 use identity::IdentityCommand;
+use init::InitCommand;
+#[cfg(feature = "kafka")]
 use kafka::consumer::KafkaConsumerCommand;
+#[cfg(feature = "kafka")]
 use kafka::producer::KafkaProducerCommand;
+#[cfg(feature = "orchestrator")]
 use lease::LeaseCommand;
 use manpages::ManpagesCommand;
 use markdown::MarkdownCommand;
 use message::MessageCommand;
 use miette::GraphicalReportHandler;
 use node::NodeCommand;
-use ockam_api::cli_state::CliState;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::cli_state::{set_vault_passphrase_prompt, CliState, CliStateError};
+use ockam_api::logs::env::otlp_logs_endpoint;
+use ockam_api::logs::LogSink;
 use ockam_core::env::get_env_with_default;
 use once_cell::sync::Lazy;
 use policy::PolicyCommand;
+#[cfg(feature = "orchestrator")]
 use project::ProjectCommand;
 use relay::RelayCommand;
 use reset::ResetCommand;
 use secure_channel::{listener::SecureChannelListenerCommand, SecureChannelCommand};
 use service::ServiceCommand;
+use setup::SetupCommand;
 #[cfg(feature = "orchestrator")]
 use share::ShareCommand;
+#[cfg(feature = "orchestrator")]
 use space::SpaceCommand;
+use state::StateCommand;
 use status::StatusCommand;
-use std::{path::PathBuf, sync::Mutex};
+use std::sync::Mutex;
 use tcp::{
     connection::TcpConnectionCommand, inlet::TcpInletCommand, listener::TcpListenerCommand,
     outlet::TcpOutletCommand,
 };
+use trust::TrustCommand;
 use trust_context::TrustContextCommand;
+#[cfg(feature = "udp")]
+use udp::UdpCommand;
 use upgrade::check_if_an_upgrade_is_available;
 use util::{exitcode, exitcode::ExitCode};
 use vault::VaultCommand;
@@ -181,15 +236,45 @@ pub struct GlobalArgs {
     #[arg(hide = docs::hide(), global = true, long, default_value_t = no_input_default_value())]
     no_input: bool,
 
-    /// Output format
+    /// Never prompt, and fail with a specific error and exit code when a command needs an
+    /// answer from the user. Spinners and colors are disabled too.
+    /// It can also be set with the OCKAM_NON_INTERACTIVE environment variable
+    #[arg(global = true, long, default_value_t = non_interactive_default_value())]
+    pub non_interactive: bool,
+
+    /// Output format. The json, yaml and table formats display the same document.
+    /// The fields of this document are only stable for a given --output-schema version
+    /// for the commands supporting --output-schema
+    #[arg(global = true, long = "output", value_enum, default_value = "plain")]
+    output_format: OutputFormat,
+
+    /// Version of the schema of the json, yaml and table outputs, for the commands having
+    /// a versioned output: `tcp-inlet list`, `tcp-inlet show`, `vault list`, `relay create`
+    /// and `relay list`. The other commands fail when this option is set
     #[arg(
-    hide = docs::hide(),
     global = true,
-    long = "output",
-    value_enum,
-    default_value = "plain"
+    long = "output-schema",
+    value_name = "VERSION",
+    value_parser = clap::value_parser!(u8).range(1..=OUTPUT_SCHEMA_VERSION as i64)
     )]
-    output_format: OutputFormat,
+    output_schema: Option<u8>,
+
+    /// Display of the progress of the long-running commands, like `enroll`, `node create`
+    /// or `tcp-inlet create`. With `json`, newline-delimited events with a stage, a message
+    /// and an optional percentage are written to stderr instead of spinners and log messages
+    #[arg(
+        global = true,
+        long = "progress",
+        value_enum,
+        default_value = "spinner"
+    )]
+    pub progress_format: ProgressFormat,
+
+    /// Print the plan of the API calls and state changes of the command, in JSON,
+    /// without executing them. Only the `node create`, `tcp-inlet create` and
+    /// `policy create` commands support it
+    #[arg(global = true, long)]
+    pub dry_run: bool,
 
     // if test_argument_parser is true, command arguments are checked
     // but the command is not executed.
@@ -209,6 +294,10 @@ fn no_input_default_value() -> bool {
     get_env_with_default("NO_INPUT", false).unwrap_or(false)
 }
 
+fn non_interactive_default_value() -> bool {
+    get_env_with_default("OCKAM_NON_INTERACTIVE", false).unwrap_or(false)
+}
+
 impl Default for GlobalArgs {
     fn default() -> Self {
         Self {
@@ -217,7 +306,11 @@ impl Default for GlobalArgs {
             verbose: 0,
             no_color: no_color_default_value(),
             no_input: no_input_default_value(),
+            non_interactive: non_interactive_default_value(),
             output_format: OutputFormat::Plain,
+            output_schema: None,
+            progress_format: ProgressFormat::default(),
+            dry_run: false,
             test_argument_parser: false,
         }
     }
@@ -229,6 +322,11 @@ impl GlobalArgs {
         clone.quiet = true;
         clone
     }
+
+    /// Version of the output schema, which is the latest version by default
+    pub fn output_schema(&self) -> u8 {
+        self.output_schema.unwrap_or(OUTPUT_SCHEMA_VERSION)
+    }
 }
 
 #[derive(Clone)]
@@ -239,13 +337,23 @@ pub struct CommandGlobalOpts {
 }
 
 impl CommandGlobalOpts {
-    pub fn new(global_args: GlobalArgs) -> Self {
+    pub fn new(mut global_args: GlobalArgs) -> Self {
+        // The non-interactive mode implies that there are no prompts and no colors
+        if global_args.non_interactive {
+            global_args.no_input = true;
+            global_args.no_color = true;
+        }
         let state = match CliState::initialize() {
             Ok(state) => state,
+            // The state written by a newer version of ockam must not be reset
+            Err(err @ CliStateError::UnsupportedSchemaVersion { .. }) => {
+                eprintln!("{:?}", miette::Report::new(err));
+                std::process::exit(exitcode::CONFIG);
+            }
             Err(err) => {
                 eprintln!("Failed to initialize state: {}", err);
                 let state = CliState::backup_and_reset().expect(
-                    "Failed to initialize CliState. Try to manually remove the '~/.ockam' directory",
+                    "Failed to initialize CliState. Try to manually remove the $OCKAM_HOME directory ('~/.ockam' by default)",
                 );
                 let dir = &state.dir;
                 let backup_dir = CliState::backup_default_dir().unwrap();
@@ -255,12 +363,7 @@ impl CommandGlobalOpts {
                 state
             }
         };
-        let terminal = Terminal::new(
-            global_args.quiet,
-            global_args.no_color,
-            global_args.no_input,
-            global_args.output_format.clone(),
-        );
+        let terminal = Self::terminal(&global_args);
         Self {
             global_args,
             state,
@@ -268,6 +371,18 @@ impl CommandGlobalOpts {
         }
     }
 
+    /// Create the terminal used to interact with the user
+    pub fn terminal(global_args: &GlobalArgs) -> Terminal<TerminalStream<Term>> {
+        Terminal::new(
+            global_args.quiet,
+            global_args.no_color,
+            global_args.no_input,
+            global_args.output_format.clone(),
+        )
+        .with_progress_format(global_args.progress_format.clone())
+        .with_non_interactive(global_args.non_interactive)
+    }
+
     pub fn set_quiet(&self) -> Self {
         let mut clone = self.clone();
         clone.global_args = clone.global_args.set_quiet();
@@ -304,30 +419,51 @@ impl CommandGlobalOpts {
 
 #[derive(Clone, Debug, Subcommand)]
 pub enum OckamSubcommand {
+    #[command(display_order = 799)]
+    Init(InitCommand),
+    #[command(display_order = 799)]
+    Setup(SetupCommand),
+    #[cfg(feature = "orchestrator")]
     #[command(display_order = 800)]
     Enroll(EnrollCommand),
+    #[cfg(feature = "orchestrator")]
     Space(SpaceCommand),
+    #[cfg(feature = "orchestrator")]
     Project(ProjectCommand),
+    #[cfg(feature = "orchestrator")]
     Sidecar(SidecarCommand),
+    #[cfg(feature = "orchestrator")]
     Admin(AdminCommand),
     #[cfg(feature = "orchestrator")]
     Share(ShareCommand),
+    #[cfg(feature = "orchestrator")]
     Subscription(SubscriptionCommand),
 
     Node(Box<NodeCommand>),
     Worker(WorkerCommand),
     Service(ServiceCommand),
     Message(MessageCommand),
+    Exec(ExecCommand),
+    Chat(ChatCommand),
     Relay(RelayCommand),
+    Discover(DiscoverCommand),
+    Events(EventsCommand),
 
     TcpListener(TcpListenerCommand),
     TcpConnection(TcpConnectionCommand),
     TcpOutlet(TcpOutletCommand),
     TcpInlet(TcpInletCommand),
 
+    #[cfg(feature = "udp")]
+    Udp(UdpCommand),
+
+    #[cfg(feature = "kafka")]
     KafkaOutlet(KafkaOutletCommand),
+    #[cfg(feature = "kafka")]
     KafkaConsumer(KafkaConsumerCommand),
+    #[cfg(feature = "kafka")]
     KafkaDirect(KafkaDirectCommand),
+    #[cfg(feature = "kafka")]
     KafkaProducer(KafkaProducerCommand),
 
     SecureChannelListener(SecureChannelListenerCommand),
@@ -338,11 +474,14 @@ pub enum OckamSubcommand {
     Credential(CredentialCommand),
     Authority(AuthorityCommand),
     Policy(PolicyCommand),
+    #[cfg(feature = "orchestrator")]
     Lease(LeaseCommand),
 
     Run(RunCommand),
     Status(StatusCommand),
+    Doctor(DoctorCommand),
     Reset(ResetCommand),
+    State(StateCommand),
     Authenticated(AuthenticatedCommand),
     Configuration(ConfigurationCommand),
 
@@ -350,15 +489,54 @@ pub enum OckamSubcommand {
     Markdown(MarkdownCommand),
     Manpages(ManpagesCommand),
     TrustContext(TrustContextCommand),
+    Trust(TrustCommand),
     Environment(EnvironmentCommand),
 
     FlowControl(FlowControlCommand),
+    Debug(DebugCommand),
 }
 
 impl OckamSubcommand {
     pub fn should_display_header(&self) -> bool {
-        // Currently only enroll command displays the header
-        matches!(self, OckamSubcommand::Enroll(_))
+        // Currently only the init and enroll commands display the header
+        if let OckamSubcommand::Init(_) = self {
+            return true;
+        }
+        #[cfg(feature = "orchestrator")]
+        if let OckamSubcommand::Enroll(c) = self {
+            return c.subcommand.is_none();
+        }
+        false
+    }
+
+    /// Return true if the command can print its plan instead of being executed
+    pub fn supports_dry_run(&self) -> bool {
+        match self {
+            OckamSubcommand::Node(c) => matches!(c.subcommand, node::NodeSubcommand::Create(_)),
+            OckamSubcommand::TcpInlet(c) => {
+                matches!(c.subcommand, tcp::inlet::TcpInletSubCommand::Create(_))
+            }
+            OckamSubcommand::Policy(c) => {
+                matches!(c.subcommand, policy::PolicySubcommand::Create(_))
+            }
+            _ => false,
+        }
+    }
+
+    /// Return true if the command output is defined for each version of the output schema
+    pub fn supports_output_schema(&self) -> bool {
+        match self {
+            OckamSubcommand::TcpInlet(c) => matches!(
+                c.subcommand,
+                tcp::inlet::TcpInletSubCommand::List(_) | tcp::inlet::TcpInletSubCommand::Show(_)
+            ),
+            OckamSubcommand::Vault(c) => matches!(c.subcommand, vault::VaultSubcommand::List(_)),
+            OckamSubcommand::Relay(c) => matches!(
+                c.subcommand,
+                relay::RelaySubCommand::Create(_) | relay::RelaySubCommand::List(_)
+            ),
+            _ => false,
+        }
     }
 }
 
@@ -366,6 +544,7 @@ pub fn run() {
     let input = std::env::args()
         .map(replace_hyphen_with_stdin)
         .collect::<Vec<_>>();
+    let input = configuration::expand_alias(input, &configuration::configured_aliases());
 
     match OckamCommand::try_parse_from(input) {
         Ok(command) => {
@@ -375,7 +554,20 @@ pub fn run() {
 
             command.run();
         }
-        Err(help) => pager::render_help(help),
+        Err(help) => match features::disabled_command(&input) {
+            Some(disabled) => {
+                eprintln!(
+                    "{}",
+                    crate::fmt_err!(
+                        "The `{}` command is not available: this build of ockam was compiled without the \"{}\" feature",
+                        disabled.name,
+                        disabled.feature
+                    )
+                );
+                std::process::exit(exitcode::USAGE);
+            }
+            None => pager::render_help(help),
+        },
     };
 }
 
@@ -392,15 +584,36 @@ impl OckamCommand {
                     .with_urls(false),
             )
         }));
+        // The state directory is migrated when it is loaded, so the pending migrations
+        // must be checked before
+        if let OckamSubcommand::State(c) = &self.subcommand {
+            if let Some(migrate) = c.migration_check() {
+                if !self.global_args.test_argument_parser {
+                    migrate.check(&self.global_args);
+                }
+                return;
+            }
+        }
+
         let options = CommandGlobalOpts::new(self.global_args.clone());
 
+        // Ask for the passphrase of a locked vault when it is not set in the environment
+        if options.terminal.can_ask_for_user_input() {
+            set_vault_passphrase_prompt(|name| {
+                dialoguer::Password::new()
+                    .with_prompt(format!("Passphrase of the vault {name}"))
+                    .interact()
+                    .ok()
+            });
+        }
+
         let _tracing_guard = if !options.global_args.quiet {
-            let log_path = self.log_path(&options);
+            let log_sinks = self.log_sinks(&options);
             let guard = setup_logging(
                 options.global_args.verbose,
                 options.global_args.no_color,
                 options.terminal.is_tty(),
-                log_path,
+                log_sinks,
             );
             tracing::debug!("{}", Version::short());
             tracing::debug!("Parsed {:?}", &self);
@@ -416,6 +629,27 @@ impl OckamCommand {
             return;
         }
 
+        // A command which can't print its plan must not be executed
+        if options.global_args.dry_run && !self.subcommand.supports_dry_run() {
+            eprintln!(
+                "{}",
+                crate::fmt_err!("This command doesn't support --dry-run, it has not been executed")
+            );
+            std::process::exit(exitcode::USAGE);
+        }
+
+        // The output of the other commands is not versioned
+        if options.global_args.output_schema.is_some() && !self.subcommand.supports_output_schema()
+        {
+            eprintln!(
+                "{}",
+                crate::fmt_err!(
+                    "This command doesn't support --output-schema, it has not been executed"
+                )
+            );
+            std::process::exit(exitcode::USAGE);
+        }
+
         // Display Header if needed
         if self.subcommand.should_display_header() {
             let ockam_header = include_str!("../static/ockam_ascii.txt").trim();
@@ -430,28 +664,46 @@ impl OckamCommand {
         }
 
         match self.subcommand {
+            OckamSubcommand::Init(c) => c.run(options),
+            OckamSubcommand::Setup(c) => c.run(options),
+            #[cfg(feature = "orchestrator")]
             OckamSubcommand::Enroll(c) => c.run(options),
+            #[cfg(feature = "orchestrator")]
             OckamSubcommand::Space(c) => c.run(options),
+            #[cfg(feature = "orchestrator")]
             OckamSubcommand::Project(c) => c.run(options),
+            #[cfg(feature = "orchestrator")]
             OckamSubcommand::Admin(c) => c.run(options),
             #[cfg(feature = "orchestrator")]
             OckamSubcommand::Share(c) => c.run(options),
+            #[cfg(feature = "orchestrator")]
             OckamSubcommand::Subscription(c) => c.run(options),
 
             OckamSubcommand::Node(c) => c.run(options),
             OckamSubcommand::Worker(c) => c.run(options),
             OckamSubcommand::Service(c) => c.run(options),
             OckamSubcommand::Message(c) => c.run(options),
+            OckamSubcommand::Exec(c) => c.run(options),
+            OckamSubcommand::Chat(c) => c.run(options),
             OckamSubcommand::Relay(c) => c.run(options),
+            OckamSubcommand::Discover(c) => c.run(options),
+            OckamSubcommand::Events(c) => c.run(options),
 
+            #[cfg(feature = "kafka")]
             OckamSubcommand::KafkaOutlet(c) => c.run(options),
             OckamSubcommand::TcpListener(c) => c.run(options),
             OckamSubcommand::TcpConnection(c) => c.run(options),
             OckamSubcommand::TcpOutlet(c) => c.run(options),
             OckamSubcommand::TcpInlet(c) => c.run(options),
 
+            #[cfg(feature = "udp")]
+            OckamSubcommand::Udp(c) => c.run(options),
+
+            #[cfg(feature = "kafka")]
             OckamSubcommand::KafkaConsumer(c) => c.run(options),
+            #[cfg(feature = "kafka")]
             OckamSubcommand::KafkaProducer(c) => c.run(options),
+            #[cfg(feature = "kafka")]
             OckamSubcommand::KafkaDirect(c) => c.run(options),
 
             OckamSubcommand::SecureChannelListener(c) => c.run(options),
@@ -462,46 +714,85 @@ impl OckamCommand {
             OckamSubcommand::Credential(c) => c.run(options),
             OckamSubcommand::Authority(c) => c.run(options),
             OckamSubcommand::Policy(c) => c.run(options),
+            #[cfg(feature = "orchestrator")]
             OckamSubcommand::Lease(c) => c.run(options),
 
             OckamSubcommand::Run(c) => c.run(options),
             OckamSubcommand::Status(c) => c.run(options),
+            OckamSubcommand::Doctor(c) => c.run(options),
             OckamSubcommand::Reset(c) => c.run(options),
+            OckamSubcommand::State(c) => c.run(options),
             OckamSubcommand::Authenticated(c) => c.run(options),
             OckamSubcommand::Configuration(c) => c.run(options),
 
-            OckamSubcommand::Completion(c) => c.run(),
+            OckamSubcommand::Completion(c) => c.run(options),
             OckamSubcommand::Markdown(c) => c.run(),
             OckamSubcommand::Manpages(c) => c.run(),
             OckamSubcommand::TrustContext(c) => c.run(options),
+            OckamSubcommand::Trust(c) => c.run(options),
             OckamSubcommand::Environment(c) => c.run(),
 
             OckamSubcommand::FlowControl(c) => c.run(options),
+            OckamSubcommand::Debug(c) => c.run(options),
+            #[cfg(feature = "orchestrator")]
             OckamSubcommand::Sidecar(c) => c.run(options),
         }
     }
 
-    fn log_path(&self, opts: &CommandGlobalOpts) -> Option<PathBuf> {
-        // If the subcommand is `node create` then return the log path
-        // for the node that is being created
+    fn log_sinks(&self, opts: &CommandGlobalOpts) -> Vec<LoggingSink> {
+        // If the subcommand is `node create` then return the log sinks
+        // of the node that is being created
         if let OckamSubcommand::Node(c) = &self.subcommand {
             if let NodeSubcommand::Create(c) = &c.subcommand {
-                if c.logging_to_stdout() {
-                    return None;
+                let (mut sinks, log_sinks, endpoint) = if c.logging_to_stdout() {
+                    (
+                        vec![LoggingSink::Stdout],
+                        c.log_sinks.clone(),
+                        c.otlp_logs_endpoint.clone(),
+                    )
+                } else {
+                    // A node running in a child process uses the sinks stored in its setup,
+                    // so that they are kept when the node is restarted
+                    let setup = opts
+                        .state
+                        .nodes
+                        .get(&c.node_name)
+                        .map(|n| n.config().setup().clone())
+                        .unwrap_or_default();
+                    let log_sinks = if setup.log_sinks.is_empty() {
+                        vec![LogSink::File]
+                    } else {
+                        setup.log_sinks
+                    };
+                    (vec![], log_sinks, setup.otlp_logs_endpoint)
+                };
+                for log_sink in log_sinks {
+                    match log_sink {
+                        LogSink::File => {
+                            // In the case where a node is explicitly created in foreground mode, we need
+                            // to initialize the node directories before we can get the log path.
+                            let path =
+                                opts.state
+                                    .nodes
+                                    .stdout_logs(&c.node_name)
+                                    .unwrap_or_else(|_| {
+                                        panic!(
+                                            "Failed to initialize logs file for node {}",
+                                            c.node_name
+                                        )
+                                    });
+                            sinks.push(LoggingSink::File(path));
+                        }
+                        LogSink::Journald => sinks.push(LoggingSink::Journald),
+                        LogSink::Otlp => sinks.push(LoggingSink::Otlp(
+                            endpoint.clone().unwrap_or_else(otlp_logs_endpoint),
+                        )),
+                    }
                 }
-                // In the case where a node is explicitly created in foreground mode, we need
-                // to initialize the node directories before we can get the log path.
-                let path = opts
-                    .state
-                    .nodes
-                    .stdout_logs(&c.node_name)
-                    .unwrap_or_else(|_| {
-                        panic!("Failed to initialize logs file for node {}", c.node_name)
-                    });
-                return Some(path);
+                return sinks;
             }
         }
-        None
+        vec![LoggingSink::Stdout]
     }
 }
 
@@ -1,20 +1,55 @@
 use ockam_api::logs::env::{log_format, log_level, log_max_files, log_max_size_bytes};
 use ockam_api::logs::rolling::{RollingConditionBasic, RollingFileAppender};
 use ockam_api::logs::LogFormat;
-use std::io::stdout;
+use opentelemetry::KeyValue;
+use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::logs::LoggerProvider;
+use opentelemetry_sdk::Resource;
+use std::io::{stdout, Write};
 use std::path::PathBuf;
 use std::str::FromStr;
 use tracing::level_filters::LevelFilter;
-use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_subscriber::filter::filter_fn;
 use tracing_subscriber::fmt::layer;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use tracing_subscriber::registry::Registry;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Destination of the logs of a command
+pub enum LoggingSink {
+    /// The standard output of the command
+    Stdout,
+    /// Rotating log files, starting with the given file
+    File(PathBuf),
+    /// The systemd journal
+    Journald,
+    /// An OpenTelemetry collector receiving the logs at the given endpoint
+    Otlp(String),
+}
+
+/// Keep the logging sinks running until the command exits, and flush them when it is dropped
+pub struct LoggingGuard {
+    _workers: Vec<WorkerGuard>,
+    logger_provider: Option<LoggerProvider>,
+}
+
+impl Drop for LoggingGuard {
+    fn drop(&mut self) {
+        if let Some(logger_provider) = &self.logger_provider {
+            logger_provider.force_flush();
+        }
+    }
+}
 
 pub fn setup_logging(
     verbose: u8,
     no_color: bool,
     is_tty: bool,
-    log_path: Option<PathBuf>,
-) -> Option<WorkerGuard> {
+    sinks: Vec<LoggingSink>,
+) -> Option<LoggingGuard> {
     let level = {
         // Parse the the raw log level value (e.g. "info" or "-vvv").
         let level_raw = match log_level() {
@@ -51,37 +86,102 @@ pub fn setup_logging(
             .with_default_directive(level.into())
             .parse_lossy(ockam_crates.map(|c| format!("{c}={level}")).join(","))
     };
-    let subscriber = tracing_subscriber::registry()
-        .with(filter)
-        .with(tracing_error::ErrorLayer::default());
-    let (appender, guard) = match log_path {
-        // If a log path is not provided, log to stdout.
-        None => {
-            let color = !no_color && is_tty;
-            let (n, guard) = tracing_appender::non_blocking(stdout());
-            let appender = layer().with_ansi(color).with_writer(n);
-            (Box::new(appender), guard)
-        }
-        // If a log path is provided, log to a rolling file appender.
-        Some(log_path) => {
-            let r = RollingFileAppender::new(
-                log_path,
-                RollingConditionBasic::new()
-                    .daily()
-                    .max_size(log_max_size_bytes()),
-                log_max_files(),
-            )
-            .expect("Failed to create rolling file appender");
-            let (n, guard) = tracing_appender::non_blocking(r);
-            let appender = layer().with_ansi(false).with_writer(n);
-            (Box::new(appender), guard)
+    let mut layers: Vec<BoxedLayer> = vec![];
+    let mut workers = vec![];
+    let mut otlp_logger_provider = None;
+    for sink in sinks {
+        match sink {
+            LoggingSink::Stdout => {
+                let color = !no_color && is_tty;
+                let (n, worker) = tracing_appender::non_blocking(stdout());
+                layers.push(fmt_layer(n, color));
+                workers.push(worker);
+            }
+            LoggingSink::File(log_path) => {
+                let r = RollingFileAppender::new(
+                    log_path,
+                    RollingConditionBasic::new()
+                        .daily()
+                        .max_size(log_max_size_bytes()),
+                    log_max_files(),
+                )
+                .expect("Failed to create rolling file appender");
+                let (n, worker) = tracing_appender::non_blocking(r);
+                layers.push(fmt_layer(n, false));
+                workers.push(worker);
+            }
+            LoggingSink::Journald => match journald_layer() {
+                Ok(journald) => layers.push(journald),
+                Err(e) => sink_unavailable("journald", e),
+            },
+            LoggingSink::Otlp(endpoint) => match create_otlp_logger_provider(&endpoint) {
+                Ok(logger_provider) => {
+                    // Only the logs of the ockam crates are exported, to avoid exporting
+                    // the logs produced by the export itself
+                    let bridge = OpenTelemetryTracingBridge::new(&logger_provider)
+                        .with_filter(filter_fn(|m| m.target().starts_with("ockam")));
+                    layers.push(Box::new(bridge));
+                    otlp_logger_provider = Some(logger_provider);
+                }
+                Err(e) => sink_unavailable("otlp", e),
+            },
         }
-    };
-    let res = match log_format() {
-        LogFormat::Pretty => subscriber.with(appender.pretty()).try_init(),
-        LogFormat::Json => subscriber.with(appender.json()).try_init(),
-        LogFormat::Default => subscriber.with(appender).try_init(),
-    };
-    res.expect("Failed to initialize tracing subscriber");
-    Some(guard)
+    }
+    tracing_subscriber::registry()
+        .with(layers)
+        .with(filter)
+        .with(tracing_error::ErrorLayer::default())
+        .try_init()
+        .expect("Failed to initialize tracing subscriber");
+    Some(LoggingGuard {
+        _workers: workers,
+        logger_provider: otlp_logger_provider,
+    })
+}
+
+fn fmt_layer(writer: NonBlocking, color: bool) -> BoxedLayer {
+    let appender = layer().with_ansi(color).with_writer(writer);
+    match log_format() {
+        LogFormat::Pretty => appender.pretty().boxed(),
+        LogFormat::Json => appender.json().boxed(),
+        LogFormat::Default => appender.boxed(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn journald_layer() -> Result<BoxedLayer, String> {
+    tracing_journald::layer()
+        .map(|l| l.with_syslog_identifier("ockam".to_string()).boxed())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn journald_layer() -> Result<BoxedLayer, String> {
+    Err("journald is only available on Linux".to_string())
+}
+
+fn create_otlp_logger_provider(endpoint: &str) -> Result<LoggerProvider, String> {
+    opentelemetry_otlp::new_pipeline()
+        .logging()
+        .with_log_config(
+            opentelemetry_sdk::logs::Config::default()
+                .with_resource(Resource::new(vec![KeyValue::new("service.name", "ockam")])),
+        )
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(endpoint),
+        )
+        // The logs are exported from a dedicated thread, since the tracing subscriber
+        // is set up before the tokio runtime of the command is started
+        .install_batch(opentelemetry_sdk::runtime::TokioCurrentThread)
+        .map_err(|e| e.to_string())
+}
+
+/// A sink which can't be set up must not prevent the command from running
+fn sink_unavailable(sink: &str, reason: String) {
+    let _ = writeln!(
+        std::io::stderr(),
+        "The {sink} logs sink can't be used: {reason}"
+    );
 }
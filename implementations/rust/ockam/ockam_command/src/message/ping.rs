@@ -0,0 +1,99 @@
+use core::time::Duration;
+
+use clap::Args;
+use miette::{Context as _, IntoDiagnostic};
+
+use ockam::Context;
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::service::message::{PingReport, PingRoute};
+use ockam_api::nodes::BackgroundNode;
+use ockam_core::api::Request;
+use ockam_multiaddr::MultiAddr;
+
+use crate::node::{get_node_name, initialize_node_if_default};
+use crate::util::duration::duration_parser;
+use crate::util::{clean_nodes_multiaddr, node_rpc};
+use crate::{docs, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/ping/after_long_help.txt");
+
+/// Measure the round-trip time to an Ockam node with pings to its echo service
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct PingCommand {
+    /// The route to the node to ping. The echo service is added at the end of the route
+    /// when it is not given
+    pub to: MultiAddr,
+
+    /// The node sending the pings
+    #[arg(short, long, value_name = "NODE")]
+    from: Option<String>,
+
+    /// Number of pings
+    #[arg(short, long, default_value_t = 4)]
+    pub count: u32,
+
+    /// Time after which a ping is considered lost
+    #[arg(long, value_name = "TIMEOUT", default_value = "5s", value_parser = duration_parser)]
+    pub timeout: Duration,
+}
+
+impl PingCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_node_if_default(&opts, &self.from);
+        node_rpc(rpc, (opts, self))
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, PingCommand)) -> miette::Result<()> {
+    let (to, _) =
+        clean_nodes_multiaddr(&cmd.to, &opts.state).context("Argument 'to' is invalid")?;
+    let node_name = extract_address_value(&get_node_name(&opts.state, &cmd.from))?;
+    let mut node = BackgroundNode::create(&ctx, &opts.state, &node_name).await?;
+    // The connection to the node and all the pings must complete before the request times out
+    let request_timeout = cmd.timeout * (cmd.count + 2);
+    let report: PingReport = node
+        .set_timeout(request_timeout)
+        .ask(
+            &ctx,
+            Request::post("v0/message/ping").body(PingRoute::new(&to, cmd.count, cmd.timeout)),
+        )
+        .await?;
+
+    let mut plain = format!("PING {}\n", report.route);
+    for (seq, rtt) in report.rtts.iter().enumerate() {
+        match rtt {
+            Some(rtt) => plain.push_str(&format!("seq={seq} time={}\n", format_rtt(rtt))),
+            None => plain.push_str(&format!("seq={seq} timeout\n")),
+        }
+    }
+    let received: Vec<&Duration> = report.rtts.iter().flatten().collect();
+    let lost = report.rtts.len() - received.len();
+    plain.push_str(&format!(
+        "{} pings sent, {} received, {}% lost",
+        report.rtts.len(),
+        received.len(),
+        (lost * 100).checked_div(report.rtts.len()).unwrap_or(0)
+    ));
+    if let (Some(min), Some(max)) = (received.iter().min(), received.iter().max()) {
+        let avg = received.iter().copied().sum::<Duration>() / received.len() as u32;
+        plain.push_str(&format!(
+            "\nrtt min/avg/max = {}/{}/{}",
+            format_rtt(min),
+            format_rtt(&avg),
+            format_rtt(max)
+        ));
+    }
+
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .json(serde_json::to_string_pretty(&report).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+/// Display a round-trip time in milliseconds
+pub(crate) fn format_rtt(rtt: &Duration) -> String {
+    format!("{:.3}ms", rtt.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,81 @@
+use core::time::Duration;
+
+use clap::Args;
+use miette::{Context as _, IntoDiagnostic};
+
+use ockam::Context;
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::service::message::{TraceHop, TraceRoute};
+use ockam_api::nodes::BackgroundNode;
+use ockam_core::api::Request;
+use ockam_multiaddr::MultiAddr;
+
+use crate::message::ping::format_rtt;
+use crate::node::{get_node_name, initialize_node_if_default};
+use crate::util::duration::duration_parser;
+use crate::util::{clean_nodes_multiaddr, node_rpc};
+use crate::{docs, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/trace/after_long_help.txt");
+
+/// Show each hop of a route to an Ockam node, with the round-trip time to the node reached
+/// after this hop
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct TraceCommand {
+    /// The route to trace. The echo service is added at the end of the route
+    /// when it is not given
+    pub to: MultiAddr,
+
+    /// The node tracing the route
+    #[arg(short, long, value_name = "NODE")]
+    from: Option<String>,
+
+    /// Maximum number of hops which are probed
+    #[arg(long, default_value_t = 16)]
+    pub max_hops: u8,
+
+    /// Time after which a hop is considered unreachable
+    #[arg(long, value_name = "TIMEOUT", default_value = "5s", value_parser = duration_parser)]
+    pub timeout: Duration,
+}
+
+impl TraceCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_node_if_default(&opts, &self.from);
+        node_rpc(rpc, (opts, self))
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, TraceCommand)) -> miette::Result<()> {
+    let (to, _) =
+        clean_nodes_multiaddr(&cmd.to, &opts.state).context("Argument 'to' is invalid")?;
+    let node_name = extract_address_value(&get_node_name(&opts.state, &cmd.from))?;
+    let mut node = BackgroundNode::create(&ctx, &opts.state, &node_name).await?;
+    // The connection to the node and all the hops must complete before the request times out
+    let request_timeout = cmd.timeout * (cmd.max_hops as u32 + 2);
+    let hops: Vec<TraceHop> = node
+        .set_timeout(request_timeout)
+        .ask(
+            &ctx,
+            Request::post("v0/message/trace").body(TraceRoute::new(&to, cmd.max_hops, cmd.timeout)),
+        )
+        .await?;
+
+    let plain = hops
+        .iter()
+        .enumerate()
+        .map(|(i, hop)| {
+            let rtt = hop.rtt.as_ref().map(format_rtt).unwrap_or("*".to_string());
+            format!("{:>2}  {}  {rtt}", i + 1, hop.address)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .json(serde_json::to_string_pretty(&hops).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
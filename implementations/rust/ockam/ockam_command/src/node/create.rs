@@ -1,7 +1,8 @@
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::{path::PathBuf, process, str::FromStr};
+use std::{process, str::FromStr};
 
-use clap::Args;
+use clap::{crate_version, Args};
 use colorful::Colorful;
 use miette::Context as _;
 use miette::{miette, IntoDiagnostic};
@@ -9,12 +10,18 @@ use minicbor::{Decoder, Encode};
 use tokio::sync::Mutex;
 use tokio::time::{sleep, Duration};
 use tokio::try_join;
+use tracing::{info, warn};
 
+use ockam::identity::MAX_ATTRIBUTES_GRACE_PERIOD;
 use ockam::{Address, AsyncTryClone, TcpListenerOptions};
-use ockam::{Context, TcpTransport};
+use ockam::{Context, RelayQuotas, TcpProxy, TcpTransport};
 use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
 use ockam_api::cli_state::{add_project_info_to_node_state, init_node_state, random_name};
+use ockam_api::cloud::project_node::{parse_label, ProjectNodes, RegisterProjectNode};
+use ockam_api::logs::LogSink;
 use ockam_api::nodes::models::transport::CreateTransportJson;
+use ockam_api::nodes::pairing::PairingApproval;
+use ockam_api::nodes::secrets::parse_env_file;
 use ockam_api::nodes::service::NodeManagerTrustOptions;
 use ockam_api::nodes::BackgroundNode;
 use ockam_api::nodes::InMemoryNode;
@@ -28,23 +35,30 @@ use ockam_api::{
 };
 use ockam_core::api::{Request, ResponseHeader, Status};
 use ockam_core::{route, LOCAL};
+use ockam_transport_tcp::{CustomDnsResolver, DnsServer};
 
+use crate::dry_run::{Plan, Step};
 use crate::node::util::{spawn_node, NodeManagerDefaults};
 use crate::secure_channel::listener::create as secure_channel_listener;
 use crate::service::config::Config;
 use crate::terminal::OckamColor;
 use crate::util::api::TrustContextOpts;
+use crate::util::duration::duration_parser;
 use crate::util::{api, parse_node_name};
 use crate::util::{embedded_node_that_is_not_stopped, exitcode};
 use crate::util::{local_cmd, node_rpc};
 use crate::{docs, shutdown, CommandGlobalOpts, Result};
 use crate::{fmt_log, fmt_ok};
 
+use super::scheduler;
 use super::show::is_node_up;
 
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
 
+/// Number of attempts to register the node in the inventory of its project
+const INVENTORY_REGISTRATION_ATTEMPTS: u32 = 5;
+
 /// Create a new node
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -64,7 +78,8 @@ pub struct CreateCommand {
     #[arg(display_order = 900, long = "exit-on-eof", short)]
     pub exit_on_eof: bool,
 
-    /// TCP listener address
+    /// TCP listener address. An IPv6 address like `[::1]:4000` can be used, and the
+    /// unspecified IPv6 address `[::]` accepts both the IPv4 and IPv6 connections
     #[arg(
         display_order = 900,
         long,
@@ -74,10 +89,60 @@ pub struct CreateCommand {
     )]
     pub tcp_listener_address: String,
 
+    /// Address on which the node accepts QUIC connections, on a single UDP port.
+    /// Secure channels can be created via this listener
+    #[arg(display_order = 900, long, value_name = "SOCKET_ADDRESS")]
+    pub quic_listener_address: Option<String>,
+
+    /// Address on which the node accepts WebSocket connections.
+    /// Secure channels can be created via this listener
+    #[arg(display_order = 900, long, value_name = "SOCKET_ADDRESS")]
+    pub websocket_listener_address: Option<String>,
+
+    /// Proxy used to open the outgoing TCP connections, as `http://[user:password@]host:port`,
+    /// `socks5://[user:password@]host:port` or `socks5h://[user:password@]host:port`.
+    /// When it is not given, the HTTPS_PROXY or ALL_PROXY environment variables are used
+    #[arg(display_order = 900, long, value_name = "URL", value_parser = parse_proxy)]
+    pub proxy: Option<String>,
+
+    /// DNS server used to resolve the host names of the peers instead of the servers of
+    /// the system. It can be given several times. A server is given as `IP[:PORT]`, or as
+    /// `tls://NAME@IP[:PORT]` and `https://NAME@IP[:PORT]` for DNS over TLS and DNS over HTTPS,
+    /// where NAME is the name of the certificate of the server
+    #[arg(display_order = 900, long = "dns-server", value_name = "SERVER", value_parser = parse_dns_server)]
+    pub dns_servers: Vec<String>,
+
+    /// Advertise the node on the local network, so that it can be found with `ockam discover`.
+    /// The TCP listener must accept the connections of the local network, for example with
+    /// `--tcp-listener-address 0.0.0.0:4000`
+    #[arg(display_order = 900, long)]
+    pub advertise: bool,
+
+    /// Keep authorizing the peers whose credentials have expired, from their cached attributes,
+    /// during this time. This keeps the existing portals open while the project authority
+    /// can't be reached to issue new credentials: the grace period only applies once a request
+    /// to the authority has failed, and until it answers again. At most 24h
+    #[arg(display_order = 900, long, value_name = "DURATION", value_parser = parse_grace_period)]
+    pub credential_grace_period: Option<Duration>,
+
+    /// Enable the endpoints used to debug the node, like the heap statistics returned by
+    /// `ockam debug heap`
+    #[arg(display_order = 900, long)]
+    pub enable_debug_endpoints: bool,
+
+    /// Policy file, in YAML or JSON, limiting the concurrent streams and the bytes per day
+    /// forwarded by the relays registered on this node. See the examples
+    #[arg(display_order = 900, long, value_name = "PATH")]
+    pub relay_quotas: Option<PathBuf>,
+
     /// `node create` started a child process to run this node in foreground.
     #[arg(long, hide = true)]
     pub child_process: bool,
 
+    /// The node is run as a Windows service by the Service Control Manager.
+    #[arg(long, hide = true, requires = "foreground")]
+    pub windows_service: bool,
+
     /// JSON config to setup a foreground node
     ///
     /// This argument is currently ignored on background nodes.  Node
@@ -109,6 +174,34 @@ pub struct CreateCommand {
 
     #[command(flatten)]
     pub trust_context_opts: TrustContextOpts,
+
+    /// Label published to the project inventory, as `key=value`. Can be repeated
+    #[arg(long = "label", value_name = "KEY=VALUE", value_parser = parse_label)]
+    pub labels: Vec<(String, String)>,
+
+    /// Environment file defining secrets for the node services, as `NAME=VALUE` lines.
+    /// The secrets are only kept in memory and are referred to as `secret:NAME`
+    #[arg(long, value_name = "PATH")]
+    pub env_file: Option<PathBuf>,
+
+    /// Ask for an approval on the terminal of the node before accepting the first secure channel
+    /// from an unknown identity. The decision is pinned for the next secure channels
+    #[arg(long, requires = "foreground", conflicts_with = "pairing_hook")]
+    pub pairing: bool,
+
+    /// Program approving the first secure channel from an unknown identity. It is called with
+    /// the identifier as argument, and the decision is pinned for the next secure channels
+    #[arg(long, value_name = "PROGRAM")]
+    pub pairing_hook: Option<String>,
+
+    /// Destinations of the logs of the node: `file`, `journald` or `otlp`.
+    /// Several sinks can be given, separated by commas. The logs are written to files by default
+    #[arg(long = "log-sink", value_name = "SINK", value_delimiter = ',')]
+    pub log_sinks: Vec<LogSink>,
+
+    /// Endpoint of the OpenTelemetry collector receiving the logs of the `otlp` sink
+    #[arg(long, value_name = "URL", requires = "log_sinks")]
+    pub otlp_logs_endpoint: Option<String>,
 }
 
 impl Default for CreateCommand {
@@ -118,8 +211,17 @@ impl Default for CreateCommand {
             node_name: random_name(),
             exit_on_eof: false,
             tcp_listener_address: node_manager_defaults.tcp_listener_address,
+            quic_listener_address: None,
+            websocket_listener_address: None,
+            proxy: None,
+            dns_servers: vec![],
+            advertise: false,
+            credential_grace_period: None,
+            enable_debug_endpoints: false,
+            relay_quotas: None,
             foreground: false,
             child_process: false,
+            windows_service: false,
             launch_config: None,
             vault: None,
             identity: None,
@@ -129,6 +231,12 @@ impl Default for CreateCommand {
             authority_identity: None,
             credential: None,
             trust_context_opts: node_manager_defaults.trust_context_opts,
+            labels: vec![],
+            env_file: None,
+            pairing: false,
+            pairing_hook: None,
+            log_sinks: vec![],
+            otlp_logs_endpoint: None,
         }
     }
 }
@@ -146,7 +254,14 @@ impl CreateCommand {
                 }
             }
         }
+        if opts.global_args.dry_run {
+            return local_cmd(self.plan(&opts).and_then(|plan| plan.print(&opts)));
+        }
         if self.foreground {
+            #[cfg(windows)]
+            if self.windows_service {
+                return local_cmd(super::windows::run(opts, self));
+            }
             local_cmd(foreground_mode(opts, self));
         } else {
             node_rpc(background_mode, (opts, self))
@@ -156,7 +271,7 @@ impl CreateCommand {
     pub fn logging_to_file(&self) -> bool {
         // Background nodes will spawn a foreground node in a child process.
         // In that case, the child process will log to files.
-        if self.child_process {
+        if self.child_process || self.windows_service {
             true
         }
         // The main process will log to stdout only if it's a foreground node.
@@ -165,9 +280,71 @@ impl CreateCommand {
         }
     }
 
+    fn pairing_approval(&self) -> Option<PairingApproval> {
+        match &self.pairing_hook {
+            Some(program) => Some(PairingApproval::Hook(program.clone())),
+            None if self.pairing => Some(PairingApproval::Prompt),
+            None => None,
+        }
+    }
+
     pub fn logging_to_stdout(&self) -> bool {
         !self.logging_to_file()
     }
+
+    /// Return true if some options are stored in the setup of the node state
+    fn has_setup_options(&self) -> bool {
+        !self.labels.is_empty()
+            || self.env_file.is_some()
+            || self.pairing_hook.is_some()
+            || !self.log_sinks.is_empty()
+            || self.quic_listener_address.is_some()
+            || self.websocket_listener_address.is_some()
+            || self.proxy.is_some()
+            || !self.dns_servers.is_empty()
+            || self.credential_grace_period.is_some()
+            || self.advertise
+            || self.enable_debug_endpoints
+            || self.relay_quotas.is_some()
+    }
+
+    /// Return the state changes made to create the node, for `--dry-run`
+    pub(crate) fn plan(&self, opts: &CommandGlobalOpts) -> miette::Result<Plan> {
+        let node_name = parse_node_name(&self.node_name)?;
+        let mut plan = Plan::new("node create");
+        let node_exists = opts.state.nodes.exists(&node_name);
+        // A background node state is always initialized, a foreground node state only once
+        if !self.foreground || !node_exists {
+            match &self.vault {
+                Some(vault) => {
+                    opts.state.vaults.get(vault)?;
+                }
+                None if opts.state.vaults.default().is_err() => {
+                    plan.push(Step::state_change("create", "vault", "<random name>"))
+                }
+                None => (),
+            }
+            if opts
+                .state
+                .identities
+                .get_or_default(self.identity.as_deref())
+                .is_err()
+            {
+                let name = self.identity.as_deref().unwrap_or("<random name>");
+                plan.push(Step::state_change("create", "identity", name));
+            }
+            let action = if node_exists { "overwrite" } else { "create" };
+            plan.push(Step::state_change(action, "node", &node_name));
+            if !self.foreground && self.has_setup_options() {
+                plan.push(Step::state_change("update", "node setup", &node_name));
+            }
+        }
+        plan.push(Step::StartNode {
+            name: node_name,
+            foreground: self.foreground,
+        });
+        Ok(plan)
+    }
 }
 
 pub fn parse_launch_config(config_or_path: &str) -> Result<Config> {
@@ -182,6 +359,27 @@ pub fn parse_launch_config(config_or_path: &str) -> Result<Config> {
     }
 }
 
+fn parse_proxy(proxy: &str) -> Result<String> {
+    TcpProxy::from_str(proxy).into_diagnostic()?;
+    Ok(proxy.to_string())
+}
+
+fn parse_dns_server(server: &str) -> Result<String> {
+    DnsServer::from_str(server).into_diagnostic()?;
+    Ok(server.to_string())
+}
+
+fn parse_grace_period(grace_period: &str) -> std::result::Result<Duration, clap::Error> {
+    let grace_period = duration_parser(grace_period)?;
+    if grace_period > MAX_ATTRIBUTES_GRACE_PERIOD {
+        return Err(clap::Error::raw(
+            clap::error::ErrorKind::InvalidValue,
+            "The credential grace period can't be longer than 24h",
+        ));
+    }
+    Ok(grace_period)
+}
+
 // Create a new node running in the background (i.e. another, new OS process)
 pub(crate) async fn background_mode(
     ctx: Context,
@@ -244,7 +442,7 @@ pub(crate) async fn background_mode(
 }
 
 // Create a new node in the foreground (i.e. in this OS process)
-fn foreground_mode(opts: CommandGlobalOpts, cmd: CreateCommand) -> miette::Result<()> {
+pub(crate) fn foreground_mode(opts: CommandGlobalOpts, cmd: CreateCommand) -> miette::Result<()> {
     embedded_node_that_is_not_stopped(run_foreground_node, (opts, cmd))?;
     Ok(())
 }
@@ -290,10 +488,73 @@ async fn run_foreground_node(
 
     let node_state = opts.state.nodes.get(&node_name)?;
     node_state.set_pid(process::id() as i32)?;
+    let mut setup = node_state.config().setup_mut();
+    if !cmd.labels.is_empty() {
+        setup = setup.set_labels(cmd.labels.iter().cloned().collect());
+    }
+    if let Some(env_file) = &cmd.env_file {
+        setup = setup.set_env_file(canonical_env_file(env_file)?);
+    }
+    if let Some(pairing) = cmd.pairing_approval() {
+        setup = setup.set_pairing(pairing);
+    }
+    if !cmd.log_sinks.is_empty() {
+        setup = setup.set_log_sinks(cmd.log_sinks.clone());
+    }
+    if let Some(endpoint) = &cmd.otlp_logs_endpoint {
+        setup = setup.set_otlp_logs_endpoint(endpoint.clone());
+    }
+    if let Some(address) = &cmd.quic_listener_address {
+        setup = setup.set_quic_listener_address(address.clone());
+    }
+    if let Some(address) = &cmd.websocket_listener_address {
+        setup = setup.set_websocket_listener_address(address.clone());
+    }
+    if let Some(proxy) = &cmd.proxy {
+        setup = setup.set_proxy(proxy.clone());
+    }
+    if !cmd.dns_servers.is_empty() {
+        setup = setup.set_dns_servers(cmd.dns_servers.clone());
+    }
+    if let Some(grace_period) = cmd.credential_grace_period {
+        setup = setup.set_credential_grace_period(grace_period);
+    }
+    if cmd.advertise {
+        setup = setup.set_advertise();
+    }
+    if cmd.enable_debug_endpoints {
+        setup = setup.set_debug_endpoints();
+    }
+    if let Some(relay_quotas) = &cmd.relay_quotas {
+        setup = setup.set_relay_quotas(canonical_relay_quotas(relay_quotas)?);
+    }
+    let proxy = match &setup.proxy {
+        Some(proxy) => Some(TcpProxy::from_str(proxy).into_diagnostic()?),
+        None => TcpProxy::from_env().into_diagnostic()?,
+    };
+    if let Some(proxy) = &proxy {
+        info!(%proxy, "opening the outgoing TCP connections via a proxy");
+    }
+    tcp.set_proxy(proxy);
+    if !setup.dns_servers.is_empty() {
+        let servers = setup
+            .dns_servers
+            .iter()
+            .map(|s| DnsServer::from_str(s))
+            .collect::<ockam::Result<Vec<_>>>()
+            .into_diagnostic()?;
+        info!(?servers, "resolving the host names with custom DNS servers");
+        tcp.set_resolver(Arc::new(CustomDnsResolver::new(servers).into_diagnostic()?));
+    }
+    let env_file = setup.env_file.clone();
+    let quic_listener_address = setup.quic_listener_address.clone();
+    let websocket_listener_address = setup.websocket_listener_address.clone();
+    let credential_grace_period = setup.credential_grace_period;
+    let advertise = setup.advertise.unwrap_or(false);
+    let debug_endpoints = setup.debug_endpoints.unwrap_or(false);
+    let relay_quotas = setup.relay_quotas.clone();
     node_state.set_setup(
-        &node_state
-            .config()
-            .setup_mut()
+        &setup
             .set_verbose(opts.global_args.verbose)
             .set_api_transport(
                 CreateTransportJson::new(
@@ -307,15 +568,24 @@ async fn run_foreground_node(
 
     let pre_trusted_identities = load_pre_trusted_identities(&cmd)?;
 
+    let mut general_options = NodeManagerGeneralOptions::new(
+        opts.state.clone(),
+        cmd.node_name.clone(),
+        pre_trusted_identities,
+        cmd.launch_config.is_none(),
+        true,
+    )
+    .with_version(crate_version!())
+    .with_debug_endpoints(debug_endpoints);
+    if let Some(grace_period) = credential_grace_period {
+        general_options = general_options.with_credential_grace_period(grace_period);
+    }
+    if let Some(relay_quotas) = &relay_quotas {
+        general_options = general_options.with_relay_quotas(load_relay_quotas(relay_quotas)?);
+    }
     let node_man = InMemoryNode::new(
         &ctx,
-        NodeManagerGeneralOptions::new(
-            opts.state.clone(),
-            cmd.node_name.clone(),
-            pre_trusted_identities,
-            cmd.launch_config.is_none(),
-            true,
-        ),
+        general_options,
         NodeManagerTransportOptions::new(
             listener.flow_control_id().clone(),
             tcp.async_try_clone().await.into_diagnostic()?,
@@ -324,7 +594,52 @@ async fn run_foreground_node(
     )
     .await
     .into_diagnostic()?;
-    let node_manager_worker = NodeManagerWorker::new(Arc::new(node_man));
+
+    if let Some(env_file) = &env_file {
+        load_secrets(&node_man, env_file)?;
+    }
+
+    #[cfg(feature = "quic")]
+    if let Some(address) = &quic_listener_address {
+        let quic_listener = node_man
+            .create_quic_listener(&ctx, address)
+            .await
+            .into_diagnostic()?;
+        info!(address = %quic_listener.socket_address(), "accepting QUIC connections");
+    }
+    #[cfg(not(feature = "quic"))]
+    if quic_listener_address.is_some() {
+        return Err(miette!(
+            "This build doesn't support the QUIC listener of the nodes"
+        ));
+    }
+
+    #[cfg(feature = "websocket")]
+    if let Some(address) = &websocket_listener_address {
+        let socket_address = node_man
+            .create_websocket_listener(&ctx, address)
+            .await
+            .into_diagnostic()?;
+        info!(address = %socket_address, "accepting WebSocket connections");
+    }
+    #[cfg(not(feature = "websocket"))]
+    if websocket_listener_address.is_some() {
+        return Err(miette!(
+            "This build doesn't support the WebSocket listener of the nodes"
+        ));
+    }
+
+    if advertise {
+        if listener.socket_address().ip().is_loopback() {
+            warn!(address = %listener.socket_address(), "the advertised TCP listener only accepts local connections");
+        }
+        node_man
+            .start_advertising(listener.socket_address().port())
+            .into_diagnostic()?;
+    }
+
+    let node_man = Arc::new(node_man);
+    let node_manager_worker = NodeManagerWorker::new(node_man.clone());
 
     ctx.flow_controls()
         .add_consumer(NODEMANAGER_ADDR, listener.flow_control_id());
@@ -332,6 +647,18 @@ async fn run_foreground_node(
         .await
         .into_diagnostic()?;
 
+    // The project can be unreachable for a while, so the registration is retried
+    // without delaying the start of the node
+    {
+        let ctx = ctx.async_try_clone().await.into_diagnostic()?;
+        let opts = opts.clone();
+        let node_man = node_man.clone();
+        let node_name = node_name.clone();
+        tokio::spawn(async move {
+            register_in_project_inventory_with_retries(&ctx, &opts, &node_man, &node_name).await
+        });
+    }
+
     if let Some(config) = &cmd.launch_config {
         if start_services(&ctx, config).await.is_err() {
             //TODO: Process should terminate on any error during its setup phase,
@@ -346,10 +673,29 @@ async fn run_foreground_node(
             ctx.stop().await.into_diagnostic()?;
             return Err(miette!("Failed to start services"));
         }
+        if let Some(tasks) = &config.scheduled_tasks {
+            scheduler::start(&ctx, node_man.clone(), tasks).await?;
+        }
     }
 
     // Create a channel for communicating back to the main thread
     let (tx, mut rx) = tokio::sync::mpsc::channel(2);
+
+    // There are no SIGTERM signals on Windows, a stop request is used instead
+    #[cfg(windows)]
+    {
+        let tx = tx.clone();
+        let node_state = node_state.clone();
+        node_state.take_stop_request();
+        tokio::spawn(async move {
+            while !node_state.take_stop_request() {
+                sleep(Duration::from_millis(500)).await;
+            }
+            info!(node_name = %node_state.name(), "stop request received");
+            let _ = tx.send(()).await;
+        });
+    }
+
     shutdown::wait(
         opts.terminal.clone(),
         cmd.exit_on_eof,
@@ -371,6 +717,104 @@ async fn run_foreground_node(
     Ok(())
 }
 
+/// Register the node in the inventory of its project, retrying with an increasing delay
+/// when the registration fails
+async fn register_in_project_inventory_with_retries(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_manager: &InMemoryNode,
+    node_name: &str,
+) {
+    let mut delay = Duration::from_secs(1);
+    for attempt in 1..=INVENTORY_REGISTRATION_ATTEMPTS {
+        match register_in_project_inventory(ctx, opts, node_manager, node_name).await {
+            Ok(()) => return,
+            Err(e) => warn!(
+                %node_name,
+                attempt, "the node could not be registered in the project inventory: {e:?}"
+            ),
+        }
+        if attempt < INVENTORY_REGISTRATION_ATTEMPTS {
+            sleep(delay).await;
+            delay *= 2;
+        }
+    }
+}
+
+/// Publish the node labels to the inventory of the node project, if any
+async fn register_in_project_inventory(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_manager: &InMemoryNode,
+    node_name: &str,
+) -> miette::Result<()> {
+    let node_state = opts.state.nodes.get(node_name)?;
+    let setup = node_state.config().setup();
+    let project = match &setup.project {
+        Some(project) if !setup.labels.is_empty() => project,
+        _ => return Ok(()),
+    };
+    let controller = node_manager.create_controller().await?;
+    let registration = RegisterProjectNode::new(
+        node_name,
+        node_state.config().identifier()?,
+        setup.labels.clone(),
+    );
+    controller
+        .register_project_node(ctx, project.id.clone(), registration)
+        .await?;
+    info!(%node_name, project = %project.name, "node registered in the project inventory");
+    Ok(())
+}
+
+/// Return the absolute path of an environment file, so that the node process can find it
+fn canonical_env_file(env_file: &Path) -> miette::Result<PathBuf> {
+    std::fs::canonicalize(env_file)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Cannot read the environment file {}", env_file.display()))
+}
+
+/// Load the secrets defined in an environment file in the node secret store
+fn load_secrets(node_manager: &InMemoryNode, env_file: &Path) -> miette::Result<()> {
+    let contents = std::fs::read_to_string(env_file)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Cannot read the environment file {}", env_file.display()))?;
+    let secrets = parse_env_file(&contents)
+        .map_err(|e| miette!("Invalid environment file {}: {e}", env_file.display()))?;
+    info!(env_file = %env_file.display(), count = secrets.len(), "secrets loaded");
+    node_manager.secrets().extend(secrets);
+    Ok(())
+}
+
+/// Return the absolute path of a relay quotas policy file, after checking that it is valid
+fn canonical_relay_quotas(relay_quotas: &Path) -> miette::Result<PathBuf> {
+    load_relay_quotas(relay_quotas)?;
+    std::fs::canonicalize(relay_quotas)
+        .into_diagnostic()
+        .wrap_err_with(|| {
+            format!(
+                "Cannot read the relay quotas file {}",
+                relay_quotas.display()
+            )
+        })
+}
+
+/// Read the quotas of the relays registered on the node from a YAML or JSON policy file
+fn load_relay_quotas(relay_quotas: &Path) -> miette::Result<RelayQuotas> {
+    let contents = std::fs::read_to_string(relay_quotas)
+        .into_diagnostic()
+        .wrap_err_with(|| {
+            format!(
+                "Cannot read the relay quotas file {}",
+                relay_quotas.display()
+            )
+        })?;
+    let quotas: RelayQuotas = serde_yaml::from_str(&contents)
+        .map_err(|e| miette!("Invalid relay quotas file {}: {e}", relay_quotas.display()))?;
+    info!(relay_quotas = %relay_quotas.display(), relays = quotas.relays.len(), "relay quotas loaded");
+    Ok(quotas)
+}
+
 pub fn load_pre_trusted_identities(cmd: &CreateCommand) -> Result<Option<PreTrustedIdentities>> {
     let command = cmd.clone();
     let pre_trusted_identities = match (
@@ -386,7 +830,7 @@ pub fn load_pre_trusted_identities(cmd: &CreateCommand) -> Result<Option<PreTrus
     Ok(pre_trusted_identities)
 }
 
-async fn start_services(ctx: &Context, cfg: &Config) -> miette::Result<()> {
+pub(super) async fn start_services(ctx: &Context, cfg: &Config) -> miette::Result<()> {
     let config = {
         if let Some(sc) = &cfg.startup_services {
             sc.clone()
@@ -422,7 +866,7 @@ async fn start_services(ctx: &Context, cfg: &Config) -> miette::Result<()> {
     Ok(())
 }
 
-async fn send_req_to_node_manager<T>(ctx: &Context, req: Request<T>) -> Result<()>
+pub(super) async fn send_req_to_node_manager<T>(ctx: &Context, req: Request<T>) -> Result<()>
 where
     T: Encode<()>,
 {
@@ -450,6 +894,51 @@ pub async fn spawn_background_node(
         cmd.identity.as_deref(),
     )
     .await?;
+    if cmd.has_setup_options() {
+        let node_state = opts.state.nodes.get(&node_name)?;
+        let mut setup = node_state.config().setup_mut();
+        if !cmd.labels.is_empty() {
+            setup = setup.set_labels(cmd.labels.iter().cloned().collect());
+        }
+        // Only the path of the file is given to the node process, never the secrets
+        if let Some(env_file) = &cmd.env_file {
+            setup = setup.set_env_file(canonical_env_file(env_file)?);
+        }
+        if let Some(pairing) = cmd.pairing_approval() {
+            setup = setup.set_pairing(pairing);
+        }
+        if !cmd.log_sinks.is_empty() {
+            setup = setup.set_log_sinks(cmd.log_sinks.clone());
+        }
+        if let Some(endpoint) = &cmd.otlp_logs_endpoint {
+            setup = setup.set_otlp_logs_endpoint(endpoint.clone());
+        }
+        if let Some(address) = &cmd.quic_listener_address {
+            setup = setup.set_quic_listener_address(address.clone());
+        }
+        if let Some(address) = &cmd.websocket_listener_address {
+            setup = setup.set_websocket_listener_address(address.clone());
+        }
+        if let Some(proxy) = &cmd.proxy {
+            setup = setup.set_proxy(proxy.clone());
+        }
+        if !cmd.dns_servers.is_empty() {
+            setup = setup.set_dns_servers(cmd.dns_servers.clone());
+        }
+        if let Some(grace_period) = cmd.credential_grace_period {
+            setup = setup.set_credential_grace_period(grace_period);
+        }
+        if cmd.advertise {
+            setup = setup.set_advertise();
+        }
+        if cmd.enable_debug_endpoints {
+            setup = setup.set_debug_endpoints();
+        }
+        if let Some(relay_quotas) = &cmd.relay_quotas {
+            setup = setup.set_relay_quotas(canonical_relay_quotas(relay_quotas)?);
+        }
+        node_state.set_setup(&setup)?;
+    }
 
     let trust_context_path = match cmd.trust_context_opts.trust_context.clone() {
         Some(tc) => {
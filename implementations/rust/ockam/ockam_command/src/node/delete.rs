@@ -61,7 +61,7 @@ fn run_impl(opts: CommandGlobalOpts, cmd: DeleteCommand) -> miette::Result<()> {
         DeleteMode::Selected(opts.terminal.select_multiple(
             "Select one or more nodes that you want to delete".to_string(),
             nodes_names,
-        ))
+        )?)
     } else {
         DeleteMode::Default
     };
@@ -91,7 +91,7 @@ fn run_impl(opts: CommandGlobalOpts, cmd: DeleteCommand) -> miette::Result<()> {
             if opts.terminal.confirm_interactively(format!(
                 "Would you like to delete these items : {:?}?",
                 selected_node_names
-            )) {
+            ))? {
                 let output = selected_node_names
                     .iter()
                     .map(|name| {
@@ -0,0 +1,28 @@
+mod generate;
+
+pub(crate) use generate::GenerateCommand;
+
+use crate::CommandGlobalOpts;
+use clap::{Args, Subcommand};
+
+/// Manage the firewall rules of the host of a node
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, subcommand_required = true)]
+pub struct FirewallRulesCommand {
+    #[command(subcommand)]
+    subcommand: FirewallRulesSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum FirewallRulesSubcommand {
+    #[command(display_order = 800)]
+    Generate(GenerateCommand),
+}
+
+impl FirewallRulesCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            FirewallRulesSubcommand::Generate(c) => c.run(options),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt::Write;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::identity::TimestampInSeconds;
+use ockam_api::cli_state::StateDirTrait;
+
+use crate::node::get_node_name;
+use crate::output::human_readable_time;
+use crate::terminal::OckamColor;
+use crate::util::local_cmd;
+use crate::{docs, fmt_log, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/history/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/history/after_long_help.txt");
+
+/// Display the history of the changes made to the resources of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct HistoryCommand {
+    /// Name of the node
+    node_name: Option<String>,
+}
+
+impl HistoryCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: HistoryCommand) -> miette::Result<()> {
+    let node_name = get_node_name(&opts.state, &cmd.node_name);
+    let history = opts.state.nodes.get(&node_name)?.history()?;
+
+    let mut plain = fmt_ok!(
+        "The node {} has {} revisions\n",
+        node_name.color(OckamColor::PrimaryResource.color()),
+        history.len()
+    );
+    for entry in &history {
+        let changes = if entry.previous.is_none() {
+            "created".to_string()
+        } else {
+            entry.changes.join(", ")
+        };
+        writeln!(
+            plain,
+            "{}",
+            fmt_log!(
+                "Revision {} at {} by {}: {}",
+                entry
+                    .revision
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color()),
+                human_readable_time(TimestampInSeconds(entry.changed_at)),
+                entry.actor,
+                changes
+            )
+        )
+        .into_diagnostic()?;
+        writeln!(plain, "{}", fmt_log!("    {}", entry.command)).into_diagnostic()?;
+    }
+
+    opts.terminal
+        .stdout()
+        .plain(plain.trim_end())
+        .machine(
+            history
+                .iter()
+                .map(|e| e.revision.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+        .json(serde_json::to_string_pretty(&history).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::env::current_exe;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+
+use ockam_api::cli_state::{StateDirTrait, StateItemTrait};
+
+use crate::node::get_node_name;
+use crate::node::service_manager::{NodeService, ServiceManager};
+use crate::node::util::NodeManagerDefaults;
+use crate::util::local_cmd;
+use crate::{docs, fmt_log, fmt_ok, CommandGlobalOpts, OckamColor};
+
+const LONG_ABOUT: &str = include_str!("./static/install_service/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/install_service/after_long_help.txt");
+
+/// Run a node as a service of the operating system
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct InstallServiceCommand {
+    /// Name of the node to run as a service
+    node_name: Option<String>,
+
+    /// Install the service for the whole system instead of the current user
+    #[arg(long)]
+    system: bool,
+
+    /// TCP listener address of the node. Defaults to the current address of the node, if it exists
+    #[arg(long, value_name = "SOCKET_ADDRESS")]
+    tcp_listener_address: Option<String>,
+
+    /// Only print the service definition, without installing it
+    #[arg(long)]
+    print: bool,
+}
+
+impl InstallServiceCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: InstallServiceCommand) -> miette::Result<()> {
+    let service_manager = ServiceManager::detect()?;
+    let node_name = get_node_name(&opts.state, &cmd.node_name);
+
+    let existing_node = opts.state.nodes.get(&node_name).ok();
+    if let Some(node_state) = &existing_node {
+        if node_state.is_running() && !cmd.print {
+            return Err(miette!(
+                "The node {node_name} is already running. Please stop it with \
+                `ockam node stop {node_name}` before running it as a service"
+            ));
+        }
+    }
+    let tcp_listener_address = match (cmd.tcp_listener_address, &existing_node) {
+        (Some(address), _) => address,
+        (None, Some(node_state)) => node_state
+            .config()
+            .setup()
+            .api_transport()
+            .map(|t| t.addr.to_string())
+            .unwrap_or_else(|_| NodeManagerDefaults::default().tcp_listener_address),
+        (None, None) => NodeManagerDefaults::default().tcp_listener_address,
+    };
+
+    let service = NodeService {
+        executable: current_exe()
+            .and_then(std::fs::canonicalize)
+            .into_diagnostic()?,
+        ockam_home: opts.state.dir.clone(),
+        tcp_listener_address,
+        stdout_log: opts.state.nodes.stdout_logs(&node_name)?,
+        stderr_log: opts.state.nodes.stderr_logs(&node_name)?,
+        system: cmd.system,
+        node_name,
+    };
+
+    if cmd.print {
+        opts.terminal
+            .stdout()
+            .plain(service_manager.definition(&service))
+            .write_line()?;
+        return Ok(());
+    }
+
+    let path = service_manager.install(&service)?;
+    let service_name = service_manager.service_name(&service.node_name);
+    opts.terminal
+        .stdout()
+        .plain(
+            fmt_ok!(
+                "The node {} is now running as the service {}\n",
+                service
+                    .node_name
+                    .clone()
+                    .color(OckamColor::PrimaryResource.color()),
+                service_name
+                    .clone()
+                    .color(OckamColor::PrimaryResource.color())
+            ) + &fmt_log!("The service definition was written to {}", path.display()),
+        )
+        .machine(&service_name)
+        .json(serde_json::json!({
+            "node": service.node_name,
+            "service": service_name,
+            "path": path,
+        }))
+        .write_line()?;
+    Ok(())
+}
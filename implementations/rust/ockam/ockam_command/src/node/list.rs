@@ -1,20 +1,23 @@
+use std::time::{Duration, Instant};
+
 use clap::Args;
 use colorful::Colorful;
 use indoc::formatdoc;
 use miette::Context as _;
-use miette::IntoDiagnostic;
+use miette::{miette, IntoDiagnostic};
 use serde::Serialize;
 use tokio::sync::Mutex;
 use tokio::try_join;
 
-use ockam::Context;
-use ockam_api::cli_state::StateDirTrait;
+use ockam::{AsyncTryClone, Context};
+use ockam_api::cli_state::{NodeSummary, NodesState, StateDirTrait, StateItemTrait};
 use ockam_api::nodes::models::base::NodeStatus;
 use ockam_api::nodes::BackgroundNode;
 
 use crate::node::get_default_node_name;
 use crate::output::Output;
 use crate::terminal::OckamColor;
+use crate::util::duration::duration_parser;
 use crate::util::{api, node_rpc};
 use crate::{docs, CommandGlobalOpts, Result};
 
@@ -22,6 +25,11 @@ const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
 
+/// Maximum time to wait for the status of a node whose process is running
+const RUNNING_NODE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Maximum time to wait for the status of a node whose recorded process is not running
+const STOPPED_NODE_TIMEOUT: Duration = Duration::from_millis(500);
+
 /// List nodes
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -29,7 +37,15 @@ const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
     before_help = docs::before_help(PREVIEW_TAG),
     after_long_help = docs::after_help(AFTER_LONG_HELP)
 )]
-pub struct ListCommand {}
+pub struct ListCommand {
+    /// Check the health of every node: ping its API, verify its identity and measure its latency
+    #[arg(long)]
+    check: bool,
+
+    /// Maximum time to wait for the response of each node when checking their health
+    #[arg(long, default_value = "5s", value_parser = duration_parser, requires = "check")]
+    timeout: Duration,
+}
 
 impl ListCommand {
     pub fn run(self, options: CommandGlobalOpts) {
@@ -39,7 +55,7 @@ impl ListCommand {
 
 async fn run_impl(
     ctx: Context,
-    (opts, _cmd): (CommandGlobalOpts, ListCommand),
+    (opts, cmd): (CommandGlobalOpts, ListCommand),
 ) -> miette::Result<()> {
     // Before printing node states we verify them.
     // We send a QueryStatus request to every node on
@@ -47,10 +63,17 @@ async fn run_impl(
     // one in config, we update the pid stored in the config.
     // This should only happen if the node has failed in the past,
     // and has been restarted by something that is not this CLI.
-    let node_names: Vec<_> = {
-        let nodes_states = opts.state.nodes.list()?;
-        nodes_states.iter().map(|s| s.name().to_string()).collect()
-    };
+    let node_names: Vec<_> = opts
+        .state
+        .nodes
+        .list_items_names()?
+        .into_iter()
+        .filter(|name| opts.state.nodes.exists(name))
+        .collect();
+
+    if cmd.check {
+        return check_nodes(ctx, &opts, node_names, cmd.timeout).await;
+    }
 
     let nodes = get_nodes_info(ctx, &opts, node_names).await?;
     print_nodes_info(&opts, nodes)?;
@@ -58,60 +81,218 @@ async fn run_impl(
     Ok(())
 }
 
-pub async fn get_nodes_info(
+/// Check all the nodes concurrently and print a summary of their health.
+///
+/// An error is returned if at least one node is not healthy, so that scripts can rely on the
+/// exit code of the command.
+async fn check_nodes(
     ctx: Context,
     opts: &CommandGlobalOpts,
     node_names: Vec<String>,
-) -> Result<Vec<NodeListOutput>> {
-    let mut nodes: Vec<NodeListOutput> = Vec::new();
-    let default_node_name = get_default_node_name(&opts.state);
+    timeout: Duration,
+) -> miette::Result<()> {
+    let mut checks = vec![];
     for node_name in node_names {
-        let node = BackgroundNode::create(&ctx, &opts.state, &node_name).await?;
-
-        let is_finished: Mutex<bool> = Mutex::new(false);
-
-        let get_node_status = async {
-            let result: miette::Result<NodeStatus> = node.ask(&ctx, api::query_status()).await;
-            let node_status = match result {
-                Ok(node_status) => {
-                    if let Ok(node_state) = opts.state.nodes.get(&node_name) {
-                        // Update the persisted configuration data with the pids
-                        // responded by nodes.
-                        if node_state.pid()? != Some(node_status.pid) {
-                            node_state
-                                .set_pid(node_status.pid)
-                                .context("Failed to update pid for node {node_name}")?;
-                        }
-                    }
-                    node_status
-                }
-                Err(_) => NodeStatus::new(node_name.to_string(), "Not running".to_string(), 0, 0),
-            };
-            *is_finished.lock().await = true;
-            Ok(node_status)
-        };
+        let ctx = ctx.async_try_clone().await.into_diagnostic()?;
+        let opts = opts.clone();
+        checks.push(tokio::spawn(async move {
+            check_node(&ctx, &opts, node_name, timeout).await
+        }));
+    }
+
+    let mut nodes = vec![];
+    for check in checks {
+        nodes.push(check.await.into_diagnostic()?);
+    }
+    let summary = NodesCheckSummary::new(nodes);
 
-        let output_messages = vec![format!(
-            "Retrieving node {}...\n",
-            node_name
+    let mut plain = opts.terminal.build_list(
+        &summary.nodes,
+        "Nodes health",
+        "No nodes found on this system.",
+    )?;
+    if summary.total > 0 {
+        plain.push_str(&fmt_log!(
+            "{} of {} nodes are healthy",
+            summary
+                .healthy
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            summary
+                .total
                 .to_string()
                 .color(OckamColor::PrimaryResource.color())
-        )];
-        let progress_output = opts
-            .terminal
-            .progress_output(&output_messages, &is_finished);
-
-        let (node_status, _) = try_join!(get_node_status, progress_output)?;
-
-        nodes.push(NodeListOutput::new(
-            node_status.node_name.to_string(),
-            node_status.status.to_string(),
-            node_status.pid,
-            node_status.node_name == default_node_name,
         ));
     }
+    opts.terminal
+        .clone()
+        .stdout()
+        .plain(plain)
+        .json(serde_json::to_string_pretty(&summary).into_diagnostic()?)
+        .write_line()?;
 
-    Ok(nodes)
+    if summary.unhealthy > 0 {
+        return Err(miette!(
+            "{} of {} nodes are not healthy",
+            summary.unhealthy,
+            summary.total
+        ));
+    }
+    Ok(())
+}
+
+async fn check_node(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: String,
+    timeout: Duration,
+) -> NodeCheckOutput {
+    let expected_identifier = opts
+        .state
+        .nodes
+        .get(&node_name)
+        .ok()
+        .and_then(|s| s.config().identifier().ok())
+        .map(|i| i.to_string());
+    let mut output = NodeCheckOutput::new(node_name);
+
+    let node = match BackgroundNode::create(ctx, &opts.state, &output.node_name).await {
+        Ok(node) => node,
+        Err(e) => return output.failed(NodeHealth::Unreachable, e.to_string()),
+    };
+    let started = Instant::now();
+    let status: NodeStatus = match node
+        .ask_with_timeout(ctx, api::query_status(), timeout)
+        .await
+    {
+        Ok(status) => status,
+        Err(e) => return output.failed(NodeHealth::Unreachable, e.to_string()),
+    };
+    output.latency_ms = Some(started.elapsed().as_millis() as u64);
+    output.version = status.version;
+    output.identifier = status.identifier;
+
+    match (&output.identifier, &expected_identifier) {
+        (Some(identifier), Some(expected)) if identifier == expected => {
+            output.health = NodeHealth::Healthy;
+            output
+        }
+        (Some(_), Some(expected)) => {
+            let error = format!("the node is expected to use the identity {expected}");
+            output.failed(NodeHealth::IdentityMismatch, error)
+        }
+        (None, _) => output.failed(
+            NodeHealth::IdentityMismatch,
+            "the node did not report its identity".to_string(),
+        ),
+        (_, None) => output.failed(
+            NodeHealth::IdentityMismatch,
+            "the identity of the node is not known locally".to_string(),
+        ),
+    }
+}
+
+pub async fn get_nodes_info(
+    ctx: Context,
+    opts: &CommandGlobalOpts,
+    node_names: Vec<String>,
+) -> Result<Vec<NodeListOutput>> {
+    let default_node_name = get_default_node_name(&opts.state);
+    let summaries: Vec<NodeSummary> = opts
+        .state
+        .nodes
+        .summaries()?
+        .into_iter()
+        .filter(|s| node_names.contains(&s.name))
+        .collect();
+    // All the processes are probed at once, then all the nodes are queried concurrently
+    let running_pids = NodesState::running_pids(summaries.iter().filter_map(|s| s.pid));
+
+    let is_finished: Mutex<bool> = Mutex::new(false);
+
+    let get_nodes_status = async {
+        let mut queries = vec![];
+        for summary in &summaries {
+            let ctx = ctx.async_try_clone().await.into_diagnostic()?;
+            let opts = opts.clone();
+            let node_name = summary.name.clone();
+            let is_running = summary
+                .pid
+                .map(|pid| running_pids.contains(&pid))
+                .unwrap_or(false);
+            queries.push(tokio::spawn(async move {
+                get_node_status(&ctx, &opts, node_name, is_running).await
+            }));
+        }
+
+        let mut nodes_status = vec![];
+        for query in queries {
+            nodes_status.push(query.await.into_diagnostic()??);
+        }
+        *is_finished.lock().await = true;
+        Ok(nodes_status)
+    };
+
+    let output_messages = vec![format!(
+        "Retrieving {} nodes...\n",
+        summaries
+            .len()
+            .to_string()
+            .color(OckamColor::PrimaryResource.color())
+    )];
+    let progress_output = opts
+        .terminal
+        .progress_output(&output_messages, &is_finished);
+
+    let (nodes_status, _): (Vec<NodeStatus>, _) = try_join!(get_nodes_status, progress_output)?;
+
+    Ok(nodes_status
+        .into_iter()
+        .map(|node_status| {
+            NodeListOutput::new(
+                node_status.node_name.to_string(),
+                node_status.status.to_string(),
+                node_status.pid,
+                node_status.node_name == default_node_name,
+            )
+        })
+        .collect())
+}
+
+/// Query the status of a node.
+///
+/// A node whose recorded process is not running is still queried, in case it was restarted by
+/// something that is not this CLI, but with a shorter timeout
+async fn get_node_status(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: String,
+    is_running: bool,
+) -> miette::Result<NodeStatus> {
+    let node = BackgroundNode::create(ctx, &opts.state, &node_name).await?;
+    let timeout = if is_running {
+        RUNNING_NODE_TIMEOUT
+    } else {
+        STOPPED_NODE_TIMEOUT
+    };
+    let result: miette::Result<NodeStatus> = node
+        .ask_with_timeout(ctx, api::query_status(), timeout)
+        .await;
+    match result {
+        Ok(node_status) => {
+            if let Ok(node_state) = opts.state.nodes.get(&node_name) {
+                // Update the persisted configuration data with the pids
+                // responded by nodes.
+                if node_state.pid()? != Some(node_status.pid) {
+                    node_state
+                        .set_pid(node_status.pid)
+                        .context("Failed to update pid for node {node_name}")?;
+                }
+            }
+            Ok(node_status)
+        }
+        Err(_) => Ok(NodeStatus::new(node_name, "Not running".to_string(), 0, 0)),
+    }
 }
 
 pub fn print_nodes_info(
@@ -187,3 +368,99 @@ impl Output for NodeListOutput {
         Ok(output)
     }
 }
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeHealth {
+    Healthy,
+    Unreachable,
+    IdentityMismatch,
+}
+
+#[derive(Serialize)]
+pub struct NodeCheckOutput {
+    pub node_name: String,
+    pub health: NodeHealth,
+    pub latency_ms: Option<u64>,
+    pub version: Option<String>,
+    pub identifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl NodeCheckOutput {
+    fn new(node_name: String) -> Self {
+        Self {
+            node_name,
+            health: NodeHealth::Unreachable,
+            latency_ms: None,
+            version: None,
+            identifier: None,
+            error: None,
+        }
+    }
+
+    fn failed(mut self, health: NodeHealth, error: String) -> Self {
+        self.health = health;
+        self.error = Some(error);
+        self
+    }
+}
+
+impl Output for NodeCheckOutput {
+    fn output(&self) -> Result<String> {
+        let health = match self.health {
+            NodeHealth::Healthy => "HEALTHY".color(OckamColor::Success.color()),
+            NodeHealth::Unreachable => "UNREACHABLE".color(OckamColor::Failure.color()),
+            NodeHealth::IdentityMismatch => "IDENTITY MISMATCH".color(OckamColor::Failure.color()),
+        };
+        let details = match &self.error {
+            Some(error) => error.to_string(),
+            None => format!(
+                "Latency {}ms, version {}",
+                self.latency_ms
+                    .unwrap_or_default()
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color()),
+                self.version
+                    .as_deref()
+                    .unwrap_or("unknown")
+                    .color(OckamColor::PrimaryResource.color())
+            ),
+        };
+
+        let output = formatdoc! {"
+        Node {node_name} {health}
+        {details}",
+        node_name = self
+            .node_name
+            .to_string()
+            .color(OckamColor::PrimaryResource.color()),
+        };
+
+        Ok(output)
+    }
+}
+
+#[derive(Serialize)]
+pub struct NodesCheckSummary {
+    pub total: usize,
+    pub healthy: usize,
+    pub unhealthy: usize,
+    pub nodes: Vec<NodeCheckOutput>,
+}
+
+impl NodesCheckSummary {
+    fn new(nodes: Vec<NodeCheckOutput>) -> Self {
+        let healthy = nodes
+            .iter()
+            .filter(|n| n.health == NodeHealth::Healthy)
+            .count();
+        Self {
+            total: nodes.len(),
+            healthy,
+            unhealthy: nodes.len() - healthy,
+            nodes,
+        }
+    }
+}
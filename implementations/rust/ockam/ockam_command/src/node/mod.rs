@@ -4,25 +4,49 @@ use colorful::Colorful;
 pub use create::CreateCommand;
 use default::DefaultCommand;
 use delete::DeleteCommand;
+use firewall_rules::FirewallRulesCommand;
+use history::HistoryCommand;
+use install_service::InstallServiceCommand;
 use list::ListCommand;
 use logs::LogCommand;
 use ockam_api::cli_state::{CliState, StateDirTrait};
+use prune::PruneCommand;
+use reload::ReloadCommand;
+use rollback::RollbackCommand;
 use show::ShowCommand;
 use start::StartCommand;
 use stop::StopCommand;
+use top::TopCommand;
+use uninstall_service::UninstallServiceCommand;
+use upgrade::UpgradeCommand;
+use validate::ValidateCommand;
 
 use crate::{docs, fmt_log, terminal::OckamColor, CommandGlobalOpts, PARSER_LOGS};
 
 mod create;
 mod default;
 mod delete;
+mod firewall_rules;
+mod history;
+mod install_service;
 mod list;
 mod logs;
 mod models;
+pub(crate) mod prune;
+mod reload;
+mod rollback;
+mod scheduler;
+mod service_manager;
 mod show;
 mod start;
 mod stop;
+pub(crate) mod top;
+mod uninstall_service;
+mod upgrade;
 pub mod util;
+mod validate;
+#[cfg(windows)]
+mod windows;
 pub use create::*;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -51,13 +75,23 @@ pub enum NodeSubcommand {
     List(ListCommand),
     #[command(display_order = 800)]
     Logs(LogCommand),
+    Prune(PruneCommand),
     Show(ShowCommand),
     #[command(display_order = 800)]
     Start(StartCommand),
     #[command(display_order = 800)]
     Stop(StopCommand),
+    Top(TopCommand),
+    Reload(ReloadCommand),
+    History(HistoryCommand),
+    Rollback(RollbackCommand),
+    Validate(ValidateCommand),
     #[command(display_order = 800)]
     Default(DefaultCommand),
+    Upgrade(UpgradeCommand),
+    InstallService(InstallServiceCommand),
+    UninstallService(UninstallServiceCommand),
+    FirewallRules(FirewallRulesCommand),
 }
 
 impl NodeCommand {
@@ -69,8 +103,18 @@ impl NodeCommand {
             NodeSubcommand::Show(c) => c.run(options),
             NodeSubcommand::Start(c) => c.run(options),
             NodeSubcommand::Stop(c) => c.run(options),
+            NodeSubcommand::Top(c) => c.run(options),
+            NodeSubcommand::Reload(c) => c.run(options),
+            NodeSubcommand::History(c) => c.run(options),
+            NodeSubcommand::Rollback(c) => c.run(options),
+            NodeSubcommand::Validate(c) => c.run(options),
             NodeSubcommand::Logs(c) => c.run(options),
+            NodeSubcommand::Prune(c) => c.run(options),
             NodeSubcommand::Default(c) => c.run(options),
+            NodeSubcommand::Upgrade(c) => c.run(options),
+            NodeSubcommand::InstallService(c) => c.run(options),
+            NodeSubcommand::UninstallService(c) => c.run(options),
+            NodeSubcommand::FirewallRules(c) => c.run(options),
         }
     }
 }
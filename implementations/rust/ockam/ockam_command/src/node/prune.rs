@@ -0,0 +1,92 @@
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use crate::util::local_cmd;
+use crate::{docs, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/prune/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/prune/after_long_help.txt");
+
+/// Kill orphan node processes and clean the dangling nodes state
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct PruneCommand {
+    /// Only list the inconsistencies, without fixing them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Terminate orphan node processes immediately (uses SIGKILL instead of SIGTERM)
+    #[arg(display_order = 901, long, short)]
+    force: bool,
+
+    /// Confirm the cleanup without prompting
+    #[arg(display_order = 901, long, short)]
+    yes: bool,
+}
+
+impl PruneCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: PruneCommand) -> miette::Result<()> {
+    let inconsistencies = opts.state.nodes.inconsistencies()?;
+    if inconsistencies.is_empty() {
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!("There is nothing to prune"))
+            .json(serde_json::json!([]))
+            .write_line()?;
+        return Ok(());
+    }
+
+    let found = inconsistencies
+        .iter()
+        .map(|i| fmt_log!("{i}\n"))
+        .collect::<String>();
+    if cmd.dry_run {
+        opts.terminal
+            .stdout()
+            .plain(found)
+            .json(serde_json::to_string_pretty(&inconsistencies).into_diagnostic()?)
+            .write_line()?;
+        return Ok(());
+    }
+
+    opts.terminal.write_line(&found)?;
+    if !opts.terminal.confirmed_with_flag_or_prompt(
+        cmd.yes,
+        "Are you sure you want to fix these inconsistencies?",
+    )? {
+        return Ok(());
+    }
+
+    let output = inconsistencies
+        .iter()
+        .map(|i| match opts.state.nodes.prune(i, cmd.force) {
+            Ok(()) => fmt_ok!("Fixed: {i}\n"),
+            Err(e) => fmt_warn!("Failed to fix: {i}: {e}\n"),
+        })
+        .collect::<String>();
+    opts.terminal
+        .stdout()
+        .plain(output)
+        .json(serde_json::to_string_pretty(&inconsistencies).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+/// Warn about the inconsistencies between the nodes state and the node processes, if any
+pub(crate) fn warn_about_inconsistencies(opts: &CommandGlobalOpts) {
+    let inconsistencies = opts.state.nodes.inconsistencies().unwrap_or_default();
+    if !inconsistencies.is_empty() {
+        let _ = opts.terminal.write_line(&fmt_warn!(
+            "Found {} inconsistencies between the nodes and their processes. Run `ockam node prune` to fix them",
+            inconsistencies.len()
+        ));
+    }
+}
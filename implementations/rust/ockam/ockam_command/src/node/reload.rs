@@ -0,0 +1,71 @@
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+
+use ockam_api::cli_state::StateDirTrait;
+use ockam_api::nodes::models::base::NodeReloaded;
+use ockam_api::nodes::BackgroundNode;
+use ockam_node::Context;
+
+use crate::node::get_node_name;
+use crate::terminal::OckamColor;
+use crate::util::{api, node_rpc};
+use crate::{docs, fmt_log, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/reload/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/reload/after_long_help.txt");
+
+/// Reload the trust context of a running node
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ReloadCommand {
+    /// Name of the node to reload
+    #[arg()]
+    node_name: Option<String>,
+}
+
+impl ReloadCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, ReloadCommand),
+) -> miette::Result<()> {
+    let node_name = get_node_name(&opts.state, &cmd.node_name);
+    if !opts.state.nodes.get(&node_name)?.is_running() {
+        return Err(miette!("The node '{}' is not running", node_name));
+    }
+
+    let node = BackgroundNode::create(&ctx, &opts.state, &node_name).await?;
+    let reloaded: NodeReloaded = node.ask(&ctx, api::reload_node()).await?;
+
+    let mut output = fmt_ok!(
+        "Reloaded the trust context of the node {}\n",
+        node_name
+            .to_string()
+            .color(OckamColor::PrimaryResource.color())
+    );
+    output.push_str(&fmt_log!(
+        "Trust context: {}",
+        reloaded
+            .trust_context_id
+            .color(OckamColor::PrimaryResource.color())
+    ));
+    if let Some(authority) = reloaded.authority_identifier {
+        output.push('\n');
+        output.push_str(&fmt_log!(
+            "Authority: {}",
+            authority.color(OckamColor::PrimaryResource.color())
+        ));
+    }
+    opts.terminal.stdout().plain(output).write_line()?;
+    Ok(())
+}
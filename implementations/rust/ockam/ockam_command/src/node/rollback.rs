@@ -0,0 +1,61 @@
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam_api::cli_state::StateDirTrait;
+
+use crate::node::get_node_name;
+use crate::terminal::OckamColor;
+use crate::util::local_cmd;
+use crate::{docs, fmt_log, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/rollback/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/rollback/after_long_help.txt");
+
+/// Re-apply the resources of a previous revision of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RollbackCommand {
+    /// Name of the node
+    node_name: Option<String>,
+
+    /// Revision to re-apply, as displayed by `ockam node history`
+    #[arg(long, value_name = "REVISION")]
+    to: u64,
+}
+
+impl RollbackCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: RollbackCommand) -> miette::Result<()> {
+    let node_name = get_node_name(&opts.state, &cmd.node_name);
+    let node = opts.state.nodes.get(&node_name)?;
+    let entry = node.rollback(cmd.to)?;
+
+    let mut plain =
+        fmt_ok!(
+        "The resources of the revision {} have been re-applied to the node {}, as the revision {}",
+        cmd.to.to_string().color(OckamColor::PrimaryResource.color()),
+        node_name.color(OckamColor::PrimaryResource.color()),
+        entry.revision.to_string().color(OckamColor::PrimaryResource.color())
+    );
+    if node.is_running() {
+        plain.push_str(&format!(
+            "\n{}",
+            fmt_log!("The node is running, it must be restarted for the rollback to take effect")
+        ));
+    }
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .machine(entry.revision.to_string())
+        .json(serde_json::to_string_pretty(&entry).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
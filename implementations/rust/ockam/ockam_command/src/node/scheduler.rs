@@ -0,0 +1,185 @@
+//! Periodic tasks run by a node.
+//!
+//! The tasks are declared in the `scheduled_tasks` section of the node launch configuration,
+//! or in the `scheduled-tasks` section of a node in an `ockam run` recipe:
+//!
+//! ```yaml
+//! scheduled-tasks:
+//!   - task: refresh_credential
+//!     every: 12h
+//!   - task: upload_report
+//!     url: https://reports.example.com/nodes
+//!     token: secret:REPORTS_TOKEN
+//!     every: 5m
+//! ```
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use miette::{miette, IntoDiagnostic};
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+use ockam::{AsyncTryClone, Context};
+use ockam_api::nodes::models::credentials::GetCredentialRequest;
+use ockam_api::nodes::InMemoryNode;
+use ockam_core::api::Request;
+
+use crate::node::create::{send_req_to_node_manager, start_services};
+use crate::service::config::{Config, ScheduledTask, ScheduledTaskConfig};
+use crate::util::duration::duration_parser;
+use crate::version::Version;
+
+/// Start a background loop for each enabled task
+pub(crate) async fn start(
+    ctx: &Context,
+    node_manager: Arc<InMemoryNode>,
+    tasks: &[ScheduledTaskConfig],
+) -> miette::Result<()> {
+    for config in tasks.iter().filter(|t| !t.disabled) {
+        let every = duration_parser(&config.every).map_err(|_| {
+            miette!(
+                "The interval '{}' of the scheduled task {:?} is invalid",
+                config.every,
+                config.task
+            )
+        })?;
+        if every.is_zero() {
+            return Err(miette!(
+                "The interval of the scheduled task {:?} must be greater than 0",
+                config.task
+            ));
+        }
+        let ctx = ctx.async_try_clone().await.into_diagnostic()?;
+        let node_manager = node_manager.clone();
+        let task = config.task.clone();
+        info!(?task, ?every, "scheduling task");
+        tokio::spawn(async move { run_periodically(ctx, node_manager, task, every).await });
+    }
+    Ok(())
+}
+
+async fn run_periodically(
+    ctx: Context,
+    node_manager: Arc<InMemoryNode>,
+    task: ScheduledTask,
+    every: Duration,
+) {
+    let mut runner = TaskRunner::default();
+    let mut interval = tokio::time::interval(every);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    // The first tick completes immediately, the node has just been set up
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        match runner.run(&ctx, &node_manager, &task).await {
+            Ok(()) => debug!(?task, "scheduled task completed"),
+            Err(e) => warn!(?task, "scheduled task failed: {e:?}"),
+        }
+    }
+}
+
+/// State kept between two runs of a task
+#[derive(Default)]
+struct TaskRunner {
+    /// Last configuration applied by a `reapply_config` task
+    last_applied_config: Option<String>,
+}
+
+impl TaskRunner {
+    async fn run(
+        &mut self,
+        ctx: &Context,
+        node_manager: &InMemoryNode,
+        task: &ScheduledTask,
+    ) -> miette::Result<()> {
+        match task {
+            ScheduledTask::RefreshCredential => {
+                let req = Request::post("/node/credentials/actions/get")
+                    .body(GetCredentialRequest::new(true, None));
+                send_req_to_node_manager(ctx, req).await?;
+            }
+            ScheduledTask::ProbeRelays => {
+                for relay in node_manager.get_relays().await {
+                    match node_manager.relay_is_up(&relay) {
+                        Some(false) => {
+                            warn!(relay = %relay.remote_address(), "the relay is not connected")
+                        }
+                        _ => debug!(relay = %relay.remote_address(), "the relay is connected"),
+                    }
+                }
+            }
+            ScheduledTask::UploadReport { url, token } => {
+                let report = report(node_manager).await;
+                let req = reqwest::Client::new().post(url).json(&report);
+                with_token(node_manager, req, token)?
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .into_diagnostic()?;
+            }
+            ScheduledTask::ReapplyConfig { url, token } => {
+                let req = reqwest::Client::new().get(url);
+                let contents = with_token(node_manager, req, token)?
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .into_diagnostic()?
+                    .text()
+                    .await
+                    .into_diagnostic()?;
+                if self.last_applied_config.as_ref() == Some(&contents) {
+                    return Ok(());
+                }
+                let config: Config = serde_json::from_str(&contents).into_diagnostic()?;
+                start_services(ctx, &config).await?;
+                info!(%url, "configuration applied");
+                self.last_applied_config = Some(contents);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Authenticate a request with a bearer token, which can refer to a node secret
+fn with_token(
+    node_manager: &InMemoryNode,
+    req: reqwest::RequestBuilder,
+    token: &Option<String>,
+) -> miette::Result<reqwest::RequestBuilder> {
+    match token {
+        Some(token) => {
+            let token = node_manager
+                .secrets()
+                .resolve(token)
+                .map_err(|e| miette!(e))?;
+            Ok(req.bearer_auth(token.expose()))
+        }
+        None => Ok(req),
+    }
+}
+
+/// Report describing the state of the node
+async fn report(node_manager: &InMemoryNode) -> serde_json::Value {
+    let relays: Vec<_> = node_manager
+        .get_relays()
+        .await
+        .iter()
+        .map(|relay| {
+            serde_json::json!({
+                "remote_address": relay.remote_address(),
+                "up": node_manager.relay_is_up(relay),
+            })
+        })
+        .collect();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    serde_json::json!({
+        "node": node_manager.node_name(),
+        "version": Version::short(),
+        "timestamp": timestamp,
+        "relays": relays,
+    })
+}
@@ -0,0 +1,449 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use miette::{miette, IntoDiagnostic, WrapErr};
+
+/// Description of a node running as a service of the operating system.
+/// The service runs `ockam node create <name> --foreground` and restarts it on failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeService {
+    pub node_name: String,
+    pub executable: PathBuf,
+    pub ockam_home: PathBuf,
+    pub tcp_listener_address: String,
+    pub stdout_log: PathBuf,
+    pub stderr_log: PathBuf,
+    /// Install the service for the whole system instead of the current user
+    pub system: bool,
+}
+
+impl NodeService {
+    /// Arguments used to run the node in foreground mode
+    fn arguments(&self) -> Vec<String> {
+        vec![
+            "node".to_string(),
+            "create".to_string(),
+            self.node_name.clone(),
+            "--foreground".to_string(),
+            "--tcp-listener-address".to_string(),
+            self.tcp_listener_address.clone(),
+            "--no-color".to_string(),
+        ]
+    }
+
+    /// Arguments used to run the node as a Windows service
+    pub(crate) fn windows_service_arguments(&self) -> Vec<String> {
+        let mut arguments = self.arguments();
+        arguments.push("--windows-service".to_string());
+        arguments
+    }
+
+    /// Environment variables set for the node process
+    fn environment(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("OCKAM_HOME", self.ockam_home.display().to_string()),
+            ("OCKAM_DISABLE_UPGRADE_CHECK", "true".to_string()),
+        ]
+    }
+}
+
+/// Service managers supported to run nodes as services
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ServiceManager {
+    Systemd,
+    Launchd,
+    WindowsService,
+}
+
+impl ServiceManager {
+    /// Return the service manager of the current operating system
+    pub fn detect() -> miette::Result<Self> {
+        if cfg!(target_os = "linux") {
+            Ok(ServiceManager::Systemd)
+        } else if cfg!(target_os = "macos") {
+            Ok(ServiceManager::Launchd)
+        } else if cfg!(windows) {
+            Ok(ServiceManager::WindowsService)
+        } else {
+            Err(miette!(
+                "Installing a node as a service is not supported on this operating system"
+            ))
+        }
+    }
+
+    /// Name of the service running the given node
+    pub fn service_name(&self, node_name: &str) -> String {
+        match self {
+            ServiceManager::Systemd => format!("ockam-node-{node_name}.service"),
+            ServiceManager::Launchd => format!("io.ockam.node.{node_name}"),
+            ServiceManager::WindowsService => format!("ockam-node-{node_name}"),
+        }
+    }
+
+    /// Path of the file containing the service definition.
+    /// Windows services are always installed for the whole system and are defined in the registry.
+    pub fn definition_path(&self, node_name: &str, system: bool) -> miette::Result<PathBuf> {
+        if let ServiceManager::WindowsService = self {
+            return Ok(PathBuf::from(windows_registry_key(
+                &self.service_name(node_name),
+            )));
+        }
+        let home = || home::home_dir().ok_or_else(|| miette!("Unable to find the home directory"));
+        let dir = match (self, system) {
+            (ServiceManager::Systemd, true) => PathBuf::from("/etc/systemd/system"),
+            (ServiceManager::Systemd, false) => home()?.join(".config/systemd/user"),
+            (ServiceManager::Launchd, true) => PathBuf::from("/Library/LaunchDaemons"),
+            (ServiceManager::Launchd, false) => home()?.join("Library/LaunchAgents"),
+            (ServiceManager::WindowsService, _) => unreachable!(),
+        };
+        let file_name = match self {
+            ServiceManager::Launchd => format!("{}.plist", self.service_name(node_name)),
+            _ => self.service_name(node_name),
+        };
+        Ok(dir.join(file_name))
+    }
+
+    /// Return the contents of the service definition file
+    pub fn definition(&self, service: &NodeService) -> String {
+        match self {
+            ServiceManager::Systemd => systemd_unit(service),
+            ServiceManager::Launchd => {
+                launchd_plist(self.service_name(&service.node_name), service)
+            }
+            ServiceManager::WindowsService => {
+                sc_commands(self.service_name(&service.node_name), service)
+            }
+        }
+    }
+
+    /// Write the service definition, then enable and start the service
+    pub fn install(&self, service: &NodeService) -> miette::Result<PathBuf> {
+        let path = self.definition_path(&service.node_name, service.system)?;
+        if let ServiceManager::WindowsService = self {
+            install_windows_service(&self.service_name(&service.node_name), service)?;
+            return Ok(path);
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).into_diagnostic()?;
+        }
+        std::fs::write(&path, self.definition(service))
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Unable to write the service file {}", path.display()))?;
+        match self {
+            ServiceManager::Systemd => {
+                let service_name = self.service_name(&service.node_name);
+                systemctl(service.system, &["daemon-reload"])?;
+                systemctl(service.system, &["enable", "--now", &service_name])?;
+            }
+            ServiceManager::Launchd => {
+                run("launchctl", &["load", "-w", &path.display().to_string()])?;
+            }
+            ServiceManager::WindowsService => unreachable!(),
+        }
+        Ok(path)
+    }
+
+    /// Stop and disable the service, then remove the service definition
+    pub fn uninstall(&self, node_name: &str, system: bool) -> miette::Result<PathBuf> {
+        let path = self.definition_path(node_name, system)?;
+        if let ServiceManager::WindowsService = self {
+            uninstall_windows_service(&self.service_name(node_name))?;
+            return Ok(path);
+        }
+        if !path.exists() {
+            return Err(miette!(
+                "There is no service installed for the node {node_name} at {}",
+                path.display()
+            ));
+        }
+        match self {
+            ServiceManager::Systemd => {
+                let service_name = self.service_name(node_name);
+                systemctl(system, &["disable", "--now", &service_name])?;
+                std::fs::remove_file(&path).into_diagnostic()?;
+                systemctl(system, &["daemon-reload"])?;
+            }
+            ServiceManager::Launchd => {
+                run("launchctl", &["unload", "-w", &path.display().to_string()])?;
+                std::fs::remove_file(&path).into_diagnostic()?;
+            }
+            ServiceManager::WindowsService => unreachable!(),
+        }
+        Ok(path)
+    }
+}
+
+fn systemd_unit(service: &NodeService) -> String {
+    let exec_start = std::iter::once(service.executable.display().to_string())
+        .chain(service.arguments())
+        .map(|a| systemd_quote(&a))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let environment = service
+        .environment()
+        .into_iter()
+        .map(|(k, v)| format!("Environment={}\n", systemd_quote(&format!("{k}={v}"))))
+        .collect::<String>();
+    let user = match (service.system, std::env::var("USER")) {
+        (true, Ok(user)) => format!("User={user}\n"),
+        _ => String::new(),
+    };
+    let wanted_by = if service.system {
+        "multi-user.target"
+    } else {
+        "default.target"
+    };
+    format!(
+        "[Unit]
+Description=Ockam node {node_name}
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=simple
+{user}{environment}ExecStart={exec_start}
+Restart=on-failure
+RestartSec=5
+KillSignal=SIGTERM
+TimeoutStopSec=30
+StandardOutput=append:{stdout}
+StandardError=append:{stderr}
+
+[Install]
+WantedBy={wanted_by}
+",
+        node_name = service.node_name,
+        stdout = service.stdout_log.display(),
+        stderr = service.stderr_log.display(),
+    )
+}
+
+fn launchd_plist(label: String, service: &NodeService) -> String {
+    let arguments = std::iter::once(service.executable.display().to_string())
+        .chain(service.arguments())
+        .map(|a| format!("        <string>{}</string>\n", xml_escape(&a)))
+        .collect::<String>();
+    let environment = service
+        .environment()
+        .into_iter()
+        .map(|(k, v)| {
+            format!(
+                "        <key>{k}</key>\n        <string>{}</string>\n",
+                xml_escape(&v)
+            )
+        })
+        .collect::<String>();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>EnvironmentVariables</key>
+    <dict>
+{environment}    </dict>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+    <key>ThrottleInterval</key>
+    <integer>5</integer>
+    <key>StandardOutPath</key>
+    <string>{stdout}</string>
+    <key>StandardErrorPath</key>
+    <string>{stderr}</string>
+</dict>
+</plist>
+"#,
+        label = xml_escape(&label),
+        stdout = xml_escape(&service.stdout_log.display().to_string()),
+        stderr = xml_escape(&service.stderr_log.display().to_string()),
+    )
+}
+
+/// Commands creating an equivalent Windows service with `sc.exe`
+fn sc_commands(service_name: String, service: &NodeService) -> String {
+    let bin_path = std::iter::once(service.executable.display().to_string())
+        .chain(service.windows_service_arguments())
+        .map(|a| windows_quote(&a))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        r#"sc.exe create {service_name} binPath= "{bin_path}" start= auto DisplayName= "Ockam node {node_name}"
+sc.exe failure {service_name} reset= 86400 actions= restart/5000
+reg.exe add {key} /v Environment /t REG_MULTI_SZ /d "{environment}" /f
+sc.exe start {service_name}
+"#,
+        bin_path = bin_path.replace('"', "\\\""),
+        node_name = service.node_name,
+        key = windows_registry_key(&service_name),
+        environment = windows_environment(service),
+    )
+}
+
+/// Registry key containing the configuration of a Windows service
+fn windows_registry_key(service_name: &str) -> String {
+    format!(r"HKLM\SYSTEM\CurrentControlSet\Services\{service_name}")
+}
+
+/// Environment of a Windows service, as a `REG_MULTI_SZ` value with `\0` separators
+fn windows_environment(service: &NodeService) -> String {
+    service
+        .environment()
+        .into_iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join("\\0")
+}
+
+#[cfg(windows)]
+fn install_windows_service(service_name: &str, service: &NodeService) -> miette::Result<()> {
+    crate::node::windows::install(service_name, service)?;
+    // The service environment is read by the Service Control Manager from the registry
+    run(
+        "reg",
+        &[
+            "add",
+            &windows_registry_key(service_name),
+            "/v",
+            "Environment",
+            "/t",
+            "REG_MULTI_SZ",
+            "/d",
+            &windows_environment(service),
+            "/f",
+        ],
+    )?;
+    crate::node::windows::start(service_name)
+}
+
+#[cfg(not(windows))]
+fn install_windows_service(_service_name: &str, _service: &NodeService) -> miette::Result<()> {
+    Err(miette!("Windows services can only be installed on Windows"))
+}
+
+#[cfg(windows)]
+fn uninstall_windows_service(service_name: &str) -> miette::Result<()> {
+    crate::node::windows::uninstall(service_name)
+}
+
+#[cfg(not(windows))]
+fn uninstall_windows_service(_service_name: &str) -> miette::Result<()> {
+    Err(miette!(
+        "Windows services can only be uninstalled on Windows"
+    ))
+}
+
+/// Quote a Windows command line argument if it contains whitespace
+fn windows_quote(s: &str) -> String {
+    if s.contains(char::is_whitespace) {
+        format!("\"{s}\"")
+    } else {
+        s.to_string()
+    }
+}
+
+/// Quote a systemd argument if it contains whitespace or quotes
+fn systemd_quote(s: &str) -> String {
+    if s.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn systemctl(system: bool, args: &[&str]) -> miette::Result<()> {
+    let mut all_args = vec![];
+    if !system {
+        all_args.push("--user");
+    }
+    all_args.extend_from_slice(args);
+    run("systemctl", &all_args)
+}
+
+fn run(program: impl AsRef<Path>, args: &[&str]) -> miette::Result<()> {
+    let program = program.as_ref();
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Unable to run {}", program.display()))?;
+    if !output.status.success() {
+        return Err(miette!(
+            "'{} {}' failed: {}",
+            program.display(),
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> NodeService {
+        NodeService {
+            node_name: "n1".to_string(),
+            executable: PathBuf::from("/usr/local/bin/ockam"),
+            ockam_home: PathBuf::from("/home/user/.ockam"),
+            tcp_listener_address: "127.0.0.1:4000".to_string(),
+            stdout_log: PathBuf::from("/home/user/.ockam/nodes/n1/stdout.log"),
+            stderr_log: PathBuf::from("/home/user/.ockam/nodes/n1/stderr.log"),
+            system: false,
+        }
+    }
+
+    #[test]
+    fn systemd_unit_runs_the_node_in_foreground() {
+        let unit = ServiceManager::Systemd.definition(&service());
+        assert!(unit.contains(
+            "ExecStart=/usr/local/bin/ockam node create n1 --foreground \
+            --tcp-listener-address 127.0.0.1:4000 --no-color"
+        ));
+        assert!(unit.contains("Environment=OCKAM_HOME=/home/user/.ockam"));
+        assert!(unit.contains("Restart=on-failure"));
+        assert!(unit.contains("StandardOutput=append:/home/user/.ockam/nodes/n1/stdout.log"));
+        assert!(unit.contains("WantedBy=default.target"));
+    }
+
+    #[test]
+    fn launchd_plist_runs_the_node_in_foreground() {
+        let plist = ServiceManager::Launchd.definition(&service());
+        assert!(plist.contains("<string>io.ockam.node.n1</string>"));
+        assert!(plist.contains("<string>--foreground</string>"));
+        assert!(plist.contains("<key>OCKAM_HOME</key>"));
+        assert!(plist.contains("<string>/home/user/.ockam/nodes/n1/stderr.log</string>"));
+    }
+
+    #[test]
+    fn windows_service_runs_the_node_in_foreground() {
+        let commands = ServiceManager::WindowsService.definition(&service());
+        assert!(commands.contains("sc.exe create ockam-node-n1"));
+        assert!(commands.contains("--foreground"));
+        assert!(commands.contains("--windows-service"));
+        assert!(commands.contains("actions= restart/5000"));
+    }
+
+    #[test]
+    fn quote_systemd_arguments() {
+        assert_eq!(systemd_quote("simple"), "simple");
+        assert_eq!(systemd_quote("with space"), "\"with space\"");
+    }
+}
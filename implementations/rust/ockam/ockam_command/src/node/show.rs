@@ -73,7 +73,7 @@ async fn run_impl(
             let selected_node_names = opts.terminal.select_multiple(
                 "Select one or more nodes that you want to show".to_string(),
                 node_names,
-            );
+            )?;
             match selected_node_names.len() {
                 0 => {
                     opts.terminal
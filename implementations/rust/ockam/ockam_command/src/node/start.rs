@@ -64,7 +64,7 @@ async fn run_impl(
         _ => {
             let selected_nodes = opts
                 .terminal
-                .select_multiple("Select the nodes".to_string(), inactive_nodes);
+                .select_multiple("Select the nodes".to_string(), inactive_nodes)?;
             match selected_nodes.len() {
                 0 => {
                     opts.terminal
@@ -77,7 +77,7 @@ async fn run_impl(
                     if !opts.terminal.confirm_interactively(format!(
                         "You are about to start the given nodes:[ {} ]. Confirm?",
                         &selected_nodes.join(", ")
-                    )) {
+                    ))? {
                         opts.terminal
                             .stdout()
                             .plain(fmt_info!("No node selected, exiting gratefully!"))
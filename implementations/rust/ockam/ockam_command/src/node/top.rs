@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use colorful::Colorful;
+use console::Term;
+use miette::{miette, IntoDiagnostic};
+
+use ockam_api::cli_state::StateDirTrait;
+use ockam_api::nodes::models::stats::{NodeStats, PortalKind};
+use ockam_api::nodes::BackgroundNode;
+use ockam_node::Context;
+
+use crate::node::get_node_name;
+use crate::terminal::OckamColor;
+use crate::util::duration::duration_parser;
+use crate::util::{api, node_rpc};
+use crate::{docs, CommandGlobalOpts, OutputFormat};
+
+const LONG_ABOUT: &str = include_str!("./static/top/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/top/after_long_help.txt");
+
+/// Number of lines used by the summary and the table headers
+const HEADER_LINES: usize = 7;
+
+/// Display a live view of the activity of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct TopCommand {
+    /// Name of the node to monitor
+    #[arg()]
+    node_name: Option<String>,
+
+    /// Time between two refreshes
+    #[arg(long, short, default_value = "2s", value_parser = duration_parser)]
+    delay: Duration,
+
+    /// Stop after the given number of refreshes
+    #[arg(long, short = 'n')]
+    iterations: Option<u64>,
+}
+
+impl TopCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, TopCommand),
+) -> miette::Result<()> {
+    let node_name = get_node_name(&opts.state, &cmd.node_name);
+    if !opts.state.nodes.get(&node_name)?.is_running() {
+        return Err(miette!("The node '{}' is not running", node_name));
+    }
+    if cmd.delay.is_zero() {
+        return Err(miette!(
+            "The delay between two refreshes must be greater than 0"
+        ));
+    }
+
+    let node = BackgroundNode::create(&ctx, &opts.state, &node_name).await?;
+    let term = Term::stdout();
+    let interactive = opts.global_args.output_format == OutputFormat::Plain && term.is_term();
+
+    let mut previous: Option<Sample> = None;
+    let mut iteration = 0;
+    loop {
+        let stats: NodeStats = node.ask(&ctx, api::node_stats()).await?;
+        let sample = Sample {
+            stats,
+            at: Instant::now(),
+        };
+
+        let height = term.size().0 as usize;
+        let view = render(&node_name, &sample, previous.as_ref(), height);
+        if interactive {
+            term.clear_screen().into_diagnostic()?;
+            term.write_str(&view).into_diagnostic()?;
+        } else {
+            opts.terminal
+                .clone()
+                .stdout()
+                .plain(view)
+                .json(serde_json::to_string(&sample.stats).into_diagnostic()?)
+                .write_line()?;
+        }
+
+        previous = Some(sample);
+        iteration += 1;
+        if cmd.iterations.map_or(false, |n| iteration >= n) {
+            break;
+        }
+        tokio::time::sleep(cmd.delay).await;
+    }
+    Ok(())
+}
+
+/// Statistics retrieved at a given time
+struct Sample {
+    stats: NodeStats,
+    at: Instant,
+}
+
+/// Render the statistics of a node, with rates computed from the previous sample if there is one
+fn render(node_name: &str, current: &Sample, previous: Option<&Sample>, height: usize) -> String {
+    let elapsed = previous.map(|p| current.at.duration_since(p.at).as_secs_f64());
+    let stats = &current.stats;
+    let mut view = String::new();
+
+    let _ = writeln!(
+        view,
+        "Node {}  workers: {}  secure channels: {}  secure channel listeners: {}",
+        node_name.color(OckamColor::PrimaryResource.color()),
+        stats.workers.len(),
+        stats.secure_channels,
+        stats.secure_channel_listeners
+    );
+    let _ = writeln!(view);
+
+    // Portals
+    let previous_portals: HashMap<(&str, PortalKind), (u64, u64)> = previous
+        .map(|p| {
+            p.stats
+                .portals
+                .iter()
+                .map(|portal| {
+                    (
+                        (portal.alias.as_str(), portal.kind),
+                        (portal.bytes_received, portal.bytes_sent),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let _ = writeln!(
+        view,
+        "{:<30} {:<7} {:>6} {:>12} {:>12} {:>12} {:>12}",
+        "PORTAL", "KIND", "CONNS", "IN/s", "OUT/s", "IN", "OUT"
+    );
+    for portal in &stats.portals {
+        let (received_rate, sent_rate) = match (
+            elapsed,
+            previous_portals.get(&(portal.alias.as_str(), portal.kind)),
+        ) {
+            (Some(elapsed), Some((received, sent))) => (
+                format_bytes(rate(portal.bytes_received, *received, elapsed)),
+                format_bytes(rate(portal.bytes_sent, *sent, elapsed)),
+            ),
+            _ => ("-".to_string(), "-".to_string()),
+        };
+        let kind = match portal.kind {
+            PortalKind::Inlet => "inlet",
+            PortalKind::Outlet => "outlet",
+        };
+        let _ = writeln!(
+            view,
+            "{:<30} {:<7} {:>6} {:>12} {:>12} {:>12} {:>12}",
+            truncate(&portal.alias, 30),
+            kind,
+            portal.connections,
+            received_rate,
+            sent_rate,
+            format_bytes(portal.bytes_received as f64),
+            format_bytes(portal.bytes_sent as f64)
+        );
+    }
+    let _ = writeln!(view);
+
+    // Workers, the busiest ones first
+    let previous_workers: HashMap<&str, u64> = previous
+        .map(|p| {
+            p.stats
+                .workers
+                .iter()
+                .map(|w| (w.address.as_str(), w.messages))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut workers: Vec<_> = stats
+        .workers
+        .iter()
+        .map(|w| {
+            let messages_rate = elapsed
+                .zip(previous_workers.get(w.address.as_str()))
+                .map(|(elapsed, messages)| rate(w.messages, *messages, elapsed));
+            (w, messages_rate)
+        })
+        .collect();
+    workers.sort_by(|(w1, r1), (w2, r2)| {
+        r2.unwrap_or_default()
+            .total_cmp(&r1.unwrap_or_default())
+            .then(w2.mailbox_len.cmp(&w1.mailbox_len))
+            .then(w1.address.cmp(&w2.address))
+    });
+
+    let _ = writeln!(
+        view,
+        "{:<50} {:<9} {:>10} {:>8} {:>12}",
+        "WORKER", "TYPE", "MSG/s", "QUEUE", "MESSAGES"
+    );
+    let rows = height
+        .saturating_sub(HEADER_LINES + stats.portals.len())
+        .max(1);
+    for (worker, messages_rate) in workers.iter().take(rows) {
+        let _ = writeln!(
+            view,
+            "{:<50} {:<9} {:>10} {:>8} {:>12}",
+            truncate(&worker.address, 50),
+            if worker.processor {
+                "processor"
+            } else {
+                "worker"
+            },
+            messages_rate
+                .map(|r| format!("{r:.1}"))
+                .unwrap_or_else(|| "-".to_string()),
+            worker.mailbox_len,
+            worker.messages
+        );
+    }
+    view
+}
+
+/// Rate per second of a counter between two samples
+fn rate(current: u64, previous: u64, elapsed_secs: f64) -> f64 {
+    if elapsed_secs <= 0.0 {
+        return 0.0;
+    }
+    current.saturating_sub(previous) as f64 / elapsed_secs
+}
+
+pub(crate) fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{value:.0} {}", UNITS[unit])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+fn truncate(s: &str, width: usize) -> String {
+    if s.chars().count() <= width {
+        s.to_string()
+    } else {
+        let mut truncated: String = s.chars().take(width - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_rates() {
+        assert_eq!(rate(150, 50, 2.0), 50.0);
+        // A counter can be reset if a worker is restarted
+        assert_eq!(rate(10, 50, 2.0), 0.0);
+        assert_eq!(rate(10, 0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn format_byte_counts() {
+        assert_eq!(format_bytes(512.0), "512 B");
+        assert_eq!(format_bytes(1536.0), "1.5 KiB");
+        assert_eq!(format_bytes(3.0 * 1024.0 * 1024.0), "3.0 MiB");
+        assert_eq!(truncate("abcdef", 4), "abc…");
+    }
+}
@@ -0,0 +1,56 @@
+use clap::Args;
+use colorful::Colorful;
+
+use crate::node::get_node_name;
+use crate::node::service_manager::ServiceManager;
+use crate::util::local_cmd;
+use crate::{docs, fmt_ok, CommandGlobalOpts, OckamColor};
+
+const LONG_ABOUT: &str = include_str!("./static/uninstall_service/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/uninstall_service/after_long_help.txt");
+
+/// Stop running a node as a service of the operating system
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct UninstallServiceCommand {
+    /// Name of the node running as a service
+    node_name: Option<String>,
+
+    /// Uninstall a service installed for the whole system
+    #[arg(long)]
+    system: bool,
+}
+
+impl UninstallServiceCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: UninstallServiceCommand) -> miette::Result<()> {
+    let service_manager = ServiceManager::detect()?;
+    let node_name = get_node_name(&opts.state, &cmd.node_name);
+    let path = service_manager.uninstall(&node_name, cmd.system)?;
+    let service_name = service_manager.service_name(&node_name);
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!(
+            "The service {} has been stopped and removed",
+            service_name
+                .clone()
+                .color(OckamColor::PrimaryResource.color())
+        ))
+        .machine(&service_name)
+        .json(serde_json::json!({
+            "node": node_name,
+            "service": service_name,
+            "path": path,
+        }))
+        .write_line()?;
+    Ok(())
+}
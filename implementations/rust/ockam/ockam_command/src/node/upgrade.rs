@@ -0,0 +1,357 @@
+use std::env::current_exe;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, Context as _, IntoDiagnostic};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+use url::Url;
+
+use ockam_api::cli_state::{StateDirTrait, StateItemTrait};
+use ockam_api::nodes::BackgroundNode;
+use ockam_node::Context;
+
+use crate::node::show::is_node_up;
+use crate::util::node_rpc;
+use crate::{docs, fmt_info, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts, OckamColor};
+
+const LONG_ABOUT: &str = include_str!("./static/upgrade/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/upgrade/after_long_help.txt");
+
+/// Time given to a node to exit after receiving a SIGTERM signal
+const NODE_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+const NODE_STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Upgrade the ockam binary and restart the background nodes
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct UpgradeCommand {
+    /// Path to the new ockam binary
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "url",
+        required_unless_present = "url"
+    )]
+    binary: Option<PathBuf>,
+
+    /// URL from which the new ockam binary is downloaded
+    #[arg(long, value_name = "URL")]
+    url: Option<Url>,
+
+    /// Expected SHA-256 checksum of the new binary, as an hex encoded string
+    #[arg(long, value_name = "HEX")]
+    sha256: Option<String>,
+
+    /// Keep a copy of the previous binary, with a `.bak` extension
+    #[arg(long)]
+    keep_backup: bool,
+
+    /// Confirm the upgrade without prompting
+    #[arg(long, short)]
+    yes: bool,
+}
+
+impl UpgradeCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, UpgradeCommand),
+) -> miette::Result<()> {
+    let installed = current_exe()
+        .and_then(std::fs::canonicalize)
+        .into_diagnostic()
+        .context("Unable to locate the current ockam binary")?;
+    let paths = UpgradePaths::new(&installed);
+
+    // Fetch the new binary next to the current one so that it can be swapped atomically
+    fetch_binary(&cmd, &paths.staged).await?;
+    let version = match validate_binary(&paths.staged, cmd.sha256.as_deref()) {
+        Ok(version) => version,
+        Err(e) => {
+            let _ = std::fs::remove_file(&paths.staged);
+            return Err(e);
+        }
+    };
+    opts.terminal.write_line(&fmt_log!(
+        "The new binary has been validated: {}",
+        version.clone().color(OckamColor::PrimaryResource.color())
+    ))?;
+
+    let nodes = running_nodes(&opts)?;
+    let prompt = if nodes.is_empty() {
+        format!("Replace {} with {version}?", installed.display())
+    } else {
+        format!(
+            "Replace {} with {version} and restart the nodes [{}]?",
+            installed.display(),
+            nodes.join(", ")
+        )
+    };
+    if !opts
+        .terminal
+        .confirmed_with_flag_or_prompt(cmd.yes, prompt)?
+    {
+        let _ = std::fs::remove_file(&paths.staged);
+        return Ok(());
+    }
+
+    stop_nodes(&opts, &nodes).await?;
+    paths.swap()?;
+
+    if let Err(e) = restart_nodes(&ctx, &opts, &paths.installed, &nodes).await {
+        opts.terminal.write_line(&fmt_warn!(
+            "The upgraded nodes are not healthy, rolling back to the previous binary"
+        ))?;
+        // The previous binary is restored even if some upgraded nodes can't be stopped
+        let stopped = stop_nodes(&opts, &nodes).await;
+        paths.rollback()?;
+        stopped
+            .context("Failed to stop the upgraded nodes, the previous binary has been restored")?;
+        restart_nodes(&ctx, &opts, &paths.installed, &nodes)
+            .await
+            .context("Failed to restart the nodes with the previous binary")?;
+        return Err(e.wrap_err("The upgrade failed and the previous binary has been restored"));
+    }
+
+    if !cmd.keep_backup {
+        let _ = std::fs::remove_file(&paths.backup);
+    }
+
+    opts.terminal
+        .stdout()
+        .plain(
+            fmt_ok!(
+                "Upgraded {} to {}\n",
+                installed.display().to_string(),
+                version.clone().color(OckamColor::PrimaryResource.color())
+            ) + &if nodes.is_empty() {
+                fmt_info!("There were no running nodes to restart")
+            } else {
+                fmt_log!("Restarted the nodes {}", nodes.join(", "))
+            },
+        )
+        .machine(&version)
+        .json(serde_json::json!({ "version": version, "restarted_nodes": nodes }))
+        .write_line()?;
+    Ok(())
+}
+
+/// Paths of the binaries involved in an upgrade
+struct UpgradePaths {
+    /// Path of the currently installed binary
+    installed: PathBuf,
+    /// Path of the new binary, before it replaces the installed one
+    staged: PathBuf,
+    /// Path of the previous binary, once it has been replaced
+    backup: PathBuf,
+}
+
+impl UpgradePaths {
+    fn new(installed: &Path) -> Self {
+        let with_extension = |extension: &str| {
+            let mut path = installed.as_os_str().to_owned();
+            path.push(".");
+            path.push(extension);
+            PathBuf::from(path)
+        };
+        Self {
+            installed: installed.to_path_buf(),
+            staged: with_extension("new"),
+            backup: with_extension("bak"),
+        }
+    }
+
+    fn swap(&self) -> miette::Result<()> {
+        std::fs::rename(&self.installed, &self.backup)
+            .into_diagnostic()
+            .context("Unable to backup the current binary")?;
+        if let Err(e) = std::fs::rename(&self.staged, &self.installed) {
+            let _ = std::fs::rename(&self.backup, &self.installed);
+            return Err(e)
+                .into_diagnostic()
+                .context("Unable to install the new binary");
+        }
+        info!(path = %self.installed.display(), "ockam binary replaced");
+        Ok(())
+    }
+
+    fn rollback(&self) -> miette::Result<()> {
+        std::fs::rename(&self.backup, &self.installed)
+            .into_diagnostic()
+            .context("Unable to restore the previous binary")?;
+        warn!(path = %self.installed.display(), "ockam binary restored");
+        Ok(())
+    }
+}
+
+/// Copy or download the new binary to the given path
+async fn fetch_binary(cmd: &UpgradeCommand, destination: &Path) -> miette::Result<()> {
+    match (&cmd.binary, &cmd.url) {
+        (Some(path), _) => {
+            std::fs::copy(path, destination)
+                .into_diagnostic()
+                .with_context(|| format!("Unable to copy the binary {}", path.display()))?;
+        }
+        (None, Some(url)) => {
+            let response = reqwest::get(url.clone())
+                .await
+                .and_then(|r| r.error_for_status())
+                .into_diagnostic()
+                .with_context(|| format!("Unable to download the binary from {url}"))?;
+            let bytes = response.bytes().await.into_diagnostic()?;
+            std::fs::write(destination, bytes).into_diagnostic()?;
+        }
+        (None, None) => return Err(miette!("Either --binary or --url must be provided")),
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(destination, std::fs::Permissions::from_mode(0o755))
+            .into_diagnostic()?;
+    }
+    Ok(())
+}
+
+/// Check the binary checksum, if provided, and make sure that the binary can be executed.
+/// Return the version reported by the binary.
+fn validate_binary(path: &Path, sha256: Option<&str>) -> miette::Result<String> {
+    if let Some(expected) = sha256 {
+        let contents = std::fs::read(path).into_diagnostic()?;
+        let actual = hex::encode(Sha256::digest(contents));
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(miette!(
+                "The checksum of the new binary is {actual} but {expected} was expected"
+            ));
+        }
+    }
+    let output = Command::new(path)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .into_diagnostic()
+        .context("Unable to execute the new binary")?;
+    if !output.status.success() {
+        return Err(miette!(
+            "The new binary failed to report its version: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    let version = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    Ok(version)
+}
+
+fn running_nodes(opts: &CommandGlobalOpts) -> miette::Result<Vec<String>> {
+    Ok(opts
+        .state
+        .nodes
+        .list()?
+        .into_iter()
+        .filter(|n| n.is_running())
+        .map(|n| n.name().to_string())
+        .collect())
+}
+
+/// Send a SIGTERM signal to the nodes and wait for them to exit.
+/// The nodes which are still running after a timeout are killed.
+async fn stop_nodes(opts: &CommandGlobalOpts, nodes: &[String]) -> miette::Result<()> {
+    for node_name in nodes {
+        let node_state = opts.state.nodes.get(node_name)?;
+        if !node_state.is_running() {
+            continue;
+        }
+        node_state.kill_process(false)?;
+        let started = std::time::Instant::now();
+        while node_state.is_running() && started.elapsed() < NODE_STOP_TIMEOUT {
+            tokio::time::sleep(NODE_STOP_CHECK_INTERVAL).await;
+        }
+        if node_state.is_running() {
+            warn!(%node_name, "node didn't stop in time, killing it");
+            node_state.kill_process(true)?;
+        }
+    }
+    Ok(())
+}
+
+/// Start the nodes with the given binary and check that they are responding to requests
+async fn restart_nodes(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    binary: &Path,
+    nodes: &[String],
+) -> miette::Result<()> {
+    for node_name in nodes {
+        let status = Command::new(binary)
+            .args(["--quiet", "node", "start", node_name])
+            .env("OCKAM", binary)
+            .stdin(Stdio::null())
+            .status()
+            .into_diagnostic()
+            .with_context(|| format!("Unable to start the node {node_name}"))?;
+        if !status.success() {
+            return Err(miette!("The node {node_name} could not be started"));
+        }
+        let mut node = BackgroundNode::create(ctx, &opts.state, node_name).await?;
+        if !is_node_up(ctx, node_name, &mut node, opts.state.clone(), true).await? {
+            return Err(miette!(
+                "The node {node_name} is not responding after the upgrade"
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_previous_binary_is_restored_by_a_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = UpgradePaths::new(&dir.path().join("ockam"));
+        std::fs::write(&paths.installed, "previous").unwrap();
+        std::fs::write(&paths.staged, "new").unwrap();
+
+        paths.swap().unwrap();
+        assert_eq!(std::fs::read_to_string(&paths.installed).unwrap(), "new");
+        assert_eq!(std::fs::read_to_string(&paths.backup).unwrap(), "previous");
+        assert!(!paths.staged.exists());
+
+        paths.rollback().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&paths.installed).unwrap(),
+            "previous"
+        );
+        assert!(!paths.backup.exists());
+    }
+
+    #[test]
+    fn the_installed_binary_is_kept_when_the_new_binary_is_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = UpgradePaths::new(&dir.path().join("ockam"));
+        std::fs::write(&paths.installed, "previous").unwrap();
+
+        assert!(paths.swap().is_err());
+        assert_eq!(
+            std::fs::read_to_string(&paths.installed).unwrap(),
+            "previous"
+        );
+    }
+}
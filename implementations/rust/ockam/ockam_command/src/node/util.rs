@@ -169,6 +169,9 @@ pub fn run_ockam(
     let node_state = opts.state.nodes.get(node_name)?;
 
     let mut cmd = Command::new(ockam_exe);
+    // The node must use the same state directory as this process, even if it was not
+    // configured with the OCKAM_HOME environment variable
+    cmd.env("OCKAM_HOME", &opts.state.dir);
 
     if logging_to_file {
         let (mlog, elog) = { (node_state.stdout_log(), node_state.stderr_log()) };
@@ -187,6 +190,16 @@ pub fn run_ockam(
         cmd.stdout(main_log_file).stderr(stderr_log_file);
     }
 
+    // Detach the node from the console of the current process so that it keeps
+    // running once the console is closed
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x00000008;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+        cmd.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+    }
+
     let child = cmd
         .args(args)
         .stdin(Stdio::null())
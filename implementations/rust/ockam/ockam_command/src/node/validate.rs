@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use crate::run::include::read_config;
+use crate::run::validator::validate_config;
+use crate::terminal::OckamColor;
+use crate::util::{exitcode, local_cmd};
+use crate::{docs, fmt_err, fmt_log, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/validate/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/validate/after_long_help.txt");
+
+/// Check a node configuration file without applying it
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ValidateCommand {
+    /// Path to the configuration file
+    config: PathBuf,
+}
+
+impl ValidateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: ValidateCommand) -> miette::Result<()> {
+    let contents = read_config(&cmd.config)?;
+    let validation = validate_config(&contents, &opts.state);
+
+    let mut plain = String::new();
+    for change in &validation.changes {
+        plain.push_str(&fmt_log!(
+            "The node {} would be {}d\n",
+            change
+                .node
+                .as_str()
+                .color(OckamColor::PrimaryResource.color()),
+            change.action
+        ));
+    }
+    if validation.is_valid() {
+        plain.push_str(&fmt_ok!(
+            "The configuration {} is valid",
+            cmd.config
+                .display()
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        ));
+    } else {
+        for issue in &validation.issues {
+            plain.push_str(&fmt_err!("{issue}\n"));
+        }
+        plain.push_str(&fmt_err!(
+            "The configuration {} has {} issue(s)",
+            cmd.config
+                .display()
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            validation.issues.len()
+        ));
+    }
+    let machine = validation
+        .issues
+        .iter()
+        .map(|issue| issue.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .machine(machine)
+        .json(serde_json::to_string_pretty(&validation).into_diagnostic()?)
+        .write_line()?;
+
+    if !validation.is_valid() {
+        std::process::exit(exitcode::DATAERR);
+    }
+    Ok(())
+}
@@ -0,0 +1,179 @@
+//! Integration of the nodes with the Windows Service Control Manager (SCM).
+//!
+//! A node installed with `ockam node install-service` is started by the SCM with
+//! `ockam node create <name> --foreground --windows-service`. The process then connects to
+//! the SCM, which notifies it when the service must be stopped.
+
+use std::ffi::{OsStr, OsString};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use miette::{miette, IntoDiagnostic, WrapErr};
+use tracing::{error, info};
+use windows_service::service::{
+    ServiceAccess, ServiceAction, ServiceActionType, ServiceControl, ServiceControlAccept,
+    ServiceErrorControl, ServiceExitCode, ServiceFailureActions, ServiceFailureResetPeriod,
+    ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+use windows_service::{define_windows_service, service_dispatcher};
+
+use ockam_api::cli_state::StateDirTrait;
+
+use crate::node::create::foreground_mode;
+use crate::node::service_manager::NodeService;
+use crate::node::CreateCommand;
+use crate::CommandGlobalOpts;
+
+/// Delay before the SCM restarts a node which exited with an error
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+/// Period after which the failure count of a service is reset
+const FAILURE_RESET_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The service entry point is called by the SCM on another thread, without arguments,
+/// so the parsed command is handed over through this variable
+static SERVICE_COMMAND: Mutex<Option<(CommandGlobalOpts, CreateCommand)>> = Mutex::new(None);
+
+define_windows_service!(ffi_service_main, service_main);
+
+/// Connect to the SCM and run the node until the service is stopped
+pub fn run(opts: CommandGlobalOpts, cmd: CreateCommand) -> miette::Result<()> {
+    let service_name = service_name(&cmd);
+    *SERVICE_COMMAND.lock().unwrap() = Some((opts, cmd));
+    service_dispatcher::start(service_name, ffi_service_main)
+        .into_diagnostic()
+        .wrap_err("A Windows service node can only be started by the Service Control Manager")
+}
+
+fn service_main(_arguments: Vec<OsString>) {
+    if let Err(e) = run_service() {
+        error!("the Windows service failed: {e:?}");
+    }
+}
+
+fn run_service() -> miette::Result<()> {
+    let (opts, cmd) = SERVICE_COMMAND
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| miette!("The service was started more than once"))?;
+
+    let node_name = cmd.node_name.clone();
+    let state = opts.state.clone();
+    let event_handler = move |control| match control {
+        ServiceControl::Stop | ServiceControl::Shutdown => {
+            // The node stops gracefully once it notices the stop request
+            info!(%node_name, "stop requested by the Service Control Manager");
+            if let Ok(node_state) = state.nodes.get(&node_name) {
+                let _ = node_state.request_stop();
+            }
+            ServiceControlHandlerResult::NoError
+        }
+        ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+        _ => ServiceControlHandlerResult::NotImplemented,
+    };
+    let status_handle =
+        service_control_handler::register(service_name(&cmd), event_handler).into_diagnostic()?;
+    let set_status = |current_state, exit_code| {
+        status_handle
+            .set_service_status(ServiceStatus {
+                service_type: ServiceType::OWN_PROCESS,
+                current_state,
+                controls_accepted: if current_state == ServiceState::Running {
+                    ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN
+                } else {
+                    ServiceControlAccept::empty()
+                },
+                exit_code: ServiceExitCode::Win32(exit_code),
+                checkpoint: 0,
+                wait_hint: Duration::default(),
+                process_id: None,
+            })
+            .into_diagnostic()
+    };
+
+    set_status(ServiceState::Running, 0)?;
+    let result = foreground_mode(opts, cmd);
+    // A non-zero exit code lets the SCM apply the restart policy of the service
+    set_status(ServiceState::Stopped, if result.is_ok() { 0 } else { 1 })?;
+    result
+}
+
+fn service_name(cmd: &CreateCommand) -> String {
+    super::service_manager::ServiceManager::WindowsService.service_name(&cmd.node_name)
+}
+
+/// Create a service started automatically at boot time and restarted on failure
+pub fn install(service_name: &str, service: &NodeService) -> miette::Result<()> {
+    let manager = ServiceManager::local_computer(
+        None::<&str>,
+        ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+    )
+    .into_diagnostic()?;
+    let service_info = ServiceInfo {
+        name: OsString::from(service_name),
+        display_name: OsString::from(format!("Ockam node {}", service.node_name)),
+        service_type: ServiceType::OWN_PROCESS,
+        start_type: ServiceStartType::AutoStart,
+        error_control: ServiceErrorControl::Normal,
+        executable_path: service.executable.clone(),
+        launch_arguments: service
+            .windows_service_arguments()
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+        dependencies: vec![],
+        account_name: None,
+        account_password: None,
+    };
+    let windows_service = manager
+        .create_service(&service_info, ServiceAccess::CHANGE_CONFIG)
+        .into_diagnostic()?;
+    windows_service
+        .update_failure_actions(ServiceFailureActions {
+            reset_period: ServiceFailureResetPeriod::After(FAILURE_RESET_PERIOD),
+            reboot_msg: None,
+            command: None,
+            actions: Some(vec![ServiceAction {
+                action_type: ServiceActionType::Restart,
+                delay: RESTART_DELAY,
+            }]),
+        })
+        .into_diagnostic()?;
+    windows_service
+        .set_failure_actions_on_non_crash_failures(true)
+        .into_diagnostic()?;
+    Ok(())
+}
+
+pub fn start(service_name: &str) -> miette::Result<()> {
+    let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+        .into_diagnostic()?;
+    manager
+        .open_service(service_name, ServiceAccess::START)
+        .into_diagnostic()?
+        .start(&[] as &[&OsStr])
+        .into_diagnostic()
+}
+
+/// Stop the service if it is running, then delete it
+pub fn uninstall(service_name: &str) -> miette::Result<()> {
+    let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+        .into_diagnostic()?;
+    let windows_service = manager
+        .open_service(
+            service_name,
+            ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+        )
+        .map_err(|_| miette!("There is no Windows service named {service_name}"))?;
+    if windows_service
+        .query_status()
+        .into_diagnostic()?
+        .current_state
+        != ServiceState::Stopped
+    {
+        windows_service.stop().into_diagnostic()?;
+    }
+    windows_service.delete().into_diagnostic()
+}
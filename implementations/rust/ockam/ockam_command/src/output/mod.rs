@@ -2,7 +2,9 @@ mod encode_format;
 #[allow(clippy::module_inception)]
 pub(crate) mod output;
 mod output_format;
+mod schema;
 
 pub use encode_format::*;
 pub use output::*;
 pub use output_format::*;
+pub use schema::*;
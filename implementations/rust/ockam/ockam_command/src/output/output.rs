@@ -16,6 +16,7 @@ use serde::{Serialize, Serializer};
 
 use ockam_api::cli_state::{ProjectConfigCompact, StateItemTrait, VaultState};
 use ockam_api::cloud::project::Project;
+use ockam_api::cloud::project_node::ProjectNode;
 use ockam_api::cloud::space::Space;
 use ockam_api::nodes::models::portal::{InletStatus, OutletStatus};
 use ockam_api::nodes::models::secure_channel::{
@@ -179,6 +180,50 @@ Space {}"#,
     }
 }
 
+impl Output for ProjectNode {
+    fn output(&self) -> Result<String> {
+        let mut w = String::new();
+        write!(w, "Node")?;
+        write!(w, "\n  Name: {}", self.name)?;
+        write!(w, "\n  Identifier: {}", self.identifier)?;
+        write!(w, "\n  Labels: {}", labels_output(self))?;
+        write!(
+            w,
+            "\n  Last seen at: {}",
+            self.last_seen_at.as_deref().unwrap_or("N/A")
+        )?;
+        Ok(w)
+    }
+
+    fn list_output(&self) -> Result<String> {
+        let output = format!(
+            r#"Node {}
+Identifier {}
+Labels {}"#,
+            self.name
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            self.identifier
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            labels_output(self).color(OckamColor::PrimaryResource.color()),
+        );
+
+        Ok(output)
+    }
+}
+
+fn labels_output(node: &ProjectNode) -> String {
+    if node.labels.is_empty() {
+        return "N/A".to_string();
+    }
+    node.labels
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 impl Output for ProjectConfigCompact {
     fn output(&self) -> Result<String> {
         let pi = self
@@ -365,19 +410,12 @@ impl Output for VaultState {
     fn output(&self) -> Result<String> {
         let mut output = String::new();
         writeln!(output, "Name: {}", self.name())?;
-        writeln!(
-            output,
-            "Type: {}",
-            match self.config().is_aws() {
-                true => "AWS KMS",
-                false => "OCKAM",
-            }
-        )?;
+        writeln!(output, "Type: {}", self.config().kind())?;
         Ok(output)
     }
 }
 
-fn human_readable_time(time: TimestampInSeconds) -> String {
+pub fn human_readable_time(time: TimestampInSeconds) -> String {
     use time::format_description::well_known::iso8601::*;
     use time::Error::Format;
     use time::OffsetDateTime;
@@ -511,6 +549,24 @@ impl fmt::Display for CredentialDisplay {
             human_readable_time(credential_data.expires_at)
         )?;
 
+        if let Some(route_constraints) = &credential_data.route_constraints {
+            if !route_constraints.via.is_empty() {
+                writeln!(
+                    f,
+                    "Only Via:                   {}",
+                    route_constraints
+                        .via
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )?;
+            }
+            if route_constraints.deny_direct {
+                writeln!(f, "Direct Presentation:        denied")?;
+            }
+        }
+
         writeln!(f, "Attributes: ")?;
 
         write!(
@@ -611,6 +667,16 @@ impl fmt::Display for CredentialAndPurposeKeyDisplay {
             PurposeKeyDisplay(self.0.purpose_key_attestation.clone())
         )?;
 
+        for co_signature in self.0.co_signatures() {
+            writeln!(f)?;
+            writeln!(f, "Co-signature purpose key:")?;
+            writeln!(
+                f,
+                "{}",
+                PurposeKeyDisplay(co_signature.purpose_key_attestation.clone())
+            )?;
+        }
+
         Ok(())
     }
 }
@@ -1,19 +1,48 @@
 use crate::output::output::Output;
 use crate::Result;
 use clap::ValueEnum;
-use miette::{Context, IntoDiagnostic};
+use cli_table::{Cell, CellStruct, Style, Table};
+use miette::{miette, Context, IntoDiagnostic};
+use serde_json::Value;
 
-/// There are 2 available formats:
+/// Version of the schemas of the JSON, YAML and table outputs.
+///
+/// Within a version, the fields of an output are stable: new fields can be added but
+/// existing fields are never renamed, removed or given another type. Any other change
+/// requires a new version, so that scripts can pin the version they depend on with `--output-schema`.
+///
+/// Only the commands accepted by `OckamSubcommand::supports_output_schema` have a versioned
+/// output, defined with [`SchemaOutput`](crate::output::SchemaOutput). The JSON document of the
+/// other commands is not versioned, and they fail when `--output-schema` is set
+pub const OUTPUT_SCHEMA_VERSION: u8 = 1;
+
+/// There are 5 available formats:
 ///
 ///  - Plain formats a user readable string
 ///  - Json returns some prettified JSON
+///  - Yaml returns the same document as the JSON output, in YAML, or the plain output as a
+///    YAML string when the command has no JSON output
+///  - Table returns the same document as the JSON output, as a table, or the plain output
+///    when the command has no JSON output
+///  - Jwt returns a signed JSON Web Token, only for the commands showing credentials
 #[derive(Debug, Clone, ValueEnum, PartialEq, Eq)]
 pub enum OutputFormat {
     Plain,
     Json,
+    Yaml,
+    Table,
+    Jwt,
 }
 
 impl OutputFormat {
+    /// Return true if the output is derived from the JSON document of a command
+    pub fn is_structured(&self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Table
+        )
+    }
+
     /// Print a value on the console for any value having a textual Output and a JSON
     /// representation via serde
     pub fn println_value<T>(&self, t: &T) -> Result<()>
@@ -28,8 +57,119 @@ impl OutputFormat {
             OutputFormat::Json => serde_json::to_string_pretty(t)
                 .into_diagnostic()
                 .context("Failed to serialize output")?,
+            OutputFormat::Yaml | OutputFormat::Table => {
+                let value = serde_json::to_value(t)
+                    .into_diagnostic()
+                    .context("Failed to serialize output")?;
+                self.format_value(&value)?
+            }
+            OutputFormat::Jwt => {
+                return Err(miette!("JWT output is not defined for this command").into())
+            }
         };
         println!("{output}");
         Ok(())
     }
+
+    /// Format the JSON document produced by a command with this output format
+    pub fn format_json(&self, json: &str) -> Result<String> {
+        match self {
+            OutputFormat::Json => Ok(json.to_string()),
+            _ => {
+                let value: Value = serde_json::from_str(json)
+                    .into_diagnostic()
+                    .context("Failed to parse the JSON output")?;
+                self.format_value(&value)
+            }
+        }
+    }
+
+    fn format_value(&self, value: &Value) -> Result<String> {
+        match self {
+            OutputFormat::Yaml => Ok(serde_yaml::to_string(value)?),
+            OutputFormat::Table => table(value),
+            _ => Ok(serde_json::to_string_pretty(value)?),
+        }
+    }
+}
+
+/// Display a JSON document as a table:
+///
+///  - a list of objects has a column for each field
+///  - an object has a row for each field
+fn table(value: &Value) -> Result<String> {
+    let (title, rows): (Vec<CellStruct>, Vec<Vec<CellStruct>>) = match value {
+        Value::Array(items) => {
+            let mut columns: Vec<&String> = vec![];
+            for item in items {
+                if let Value::Object(fields) = item {
+                    for key in fields.keys() {
+                        if !columns.contains(&key) {
+                            columns.push(key);
+                        }
+                    }
+                }
+            }
+            if columns.is_empty() {
+                let rows = items.iter().map(|i| vec![cell_value(i).cell()]).collect();
+                (vec![], rows)
+            } else {
+                let rows = items
+                    .iter()
+                    .map(|item| {
+                        columns
+                            .iter()
+                            .map(|c| item.get(c).map(cell_value).unwrap_or_default().cell())
+                            .collect()
+                    })
+                    .collect();
+                let title = columns.iter().map(|c| c.cell().bold(true)).collect();
+                (title, rows)
+            }
+        }
+        Value::Object(fields) => {
+            let rows = fields
+                .iter()
+                .map(|(k, v)| vec![k.cell().bold(true), cell_value(v).cell()])
+                .collect();
+            (vec![], rows)
+        }
+        value => return Ok(cell_value(value)),
+    };
+    if rows.is_empty() {
+        return Ok(String::new());
+    }
+    let table = rows.table();
+    let table = if title.is_empty() {
+        table
+    } else {
+        table.title(title)
+    };
+    Ok(table.display()?.to_string())
+}
+
+/// Nested documents are displayed as compact JSON in a table cell
+fn cell_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_json() {
+        let json = r#"[{"alias":"a","port":1},{"alias":"b","status":"up"}]"#;
+        let yaml = OutputFormat::Yaml.format_json(json).unwrap();
+        assert_eq!(yaml, "- alias: a\n  port: 1\n- alias: b\n  status: up\n");
+
+        let table = OutputFormat::Table.format_json(json).unwrap();
+        let header = table.lines().nth(1).unwrap();
+        assert!(header.contains("alias") && header.contains("port") && header.contains("status"));
+        assert!(OutputFormat::Table.format_json("not json").is_err());
+    }
 }
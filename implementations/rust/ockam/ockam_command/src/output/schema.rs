@@ -0,0 +1,48 @@
+//! Documents written by the commands with the json, yaml and table outputs.
+//!
+//! The document of an item is defined by a struct for each version of the output schema,
+//! instead of the models of the node API, so that a change of those models never changes
+//! the document of a published version.
+//!
+//! The versioned documents are only defined for the `tcp-inlet list`, `tcp-inlet show`,
+//! `vault list`, `relay create` and `relay list` commands. A command having a versioned
+//! document must also be added to `OckamSubcommand::supports_output_schema`, otherwise
+//! `--output-schema` is rejected before it runs.
+
+use miette::miette;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::output::OUTPUT_SCHEMA_VERSION;
+use crate::util::exitcode;
+use crate::Result;
+
+/// Item which has a document for each supported version of the output schema
+pub trait SchemaOutput {
+    /// Return the document of the item for a version of the output schema
+    fn schema_output(&self, version: u8) -> Result<Value>;
+}
+
+/// Return the JSON document of a list of items for a version of the output schema
+pub fn schema_json_list<T: SchemaOutput>(items: &[T], version: u8) -> Result<String> {
+    let items = items
+        .iter()
+        .map(|item| item.schema_output(version))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(serde_json::to_string_pretty(&items)?)
+}
+
+/// Serialize the document of a supported version of the output schema
+pub fn to_schema_value(document: impl Serialize) -> Result<Value> {
+    Ok(serde_json::to_value(document)?)
+}
+
+/// Error returned for a version of the output schema which is not defined for an item
+pub fn unsupported_schema(version: u8) -> crate::Error {
+    crate::Error::new(
+        exitcode::USAGE,
+        miette!(
+            "The output schema version {version} is not supported by this command, the latest version is {OUTPUT_SCHEMA_VERSION}"
+        ),
+    )
+}
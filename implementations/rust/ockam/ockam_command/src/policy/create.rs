@@ -5,7 +5,9 @@ use ockam_abac::{Action, Expr, Resource};
 use ockam_api::nodes::models::policy::Policy;
 use ockam_api::nodes::BackgroundNode;
 use ockam_core::api::Request;
+use serde_json::json;
 
+use crate::dry_run::{Plan, Step};
 use crate::node::get_node_name;
 use crate::policy::policy_path;
 use crate::util::{node_rpc, parse_node_name};
@@ -43,6 +45,16 @@ async fn run_impl(
 ) -> miette::Result<()> {
     let at = get_node_name(&opts.state, &cmd.at);
     let node_name = parse_node_name(&at)?;
+    if opts.global_args.dry_run {
+        let mut plan = Plan::new("policy create");
+        plan.push(Step::api_call(
+            &node_name,
+            "POST",
+            policy_path(&cmd.resource, &cmd.action),
+            json!({ "expression": cmd.expression.to_string() }),
+        ));
+        return plan.print(&opts);
+    }
     let bdy = Policy::new(cmd.expression);
     let req = Request::post(policy_path(&cmd.resource, &cmd.action)).body(bdy);
     let node = BackgroundNode::create(ctx, &opts.state, &node_name).await?;
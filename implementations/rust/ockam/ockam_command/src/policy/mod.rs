@@ -22,7 +22,7 @@ mod show;
 #[derive(Clone, Debug, Args)]
 pub struct PolicyCommand {
     #[command(subcommand)]
-    subcommand: PolicySubcommand,
+    pub subcommand: PolicySubcommand,
 }
 
 #[derive(Clone, Debug, Subcommand)]
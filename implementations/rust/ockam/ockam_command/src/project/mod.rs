@@ -4,6 +4,7 @@ mod delete;
 pub(crate) mod enroll;
 mod info;
 mod list;
+mod nodes;
 mod show;
 mod ticket;
 pub mod util;
@@ -18,6 +19,7 @@ pub use delete::DeleteCommand;
 pub use enroll::EnrollCommand;
 pub use info::InfoCommand;
 pub use list::ListCommand;
+pub use nodes::NodesCommand;
 pub use show::ShowCommand;
 pub use ticket::TicketCommand;
 pub use version::VersionCommand;
@@ -50,6 +52,7 @@ pub enum ProjectSubcommand {
     Ticket(TicketCommand),
     Addon(AddonCommand),
     Enroll(EnrollCommand),
+    Nodes(NodesCommand),
 }
 
 impl ProjectCommand {
@@ -64,6 +67,7 @@ impl ProjectCommand {
             ProjectSubcommand::Information(c) => c.run(options),
             ProjectSubcommand::Addon(c) => c.run(options),
             ProjectSubcommand::Enroll(c) => c.run(options),
+            ProjectSubcommand::Nodes(c) => c.run(options),
         }
     }
 }
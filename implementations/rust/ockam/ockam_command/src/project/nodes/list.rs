@@ -0,0 +1,98 @@
+use clap::Args;
+use miette::{miette, IntoDiagnostic};
+use tokio::sync::Mutex;
+use tokio::try_join;
+
+use ockam::Context;
+use ockam_api::cli_state::{StateDirTrait, StateItemTrait};
+use ockam_api::cloud::project_node::{LabelSelector, ProjectNodes};
+use ockam_api::nodes::InMemoryNode;
+
+use crate::project::addon::get_project_id;
+use crate::util::api::CloudOpts;
+use crate::util::node_rpc;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// List the nodes registered in a project
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct ListCommand {
+    /// Name of the project. Defaults to the default project
+    #[arg(long = "project", value_name = "PROJECT_NAME")]
+    project_name: Option<String>,
+
+    /// Only list the nodes with matching labels, for example `role=gateway,region!=eu-west`
+    #[arg(long, short = 'l', value_name = "SELECTOR")]
+    selector: Option<LabelSelector>,
+
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+}
+
+impl ListCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(run_impl, (opts, self));
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, ListCommand),
+) -> miette::Result<()> {
+    let project_name = match cmd.project_name {
+        Some(name) => name,
+        None => opts
+            .state
+            .projects
+            .default()
+            .map_err(|_| miette!("There is no default project, please use the --project argument"))?
+            .name()
+            .to_string(),
+    };
+    let project_id = get_project_id(&opts.state, &project_name)?;
+
+    let node = InMemoryNode::start(&ctx, &opts.state).await?;
+    let controller = node.create_controller().await?;
+    let is_finished: Mutex<bool> = Mutex::new(false);
+
+    let get_nodes = async {
+        let nodes = controller.list_project_nodes(&ctx, project_id).await?;
+        *is_finished.lock().await = true;
+        Ok(nodes)
+    };
+
+    let output_messages = vec![format!(
+        "Listing the nodes of the project {project_name}...\n"
+    )];
+    let progress_output = opts
+        .terminal
+        .progress_output(&output_messages, &is_finished);
+
+    let (nodes, _) = try_join!(get_nodes, progress_output)?;
+    let selector = cmd.selector.unwrap_or_default();
+    let nodes: Vec<_> = nodes
+        .into_iter()
+        .filter(|n| selector.matches(&n.labels))
+        .collect();
+
+    let plain = opts.terminal.build_list(
+        &nodes,
+        &format!("Nodes of project {project_name}"),
+        "No nodes found in this project.",
+    )?;
+    let json = serde_json::to_string_pretty(&nodes).into_diagnostic()?;
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .json(json)
+        .write_line()?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+mod list;
+
+use clap::{Args, Subcommand};
+
+use crate::project::nodes::list::ListCommand;
+use crate::CommandGlobalOpts;
+
+/// Query the nodes registered in the inventory of a project
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, subcommand_required = true)]
+pub struct NodesCommand {
+    #[command(subcommand)]
+    subcommand: NodesSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum NodesSubcommand {
+    List(ListCommand),
+}
+
+impl NodesCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        match self.subcommand {
+            NodesSubcommand::List(cmd) => cmd.run(opts),
+        }
+    }
+}
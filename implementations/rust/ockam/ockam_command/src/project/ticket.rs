@@ -9,13 +9,13 @@ use miette::{miette, IntoDiagnostic};
 use ockam::identity::Identifier;
 use ockam::Context;
 use ockam_api::authenticator::enrollment_tokens::{Members, TokenIssuer};
-use ockam_api::cli_state::{CliState, StateDirTrait, StateItemTrait};
-use ockam_api::config::lookup::{ProjectAuthority, ProjectLookup};
+use ockam_api::config::lookup::ProjectLookup;
 use ockam_api::nodes::InMemoryNode;
 
-use ockam_multiaddr::{proto, MultiAddr, Protocol};
+use ockam_multiaddr::MultiAddr;
 
 use crate::identity::{get_identity_name, initialize_identity_if_default};
+use crate::project::util::get_project;
 
 use crate::util::api::{CloudOpts, TrustContextOpts};
 use crate::util::node_rpc;
@@ -57,6 +57,11 @@ pub struct TicketCommand {
         conflicts_with = "member"
     )]
     usage_count: Option<u64>,
+
+    /// Duration of the membership obtained with the ticket, for example the TTL of an
+    /// ephemeral identity created for a CI job
+    #[arg(long = "member-expires-in", value_name = "DURATION", conflicts_with = "member", value_parser=duration_parser)]
+    member_expires_in: Option<Duration>,
 }
 
 impl TicketCommand {
@@ -140,7 +145,13 @@ async fn run_impl(
             .await?
     } else {
         let token = authority_node
-            .create_token(&ctx, cmd.attributes()?, cmd.expires_in, cmd.usage_count)
+            .create_token(
+                &ctx,
+                cmd.attributes()?,
+                cmd.expires_in,
+                cmd.usage_count,
+                cmd.member_expires_in,
+            )
             .await?;
 
         let ticket = EnrollmentTicket::new(token, project, trust_context);
@@ -154,32 +165,3 @@ async fn run_impl(
 
     Ok(())
 }
-
-/// Get the project authority from the first address protocol.
-///
-/// If the first protocol is a `/project`, look up the project's config.
-async fn get_project(
-    cli_state: &CliState,
-    input: &MultiAddr,
-) -> Result<(Option<ProjectLookup>, Option<ProjectAuthority>)> {
-    if let Some(proto) = input.first() {
-        if proto.code() == proto::Project::CODE {
-            let proj = proto.cast::<proto::Project>().expect("project protocol");
-            return if let Ok(p) = cli_state.projects.get(proj.to_string()) {
-                let c = p.config();
-                let a =
-                    ProjectAuthority::from_raw(&c.authority_access_route, &c.authority_identity)
-                        .await?;
-                if a.is_some() {
-                    let p = ProjectLookup::from_project(c).await?;
-                    Ok((Some(p), a))
-                } else {
-                    Err(miette!("missing authority in project {:?}", &*proj).into())
-                }
-            } else {
-                Err(miette!("unknown project {}", &*proj).into())
-            };
-        }
-    }
-    Ok((None, None))
-}
@@ -7,10 +7,10 @@ use tokio_retry::strategy::FixedInterval;
 use tokio_retry::Retry;
 use tracing::debug;
 
-use ockam_api::cli_state::{StateDirTrait, StateItemTrait};
+use ockam_api::cli_state::{CliState, StateDirTrait, StateItemTrait};
 use ockam_api::cloud::project::{Project, Projects};
 use ockam_api::cloud::{Controller, ORCHESTRATOR_AWAIT_TIMEOUT};
-use ockam_api::config::lookup::LookupMeta;
+use ockam_api::config::lookup::{LookupMeta, ProjectAuthority, ProjectLookup};
 use ockam_api::error::ApiError;
 use ockam_api::nodes::service::relay::SecureChannelsCreation;
 use ockam_api::nodes::InMemoryNode;
@@ -18,7 +18,7 @@ use ockam_api::nodes::InMemoryNode;
 use ockam_api::route_to_multiaddr;
 use ockam_core::compat::str::FromStr;
 use ockam_core::route;
-use ockam_multiaddr::{MultiAddr, Protocol};
+use ockam_multiaddr::{proto, MultiAddr, Protocol};
 use ockam_node::Context;
 
 use crate::{CommandGlobalOpts, Result};
@@ -274,3 +274,32 @@ pub async fn refresh_projects(
     }
     Ok(())
 }
+
+/// Get the project authority from the first address protocol.
+///
+/// If the first protocol is a `/project`, look up the project's config.
+pub async fn get_project(
+    cli_state: &CliState,
+    input: &MultiAddr,
+) -> Result<(Option<ProjectLookup>, Option<ProjectAuthority>)> {
+    if let Some(proto) = input.first() {
+        if proto.code() == proto::Project::CODE {
+            let proj = proto.cast::<proto::Project>().expect("project protocol");
+            return if let Ok(p) = cli_state.projects.get(proj.to_string()) {
+                let c = p.config();
+                let a =
+                    ProjectAuthority::from_raw(&c.authority_access_route, &c.authority_identity)
+                        .await?;
+                if a.is_some() {
+                    let p = ProjectLookup::from_project(c).await?;
+                    Ok((Some(p), a))
+                } else {
+                    Err(miette!("missing authority in project {:?}", &*proj).into())
+                }
+            } else {
+                Err(miette!("unknown project {}", &*proj).into())
+            };
+        }
+    }
+    Ok((None, None))
+}
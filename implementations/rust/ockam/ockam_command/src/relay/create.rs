@@ -19,7 +19,7 @@ use ockam_multiaddr::proto::Project;
 use ockam_multiaddr::{MultiAddr, Protocol};
 
 use crate::node::{get_node_name, initialize_node_if_default};
-use crate::output::Output;
+use crate::output::{Output, SchemaOutput};
 use crate::terminal::OckamColor;
 use crate::util::{node_rpc, process_nodes_multiaddr};
 use crate::{display_parse_logs, docs, fmt_ok, CommandGlobalOpts};
@@ -128,7 +128,9 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> m
     let (relay, _) = try_join!(get_relay_info, progress_output)?;
 
     let machine = relay.remote_address_ma().into_diagnostic()?;
-    let json = serde_json::to_string_pretty(&relay).into_diagnostic()?;
+    let json =
+        serde_json::to_string_pretty(&relay.schema_output(opts.global_args.output_schema())?)
+            .into_diagnostic()?;
 
     let formatted_from = format!(
         "{}{}",
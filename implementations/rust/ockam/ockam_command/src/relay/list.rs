@@ -1,21 +1,34 @@
+use std::str::FromStr;
+
 use clap::Args;
 use colorful::Colorful;
 use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+use serde_json::Value;
 use tokio::sync::Mutex;
 use tokio::try_join;
 use tracing::trace;
 
+use ockam::identity::TimestampInSeconds;
 use ockam::Context;
 use ockam_api::address::extract_address_value;
 use ockam_api::cli_state::StateDirTrait;
-use ockam_api::nodes::models::relay::RelayInfo;
+use ockam_api::nodes::models::relay::{RelayInfo, RelayServiceEntry};
+use ockam_api::nodes::service::relay::Relays;
 use ockam_api::nodes::BackgroundNode;
+use ockam_api::DefaultAddress;
 use ockam_core::api::Request;
+use ockam_multiaddr::MultiAddr;
 
 use crate::node::{get_node_name, initialize_node_if_default};
+use crate::output::{
+    human_readable_time, schema_json_list, to_schema_value, unsupported_schema, Output,
+    SchemaOutput,
+};
+use crate::relay::relay_schema_output;
 use crate::terminal::OckamColor;
 use crate::util::node_rpc;
-use crate::{docs, CommandGlobalOpts};
+use crate::{docs, fmt_warn, CommandGlobalOpts};
 
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
 const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
@@ -31,17 +44,237 @@ const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
 )]
 pub struct ListCommand {
     /// Get the list of Relays at the given node
-    #[arg(global = true, long, value_name = "NODE")]
+    #[arg(global = true, long, value_name = "NODE", conflicts_with = "all")]
     pub to: Option<String>,
+
+    /// Get the Relays registered by all the running local nodes, including the Relays
+    /// registered at the project, with their creation time and liveness
+    #[arg(long)]
+    pub all: bool,
 }
 
 impl ListCommand {
     pub fn run(self, options: CommandGlobalOpts) {
-        initialize_node_if_default(&options, &self.to);
-        node_rpc(run_impl, (options, self));
+        if self.all {
+            node_rpc(run_all_impl, (options, self));
+        } else {
+            initialize_node_if_default(&options, &self.to);
+            node_rpc(run_impl, (options, self));
+        }
+    }
+}
+
+/// Relay registered by a local node, which is the target of the relay
+struct NodeRelayOutput {
+    node_name: String,
+    relay: RelayInfo,
+}
+
+impl SchemaOutput for NodeRelayOutput {
+    fn schema_output(&self, version: u8) -> crate::error::Result<Value> {
+        relay_schema_output(&self.relay, Some(&self.node_name), version)
+    }
+}
+
+impl Output for NodeRelayOutput {
+    fn output(&self) -> crate::error::Result<String> {
+        self.list_output()
+    }
+
+    fn list_output(&self) -> crate::error::Result<String> {
+        let created_at = self
+            .relay
+            .created_at()
+            .map(|t| human_readable_time(TimestampInSeconds(t)))
+            .unwrap_or_else(|| "unknown".to_string());
+        let liveness = match self.relay.is_up() {
+            Some(true) => "up",
+            Some(false) => "down",
+            None => "not monitored",
+        };
+        Ok(format!(
+            r#"Relay {}
+Target node {}
+Route {}
+Created at {created_at}, {liveness}"#,
+            self.relay
+                .remote_address()
+                .color(OckamColor::PrimaryResource.color()),
+            self.node_name
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            self.relay.forwarding_route(),
+        ))
+    }
+}
+
+/// Relay registered at the project, with the local node which is its target, if any
+struct ProjectRelayOutput {
+    project_name: String,
+    node_name: Option<String>,
+    entry: RelayServiceEntry,
+}
+
+/// Document of a relay registered at a project in the version 1 of the output schema
+#[derive(Serialize)]
+struct ProjectRelayOutputV1<'a> {
+    /// Name of the project where the relay is registered
+    project_name: &'a str,
+    /// Name of the local node targeted by the relay, if the relay was registered by a local node
+    node_name: Option<&'a str>,
+    /// Address of the relay on the project node
+    remote_address: &'a str,
+    /// Route from the project node to the worker which registered the relay
+    forward_route: &'a str,
+    /// Creation time of the relay, in seconds since the Unix epoch
+    created_at: u64,
+}
+
+impl SchemaOutput for ProjectRelayOutput {
+    fn schema_output(&self, version: u8) -> crate::error::Result<Value> {
+        match version {
+            1 => to_schema_value(ProjectRelayOutputV1 {
+                project_name: &self.project_name,
+                node_name: self.node_name.as_deref(),
+                remote_address: &self.entry.remote_address,
+                forward_route: &self.entry.forward_route,
+                created_at: self.entry.created_at,
+            }),
+            _ => Err(unsupported_schema(version)),
+        }
+    }
+}
+
+impl Output for ProjectRelayOutput {
+    fn output(&self) -> crate::error::Result<String> {
+        self.list_output()
+    }
+
+    fn list_output(&self) -> crate::error::Result<String> {
+        let created_at = if self.entry.created_at == 0 {
+            "unknown".to_string()
+        } else {
+            human_readable_time(TimestampInSeconds(self.entry.created_at))
+        };
+        Ok(format!(
+            r#"Relay {}
+Target node {}
+Route {}
+Created at {created_at}"#,
+            self.entry
+                .remote_address
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            self.node_name
+                .as_deref()
+                .unwrap_or("not a local node")
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            self.entry.forward_route,
+        ))
     }
 }
 
+async fn run_all_impl(
+    ctx: Context,
+    (opts, _cmd): (CommandGlobalOpts, ListCommand),
+) -> miette::Result<()> {
+    let mut relays = vec![];
+    let mut nodes = vec![];
+    for node_name in opts.state.nodes.list_items_names()? {
+        let is_running = opts
+            .state
+            .nodes
+            .get(&node_name)
+            .map(|n| n.is_running())
+            .unwrap_or(false);
+        if !is_running {
+            continue;
+        }
+        let node_relays = match BackgroundNode::create(&ctx, &opts.state, &node_name).await {
+            Ok(node) => {
+                let node_relays = node
+                    .ask::<(), Vec<RelayInfo>>(&ctx, Request::get("/node/forwarder"))
+                    .await;
+                nodes.push(node);
+                node_relays
+            }
+            Err(e) => Err(e),
+        };
+        match node_relays {
+            Ok(node_relays) => {
+                relays.extend(node_relays.into_iter().map(|relay| NodeRelayOutput {
+                    node_name: node_name.clone(),
+                    relay,
+                }))
+            }
+            Err(e) => opts.terminal.write_line(&fmt_warn!(
+                "The relays of the node {node_name} could not be retrieved: {e}"
+            ))?,
+        }
+    }
+    trace!(count = relays.len(), "Relays retrieved");
+
+    // The relays of the project are retrieved by the first running node
+    let mut project_relays = vec![];
+    if let (Ok(project), Some(node)) = (opts.state.projects.default(), nodes.first()) {
+        let project_name = project.name().to_string();
+        let address = MultiAddr::from_str(&format!(
+            "/project/{project_name}/service/{}",
+            DefaultAddress::RELAY_SERVICE
+        ))
+        .into_diagnostic()?;
+        match node.get_relay_service_entries(&ctx, &address).await {
+            Ok(entries) => project_relays.extend(entries.into_iter().map(|entry| {
+                ProjectRelayOutput {
+                    node_name: relays
+                        .iter()
+                        .find(|r| r.relay.remote_address() == entry.remote_address)
+                        .map(|r| r.node_name.clone()),
+                    project_name: project_name.clone(),
+                    entry,
+                }
+            })),
+            Err(e) => opts.terminal.write_line(&fmt_warn!(
+                "The relays of the project {project_name} could not be retrieved: {e}"
+            ))?,
+        }
+    }
+    trace!(count = project_relays.len(), "Project relays retrieved");
+
+    let mut plain = opts.terminal.build_list(
+        &relays,
+        "Relays of all the local nodes",
+        "No Relays found on the local nodes.",
+    )?;
+    if !project_relays.is_empty() {
+        plain.push_str(&opts.terminal.build_list(
+            &project_relays,
+            "Relays of the project",
+            "No Relays found at the project.",
+        )?);
+    }
+    let version = opts.global_args.output_schema();
+    let json = serde_json::to_string_pretty(&serde_json::json!({
+        "node_relays": relays
+            .iter()
+            .map(|r| r.schema_output(version))
+            .collect::<crate::error::Result<Vec<_>>>()?,
+        "project_relays": project_relays
+            .iter()
+            .map(|r| r.schema_output(version))
+            .collect::<crate::error::Result<Vec<_>>>()?,
+    }))
+    .into_diagnostic()?;
+
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .json(json)
+        .write_line()?;
+    Ok(())
+}
+
 async fn run_impl(
     ctx: Context,
     (opts, cmd): (CommandGlobalOpts, ListCommand),
@@ -81,7 +314,7 @@ async fn run_impl(
         &format!("Relays on Node {node_name}"),
         &format!("No Relays found on node {node_name}."),
     )?;
-    let json = serde_json::to_string_pretty(&relays).into_diagnostic()?;
+    let json = schema_json_list(&relays, opts.global_args.output_schema())?;
 
     opts.terminal
         .stdout()
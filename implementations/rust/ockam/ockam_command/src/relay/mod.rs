@@ -1,10 +1,15 @@
 use clap::{Args, Subcommand};
+use serde::Serialize;
+use serde_json::Value;
+
+use ockam_api::nodes::models::relay::RelayInfo;
 
 pub(crate) use create::CreateCommand;
 pub(crate) use delete::DeleteCommand;
 pub(crate) use list::ListCommand;
 pub(crate) use show::ShowCommand;
 
+use crate::output::{to_schema_value, unsupported_schema, SchemaOutput};
 use crate::{docs, CommandGlobalOpts};
 
 mod create;
@@ -25,7 +30,7 @@ const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
 )]
 pub struct RelayCommand {
     #[command(subcommand)]
-    subcommand: RelaySubCommand,
+    pub subcommand: RelaySubCommand,
 }
 
 #[derive(Clone, Debug, Subcommand)]
@@ -46,3 +51,71 @@ impl RelayCommand {
         }
     }
 }
+
+/// Document of a relay in the version 1 of the output schema
+#[derive(Serialize)]
+struct RelayOutputV1<'a> {
+    /// Name of the local node targeted by the relay, when the relays of several nodes are listed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    node_name: Option<&'a str>,
+    /// Address of the relay on the node where it is registered
+    remote_address: &'a str,
+    /// Route to the relay
+    forwarding_route: &'a str,
+    /// Address of the worker maintaining the relay on the local node
+    worker_address: &'a str,
+    /// Flow control id of the messages received from the relay
+    flow_control_id: Option<String>,
+    /// Most recent status changes of the relay, when its registration is monitored
+    events: Vec<RelayEventOutputV1<'a>>,
+    /// Creation time of the relay, in seconds since the Unix epoch, when it is monitored
+    created_at: Option<u64>,
+    /// True if the relay answers the pings of the node, when it is monitored
+    up: Option<bool>,
+}
+
+/// Document of a status change of a relay in the version 1 of the output schema
+#[derive(Serialize)]
+struct RelayEventOutputV1<'a> {
+    /// Time of the event, in seconds since the Unix epoch
+    timestamp: u64,
+    /// Status of the relay after the event: up, degraded or down
+    status: &'a str,
+    /// Description of the event
+    message: &'a str,
+}
+
+/// Return the document of a relay, targeting a local node if its name is given
+fn relay_schema_output(
+    relay: &RelayInfo,
+    node_name: Option<&str>,
+    version: u8,
+) -> crate::Result<Value> {
+    match version {
+        1 => to_schema_value(RelayOutputV1 {
+            node_name,
+            remote_address: relay.remote_address(),
+            forwarding_route: relay.forwarding_route(),
+            worker_address: relay.worker_address(),
+            flow_control_id: relay.flow_control_id().as_ref().map(|id| id.to_string()),
+            events: relay
+                .events()
+                .iter()
+                .map(|e| RelayEventOutputV1 {
+                    timestamp: e.timestamp,
+                    status: &e.status,
+                    message: &e.message,
+                })
+                .collect(),
+            created_at: relay.created_at(),
+            up: relay.is_up(),
+        }),
+        _ => Err(unsupported_schema(version)),
+    }
+}
+
+impl SchemaOutput for RelayInfo {
+    fn schema_output(&self, version: u8) -> crate::Result<Value> {
+        relay_schema_output(self, None, version)
+    }
+}
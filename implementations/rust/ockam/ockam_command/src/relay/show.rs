@@ -1,10 +1,13 @@
+use std::fmt::Write;
+
 use clap::Args;
 use indoc::formatdoc;
 use miette::IntoDiagnostic;
 
+use ockam::identity::TimestampInSeconds;
 use ockam::Context;
 use ockam_api::address::extract_address_value;
-use ockam_api::nodes::models::relay::RelayInfo;
+use ockam_api::nodes::models::relay::{RelayEvent, RelayInfo};
 use ockam_api::nodes::BackgroundNode;
 use ockam_core::api::Request;
 use ockam_multiaddr::MultiAddr;
@@ -12,7 +15,7 @@ use ockam_multiaddr::MultiAddr;
 use serde::Serialize;
 
 use crate::node::get_node_name;
-use crate::output::Output;
+use crate::output::{human_readable_time, Output};
 use crate::util::node_rpc;
 use crate::{docs, CommandGlobalOpts};
 
@@ -47,11 +50,12 @@ struct RelayShowOutput {
     pub relay_route: String,
     pub remote_address: MultiAddr,
     pub worker_address: MultiAddr,
+    pub events: Vec<RelayEvent>,
 }
 
 impl Output for RelayShowOutput {
     fn output(&self) -> crate::error::Result<String> {
-        Ok(formatdoc!(
+        let mut output = formatdoc!(
             r#"
         Relay:
             Relay Route: {route}
@@ -61,7 +65,20 @@ impl Output for RelayShowOutput {
             route = self.relay_route,
             remote_addr = self.remote_address,
             worker_addr = self.worker_address,
-        ))
+        );
+        if !self.events.is_empty() {
+            writeln!(output, "    Events:")?;
+            for event in &self.events {
+                writeln!(
+                    output,
+                    "        {} {}: {}",
+                    human_readable_time(TimestampInSeconds(event.timestamp)),
+                    event.status,
+                    event.message
+                )?;
+            }
+        }
+        Ok(output)
     }
 }
 
@@ -84,6 +101,7 @@ async fn run_impl(
         relay_route: relay_info.forwarding_route().to_string(),
         remote_address: relay_info.remote_address_ma().into_diagnostic()?,
         worker_address: relay_info.worker_address_ma().into_diagnostic()?,
+        events: relay_info.events().to_vec(),
     };
 
     opts.terminal
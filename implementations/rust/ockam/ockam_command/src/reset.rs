@@ -1,30 +1,120 @@
 use crate::terminal::ConfirmResult;
 use crate::util::local_cmd;
-use crate::{fmt_ok, CommandGlobalOpts};
+use crate::Result;
+use crate::{fmt_log, fmt_ok, CommandGlobalOpts};
 use clap::Args;
 use colorful::Colorful;
 use miette::miette;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
 use ockam_api::cli_state::CliState;
 
-/// Removes the local Ockam configuration including all Identities and Nodes
+/// Removes the local Ockam configuration including all Identities and Nodes,
+/// or only some parts of it
 #[derive(Clone, Debug, Args)]
 pub struct ResetCommand {
     /// Confirm the reset without prompting
     #[arg(display_order = 901, long, short)]
     yes: bool,
+
+    /// Only delete the nodes, after stopping them
+    #[arg(display_order = 902, long)]
+    nodes: bool,
+
+    /// Only delete the identities, with the credentials. The identities used by nodes
+    /// which are not deleted are kept, and the vaults are deleted when no identity is left
+    #[arg(display_order = 902, long)]
+    identities: bool,
+
+    /// Only delete the spaces and projects
+    #[arg(display_order = 902, long)]
+    projects: bool,
+
+    /// Keep the enrolled identities, their vaults, spaces and projects,
+    /// so that Ockam Orchestrator can be used without enrolling again
+    #[arg(display_order = 902, long, conflicts_with = "projects")]
+    keep_enrollment: bool,
+}
+
+/// Parts of the local configuration which can be deleted separately
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Scope {
+    Nodes,
+    Identities,
+    Projects,
+}
+
+impl Scope {
+    const ALL: [Scope; 3] = [Scope::Nodes, Scope::Identities, Scope::Projects];
+
+    fn description(&self) -> &'static str {
+        match self {
+            Scope::Nodes => "nodes",
+            Scope::Identities => "identities, vaults and credentials",
+            Scope::Projects => "spaces and projects",
+        }
+    }
 }
 
 impl ResetCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
         local_cmd(run_impl(opts, self));
     }
+
+    /// Return the scopes selected with the flags, or by the user when no flag is given.
+    /// All the scopes are selected when the user can't be asked
+    fn scopes(&self, opts: &CommandGlobalOpts) -> Result<Vec<Scope>> {
+        Ok(self
+            .selected_scopes(opts)?
+            .into_iter()
+            .filter(|s| !(self.keep_enrollment && *s == Scope::Projects))
+            .collect())
+    }
+
+    fn selected_scopes(&self, opts: &CommandGlobalOpts) -> Result<Vec<Scope>> {
+        let flags = [
+            (Scope::Nodes, self.nodes),
+            (Scope::Identities, self.identities),
+            (Scope::Projects, self.projects),
+        ];
+        let selected: Vec<Scope> = flags.iter().filter(|f| f.1).map(|f| f.0).collect();
+        if !selected.is_empty() {
+            return Ok(selected);
+        }
+        if self.yes || !opts.terminal.can_ask_for_user_input() {
+            return Ok(Scope::ALL.to_vec());
+        }
+        let choices = opts.terminal.select_multiple(
+            "Select what to delete".to_string(),
+            Scope::ALL
+                .iter()
+                .filter(|s| !(self.keep_enrollment && **s == Scope::Projects))
+                .map(|s| capitalize(s.description()))
+                .collect(),
+        )?;
+        Ok(Scope::ALL
+            .iter()
+            .copied()
+            .filter(|s| choices.contains(&capitalize(s.description())))
+            .collect())
+    }
 }
 
 fn run_impl(opts: CommandGlobalOpts, cmd: ResetCommand) -> miette::Result<()> {
+    let scopes = cmd.scopes(&opts)?;
+    if scopes.is_empty() {
+        return Ok(());
+    }
+    let full_reset = scopes.len() == Scope::ALL.len();
+    let description = if full_reset {
+        "the local Ockam configuration".to_string()
+    } else {
+        format!("the local {}", describe(&scopes))
+    };
+
     if !cmd.yes {
         match opts
             .terminal
-            .confirm("This will delete the local Ockam configuration. Are you sure?")?
+            .confirm(format!("This will delete {description}. Are you sure?"))?
         {
             ConfirmResult::Yes => {}
             ConfirmResult::No => {
@@ -35,10 +125,94 @@ fn run_impl(opts: CommandGlobalOpts, cmd: ResetCommand) -> miette::Result<()> {
             }
         }
     }
-    CliState::delete()?;
+
+    if full_reset {
+        CliState::delete()?;
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!("Local Ockam configuration deleted"))
+            .write_line()?;
+        return Ok(());
+    }
+
+    let state = &opts.state;
+    // The nodes are deleted first, so that their identities can be deleted
+    if scopes.contains(&Scope::Nodes) {
+        for node in state.nodes.list()? {
+            state.nodes.delete_sigkill(node.name(), true)?;
+        }
+    }
+    if scopes.contains(&Scope::Identities) {
+        delete_identities(state, cmd.keep_enrollment)?;
+    }
+    if scopes.contains(&Scope::Projects) {
+        for project in state.projects.list_items_names()? {
+            state.projects.delete(project)?;
+        }
+        for space in state.spaces.list_items_names()? {
+            state.spaces.delete(space)?;
+        }
+    }
+
+    let deleted = describe(&scopes);
+    let mut plain = fmt_ok!("Local {deleted} deleted\n");
+    if cmd.keep_enrollment {
+        plain.push_str(&fmt_log!(
+            "The enrolled identities, their spaces and projects are kept"
+        ));
+    }
     opts.terminal
         .stdout()
-        .plain(fmt_ok!("Local Ockam configuration deleted"))
+        .plain(plain)
+        .json(serde_json::json!({
+            "deleted": scopes.iter().map(|s| s.description()).collect::<Vec<_>>(),
+            "keep_enrollment": cmd.keep_enrollment,
+        }))
         .write_line()?;
     Ok(())
 }
+
+/// Delete the identities which are not used by a node, and the credentials unless the
+/// enrolled identities are kept. The vaults are only deleted when no identity is left,
+/// since they store the keys of the identities
+fn delete_identities(state: &CliState, keep_enrollment: bool) -> miette::Result<()> {
+    let used: Vec<_> = state
+        .nodes
+        .list()?
+        .iter()
+        .filter_map(|n| n.config().identifier().ok())
+        .collect();
+    for identity in state.identities.list()? {
+        if (keep_enrollment && identity.is_enrolled()) || used.contains(&identity.identifier()) {
+            continue;
+        }
+        state.identities.delete(identity.name())?;
+    }
+    if !keep_enrollment {
+        for credential in state.credentials.list_items_names()? {
+            state.credentials.delete(credential)?;
+        }
+    }
+    if state.identities.list()?.is_empty() {
+        for vault in state.vaults.list_items_names()? {
+            state.vaults.delete(vault)?;
+        }
+    }
+    Ok(())
+}
+
+fn describe(scopes: &[Scope]) -> String {
+    scopes
+        .iter()
+        .map(|s| s.description())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
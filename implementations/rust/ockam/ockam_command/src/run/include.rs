@@ -0,0 +1,245 @@
+//! Configurations split across several files.
+//!
+//! Like the `Include` directive of OpenSSH, the top-level `include` key of a configuration
+//! lists other files to merge with it, for example to share the portals of a team:
+//!
+//! ```yml
+//! include:
+//!   - ~/.ockam/portals.d/*.yaml
+//!   - ../shared/relays.yaml
+//! nodes:
+//!   n1:
+//!     identity: alice
+//! ```
+//!
+//! The relative paths are relative to the directory of the including file. A directory
+//! includes all its `.yml` and `.yaml` files, and the file name of a path can contain the `*`
+//! and `?` wildcards. The files are merged in the order of their paths, and a value defined
+//! differently in two files is an error, so that the result doesn't depend on that order.
+
+use miette::{miette, Context, IntoDiagnostic};
+use serde_yaml::{Mapping, Value};
+use std::path::{Path, PathBuf};
+
+const INCLUDE_KEY: &str = "include";
+const CONFIG_EXTENSIONS: &[&str] = &["yml", "yaml"];
+
+/// Read a configuration file, merged with the files it includes
+pub fn read_config(path: &Path) -> miette::Result<String> {
+    let value = Loader::default().load_file(path)?;
+    serde_yaml::to_string(&value).into_diagnostic()
+}
+
+/// Merge an inline configuration with the files it includes, relative to a directory
+pub fn resolve_includes(contents: &str, dir: &Path) -> miette::Result<String> {
+    let value = Loader::default().load_str(contents, dir, Path::new("the inline configuration"))?;
+    serde_yaml::to_string(&value).into_diagnostic()
+}
+
+#[derive(Default)]
+struct Loader {
+    /// Files being loaded, to detect the include cycles
+    stack: Vec<PathBuf>,
+}
+
+impl Loader {
+    fn load_file(&mut self, path: &Path) -> miette::Result<Value> {
+        let path = path
+            .canonicalize()
+            .into_diagnostic()
+            .context(format!("failed to read {}", path.display()))?;
+        if self.stack.contains(&path) {
+            return Err(miette!(
+                "The configuration file {} includes itself",
+                path.display()
+            ));
+        }
+        let contents = std::fs::read_to_string(&path)
+            .into_diagnostic()
+            .context(format!("failed to read {}", path.display()))?;
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        self.stack.push(path.clone());
+        let value = self.load_str(&contents, &dir, &path);
+        self.stack.pop();
+        value
+    }
+
+    fn load_str(&mut self, contents: &str, dir: &Path, source: &Path) -> miette::Result<Value> {
+        let mut value: Value = serde_yaml::from_str(contents)
+            .into_diagnostic()
+            .context(format!("invalid YAML in {}", source.display()))?;
+        if value.is_null() {
+            value = Value::Mapping(Mapping::new());
+        }
+        let includes = match value.as_mapping_mut().and_then(|m| m.remove(INCLUDE_KEY)) {
+            None => vec![],
+            Some(Value::String(pattern)) => vec![pattern],
+            Some(Value::Sequence(patterns)) => patterns
+                .into_iter()
+                .map(|p| match p {
+                    Value::String(pattern) => Ok(pattern),
+                    _ => Err(include_error(source)),
+                })
+                .collect::<miette::Result<_>>()?,
+            Some(_) => return Err(include_error(source)),
+        };
+        for pattern in includes {
+            for file in expand(&pattern, dir)? {
+                let included = self.load_file(&file)?;
+                merge(&mut value, included, "", &file)?;
+            }
+        }
+        Ok(value)
+    }
+}
+
+fn include_error(source: &Path) -> miette::Report {
+    miette!(
+        "{}: {INCLUDE_KEY} must be a path or a list of paths",
+        source.display()
+    )
+}
+
+/// Return the files of an include path, sorted by path
+fn expand(pattern: &str, dir: &Path) -> miette::Result<Vec<PathBuf>> {
+    let path = match pattern.strip_prefix("~/") {
+        Some(rest) => home::home_dir()
+            .ok_or_else(|| miette!("The home directory can't be found to include {pattern}"))?
+            .join(rest),
+        None => dir.join(pattern),
+    };
+    if path.is_dir() {
+        return list_files(&path, |name| {
+            Path::new(name)
+                .extension()
+                .and_then(|e| e.to_str())
+                .map_or(false, |e| CONFIG_EXTENSIONS.contains(&e))
+        });
+    }
+    let file_name = match path.file_name().and_then(|n| n.to_str()) {
+        Some(name) if name.contains(|c| c == '*' || c == '?') => name.to_string(),
+        // A missing file is reported when it is read
+        _ => return Ok(vec![path]),
+    };
+    let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    if !parent.is_dir() {
+        // Like in OpenSSH, a pattern which doesn't match any file includes nothing
+        return Ok(vec![]);
+    }
+    let file_name: Vec<char> = file_name.chars().collect();
+    list_files(&parent, |name| {
+        wildcard_match(&file_name, &name.chars().collect::<Vec<_>>())
+    })
+}
+
+/// Return the files of a directory whose name is accepted, ignoring the hidden files
+fn list_files(dir: &Path, accept: impl Fn(&str) -> bool) -> miette::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir)
+        .into_diagnostic()
+        .context(format!("failed to read {}", dir.display()))?
+    {
+        let path = entry.into_diagnostic()?.path();
+        let accepted = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map_or(false, |name| !name.starts_with('.') && accept(name));
+        if accepted && path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn wildcard_match(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some('*'), _) => {
+            wildcard_match(&pattern[1..], name)
+                || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        }
+        (Some('?'), Some(_)) => wildcard_match(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => wildcard_match(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+/// Merge an included value, where `path` is the path of the value, like `nodes.n1.identity`
+fn merge(target: &mut Value, value: Value, path: &str, source: &Path) -> miette::Result<()> {
+    match (target, value) {
+        (Value::Mapping(target), Value::Mapping(value)) => {
+            for (key, value) in value {
+                let key_path = match key.as_str() {
+                    Some(key) if path.is_empty() => key.to_string(),
+                    Some(key) => format!("{path}.{key}"),
+                    None => path.to_string(),
+                };
+                match target.get_mut(&key) {
+                    Some(existing) => merge(existing, value, &key_path, source)?,
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+            Ok(())
+        }
+        // A node can be declared without any value in a file, and defined in another one
+        (target, value) if target.is_null() => {
+            *target = value;
+            Ok(())
+        }
+        (target, value) if value.is_null() || *target == value => Ok(()),
+        _ => Err(miette!(
+            "{path} is defined with a different value in {}",
+            source.display()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run::parser::Config;
+
+    #[test]
+    fn included_files_are_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        let portals = dir.path().join("portals.d");
+        std::fs::create_dir(&portals).unwrap();
+        std::fs::write(
+            portals.join("db.yaml"),
+            "nodes:\n  n1:\n    tcp-inlets:\n      db:\n        from: 127.0.0.1:5432\n        to: /project/default/service/db\n",
+        )
+        .unwrap();
+        std::fs::write(
+            portals.join("web.yaml"),
+            "nodes:\n  n1:\n    tcp-outlets:\n      web:\n        from: /service/web\n        to: 127.0.0.1:8080\n",
+        )
+        .unwrap();
+        std::fs::write(portals.join("notes.txt"), "not a configuration").unwrap();
+        let main = dir.path().join("ockam.yaml");
+        std::fs::write(
+            &main,
+            "include: portals.d/*.yaml\nnodes:\n  n1:\n    identity: alice\n",
+        )
+        .unwrap();
+
+        let config: Config = serde_yaml::from_str(&read_config(&main).unwrap()).unwrap();
+        let n1 = &config.nodes["n1"];
+        assert_eq!(n1.identity.as_deref(), Some("alice"));
+        assert_eq!(n1.tcp_inlets.as_ref().unwrap()["db"].from, "127.0.0.1:5432");
+        assert_eq!(n1.tcp_outlets.as_ref().unwrap()["web"].to, "127.0.0.1:8080");
+
+        // A conflicting definition and an include cycle are rejected
+        std::fs::write(
+            portals.join("other.yaml"),
+            "nodes:\n  n1:\n    identity: bob\n",
+        )
+        .unwrap();
+        assert!(read_config(&main).is_err());
+        std::fs::write(portals.join("other.yaml"), "include: ../ockam.yaml\n").unwrap();
+        let error = read_config(&main).unwrap_err().to_string();
+        assert!(error.contains("includes itself"));
+    }
+}
@@ -1,4 +1,6 @@
+pub(crate) mod include;
 mod parser;
+pub(crate) mod validator;
 
 use crate::util::node_rpc;
 use crate::{docs, CommandGlobalOpts};
@@ -9,10 +11,16 @@ use ockam::Context;
 pub use parser::ConfigRunner;
 use std::path::PathBuf;
 
-/// Create nodes given a declarative configuration file
+/// Create nodes given a declarative configuration file.
+/// The nodes are created in the order of their dependencies, then their policies,
+/// portals and relays, and the result of each step is reported
 #[derive(Clone, Debug, Args)]
 #[command(hide = docs::hide())]
 pub struct RunCommand {
+    /// Path to the recipe file, which can also be given with --recipe
+    #[arg(conflicts_with_all = ["recipe", "inline"])]
+    pub path: Option<PathBuf>,
+
     /// Path to the recipe file
     #[arg(long, conflicts_with = "inline")]
     pub recipe: Option<PathBuf>,
@@ -26,6 +34,11 @@ pub struct RunCommand {
     /// To be used with docker or kubernetes.
     #[arg(long)]
     pub blocking: bool,
+
+    /// If a step fails, delete the nodes, policies, portals and relays created by the
+    /// previous steps, in the reverse order of their creation
+    #[arg(long)]
+    pub rollback: bool,
 }
 
 impl RunCommand {
@@ -40,9 +53,14 @@ async fn rpc(_ctx: Context, (opts, cmd): (CommandGlobalOpts, RunCommand)) -> mie
 
 async fn run_impl(opts: CommandGlobalOpts, cmd: RunCommand) -> miette::Result<()> {
     let config = match cmd.inline {
-        Some(config) => config,
+        Some(config) => {
+            let dir = std::env::current_dir()
+                .into_diagnostic()
+                .context("Failed to get current directory")?;
+            include::resolve_includes(&config, &dir)?
+        }
         None => {
-            let path = match cmd.recipe {
+            let path = match cmd.path.or(cmd.recipe) {
                 Some(path) => path,
                 None => {
                     let mut path = std::env::current_dir()
@@ -67,8 +85,8 @@ async fn run_impl(opts: CommandGlobalOpts, cmd: RunCommand) -> miette::Result<()
                     path
                 }
             };
-            std::fs::read_to_string(path).into_diagnostic()?
+            include::read_config(&path)?
         }
     };
-    ConfigRunner::go(opts, &config, cmd.blocking).await
+    ConfigRunner::go(opts, &config, cmd.blocking, cmd.rollback).await
 }
@@ -1,6 +1,8 @@
-use crate::{shutdown, CommandGlobalOpts};
+use crate::service::config::ScheduledTaskConfig;
+use crate::{fmt_err, fmt_ok, fmt_warn, shutdown, CommandGlobalOpts};
+use colorful::Colorful;
 use duct::Expression;
-use miette::IntoDiagnostic;
+use miette::{miette, IntoDiagnostic};
 use ockam_api::cli_state::StateDirTrait;
 use ockam_core::compat::collections::HashMap;
 use once_cell::sync::Lazy;
@@ -20,24 +22,48 @@ pub struct ParsedCommand {
     pub depends_on: Option<String>,
     pub cmd: Expression,
     pub block_on_node: Option<String>,
+    pub rollback: Option<Rollback>,
+}
+
+/// Undo of a successful command, run when a later command fails with `--rollback`
+#[derive(Clone)]
+pub enum Rollback {
+    /// Delete the node created by the command. A node which existed before is kept
+    DeleteNode(String),
+    /// Delete the resource created by the command
+    Command(Expression),
+}
+
+impl Rollback {
+    fn command(&self) -> Expression {
+        match self {
+            Rollback::DeleteNode(node_name) => ockam_cmd(&["node", "delete", node_name, "--yes"]),
+            Rollback::Command(cmd) => cmd.clone(),
+        }
+    }
 }
 
 impl ConfigRunner {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             commands_sorted: vec![],
             commands_index: Default::default(),
         }
     }
 
-    pub async fn go(opts: CommandGlobalOpts, config: &str, blocking: bool) -> miette::Result<()> {
+    pub async fn go(
+        opts: CommandGlobalOpts,
+        config: &str,
+        blocking: bool,
+        rollback: bool,
+    ) -> miette::Result<()> {
         let mut cr = Self::new();
         cr.parse(config, blocking)?;
-        cr.run(opts).await?;
+        cr.run(opts, rollback).await?;
         Ok(())
     }
 
-    fn parse(&mut self, config: &str, blocking: bool) -> miette::Result<()> {
+    pub(crate) fn parse(&mut self, config: &str, blocking: bool) -> miette::Result<()> {
         let config: Config = serde_yaml::from_str(config).into_diagnostic()?;
         let mut visited = HashSet::new();
         let mut nodes = VecDeque::new();
@@ -82,16 +108,25 @@ impl ConfigRunner {
         Ok(())
     }
 
-    async fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+    async fn run(self, opts: CommandGlobalOpts, rollback: bool) -> miette::Result<()> {
         let mut spawned_nodes = vec![];
         let mut handlers = vec![];
+        // Undo of the successful commands, with the id of their command
+        let mut rollbacks = vec![];
 
         for command in self.commands_sorted.into_iter() {
             debug!("Running command: {}: {:?}", command.id, command.cmd);
 
-            // If a command fails it will show the appropriate error in its subshell.
-            // No need to return an error here.
-            if let Some(spawn_node) = command.block_on_node {
+            // Creating an existing node only starts it, so it must not be deleted by a rollback
+            let command_rollback = match command.rollback {
+                Some(Rollback::DeleteNode(node_name)) if opts.state.nodes.exists(&node_name) => {
+                    None
+                }
+                other => other,
+            };
+
+            // If a command fails it will show the appropriate error in its subshell
+            let succeeded = if let Some(spawn_node) = command.block_on_node {
                 let result = command.cmd.start();
                 match result {
                     Ok(handle) => {
@@ -99,15 +134,23 @@ impl ConfigRunner {
                         spawned_nodes.push(spawn_node);
                     }
                     Err(_err) => {
-                        break;
+                        return Self::fail(&opts, &command.id, rollbacks, rollback, spawned_nodes);
                     }
                 }
 
                 // the next command will expect the node to be up and running
                 //TODO: wait for the node availability rather than sleeping
                 tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            } else if command.cmd.run().is_err() {
-                break;
+                true
+            } else {
+                command.cmd.run().is_ok()
+            };
+            if !succeeded {
+                return Self::fail(&opts, &command.id, rollbacks, rollback, spawned_nodes);
+            }
+            opts.terminal.write_line(&fmt_ok!("{}", command.id))?;
+            if let Some(command_rollback) = command_rollback {
+                rollbacks.push((command.id, command_rollback));
             }
         }
 
@@ -129,17 +172,49 @@ impl ConfigRunner {
             // Wait for CTRL+C or any other exit condition (like receiving a signal)
             shutdown::wait(opts.terminal, true, true, tx, &mut rx).await?;
 
-            // Send a SIGTERM to all nodes if they are still running
-            for node_name in spawned_nodes {
-                if let Ok(node) = opts.state.nodes.get(node_name) {
-                    if node.is_running() {
-                        let _ = node.kill_process(false);
-                    }
+            stop_nodes(&opts, spawned_nodes);
+        }
+
+        Ok(())
+    }
+
+    /// Report a failed command and undo the previous commands if `rollback` is true,
+    /// in the reverse order of their execution
+    fn fail(
+        opts: &CommandGlobalOpts,
+        failed_id: &str,
+        rollbacks: Vec<(String, Rollback)>,
+        rollback: bool,
+        spawned_nodes: Vec<String>,
+    ) -> miette::Result<()> {
+        opts.terminal.write_line(&fmt_err!("{failed_id} failed"))?;
+        if rollback {
+            for (id, command_rollback) in rollbacks.into_iter().rev() {
+                debug!("Rolling back command: {id}");
+                if command_rollback.command().run().is_ok() {
+                    opts.terminal
+                        .write_line(&fmt_ok!("{id} has been rolled back"))?;
+                } else {
+                    opts.terminal
+                        .write_line(&fmt_warn!("{id} couldn't be rolled back"))?;
                 }
             }
         }
+        stop_nodes(opts, spawned_nodes);
+        Err(miette!(
+            "The configuration couldn't be applied: {failed_id} failed"
+        ))
+    }
+}
 
-        Ok(())
+/// Send a SIGTERM to the nodes if they are still running
+fn stop_nodes(opts: &CommandGlobalOpts, node_names: Vec<String>) {
+    for node_name in node_names {
+        if let Ok(node) = opts.state.nodes.get(node_name) {
+            if node.is_running() {
+                let _ = node.kill_process(false);
+            }
+        }
     }
 }
 
@@ -156,6 +231,7 @@ impl ConfigRunner {
 ///
 ///   influxdb:
 ///     enrollment-token: $OCKAM_INFLUXDB_TOKEN
+///     identity: influxdb
 ///     tcp-outlets:
 ///       influxdb:
 ///         from: /service/outlet
@@ -164,7 +240,13 @@ impl ConfigRunner {
 ///     relays:
 ///       influxdb:
 ///         at: /project/default
+///     scheduled-tasks:
+///       - task: probe_relays
+///         every: 1m
 /// ```
+///
+/// The inlets, outlets and relays can also be defined in other files, listed by a top-level
+/// `include` key, see [`crate::run::include`].
 #[derive(Debug, Deserialize)]
 pub struct Config {
     pub nodes: HashMap<String, NodeConfig>,
@@ -177,19 +259,27 @@ pub struct NodeConfig {
     pub depends_on: Option<String>,
     #[serde(rename(deserialize = "enrollment-ticket"))]
     pub enrollment_ticket: Option<String>,
+    /// Name of an existing identity used by the node
+    pub identity: Option<String>,
+    /// Name of an existing trust context used by the node.
+    /// It defaults to the trust context created by the enrollment of the node
+    #[serde(rename(deserialize = "trust-context"))]
+    pub trust_context: Option<String>,
     #[serde(rename(deserialize = "tcp-inlets"))]
     pub tcp_inlets: Option<HashMap<String, InletConfig>>,
     #[serde(rename(deserialize = "tcp-outlets"))]
     pub tcp_outlets: Option<HashMap<String, OutletConfig>>,
     pub relays: Option<HashMap<String, RelayConfig>>,
+    #[serde(rename(deserialize = "scheduled-tasks"))]
+    pub scheduled_tasks: Option<Vec<ScheduledTaskConfig>>,
 }
 
 impl NodeConfig {
     fn parse(self, node_name: &str, blocking: bool, cmds: &mut ConfigRunner) -> miette::Result<()> {
         let mut insert_command =
-            |subject: &str, name: &str, depends_on, args: &[&str], blocks: bool| {
+            |subject: &str, name: &str, depends_on, args: &[&str], blocks: bool, rollback| {
                 debug!("Parsed command: {} {}", binary_path(), args.join(" "));
-                let cmd = duct::cmd(binary_path(), args);
+                let cmd = ockam_cmd(args);
                 let id = format!("{subject}/{name}");
                 if cmds.commands_index.contains_key(&id) {
                     return Err(miette::miette!(
@@ -212,6 +302,7 @@ impl NodeConfig {
                     depends_on,
                     cmd,
                     block_on_node,
+                    rollback,
                 });
                 Ok(())
             };
@@ -231,19 +322,40 @@ impl NodeConfig {
                     enroll_ticket,
                 ],
                 false,
+                None,
             )?;
         }
 
+        // The scheduled tasks are passed to the node with its launch configuration
+        let launch_config = match &self.scheduled_tasks {
+            Some(tasks) => Some(
+                serde_json::to_string(&serde_json::json!({ "scheduled_tasks": tasks }))
+                    .into_diagnostic()?,
+            ),
+            None => None,
+        };
+
         // Always create the node, if it already exists (but not running) it'll be-started.
         let args = {
             let mut args = vec!["node", "create", node_name];
             if blocking {
                 args.push("--foreground");
             }
-            if self.enrollment_ticket.is_some() {
+            if let Some(identity) = &self.identity {
+                args.push("--identity");
+                args.push(identity);
+            }
+            if let Some(trust_context) = &self.trust_context {
+                args.push("--trust-context");
+                args.push(trust_context);
+            } else if self.enrollment_ticket.is_some() {
                 args.push("--trust-context");
                 args.push(node_name);
             }
+            if let Some(launch_config) = &launch_config {
+                args.push("--launch-config");
+                args.push(launch_config);
+            }
             args
         };
         insert_command(
@@ -252,6 +364,7 @@ impl NodeConfig {
             self.depends_on.map(|s| format!("node/{s}")),
             &args,
             blocking,
+            Some(Rollback::DeleteNode(node_name.to_string())),
         )?;
 
         // TODO: all commands should support both `/node/{name}` and `{name}` formats.
@@ -271,7 +384,8 @@ impl NodeConfig {
                         "--expression",
                         exp,
                     ];
-                    insert_command("policy", name, None, args, false)?;
+                    let rollback = delete_policy(&node_name_formatted, "tcp-inlet");
+                    insert_command("policy", name, None, args, false, Some(rollback))?;
                 }
                 let args = &[
                     "tcp-inlet",
@@ -285,7 +399,22 @@ impl NodeConfig {
                     "--alias",
                     name,
                 ];
-                insert_command("inlet", name, None, args, false)?;
+                let rollback = ockam_cmd(&[
+                    "tcp-inlet",
+                    "delete",
+                    name,
+                    "--at",
+                    &node_name_formatted,
+                    "--yes",
+                ]);
+                insert_command(
+                    "inlet",
+                    name,
+                    None,
+                    args,
+                    false,
+                    Some(Rollback::Command(rollback)),
+                )?;
             }
         }
 
@@ -303,7 +432,8 @@ impl NodeConfig {
                         "--expression",
                         exp,
                     ];
-                    insert_command("policy", name, None, args, false)?;
+                    let rollback = delete_policy(&node_name_formatted, "tcp-outlet");
+                    insert_command("policy", name, None, args, false, Some(rollback))?;
                 }
                 let args = &[
                     "tcp-outlet",
@@ -317,7 +447,22 @@ impl NodeConfig {
                     "--alias",
                     name,
                 ];
-                insert_command("outlet", name, None, args, false)?;
+                let rollback = ockam_cmd(&[
+                    "tcp-outlet",
+                    "delete",
+                    name,
+                    "--at",
+                    &node_name_formatted,
+                    "--yes",
+                ]);
+                insert_command(
+                    "outlet",
+                    name,
+                    None,
+                    args,
+                    false,
+                    Some(Rollback::Command(rollback)),
+                )?;
             }
         }
 
@@ -333,7 +478,22 @@ impl NodeConfig {
                     "--at",
                     &relay.at,
                 ];
-                insert_command("relay", name, None, args, false)?;
+                let rollback = ockam_cmd(&[
+                    "relay",
+                    "delete",
+                    name,
+                    "--at",
+                    &node_name_formatted,
+                    "--yes",
+                ]);
+                insert_command(
+                    "relay",
+                    name,
+                    None,
+                    args,
+                    false,
+                    Some(Rollback::Command(rollback)),
+                )?;
             }
         }
 
@@ -373,9 +533,30 @@ fn binary_path() -> &'static str {
     &BINARY_PATH
 }
 
+/// Return an ockam command, run with the binary of the current process
+fn ockam_cmd(args: &[&str]) -> Expression {
+    duct::cmd(binary_path(), args)
+}
+
+/// Delete the policy created for the portals of a node
+fn delete_policy(node_name: &str, resource: &str) -> Rollback {
+    Rollback::Command(ockam_cmd(&[
+        "policy",
+        "delete",
+        "--at",
+        node_name,
+        "--resource",
+        resource,
+        "--action",
+        "handle_message",
+        "--yes",
+    ]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::service::config::ScheduledTask;
 
     #[test]
     fn test_parse_config_with_depends_on() {
@@ -415,6 +596,50 @@ mod tests {
         );
         assert_eq!(sut.commands_sorted[5].id, "policy/telegraf");
         assert_eq!(sut.commands_sorted[6].id, "inlet/telegraf");
+        assert!(matches!(
+            &sut.commands_sorted[0].rollback,
+            Some(Rollback::DeleteNode(name)) if name == "influxdb"
+        ));
+        assert!(sut.commands_sorted.iter().all(|c| c.rollback.is_some()));
+    }
+
+    #[test]
+    fn parse_scheduled_tasks() {
+        let config = r#"
+            nodes:
+              n1:
+                scheduled-tasks:
+                  - task: upload_report
+                    url: https://example.com/reports
+                    every: 5m
+                  - task: refresh_credential
+                    every: 12h
+        "#;
+        let config: Config = serde_yaml::from_str(config).unwrap();
+        let tasks = config.nodes["n1"].scheduled_tasks.as_ref().unwrap();
+        assert_eq!(
+            tasks[0].task,
+            ScheduledTask::UploadReport {
+                url: "https://example.com/reports".to_string(),
+                token: None,
+            }
+        );
+        assert_eq!(tasks[1].task, ScheduledTask::RefreshCredential);
+        assert_eq!(tasks[1].every, "12h");
+
+        let mut sut = ConfigRunner::new();
+        sut.parse(
+            r#"
+            nodes:
+              n1:
+                scheduled-tasks:
+                  - task: probe_relays
+                    every: 1m
+            "#,
+            false,
+        )
+        .unwrap();
+        assert_eq!(sut.commands_sorted.len(), 1);
     }
 
     #[test]
@@ -0,0 +1,347 @@
+use crate::run::parser::{Config, ConfigRunner};
+use crate::util::duration::duration_parser;
+use ockam_api::cli_state::{CliState, StateDirTrait};
+use serde::Serialize;
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+
+const TOP_LEVEL_KEYS: &[&str] = &["nodes"];
+const NODE_KEYS: &[&str] = &[
+    "depends-on",
+    "enrollment-ticket",
+    "identity",
+    "trust-context",
+    "tcp-inlets",
+    "tcp-outlets",
+    "relays",
+    "scheduled-tasks",
+];
+const PORTAL_KEYS: &[&str] = &["from", "to", "access_control"];
+const RELAY_KEYS: &[&str] = &["at"];
+
+/// Result of the validation of a configuration file, without applying it
+#[derive(Debug, Default, Serialize)]
+pub struct ConfigValidation {
+    pub issues: Vec<ConfigIssue>,
+    /// Changes which would be made to the nodes if the configuration was applied
+    pub changes: Vec<NodeChange>,
+}
+
+impl ConfigValidation {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn issue(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.issues.push(ConfigIssue {
+            path: path.into(),
+            message: message.into(),
+        })
+    }
+}
+
+/// Problem found in a configuration file, at a path like `nodes.n1.tcp-inlets.i1`
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for ConfigIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct NodeChange {
+    pub node: String,
+    pub action: NodeAction,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeAction {
+    /// The node doesn't exist and would be created
+    Create,
+    /// The node exists but is stopped, it would be started
+    Start,
+    /// The node is running, its resources would be created again
+    Update,
+}
+
+impl Display for NodeAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeAction::Create => write!(f, "create"),
+            NodeAction::Start => write!(f, "start"),
+            NodeAction::Update => write!(f, "update"),
+        }
+    }
+}
+
+/// Check a configuration file, as used by `ockam run`, for schema errors, references to unknown
+/// nodes, identities or trust contexts, port conflicts and invalid policies
+pub fn validate_config(contents: &str, state: &CliState) -> ConfigValidation {
+    let mut validation = ConfigValidation::default();
+    let value: Value = match serde_yaml::from_str(contents) {
+        Ok(value) => value,
+        Err(e) => {
+            validation.issue("", format!("invalid YAML: {e}"));
+            return validation;
+        }
+    };
+    check_keys(&value, &mut validation);
+    let config: Config = match serde_yaml::from_value(value) {
+        Ok(config) => config,
+        Err(e) => {
+            validation.issue("", e.to_string());
+            return validation;
+        }
+    };
+
+    // Sort the nodes to report the issues in a stable order
+    let nodes: BTreeMap<_, _> = config.nodes.iter().collect();
+    let mut inlet_addresses: Vec<(SocketAddr, String)> = vec![];
+    for (name, node) in &nodes {
+        let path = format!("nodes.{name}");
+        if let Some(depends_on) = &node.depends_on {
+            if !config.nodes.contains_key(depends_on) {
+                validation.issue(
+                    format!("{path}.depends-on"),
+                    format!("the node {depends_on} is not defined"),
+                );
+            }
+        }
+        if let Some(identity) = &node.identity {
+            if !state.identities.exists(identity) {
+                validation.issue(
+                    format!("{path}.identity"),
+                    format!("the identity {identity} does not exist"),
+                );
+            }
+        }
+        if let Some(trust_context) = &node.trust_context {
+            // The enrollment of a node creates a trust context named after the node
+            let enrolled = node.enrollment_ticket.is_some() && trust_context == *name;
+            if !enrolled && !state.trust_contexts.exists(trust_context) {
+                validation.issue(
+                    format!("{path}.trust-context"),
+                    format!("the trust context {trust_context} does not exist"),
+                );
+            }
+        }
+        let inlets: BTreeMap<_, _> = node.tcp_inlets.iter().flatten().collect();
+        for (inlet_name, inlet) in inlets {
+            let inlet_path = format!("{path}.tcp-inlets.{inlet_name}");
+            match inlet.from.parse::<SocketAddr>() {
+                Ok(address) => {
+                    if let Some((_, other)) = inlet_addresses
+                        .iter()
+                        .find(|(other, _)| addresses_conflict(&address, other))
+                    {
+                        validation.issue(
+                            format!("{inlet_path}.from"),
+                            format!("the port {} is already used by {other}", address.port()),
+                        );
+                    }
+                    inlet_addresses.push((address, inlet_path.clone()));
+                }
+                Err(_) => validation.issue(
+                    format!("{inlet_path}.from"),
+                    format!("{} is not a valid socket address", inlet.from),
+                ),
+            }
+            check_policy(
+                &inlet_path,
+                inlet.access_control.as_deref(),
+                &mut validation,
+            );
+        }
+        let outlets: BTreeMap<_, _> = node.tcp_outlets.iter().flatten().collect();
+        for (outlet_name, outlet) in outlets {
+            let outlet_path = format!("{path}.tcp-outlets.{outlet_name}");
+            check_policy(
+                &outlet_path,
+                outlet.access_control.as_deref(),
+                &mut validation,
+            );
+        }
+        for (i, task) in node.scheduled_tasks.iter().flatten().enumerate() {
+            if !matches!(duration_parser(&task.every), Ok(every) if !every.is_zero()) {
+                validation.issue(
+                    format!("{path}.scheduled-tasks.{i}.every"),
+                    format!("{} is not a valid interval", task.every),
+                );
+            }
+        }
+
+        let action = match state.nodes.get(name) {
+            Ok(node_state) if node_state.is_running() => NodeAction::Update,
+            Ok(_) => NodeAction::Start,
+            Err(_) => NodeAction::Create,
+        };
+        validation.changes.push(NodeChange {
+            node: name.to_string(),
+            action,
+        });
+    }
+
+    // The dependencies and the names of the resources are checked when the commands
+    // are built. This can only be done once all the dependencies are known to exist
+    if validation.is_valid() {
+        if let Err(e) = ConfigRunner::new().parse(contents, false) {
+            validation.issue("nodes", e.to_string());
+        }
+    }
+    validation
+}
+
+/// Two inlets can't listen on the same port, unless they listen on distinct IP addresses
+fn addresses_conflict(address: &SocketAddr, other: &SocketAddr) -> bool {
+    address.port() == other.port()
+        && (address.ip() == other.ip()
+            || address.ip().is_unspecified()
+            || other.ip().is_unspecified())
+}
+
+fn check_policy(path: &str, access_control: Option<&str>, validation: &mut ConfigValidation) {
+    if let Some(expression) = access_control {
+        if let Err(e) = ockam_abac::parse(expression) {
+            validation.issue(
+                format!("{path}.access_control"),
+                format!("invalid policy: {e}"),
+            );
+        }
+    }
+}
+
+/// Report the keys which are not part of the configuration schema
+fn check_keys(value: &Value, validation: &mut ConfigValidation) {
+    unknown_keys("", value, TOP_LEVEL_KEYS, validation);
+    for (name, node) in entries(value.get("nodes")) {
+        let path = format!("nodes.{name}");
+        unknown_keys(&path, node, NODE_KEYS, validation);
+        for key in ["tcp-inlets", "tcp-outlets"] {
+            for (portal, portal_value) in entries(node.get(key)) {
+                let portal_path = format!("{path}.{key}.{portal}");
+                unknown_keys(&portal_path, portal_value, PORTAL_KEYS, validation);
+            }
+        }
+        for (relay, relay_value) in entries(node.get("relays")) {
+            let relay_path = format!("{path}.relays.{relay}");
+            unknown_keys(&relay_path, relay_value, RELAY_KEYS, validation);
+        }
+    }
+}
+
+fn unknown_keys(path: &str, value: &Value, known: &[&str], validation: &mut ConfigValidation) {
+    for (key, _) in entries(Some(value)) {
+        if !known.contains(&key.as_str()) {
+            let key_path = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            validation.issue(key_path, "unknown field");
+        }
+    }
+}
+
+/// Return the entries of a YAML mapping, with their keys as strings
+fn entries(value: Option<&Value>) -> Vec<(String, &Value)> {
+    match value.and_then(Value::as_mapping) {
+        Some(mapping) => mapping
+            .iter()
+            .map(|(k, v)| {
+                let key = match k {
+                    Value::String(s) => s.clone(),
+                    other => serde_yaml::to_string(other)
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                };
+                (key, v)
+            })
+            .collect(),
+        None => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_the_issues_of_a_config() {
+        let state = CliState::test().unwrap();
+        let config = r#"
+            nodes:
+              n1:
+                depends-on: n3
+                identity: unknown
+                tcp-inlets:
+                  i1:
+                    from: '127.0.0.1:8087'
+                    to: /project/default/service/outlet
+                    access_control: '(= subject.component'
+                  i2:
+                    from: '0.0.0.0:8087'
+                    to: /project/default/service/outlet
+              n2:
+                tcp-outlets:
+                  o1:
+                    from: /service/outlet
+                    to: '127.0.0.1:8086'
+                    acess_control: '(= subject.component "n1")'
+        "#;
+        let validation = validate_config(config, &state);
+        let issues: Vec<_> = validation.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(
+            issues,
+            vec![
+                "nodes.n2.tcp-outlets.o1.acess_control",
+                "nodes.n1.depends-on",
+                "nodes.n1.identity",
+                "nodes.n1.tcp-inlets.i1.access_control",
+                "nodes.n1.tcp-inlets.i2.from",
+            ]
+        );
+        assert_eq!(
+            validation.changes,
+            vec![
+                NodeChange {
+                    node: "n1".to_string(),
+                    action: NodeAction::Create
+                },
+                NodeChange {
+                    node: "n2".to_string(),
+                    action: NodeAction::Create
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn report_circular_dependencies() {
+        let state = CliState::test().unwrap();
+        let config = r#"
+            nodes:
+              n1:
+                depends-on: n2
+              n2:
+                depends-on: n1
+        "#;
+        let validation = validate_config(config, &state);
+        assert_eq!(validation.issues.len(), 1);
+        assert!(validation.issues[0]
+            .message
+            .contains("Circular dependency detected"));
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use clap::Args;
 use colorful::Colorful;
 use miette::{miette, IntoDiagnostic, WrapErr};
@@ -23,9 +25,10 @@ use crate::project::util::{
 };
 use crate::util::api::CloudOpts;
 use crate::util::clean_nodes_multiaddr;
+use crate::util::duration::duration_parser;
 use crate::{
     error::Error,
-    fmt_log, fmt_ok,
+    fmt_log, fmt_ok, fmt_warn,
     terminal::OckamColor,
     util::{exitcode, node_rpc},
     CommandGlobalOpts,
@@ -61,6 +64,24 @@ pub struct CreateCommand {
     /// Name of a stored Credential to use within this Secure Channel
     #[arg(short, long)]
     pub credential: Option<String>,
+
+    /// Combine the X25519 key agreement of the handshake with a Kyber768 key encapsulation,
+    /// to protect long-lived channels against harvest-now-decrypt-later attacks.
+    /// The listener must support this handshake extension
+    #[arg(long)]
+    pub pq_hybrid: bool,
+
+    /// Time to wait for the handshake to complete, instead of the default 2 minutes
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    pub timeout: Option<Duration>,
+
+    /// Number of times a handshake which timed out is started again
+    #[arg(long, value_name = "RETRIES")]
+    pub handshake_retries: Option<u32>,
+
+    /// Delay before retrying a handshake which timed out, doubled after each retry
+    #[arg(long, value_name = "DURATION", requires = "handshake_retries", value_parser = duration_parser)]
+    pub handshake_backoff: Option<Duration>,
 }
 
 impl CreateCommand {
@@ -117,16 +138,25 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> m
 
     let create_secure_channel = async {
         let identity_name = get_identity_name(&opts.state, &cmd.cloud_opts.identity);
-        let payload = CreateSecureChannelRequest::new(
+        let mut payload = CreateSecureChannelRequest::new(
             &to,
             authorized_identifiers,
             Some(identity_name),
             cmd.credential.clone(),
         );
+        if cmd.pq_hybrid {
+            payload.set_pq_hybrid();
+        }
+        if let Some(timeout) = cmd.timeout {
+            payload.set_timeout(timeout);
+        }
+        if let Some(retries) = cmd.handshake_retries {
+            payload.set_handshake_retries(retries, cmd.handshake_backoff);
+        }
         let request = Request::post("/node/secure_channel").body(payload);
         let response: CreateSecureChannelResponse = node.ask(&ctx, request).await?;
         *is_finished.lock().await = true;
-        Ok(response.addr)
+        Ok(response)
     };
 
     let output_messages = vec!["Creating Secure Channel...".to_string()];
@@ -135,9 +165,16 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> m
         .terminal
         .progress_output(&output_messages, &is_finished);
 
-    let (secure_channel, _) = try_join!(create_secure_channel, progress_output)?;
+    let (response, _) = try_join!(create_secure_channel, progress_output)?;
+    if let Some(pinned) = &response.pinned_identifier {
+        opts.terminal.write_line(&fmt_warn!(
+            "The identifier of {} changed since the first secure channel to it: {pinned} was pinned. \
+            Run `ockam trust pin remove` if this change is expected",
+            to.to_string().color(OckamColor::PrimaryResource.color())
+        ))?;
+    }
 
-    let route = &route![secure_channel.to_string()];
+    let route = &route![response.addr.to_string()];
     let multi_addr = route_to_multiaddr(route).ok_or_else(|| {
         Error::new(
             exitcode::PROTOCOL,
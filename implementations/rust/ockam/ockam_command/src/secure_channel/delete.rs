@@ -28,7 +28,7 @@ long_about = docs::about(LONG_ABOUT),
 after_long_help = docs::after_help(AFTER_LONG_HELP),
 )]
 pub struct DeleteCommand {
-    /// Node at which the secure channel was initiated
+    /// Node at which the secure channel was initiated or accepted
     #[arg(value_name = "NODE", long, display_order = 800)]
     at: Option<String>,
 
@@ -64,10 +64,15 @@ impl DeleteCommand {
                             println!("{multiaddr}")
                         }
 
-                        // if output format is json, write json to stdout.
-                        if options.global_args.output_format == OutputFormat::Json {
-                            let json = json!([{ "address": multiaddr.to_string() }]);
-                            println!("{json}");
+                        // if output format is json, yaml or table, write it to stdout.
+                        if options.global_args.output_format.is_structured() {
+                            let json = json!([{ "address": multiaddr.to_string() }]).to_string();
+                            let output = options
+                                .global_args
+                                .output_format
+                                .format_json(&json)
+                                .unwrap_or(json);
+                            println!("{output}");
                         }
 
                         // if stderr is interactive/tty and we haven't been asked to be quiet
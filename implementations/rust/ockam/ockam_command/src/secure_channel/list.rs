@@ -2,19 +2,20 @@ use std::fmt::Write;
 
 use clap::Args;
 use colorful::Colorful;
-use miette::miette;
+use miette::{miette, IntoDiagnostic};
 use tokio::sync::Mutex;
 use tokio::try_join;
 
+use ockam::identity::TimestampInSeconds;
 use ockam::Context;
 use ockam_api::cli_state::StateDirTrait;
-use ockam_api::nodes::models::secure_channel::ShowSecureChannelResponse;
+use ockam_api::nodes::models::secure_channel::SecureChannelState;
 use ockam_api::nodes::BackgroundNode;
 use ockam_api::route_to_multiaddr;
-use ockam_core::{route, Address};
+use ockam_core::route;
 
 use crate::node::get_node_name;
-use crate::output::Output;
+use crate::output::{human_readable_time, Output};
 use crate::terminal::OckamColor;
 use crate::util::parse_node_name;
 use crate::{
@@ -36,7 +37,7 @@ const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
     after_long_help = docs::after_help(AFTER_LONG_HELP),
 )]
 pub struct ListCommand {
-    /// Node at which the returned secure channels were initiated
+    /// Node at which the returned secure channels were initiated or accepted
     #[arg(value_name = "NODE_NAME", long, display_order = 800)]
     at: Option<String>,
 }
@@ -45,42 +46,6 @@ impl ListCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
         node_rpc(rpc, (opts, self));
     }
-
-    fn build_output(
-        &self,
-        node_name: &str,
-        channel_address: &str,
-        show_response: ShowSecureChannelResponse,
-    ) -> crate::Result<SecureChannelListOutput> {
-        let from = node_name.to_string();
-        let at = {
-            let channel_route = &route![channel_address];
-            let channel_multiaddr = route_to_multiaddr(channel_route).ok_or(miette!(
-                "Failed to convert route {channel_route} to multi-address"
-            ))?;
-            channel_multiaddr.to_string()
-        };
-
-        let to = {
-            let show_route = show_response.route.ok_or(miette!(
-                "Failed to retrieve route from show channel response"
-            ))?;
-            show_route
-                .split(" => ")
-                .map(|p| {
-                    let r = route![p];
-                    route_to_multiaddr(&r)
-                        .ok_or(miette!("Failed to convert route {r} to multi-address"))
-                })
-                .collect::<Result<Vec<_>, _>>()?
-                .iter()
-                .map(ToString::to_string)
-                .collect::<Vec<_>>()
-                .join("")
-        };
-
-        Ok(SecureChannelListOutput { from, to, at })
-    }
 }
 
 async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, ListCommand)) -> miette::Result<()> {
@@ -94,52 +59,35 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, ListCommand)) -> mie
     let is_finished: Mutex<bool> = Mutex::new(false);
     let node = BackgroundNode::create(&ctx, &opts.state, &node_name).await?;
 
-    let get_secure_channel_identifiers = async {
-        let secure_channel_identifiers: Vec<String> =
-            node.ask(&ctx, api::list_secure_channels()).await?;
+    let get_secure_channels = async {
+        let secure_channels: Vec<SecureChannelState> =
+            node.ask(&ctx, api::list_secure_channels_state()).await?;
         *is_finished.lock().await = true;
-        Ok(secure_channel_identifiers)
+        Ok(secure_channels)
     };
 
-    let output_messages = vec!["Retrieving secure channel identifiers...\n".to_string()];
+    let output_messages = vec!["Retrieving secure channels...\n".to_string()];
     let progress_output = opts
         .terminal
         .progress_output(&output_messages, &is_finished);
 
-    let (channel_identifiers, _) = try_join!(get_secure_channel_identifiers, progress_output)?;
-
-    let mut responses = Vec::with_capacity(channel_identifiers.len());
-    for channel_addr in &channel_identifiers {
-        let is_finished: Mutex<bool> = Mutex::new(false);
-        let get_secure_channel_output = async {
-            let request = api::show_secure_channel(&Address::from(channel_addr));
-            let show_response: ShowSecureChannelResponse = node.ask(&ctx, request).await?;
-            let secure_channel_output =
-                cmd.build_output(&node_name, channel_addr, show_response)?;
-            *is_finished.lock().await = true;
-            Ok(secure_channel_output)
-        };
-        let output_messages = vec![format!(
-            "Retrieving secure channel {}...\n",
-            channel_addr
-                .to_string()
-                .color(OckamColor::PrimaryResource.color())
-        )];
-        let progress_output = opts
-            .terminal
-            .progress_output(&output_messages, &is_finished);
-
-        let (secure_channel_output, _) = try_join!(get_secure_channel_output, progress_output)?;
+    let (secure_channels, _) = try_join!(get_secure_channels, progress_output)?;
 
-        responses.push(secure_channel_output);
-    }
+    let responses = secure_channels
+        .iter()
+        .map(|state| SecureChannelListOutput::new(&node_name, state))
+        .collect::<Vec<_>>();
 
     let list = opts.terminal.build_list(
         &responses,
         &format!("Secure Channels on {}", node_name),
         &format!("No secure channels found on {}", node_name),
     )?;
-    opts.terminal.stdout().plain(list).write_line()?;
+    opts.terminal
+        .stdout()
+        .plain(list)
+        .json(serde_json::to_string_pretty(&secure_channels).into_diagnostic()?)
+        .write_line()?;
 
     Ok(())
 }
@@ -148,6 +96,49 @@ pub struct SecureChannelListOutput {
     pub from: String,
     pub to: String,
     pub at: String,
+    pub peer: String,
+    pub created_at: String,
+    pub last_activity: String,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    pub rekeys: u64,
+}
+
+impl SecureChannelListOutput {
+    fn new(node_name: &str, state: &SecureChannelState) -> Self {
+        // Routes containing transport connections can't always be displayed as multi-addresses
+        let to_multiaddr = |address: &str| {
+            route_to_multiaddr(&route![address])
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| address.to_string())
+        };
+        let remote = state
+            .route
+            .split(" => ")
+            .map(to_multiaddr)
+            .collect::<Vec<_>>()
+            .join("");
+        let (from, to) = if state.is_initiator {
+            (node_name.to_string(), remote)
+        } else {
+            (remote, node_name.to_string())
+        };
+
+        Self {
+            from,
+            to,
+            at: to_multiaddr(&state.channel),
+            peer: state.their_identifier.clone(),
+            created_at: human_readable_time(TimestampInSeconds(state.created_at)),
+            last_activity: state
+                .last_activity
+                .map(|t| human_readable_time(TimestampInSeconds(t)))
+                .unwrap_or_else(|| "never".to_string()),
+            bytes_in: state.bytes_in,
+            bytes_out: state.bytes_out,
+            rekeys: state.rekeys,
+        }
+    }
 }
 
 impl Output for SecureChannelListOutput {
@@ -163,13 +154,30 @@ impl Output for SecureChannelListOutput {
                 .to_string()
                 .color(OckamColor::PrimaryResource.color())
         )?;
-        write!(
+        writeln!(
             output,
             "At {}",
             self.at
                 .to_string()
                 .color(OckamColor::PrimaryResource.color())
         )?;
+        writeln!(
+            output,
+            "Peer {}",
+            self.peer
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        )?;
+        writeln!(
+            output,
+            "Created at {}, last activity {}",
+            self.created_at, self.last_activity
+        )?;
+        write!(
+            output,
+            "{} bytes in, {} bytes out, {} rekeys",
+            self.bytes_in, self.bytes_out, self.rekeys
+        )?;
 
         Ok(output)
     }
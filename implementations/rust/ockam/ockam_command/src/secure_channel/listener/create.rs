@@ -2,14 +2,17 @@ use clap::Args;
 use colorful::Colorful;
 use miette::{miette, IntoDiagnostic, WrapErr};
 
-use ockam::identity::Identifier;
+use ockam::identity::{HandshakePattern, Identifier, RekeyPolicy};
 use ockam::Context;
 use ockam_api::nodes::models::secure_channel::CreateSecureChannelListenerRequest;
+use ockam_api::nodes::pairing::PairingApproval;
 use ockam_api::nodes::{BackgroundNode, NODEMANAGER_ADDR};
 use ockam_core::api::{Request, Status};
 use ockam_core::{Address, Route};
 
 use crate::node::{get_node_name, initialize_node_if_default, NodeOpts};
+use crate::util::api::QuotaOpts;
+use crate::util::parsers::{attribute_parser, rekey_policy_parser};
 use crate::util::{api, exitcode, node_rpc, parse_node_name};
 use crate::{docs, fmt_log, fmt_ok, terminal::OckamColor, CommandGlobalOpts};
 
@@ -41,6 +44,45 @@ pub struct CreateCommand {
     /// Name of the Identity that the secure-channel listener will use
     #[arg(value_name = "IDENTITY_NAME", long)]
     identity: Option<String>,
+
+    /// Program approving the first secure channel from an identifier which is not authorized.
+    /// It is called with the identifier as argument, and the decision is pinned by the node
+    #[arg(value_name = "PROGRAM", long)]
+    pairing_hook: Option<String>,
+
+    /// Hide the identity of the listener until the initiator of a secure channel has been
+    /// authenticated and trusted
+    #[arg(long)]
+    hide_identity: bool,
+
+    /// Handshake patterns accepted by the listener: xx, xx-hidden-responder or xx-psk.
+    /// By default xx and xx-hidden-responder are accepted
+    #[arg(long = "handshake-pattern", value_name = "PATTERN")]
+    handshake_patterns: Vec<HandshakePattern>,
+
+    /// Hex encoded 32 bytes key which must be known by the initiators of secure channels.
+    /// Only the xx-psk handshake pattern is then accepted
+    #[arg(long, value_name = "HEX_KEY")]
+    pre_shared_key: Option<String>,
+
+    /// Renew the keys of each channel after a number of bytes and/or a duration, for example
+    /// `1GB/1h`. The keys are also renewed every 32 messages
+    #[arg(long, value_name = "BYTES/DURATION", value_parser = rekey_policy_parser)]
+    rekey_after: Option<RekeyPolicy>,
+
+    /// Attribute which the credential of the initiators must give them, with the format
+    /// "NAME=VALUE". The other initiators are rejected during the handshake. Can be repeated
+    #[arg(long = "require", value_name = "ATTRIBUTE", value_parser = attribute_parser)]
+    required_attributes: Vec<(String, String)>,
+
+    /// Accept the initiators which have no credential yet, or not the required attributes,
+    /// with the attribute "trust_level=low". They can then present a credential over their
+    /// secure channel, with `ockam credential present`, to be given its attributes instead
+    #[arg(long)]
+    progressive_trust: bool,
+
+    #[command(flatten)]
+    quota_opts: QuotaOpts,
 }
 
 impl CreateCommand {
@@ -61,14 +103,35 @@ async fn run_impl(
     let at = get_node_name(&opts.state, &cmd.node_opts.at_node);
     let node_name = parse_node_name(&at)?;
     let node = BackgroundNode::create(ctx, &opts.state, &node_name).await?;
-    let req = Request::post("/node/secure_channel_listener").body(
-        CreateSecureChannelListenerRequest::new(
-            &cmd.address,
-            cmd.authorized,
-            cmd.vault,
-            cmd.identity,
-        ),
+    let mut payload = CreateSecureChannelListenerRequest::new(
+        &cmd.address,
+        cmd.authorized,
+        cmd.vault,
+        cmd.identity,
     );
+    payload.set_quota(cmd.quota_opts.to_quota());
+    if let Some(program) = cmd.pairing_hook {
+        payload.set_pairing(PairingApproval::Hook(program));
+    }
+    if cmd.hide_identity {
+        payload.set_hide_identity();
+    }
+    if !cmd.handshake_patterns.is_empty() {
+        payload.set_handshake_patterns(cmd.handshake_patterns);
+    }
+    if let Some(pre_shared_key) = cmd.pre_shared_key {
+        payload.set_pre_shared_key(pre_shared_key);
+    }
+    if let Some(rekey_policy) = cmd.rekey_after {
+        payload.set_rekey_policy(rekey_policy);
+    }
+    if !cmd.required_attributes.is_empty() {
+        payload.set_required_attributes(cmd.required_attributes.into_iter().collect());
+    }
+    if cmd.progressive_trust {
+        payload.set_progressive_trust();
+    }
+    let req = Request::post("/node/secure_channel_listener").body(payload);
     let result = node.tell(ctx, req).await;
     match result {
         Ok(_) => {
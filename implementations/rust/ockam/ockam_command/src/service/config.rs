@@ -58,9 +58,46 @@ pub struct ServiceConfigs {
     pub(crate) okta_identity_provider: Option<OktaIdentityProviderConfig>,
 }
 
+/// Task run periodically by a node
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "task", rename_all = "snake_case")]
+pub enum ScheduledTask {
+    /// Retrieve a fresh credential from the project authority
+    RefreshCredential,
+    /// Check that the relays created on the node are connected
+    ProbeRelays,
+    /// Post a JSON report describing the node to an HTTP endpoint
+    UploadReport {
+        url: String,
+        /// Bearer token sent with the request, usually a reference to a node secret: `secret:NAME`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+    },
+    /// Download a launch configuration and start the services it describes, when it changes
+    ReapplyConfig {
+        url: String,
+        /// Bearer token sent with the request, usually a reference to a node secret: `secret:NAME`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTaskConfig {
+    #[serde(flatten)]
+    pub(crate) task: ScheduledTask,
+
+    /// Interval between two runs of the task, for example `30s`, `5m` or `1h`
+    pub(crate) every: String,
+
+    #[serde(default)]
+    pub(crate) disabled: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub(crate) startup_services: Option<ServiceConfigs>,
+    pub(crate) scheduled_tasks: Option<Vec<ScheduledTaskConfig>>,
 }
 
 impl Config {
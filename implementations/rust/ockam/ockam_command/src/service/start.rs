@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use clap::{Args, Subcommand};
 use colorful::Colorful;
 use miette::miette;
@@ -5,11 +7,13 @@ use minicbor::Encode;
 
 use ockam::Context;
 use ockam_api::nodes::BackgroundNode;
+use ockam_api::provisioner::DEFAULT_PROVISIONING_ATTRIBUTE;
 use ockam_api::DefaultAddress;
 use ockam_core::api::Request;
 
 use crate::node::{get_node_name, initialize_node_if_default, NodeOpts};
 use crate::terminal::OckamColor;
+use crate::util::duration::duration_parser;
 use crate::util::{api, node_rpc};
 use crate::{fmt_ok, CommandGlobalOpts};
 use crate::{fmt_warn, Result};
@@ -33,6 +37,40 @@ pub enum StartSubCommand {
         #[arg(long, default_value_t = authenticated_default_addr())]
         addr: String,
     },
+    /// Let the UDP hole punchers of other nodes find each other, via the UDP listeners of this node
+    Rendezvous {
+        #[arg(long, default_value_t = rendezvous_default_addr())]
+        addr: String,
+    },
+    /// Run allowlisted commands on behalf of the identities authorized by the service policy
+    Exec {
+        #[arg(long, default_value_t = exec_default_addr())]
+        addr: String,
+
+        /// Command which can be run, with the format "PROGRAM [ARGS...]". A command is allowed
+        /// if it starts with one of these commands. Can be repeated
+        #[arg(long = "allow", value_name = "COMMAND", required = true)]
+        allowed_commands: Vec<String>,
+
+        /// Maximum time a command can run before being killed
+        #[arg(long, value_name = "TIMEOUT", value_parser = duration_parser)]
+        timeout: Option<Duration>,
+    },
+    /// Create a TCP outlet for each peer connecting with a TCP inlet, to the target selected by
+    /// an attribute of its credential. The outlet is deleted when the peer disconnects
+    Provisioner {
+        #[arg(long, default_value_t = provisioner_default_addr())]
+        addr: String,
+
+        /// Attribute of the peers used to select their target
+        #[arg(long, value_name = "ATTRIBUTE", default_value = DEFAULT_PROVISIONING_ATTRIBUTE)]
+        attribute: String,
+
+        /// Target of the outlets created for the peers with an attribute value, with the format
+        /// "VALUE=IP:PORT", for example "postgres=127.0.0.1:5432". Can be repeated
+        #[arg(long = "target", value_name = "TARGET", required = true)]
+        targets: Vec<String>,
+    },
     Credentials {
         #[arg(long)]
         identity: String,
@@ -56,6 +94,18 @@ fn hop_default_addr() -> String {
     DefaultAddress::HOP_SERVICE.to_string()
 }
 
+fn rendezvous_default_addr() -> String {
+    DefaultAddress::RENDEZVOUS_SERVICE.to_string()
+}
+
+fn exec_default_addr() -> String {
+    DefaultAddress::EXEC_SERVICE.to_string()
+}
+
+fn provisioner_default_addr() -> String {
+    DefaultAddress::PROVISIONER_SERVICE.to_string()
+}
+
 fn authenticated_default_addr() -> String {
     DefaultAddress::AUTHENTICATED_SERVICE.to_string()
 }
@@ -94,6 +144,29 @@ async fn run_impl(ctx: &Context, opts: CommandGlobalOpts, cmd: StartCommand) ->
             start_service_impl(ctx, &node, "Authenticated", req).await?;
             addr
         }
+        StartSubCommand::Rendezvous { addr } => {
+            let req = api::start_rendezvous_service(&addr);
+            start_service_impl(ctx, &node, "Rendezvous", req).await?;
+            addr
+        }
+        StartSubCommand::Exec {
+            addr,
+            allowed_commands,
+            timeout,
+        } => {
+            let req = api::start_exec_service(&addr, allowed_commands, timeout);
+            start_service_impl(ctx, &node, "Exec", req).await?;
+            addr
+        }
+        StartSubCommand::Provisioner {
+            addr,
+            attribute,
+            targets,
+        } => {
+            let req = api::start_provisioner_service(&addr, &attribute, targets);
+            start_service_impl(ctx, &node, "Provisioner", req).await?;
+            addr
+        }
         StartSubCommand::Credentials {
             identity,
             addr,
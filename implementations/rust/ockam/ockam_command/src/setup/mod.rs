@@ -0,0 +1,278 @@
+use std::time::Duration;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+use tokio::time::sleep;
+
+use ockam::Context;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::nodes::models::portal::OutletList;
+use ockam_api::nodes::models::relay::RelayInfo;
+use ockam_api::nodes::service::portals::Inlets;
+use ockam_api::nodes::BackgroundNode;
+use ockam_core::api::Request;
+
+use crate::init::parse_args;
+use crate::terminal::OckamColor;
+use crate::util::duration::duration_parser;
+use crate::util::parsers::socket_addr_parser;
+use crate::util::{embedded_node, local_cmd};
+use crate::{docs, fmt_info, fmt_log, fmt_ok, fmt_warn, node, tcp, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+const NEW_NODE: &str = "Create a new node";
+const RELAY_BY_NAME: &str = "Another relay of the project, by name";
+const ROUTE: &str = "Enter a route";
+
+/// Time between two checks of the status of the created inlet
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Create a first portal, step by step, by answering questions
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct SetupCommand {
+    /// How long to wait for the portal to be connected, before reporting it as unreachable
+    #[arg(long, value_name = "DURATION", default_value = "10s", value_parser = duration_parser)]
+    timeout: Duration,
+}
+
+impl SetupCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self))
+    }
+}
+
+/// Summary of the created portal
+#[derive(Serialize)]
+struct SetupSummary {
+    node: String,
+    alias: String,
+    address: String,
+    route: String,
+    connected: bool,
+}
+
+/// Service which can be reached with an inlet
+struct Destination {
+    label: String,
+    route: String,
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: SetupCommand) -> miette::Result<()> {
+    opts.terminal
+        .fail_if_non_interactive("Which node should host the inlet?")?;
+    if !opts.terminal.can_ask_for_user_input() {
+        return Err(miette!(
+            "The setup wizard needs an interactive terminal. Use `ockam tcp-inlet create` instead"
+        ));
+    }
+
+    let node = setup_node(&opts)?;
+    let route = choose_destination(&opts)?;
+    let address = opts.terminal.input(
+        "Address where the service will be reached",
+        tcp::inlet::create::default_from_addr()?.to_string(),
+    )?;
+    let address = socket_addr_parser(&address)?;
+    let alias = opts
+        .terminal
+        .input("Name of the inlet", format!("inlet-{}", address.port()))?;
+
+    parse_args::<tcp::inlet::create::CreateCommand>(&[
+        "--at",
+        &node,
+        "--from",
+        &address.to_string(),
+        "--to",
+        &route,
+        "--alias",
+        &alias,
+    ])?
+    .run(opts.clone());
+
+    opts.terminal.write_line(&fmt_log!(
+        "Testing the connectivity of the inlet {}...\n",
+        alias.as_str().color(OckamColor::PrimaryResource.color())
+    ))?;
+    let connected = embedded_node(
+        is_connected,
+        (opts.clone(), node.clone(), alias.clone(), cmd.timeout),
+    )?;
+
+    let mut plain = if connected {
+        fmt_ok!(
+            "The service can be reached at {}\n",
+            address
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        )
+    } else {
+        fmt_warn!(
+            "The inlet {} is created but the service can't be reached yet\n",
+            alias.as_str().color(OckamColor::PrimaryResource.color())
+        )
+    };
+    plain.push_str(&fmt_info!(
+        "Run {} to see the status of your portals",
+        "ockam status --all".color(OckamColor::PrimaryResource.color())
+    ));
+    let summary = SetupSummary {
+        node,
+        alias,
+        address: address.to_string(),
+        route,
+        connected,
+    };
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .machine(&summary.address)
+        .json(serde_json::to_string_pretty(&summary).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
+
+/// Select an existing node or create a new one. The selected node is started if it is not running
+fn setup_node(opts: &CommandGlobalOpts) -> miette::Result<String> {
+    let mut items = opts.state.nodes.list_items_names()?;
+    items.push(NEW_NODE.to_string());
+    let selected = opts
+        .terminal
+        .select("Which node should host the inlet?".to_string(), items)?
+        .ok_or_else(|| miette!("No node was selected"))?;
+
+    let (name, running) = if selected == NEW_NODE {
+        (opts.terminal.input("Name of the node", "default")?, false)
+    } else {
+        let running = opts.state.nodes.get(&selected)?.is_running();
+        (selected, running)
+    };
+    if !running {
+        // Creating an existing node starts it again
+        let mut create_command = node::CreateCommand::default();
+        create_command.node_name = name.clone();
+        create_command.run(opts.clone());
+    }
+    Ok(name)
+}
+
+/// Select the relay or the outlet that the inlet connects to, and return its route
+fn choose_destination(opts: &CommandGlobalOpts) -> miette::Result<String> {
+    let project = opts
+        .state
+        .projects
+        .default()
+        .ok()
+        .map(|p| p.name().to_string());
+    let destinations = embedded_node(list_destinations, (opts.clone(), project.clone()))?;
+
+    let mut items: Vec<String> = destinations.iter().map(|d| d.label.clone()).collect();
+    if project.is_some() {
+        items.push(RELAY_BY_NAME.to_string());
+    }
+    items.push(ROUTE.to_string());
+    let selected = opts
+        .terminal
+        .select("Which service should the inlet reach?".to_string(), items)?
+        .ok_or_else(|| miette!("No service was selected"))?;
+
+    match (selected.as_str(), &project) {
+        (RELAY_BY_NAME, Some(project)) => {
+            let relay = opts.terminal.input("Name of the relay", "default")?;
+            Ok(relay_route(project, &format!("forward_to_{relay}")))
+        }
+        (ROUTE, _) => Ok(opts.terminal.input(
+            "Route to the outlet",
+            tcp::inlet::create::default_to_addr().to_string(),
+        )?),
+        (label, _) => destinations
+            .into_iter()
+            .find(|d| d.label == label)
+            .map(|d| d.route)
+            .ok_or_else(|| miette!("Unknown service {label}")),
+    }
+}
+
+/// List the relays registered in the project and the outlets of the running local nodes
+async fn list_destinations(
+    ctx: Context,
+    (opts, project): (CommandGlobalOpts, Option<String>),
+) -> miette::Result<Vec<Destination>> {
+    let mut destinations = vec![];
+    for node_state in opts.state.nodes.list()? {
+        if !node_state.is_running() {
+            continue;
+        }
+        let node_name = node_state.name();
+        let node = BackgroundNode::create(&ctx, &opts.state, node_name).await?;
+        if let Some(project) = &project {
+            // The nodes which don't answer are not listed
+            let relays: Vec<RelayInfo> = node
+                .ask(&ctx, Request::get("/node/forwarder"))
+                .await
+                .unwrap_or_default();
+            for relay in relays {
+                destinations.push(Destination {
+                    label: format!("Relay {} in {project}", relay.remote_address()),
+                    route: relay_route(project, relay.remote_address()),
+                });
+            }
+        }
+        let outlets: Option<OutletList> = node.ask(&ctx, Request::get("/node/outlet")).await.ok();
+        for outlet in outlets.map(|o| o.list).unwrap_or_default() {
+            destinations.push(Destination {
+                label: format!(
+                    "Outlet {} to {} on the node {node_name}",
+                    outlet.alias, outlet.socket_addr
+                ),
+                route: format!("/node/{node_name}/service/{}", outlet.worker_addr.address()),
+            });
+        }
+    }
+    Ok(destinations)
+}
+
+/// Route to the outlet of a node which registered a relay in a project
+fn relay_route(project: &str, remote_address: &str) -> String {
+    format!("/project/{project}/service/{remote_address}/secure/api/service/outlet")
+}
+
+/// Return true if the inlet is connected to its outlet before the timeout
+async fn is_connected(
+    ctx: Context,
+    (opts, node_name, alias, timeout): (CommandGlobalOpts, String, String, Duration),
+) -> miette::Result<bool> {
+    let node = BackgroundNode::create(&ctx, &opts.state, &node_name).await?;
+    let attempts = (timeout.as_millis() / POLL_INTERVAL.as_millis()).max(1);
+    for _ in 0..attempts {
+        if let Ok(inlet) = node.show_inlet(&ctx, &alias).await?.success() {
+            if inlet.status == "up" {
+                return Ok(true);
+            }
+        }
+        sleep(POLL_INTERVAL).await;
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relay_routes_reach_the_outlet() {
+        assert_eq!(
+            relay_route("default", "forward_to_n1"),
+            "/project/default/service/forward_to_n1/secure/api/service/outlet"
+        );
+    }
+}
@@ -25,8 +25,9 @@ pub struct SecureRelayInlet {
     pub service_name: String,
 
     /// Address on which to accept tcp connections.
-    #[arg(long, display_order = 900, id = "SOCKET_ADDRESS", default_value_t = default_from_addr(), value_parser = socket_addr_parser)]
-    from: SocketAddr,
+    /// By default, an available port of localhost, in the configured inlet port range if any
+    #[arg(long, display_order = 900, id = "SOCKET_ADDRESS", value_parser = socket_addr_parser)]
+    from: Option<SocketAddr>,
 
     /// Just print the recipe and exit
     #[arg(long)]
@@ -68,6 +69,10 @@ async fn rpc(
 impl SecureRelayInlet {
     pub async fn create_config_and_start(self, opts: CommandGlobalOpts) -> miette::Result<()> {
         let stdout = opts.terminal.clone().stdout();
+        let from = match self.from {
+            Some(from) => from,
+            None => default_from_addr()?,
+        };
 
         let enrollment_ticket: String = if let Some(t) = self.enroll.enroll_ticket.as_ref() {
             format! {
@@ -87,7 +92,7 @@ impl SecureRelayInlet {
                     from: {from}
                     to: /project/default/service/forward_to_{service_name}/secure/api/service/outlet_{service_name}
             "#,
-            from = self.from.to_string(),
+            from = from.to_string(),
             service_name = self.service_name,
         };
 
@@ -107,6 +112,6 @@ impl SecureRelayInlet {
             ))
             .write_line()?;
 
-        ConfigRunner::go(opts, &recipe, true).await
+        ConfigRunner::go(opts, &recipe, true, false).await
     }
 }
@@ -110,6 +110,6 @@ impl SecureRelayOutlet {
             ))
             .write_line()?;
 
-        ConfigRunner::go(opts, &recipe, true).await
+        ConfigRunner::go(opts, &recipe, true, false).await
     }
 }
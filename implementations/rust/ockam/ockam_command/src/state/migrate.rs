@@ -0,0 +1,103 @@
+use clap::Args;
+use colorful::Colorful;
+use console::Term;
+
+use ockam_api::cli_state::schema::{latest_schema_version, pending_migrations, schema_version};
+use ockam_api::cli_state::CliState;
+
+use crate::terminal::OckamColor;
+use crate::util::{exitcode, local_cmd};
+use crate::{
+    docs, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts, GlobalArgs, Terminal, TerminalStream,
+};
+
+const LONG_ABOUT: &str = include_str!("./static/migrate/long_about.txt");
+
+/// Upgrade the state directory to the layout of this version of ockam
+#[derive(Clone, Debug, Args)]
+#[command(long_about = docs::about(LONG_ABOUT))]
+pub struct MigrateCommand {
+    /// Only list the pending migrations, without applying them.
+    /// The command fails when a migration is pending
+    #[arg(long)]
+    pub check: bool,
+}
+
+impl MigrateCommand {
+    /// The state directory is migrated when it is loaded, before the command is run
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(report(
+            &opts.terminal,
+            &opts.state.dir.display().to_string(),
+        ));
+    }
+
+    /// Check the pending migrations without loading the state, since loading the state
+    /// directory migrates it
+    pub fn check(&self, global_args: &GlobalArgs) {
+        local_cmd(check_impl(&CommandGlobalOpts::terminal(global_args)));
+    }
+}
+
+fn check_impl(terminal: &Terminal<TerminalStream<Term>>) -> miette::Result<()> {
+    let dir = CliState::default_dir()?;
+    let pending = if dir.exists() {
+        pending_migrations(&dir)?
+    } else {
+        vec![]
+    };
+    if pending.is_empty() {
+        return report(terminal, &dir.display().to_string());
+    }
+
+    let version = schema_version(&dir)?;
+    let mut plain = fmt_warn!(
+        "The state directory {} has the version {version}, the following migrations are pending:\n",
+        dir.display()
+            .to_string()
+            .color(OckamColor::PrimaryResource.color())
+    );
+    for migration in &pending {
+        plain.push_str(&fmt_log!(
+            "Version {}: {}\n",
+            migration.version,
+            migration.description
+        ));
+    }
+    plain.push_str(&fmt_log!(
+        "They are applied by the next ockam command, after a backup of the state directory"
+    ));
+    terminal
+        .stdout()
+        .plain(plain)
+        .json(serde_json::json!({
+            "dir": dir,
+            "version": version,
+            "latest_version": latest_schema_version(),
+            "pending": pending
+                .iter()
+                .map(|m| serde_json::json!({ "version": m.version, "description": m.description }))
+                .collect::<Vec<_>>(),
+        }))
+        .write_line()?;
+    std::process::exit(exitcode::CONFIG);
+}
+
+/// Report that the state directory has the latest version
+fn report(terminal: &Terminal<TerminalStream<Term>>, dir: &str) -> miette::Result<()> {
+    let version = latest_schema_version();
+    terminal
+        .stdout()
+        .plain(fmt_ok!(
+            "The state directory {} has the latest version {version}",
+            dir.color(OckamColor::PrimaryResource.color())
+        ))
+        .json(serde_json::json!({
+            "dir": dir,
+            "version": version,
+            "latest_version": version,
+            "pending": [],
+        }))
+        .write_line()?;
+    Ok(())
+}
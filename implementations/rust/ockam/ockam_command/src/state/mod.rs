@@ -0,0 +1,45 @@
+mod migrate;
+mod move_dir;
+
+use migrate::MigrateCommand;
+use move_dir::MoveCommand;
+
+use crate::{docs, CommandGlobalOpts};
+use clap::{Args, Subcommand};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+
+/// Manage the local state directory
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+subcommand_required = true,
+long_about = docs::about(LONG_ABOUT),
+)]
+pub struct StateCommand {
+    #[command(subcommand)]
+    subcommand: StateSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum StateSubcommand {
+    Move(MoveCommand),
+    Migrate(MigrateCommand),
+}
+
+impl StateCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            StateSubcommand::Move(c) => c.run(options),
+            StateSubcommand::Migrate(c) => c.run(options),
+        }
+    }
+
+    /// Return the migrate command when it only checks the pending migrations
+    pub fn migration_check(&self) -> Option<&MigrateCommand> {
+        match &self.subcommand {
+            StateSubcommand::Migrate(c) if c.check => Some(c),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use colorful::Colorful;
+use ockam_api::cli_state::CliState;
+
+use crate::terminal::OckamColor;
+use crate::util::local_cmd;
+use crate::{docs, fmt_log, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/move/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/move/after_long_help.txt");
+
+/// Move the state directory to a new location
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct MoveCommand {
+    /// New location of the state directory. It must not exist, or be empty
+    #[arg(value_name = "NEW_PATH")]
+    new_path: PathBuf,
+}
+
+impl MoveCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: MoveCommand) -> miette::Result<()> {
+    let previous_dir = opts.state.dir.clone();
+    let state = opts.state.move_to(&cmd.new_path)?;
+    let new_dir = state.dir.display().to_string();
+
+    let mut plain = fmt_ok!(
+        "The state directory was moved from {} to {}\n",
+        previous_dir
+            .display()
+            .to_string()
+            .color(OckamColor::PrimaryResource.color()),
+        new_dir.clone().color(OckamColor::PrimaryResource.color())
+    );
+    if CliState::default_dir().ok().as_ref() != Some(&state.dir) {
+        plain.push_str(&fmt_log!(
+            "Set the OCKAM_HOME environment variable to use it: {}",
+            format!("export OCKAM_HOME={new_dir}").color(OckamColor::PrimaryResource.color())
+        ));
+    }
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .machine(&new_dir)
+        .json(serde_json::json!({ "previous_dir": previous_dir, "dir": state.dir }))
+        .write_line()?;
+    Ok(())
+}
@@ -0,0 +1,331 @@
+use std::io::Write;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use clap::crate_version;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::cli_state::NodesState;
+use ockam_api::nodes::models::relay::RelayInfo;
+use ockam_api::nodes::BackgroundNode;
+use ockam_core::api::Request;
+
+use super::{get_inlets_health, get_node_status, get_orchestrator_version, InletHealth};
+use crate::credential::{identities, validate_encoded_cred};
+use crate::vault::default_vault_name;
+use crate::{CommandGlobalOpts, Result};
+
+/// Time after which a node which doesn't answer is reported as unresponsive
+const NODE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Run all the diagnostics and print a summary, or a JSON report which can be
+/// attached to a support ticket
+pub(super) async fn diagnose(
+    ctx: &Context,
+    opts: CommandGlobalOpts,
+    timeout: Duration,
+) -> miette::Result<()> {
+    let orchestrator = diagnose_orchestrator(ctx, &opts, timeout).await;
+    let identities = diagnose_identities(&opts)?;
+    let credentials = diagnose_credentials(&opts).await?;
+    let nodes = diagnose_nodes(ctx, &opts).await?;
+    let healthy = orchestrator.reachable
+        && credentials.iter().all(|c| c.valid)
+        && nodes.iter().all(|n| n.is_healthy());
+    let report = DiagnosticReport {
+        ockam_version: crate_version!().to_string(),
+        generated_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        healthy,
+        orchestrator,
+        identities,
+        credentials,
+        nodes,
+    };
+
+    let json = serde_json::to_string_pretty(&report).into_diagnostic()?;
+    opts.terminal
+        .stdout()
+        .plain(build_plain_output(&report)?)
+        .machine(if healthy { "healthy" } else { "unhealthy" })
+        .json(json)
+        .write_line()?;
+    Ok(())
+}
+
+async fn diagnose_orchestrator(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    timeout: Duration,
+) -> OrchestratorDiagnostic {
+    let start = Instant::now();
+    match get_orchestrator_version(ctx, opts, timeout).await {
+        Ok(version) => OrchestratorDiagnostic {
+            reachable: true,
+            latency_ms: Some(start.elapsed().as_millis() as u64),
+            controller_version: Some(version.controller_version),
+            project_version: Some(version.project_version),
+            error: None,
+        },
+        Err(e) => OrchestratorDiagnostic {
+            reachable: false,
+            latency_ms: None,
+            controller_version: None,
+            project_version: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+fn diagnose_identities(opts: &CommandGlobalOpts) -> Result<Vec<IdentityDiagnostic>> {
+    let default = opts.state.identities.default().ok();
+    let mut identities = vec![];
+    for identity in opts.state.identities.list()? {
+        identities.push(IdentityDiagnostic {
+            name: identity.name().to_string(),
+            identifier: identity.identifier().to_string(),
+            default: default.as_ref().map(|d| d.name()) == Some(identity.name()),
+            enrolled: identity.is_enrolled(),
+        });
+    }
+    Ok(identities)
+}
+
+async fn diagnose_credentials(opts: &CommandGlobalOpts) -> Result<Vec<CredentialDiagnostic>> {
+    let mut credentials = vec![];
+    let credential_states = opts.state.credentials.list()?;
+    if credential_states.is_empty() {
+        return Ok(credentials);
+    }
+    let identities = identities(&default_vault_name(&opts.state), opts).await?;
+    for state in credential_states {
+        let config = state.config();
+        let validation = validate_encoded_cred(
+            &config.encoded_credential,
+            identities.clone(),
+            &config.issuer_identifier,
+        )
+        .await;
+        credentials.push(CredentialDiagnostic {
+            name: state.name().to_string(),
+            valid: validation.is_ok(),
+            error: validation.err().map(|e| e.to_string()),
+        });
+    }
+    Ok(credentials)
+}
+
+async fn diagnose_nodes(ctx: &Context, opts: &CommandGlobalOpts) -> Result<Vec<NodeDiagnostic>> {
+    let summaries = opts.state.nodes.summaries()?;
+    let running_pids = NodesState::running_pids(summaries.iter().filter_map(|s| s.pid));
+    let mut nodes = vec![];
+    for summary in summaries {
+        let running = summary
+            .pid
+            .map(|pid| running_pids.contains(&pid))
+            .unwrap_or(false);
+        let mut node = NodeDiagnostic {
+            name: summary.name,
+            running,
+            responsive: false,
+            latency_ms: None,
+            relays: vec![],
+            inlets: vec![],
+            error: None,
+        };
+        if running {
+            let start = Instant::now();
+            if get_node_status(ctx, opts, &node.name).await == "Running" {
+                node.responsive = true;
+                node.latency_ms = Some(start.elapsed().as_millis() as u64);
+                match get_relays(ctx, opts, &node.name).await {
+                    Ok(relays) => node.relays = relays,
+                    Err(e) => node.error = Some(e.to_string()),
+                }
+                match get_inlets_health(ctx, opts, &node.name).await {
+                    Ok(inlets) => node.inlets = inlets,
+                    Err(e) => node.error = Some(e.to_string()),
+                }
+            } else {
+                node.error = Some("The node API doesn't respond".to_string());
+            }
+        }
+        nodes.push(node);
+    }
+    Ok(nodes)
+}
+
+async fn get_relays(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+) -> Result<Vec<RelayDiagnostic>> {
+    let mut node = BackgroundNode::create(ctx, &opts.state, node_name).await?;
+    node.set_timeout(NODE_TIMEOUT);
+    let relays: Vec<RelayInfo> = node.ask(ctx, Request::get("/node/forwarder")).await?;
+    Ok(relays
+        .into_iter()
+        .map(|relay| RelayDiagnostic {
+            remote_address: relay.remote_address().to_string(),
+            // The relays without an alias are not monitored, so they are considered as up
+            up: relay.is_up().unwrap_or(true),
+        })
+        .collect())
+}
+
+fn build_plain_output(report: &DiagnosticReport) -> Result<String> {
+    let mut plain = Vec::new();
+    let check = |ok: bool| if ok { "✔" } else { "✘" };
+    let verdict = if report.healthy {
+        "healthy"
+    } else {
+        "unhealthy"
+    };
+    writeln!(&mut plain, "Status: {verdict}")?;
+
+    let orchestrator = &report.orchestrator;
+    match (&orchestrator.latency_ms, &orchestrator.error) {
+        (Some(latency), _) => writeln!(
+            &mut plain,
+            "{} Orchestrator: reachable in {latency}ms",
+            check(true)
+        )?,
+        (None, error) => writeln!(
+            &mut plain,
+            "{} Orchestrator: unreachable ({})",
+            check(false),
+            error.as_deref().unwrap_or("unknown error")
+        )?,
+    }
+
+    for identity in &report.identities {
+        let enrolled = if identity.enrolled {
+            "enrolled"
+        } else {
+            "not enrolled"
+        };
+        writeln!(
+            &mut plain,
+            "  Identity {} ({}): {enrolled}",
+            identity.name, identity.identifier
+        )?;
+    }
+    for credential in &report.credentials {
+        match &credential.error {
+            None => writeln!(
+                &mut plain,
+                "{} Credential {}: valid",
+                check(true),
+                credential.name
+            )?,
+            Some(error) => writeln!(
+                &mut plain,
+                "{} Credential {}: {error}",
+                check(false),
+                credential.name
+            )?,
+        }
+    }
+
+    for node in &report.nodes {
+        let status = match (node.running, node.responsive, node.latency_ms) {
+            (false, _, _) => "not running".to_string(),
+            (true, true, Some(latency)) => format!("responds in {latency}ms"),
+            _ => "does not respond".to_string(),
+        };
+        writeln!(
+            &mut plain,
+            "{} Node {}: {status}",
+            check(node.is_healthy()),
+            node.name
+        )?;
+        for relay in &node.relays {
+            let status = if relay.up { "registered" } else { "lost" };
+            writeln!(
+                &mut plain,
+                "  {} Relay {}: {status}",
+                check(relay.up),
+                relay.remote_address
+            )?;
+        }
+        for inlet in &node.inlets {
+            writeln!(
+                &mut plain,
+                "  {} Inlet {}: {}",
+                check(inlet.connected),
+                inlet.alias,
+                inlet.status
+            )?;
+        }
+        if let Some(error) = &node.error {
+            writeln!(&mut plain, "  {error}")?;
+        }
+    }
+    Ok(String::from_utf8(plain).expect("Invalid UTF-8 output"))
+}
+
+#[derive(serde::Serialize)]
+struct DiagnosticReport {
+    ockam_version: String,
+    /// Time of the report, in seconds since the Unix epoch
+    generated_at: u64,
+    healthy: bool,
+    orchestrator: OrchestratorDiagnostic,
+    identities: Vec<IdentityDiagnostic>,
+    credentials: Vec<CredentialDiagnostic>,
+    nodes: Vec<NodeDiagnostic>,
+}
+
+#[derive(serde::Serialize)]
+struct OrchestratorDiagnostic {
+    reachable: bool,
+    latency_ms: Option<u64>,
+    controller_version: Option<String>,
+    project_version: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct IdentityDiagnostic {
+    name: String,
+    identifier: String,
+    default: bool,
+    enrolled: bool,
+}
+
+#[derive(serde::Serialize)]
+struct CredentialDiagnostic {
+    name: String,
+    valid: bool,
+    error: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct NodeDiagnostic {
+    name: String,
+    running: bool,
+    responsive: bool,
+    latency_ms: Option<u64>,
+    relays: Vec<RelayDiagnostic>,
+    inlets: Vec<InletHealth>,
+    error: Option<String>,
+}
+
+impl NodeDiagnostic {
+    fn is_healthy(&self) -> bool {
+        self.running
+            && self.responsive
+            && self.error.is_none()
+            && self.relays.iter().all(|r| r.up)
+            && self.inlets.iter().all(|i| i.connected)
+    }
+}
+
+#[derive(serde::Serialize)]
+struct RelayDiagnostic {
+    remote_address: String,
+    up: bool,
+}
@@ -0,0 +1,490 @@
+use std::io::Write;
+use std::time::Duration;
+
+use clap::Args;
+use miette::{miette, IntoDiagnostic};
+use minicbor::{Decode, Decoder, Encode};
+use tracing::warn;
+
+use ockam::identity::{Identifier, SecureChannelOptions, TrustIdentifierPolicy};
+use ockam::{AsyncTryClone, Context, Node, TcpConnectionOptions, TcpTransport};
+use ockam_api::cli_state::identities::IdentityState;
+use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
+use ockam_api::cli_state::NodesState;
+use ockam_api::nodes::models::base::NodeStatus as NodeStatusModel;
+use ockam_api::nodes::models::portal::InletList;
+use ockam_api::nodes::{BackgroundNode, NodeManager};
+use ockam_core::api::{Request, ResponseHeader, Status};
+use ockam_core::route;
+use ockam_node::MessageSendReceiveOptions;
+
+mod diagnostics;
+
+use crate::credential::{identities, validate_encoded_cred};
+use crate::node::prune::warn_about_inconsistencies;
+use crate::util::{api, exitcode, node_rpc};
+use crate::vault::default_vault_name;
+use crate::CommandGlobalOpts;
+use crate::Result;
+
+/// Display information about the system's status
+#[derive(Clone, Debug, Args)]
+pub struct StatusCommand {
+    /// Run all the diagnostics: orchestrator reachability, identities enrollment, credentials
+    /// validity, and for each node its API responsiveness, relay registrations and inlet sessions.
+    /// With `--output json`, the report can be attached to a support ticket
+    #[arg(long, short, conflicts_with = "check")]
+    all: bool,
+
+    /// Override default timeout (in seconds)
+    #[arg(long, default_value = "30")]
+    timeout: u64,
+
+    /// Only check that all the nodes are running, all the inlets are connected and all the
+    /// credentials are valid. The command exits with a non-zero code if it is not the case
+    #[arg(long)]
+    check: bool,
+}
+
+impl StatusCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, StatusCommand)) -> miette::Result<()> {
+    run_impl(&ctx, opts, cmd).await
+}
+
+async fn run_impl(
+    ctx: &Context,
+    opts: CommandGlobalOpts,
+    cmd: StatusCommand,
+) -> miette::Result<()> {
+    if cmd.check {
+        return check_health(ctx, opts).await;
+    }
+    if cmd.all {
+        return diagnostics::diagnose(ctx, opts, Duration::from_secs(cmd.timeout)).await;
+    }
+    warn_about_inconsistencies(&opts);
+    let identities_details = get_identities_details(&opts)?;
+    let nodes_details = get_nodes_details(ctx, &opts).await?;
+    let orchestrator_version =
+        get_orchestrator_version(ctx, &opts, Duration::from_secs(cmd.timeout)).await;
+    let status = StatusData::from_parts(orchestrator_version, identities_details, nodes_details)?;
+    print_output(opts, status)?;
+    Ok(())
+}
+
+async fn get_nodes_details(ctx: &Context, opts: &CommandGlobalOpts) -> Result<Vec<NodeDetails>> {
+    let summaries = opts.state.nodes.summaries()?;
+    // Only the nodes with a running process are queried, all at once
+    let running_pids = NodesState::running_pids(summaries.iter().filter_map(|s| s.pid));
+
+    let mut queries = vec![];
+    for summary in summaries {
+        let identifier = match summary.identifier.as_deref().map(Identifier::try_from) {
+            Some(Ok(identifier)) => identifier,
+            _ => continue,
+        };
+        let is_running = summary
+            .pid
+            .map(|pid| running_pids.contains(&pid))
+            .unwrap_or(false);
+        let ctx = ctx.async_try_clone().await?;
+        let opts = opts.clone();
+        queries.push(tokio::spawn(async move {
+            let status = if is_running {
+                get_node_status(&ctx, &opts, &summary.name).await
+            } else {
+                "Stopped".to_string()
+            };
+            NodeDetails {
+                identifier,
+                name: summary.name,
+                status,
+            }
+        }));
+    }
+
+    let mut node_details = vec![];
+    for query in queries {
+        node_details.push(query.await.into_diagnostic()?);
+    }
+    Ok(node_details)
+}
+
+async fn get_node_status(ctx: &Context, opts: &CommandGlobalOpts, node_name: &str) -> String {
+    let node_status_model: miette::Result<NodeStatusModel> = async {
+        let mut node = BackgroundNode::create(ctx, &opts.state, node_name).await?;
+        node.set_timeout(Duration::from_millis(200));
+        node.ask(ctx, api::query_status()).await
+    }
+    .await;
+    node_status_model
+        .map(|m| m.status)
+        .unwrap_or("Stopped".to_string())
+}
+
+/// Print a health report of the local nodes and credentials, and exit with
+/// [`exitcode::UNAVAILABLE`] if something is not healthy
+async fn check_health(ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+    let mut nodes = vec![];
+    for summary in opts.state.nodes.summaries()? {
+        let running =
+            summary.pid.is_some() && get_node_status(ctx, &opts, &summary.name).await == "Running";
+        // A node which can't list its inlets is reported as unhealthy, the other nodes are still checked
+        let (inlets, inlets_error) = if running {
+            match get_inlets_health(ctx, &opts, &summary.name).await {
+                Ok(inlets) => (inlets, None),
+                Err(e) => (vec![], Some(e.to_string())),
+            }
+        } else {
+            (vec![], None)
+        };
+        nodes.push(NodeHealth {
+            name: summary.name,
+            running,
+            inlets,
+            inlets_error,
+        });
+    }
+
+    let mut credentials = vec![];
+    let credential_states = opts.state.credentials.list()?;
+    if !credential_states.is_empty() {
+        let identities = identities(&default_vault_name(&opts.state), &opts).await?;
+        for state in credential_states {
+            let config = state.config();
+            let valid = validate_encoded_cred(
+                &config.encoded_credential,
+                identities.clone(),
+                &config.issuer_identifier,
+            )
+            .await
+            .is_ok();
+            credentials.push(CredentialHealth {
+                name: state.name().to_string(),
+                valid,
+            });
+        }
+    }
+
+    let healthy = nodes
+        .iter()
+        .all(|n| n.running && n.inlets_error.is_none() && n.inlets.iter().all(|i| i.connected))
+        && credentials.iter().all(|c| c.valid);
+    let report = HealthReport {
+        healthy,
+        nodes,
+        credentials,
+    };
+
+    let json = serde_json::to_string(&report).into_diagnostic()?;
+    opts.terminal
+        .stdout()
+        .plain(build_health_plain_output(&report)?)
+        .machine(if healthy { "healthy" } else { "unhealthy" })
+        .json(json)
+        .write_line()?;
+    if !healthy {
+        std::process::exit(exitcode::UNAVAILABLE);
+    }
+    Ok(())
+}
+
+async fn get_inlets_health(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+) -> Result<Vec<InletHealth>> {
+    let mut node = BackgroundNode::create(ctx, &opts.state, node_name).await?;
+    node.set_timeout(Duration::from_secs(5));
+    let inlets: InletList = node.ask(ctx, Request::get("/node/inlet")).await?;
+    Ok(inlets
+        .list
+        .into_iter()
+        .map(|inlet| InletHealth {
+            connected: inlet.status == "up",
+            alias: inlet.alias,
+            status: inlet.status,
+        })
+        .collect())
+}
+
+fn build_health_plain_output(report: &HealthReport) -> Result<String> {
+    let mut plain = Vec::new();
+    let verdict = if report.healthy {
+        "healthy"
+    } else {
+        "unhealthy"
+    };
+    writeln!(&mut plain, "Status: {verdict}")?;
+    for node in &report.nodes {
+        let status = if node.running {
+            "running"
+        } else {
+            "not running"
+        };
+        writeln!(&mut plain, "{:2}Node {}: {status}", "", node.name)?;
+        if let Some(error) = &node.inlets_error {
+            writeln!(&mut plain, "{:4}Inlets: could not be listed ({error})", "")?;
+        }
+        for inlet in &node.inlets {
+            writeln!(
+                &mut plain,
+                "{:4}Inlet {}: {}",
+                "", inlet.alias, inlet.status
+            )?;
+        }
+    }
+    for credential in &report.credentials {
+        let validity = if credential.valid { "valid" } else { "invalid" };
+        writeln!(
+            &mut plain,
+            "{:2}Credential {}: {validity}",
+            "", credential.name
+        )?;
+    }
+    Ok(String::from_utf8(plain).expect("Invalid UTF-8 output"))
+}
+
+fn get_identities_details(opts: &CommandGlobalOpts) -> Result<Vec<IdentityState>> {
+    let mut identities_details: Vec<IdentityState> = vec![];
+    for identity in opts.state.identities.list()? {
+        match &identity.config().enrollment_status {
+            Some(_enrollment) => identities_details.push(identity),
+            None => (),
+        }
+    }
+    Ok(identities_details)
+}
+
+async fn get_orchestrator_version(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    timeout: Duration,
+) -> Result<OrchestratorVersionInfo> {
+    // for new we get the controller address directly until we
+    // access a Controller interface from the NodeManager
+    let controller_addr = NodeManager::controller_multiaddr();
+    let controller_identifier = NodeManager::load_controller_identifier()?;
+    let controller_tcp_addr = controller_addr.to_socket_addr()?;
+    let tcp = TcpTransport::create(ctx).await?;
+    let connection = tcp
+        .connect(controller_tcp_addr, TcpConnectionOptions::new())
+        .await?;
+
+    // Create node that will be used to send the request
+    let node = {
+        // Get or create a vault to store the identity
+        let vault = match opts.state.vaults.default() {
+            Ok(v) => v,
+            Err(_) => opts.state.create_vault_state(None).await?,
+        }
+        .get()
+        .await?;
+        let identities_repository = opts.state.identities.identities_repository().await?;
+        Node::builder()
+            .with_vault(vault)
+            .with_identities_repository(identities_repository)
+            .build(ctx)
+            .await?
+    };
+
+    // Establish secure channel with controller
+    let node_identity = opts
+        .state
+        .default_identities()
+        .await?
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let secure_channel_options = SecureChannelOptions::new()
+        .with_trust_policy(TrustIdentifierPolicy::new(controller_identifier))
+        .with_timeout(timeout);
+    let secure_channel = node
+        .create_secure_channel(
+            node_identity.identifier(),
+            route![connection, "api"],
+            secure_channel_options,
+        )
+        .await?;
+
+    // Send request
+    let buf: Vec<u8> = node
+        .send_and_receive_extended::<Vec<u8>>(
+            route![secure_channel, "version_info"],
+            Request::get("").to_vec()?,
+            MessageSendReceiveOptions::new().with_timeout(timeout),
+        )
+        .await?
+        .body();
+    let mut dec = Decoder::new(&buf);
+
+    // Decode response
+    let hdr = dec.decode::<ResponseHeader>()?;
+    if hdr.status() == Some(Status::Ok) {
+        Ok(dec.decode::<OrchestratorVersionInfo>()?)
+    } else {
+        Err(miette!("Failed to retrieve version information from node.").into())
+    }
+}
+
+fn print_output(opts: CommandGlobalOpts, status: StatusData) -> Result<()> {
+    let plain = build_plain_output(&opts, &status)?;
+    let json = serde_json::to_string(&status)?;
+    opts.terminal
+        .stdout()
+        .plain(String::from_utf8(plain).expect("Invalid UTF-8 output"))
+        .json(json)
+        .write_line()?;
+    Ok(())
+}
+
+fn build_plain_output(opts: &CommandGlobalOpts, status: &StatusData) -> Result<Vec<u8>> {
+    let mut plain = Vec::new();
+    writeln!(
+        &mut plain,
+        "Controller version: {}",
+        status.orchestrator_version.controller_version
+    )?;
+    writeln!(
+        &mut plain,
+        "Project version: {}",
+        status.orchestrator_version.project_version
+    )?;
+    if status.identities.is_empty() {
+        writeln!(
+            &mut plain,
+            "No enrolled identities could be found. \
+            Try passing the `--all` argument to run all the diagnostics, including all the identities. \
+            Also consider running `ockam enroll` to enroll an identity.",
+        )?;
+        return Ok(plain);
+    }
+    let default_identity = opts.state.identities.default()?;
+    for (i_idx, i) in status.identities.iter().enumerate() {
+        writeln!(&mut plain, "Identity[{i_idx}]")?;
+        if default_identity.config().identifier() == i.identity.config().identifier() {
+            writeln!(&mut plain, "{:2}Default: yes", "")?;
+        }
+        for line in i.identity.to_string().lines() {
+            writeln!(&mut plain, "{:2}{}", "", line)?;
+        }
+        if !i.nodes.is_empty() {
+            writeln!(&mut plain, "{:2}Linked Nodes:", "")?;
+            for (n_idx, node) in i.nodes.iter().enumerate() {
+                writeln!(&mut plain, "{:4}Node[{}]:", "", n_idx)?;
+                writeln!(&mut plain, "{:6}Name: {}", "", node.name)?;
+                writeln!(&mut plain, "{:6}Status: {}", "", node.status)?;
+            }
+        }
+    }
+    Ok(plain)
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StatusData {
+    #[serde(flatten)]
+    orchestrator_version: OrchestratorVersionInfo,
+    identities: Vec<IdentityWithLinkedNodes>,
+}
+
+impl StatusData {
+    fn from_parts(
+        orchestrator_version: Result<OrchestratorVersionInfo>,
+        identities_details: Vec<IdentityState>,
+        mut nodes_details: Vec<NodeDetails>,
+    ) -> Result<Self> {
+        let orchestrator_version = orchestrator_version
+            .map_err(|e| warn!(%e, "Failed to retrieve orchestrator version"))
+            .unwrap_or(OrchestratorVersionInfo {
+                controller_version: "N/A".to_string(),
+                project_version: "N/A".to_string(),
+            });
+        let mut identities = vec![];
+        for identity in identities_details.into_iter() {
+            let mut identity_status = IdentityWithLinkedNodes {
+                identity,
+                nodes: vec![],
+            };
+            nodes_details
+                .retain(|nd| nd.identifier == identity_status.identity.config().identifier());
+            if !nodes_details.is_empty() {
+                for node in nodes_details.iter() {
+                    identity_status.nodes.push(NodeStatus {
+                        name: node.name.clone(),
+                        status: node.status.clone(),
+                    });
+                }
+            }
+            identities.push(identity_status);
+        }
+        Ok(Self {
+            orchestrator_version,
+            identities,
+        })
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct IdentityWithLinkedNodes {
+    identity: IdentityState,
+    nodes: Vec<NodeStatus>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct IdentityStatus {}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct NodeStatus {
+    name: String,
+    status: String,
+}
+
+#[derive(serde::Serialize)]
+struct HealthReport {
+    healthy: bool,
+    nodes: Vec<NodeHealth>,
+    credentials: Vec<CredentialHealth>,
+}
+
+#[derive(serde::Serialize)]
+struct NodeHealth {
+    name: String,
+    running: bool,
+    inlets: Vec<InletHealth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inlets_error: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct InletHealth {
+    alias: String,
+    status: String,
+    connected: bool,
+}
+
+#[derive(serde::Serialize)]
+struct CredentialHealth {
+    name: String,
+    valid: bool,
+}
+
+struct NodeDetails {
+    identifier: Identifier,
+    name: String,
+    status: String,
+}
+
+#[derive(Encode, Decode, Debug, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(test, derive(Clone))]
+#[cbor(map)]
+struct OrchestratorVersionInfo {
+    #[n(1)]
+    controller_version: String,
+    #[n(2)]
+    project_version: String,
+}
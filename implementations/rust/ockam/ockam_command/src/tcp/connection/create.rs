@@ -56,9 +56,9 @@ async fn run_impl(
     let plain = formatdoc! {r#"
         TCP Connection:
             From: /node/{from}
-            To: {to} (/ip4/{}/tcp/{})
+            To: {to} (/{}/{}/tcp/{})
             Address: {}
-    "#, to.ip(), to.port(), transport_status.multiaddr().into_diagnostic()?};
+    "#, if to.is_ipv6() { "ip6" } else { "ip4" }, to.ip(), to.port(), transport_status.multiaddr().into_diagnostic()?};
     let json = serde_json::json!([{"route": transport_status.multiaddr().into_diagnostic()? }]);
     opts.terminal
         .stdout()
@@ -13,23 +13,28 @@ use tracing::log::trace;
 use ockam::identity::Identifier;
 use ockam::Context;
 
-use ockam_api::nodes::models::portal::InletStatus;
+use ockam_api::cli_state::{CliState, SettingsState};
+use ockam_api::nodes::models::portal::{DataFlow, InletProtocol, InletStatus};
 use ockam_api::nodes::service::portals::Inlets;
 use ockam_api::nodes::BackgroundNode;
+use ockam_api::port_range::PortRange;
 use ockam_core::api::{Reply, Status};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::Error;
 use ockam_multiaddr::proto::Project;
 use ockam_multiaddr::{MultiAddr, Protocol as _};
 
+use crate::dry_run::{Plan, Step};
 use crate::node::{get_node_name, initialize_node_if_default};
 
 use crate::tcp::util::alias_parser;
 use crate::terminal::OckamColor;
+use crate::util::api::QuotaOpts;
 use crate::util::duration::duration_parser;
 use crate::util::parsers::socket_addr_parser;
 use crate::util::{
-    find_available_port, node_rpc, parse_node_name, port_is_free_guard, process_nodes_multiaddr,
+    find_available_port, find_available_port_in_range, node_rpc, parse_node_name,
+    port_is_free_guard, process_nodes_multiaddr,
 };
 use crate::{display_parse_logs, docs, fmt_log, fmt_ok, CommandGlobalOpts};
 
@@ -44,18 +49,26 @@ pub struct CreateCommand {
     at: Option<String>,
 
     /// Address on which to accept tcp connections.
-    #[arg(long, display_order = 900, id = "SOCKET_ADDRESS", hide_default_value = true, default_value_t = default_from_addr(), value_parser = socket_addr_parser)]
-    from: SocketAddr,
+    /// By default, an available port of localhost, in the configured inlet port range if any
+    #[arg(long, display_order = 900, id = "SOCKET_ADDRESS", value_parser = socket_addr_parser)]
+    from: Option<SocketAddr>,
 
     /// Route to a tcp outlet.
     #[arg(long, display_order = 900, id = "ROUTE", default_value_t = default_to_addr())]
     to: MultiAddr,
 
+    /// Another route to the same tcp outlet, for example directly to the outlet node instead of
+    /// via a relay. It can be given several times. The routes are probed with pings and the one
+    /// with the lowest latency is used. It is selected again when the inlet is recreated
+    #[arg(long = "alternate-to", display_order = 900, value_name = "ROUTE")]
+    alternate_to: Vec<MultiAddr>,
+
     /// Authorized identity for secure channel connection
     #[arg(long, name = "AUTHORIZED", display_order = 900)]
     authorized: Option<Identifier>,
 
-    /// Assign a name to this inlet.
+    /// Assign a name to this inlet. If not provided, an alias is derived from the inlet target
+    /// using the template set with `ockam configuration set alias-template`.
     #[arg(long, display_order = 900, id = "ALIAS", value_parser = alias_parser)]
     alias: Option<String>,
 
@@ -70,38 +83,129 @@ pub struct CreateCommand {
     /// Override default timeout
     #[arg(long, value_parser = duration_parser)]
     timeout: Option<Duration>,
+
+    /// Direction in which data can flow through the inlet: bidirectional, client-to-server
+    /// or server-to-client. Data sent in the other direction is dropped by the inlet.
+    #[arg(long, display_order = 900, id = "DATA_FLOW", default_value_t = DataFlow::default())]
+    data_flow: DataFlow,
+
+    /// Protocol expected at the beginning of each connection: http, tls or ssh.
+    /// Connections starting with other bytes are closed before any data is sent to the outlet.
+    #[arg(long, display_order = 900, id = "PROTOCOL")]
+    expect_protocol: Option<InletProtocol>,
+
+    #[command(flatten)]
+    quota_opts: QuotaOpts,
 }
 
-pub(crate) fn default_from_addr() -> SocketAddr {
-    let port = find_available_port().expect("Failed to find available port");
-    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port)
+/// Return an available address of localhost, in the port range configured with
+/// `ockam configuration set inlet-port-range` if any. Fail if no port is available
+pub(crate) fn default_from_addr() -> crate::Result<SocketAddr> {
+    let port = match configured_inlet_port_range() {
+        Some(range) => find_available_port_in_range(&range)?,
+        None => find_available_port()?,
+    };
+    Ok(SocketAddr::new(
+        IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+        port,
+    ))
 }
 
-fn default_to_addr() -> MultiAddr {
+/// Return the port range configured with `ockam configuration set inlet-port-range`, if any.
+/// The settings are read directly from the state directory, so that the commands which
+/// suggest an inlet address can call this function without a `CliState`.
+fn configured_inlet_port_range() -> Option<PortRange> {
+    let dir = CliState::default_dir().ok()?;
+    SettingsState::new(&dir).get().ok()?.inlet_port_range
+}
+
+pub(crate) fn default_to_addr() -> MultiAddr {
     MultiAddr::from_str("/project/default/service/forward_to_default/secure/api/service/outlet")
         .expect("Failed to parse default multiaddr")
 }
 
 impl CreateCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
-        initialize_node_if_default(&opts, &self.at);
+        if !opts.global_args.dry_run {
+            initialize_node_if_default(&opts, &self.at);
+        }
         node_rpc(rpc, (opts, self));
     }
+
+    /// Return the address given with `--from`, or an available address of localhost
+    fn from_addr(&self) -> crate::Result<SocketAddr> {
+        match self.from {
+            Some(from) => Ok(from),
+            None => default_from_addr(),
+        }
+    }
+
+    /// Return the API call made to create the inlet, and the creation of the default node
+    /// if it doesn't exist yet, for `--dry-run`
+    fn plan(&self, opts: &CommandGlobalOpts) -> miette::Result<Plan> {
+        let from = self.from_addr()?;
+        port_is_free_guard(&from)?;
+        let to = process_nodes_multiaddr(&self.to, &opts.state)?;
+        let alternate_to = self
+            .alternate_to
+            .iter()
+            .map(|to| process_nodes_multiaddr(to, &opts.state))
+            .collect::<crate::Result<Vec<_>>>()?;
+        if to.matches(0, &[Project::CODE.into()]) && self.authorized.is_some() {
+            return Err(miette!(
+                "--authorized can not be used with project addresses"
+            ));
+        }
+
+        let node_name = parse_node_name(&get_node_name(&opts.state, &self.at))?;
+        let mut plan = Plan::new("tcp-inlet create");
+        if node_name == "default" && opts.state.nodes.default().is_err() {
+            let mut create_node = crate::node::CreateCommand::default();
+            create_node.node_name = node_name.clone();
+            for step in create_node.plan(opts)?.into_steps() {
+                plan.push(step);
+            }
+        }
+        plan.push(Step::api_call(
+            &node_name,
+            "POST",
+            "/node/inlet",
+            serde_json::json!({
+                "listen_addr": from.to_string(),
+                "outlet_addr": to.to_string(),
+                "alternate_outlet_addrs": alternate_to.iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+                "alias": self.alias,
+                "authorized": self.authorized.as_ref().map(|i| i.to_string()),
+                "wait_for_outlet_duration": format!("{:?}", self.connection_wait),
+                "data_flow": self.data_flow,
+                "quota": format!("{:?}", self.quota_opts.to_quota()),
+                "expected_protocol": self.expect_protocol,
+            }),
+        ));
+        Ok(plan)
+    }
 }
 
 async fn rpc(
     ctx: Context,
     (opts, mut cmd): (CommandGlobalOpts, CreateCommand),
 ) -> miette::Result<()> {
+    if opts.global_args.dry_run {
+        return cmd.plan(&opts)?.print(&opts);
+    }
+    let from = cmd.from_addr()?;
     opts.terminal.write_line(&fmt_log!(
         "Creating TCP Inlet at {}...\n",
-        cmd.from
-            .to_string()
-            .color(OckamColor::PrimaryResource.color())
+        from.to_string().color(OckamColor::PrimaryResource.color())
     ))?;
     display_parse_logs(&opts);
 
     cmd.to = process_nodes_multiaddr(&cmd.to, &opts.state)?;
+    cmd.alternate_to = cmd
+        .alternate_to
+        .iter()
+        .map(|to| process_nodes_multiaddr(to, &opts.state))
+        .collect::<crate::Result<Vec<_>>>()?;
 
     let node_name = get_node_name(&opts.state, &cmd.at);
     let node_name = parse_node_name(&node_name)?;
@@ -112,7 +216,7 @@ async fn rpc(
     let is_finished: Mutex<bool> = Mutex::new(false);
     let progress_bar = opts.terminal.progress_spinner();
     let create_inlet = async {
-        port_is_free_guard(&cmd.from)?;
+        port_is_free_guard(&from)?;
         if cmd.to.clone().matches(0, &[Project::CODE.into()]) && cmd.authorized.is_some() {
             return Err(miette!("--authorized can not be used with project addresses").into());
         }
@@ -121,11 +225,15 @@ async fn rpc(
             let result: Reply<InletStatus> = node
                 .create_inlet(
                     &ctx,
-                    &cmd.from.to_string(),
+                    &from.to_string(),
                     &cmd.to,
+                    &cmd.alternate_to,
                     &cmd.alias,
                     &cmd.authorized,
                     cmd.connection_wait,
+                    cmd.data_flow,
+                    cmd.quota_opts.to_quota(),
+                    cmd.expect_protocol,
                 )
                 .await?;
 
@@ -135,6 +243,10 @@ async fn rpc(
                     break inlet_status;
                 }
                 Reply::Failed(e, s) => {
+                    // The errors with a code are reported with an actionable message
+                    if e.code().is_some() {
+                        return Err(crate::Error::from(e.into_failure()));
+                    }
                     if let Some(status) = s {
                         if status == Status::BadRequest {
                             Err(Error::new(
@@ -175,9 +287,7 @@ async fn rpc(
         ),
         format!(
             "Hosting TCP Socket at {}...",
-            &cmd.from
-                .to_string()
-                .color(OckamColor::PrimaryResource.color())
+            &from.to_string().color(OckamColor::PrimaryResource.color())
         ),
         format!(
             "Establishing connection to outlet {}...",
@@ -197,9 +307,7 @@ async fn rpc(
         .plain(
             fmt_ok!(
                 "TCP Inlet {} on node {} is now sending traffic\n",
-                &cmd.from
-                    .to_string()
-                    .color(OckamColor::PrimaryResource.color()),
+                &from.to_string().color(OckamColor::PrimaryResource.color()),
                 &node_name
                     .to_string()
                     .color(OckamColor::PrimaryResource.color())
@@ -1,6 +1,6 @@
 use clap::Args;
 use colorful::Colorful;
-use miette::{miette, IntoDiagnostic};
+use miette::miette;
 use tokio::sync::Mutex;
 use tokio::try_join;
 
@@ -12,6 +12,7 @@ use ockam_core::api::Request;
 use ockam_node::Context;
 
 use crate::node::{get_node_name, initialize_node_if_default, NodeOpts};
+use crate::output::schema_json_list;
 use crate::terminal::OckamColor;
 use crate::util::node_rpc;
 use crate::{docs, CommandGlobalOpts};
@@ -74,7 +75,7 @@ async fn run_impl(
         "Inlets",
         &format!("No TCP Inlets found on {node_name}"),
     )?;
-    let json = serde_json::to_string_pretty(&inlets.list).into_diagnostic()?;
+    let json = schema_json_list(&inlets.list, opts.global_args.output_schema())?;
     opts.terminal
         .stdout()
         .plain(plain)
@@ -2,13 +2,19 @@ pub(crate) mod create;
 mod delete;
 mod list;
 mod show;
+mod update;
 
+use crate::output::{to_schema_value, unsupported_schema, SchemaOutput};
 use crate::{docs, CommandGlobalOpts};
 use clap::{Args, Subcommand};
 use create::CreateCommand;
 use delete::DeleteCommand;
 pub(crate) use list::ListCommand;
+use ockam_api::nodes::models::portal::InletStatus;
+use serde::Serialize;
+use serde_json::Value;
 pub(crate) use show::ShowCommand;
+use update::UpdateCommand;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
@@ -23,7 +29,7 @@ const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
 )]
 pub struct TcpInletCommand {
     #[command(subcommand)]
-    subcommand: TcpInletSubCommand,
+    pub subcommand: TcpInletSubCommand,
 }
 
 #[derive(Clone, Debug, Subcommand)]
@@ -32,6 +38,7 @@ pub enum TcpInletSubCommand {
     Delete(DeleteCommand),
     List(ListCommand),
     Show(ShowCommand),
+    Update(UpdateCommand),
 }
 
 impl TcpInletCommand {
@@ -41,6 +48,40 @@ impl TcpInletCommand {
             TcpInletSubCommand::Delete(c) => c.run(options),
             TcpInletSubCommand::List(c) => c.run(options),
             TcpInletSubCommand::Show(c) => c.run(options),
+            TcpInletSubCommand::Update(c) => c.run(options),
+        }
+    }
+}
+
+/// Document of an inlet in the version 1 of the output schema
+#[derive(Serialize)]
+struct InletOutputV1<'a> {
+    /// Name of the inlet
+    alias: &'a str,
+    /// Address on which the inlet accepts the TCP connections
+    bind_addr: &'a str,
+    /// Route to the outlet
+    outlet_route: &'a str,
+    /// Status of the route to the outlet
+    status: &'a str,
+    /// Address of the inlet worker on its node
+    worker_addr: &'a str,
+    /// Details about the status, like the reason of a failure
+    payload: Option<&'a str>,
+}
+
+impl SchemaOutput for InletStatus {
+    fn schema_output(&self, version: u8) -> crate::Result<Value> {
+        match version {
+            1 => to_schema_value(InletOutputV1 {
+                alias: &self.alias,
+                bind_addr: &self.bind_addr,
+                outlet_route: &self.outlet_route,
+                status: &self.status,
+                worker_addr: &self.worker_addr,
+                payload: self.payload.as_deref(),
+            }),
+            _ => Err(unsupported_schema(version)),
         }
     }
 }
@@ -10,6 +10,7 @@ use ockam_api::nodes::BackgroundNode;
 
 use crate::fmt_ok;
 use crate::node::{get_node_name, initialize_node_if_default, NodeOpts};
+use crate::output::SchemaOutput;
 use crate::tcp::util::alias_parser;
 use crate::util::{node_rpc, parse_node_name};
 use crate::{docs, CommandGlobalOpts};
@@ -53,7 +54,9 @@ pub async fn run_impl(
         .success()
         .into_diagnostic()?;
 
-    let json = serde_json::to_string(&inlet_status).into_diagnostic()?;
+    let json =
+        serde_json::to_string(&inlet_status.schema_output(opts.global_args.output_schema())?)
+            .into_diagnostic()?;
     let InletStatus {
         alias,
         bind_addr,
@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+use clap::Args;
+use colorful::Colorful;
+use indoc::formatdoc;
+use miette::{miette, IntoDiagnostic};
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_api::nodes::models::portal::{
+    InletCanaryStatus, InletStickiness, SetInletCanaryWeights, StartInletCanary,
+};
+use ockam_api::nodes::service::portals::Inlets;
+use ockam_api::nodes::BackgroundNode;
+use ockam_multiaddr::MultiAddr;
+
+use crate::node::{get_node_name, initialize_node_if_default, NodeOpts};
+use crate::tcp::util::alias_parser;
+use crate::terminal::OckamColor;
+use crate::util::duration::duration_parser;
+use crate::util::{node_rpc, parse_node_name, process_nodes_multiaddr};
+use crate::{docs, fmt_log, fmt_ok, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/update/after_long_help.txt");
+
+/// Switch a TCP Inlet to a new outlet, testing it first with a fraction of the new connections
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct UpdateCommand {
+    /// Name of the inlet
+    #[arg(display_order = 900, required = true, id = "ALIAS", value_parser = alias_parser)]
+    alias: String,
+
+    /// Node on which the inlet was started
+    #[command(flatten)]
+    node_opts: NodeOpts,
+
+    /// Route to the new tcp outlet
+    #[arg(long, display_order = 900, id = "ROUTE", requires = "PERCENTAGE")]
+    to: Option<MultiAddr>,
+
+    /// Percentage of the new connections sent to the new outlet, for example 10%
+    #[arg(long, display_order = 900, id = "PERCENTAGE", requires = "ROUTE", value_parser = percentage_parser)]
+    canary: Option<u8>,
+
+    /// Percentage of failed connections to the new outlet above which all the new connections
+    /// are sent back to the current outlet
+    #[arg(long, display_order = 900, default_value = "5%", value_parser = percentage_parser)]
+    error_threshold: u8,
+
+    /// Number of connections to the new outlet after which it replaces the current outlet,
+    /// if its failures stay below the error threshold
+    #[arg(long, display_order = 900, id = "CONNECTIONS")]
+    promote_after: Option<u64>,
+
+    /// How the connections of a client are spread between the current and the new outlets:
+    /// none, or source-ip to send all the connections coming from the same IP address to the
+    /// same outlet, for servers keeping the state of client sessions
+    #[arg(long, display_order = 900, id = "STICKINESS", default_value_t = InletStickiness::default())]
+    stickiness: InletStickiness,
+
+    /// Authorized identity for the secure channel connection to the new outlet
+    #[arg(long, display_order = 900, id = "AUTHORIZED")]
+    authorized: Option<Identifier>,
+
+    /// Time to wait for the new outlet to be available
+    #[arg(long, display_order = 900, id = "WAIT", default_value = "5s", value_parser = duration_parser)]
+    connection_wait: Duration,
+
+    /// Relative weights of the current and the new outlets, for example 70,30, to shift
+    /// gradually the new connections between the outlets while keeping the new outlet statistics
+    #[arg(long, display_order = 901, id = "WEIGHTS", conflicts_with_all = ["ROUTE", "promote", "rollback", "status"], value_parser = weights_parser)]
+    weights: Option<(u32, u32)>,
+
+    /// Replace the current outlet with the new outlet
+    #[arg(long, display_order = 901, conflicts_with_all = ["ROUTE", "rollback", "status"])]
+    promote: bool,
+
+    /// Send all the new connections back to the current outlet
+    #[arg(long, display_order = 901, conflicts_with_all = ["ROUTE", "promote", "status"])]
+    rollback: bool,
+
+    /// Show the connections and failures of the new outlet
+    #[arg(long, display_order = 901, conflicts_with_all = ["ROUTE", "promote", "rollback"])]
+    status: bool,
+}
+
+impl UpdateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_node_if_default(&opts, &self.node_opts.at_node);
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+/// Parse a percentage between 1 and 100, with or without a trailing `%`
+fn percentage_parser(s: &str) -> Result<u8, String> {
+    match s.trim_end_matches('%').parse::<u8>() {
+        Ok(p) if (1..=100).contains(&p) => Ok(p),
+        _ => Err(format!(
+            "invalid percentage {s}, it must be a number between 1 and 100, for example 10%"
+        )),
+    }
+}
+
+/// Parse the weights of the current and the new outlets, separated by a comma
+fn weights_parser(s: &str) -> Result<(u32, u32), String> {
+    let error = || {
+        format!(
+            "invalid weights {s}, they must be two numbers separated by a comma, for example 70,30"
+        )
+    };
+    let (outlet, canary) = s.split_once(',').ok_or_else(error)?;
+    match (outlet.trim().parse::<u32>(), canary.trim().parse::<u32>()) {
+        (Ok(0), Ok(0)) => Err("at least one of the weights must be positive".to_string()),
+        (Ok(outlet), Ok(canary)) => Ok((outlet, canary)),
+        _ => Err(error()),
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, UpdateCommand),
+) -> miette::Result<()> {
+    let node_name = get_node_name(&opts.state, &cmd.node_opts.at_node);
+    let node_name = parse_node_name(&node_name)?;
+    let node = BackgroundNode::create(&ctx, &opts.state, &node_name).await?;
+
+    let status = if cmd.promote {
+        node.promote_inlet_canary(&ctx, &cmd.alias).await?
+    } else if cmd.rollback {
+        node.rollback_inlet_canary(&ctx, &cmd.alias).await?
+    } else if let Some((outlet_weight, canary_weight)) = cmd.weights {
+        let request = SetInletCanaryWeights::new(outlet_weight, canary_weight);
+        node.set_inlet_canary_weights(&ctx, &cmd.alias, request)
+            .await?
+    } else if cmd.status {
+        node.show_inlet_canary(&ctx, &cmd.alias).await?
+    } else {
+        let (to, percentage) = match (&cmd.to, cmd.canary) {
+            (Some(to), Some(percentage)) => (process_nodes_multiaddr(to, &opts.state)?, percentage),
+            _ => {
+                return Err(miette!(
+                    "Please provide the new outlet with --to and the share of connections it receives with --canary, or use one of --weights, --promote, --rollback, --status"
+                ))
+            }
+        };
+        let request = StartInletCanary::new(
+            to,
+            percentage,
+            cmd.error_threshold,
+            cmd.promote_after,
+            cmd.authorized.clone(),
+            Some(cmd.connection_wait),
+            cmd.stickiness,
+        );
+        node.start_inlet_canary(&ctx, &cmd.alias, request).await?
+    }
+    .success()
+    .into_diagnostic()?;
+
+    let json = serde_json::to_string(&status).into_diagnostic()?;
+    let InletCanaryStatus {
+        alias,
+        outlet_addr,
+        percentage,
+        status,
+        connections,
+        failures,
+        stickiness,
+    } = status;
+    let plain = fmt_ok!(
+        "The canary of the TCP Inlet {} is {}\n",
+        alias.color(OckamColor::PrimaryResource.color()),
+        status.color(OckamColor::PrimaryResource.color())
+    ) + &fmt_log!(
+        "{}",
+        formatdoc! {r#"
+            Outlet Address: {outlet_addr}
+            Share of new connections: {percentage}%
+            Stickiness: {stickiness}
+            Connections: {connections}
+            Failures: {failures}"#}
+    );
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .machine(status)
+        .json(json)
+        .write_line()?;
+    Ok(())
+}
@@ -1,3 +1,6 @@
+use std::path::PathBuf;
+
+use crate::tcp::util::read_pem_file;
 use crate::util::{node_rpc, parse_node_name};
 use crate::{docs, fmt_log, CommandGlobalOpts};
 use crate::{
@@ -10,7 +13,7 @@ use miette::IntoDiagnostic;
 use ockam_api::nodes::models::transport::{CreateTcpListener, TransportStatus};
 use ockam_api::nodes::BackgroundNode;
 use ockam_core::api::Request;
-use ockam_multiaddr::proto::{DnsAddr, Tcp};
+use ockam_multiaddr::proto::{DnsAddr, Tcp, Tls};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 
@@ -26,6 +29,15 @@ pub struct CreateCommand {
 
     /// Address for this listener (eg. 127.0.0.1:7000)
     pub address: String,
+
+    /// Wrap the accepted connections in TLS, presenting the certificate chain of this PEM file.
+    /// The connecting nodes must then use a `/tls` multiaddr instead of a `/tcp` one
+    #[arg(long, value_name = "PATH", requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM file containing the private key of the TLS certificate
+    #[arg(long, value_name = "PATH", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
 }
 
 impl CreateCommand {
@@ -41,12 +53,17 @@ async fn run_impl(
 ) -> miette::Result<()> {
     let node_name = get_node_name(&opts.state, &cmd.at);
     let node_name = parse_node_name(&node_name)?;
+    let mut payload = CreateTcpListener::new(cmd.address);
+    let tls = match (&cmd.tls_cert, &cmd.tls_key) {
+        (Some(cert), Some(key)) => {
+            payload = payload.with_tls(read_pem_file(cert)?, read_pem_file(key)?);
+            true
+        }
+        _ => false,
+    };
     let node = BackgroundNode::create(&ctx, &opts.state, &node_name).await?;
     let transport_status: TransportStatus = node
-        .ask(
-            &ctx,
-            Request::post("/node/tcp/listener").body(CreateTcpListener::new(cmd.address)),
-        )
+        .ask(&ctx, Request::post("/node/tcp/listener").body(payload))
         .await?;
 
     let socket = transport_status.socket_addr().into_diagnostic()?;
@@ -55,7 +72,11 @@ async fn run_impl(
     multiaddr
         .push_back(DnsAddr::new("localhost"))
         .into_diagnostic()?;
-    multiaddr.push_back(Tcp::new(port)).into_diagnostic()?;
+    if tls {
+        multiaddr.push_back(Tls::new(port)).into_diagnostic()?;
+    } else {
+        multiaddr.push_back(Tcp::new(port)).into_diagnostic()?;
+    }
 
     opts.terminal
         .stdout()
@@ -1,4 +1,5 @@
-use std::net::SocketAddr;
+use std::net::IpAddr;
+use std::path::PathBuf;
 
 use clap::Args;
 use colorful::Colorful;
@@ -10,14 +11,15 @@ use ockam::Context;
 use ockam_abac::Resource;
 use ockam_api::address::extract_address_value;
 use ockam_api::cli_state::{StateDirTrait, StateItemTrait};
-use ockam_api::nodes::models::portal::{CreateOutlet, OutletStatus};
+use ockam_api::nodes::models::portal::{CreateOutlet, DataFlow, OutletStatus, TlsOrigination};
 use ockam_api::nodes::BackgroundNode;
 use ockam_core::api::Request;
 
 use crate::node::{get_node_name, initialize_node_if_default};
 use crate::policy::{add_default_project_policy, has_policy};
-use crate::tcp::util::alias_parser;
+use crate::tcp::util::{alias_parser, read_pem_file, read_pem_file_or_secret};
 use crate::terminal::OckamColor;
+use crate::util::api::QuotaOpts;
 use crate::util::node_rpc;
 use crate::util::parsers::socket_addr_parser;
 use crate::{display_parse_logs, fmt_log};
@@ -38,12 +40,51 @@ pub struct CreateCommand {
     from: String,
 
     /// TCP address to send raw tcp traffic.
-    #[arg(long, display_order = 902, id = "SOCKET_ADDRESS", value_parser = socket_addr_parser)]
-    to: SocketAddr,
-
-    /// Assign a name to this outlet.
+    #[arg(long, display_order = 902, id = "SOCKET_ADDRESS")]
+    to: String,
+
+    /// Wrap the connections to the target in TLS. The certificate of the target is verified
+    /// with the native root certificates, unless a CA bundle is given with `--tls-ca-file`.
+    #[arg(long, display_order = 904)]
+    to_tls: bool,
+
+    /// PEM file containing the CA certificates used to verify the certificate of the target.
+    #[arg(long, display_order = 904, value_name = "PATH", requires = "to_tls")]
+    tls_ca_file: Option<PathBuf>,
+
+    /// Name sent with SNI and verified against the certificate of the target. By default,
+    /// the host name of the `--to` address is used, or its IP address.
+    #[arg(long, display_order = 904, value_name = "NAME", requires = "to_tls")]
+    tls_server_name: Option<String>,
+
+    /// PEM file containing the certificate chain used to authenticate to the target,
+    /// or a reference to a node secret: `secret:NAME`.
+    #[arg(long, display_order = 904, value_name = "PATH", requires_all = ["to_tls", "tls_client_key"])]
+    tls_client_cert: Option<PathBuf>,
+
+    /// PEM file containing the private key of the client certificate, or a reference to a
+    /// node secret: `secret:NAME`. The value of the secret is either the PEM contents, or the
+    /// path of a PEM file which can be read by the node.
+    #[arg(
+        long,
+        display_order = 904,
+        value_name = "PATH",
+        requires = "tls_client_cert"
+    )]
+    tls_client_key: Option<PathBuf>,
+
+    /// Assign a name to this outlet. If not provided, an alias is derived from the outlet target
+    /// using the template set with `ockam configuration set alias-template`.
     #[arg(long, display_order = 900, id = "ALIAS", value_parser = alias_parser)]
     alias: Option<String>,
+
+    /// Direction in which data can flow through the outlet: bidirectional, client-to-server
+    /// or server-to-client. Data sent in the other direction is dropped by the outlet.
+    #[arg(long, display_order = 903, id = "DATA_FLOW", default_value_t = DataFlow::default())]
+    data_flow: DataFlow,
+
+    #[command(flatten)]
+    quota_opts: QuotaOpts,
 }
 
 impl CreateCommand {
@@ -57,6 +98,40 @@ pub fn default_from_addr() -> String {
     "/service/outlet".to_string()
 }
 
+impl CreateCommand {
+    /// Return the TLS configuration of the outlet, with the contents of the PEM files
+    fn tls_origination(&self) -> miette::Result<Option<TlsOrigination>> {
+        if !self.to_tls {
+            return Ok(None);
+        }
+        Ok(Some(TlsOrigination {
+            ca_certificates: self.tls_ca_file.as_deref().map(read_pem_file).transpose()?,
+            server_name: self.tls_server_name.clone().or_else(|| host_name(&self.to)),
+            client_certificate: self
+                .tls_client_cert
+                .as_deref()
+                .map(read_pem_file_or_secret)
+                .transpose()?,
+            client_key: self
+                .tls_client_key
+                .as_deref()
+                .map(read_pem_file_or_secret)
+                .transpose()?,
+        }))
+    }
+}
+
+/// Return the host name of a `host:port` address, if it is not an IP address
+fn host_name(address: &str) -> Option<String> {
+    let (host, _) = address.rsplit_once(':')?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() || host.parse::<IpAddr>().is_ok() {
+        None
+    } else {
+        Some(host.to_string())
+    }
+}
+
 pub async fn run_impl(
     ctx: Context,
     (opts, cmd): (CommandGlobalOpts, CreateCommand),
@@ -69,6 +144,8 @@ pub async fn run_impl(
     ))?;
     display_parse_logs(&opts);
 
+    let to = socket_addr_parser(&cmd.to)?;
+    let tls = cmd.tls_origination()?;
     let node_name = get_node_name(&opts.state, &cmd.at);
     let node_name = extract_address_value(&node_name)?;
     let project = opts
@@ -89,12 +166,17 @@ pub async fn run_impl(
     let is_finished: Mutex<bool> = Mutex::new(false);
 
     let send_req = async {
-        let payload = CreateOutlet::new(
-            cmd.to,
+        let mut payload = CreateOutlet::new(
+            to,
             extract_address_value(&cmd.from)?.into(),
             cmd.alias,
             true,
         );
+        payload.set_data_flow(cmd.data_flow);
+        payload.set_quota(cmd.quota_opts.to_quota());
+        if let Some(tls) = tls {
+            payload.set_tls(tls);
+        }
         let res = send_request(&ctx, &opts, payload, node_name.clone()).await;
         *is_finished.lock().await = true;
         res
@@ -1,5 +1,7 @@
 use crate::Result;
-use miette::miette;
+use miette::{miette, IntoDiagnostic, WrapErr};
+use ockam_api::nodes::secrets::SECRET_REFERENCE_PREFIX;
+use std::path::Path;
 
 pub fn alias_parser(arg: &str) -> Result<String> {
     if arg.contains(':') {
@@ -8,3 +10,21 @@ pub fn alias_parser(arg: &str) -> Result<String> {
         Ok(arg.to_string())
     }
 }
+
+/// Read a PEM file containing certificates or a private key
+pub fn read_pem_file(path: &Path) -> miette::Result<String> {
+    std::fs::read_to_string(path)
+        .into_diagnostic()
+        .wrap_err(format!("Failed to read the PEM file {}", path.display()))
+}
+
+/// Read a PEM file, unless a reference to a node secret, `secret:NAME`, is given instead.
+/// The reference is resolved by the node, so that the PEM contents are never sent by the command
+pub fn read_pem_file_or_secret(path: &Path) -> miette::Result<String> {
+    match path.to_str() {
+        Some(reference) if reference.starts_with(SECRET_REFERENCE_PREFIX) => {
+            Ok(reference.to_string())
+        }
+        _ => read_pem_file(path),
+    }
+}
@@ -15,7 +15,9 @@ pub use fmt::*;
 use mode::*;
 use ockam_core::env::{get_env, get_env_with_default, FromString};
 use ockam_core::errcode::Kind;
+#[cfg(feature = "tui")]
 use r3bl_rs_utils_core::*;
+#[cfg(feature = "tui")]
 use r3bl_tuify::*;
 
 use crate::error::Error;
@@ -33,12 +35,34 @@ pub struct Terminal<T: TerminalWriter, WriteMode = ToStdErr> {
     stderr: T,
     quiet: bool,
     no_input: bool,
+    non_interactive: bool,
     output_format: OutputFormat,
+    progress_format: ProgressFormat,
     mode: WriteMode,
     max_width_col_count: usize,
     max_height_row_count: usize,
 }
 
+/// Display of the progress of the long-running commands
+#[derive(Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProgressFormat {
+    /// Animated spinners and log messages, when stderr is a terminal
+    #[default]
+    Spinner,
+    /// Newline-delimited JSON events written to stderr, instead of the spinners and log messages
+    Json,
+}
+
+/// Event written to stderr for each step of a long-running command, with `--progress json`
+#[derive(Debug, serde::Serialize)]
+pub struct ProgressEvent {
+    /// Name of the step of the command, or `done` when the command has finished its work
+    pub stage: String,
+    pub message: String,
+    /// Completion of the command, when it is known
+    pub percent: Option<u8>,
+}
+
 impl<T: TerminalWriter, W> Terminal<T, W> {
     pub fn is_quiet(&self) -> bool {
         self.quiet
@@ -178,6 +202,7 @@ pub struct Output {
     plain: Option<String>,
     machine: Option<String>,
     json: Option<String>,
+    jwt: Option<String>,
 }
 
 impl Output {
@@ -186,6 +211,7 @@ impl Output {
             plain: None,
             machine: None,
             json: None,
+            jwt: None,
         }
     }
 }
@@ -208,13 +234,15 @@ impl<W: TerminalWriter> Terminal<W> {
         let no_input = Self::should_disable_user_input(no_input);
         let stdout = W::stdout(no_color);
         let stderr = W::stderr(no_color);
-        let max_width_col_count = get_size().map(|it| it.col_count).unwrap_or(ch!(80)).into();
+        let max_width_col_count = Self::terminal_width();
         Self {
             stdout,
             stderr,
             quiet,
             no_input,
+            non_interactive: false,
             output_format,
+            progress_format: ProgressFormat::default(),
             mode: ToStdErr,
             max_width_col_count,
             max_height_row_count: 5,
@@ -229,8 +257,20 @@ impl<W: TerminalWriter> Terminal<W> {
         Self::new(true, false, false, OutputFormat::Plain)
     }
 
+    /// Return an error in the non-interactive mode, where no prompt is allowed.
+    /// This must be called by the commands which would otherwise use a default answer
+    pub fn fail_if_non_interactive(&self, prompt: impl AsRef<str>) -> Result<()> {
+        if self.non_interactive {
+            return Err(Error::NonInteractive {
+                prompt: prompt.as_ref().to_string(),
+            });
+        }
+        Ok(())
+    }
+
     /// Prompt the user for a confirmation.
     pub fn confirm(&self, msg: impl AsRef<str>) -> Result<ConfirmResult> {
+        self.fail_if_non_interactive(msg.as_ref())?;
         if !self.can_ask_for_user_input() {
             return Ok(ConfirmResult::NonTTY);
         }
@@ -243,6 +283,35 @@ impl<W: TerminalWriter> Terminal<W> {
         ))
     }
 
+    /// Prompt the user for a secret, without echoing it.
+    /// If `confirmation` is true, the secret must be typed twice
+    #[cfg(feature = "vault-encryption")]
+    pub fn password(&self, msg: impl AsRef<str>, confirmation: bool) -> Result<Option<String>> {
+        self.fail_if_non_interactive(msg.as_ref())?;
+        if !self.can_ask_for_user_input() {
+            return Ok(None);
+        }
+        let mut password = dialoguer::Password::new().with_prompt(msg.as_ref());
+        if confirmation {
+            password = password.with_confirmation("Confirm", "The values don't match");
+        }
+        Ok(Some(password.interact()?))
+    }
+
+    /// Prompt the user for a value.
+    /// The default value is returned when the user can't be asked
+    pub fn input(&self, msg: impl AsRef<str>, default: impl Into<String>) -> Result<String> {
+        self.fail_if_non_interactive(msg.as_ref())?;
+        let default = default.into();
+        if !self.can_ask_for_user_input() {
+            return Ok(default);
+        }
+        Ok(dialoguer::Input::<String>::new()
+            .with_prompt(msg.as_ref())
+            .default(default)
+            .interact_text()?)
+    }
+
     pub fn confirmed_with_flag_or_prompt(
         &self,
         flag: bool,
@@ -260,7 +329,9 @@ impl<W: TerminalWriter> Terminal<W> {
         }
     }
 
-    pub fn confirm_interactively(&self, header: String) -> bool {
+    #[cfg(feature = "tui")]
+    pub fn confirm_interactively(&self, header: String) -> Result<bool> {
+        self.fail_if_non_interactive(&header)?;
         let user_input = select_from_list(
             header,
             ["YES", "NO"].iter().map(|it| it.to_string()).collect(),
@@ -270,17 +341,24 @@ impl<W: TerminalWriter> Terminal<W> {
             StyleSheet::default(),
         );
 
-        match &user_input {
+        Ok(match &user_input {
             Some(it) => it.contains(&"YES".to_string()),
             None => false,
-        }
+        })
+    }
+
+    #[cfg(not(feature = "tui"))]
+    pub fn confirm_interactively(&self, header: String) -> Result<bool> {
+        Ok(matches!(self.confirm(header)?, ConfirmResult::Yes))
     }
 
     /// Returns the selected items by the user, or an empty `Vec` if the user did not select any item
     /// or if the user is not able to select an item (e.g. not a TTY, `--no-input` flag, etc.).
-    pub fn select_multiple(&self, header: String, items: Vec<String>) -> Vec<String> {
+    #[cfg(feature = "tui")]
+    pub fn select_multiple(&self, header: String, items: Vec<String>) -> Result<Vec<String>> {
+        self.fail_if_non_interactive(&header)?;
         if !self.can_ask_for_user_input() {
-            return Vec::new();
+            return Ok(Vec::new());
         }
 
         let user_selected_list = select_from_list(
@@ -292,10 +370,83 @@ impl<W: TerminalWriter> Terminal<W> {
             StyleSheet::default(),
         );
 
-        match user_selected_list {
+        Ok(match user_selected_list {
             Some(it) => it,
             None => Vec::new(),
+        })
+    }
+
+    /// Returns the selected items by the user, with a plain prompt since this build doesn't
+    /// include the interactive lists of the "tui" feature
+    #[cfg(not(feature = "tui"))]
+    pub fn select_multiple(&self, header: String, items: Vec<String>) -> Result<Vec<String>> {
+        self.fail_if_non_interactive(&header)?;
+        if !self.can_ask_for_user_input() {
+            return Ok(Vec::new());
+        }
+
+        Ok(
+            match dialoguer::MultiSelect::new()
+                .with_prompt(header)
+                .items(&items)
+                .interact_opt()
+            {
+                Ok(Some(selected)) => selected.into_iter().map(|i| items[i].clone()).collect(),
+                _ => Vec::new(),
+            },
+        )
+    }
+
+    /// Returns the item selected by the user, or `None` if the user did not select any item
+    /// or if the user is not able to select an item (e.g. not a TTY, `--no-input` flag, etc.).
+    #[cfg(feature = "tui")]
+    pub fn select(&self, header: String, items: Vec<String>) -> Result<Option<String>> {
+        self.fail_if_non_interactive(&header)?;
+        if !self.can_ask_for_user_input() {
+            return Ok(None);
+        }
+
+        Ok(select_from_list(
+            header,
+            items,
+            self.max_height_row_count,
+            self.max_width_col_count,
+            SelectionMode::Single,
+            StyleSheet::default(),
+        )
+        .and_then(|selected| selected.into_iter().next()))
+    }
+
+    /// Returns the item selected by the user, with a plain prompt since this build doesn't
+    /// include the interactive lists of the "tui" feature
+    #[cfg(not(feature = "tui"))]
+    pub fn select(&self, header: String, items: Vec<String>) -> Result<Option<String>> {
+        self.fail_if_non_interactive(&header)?;
+        if !self.can_ask_for_user_input() {
+            return Ok(None);
         }
+
+        Ok(
+            match dialoguer::Select::new()
+                .with_prompt(header)
+                .items(&items)
+                .default(0)
+                .interact_opt()
+            {
+                Ok(Some(selected)) => items.get(selected).cloned(),
+                _ => None,
+            },
+        )
+    }
+
+    #[cfg(feature = "tui")]
+    fn terminal_width() -> usize {
+        get_size().map(|it| it.col_count).unwrap_or(ch!(80)).into()
+    }
+
+    #[cfg(not(feature = "tui"))]
+    fn terminal_width() -> usize {
+        80
     }
 
     pub fn can_ask_for_user_input(&self) -> bool {
@@ -319,26 +470,45 @@ impl<W: TerminalWriter> Terminal<W> {
         clone.quiet = true;
         clone
     }
+
+    pub fn with_progress_format(mut self, progress_format: ProgressFormat) -> Self {
+        self.progress_format = progress_format;
+        self
+    }
+
+    pub fn with_non_interactive(mut self, non_interactive: bool) -> Self {
+        self.non_interactive = non_interactive;
+        self
+    }
+
+    /// Return true if the progress is reported with JSON events instead of spinners
+    pub fn is_json_progress(&self) -> bool {
+        !self.quiet && self.progress_format == ProgressFormat::Json
+    }
 }
 
 // Logging mode
 impl<W: TerminalWriter> Terminal<W, ToStdErr> {
     pub fn write(&self, msg: impl AsRef<str>) -> Result<()> {
-        if self.quiet {
+        if self.quiet || self.is_json_progress() {
             return Ok(());
         }
         self.stderr.clone().write(msg)
     }
 
     pub fn rewrite(&self, msg: impl AsRef<str>) -> Result<()> {
-        if self.quiet {
+        if self.quiet || self.is_json_progress() {
             return Ok(());
         }
         self.stderr.clone().rewrite(msg)
     }
 
     pub fn write_line(&self, msg: impl AsRef<str>) -> Result<&Self> {
-        if self.quiet || !self.stdout.is_tty() || self.output_format != OutputFormat::Plain {
+        if self.quiet
+            || self.is_json_progress()
+            || !self.stdout.is_tty()
+            || self.output_format != OutputFormat::Plain
+        {
             return Ok(self);
         }
 
@@ -399,7 +569,9 @@ impl<W: TerminalWriter> Terminal<W, ToStdErr> {
             stderr: self.stderr,
             quiet: self.quiet,
             no_input: self.no_input,
+            non_interactive: self.non_interactive,
             output_format: self.output_format,
+            progress_format: self.progress_format,
             mode: ToStdOut {
                 output: Output::new(),
             },
@@ -430,11 +602,17 @@ impl<W: TerminalWriter> Terminal<W, ToStdOut> {
         self
     }
 
+    pub fn jwt<T: Display>(mut self, msg: T) -> Self {
+        self.mode.output.jwt = Some(msg.to_string());
+        self
+    }
+
     pub fn write_line(self) -> Result<()> {
         // Check that there is at least one output format defined
         if self.mode.output.plain.is_none()
             && self.mode.output.machine.is_none()
             && self.mode.output.json.is_none()
+            && self.mode.output.jwt.is_none()
         {
             return Err(miette!("At least one output format must be defined").into());
         }
@@ -442,7 +620,9 @@ impl<W: TerminalWriter> Terminal<W, ToStdOut> {
         let plain = self.mode.output.plain.as_ref();
         let machine = self.mode.output.machine.as_ref();
         let json = self.mode.output.json.as_ref();
+        let jwt = self.mode.output.jwt.as_ref();
 
+        let formatted;
         let msg = match self.output_format {
             OutputFormat::Plain => {
                 if self.stdout.is_tty() {
@@ -467,6 +647,31 @@ impl<W: TerminalWriter> Terminal<W, ToStdOut> {
             OutputFormat::Json => {
                 json.ok_or(miette!("JSON output is not defined for this command"))?
             }
+            // The YAML document is the same as the JSON document. If there is no JSON document,
+            // the plain output is written as a YAML string
+            OutputFormat::Yaml => match (json, plain) {
+                (Some(json), _) => {
+                    formatted = self.output_format.format_json(json)?;
+                    &formatted
+                }
+                (None, Some(plain)) => {
+                    formatted = serde_yaml::to_string(plain)?;
+                    &formatted
+                }
+                _ => return Err(miette!("YAML output is not defined for this command").into()),
+            },
+            // If there is no JSON document, the table falls back to the plain output
+            OutputFormat::Table => match (json, plain) {
+                (Some(json), _) => {
+                    formatted = self.output_format.format_json(json)?;
+                    &formatted
+                }
+                (None, Some(plain)) => plain,
+                _ => return Err(miette!("Table output is not defined for this command").into()),
+            },
+            OutputFormat::Jwt => {
+                jwt.ok_or(miette!("JWT output is not defined for this command"))?
+            }
         };
         self.stdout.write_line(msg)
     }
@@ -475,7 +680,7 @@ impl<W: TerminalWriter> Terminal<W, ToStdOut> {
 // Extensions
 impl<W: TerminalWriter> Terminal<W> {
     pub fn progress_spinner(&self) -> Option<ProgressBar> {
-        if self.quiet || !self.stderr.is_tty() {
+        if self.quiet || self.non_interactive || self.is_json_progress() || !self.stderr.is_tty() {
             return None;
         }
         let ticker = [
@@ -514,6 +719,9 @@ impl<W: TerminalWriter> Terminal<W> {
         let mut i = 0;
         let progress_bar = match progress_bar {
             Some(pb) => pb,
+            None if self.is_json_progress() => {
+                return self.progress_events(output_messages, is_finished).await
+            }
             None => return Ok(()),
         };
 
@@ -536,4 +744,42 @@ impl<W: TerminalWriter> Terminal<W> {
 
         Ok(())
     }
+
+    /// Write a progress event to stderr with `--progress json`. Nothing is written otherwise
+    pub fn progress_event(
+        &self,
+        stage: &str,
+        message: impl AsRef<str>,
+        percent: Option<u8>,
+    ) -> Result<()> {
+        if !self.is_json_progress() {
+            return Ok(());
+        }
+        let message = strip_ansi_escapes::strip(message.as_ref().as_bytes());
+        let event = ProgressEvent {
+            stage: stage.to_string(),
+            message: String::from_utf8_lossy(&message).trim().to_string(),
+            percent,
+        };
+        self.stderr
+            .write_line(serde_json::to_string(&event).into_diagnostic()?)
+    }
+
+    /// Write an event for each progress message, once, until the operation is finished
+    async fn progress_events(
+        &self,
+        output_messages: &[String],
+        is_finished: &Mutex<bool>,
+    ) -> Result<()> {
+        let mut messages = output_messages.iter();
+        loop {
+            if *is_finished.lock().await {
+                return self.progress_event("finished", "", None);
+            }
+            if let Some(message) = messages.next() {
+                self.progress_event("progress", message, None)?;
+            }
+            sleep(Duration::from_millis(500)).await;
+        }
+    }
 }
@@ -66,4 +66,23 @@ mod tests {
             .write_line()
             .unwrap();
     }
+
+    #[test]
+    fn test_no_selection_in_non_interactive_mode() {
+        let sut: Terminal<TerminalStream<Term>> =
+            Terminal::new(false, false, false, OutputFormat::Plain).with_non_interactive(true);
+        let items = vec!["a".to_string(), "b".to_string()];
+        assert!(matches!(
+            sut.select("Select".to_string(), items.clone()),
+            Err(crate::Error::NonInteractive { .. })
+        ));
+        assert!(matches!(
+            sut.select_multiple("Select".to_string(), items),
+            Err(crate::Error::NonInteractive { .. })
+        ));
+        assert!(matches!(
+            sut.confirm_interactively("Confirm?".to_string()),
+            Err(crate::Error::NonInteractive { .. })
+        ));
+    }
 }
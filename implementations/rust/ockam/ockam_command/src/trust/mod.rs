@@ -0,0 +1,33 @@
+mod pin;
+
+use clap::{Args, Subcommand};
+
+use crate::trust::pin::PinCommand;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+
+/// Manage the identifiers trusted on first use
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+)]
+pub struct TrustCommand {
+    #[command(subcommand)]
+    subcommand: TrustSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum TrustSubcommand {
+    Pin(PinCommand),
+}
+
+impl TrustCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        match self.subcommand {
+            TrustSubcommand::Pin(c) => c.run(opts),
+        }
+    }
+}
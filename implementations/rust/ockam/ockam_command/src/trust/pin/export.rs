@@ -0,0 +1,40 @@
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use crate::util::local_cmd;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/export/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/export/after_long_help.txt");
+
+/// Export the pinned identifiers
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ExportCommand;
+
+impl ExportCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts) -> miette::Result<()> {
+    let pins = opts.state.trust_pins.list()?;
+    // One `<address> <identifier>` line per pin, like a `known_hosts` file
+    let lines = pins
+        .iter()
+        .map(|(name, pin)| format!("{name} {}", pin.identifier))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    opts.terminal
+        .stdout()
+        .plain(&lines)
+        .machine(&lines)
+        .json(serde_json::to_string_pretty(&pins).into_diagnostic()?)
+        .write_line()?;
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+use std::fmt::Write;
+
+use ockam::identity::Identifier;
+
+use crate::output::Output;
+use crate::terminal::OckamColor;
+use crate::util::local_cmd;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// List the pinned identifiers
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ListCommand;
+
+impl ListCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts));
+    }
+}
+
+#[derive(Serialize)]
+struct PinListOutput {
+    name: String,
+    identifier: Identifier,
+    pinned_at: u64,
+}
+
+impl Output for PinListOutput {
+    fn output(&self) -> crate::error::Result<String> {
+        let mut output = String::new();
+        writeln!(
+            output,
+            "Address {}",
+            self.name.color(OckamColor::PrimaryResource.color())
+        )?;
+        write!(
+            output,
+            "Identifier {}",
+            self.identifier
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        )?;
+        Ok(output)
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts) -> miette::Result<()> {
+    let output = opts
+        .state
+        .trust_pins
+        .list()?
+        .into_iter()
+        .map(|(name, pin)| PinListOutput {
+            name,
+            identifier: pin.identifier,
+            pinned_at: pin.pinned_at,
+        })
+        .collect::<Vec<_>>();
+
+    let plain = opts.terminal.build_list(
+        &output,
+        "Pinned identifiers",
+        "No identifiers pinned on this system.",
+    )?;
+    let json = serde_json::to_string_pretty(&output).into_diagnostic()?;
+
+    opts.terminal
+        .stdout()
+        .plain(plain)
+        .json(json)
+        .write_line()?;
+    Ok(())
+}
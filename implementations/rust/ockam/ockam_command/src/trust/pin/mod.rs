@@ -0,0 +1,41 @@
+mod export;
+mod list;
+mod remove;
+
+use clap::{Args, Subcommand};
+
+use crate::trust::pin::export::ExportCommand;
+use crate::trust::pin::list::ListCommand;
+use crate::trust::pin::remove::RemoveCommand;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+
+/// Manage the identifiers pinned for the addresses of other nodes
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+)]
+pub struct PinCommand {
+    #[command(subcommand)]
+    subcommand: PinSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum PinSubcommand {
+    List(ListCommand),
+    Remove(RemoveCommand),
+    Export(ExportCommand),
+}
+
+impl PinCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        match self.subcommand {
+            PinSubcommand::List(c) => c.run(opts),
+            PinSubcommand::Remove(c) => c.run(opts),
+            PinSubcommand::Export(c) => c.run(opts),
+        }
+    }
+}
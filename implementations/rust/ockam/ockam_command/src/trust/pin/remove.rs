@@ -0,0 +1,52 @@
+use clap::Args;
+use miette::miette;
+
+use crate::util::local_cmd;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/remove/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/remove/after_long_help.txt");
+
+/// Remove the identifier pinned for an address
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RemoveCommand {
+    /// Address of the node, as used to create the secure channel
+    pub address: String,
+
+    /// Confirm the removal without prompting
+    #[arg(display_order = 901, long, short)]
+    yes: bool,
+}
+
+impl RemoveCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: RemoveCommand) -> miette::Result<()> {
+    let address = cmd.address;
+    if opts.state.trust_pins.get(&address)?.is_none() {
+        return Err(miette!("No identifier is pinned for the address {address}"));
+    }
+    if opts.terminal.confirmed_with_flag_or_prompt(
+        cmd.yes,
+        "Are you sure you want to remove this pin? The next identifier presented by this address will be trusted",
+    )? {
+        opts.state.trust_pins.remove(&address)?;
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The identifier pinned for the address {address} has been removed"
+            ))
+            .machine(&address)
+            .json(serde_json::json!({ "address": &address }))
+            .write_line()?;
+    }
+    Ok(())
+}
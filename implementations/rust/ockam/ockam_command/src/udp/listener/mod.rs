@@ -0,0 +1,39 @@
+mod create;
+mod delete;
+mod list;
+
+pub(crate) use create::CreateCommand;
+pub(crate) use delete::DeleteCommand;
+pub(crate) use list::ListCommand;
+
+use crate::CommandGlobalOpts;
+use clap::{Args, Subcommand};
+
+/// Manage UDP Listeners
+#[derive(Args, Clone, Debug)]
+pub struct UdpListenerCommand {
+    #[command(subcommand)]
+    subcommand: UdpListenerSubCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum UdpListenerSubCommand {
+    /// Create udp listener on the selected node
+    Create(CreateCommand),
+
+    /// Delete udp listener on the selected node
+    Delete(DeleteCommand),
+
+    /// List udp listeners registered on the selected node
+    List(ListCommand),
+}
+
+impl UdpListenerCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            UdpListenerSubCommand::Create(c) => c.run(options),
+            UdpListenerSubCommand::Delete(c) => c.run(options),
+            UdpListenerSubCommand::List(c) => c.run(options),
+        }
+    }
+}
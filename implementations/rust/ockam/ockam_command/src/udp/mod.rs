@@ -0,0 +1,33 @@
+pub mod listener;
+pub mod puncher;
+
+use clap::{Args, Subcommand};
+
+use crate::{docs, CommandGlobalOpts};
+use listener::UdpListenerCommand;
+use puncher::UdpPuncherCommand;
+
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+
+/// Manage UDP Listeners and Hole Punchers
+#[derive(Args, Clone, Debug)]
+#[command(before_help = docs::before_help(PREVIEW_TAG))]
+pub struct UdpCommand {
+    #[command(subcommand)]
+    subcommand: UdpSubCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum UdpSubCommand {
+    Listener(UdpListenerCommand),
+    Puncher(UdpPuncherCommand),
+}
+
+impl UdpCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            UdpSubCommand::Listener(c) => c.run(options),
+            UdpSubCommand::Puncher(c) => c.run(options),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam_api::nodes::models::transport::{CreateUdpPuncher, UdpPuncherStatus};
+use ockam_api::nodes::BackgroundNode;
+use ockam_core::api::Request;
+use ockam_multiaddr::MultiAddr;
+use ockam_node::Context;
+
+use crate::node::{get_node_name, initialize_node_if_default, NodeOpts};
+use crate::terminal::OckamColor;
+use crate::util::{node_rpc, parse_node_name};
+use crate::{docs, fmt_log, fmt_ok, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create a UDP hole puncher, to reach a node behind a NAT.
+///
+/// The puncher and its peer puncher, on the other node, meet via a rendezvous service,
+/// started with `ockam service start rendezvous` on a node with a public UDP listener
+#[derive(Args, Clone, Debug)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct CreateCommand {
+    #[command(flatten)]
+    node_opts: NodeOpts,
+
+    /// Name of this puncher
+    pub name: String,
+
+    /// Name of the puncher on the other node
+    #[arg(long, value_name = "PEER_NAME")]
+    pub peer: String,
+
+    /// Address of the rendezvous service
+    #[arg(long, value_name = "ROUTE")]
+    pub rendezvous: MultiAddr,
+}
+
+impl CreateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_node_if_default(&opts, &self.node_opts.at_node);
+        node_rpc(run_impl, (opts, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, CreateCommand),
+) -> miette::Result<()> {
+    let node_name = get_node_name(&opts.state, &cmd.node_opts.at_node);
+    let node_name = parse_node_name(&node_name)?;
+    let node = BackgroundNode::create(&ctx, &opts.state, &node_name).await?;
+    let puncher: UdpPuncherStatus = node
+        .ask(
+            &ctx,
+            Request::post("/node/udp/puncher").body(CreateUdpPuncher::new(
+                cmd.name,
+                cmd.peer,
+                cmd.rendezvous.to_string(),
+            )),
+        )
+        .await?;
+
+    let multiaddr = puncher.multiaddr().into_diagnostic()?;
+    opts.terminal
+        .stdout()
+        .plain(
+            fmt_ok!(
+                "Udp puncher {} created! You can send messages to the node of {} via this route:\n",
+                puncher
+                    .name
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color()),
+                puncher
+                    .peer_name
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color())
+            ) + &fmt_log!("{multiaddr}"),
+        )
+        .machine(multiaddr.to_string())
+        .json(serde_json::to_value(&puncher).into_diagnostic()?)
+        .write_line()?;
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::fmt::Write;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+use tokio::sync::Mutex;
+use tokio::try_join;
+
+use ockam::Context;
+use ockam_api::cli_state::StateDirTrait;
+use ockam_api::nodes::models::transport::{UdpPuncherList, UdpPuncherStatus};
+use ockam_api::nodes::BackgroundNode;
+
+use crate::node::{get_node_name, initialize_node_if_default, NodeOpts};
+use crate::output::Output;
+use crate::terminal::OckamColor;
+use crate::util::{api, node_rpc, parse_node_name};
+use crate::{docs, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// List UDP hole punchers
+#[derive(Args, Clone, Debug)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct ListCommand {
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+impl ListCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        initialize_node_if_default(&opts, &self.node_opts.at_node);
+        node_rpc(rpc, (opts, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, ListCommand)) -> miette::Result<()> {
+    run_impl(&ctx, opts, cmd).await
+}
+
+async fn run_impl(ctx: &Context, opts: CommandGlobalOpts, cmd: ListCommand) -> miette::Result<()> {
+    let node_name = get_node_name(&opts.state, &cmd.node_opts.at_node);
+    let node_name = parse_node_name(&node_name)?;
+
+    if !opts.state.nodes.get(&node_name)?.is_running() {
+        return Err(miette!("The node '{}' is not running", node_name));
+    }
+
+    let node = BackgroundNode::create(ctx, &opts.state, &node_name).await?;
+    let is_finished: Mutex<bool> = Mutex::new(false);
+
+    let get_punchers = async {
+        let punchers: UdpPuncherList = node.ask(ctx, api::list_udp_punchers()).await?;
+        *is_finished.lock().await = true;
+        Ok(punchers)
+    };
+
+    let output_messages = vec![format!(
+        "Listing UDP Hole Punchers on {}...\n",
+        node_name
+            .to_string()
+            .color(OckamColor::PrimaryResource.color())
+    )];
+
+    let progress_output = opts
+        .terminal
+        .progress_output(&output_messages, &is_finished);
+
+    let (punchers, _) = try_join!(get_punchers, progress_output)?;
+
+    let list = opts.terminal.build_list(
+        &punchers.list,
+        &format!("UDP Hole Punchers on {}", node_name),
+        &format!(
+            "No UDP Hole Punchers found on {}",
+            node_name
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        ),
+    )?;
+    opts.terminal
+        .stdout()
+        .plain(list)
+        .json(serde_json::json!(&punchers.list))
+        .write_line()?;
+    Ok(())
+}
+
+impl Output for UdpPuncherStatus {
+    fn output(&self) -> crate::Result<String> {
+        let mut output = String::new();
+        writeln!(
+            output,
+            "Puncher {}",
+            self.name
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        )?;
+        writeln!(
+            output,
+            "Peer {}",
+            self.peer_name
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        )?;
+        writeln!(
+            output,
+            "Rendezvous {}",
+            self.rendezvous
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        )?;
+        write!(
+            output,
+            "Worker {}",
+            self.worker_addr
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        )?;
+        Ok(output)
+    }
+}
@@ -0,0 +1,33 @@
+mod create;
+mod list;
+
+pub(crate) use create::CreateCommand;
+pub(crate) use list::ListCommand;
+
+use crate::CommandGlobalOpts;
+use clap::{Args, Subcommand};
+
+/// Manage UDP Hole Punchers
+#[derive(Args, Clone, Debug)]
+pub struct UdpPuncherCommand {
+    #[command(subcommand)]
+    subcommand: UdpPuncherSubCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum UdpPuncherSubCommand {
+    /// Create a udp hole puncher on the selected node
+    Create(CreateCommand),
+
+    /// List udp hole punchers on the selected node
+    List(ListCommand),
+}
+
+impl UdpPuncherCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            UdpPuncherSubCommand::Create(c) => c.run(options),
+            UdpPuncherSubCommand::List(c) => c.run(options),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! API shim to make it nicer to interact with the ockam messaging API
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use clap::Args;
 use miette::miette;
@@ -11,9 +12,11 @@ use regex::Regex;
 use ockam::identity::Identifier;
 use ockam_api::cli_state::CliState;
 use ockam_api::nodes::models::flow_controls::AddConsumer;
+use ockam_api::nodes::models::quota::SessionQuota;
 use ockam_api::nodes::models::services::{
     StartAuthenticatedServiceRequest, StartAuthenticatorRequest, StartCredentialsService,
-    StartHopServiceRequest, StartOktaIdentityProviderRequest,
+    StartExecServiceRequest, StartHopServiceRequest, StartOktaIdentityProviderRequest,
+    StartProvisionerServiceRequest, StartRendezvousServiceRequest,
 };
 use ockam_api::nodes::*;
 use ockam_api::trust_context::TrustContextConfigBuilder;
@@ -25,6 +28,7 @@ use ockam_core::Address;
 use ockam_multiaddr::MultiAddr;
 
 use crate::service::config::OktaIdentityProviderConfig;
+use crate::util::duration::duration_parser;
 use crate::Result;
 
 ////////////// !== generators
@@ -39,6 +43,18 @@ pub(crate) fn list_tcp_listeners() -> Request<()> {
     Request::get("/node/tcp/listener")
 }
 
+/// Construct a request to query node udp listeners
+#[cfg(feature = "udp")]
+pub(crate) fn list_udp_listeners() -> Request<()> {
+    Request::get("/node/udp/listener")
+}
+
+/// Construct a request to query node udp hole punchers
+#[cfg(feature = "udp")]
+pub(crate) fn list_udp_punchers() -> Request<()> {
+    Request::get("/node/udp/puncher")
+}
+
 /// Construct a request to create node tcp connection
 pub(crate) fn create_tcp_connection(
     cmd: &crate::tcp::connection::CreateCommand,
@@ -63,9 +79,9 @@ pub(crate) fn list_outlets() -> Request<()> {
     Request::get("/node/outlet")
 }
 
-/// Construct a request builder to list all secure channels on the given node
-pub(crate) fn list_secure_channels() -> Request<()> {
-    Request::get("/node/secure_channel")
+/// Construct a request builder to get the live state of all secure channels on the given node
+pub(crate) fn list_secure_channels_state() -> Request<()> {
+    Request::get("/node/secure_channel_state")
 }
 
 /// Construct a request builder to list all workers on the given node
@@ -73,6 +89,21 @@ pub(crate) fn list_workers() -> Request<()> {
     Request::get("/node/workers")
 }
 
+/// Construct a request builder to get the statistics of the given node
+pub(crate) fn node_stats() -> Request<()> {
+    Request::get("/node/stats")
+}
+
+/// Construct a request builder to get the heap statistics of the given node
+pub(crate) fn node_heap_stats() -> Request<()> {
+    Request::get("/node/debug/heap")
+}
+
+/// Construct a request builder to reload the trust context of the given node
+pub(crate) fn reload_node() -> Request<()> {
+    Request::post("/node/reload")
+}
+
 pub(crate) fn delete_secure_channel(
     addr: &Address,
 ) -> Request<models::secure_channel::DeleteSecureChannelRequest> {
@@ -133,6 +164,32 @@ pub(crate) fn start_hop_service(addr: &str) -> Request<StartHopServiceRequest> {
     Request::post(node_service(DefaultAddress::HOP_SERVICE)).body(payload)
 }
 
+/// Construct a request to start a UDP Rendezvous Service
+pub(crate) fn start_rendezvous_service(addr: &str) -> Request<StartRendezvousServiceRequest> {
+    let payload = StartRendezvousServiceRequest::new(addr);
+    Request::post(node_service(DefaultAddress::RENDEZVOUS_SERVICE)).body(payload)
+}
+
+/// Construct a request to start an Exec Service
+pub(crate) fn start_exec_service(
+    addr: &str,
+    allowed_commands: Vec<String>,
+    timeout: Option<Duration>,
+) -> Request<StartExecServiceRequest> {
+    let payload = StartExecServiceRequest::new(addr, allowed_commands, timeout);
+    Request::post(node_service(DefaultAddress::EXEC_SERVICE)).body(payload)
+}
+
+/// Construct a request to start a Provisioner Service
+pub(crate) fn start_provisioner_service(
+    addr: &str,
+    attribute: &str,
+    targets: Vec<String>,
+) -> Request<StartProvisionerServiceRequest> {
+    let payload = StartProvisionerServiceRequest::new(addr, attribute, targets);
+    Request::post(node_service(DefaultAddress::PROVISIONER_SERVICE)).body(payload)
+}
+
 /// Construct a request to start an Authenticated Service
 pub(crate) fn start_authenticated_service(addr: &str) -> Request<StartAuthenticatedServiceRequest> {
     let payload = StartAuthenticatedServiceRequest::new(addr);
@@ -195,6 +252,27 @@ pub(crate) fn parse_create_secure_channel_listener_response(resp: &[u8]) -> Resu
 
 ////////////// !== share CLI args
 
+#[derive(Clone, Debug, Args, Default)]
+pub struct QuotaOpts {
+    /// Close each connection after it exchanged this number of bytes, in both directions
+    #[arg(long, value_name = "BYTES")]
+    pub max_bytes: Option<u64>,
+
+    /// Close each connection after it exchanged this number of messages, in both directions
+    #[arg(long, value_name = "MESSAGES")]
+    pub max_messages: Option<u64>,
+
+    /// Close each connection once it has been open for this duration, for example `30m`
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    pub max_duration: Option<Duration>,
+}
+
+impl QuotaOpts {
+    pub fn to_quota(&self) -> SessionQuota {
+        SessionQuota::new(self.max_bytes, self.max_messages, self.max_duration)
+    }
+}
+
 #[derive(Clone, Debug, Args)]
 pub struct CloudOpts {
     /// Run the command as the given identity name
@@ -11,6 +11,7 @@ use tracing::error;
 use ockam::{Address, Context, NodeBuilder};
 use ockam_api::cli_state::{CliState, StateDirTrait, StateItemTrait};
 use ockam_api::config::lookup::{InternetAddress, LookupMeta};
+use ockam_api::port_range::PortRange;
 use ockam_core::DenyAll;
 use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Project, Space, Tcp};
 use ockam_multiaddr::{
@@ -55,8 +56,9 @@ where
             let res = f(ctx, a).await;
             if let Err(e) = res {
                 error!(%e, "Failed to run command");
+                let e = crate::Error::from_report(e);
                 eprintln!("{:?}", e);
-                std::process::exit(exitcode::SOFTWARE);
+                std::process::exit(crate::Error::exit_code(&e));
             }
             Ok(())
         },
@@ -147,6 +149,14 @@ pub fn find_available_port() -> Result<u16> {
     Ok(address.port())
 }
 
+/// Return the first port of the range which is available on localhost
+pub fn find_available_port_in_range(range: &PortRange) -> Result<u16> {
+    range
+        .ports()
+        .find(|port| TcpListener::bind(("127.0.0.1", *port)).is_ok())
+        .ok_or_else(|| miette!("There is no available port in the range {range}").into())
+}
+
 #[allow(unused)]
 pub fn print_path(p: &Path) -> String {
     p.to_str().unwrap_or("<unprintable>").to_string()
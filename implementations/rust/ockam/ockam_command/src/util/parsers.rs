@@ -3,9 +3,10 @@ use std::str::FromStr;
 
 use miette::miette;
 
-use ockam::identity::Identifier;
+use ockam::identity::{Identifier, RekeyPolicy};
 use ockam_transport_tcp::resolve_peer;
 
+use crate::util::duration::duration_parser;
 use crate::Result;
 
 /// Helper function for parsing a socket from user input
@@ -30,9 +31,68 @@ pub(crate) fn identity_identifier_parser(input: &str) -> Result<Identifier> {
     Identifier::from_str(input).map_err(|_| miette!("Invalid identity identifier: {input}").into())
 }
 
+/// Helper fn for parsing a rekey policy from user input, as a number of bytes and/or a duration
+/// separated by `/`. For example `1GB/1h`, `512MiB` or `30m`
+pub(crate) fn rekey_policy_parser(input: &str) -> Result<RekeyPolicy> {
+    let mut rekey_policy = RekeyPolicy::default();
+    for part in input.split('/') {
+        let part = part.trim();
+        if part.ends_with('B') {
+            if rekey_policy.after_bytes.is_some() {
+                return Err(miette!("The number of bytes is set twice in {input}").into());
+            }
+            rekey_policy = rekey_policy.with_after_bytes(bytes_parser(part)?);
+        } else {
+            if rekey_policy.after_duration.is_some() {
+                return Err(miette!("The duration is set twice in {input}").into());
+            }
+            let duration =
+                duration_parser(part).map_err(|_| miette!("Invalid rekey policy: {input}"))?;
+            rekey_policy = rekey_policy.with_after_duration(duration);
+        }
+    }
+    Ok(rekey_policy)
+}
+
+/// Helper fn for parsing an attribute name and its value, with the format `NAME=VALUE`
+pub(crate) fn attribute_parser(input: &str) -> Result<(String, String)> {
+    match input.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(miette!("The attribute {input} must have the format NAME=VALUE").into()),
+    }
+}
+
+/// Parse a number of bytes with a unit, for example `10KB` or `1GiB`
+fn bytes_parser(input: &str) -> Result<u64> {
+    let units: [(&str, u64); 8] = [
+        ("KiB", 1 << 10),
+        ("MiB", 1 << 20),
+        ("GiB", 1 << 30),
+        ("KB", 1_000),
+        ("MB", 1_000_000),
+        ("GB", 1_000_000_000),
+        ("TB", 1_000_000_000_000),
+        ("B", 1),
+    ];
+    let (number, multiplier) = units
+        .iter()
+        .find_map(|(unit, multiplier)| {
+            input
+                .strip_suffix(unit)
+                .map(|number| (number.trim(), *multiplier))
+        })
+        .ok_or_else(|| miette!("Invalid number of bytes: {input}"))?;
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| miette!("Invalid number of bytes: {input}").into())
+}
+
 #[cfg(test)]
 mod tests {
     use std::net::Ipv6Addr;
+    use std::time::Duration;
 
     use ockam_core::compat::net::{IpAddr, Ipv4Addr, SocketAddr};
 
@@ -71,6 +131,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ipv6_unspecified_and_port() {
+        let input = "[::]:4000";
+        let result = socket_addr_parser(input);
+        assert!(result.is_ok());
+        assert_eq!(
+            SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 4000),
+            result.unwrap()
+        );
+    }
+
     #[test]
     fn test_localhost() {
         let input = "localhost:9999";
@@ -97,4 +168,23 @@ mod tests {
         let invalid_input = "192,166,0.1:9999";
         assert!(socket_addr_parser(invalid_input).is_err());
     }
+
+    #[test]
+    fn test_rekey_policy() {
+        let rekey_policy = rekey_policy_parser("1GB/1h").unwrap();
+        assert_eq!(rekey_policy.after_bytes, Some(1_000_000_000));
+        assert_eq!(rekey_policy.after_duration, Some(Duration::from_secs(3600)));
+
+        let rekey_policy = rekey_policy_parser("512MiB").unwrap();
+        assert_eq!(rekey_policy.after_bytes, Some(512 * 1024 * 1024));
+        assert_eq!(rekey_policy.after_duration, None);
+
+        let rekey_policy = rekey_policy_parser("30m").unwrap();
+        assert_eq!(rekey_policy.after_bytes, None);
+        assert_eq!(rekey_policy.after_duration, Some(Duration::from_secs(1800)));
+
+        assert!(rekey_policy_parser("1GB/2GB").is_err());
+        assert!(rekey_policy_parser("1XB").is_err());
+        assert!(rekey_policy_parser("1h/").is_err());
+    }
 }
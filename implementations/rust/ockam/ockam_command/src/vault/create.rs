@@ -1,5 +1,8 @@
-use clap::Args;
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
 use colorful::Colorful;
+use miette::miette;
 
 use ockam::Context;
 use ockam_api::cli_state;
@@ -22,8 +25,38 @@ pub struct CreateCommand {
     #[arg(hide_default_value = true, default_value_t = random_name())]
     name: String,
 
+    /// Type of the vault
+    #[arg(long = "type", value_enum, default_value_t = VaultType::Software, conflicts_with = "aws_kms")]
+    vault_type: VaultType,
+
+    /// Use an AWS KMS vault. Same as `--type aws-kms`
     #[arg(long, default_value = "false")]
     aws_kms: bool,
+
+    /// Path of the PKCS#11 module of the token, for a `pkcs11` vault
+    #[arg(long, value_name = "PATH")]
+    module: Option<PathBuf>,
+
+    /// Label of the PKCS#11 token storing the keys. The first token found is used by default
+    #[arg(long, value_name = "LABEL", requires = "module")]
+    token_label: Option<String>,
+
+    /// Path of the socket of the agent, for an `ssh-agent` vault. The agent referenced by the
+    /// SSH_AUTH_SOCK environment variable is used by default
+    #[arg(long, value_name = "PATH")]
+    socket: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum VaultType {
+    /// Keys are stored in a file of the vault
+    Software,
+    /// Keys are stored in AWS KMS
+    AwsKms,
+    /// Keys are stored in a PKCS#11 token, for example a YubiKey or an HSM
+    Pkcs11,
+    /// Ed25519 keys are stored in an ssh-agent, which computes the signatures
+    SshAgent,
 }
 
 impl CreateCommand {
@@ -41,8 +74,36 @@ async fn run_impl(
     opts: CommandGlobalOpts,
     cmd: CreateCommand,
 ) -> miette::Result<()> {
-    let CreateCommand { name, aws_kms, .. } = cmd;
-    let config = cli_state::VaultConfig::new(aws_kms)?;
+    let CreateCommand {
+        name,
+        vault_type,
+        aws_kms,
+        module,
+        token_label,
+        socket,
+    } = cmd;
+    if socket.is_some() && vault_type != VaultType::SshAgent {
+        return Err(miette!(
+            "An agent socket can only be used with an ssh-agent vault"
+        ));
+    }
+    let config = match (vault_type, module) {
+        (VaultType::Pkcs11, Some(module)) => cli_state::VaultConfig::pkcs11(module, token_label)?,
+        (VaultType::Pkcs11, None) => {
+            return Err(miette!(
+                "A PKCS#11 module is required to create a pkcs11 vault"
+            ))
+        }
+        (VaultType::SshAgent, None) => cli_state::VaultConfig::ssh_agent(socket)?,
+        (_, Some(_)) => {
+            return Err(miette!(
+                "A PKCS#11 module can only be used with a pkcs11 vault"
+            ))
+        }
+        (vault_type, None) => {
+            cli_state::VaultConfig::new(aws_kms || vault_type == VaultType::AwsKms)?
+        }
+    };
     if opts.state.vaults.is_empty()? {
         opts.terminal.write_line(&fmt_info!(
             "This is the first vault to be created in this environment. It will be set as the default vault"
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam_api::cli_state::traits::StateDirTrait;
+
+use crate::util::local_cmd;
+use crate::vault::get_passphrase;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/export/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/export/after_long_help.txt");
+
+/// Export a vault to a file encrypted with a passphrase
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ExportCommand {
+    /// Name of the vault
+    name: String,
+
+    /// Path of the encrypted file to create
+    #[arg(long, value_name = "PATH")]
+    out: PathBuf,
+}
+
+impl ExportCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: ExportCommand) -> miette::Result<()> {
+    let vault = opts.state.vaults.get(&cmd.name)?;
+    let passphrase = get_passphrase(&opts, true)?;
+    let archive = vault.export(&passphrase)?;
+    std::fs::write(&cmd.out, archive).into_diagnostic()?;
+
+    let out = cmd.out.display().to_string();
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!("Vault '{}' has been exported to {out}", cmd.name))
+        .machine(&out)
+        .json(serde_json::json!({ "name": &cmd.name, "path": &out }))
+        .write_line()?;
+    Ok(())
+}
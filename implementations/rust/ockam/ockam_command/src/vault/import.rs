@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+
+use crate::util::node_rpc;
+use crate::vault::get_passphrase;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/import/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/import/after_long_help.txt");
+
+/// Import a vault from a file created with `ockam vault export`
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ImportCommand {
+    /// Name of the vault to create
+    name: String,
+
+    /// Path of the encrypted file
+    #[arg(long, value_name = "PATH")]
+    file: PathBuf,
+}
+
+impl ImportCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self));
+    }
+}
+
+async fn rpc(_ctx: Context, (opts, cmd): (CommandGlobalOpts, ImportCommand)) -> miette::Result<()> {
+    run_impl(opts, cmd).await
+}
+
+async fn run_impl(opts: CommandGlobalOpts, cmd: ImportCommand) -> miette::Result<()> {
+    let archive = std::fs::read(&cmd.file).into_diagnostic()?;
+    let passphrase = get_passphrase(&opts, false)?;
+    opts.state
+        .vaults
+        .import(&cmd.name, &archive, &passphrase)
+        .await?;
+
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!("Vault '{}' has been imported", cmd.name))
+        .machine(&cmd.name)
+        .json(serde_json::json!({ "name": &cmd.name }))
+        .write_line()?;
+    Ok(())
+}
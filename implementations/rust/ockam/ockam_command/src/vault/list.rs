@@ -1,13 +1,14 @@
 use clap::Args;
 use colorful::Colorful;
-use miette::IntoDiagnostic;
+use serde::Serialize;
+use serde_json::Value;
 use std::fmt::Write;
 
 use ockam_api::cli_state::traits::StateDirTrait;
 use ockam_api::cli_state::StateItemTrait;
 use ockam_api::cli_state::VaultConfig;
 
-use crate::output::Output;
+use crate::output::{schema_json_list, to_schema_value, unsupported_schema, Output, SchemaOutput};
 use crate::terminal::OckamColor;
 use crate::util::local_cmd;
 use crate::{docs, CommandGlobalOpts};
@@ -31,14 +32,25 @@ impl ListCommand {
     }
 }
 
-#[derive(serde::Serialize)]
 pub struct VaultListOutput {
     name: String,
-    #[serde(flatten)]
     config: VaultConfig,
     is_default: bool,
 }
 
+/// Document of a vault in the version 1 of the output schema
+#[derive(Serialize)]
+struct VaultOutputV1<'a> {
+    /// Name of the vault
+    name: &'a str,
+    /// Kind of storage of the keys: OCKAM, AWS KMS, PKCS#11 or SSH AGENT
+    kind: &'a str,
+    /// True if the keys are stored in AWS KMS
+    aws_kms: bool,
+    /// True if the vault is the default vault
+    is_default: bool,
+}
+
 impl VaultListOutput {
     pub fn new(name: String, config: VaultConfig, is_default: bool) -> Self {
         Self {
@@ -49,6 +61,20 @@ impl VaultListOutput {
     }
 }
 
+impl SchemaOutput for VaultListOutput {
+    fn schema_output(&self, version: u8) -> crate::error::Result<Value> {
+        match version {
+            1 => to_schema_value(VaultOutputV1 {
+                name: &self.name,
+                kind: self.config.kind(),
+                aws_kms: self.config.is_aws(),
+                is_default: self.is_default,
+            }),
+            _ => Err(unsupported_schema(version)),
+        }
+    }
+}
+
 impl Output for VaultListOutput {
     fn output(&self) -> crate::error::Result<String> {
         let mut output = String::new();
@@ -63,12 +89,10 @@ impl Output for VaultListOutput {
         write!(
             output,
             "Type {}",
-            match self.config.is_aws() {
-                true => "AWS KMS",
-                false => "OCKAM",
-            }
-            .to_string()
-            .color(OckamColor::PrimaryResource.color())
+            self.config
+                .kind()
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
         )?;
         Ok(output)
     }
@@ -92,7 +116,7 @@ fn run_impl(opts: CommandGlobalOpts) -> miette::Result<()> {
         .terminal
         .build_list(&output, "Vaults", "No vaults found on this system.")?;
 
-    let json = serde_json::to_string_pretty(&output).into_diagnostic()?;
+    let json = schema_json_list(&output, opts.global_args.output_schema())?;
 
     opts.terminal
         .stdout()
@@ -0,0 +1,54 @@
+use clap::Args;
+use colorful::Colorful;
+
+use ockam_api::cli_state::traits::StateDirTrait;
+
+use crate::util::local_cmd;
+use crate::vault::get_passphrase;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/lock/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/lock/after_long_help.txt");
+
+/// Encrypt the secrets of a vault at rest
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct LockCommand {
+    /// Name of the vault
+    name: String,
+
+    /// Encrypt the secrets with a random passphrase stored in the OS keychain,
+    /// instead of a passphrase typed by the user
+    #[cfg(feature = "keychain")]
+    #[arg(long)]
+    keychain: bool,
+}
+
+impl LockCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: LockCommand) -> miette::Result<()> {
+    let vault = opts.state.vaults.get(&cmd.name)?;
+    #[cfg(feature = "keychain")]
+    let vault = if cmd.keychain {
+        vault.lock_with_keychain()?
+    } else {
+        vault.lock_with_passphrase(&get_passphrase(&opts, true)?)?
+    };
+    #[cfg(not(feature = "keychain"))]
+    let vault = vault.lock_with_passphrase(&get_passphrase(&opts, true)?)?;
+
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!("Vault '{}' has been locked", cmd.name))
+        .machine(&cmd.name)
+        .json(serde_json::json!({ "name": &cmd.name, "locked": vault.is_locked() }))
+        .write_line()?;
+    Ok(())
+}
@@ -1,19 +1,39 @@
 mod create;
 mod default;
 mod delete;
+#[cfg(feature = "vault-encryption")]
+mod export;
+#[cfg(feature = "vault-encryption")]
+mod import;
 mod list;
+#[cfg(feature = "vault-encryption")]
+mod lock;
 mod show;
+#[cfg(feature = "vault-encryption")]
+mod unlock;
 
 use crate::vault::create::CreateCommand;
 use crate::vault::default::DefaultCommand;
 use crate::vault::delete::DeleteCommand;
+#[cfg(feature = "vault-encryption")]
+use crate::vault::export::ExportCommand;
+#[cfg(feature = "vault-encryption")]
+use crate::vault::import::ImportCommand;
 use crate::vault::list::ListCommand;
+#[cfg(feature = "vault-encryption")]
+use crate::vault::lock::LockCommand;
 use crate::vault::show::ShowCommand;
+#[cfg(feature = "vault-encryption")]
+use crate::vault::unlock::UnlockCommand;
 use crate::{docs, CommandGlobalOpts};
 
 use clap::{Args, Subcommand};
+#[cfg(feature = "vault-encryption")]
+use miette::miette;
 use ockam_api::cli_state::traits::StateDirTrait;
 use ockam_api::cli_state::CliState;
+#[cfg(feature = "vault-encryption")]
+use ockam_api::cli_state::VAULT_PASSPHRASE_ENV;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 
@@ -26,7 +46,7 @@ const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 )]
 pub struct VaultCommand {
     #[command(subcommand)]
-    subcommand: VaultSubcommand,
+    pub subcommand: VaultSubcommand,
 }
 
 #[derive(Clone, Debug, Subcommand)]
@@ -36,6 +56,14 @@ pub enum VaultSubcommand {
     Delete(DeleteCommand),
     List(ListCommand),
     Default(DefaultCommand),
+    #[cfg(feature = "vault-encryption")]
+    Export(ExportCommand),
+    #[cfg(feature = "vault-encryption")]
+    Import(ImportCommand),
+    #[cfg(feature = "vault-encryption")]
+    Lock(LockCommand),
+    #[cfg(feature = "vault-encryption")]
+    Unlock(UnlockCommand),
 }
 
 impl VaultCommand {
@@ -46,6 +74,14 @@ impl VaultCommand {
             VaultSubcommand::List(cmd) => cmd.run(opts),
             VaultSubcommand::Delete(cmd) => cmd.run(opts),
             VaultSubcommand::Default(cmd) => cmd.run(opts),
+            #[cfg(feature = "vault-encryption")]
+            VaultSubcommand::Export(cmd) => cmd.run(opts),
+            #[cfg(feature = "vault-encryption")]
+            VaultSubcommand::Import(cmd) => cmd.run(opts),
+            #[cfg(feature = "vault-encryption")]
+            VaultSubcommand::Lock(cmd) => cmd.run(opts),
+            #[cfg(feature = "vault-encryption")]
+            VaultSubcommand::Unlock(cmd) => cmd.run(opts),
         }
     }
 }
@@ -56,3 +92,22 @@ pub fn default_vault_name(cli_state: &CliState) -> String {
         .default()
         .map_or("default".to_string(), |v| v.name().to_string())
 }
+
+/// Return the passphrase protecting an exported or locked vault, read from the environment
+/// or typed by the user
+#[cfg(feature = "vault-encryption")]
+fn get_passphrase(opts: &CommandGlobalOpts, confirmation: bool) -> miette::Result<String> {
+    let passphrase = match std::env::var(VAULT_PASSPHRASE_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) => opts
+            .terminal
+            .password("Passphrase", confirmation)?
+            .ok_or_else(|| {
+                miette!("The passphrase must be set with the {VAULT_PASSPHRASE_ENV} environment variable")
+            })?,
+    };
+    if passphrase.is_empty() {
+        return Err(miette!("The passphrase can't be empty"));
+    }
+    Ok(passphrase)
+}
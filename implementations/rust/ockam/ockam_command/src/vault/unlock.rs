@@ -0,0 +1,39 @@
+use clap::Args;
+use colorful::Colorful;
+
+use ockam_api::cli_state::traits::StateDirTrait;
+
+use crate::util::local_cmd;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/unlock/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/unlock/after_long_help.txt");
+
+/// Store the secrets of a locked vault in plain text again
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct UnlockCommand {
+    /// Name of the vault
+    name: String,
+}
+
+impl UnlockCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        local_cmd(run_impl(opts, self));
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: UnlockCommand) -> miette::Result<()> {
+    let vault = opts.state.vaults.get(&cmd.name)?.unlock()?;
+
+    opts.terminal
+        .stdout()
+        .plain(fmt_ok!("Vault '{}' has been unlocked", cmd.name))
+        .machine(&cmd.name)
+        .json(serde_json::json!({ "name": &cmd.name, "locked": vault.is_locked() }))
+        .write_line()?;
+    Ok(())
+}
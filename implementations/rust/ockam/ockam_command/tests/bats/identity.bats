@@ -63,4 +63,34 @@ teardown() {
   run_success "$OCKAM" identity create "${i}"
   run_success "$OCKAM" identity default "${i}"
   assert_output "${i}"
+
+  # Without a JSON output, the YAML output is the plain output as a YAML string
+  run_success "$OCKAM" identity default --output yaml
+  assert_output --partial "default identity is"
+  assert_output --partial "${i}"
+}
+
+@test "identity - enrollment status of an identity which is not enrolled" {
+  i=$(random_str)
+  run_success "$OCKAM" identity create "${i}"
+  run_success "$OCKAM" enroll status --identity "${i}" --output json
+  assert_output --partial "\"complete\": false"
+  assert_output --partial "\"name\": \"Identity created\""
+  assert_output --partial "\"status\": \"incomplete\""
+}
+
+@test "identity - rotate the key of an identity" {
+  i=$(random_str)
+  run_success "$OCKAM" identity create "${i}"
+  run_success "$OCKAM" identity show "${i}"
+  identifier=$output
+
+  run_success "$OCKAM" identity rotate "${i}" --local-only --output json
+  assert_output --partial "\"changes\": 2"
+
+  # The identifier is kept and the change history has a new change
+  run_success "$OCKAM" identity show "${i}"
+  assert_output "${identifier}"
+  run_success "$OCKAM" identity show "${i}" --full
+  assert_output --partial "Change[1]:"
 }
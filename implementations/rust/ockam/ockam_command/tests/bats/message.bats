@@ -33,6 +33,17 @@ teardown() {
   assert_output "$(to_uppercase "$msg")"
 }
 
+@test "message - ping and trace a route" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+
+  run_success "$OCKAM" message ping /node/n2 --from n1 --count 3 --timeout 5s
+  assert_output --partial "3 pings sent, 3 received, 0% lost"
+
+  run_success "$OCKAM" message trace /node/n2/secure/api --from n1 --output json
+  assert_output --partial "\"address\""
+}
+
 @test "message - secure-channels with authorized identifiers" {
   run_success "$OCKAM" vault create v1
   run_success "$OCKAM" identity create i1 --vault v1
@@ -51,3 +62,26 @@ teardown() {
               | $OCKAM message send $msg --from /node/n1 --to -/service/echo"
   assert_output "$msg"
 }
+
+@test "message - chat between identities, directly and through a mailbox" {
+  run_success "$OCKAM" node create n1
+  n1_identifier=$($OCKAM identity show)
+
+  # Deliver a message to the identity of the node
+  msg=$(random_str)
+  run_success "$OCKAM" chat send "$msg" --timeout 5 --to /node/n1/secure/api/service/chat
+  assert_output "$n1_identifier"
+  run_success "$OCKAM" chat receive --at n1
+  assert_output "$msg"
+
+  # Store a message for another identity, which fetches it over its own secure channel
+  run_success "$OCKAM" identity create i2
+  i2_identifier=$($OCKAM identity show i2)
+  msg=$(random_str)
+  run_success "$OCKAM" chat send "$msg" --timeout 5 --to /node/n1/secure/api/service/chat --for "$i2_identifier"
+  assert_output "$i2_identifier"
+  run_success "$OCKAM" chat receive --identity i2 --from /node/n1/secure/api/service/chat
+  assert_output "$msg"
+  run_success "$OCKAM" chat receive --identity i2 --from /node/n1/secure/api/service/chat
+  assert_output ""
+}
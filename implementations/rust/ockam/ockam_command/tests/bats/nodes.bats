@@ -32,6 +32,63 @@ force_kill_node() {
 
 # ===== TESTS
 
+@test "node - the node names are completed" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" completion --shell bash
+  assert_output --partial "\"node show|\") echo node ;;"
+
+  run_success "$OCKAM" completion --values node
+  assert_output --partial "n1"
+}
+
+@test "node - the status of the nodes is diagnosed" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" status --all --timeout 2 --output json
+  assert_output --partial "\"name\": \"n1\""
+  assert_output --partial "\"responsive\": true"
+}
+
+@test "node - the overall health is checked with an exit code" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" status --check --output json
+  assert_output --partial "\"healthy\":true"
+  assert_output --partial "\"name\":\"n1\",\"running\":true"
+
+  # A node which is not running makes the check fail
+  force_kill_node n1
+  run_failure "$OCKAM" status --check --output json
+  assert_output --partial "\"healthy\":false"
+  assert_output --partial "\"name\":\"n1\",\"running\":false"
+}
+
+@test "node - the health of all the nodes is checked" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+  run_success "$OCKAM" node list --check --timeout 5s --output json
+  assert_output --partial "\"node_name\": \"n1\""
+  assert_output --partial "\"node_name\": \"n2\""
+  assert_output --partial "\"healthy\": 2"
+
+  # A node which is not running is reported and the check fails
+  force_kill_node n2
+  run_failure "$OCKAM" node list --check --timeout 2s --output json
+  assert_output --partial "\"health\": \"unreachable\""
+}
+
+@test "node - a dry run prints the plan without creating the node" {
+  run_success "$OCKAM" node create n1 --dry-run
+  assert_output --partial "\"kind\": \"start_node\""
+
+  run_failure "$OCKAM" node show n1
+  run_failure "$OCKAM" node list --dry-run
+}
+
+@test "node - the progress of the creation is reported with json events" {
+  run_success "$OCKAM" node create n1 --progress json
+  assert_output --partial "{\"stage\":\"progress\",\"message\":\"Creating node...\",\"percent\":null}"
+  assert_output --partial "{\"stage\":\"finished\""
+}
+
 @test "node - create with random name" {
   run_success "$OCKAM" node create
 }
@@ -144,6 +201,28 @@ force_kill_node() {
   fi
 }
 
+@test "node - background node logs to the selected sinks only" {
+  QUIET=0
+  # The logs of the otlp sink are exported to a collector which is not running:
+  # the node still starts and nothing is written to its log file
+  n="$(random_str)"
+  run_success "$OCKAM" node create $n --log-sink otlp --otlp-logs-endpoint http://127.0.0.1:1/v1/logs
+  run_success "$OCKAM" node show $n
+
+  log_file="$($OCKAM node logs $n)"
+  if [ -s $log_file ]; then
+    fail "Log file should be empty"
+  fi
+
+  n="$(random_str)"
+  run_success "$OCKAM" node create $n --log-sink file,otlp --otlp-logs-endpoint http://127.0.0.1:1/v1/logs
+
+  log_file="$($OCKAM node logs $n)"
+  if [ ! -s $log_file ]; then
+    fail "Log file shouldn't be empty"
+  fi
+}
+
 @test "node - foreground node logs to stdout only" {
   n="$(random_str)"
   run_success "$OCKAM" node create $n -vv -f &
@@ -154,3 +233,59 @@ force_kill_node() {
     fail "Log file should be empty"
   fi
 }
+
+@test "run - delete the created node when a later step fails with --rollback" {
+  n="$(random_str)"
+  cat >"$OCKAM_HOME/playbook.yaml" <<EOF2
+nodes:
+  $n:
+    tcp-inlets:
+      i1:
+        from: 'not an address'
+        to: /node/$n/service/outlet
+EOF2
+
+  run "$OCKAM" run "$OCKAM_HOME/playbook.yaml" --rollback
+  assert_failure
+  assert_output --partial "inlet/i1 failed"
+  run_failure "$OCKAM" node show $n
+}
+
+@test "doctor - report a port which is already in use" {
+  port="$(random_port)"
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" tcp-inlet create --at /node/n1 --from "127.0.0.1:$port" --to /node/n1/service/outlet
+
+  run "$OCKAM" doctor --port "$port" --output json
+  assert_failure
+  assert_output --partial "The port $port is already in use"
+}
+
+@test "node - the secrets of the environment file are resolved by the node and never stored" {
+  cat >"$BATS_TEST_TMPDIR/secrets.env" <<EOF2
+SASL_USERNAME=kafka-user
+SASL_PASSWORD=0ckam-sasl-p4ssword
+CLIENT_KEY=$BATS_TEST_TMPDIR/client.key
+CLIENT_CERT=$BATS_TEST_TMPDIR/client.pem
+EOF2
+  run_success "$OCKAM" node create n1 --env-file "$BATS_TEST_TMPDIR/secrets.env"
+
+  run_success "$OCKAM" kafka-outlet create --at n1 \
+    --sasl-username secret:SASL_USERNAME --sasl-password secret:SASL_PASSWORD
+  run_failure "$OCKAM" tcp-outlet create --at n1 --to 127.0.0.1:5000 --to-tls \
+    --tls-client-cert secret:CLIENT_CERT --tls-client-key secret:MISSING_KEY
+  assert_output --partial "the secret 'MISSING_KEY' is not defined"
+
+  if command -v openssl >/dev/null; then
+    openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:prime256v1 -nodes -days 1 -subj "/CN=client" \
+      -keyout "$BATS_TEST_TMPDIR/client.key" -out "$BATS_TEST_TMPDIR/client.pem" 2>/dev/null
+    run_success "$OCKAM" tcp-outlet create --at n1 --to 127.0.0.1:5000 --to-tls \
+      --tls-client-cert secret:CLIENT_CERT --tls-client-key secret:CLIENT_KEY
+    key_line="$(sed -n 2p "$BATS_TEST_TMPDIR/client.key")"
+    run grep -rF "$key_line" "$OCKAM_HOME"
+    assert_failure
+  fi
+
+  run grep -rF "0ckam-sasl-p4ssword" "$OCKAM_HOME"
+  assert_failure
+}
@@ -14,6 +14,30 @@ teardown() {
 
 # ===== TESTS
 
+@test "portals - an inlet which can't be created returns an error code" {
+  outlet_port="$(random_port)"
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to "127.0.0.1:$outlet_port"
+  run_success "$OCKAM" tcp-inlet create --at /node/n1 --from "127.0.0.1:$(random_port)" --to /node/n1/service/outlet --alias "test-inlet"
+
+  run "$OCKAM" tcp-inlet create --at /node/n1 --from "127.0.0.1:$(random_port)" --to /node/n1/service/outlet --alias "test-inlet"
+  assert_failure 73
+  assert_output --partial "ALREADY_EXISTS"
+}
+
+@test "portals - a full inlet port range only fails the inlet creation" {
+  inlet_port="$(random_port)"
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to "127.0.0.1:$(random_port)"
+  run_success "$OCKAM" tcp-inlet create --at /node/n1 --from "127.0.0.1:$inlet_port" --to /node/n1/service/outlet
+  run_success "$OCKAM" configuration set inlet-port-range "$inlet_port-$inlet_port"
+
+  # The other commands are not affected
+  run_success "$OCKAM" node list
+  run_failure "$OCKAM" tcp-inlet create --at /node/n1 --to /node/n1/service/outlet
+  assert_output --partial "There is no available port in the range"
+}
+
 @test "portals - tcp inlet CRUD" {
   outlet_port="$(random_port)"
   inlet_port="$(random_port)"
@@ -35,6 +59,12 @@ teardown() {
   run_success $OCKAM tcp-inlet show test-inlet --at /node/n2 --output json
   assert_output --partial "\"alias\":\"test-inlet\""
   assert_output --partial "\"bind_addr\":\"127.0.0.1:$inlet_port\""
+  run_success $OCKAM tcp-inlet show test-inlet --at /node/n2 --output yaml --output-schema 1
+  assert_output --partial "alias: test-inlet"
+  run_failure $OCKAM tcp-inlet show test-inlet --at /node/n2 --output yaml --output-schema 2
+  # The output of the other commands is not versioned
+  run_failure $OCKAM tcp-outlet list --at /node/n1 --output json --output-schema 1
+  assert_output --partial "doesn't support --output-schema"
 
   run_success $OCKAM tcp-inlet delete "test-inlet" --at /node/n2 --yes
 
@@ -202,3 +232,8 @@ teardown() {
 
   run_failure "$OCKAM" tcp-inlet create --at "$n" --from "127.0.0.1:$port" --to "/node/$n/service/outlet"
 }
+
+@test "portals - the setup wizard needs an interactive terminal" {
+  run_failure "$OCKAM" setup --no-input
+  assert_output --partial "ockam tcp-inlet create"
+}
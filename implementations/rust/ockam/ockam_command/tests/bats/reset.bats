@@ -42,3 +42,45 @@ env'
   assert_output 'bin
 env'
 }
+
+@test "reset - only delete the nodes" {
+  run_success "$OCKAM" identity create i1
+  run_success "$OCKAM" node create n1 --identity i1
+
+  run_success "$OCKAM" reset --nodes --yes
+  run_failure "$OCKAM" node show n1
+  run_success "$OCKAM" identity show i1
+}
+
+@test "reset - fail instead of prompting in the non-interactive mode" {
+  run_success "$OCKAM" node create n1
+
+  run "$OCKAM" reset --non-interactive
+  assert_failure 66
+  assert_output --partial "non-interactive"
+
+  OCKAM_NON_INTERACTIVE=true run "$OCKAM" reset --nodes
+  assert_failure 66
+  run_success "$OCKAM" node show n1
+}
+
+@test "state - migrate an unversioned state directory after a backup" {
+  run_success "$OCKAM" node create n1
+  run_success rm "$OCKAM_HOME/schema_version"
+
+  run_failure "$OCKAM" state migrate --check
+  assert_output --partial "migrations are pending"
+
+  run_success "$OCKAM" state migrate
+  run_success ls "$OCKAM_HOME.v0.bak/nodes"
+  assert_output --partial "n1"
+  run_success "$OCKAM" state migrate --check
+  rm -rf "$OCKAM_HOME.v0.bak"
+
+  # The state of a newer version of ockam is not reset
+  echo 1000 >"$OCKAM_HOME/schema_version"
+  run_failure "$OCKAM" node show n1
+  assert_output --partial "only supports the versions up to"
+  run_success ls "$OCKAM_HOME/nodes"
+  assert_output --partial "n1"
+}
@@ -0,0 +1,50 @@
+#!/bin/bash
+
+# ===== SETUP
+
+setup() {
+  load load/base.bash
+  load_bats_ext
+  setup_home_dir
+}
+
+teardown() {
+  teardown_home_dir
+}
+
+# ===== TESTS
+
+@test "udp listener - CRUD" {
+  port="$(random_port)"
+  addr="127.0.0.1:$port"
+
+  run_success "$OCKAM" node create n1
+
+  # Create udp listener and check output
+  run_success "$OCKAM" udp listener create "$addr" --at n1
+  assert_output --regexp '/dnsaddr/localhost/udp/[[:digit:]]+'
+
+  # Check that the listener is listed
+  run_success "$OCKAM" udp listener list --at n1
+  assert_output --partial "$addr"
+
+  # Delete the listener
+  run_success "$OCKAM" udp listener delete --at n1 "$addr" --yes
+
+  # Check that it's no longer listed
+  run_success "$OCKAM" udp listener list --at n1
+  refute_output --partial "$addr"
+}
+
+@test "udp listener - send a message via a secure channel over udp" {
+  port="$(random_port)"
+
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+  run_success "$OCKAM" udp listener create "127.0.0.1:$port" --at n1
+
+  msg=$(random_str)
+  output=$($OCKAM secure-channel create --from /node/n2 --to "/ip4/127.0.0.1/udp/$port/service/api")
+  run_success "$OCKAM" message send "$msg" --timeout 5 --from /node/n2 --to "$output/service/uppercase"
+  assert_output "$(to_uppercase "$msg")"
+}
@@ -0,0 +1,27 @@
+#!/bin/bash
+
+# ===== SETUP
+
+setup() {
+  load load/base.bash
+  load_bats_ext
+  setup_home_dir
+}
+
+teardown() {
+  teardown_home_dir
+}
+
+# ===== TESTS
+
+@test "websocket listener - send a message via a secure channel over websocket" {
+  port="$(random_port)"
+
+  run_success "$OCKAM" node create n1 --websocket-listener-address "127.0.0.1:$port"
+  run_success "$OCKAM" node create n2
+
+  msg=$(random_str)
+  output=$($OCKAM secure-channel create --from /node/n2 --to "/ip4/127.0.0.1/ws/$port/service/api")
+  run_success "$OCKAM" message send "$msg" --timeout 5 --from /node/n2 --to "$output/service/uppercase"
+  assert_output "$(to_uppercase "$msg")"
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "orchestrator")]
+
 use assert_cmd::prelude::*;
 use std::process::Command;
 
@@ -154,11 +154,7 @@ impl<T> Reply<T> {
     pub fn success(self) -> Result<T> {
         match self {
             Reply::Successful(t) => Ok(t),
-            Reply::Failed(e, _) => Err(crate::Error::new(
-                Origin::Api,
-                Kind::Invalid,
-                e.message().unwrap_or("no message defined for this error"),
-            )),
+            Reply::Failed(e, _) => Err(e.into_failure()),
         }
     }
 
@@ -168,11 +164,7 @@ impl<T> Reply<T> {
         match self {
             Reply::Successful(t) => Ok(Some(t)),
             Reply::Failed(_, Some(Status::NotFound)) => Ok(None),
-            Reply::Failed(e, _) => Err(crate::Error::new(
-                Origin::Api,
-                Kind::Invalid,
-                e.message().unwrap_or("no message defined for this error"),
-            )),
+            Reply::Failed(e, _) => Err(e.into_failure()),
         }
     }
 }
@@ -320,7 +312,8 @@ pub struct Error {
     #[n(3)] message: Option<String>,
     /// The cause of the error, if any.
     #[b(4)] cause: Option<Box<Error>>,
-
+    /// A machine-readable code identifying the error, see [`ApiErrorCode`].
+    #[n(5)] code: Option<String>,
 }
 
 impl Error {
@@ -330,6 +323,7 @@ impl Error {
             path: Some(path.to_string()),
             message: None,
             cause: None,
+            code: None,
         }
     }
 
@@ -339,6 +333,7 @@ impl Error {
             path: None,
             message: None,
             cause: None,
+            code: None,
         }
     }
 
@@ -369,6 +364,11 @@ impl Error {
         self
     }
 
+    pub fn with_code(mut self, code: ApiErrorCode) -> Self {
+        self.code = Some(code.to_string());
+        self
+    }
+
     pub fn path(&self) -> Option<&str> {
         self.path.as_deref()
     }
@@ -380,14 +380,115 @@ impl Error {
     pub fn message(&self) -> Option<&str> {
         self.message.as_deref()
     }
+
+    /// Return the code of this error, if it is known by this version
+    pub fn code(&self) -> Option<ApiErrorCode> {
+        self.code.as_deref().and_then(ApiErrorCode::parse)
+    }
+
+    /// Convert this error into an [`crate::Error`] for the client of a failed request.
+    /// When the error has a code, the cause of the returned error is an [`ApiFailure`]
+    pub fn into_failure(self) -> crate::Error {
+        let message = self
+            .message()
+            .unwrap_or("no message defined for this error")
+            .to_string();
+        match self.code() {
+            Some(code) => {
+                crate::Error::new(Origin::Api, Kind::Invalid, ApiFailure { code, message })
+            }
+            None => crate::Error::new(Origin::Api, Kind::Invalid, message),
+        }
+    }
+}
+
+/// Machine-readable code of an API [`Error`], which clients can map to an actionable message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ApiErrorCode {
+    /// The outlet of a portal could not be reached
+    PortalDestinationUnreachable,
+    /// A credential has expired
+    CredentialExpired,
+    /// An identity is not authorized, for example by the trust policy of a secure channel
+    IdentityNotAuthorized,
+    /// The request could not be completed in time
+    Timeout,
+    /// A resource does not exist
+    NotFound,
+    /// A resource already exists
+    AlreadyExists,
+    /// The request is invalid
+    InvalidRequest,
+}
+
+impl ApiErrorCode {
+    const ALL: &'static [ApiErrorCode] = &[
+        ApiErrorCode::PortalDestinationUnreachable,
+        ApiErrorCode::CredentialExpired,
+        ApiErrorCode::IdentityNotAuthorized,
+        ApiErrorCode::Timeout,
+        ApiErrorCode::NotFound,
+        ApiErrorCode::AlreadyExists,
+        ApiErrorCode::InvalidRequest,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiErrorCode::PortalDestinationUnreachable => "PORTAL_DESTINATION_UNREACHABLE",
+            ApiErrorCode::CredentialExpired => "CREDENTIAL_EXPIRED",
+            ApiErrorCode::IdentityNotAuthorized => "IDENTITY_NOT_AUTHORIZED",
+            ApiErrorCode::Timeout => "TIMEOUT",
+            ApiErrorCode::NotFound => "NOT_FOUND",
+            ApiErrorCode::AlreadyExists => "ALREADY_EXISTS",
+            ApiErrorCode::InvalidRequest => "INVALID_REQUEST",
+        }
+    }
+
+    pub fn parse(code: &str) -> Option<ApiErrorCode> {
+        Self::ALL.iter().find(|c| c.as_str() == code).copied()
+    }
 }
 
+impl Display for ApiErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Failure of a request which returned an [`Error`] with a code
+#[derive(Debug, Clone)]
+pub struct ApiFailure {
+    code: ApiErrorCode,
+    message: String,
+}
+
+impl ApiFailure {
+    pub fn code(&self) -> ApiErrorCode {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for ApiFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ApiFailure {}
+
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let fields: Vec<String> = vec![
             self.message.clone().map(|m| format!("message: {m}")),
             self.path.clone().map(|p| format!("path: {p}")),
             self.method.map(|m| format!("method: {m}")),
+            self.code.clone().map(|c| format!("code: {c}")),
             self.cause.clone().map(|c| c.to_string()),
         ]
         .into_iter()
@@ -404,6 +505,7 @@ impl From<crate::Error> for Error {
             path: None,
             message: Some(e.to_string()),
             cause: None,
+            code: None,
         }
     }
 }
@@ -585,6 +687,17 @@ impl Response {
         Response::builder(r.id(), status).body(e)
     }
 
+    /// Create an error response with a machine-readable code
+    pub fn error_with_code(
+        r: &RequestHeader,
+        msg: &str,
+        status: Status,
+        code: ApiErrorCode,
+    ) -> Response<Error> {
+        let e = Error::from_failed_request(r, msg).with_code(code);
+        Response::builder(r.id(), status).body(e)
+    }
+
     pub fn ok(re: &RequestHeader) -> Response {
         Response::builder(re.id(), Status::Ok)
     }
@@ -787,6 +900,15 @@ mod tests {
             validate_with_schema("error", e)
         }
 
+        fn error_code(e: Error) -> bool {
+            let code = e.code();
+            let failure = e.into_failure();
+            let api_failure = std::error::Error::source(&failure)
+                .and_then(|s| s.downcast_ref::<ApiFailure>())
+                .map(|f| f.code());
+            api_failure == code
+        }
+
         fn type_check(a: RequestHeader, b: ResponseHeader, c: Error) -> TestResult {
             let cbor_a = minicbor::to_vec(a).unwrap();
             let cbor_b = minicbor::to_vec(b).unwrap();
@@ -826,6 +948,9 @@ mod tests {
             if bool::arbitrary(g) {
                 e = e.with_message(String::arbitrary(g))
             }
+            if bool::arbitrary(g) {
+                e = e.with_code(*g.choose(ApiErrorCode::ALL).unwrap())
+            }
             e
         }
     }
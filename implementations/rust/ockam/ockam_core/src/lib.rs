@@ -79,6 +79,7 @@ mod cbor;
 mod error;
 mod message;
 mod processor;
+mod quota;
 mod routing;
 mod uint;
 mod worker;
@@ -88,6 +89,7 @@ pub use cbor::*;
 pub use error::*;
 pub use message::*;
 pub use processor::*;
+pub use quota::*;
 pub use routing::*;
 pub use uint::*;
 pub use worker::*;
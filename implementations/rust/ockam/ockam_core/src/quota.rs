@@ -0,0 +1,147 @@
+use crate::compat::sync::{Arc, RwLock};
+use core::fmt::{Display, Formatter};
+use core::time::Duration;
+
+/// Target of the log events recording the sessions closed because they exceeded their quota
+pub const QUOTA_AUDIT_TARGET: &str = "ockam::quota::audit";
+
+/// Limits on the traffic of a session, for example a secure channel or a portal connection
+///
+/// A session is closed by its workers as soon as one of its limits is exceeded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Quota {
+    /// Maximum number of bytes exchanged in both directions
+    pub max_bytes: Option<u64>,
+    /// Maximum number of messages exchanged in both directions
+    pub max_messages: Option<u64>,
+    /// Maximum lifetime of the session
+    pub max_duration: Option<Duration>,
+}
+
+impl Quota {
+    /// Set the maximum number of bytes
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Set the maximum number of messages
+    pub fn with_max_messages(mut self, max_messages: u64) -> Self {
+        self.max_messages = Some(max_messages);
+        self
+    }
+
+    /// Set the maximum lifetime
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Return true if no limit is set
+    pub fn is_unlimited(&self) -> bool {
+        self.max_bytes.is_none() && self.max_messages.is_none() && self.max_duration.is_none()
+    }
+}
+
+/// Limit of a [`Quota`] which was exceeded by a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaExceeded {
+    /// Maximum number of bytes
+    Bytes(u64),
+    /// Maximum number of messages
+    Messages(u64),
+    /// Maximum lifetime
+    Duration(Duration),
+}
+
+impl Display for QuotaExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            QuotaExceeded::Bytes(n) => write!(f, "the quota of {n} bytes was exceeded"),
+            QuotaExceeded::Messages(n) => write!(f, "the quota of {n} messages was exceeded"),
+            QuotaExceeded::Duration(d) => {
+                write!(f, "the maximum duration of {}s was reached", d.as_secs())
+            }
+        }
+    }
+}
+
+/// Traffic of a session counted against its [`Quota`]
+///
+/// Clones share the same counters, so that the workers handling both directions of a session
+/// count their traffic together.
+#[derive(Debug, Clone)]
+pub struct QuotaUsage {
+    quota: Quota,
+    counters: Arc<RwLock<Counters>>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    bytes: u64,
+    messages: u64,
+}
+
+impl QuotaUsage {
+    /// Start counting the traffic of a new session
+    pub fn new(quota: Quota) -> Self {
+        Self {
+            quota,
+            counters: Default::default(),
+        }
+    }
+
+    /// Quota of the session
+    pub fn quota(&self) -> &Quota {
+        &self.quota
+    }
+
+    /// Number of bytes counted so far
+    pub fn bytes(&self) -> u64 {
+        self.counters.read().unwrap().bytes
+    }
+
+    /// Number of messages counted so far
+    pub fn messages(&self) -> u64 {
+        self.counters.read().unwrap().messages
+    }
+
+    /// Count a message of `len` bytes, and return the limit exceeded with this message, if any
+    pub fn add_message(&self, len: usize) -> Option<QuotaExceeded> {
+        let mut counters = self.counters.write().unwrap();
+        counters.bytes = counters.bytes.saturating_add(len as u64);
+        counters.messages = counters.messages.saturating_add(1);
+
+        match (self.quota.max_bytes, self.quota.max_messages) {
+            (Some(max_bytes), _) if counters.bytes > max_bytes => {
+                Some(QuotaExceeded::Bytes(max_bytes))
+            }
+            (_, Some(max_messages)) if counters.messages > max_messages => {
+                Some(QuotaExceeded::Messages(max_messages))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_is_shared_between_clones() {
+        let usage = QuotaUsage::new(Quota::default().with_max_bytes(10).with_max_messages(3));
+        let clone = usage.clone();
+
+        assert_eq!(usage.add_message(4), None);
+        assert_eq!(clone.add_message(6), None);
+        assert_eq!(usage.add_message(1), Some(QuotaExceeded::Bytes(10)));
+        assert_eq!(clone.bytes(), 11);
+
+        let usage = QuotaUsage::new(Quota::default().with_max_messages(1));
+        assert_eq!(usage.add_message(100), None);
+        assert_eq!(usage.add_message(0), Some(QuotaExceeded::Messages(1)));
+
+        assert!(Quota::default().is_unlimited());
+    }
+}
@@ -9,6 +9,10 @@ use core::fmt::{self, Debug, Display};
 pub enum RouteError {
     /// Message had an incomplete route
     IncompleteRoute,
+    /// Message is longer than the limit of a transport
+    MessageTooLong,
+    /// Fragment of a message is inconsistent with the other fragments
+    InvalidFragment,
 }
 
 impl From<RouteError> for Error {
@@ -16,6 +20,8 @@ impl From<RouteError> for Error {
     fn from(err: RouteError) -> Self {
         let kind = match err {
             RouteError::IncompleteRoute => Kind::Misuse,
+            RouteError::MessageTooLong => Kind::ResourceExhausted,
+            RouteError::InvalidFragment => Kind::Invalid,
         };
         Error::new(Origin::Core, kind, err)
     }
@@ -26,6 +32,8 @@ impl Display for RouteError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             RouteError::IncompleteRoute => write!(f, "incomplete route"),
+            RouteError::MessageTooLong => write!(f, "message too long"),
+            RouteError::InvalidFragment => write!(f, "invalid message fragment"),
         }
     }
 }
@@ -0,0 +1,290 @@
+//! Fragmentation of the routing messages which are larger than the payload of a transport.
+//!
+//! A transport which can only send payloads of a limited size, like a UDP datagram or a BLE
+//! characteristic, splits each encoded [`TransportMessage`] into [`Fragment`]s with a
+//! [`Fragmenter`]. The receiving side of the transport reassembles them with a [`Reassembler`],
+//! which only keeps a bounded number of partial messages, of a bounded length, so that a peer
+//! can't exhaust the memory of a node by sending fragments which are never completed.
+//!
+//! The fragments of a message can be received in any order, but a transport must not deliver
+//! a fragment twice. A [`Reassembler`] is used for a single peer.
+
+use crate::compat::collections::BTreeMap;
+use crate::compat::vec::Vec;
+use crate::{Decodable, Encodable, Message, Result, RouteError, TransportMessage};
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of bytes added to the data of a fragment when it is encoded
+pub const FRAGMENT_OVERHEAD: usize = 8 + 4 + 4 + 10;
+
+/// Default maximum length of a reassembled message
+pub const DEFAULT_MAX_MESSAGE_LENGTH: usize = 1024 * 1024;
+
+/// Default maximum number of messages being reassembled at the same time
+pub const DEFAULT_MAX_PENDING_MESSAGES: usize = 16;
+
+/// Part of an encoded routing message
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Message)]
+pub struct Fragment {
+    /// Identifier of the message, increasing for each message of a [`Fragmenter`]
+    pub message_id: u64,
+    /// Position of the fragment in the message
+    pub index: u32,
+    /// Number of fragments of the message
+    pub count: u32,
+    /// Bytes of the encoded message
+    pub data: Vec<u8>,
+}
+
+/// Split the routing messages into fragments which fit in the payload of a transport
+#[derive(Debug)]
+pub struct Fragmenter {
+    max_data_length: usize,
+    next_message_id: u64,
+}
+
+impl Fragmenter {
+    /// Create a fragmenter for a transport sending payloads up to `max_payload_length` bytes,
+    /// which must be larger than [`FRAGMENT_OVERHEAD`]
+    pub fn new(max_payload_length: usize) -> Self {
+        assert!(
+            max_payload_length > FRAGMENT_OVERHEAD,
+            "the payload of a transport must be larger than the fragment overhead"
+        );
+        Self {
+            max_data_length: max_payload_length - FRAGMENT_OVERHEAD,
+            next_message_id: 0,
+        }
+    }
+
+    /// Split a message into fragments, each one fitting in a payload once encoded
+    pub fn fragment(&mut self, message: &TransportMessage) -> Result<Vec<Fragment>> {
+        let encoded = message.encode()?;
+        let count = (encoded.len() + self.max_data_length - 1) / self.max_data_length;
+        let count = u32::try_from(count.max(1)).map_err(|_| RouteError::MessageTooLong)?;
+        let message_id = self.next_message_id;
+        self.next_message_id += 1;
+
+        if encoded.is_empty() {
+            return Ok(vec![Fragment {
+                message_id,
+                index: 0,
+                count,
+                data: encoded,
+            }]);
+        }
+        Ok(encoded
+            .chunks(self.max_data_length)
+            .zip(0..)
+            .map(|(data, index)| Fragment {
+                message_id,
+                index,
+                count,
+                data: data.to_vec(),
+            })
+            .collect())
+    }
+}
+
+/// Limits of the memory used by a [`Reassembler`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FragmentationLimits {
+    max_message_length: usize,
+    max_pending_messages: usize,
+}
+
+impl Default for FragmentationLimits {
+    fn default() -> Self {
+        Self {
+            max_message_length: DEFAULT_MAX_MESSAGE_LENGTH,
+            max_pending_messages: DEFAULT_MAX_PENDING_MESSAGES,
+        }
+    }
+}
+
+impl FragmentationLimits {
+    /// Set the maximum length of a reassembled message. The fragments of a longer message
+    /// are dropped
+    pub fn with_max_message_length(mut self, max_message_length: usize) -> Self {
+        self.max_message_length = max_message_length;
+        self
+    }
+
+    /// Set the maximum number of messages being reassembled at the same time. When a fragment
+    /// of a new message is received, the oldest partial message is dropped if necessary
+    pub fn with_max_pending_messages(mut self, max_pending_messages: usize) -> Self {
+        self.max_pending_messages = max_pending_messages.max(1);
+        self
+    }
+
+    /// Maximum length of a reassembled message
+    pub fn max_message_length(&self) -> usize {
+        self.max_message_length
+    }
+
+    /// Maximum number of messages being reassembled at the same time
+    pub fn max_pending_messages(&self) -> usize {
+        self.max_pending_messages
+    }
+}
+
+#[derive(Debug)]
+struct PartialMessage {
+    count: u32,
+    length: usize,
+    fragments: BTreeMap<u32, Vec<u8>>,
+}
+
+/// Reassemble the routing messages sent by a peer from their fragments
+#[derive(Debug, Default)]
+pub struct Reassembler {
+    limits: FragmentationLimits,
+    pending: BTreeMap<u64, PartialMessage>,
+}
+
+impl Reassembler {
+    /// Create a reassembler with some limits
+    pub fn new(limits: FragmentationLimits) -> Self {
+        Self {
+            limits,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Number of messages being reassembled
+    pub fn pending_messages(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Add a received fragment, and return its message if it is now complete.
+    ///
+    /// An invalid fragment, or a fragment making its message longer than the limits,
+    /// is rejected with an error, and the fragments of its message are dropped
+    pub fn push(&mut self, fragment: Fragment) -> Result<Option<TransportMessage>> {
+        if fragment.index >= fragment.count {
+            self.pending.remove(&fragment.message_id);
+            return Err(RouteError::InvalidFragment.into());
+        }
+        if fragment.count == 1 {
+            return self.decode(fragment.data).map(Some);
+        }
+        if fragment.count as usize > self.limits.max_message_length {
+            return Err(RouteError::MessageTooLong.into());
+        }
+
+        if !self.pending.contains_key(&fragment.message_id) {
+            while self.pending.len() >= self.limits.max_pending_messages {
+                if let Some(oldest) = self.pending.keys().next().copied() {
+                    warn!(
+                        message_id = oldest,
+                        "dropping an incomplete fragmented message"
+                    );
+                    self.pending.remove(&oldest);
+                }
+            }
+        }
+        let message_id = fragment.message_id;
+        let partial = self
+            .pending
+            .entry(message_id)
+            .or_insert_with(|| PartialMessage {
+                count: fragment.count,
+                length: 0,
+                fragments: BTreeMap::new(),
+            });
+        if partial.count != fragment.count || partial.fragments.contains_key(&fragment.index) {
+            self.pending.remove(&message_id);
+            return Err(RouteError::InvalidFragment.into());
+        }
+        partial.length += fragment.data.len();
+        if partial.length > self.limits.max_message_length {
+            self.pending.remove(&message_id);
+            return Err(RouteError::MessageTooLong.into());
+        }
+        partial.fragments.insert(fragment.index, fragment.data);
+        if partial.fragments.len() < partial.count as usize {
+            return Ok(None);
+        }
+
+        let partial = match self.pending.remove(&message_id) {
+            Some(partial) => partial,
+            None => return Ok(None),
+        };
+        let mut encoded = Vec::with_capacity(partial.length);
+        for data in partial.fragments.into_values() {
+            encoded.extend(data);
+        }
+        self.decode(encoded).map(Some)
+    }
+
+    fn decode(&self, encoded: Vec<u8>) -> Result<TransportMessage> {
+        if encoded.len() > self.limits.max_message_length {
+            return Err(RouteError::MessageTooLong.into());
+        }
+        TransportMessage::decode(&encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::route;
+
+    fn message(length: usize) -> TransportMessage {
+        TransportMessage::v1(route!["a"], route!["b"], vec![7; length])
+    }
+
+    #[test]
+    fn fragments_are_reassembled_in_any_order() {
+        let mut fragmenter = Fragmenter::new(100);
+        let mut reassembler = Reassembler::default();
+
+        let small = fragmenter.fragment(&message(10)).unwrap();
+        assert_eq!(small.len(), 1);
+        assert_eq!(
+            reassembler.push(small[0].clone()).unwrap(),
+            Some(message(10))
+        );
+
+        let mut large = fragmenter.fragment(&message(1000)).unwrap();
+        assert!(large.len() > 10);
+        for fragment in &large {
+            assert!(fragment.encode().unwrap().len() <= 100);
+        }
+        let last = large.remove(0);
+        for fragment in large.into_iter().rev() {
+            assert_eq!(reassembler.push(fragment).unwrap(), None);
+        }
+        assert_eq!(reassembler.push(last).unwrap(), Some(message(1000)));
+        assert_eq!(reassembler.pending_messages(), 0);
+    }
+
+    #[test]
+    fn reassembly_is_limited() {
+        let limits = FragmentationLimits::default()
+            .with_max_message_length(500)
+            .with_max_pending_messages(2);
+        let mut fragmenter = Fragmenter::new(100);
+        let mut reassembler = Reassembler::new(limits);
+
+        // A message longer than the limit is dropped
+        let too_long = fragmenter.fragment(&message(1000)).unwrap();
+        let result: Result<Vec<_>> = too_long.into_iter().map(|f| reassembler.push(f)).collect();
+        assert!(result.is_err());
+
+        // Only the latest incomplete messages are kept
+        for _ in 0..3 {
+            let fragments = fragmenter.fragment(&message(200)).unwrap();
+            reassembler.push(fragments[0].clone()).unwrap();
+        }
+        assert_eq!(reassembler.pending_messages(), 2);
+
+        let invalid = Fragment {
+            message_id: 42,
+            index: 2,
+            count: 2,
+            data: vec![],
+        };
+        assert!(reassembler.push(invalid).is_err());
+    }
+}
@@ -12,6 +12,9 @@ pub use route::*;
 mod message;
 pub use message::*;
 
+mod fragmentation;
+pub use fragmentation::*;
+
 mod macros;
 pub use macros::*;
 
@@ -0,0 +1,209 @@
+use core::fmt;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// Name of the attribute listing the types of the attributes of a credential which are not
+/// strings. Its value is a comma-separated list of `name:type` pairs, for example `age:int,admin:bool`
+pub const ATTRIBUTE_TYPES: &[u8] = b"attribute_types";
+
+/// The same as above but in string format
+pub const ATTRIBUTE_TYPES_UTF8: &str = "attribute_types";
+
+/// Type of an attribute value. Attribute values are always encoded as strings, the type
+/// specifies how they must be interpreted
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    /// Any string
+    #[default]
+    String,
+    /// A signed 64 bits integer
+    Int,
+    /// `true` or `false`
+    Bool,
+}
+
+impl AttributeType {
+    /// Return true if the value is a valid encoding for this type
+    pub fn is_valid(&self, value: &str) -> bool {
+        match self {
+            AttributeType::String => true,
+            AttributeType::Int => value.parse::<i64>().is_ok(),
+            AttributeType::Bool => value.parse::<bool>().is_ok(),
+        }
+    }
+}
+
+impl Display for AttributeType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeType::String => write!(f, "string"),
+            AttributeType::Int => write!(f, "int"),
+            AttributeType::Bool => write!(f, "bool"),
+        }
+    }
+}
+
+impl FromStr for AttributeType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "string" => Ok(AttributeType::String),
+            "int" => Ok(AttributeType::Int),
+            "bool" => Ok(AttributeType::Bool),
+            _ => Err(error(format!(
+                "unknown attribute type {s}, it must be one of: string, int, bool"
+            ))),
+        }
+    }
+}
+
+/// Definition of an attribute in an [`AttributesSchema`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributeDefinition {
+    /// Attribute name
+    pub name: String,
+    /// Attribute type, a string by default
+    #[serde(rename = "type", default)]
+    pub attribute_type: AttributeType,
+    /// True if the attribute must be present in the issued credentials
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Schema of the attributes which can be attached to a credential.
+///
+/// It is used to validate the attributes at issue time. The types of the non-string
+/// attributes are then added to the credential with the [`ATTRIBUTE_TYPES`] attribute, so that
+/// the verifiers can interpret the attributes values, for example to compare numbers
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributesSchema {
+    /// Definitions of the accepted attributes
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+impl AttributesSchema {
+    /// Create a schema from a list of attribute definitions
+    pub fn new(attributes: Vec<AttributeDefinition>) -> Self {
+        Self { attributes }
+    }
+
+    /// Check that the required attributes are present and that all the attributes are
+    /// defined by the schema, with a value of the defined type
+    pub fn validate(&self, attributes: &BTreeMap<String, String>) -> Result<()> {
+        for definition in &self.attributes {
+            if definition.required && !attributes.contains_key(&definition.name) {
+                return Err(error(format!(
+                    "the attribute {} is required",
+                    definition.name
+                )));
+            }
+        }
+        for (name, value) in attributes {
+            let definition = self
+                .attributes
+                .iter()
+                .find(|d| &d.name == name)
+                .ok_or_else(|| {
+                    error(format!("the attribute {name} is not defined by the schema"))
+                })?;
+            if !definition.attribute_type.is_valid(value) {
+                return Err(error(format!(
+                    "the value {value} of the attribute {name} is not a valid {}",
+                    definition.attribute_type
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the value of the [`ATTRIBUTE_TYPES`] attribute for the given attributes,
+    /// or None if all of them are strings
+    pub fn attribute_types(&self, attributes: &BTreeMap<String, String>) -> Option<String> {
+        let types: Vec<String> = self
+            .attributes
+            .iter()
+            .filter(|d| d.attribute_type != AttributeType::String)
+            .filter(|d| attributes.contains_key(&d.name))
+            .map(|d| format!("{}:{}", d.name, d.attribute_type))
+            .collect();
+        if types.is_empty() {
+            None
+        } else {
+            Some(types.join(","))
+        }
+    }
+}
+
+/// Parse the value of the [`ATTRIBUTE_TYPES`] attribute.
+/// Malformed entries are ignored, the corresponding attributes are then treated as strings
+pub fn parse_attribute_types(value: &str) -> BTreeMap<String, AttributeType> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (name, attribute_type) = entry.split_once(':')?;
+            let attribute_type = AttributeType::from_str(attribute_type.trim()).ok()?;
+            Some((name.trim().to_string(), attribute_type))
+        })
+        .collect()
+}
+
+/// Create an Identity Error
+fn error(message: String) -> Error {
+    Error::new(Origin::Identity, Kind::Invalid, message.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_attributes() {
+        let schema = AttributesSchema::new(vec![
+            AttributeDefinition {
+                name: "role".into(),
+                attribute_type: AttributeType::String,
+                required: true,
+            },
+            AttributeDefinition {
+                name: "level".into(),
+                attribute_type: AttributeType::Int,
+                required: false,
+            },
+        ]);
+
+        let mut attributes = BTreeMap::new();
+        attributes.insert("role".to_string(), "admin".to_string());
+        assert!(schema.validate(&attributes).is_ok());
+        assert_eq!(schema.attribute_types(&attributes), None);
+
+        attributes.insert("level".to_string(), "3".to_string());
+        assert!(schema.validate(&attributes).is_ok());
+        assert_eq!(
+            schema.attribute_types(&attributes),
+            Some("level:int".to_string())
+        );
+
+        attributes.insert("level".to_string(), "high".to_string());
+        assert!(schema.validate(&attributes).is_err());
+
+        attributes.remove("role");
+        attributes.insert("level".to_string(), "3".to_string());
+        assert!(schema.validate(&attributes).is_err());
+    }
+
+    #[test]
+    fn test_parse_attribute_types() {
+        let types = parse_attribute_types("level:int,admin:bool,other:unknown,malformed");
+        assert_eq!(types.len(), 2);
+        assert_eq!(types.get("level"), Some(&AttributeType::Int));
+        assert_eq!(types.get("admin"), Some(&AttributeType::Bool));
+    }
+}
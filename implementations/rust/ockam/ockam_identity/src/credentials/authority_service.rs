@@ -57,6 +57,33 @@ impl AuthorityService {
             }
         }
 
+        self.retrieve_credential(ctx, subject).await
+    }
+
+    /// Retrieve a new credential for an identity within this authority, even if a valid
+    /// credential is cached, and cache it
+    pub async fn refresh_credential(
+        &self,
+        ctx: &Context,
+        subject: &Identifier,
+    ) -> Result<CredentialAndPurposeKey> {
+        self.retrieve_credential(ctx, subject).await
+    }
+
+    /// Return the expiration time of the cached credential, if there is one
+    pub fn cached_credential_expires_at(&self) -> Option<TimestampInSeconds> {
+        self.inner_cache
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|cache| cache.valid_until)
+    }
+
+    async fn retrieve_credential(
+        &self,
+        ctx: &Context,
+        subject: &Identifier,
+    ) -> Result<CredentialAndPurposeKey> {
         // in order to keep the locking schema simple, we allow multiple concurrent retrievals
         let retriever = self
             .own_credential
@@ -82,6 +109,25 @@ impl AuthorityService {
         Ok(credential)
     }
 
+    /// Retrieve the revocation list of this authority and use it from now on to check
+    /// the credentials issued by this authority
+    pub async fn refresh_revocation_list(&self, ctx: &Context, subject: &Identifier) -> Result<()> {
+        let retriever = self
+            .own_credential
+            .clone()
+            .ok_or(IdentityError::UnknownAuthority)?;
+        if let Some(revocation_list) = retriever.retrieve_revocation_list(ctx, subject).await? {
+            debug!(
+                "retrieved a revocation list with {} revoked subjects",
+                revocation_list.revoked.len()
+            );
+            self.credentials
+                .revocations()
+                .update(&self.identifier, revocation_list);
+        }
+        Ok(())
+    }
+
     /// Issuer [`Identifier`]
     pub fn identifier(&self) -> &Identifier {
         &self.identifier
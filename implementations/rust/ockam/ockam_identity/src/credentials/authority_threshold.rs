@@ -0,0 +1,94 @@
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::RwLock;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Identifier;
+
+/// Set of Authorities which must sign together the credentials issued by an Authority.
+///
+/// A Credential issued by that Authority is only valid if it is signed by at least `threshold`
+/// distinct signers: the issuer signature counts if the issuer is one of the signers, the other
+/// signatures are the co-signatures attached to the Credential. Compromising fewer than
+/// `threshold` signers is then not enough to create valid Credentials
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthorityThreshold {
+    /// Identities of the Authorities allowed to sign the Credentials
+    pub signers: Vec<Identifier>,
+    /// Minimum number of distinct signers
+    pub threshold: usize,
+}
+
+impl AuthorityThreshold {
+    /// Create a K-of-N threshold. The threshold must be between 1 and the number of signers
+    pub fn new(signers: Vec<Identifier>, threshold: usize) -> Result<Self> {
+        let authority_threshold = Self { signers, threshold };
+        authority_threshold.validate()?;
+        Ok(authority_threshold)
+    }
+
+    /// Check that the threshold can be reached
+    pub fn validate(&self) -> Result<()> {
+        if self.threshold == 0 || self.threshold > self.signers.len() {
+            return Err(Error::new(
+                Origin::Identity,
+                Kind::Invalid,
+                format!(
+                    "the threshold must be between 1 and the number of signers ({}), got {}",
+                    self.signers.len(),
+                    self.threshold
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Return true if the identity is one of the signers
+    pub fn is_signer(&self, identifier: &Identifier) -> bool {
+        self.signers.contains(identifier)
+    }
+}
+
+/// [`AuthorityThreshold`]s to enforce when verifying Credentials, for each issuing Authority
+#[derive(Default)]
+pub struct AuthorityThresholds {
+    thresholds: RwLock<BTreeMap<Identifier, AuthorityThreshold>>,
+}
+
+impl AuthorityThresholds {
+    /// Create an empty set of thresholds
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require the Credentials issued by an Authority to reach a threshold of signatures
+    pub fn set(&self, authority: &Identifier, threshold: AuthorityThreshold) {
+        self.thresholds
+            .write()
+            .unwrap()
+            .insert(authority.clone(), threshold);
+    }
+
+    /// Return the threshold to enforce for the Credentials issued by an Authority, if any
+    pub fn get(&self, authority: &Identifier) -> Option<AuthorityThreshold> {
+        self.thresholds.read().unwrap().get(authority).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authority_threshold_validation() {
+        let signers = vec![
+            Identifier::try_from("I0000000000000000000000000000000000000001").unwrap(),
+            Identifier::try_from("I0000000000000000000000000000000000000002").unwrap(),
+        ];
+        assert!(AuthorityThreshold::new(signers.clone(), 0).is_err());
+        assert!(AuthorityThreshold::new(signers.clone(), 3).is_err());
+        assert!(AuthorityThreshold::new(signers, 2).is_ok());
+    }
+}
@@ -1,5 +1,8 @@
 use crate::models::{CredentialData, PurposeKeyAttestationData};
-use crate::{CredentialsCreation, CredentialsVerification, IdentitiesRepository, PurposeKeys};
+use crate::{
+    AuthorityThresholds, CredentialsCreation, CredentialsVerification, IdentitiesRepository,
+    PurposeKeys, Revocations,
+};
 
 use ockam_core::compat::sync::Arc;
 use ockam_vault::{VaultForSigning, VaultForVerifyingSignatures};
@@ -20,6 +23,8 @@ pub struct Credentials {
     verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
     purpose_keys: Arc<PurposeKeys>,
     identities_repository: Arc<dyn IdentitiesRepository>,
+    revocations: Arc<Revocations>,
+    authority_thresholds: Arc<AuthorityThresholds>,
 }
 
 impl Credentials {
@@ -29,12 +34,16 @@ impl Credentials {
         verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
         purpose_keys: Arc<PurposeKeys>,
         identities_repository: Arc<dyn IdentitiesRepository>,
+        revocations: Arc<Revocations>,
+        authority_thresholds: Arc<AuthorityThresholds>,
     ) -> Self {
         Self {
             credential_vault,
             verifying_vault,
             purpose_keys,
             identities_repository,
+            revocations,
+            authority_thresholds,
         }
     }
 
@@ -48,6 +57,16 @@ impl Credentials {
         self.identities_repository.clone()
     }
 
+    /// [`Revocations`]
+    pub fn revocations(&self) -> Arc<Revocations> {
+        self.revocations.clone()
+    }
+
+    /// [`AuthorityThresholds`]
+    pub fn authority_thresholds(&self) -> Arc<AuthorityThresholds> {
+        self.authority_thresholds.clone()
+    }
+
     /// Return [`CredentialsCreation`]
     pub fn credentials_creation(&self) -> Arc<CredentialsCreation> {
         Arc::new(CredentialsCreation::new(
@@ -64,6 +83,8 @@ impl Credentials {
             self.purpose_keys.purpose_keys_verification(),
             self.verifying_vault.clone(),
             self.identities_repository.clone(),
+            self.revocations.clone(),
+            self.authority_thresholds.clone(),
         ))
     }
 }
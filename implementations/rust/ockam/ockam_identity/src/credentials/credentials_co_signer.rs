@@ -0,0 +1,275 @@
+use crate::models::{CredentialAndPurposeKey, CredentialData, Identifier};
+use crate::{
+    secure_channel_required, Credentials, IdentitiesDelegation, IdentitiesRepository,
+    IdentitySecureChannelLocalInfo, SecureChannels, SecureClient, DELEGATED_BY, TRUST_CONTEXT_ID,
+};
+
+use minicbor::bytes::ByteVec;
+use minicbor::Decoder;
+use ockam_core::api::{Method, Request, RequestHeader, Response};
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::{Result, Route, Routed, Worker};
+use ockam_node::{Context, DEFAULT_TIMEOUT};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, trace, warn};
+
+/// Name of the co-signing service of an Authority
+pub const CREDENTIAL_CO_SIGNER: &str = "credential_co_signer";
+
+/// This struct runs as a Worker to co-sign the credentials issued by other Authorities.
+///
+/// A credential is only co-signed if it has been issued by the Authority sending the request,
+/// and if its attributes are the attributes known by this Authority for its subject.
+/// Then a compromised issuer can't get credentials co-signed for subjects, or attributes, which
+/// have not been enrolled with the co-signers
+pub struct CredentialsCoSigner {
+    identities_repository: Arc<dyn IdentitiesRepository>,
+    credentials: Arc<Credentials>,
+    co_signer: Identifier,
+    issuers: Vec<Identifier>,
+    identities_delegation: Option<Arc<IdentitiesDelegation>>,
+}
+
+impl CredentialsCoSigner {
+    /// Create a new co-signer for the credentials issued by some Authorities
+    pub fn new(
+        identities_repository: Arc<dyn IdentitiesRepository>,
+        credentials: Arc<Credentials>,
+        co_signer: &Identifier,
+        issuers: Vec<Identifier>,
+    ) -> Self {
+        Self {
+            identities_repository,
+            credentials,
+            co_signer: co_signer.clone(),
+            issuers,
+            identities_delegation: None,
+        }
+    }
+
+    /// Co-sign the credentials issued to the devices having a valid delegation from a member
+    pub fn with_delegations(mut self, identities_delegation: Arc<IdentitiesDelegation>) -> Self {
+        self.identities_delegation = Some(identities_delegation);
+        self
+    }
+
+    async fn co_sign_credential(
+        &self,
+        issuer: &Identifier,
+        credential_and_purpose_key: &CredentialAndPurposeKey,
+    ) -> Result<Option<CredentialAndPurposeKey>> {
+        if !self.issuers.contains(issuer) {
+            return Ok(None);
+        }
+        let data = self
+            .credentials
+            .credentials_verification()
+            .verify_issued_credential(None, &[issuer.clone()], credential_and_purpose_key)
+            .await?;
+        if !self.has_known_attributes(&data.credential_data).await? {
+            return Ok(None);
+        }
+
+        let co_signature = self
+            .credentials
+            .credentials_creation()
+            .co_sign_credential(&self.co_signer, credential_and_purpose_key)
+            .await?;
+        let mut credential_and_purpose_key = credential_and_purpose_key.clone();
+        credential_and_purpose_key
+            .co_signatures
+            .get_or_insert_with(Vec::new)
+            .push(co_signature);
+        Ok(Some(credential_and_purpose_key))
+    }
+
+    /// Return true if the attributes of a credential are the attributes known by this Authority
+    /// for the subject of the credential, or for the member who delegated its attributes to it
+    async fn has_known_attributes(&self, credential_data: &CredentialData) -> Result<bool> {
+        let subject = match &credential_data.subject {
+            Some(subject) => subject,
+            None => return Ok(false),
+        };
+        let attributes = &credential_data.subject_attributes.map;
+        let member = match attributes.get(&ByteVec::from(DELEGATED_BY.to_vec())) {
+            Some(delegator) => match self.delegator(subject).await? {
+                Some(verified) if verified.to_string().as_bytes() == delegator.as_slice() => {
+                    verified
+                }
+                _ => return Ok(false),
+            },
+            None => subject.clone(),
+        };
+
+        let entry = match self
+            .identities_repository
+            .as_attributes_reader()
+            .get_attributes(&member)
+            .await?
+        {
+            Some(entry) => entry,
+            None => return Ok(false),
+        };
+        for (key, value) in attributes {
+            if key.as_slice() == TRUST_CONTEXT_ID || key.as_slice() == DELEGATED_BY {
+                continue;
+            }
+            if entry.attrs().get(key.as_slice()).map(|v| v.as_slice()) != Some(value.as_slice()) {
+                debug!(
+                    %member,
+                    key = %String::from_utf8_lossy(key),
+                    "the attribute of a credential to co-sign is not known"
+                );
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Return the delegating Identity of a device Identity, if delegations are accepted
+    async fn delegator(&self, subject: &Identifier) -> Result<Option<Identifier>> {
+        match &self.identities_delegation {
+            Some(identities_delegation) => Ok(identities_delegation
+                .verify_delegation(subject)
+                .await?
+                .map(|delegation| delegation.delegator)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for CredentialsCoSigner {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        if let Ok(i) = IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
+            let from = i.their_identity_id();
+            let mut dec = Decoder::new(m.as_body());
+            let req: RequestHeader = dec.decode()?;
+            trace! {
+                target: "ockam_identity::credentials::credentials_co_signer",
+                from   = %from,
+                id     = %req.id(),
+                method = ?req.method(),
+                path   = %req.path(),
+                body   = %req.has_body(),
+                "request"
+            }
+            let res = match (req.method(), req.path()) {
+                (Some(Method::Post), "/") => {
+                    let credential: CredentialAndPurposeKey = dec.decode()?;
+                    match self.co_sign_credential(&from, &credential).await {
+                        Ok(Some(crd)) => Response::ok(&req).body(crd).to_vec()?,
+                        Ok(None) => {
+                            warn!(%from, "a credential was not co-signed");
+                            Response::forbidden(&req, "the credential can't be co-signed")
+                                .to_vec()?
+                        }
+                        Err(error) => {
+                            Response::internal_error(&req, &error.to_string()).to_vec()?
+                        }
+                    }
+                }
+                _ => Response::unknown_path(&req).to_vec()?,
+            };
+            c.send(m.return_route(), res).await
+        } else {
+            secure_channel_required(c, m).await
+        }
+    }
+}
+
+/// Information necessary to connect to the co-signing service of another Authority
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialsCoSignerInfo {
+    /// Identity of the co-signing Authority
+    pub identifier: Identifier,
+    /// Route used to establish a secure channel to the co-signing Authority
+    pub route: Route,
+}
+
+/// Authorities asked to co-sign the credentials issued by an Authority
+pub struct CredentialsCoSigners {
+    secure_channels: Arc<SecureChannels>,
+    co_signers: Vec<CredentialsCoSignerInfo>,
+    required: usize,
+}
+
+impl CredentialsCoSigners {
+    /// Create a set of co-signers, `required` being the number of co-signatures to attach to
+    /// each credential
+    pub fn new(
+        secure_channels: Arc<SecureChannels>,
+        co_signers: Vec<CredentialsCoSignerInfo>,
+        required: usize,
+    ) -> Self {
+        Self {
+            secure_channels,
+            co_signers,
+            required,
+        }
+    }
+
+    /// Ask the co-signers, in order, to co-sign a credential until the required number of
+    /// co-signatures is reached. The co-signers which are not available are skipped
+    pub async fn co_sign(
+        &self,
+        ctx: &Context,
+        issuer: &Identifier,
+        credential_and_purpose_key: CredentialAndPurposeKey,
+    ) -> Result<CredentialAndPurposeKey> {
+        let mut credential_and_purpose_key = credential_and_purpose_key;
+        let mut co_signatures = credential_and_purpose_key.co_signatures().len();
+        for co_signer in &self.co_signers {
+            if co_signatures >= self.required {
+                break;
+            }
+            match self
+                .ask(ctx, issuer, co_signer, &credential_and_purpose_key)
+                .await
+            {
+                Ok(co_signed) => {
+                    credential_and_purpose_key = co_signed;
+                    co_signatures += 1;
+                }
+                Err(e) => {
+                    warn!(co_signer = %co_signer.identifier, %e, "a credential could not be co-signed")
+                }
+            }
+        }
+        if co_signatures < self.required {
+            return Err(crate::IdentityError::CredentialThresholdNotReached.into());
+        }
+        Ok(credential_and_purpose_key)
+    }
+
+    async fn ask(
+        &self,
+        ctx: &Context,
+        issuer: &Identifier,
+        co_signer: &CredentialsCoSignerInfo,
+        credential_and_purpose_key: &CredentialAndPurposeKey,
+    ) -> Result<CredentialAndPurposeKey> {
+        let route = ctx.resolve_transport_route(co_signer.route.clone()).await?;
+        let client = SecureClient::new(
+            self.secure_channels.clone(),
+            route,
+            &co_signer.identifier,
+            issuer,
+            DEFAULT_TIMEOUT,
+        );
+        client
+            .ask(
+                ctx,
+                CREDENTIAL_CO_SIGNER,
+                Request::post("/").body(credential_and_purpose_key),
+            )
+            .await?
+            .success()
+    }
+}
@@ -1,5 +1,6 @@
 use crate::models::{
-    Attributes, Credential, CredentialAndPurposeKey, CredentialData, Identifier, VersionedData,
+    Attributes, Credential, CredentialAndPurposeKey, CredentialCoSignature, CredentialData,
+    CredentialRouteConstraints, Identifier, VersionedData,
 };
 use crate::utils::{add_seconds, now};
 use crate::{IdentitiesRepository, Identity, PurposeKeyCreation};
@@ -47,6 +48,20 @@ impl CredentialsCreation {
         subject: &Identifier,
         subject_attributes: Attributes,
         ttl: Duration,
+    ) -> Result<CredentialAndPurposeKey> {
+        self.issue_credential_with_route_constraints(issuer, subject, subject_attributes, ttl, None)
+            .await
+    }
+
+    /// Issue a [`Credential`] which can only be presented over the routes allowed by the
+    /// given [`CredentialRouteConstraints`]
+    pub async fn issue_credential_with_route_constraints(
+        &self,
+        issuer: &Identifier,
+        subject: &Identifier,
+        subject_attributes: Attributes,
+        ttl: Duration,
+        route_constraints: Option<CredentialRouteConstraints>,
     ) -> Result<CredentialAndPurposeKey> {
         // TODO: Allow manual PurposeKey management
         let issuer_purpose_key = self
@@ -71,6 +86,7 @@ impl CredentialsCreation {
             subject_attributes,
             created_at,
             expires_at,
+            route_constraints,
         };
         let credential_data = minicbor::to_vec(credential_data)?;
 
@@ -96,8 +112,37 @@ impl CredentialsCreation {
         let res = CredentialAndPurposeKey {
             credential,
             purpose_key_attestation: issuer_purpose_key.attestation().clone(),
+            co_signatures: None,
         };
 
         Ok(res)
     }
+
+    /// Sign the data of a [`Credential`] issued by another Authority.
+    /// The [`Credential`] itself is not verified
+    pub async fn co_sign_credential(
+        &self,
+        co_signer: &Identifier,
+        credential_and_purpose_key: &CredentialAndPurposeKey,
+    ) -> Result<CredentialCoSignature> {
+        let co_signer_purpose_key = self
+            .purpose_keys_creation
+            .get_or_create_credential_purpose_key(co_signer)
+            .await?;
+
+        let versioned_data_hash = self
+            .verifying_vault
+            .sha256(&credential_and_purpose_key.credential.data)
+            .await?;
+
+        let signature = self
+            .credential_vault
+            .sign(co_signer_purpose_key.key(), &versioned_data_hash.0)
+            .await?;
+
+        Ok(CredentialCoSignature {
+            signature: signature.into(),
+            purpose_key_attestation: co_signer_purpose_key.attestation().clone(),
+        })
+    }
 }
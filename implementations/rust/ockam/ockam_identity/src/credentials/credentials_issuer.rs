@@ -1,6 +1,12 @@
-use crate::models::{Attributes, CredentialAndPurposeKey, CredentialSchemaIdentifier, Identifier};
+use crate::models::{
+    Attributes, CredentialAndPurposeKey, CredentialRouteConstraints, CredentialSchemaIdentifier,
+    Identifier,
+};
 use crate::utils::AttributesBuilder;
-use crate::{Credentials, IdentitiesRepository, IdentitySecureChannelLocalInfo};
+use crate::{
+    Credentials, CredentialsCoSigners, IdentitiesDelegation, IdentitiesRepository,
+    IdentitySecureChannelLocalInfo,
+};
 
 use ockam_core::api::{Method, RequestHeader, Response};
 use ockam_core::compat::boxed::Box;
@@ -22,6 +28,10 @@ pub const TRUST_CONTEXT_ID: &[u8] = b"trust_context_id";
 /// The same as above but in string format
 pub const TRUST_CONTEXT_ID_UTF8: &str = "trust_context_id";
 
+/// Name of the attribute identifying the Identity which delegated its attributes to the
+/// device Identity which is the subject of a credential
+pub const DELEGATED_BY: &[u8] = b"delegated_by";
+
 /// Identifier for the schema of a project credential
 pub const PROJECT_MEMBER_SCHEMA: CredentialSchemaIdentifier = CredentialSchemaIdentifier(1);
 
@@ -34,6 +44,9 @@ pub struct CredentialsIssuer {
     credentials: Arc<Credentials>,
     issuer: Identifier,
     subject_attributes: Attributes,
+    route_constraints: Option<CredentialRouteConstraints>,
+    identities_delegation: Option<Arc<IdentitiesDelegation>>,
+    co_signers: Option<Arc<CredentialsCoSigners>>,
 }
 
 impl CredentialsIssuer {
@@ -53,24 +66,56 @@ impl CredentialsIssuer {
             credentials,
             issuer: issuer.clone(),
             subject_attributes,
+            route_constraints: None,
+            identities_delegation: None,
+            co_signers: None,
         }
     }
 
+    /// Restrict the routes over which the issued credentials can be presented
+    pub fn with_route_constraints(mut self, route_constraints: CredentialRouteConstraints) -> Self {
+        self.route_constraints = Some(route_constraints);
+        self
+    }
+
+    /// Issue credentials to the devices having a valid delegation from a member. They get the
+    /// attributes of that member
+    pub fn with_delegations(mut self, identities_delegation: Arc<IdentitiesDelegation>) -> Self {
+        self.identities_delegation = Some(identities_delegation);
+        self
+    }
+
+    /// Get the issued credentials co-signed by other Authorities, when the credentials of this
+    /// issuer must reach a threshold of signatures
+    pub fn with_co_signers(mut self, co_signers: Arc<CredentialsCoSigners>) -> Self {
+        self.co_signers = Some(co_signers);
+        self
+    }
+
     async fn issue_credential(
         &self,
+        ctx: &Context,
         subject: &Identifier,
     ) -> Result<Option<CredentialAndPurposeKey>> {
-        let entry = match self
-            .identities_repository
-            .as_attributes_reader()
-            .get_attributes(subject)
-            .await?
-        {
+        let attributes_reader = self.identities_repository.as_attributes_reader();
+        let mut subject_attributes = self.subject_attributes.clone();
+        let entry = match attributes_reader.get_attributes(subject).await? {
             Some(entry) => entry,
-            None => return Ok(None),
+            None => match self.delegator(subject).await? {
+                Some(delegator) => match attributes_reader.get_attributes(&delegator).await? {
+                    Some(entry) => {
+                        subject_attributes.map.insert(
+                            DELEGATED_BY.to_vec().into(),
+                            delegator.to_string().as_bytes().to_vec().into(),
+                        );
+                        entry
+                    }
+                    None => return Ok(None),
+                },
+                None => return Ok(None),
+            },
         };
 
-        let mut subject_attributes = self.subject_attributes.clone();
         for (key, value) in entry.attrs().iter() {
             subject_attributes
                 .map
@@ -80,16 +125,33 @@ impl CredentialsIssuer {
         let credential = self
             .credentials
             .credentials_creation()
-            .issue_credential(
+            .issue_credential_with_route_constraints(
                 &self.issuer,
                 subject,
                 subject_attributes,
                 MAX_CREDENTIAL_VALIDITY,
+                self.route_constraints.clone(),
             )
             .await?;
 
+        let credential = match &self.co_signers {
+            Some(co_signers) => co_signers.co_sign(ctx, &self.issuer, credential).await?,
+            None => credential,
+        };
+
         Ok(Some(credential))
     }
+
+    /// Return the delegating Identity of a device Identity, if delegations are accepted
+    async fn delegator(&self, subject: &Identifier) -> Result<Option<Identifier>> {
+        match &self.identities_delegation {
+            Some(identities_delegation) => Ok(identities_delegation
+                .verify_delegation(subject)
+                .await?
+                .map(|delegation| delegation.delegator)),
+            None => Ok(None),
+        }
+    }
 }
 
 #[ockam_core::worker]
@@ -113,7 +175,7 @@ impl Worker for CredentialsIssuer {
             }
             let res = match (req.method(), req.path()) {
                 (Some(Method::Post), "/") | (Some(Method::Post), "/credential") => {
-                    match self.issue_credential(&from).await {
+                    match self.issue_credential(c, &from).await {
                         Ok(Some(crd)) => Response::ok(&req).body(crd).to_vec()?,
                         Ok(None) => {
                             // Again, this has already been checked by the access control, so if we
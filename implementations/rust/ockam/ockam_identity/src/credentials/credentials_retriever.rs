@@ -4,11 +4,12 @@ use tracing::debug;
 use tracing::trace;
 
 use ockam_core::compat::boxed::Box;
-use ockam_core::compat::sync::Arc;
+use ockam_core::compat::sync::{Arc, Mutex};
 use ockam_core::{async_trait, Address, Result, Route};
 use ockam_node::{Context, DEFAULT_TIMEOUT};
 
-use crate::models::CredentialAndPurposeKey;
+use crate::models::{CredentialAndPurposeKey, RevocationList, TimestampInSeconds};
+use crate::utils::now;
 use crate::{Identifier, SecureChannels, SecureClient};
 
 /// Trait for retrieving a credential for a given identity
@@ -20,6 +21,15 @@ pub trait CredentialsRetriever: Send + Sync + 'static {
         ctx: &Context,
         for_identity: &Identifier,
     ) -> Result<CredentialAndPurposeKey>;
+
+    /// Retrieve the list of the credentials revoked by the issuer, if it publishes one
+    async fn retrieve_revocation_list(
+        &self,
+        _ctx: &Context,
+        _for_identity: &Identifier,
+    ) -> Result<Option<RevocationList>> {
+        Ok(None)
+    }
 }
 
 /// Credentials retriever that retrieves a credential from memory
@@ -48,10 +58,42 @@ impl CredentialsRetriever for CredentialsMemoryRetriever {
     }
 }
 
+/// Result of the last requests sent to an authority, shared by the credentials retriever
+/// which sends these requests and the identities repository, which only keeps using
+/// expired attributes while the authority can't be reached
+#[derive(Clone, Default)]
+pub struct AuthorityStatus {
+    unreachable_since: Arc<Mutex<Option<TimestampInSeconds>>>,
+}
+
+impl AuthorityStatus {
+    /// Record that a request to the authority failed
+    pub fn request_failed(&self) {
+        if let Ok(mut unreachable_since) = self.unreachable_since.lock() {
+            if unreachable_since.is_none() {
+                *unreachable_since = now().ok();
+            }
+        }
+    }
+
+    /// Record that the authority answered a request
+    pub fn request_succeeded(&self) {
+        if let Ok(mut unreachable_since) = self.unreachable_since.lock() {
+            *unreachable_since = None;
+        }
+    }
+
+    /// Return the time of the first failed request, if the authority has not answered since
+    pub fn unreachable_since(&self) -> Option<TimestampInSeconds> {
+        self.unreachable_since.lock().ok().and_then(|u| *u)
+    }
+}
+
 /// Credentials retriever for credentials located on a different node
 pub struct RemoteCredentialsRetriever {
     secure_channels: Arc<SecureChannels>,
     issuer: RemoteCredentialsRetrieverInfo,
+    authority_status: AuthorityStatus,
 }
 
 impl RemoteCredentialsRetriever {
@@ -63,7 +105,23 @@ impl RemoteCredentialsRetriever {
         Self {
             secure_channels,
             issuer,
+            authority_status: AuthorityStatus::default(),
+        }
+    }
+
+    /// Record the result of each request to the issuer in a shared status
+    pub fn with_authority_status(mut self, authority_status: AuthorityStatus) -> Self {
+        self.authority_status = authority_status;
+        self
+    }
+
+    /// Record the result of a request to the issuer
+    fn record<T>(&self, result: Result<T>) -> Result<T> {
+        match &result {
+            Ok(_) => self.authority_status.request_succeeded(),
+            Err(_) => self.authority_status.request_failed(),
         }
+        result
     }
 
     async fn make_secure_client(
@@ -97,12 +155,32 @@ impl CredentialsRetriever for RemoteCredentialsRetriever {
         for_identity: &Identifier,
     ) -> Result<CredentialAndPurposeKey> {
         debug!("Getting credential from: {}", &self.issuer.route);
-        let client = self.make_secure_client(ctx, for_identity).await?;
-        let credential = client
-            .ask(ctx, "credential_issuer", Request::post("/"))
-            .await?
-            .success()?;
-        Ok(credential)
+        let credential: Result<CredentialAndPurposeKey> = async {
+            let client = self.make_secure_client(ctx, for_identity).await?;
+            client
+                .ask(ctx, "credential_issuer", Request::post("/"))
+                .await?
+                .success()
+        }
+        .await;
+        self.record(credential)
+    }
+
+    async fn retrieve_revocation_list(
+        &self,
+        ctx: &Context,
+        for_identity: &Identifier,
+    ) -> Result<Option<RevocationList>> {
+        debug!("Getting the revocation list from: {}", &self.issuer.route);
+        let revocation_list: Result<RevocationList> = async {
+            let client = self.make_secure_client(ctx, for_identity).await?;
+            client
+                .ask(ctx, "revocation_list", Request::get("/"))
+                .await?
+                .success()
+        }
+        .await;
+        Ok(Some(self.record(revocation_list)?))
     }
 }
 
@@ -68,13 +68,17 @@ impl CredentialsServer for CredentialsServerModule {
             .ask_with_local_info(ctx, Request::post(path).body(credential), None)
             .await?;
 
-        let their_id =
-            IdentitySecureChannelLocalInfo::find_info_from_list(&local_info)?.their_identity_id();
+        let info = IdentitySecureChannelLocalInfo::find_info_from_list(&local_info)?;
 
         let credential_and_purpose_key: CredentialAndPurposeKey = reply.success()?;
         self.credentials
             .credentials_verification()
-            .receive_presented_credential(&their_id, authorities, &credential_and_purpose_key)
+            .receive_presented_credential(
+                &info.their_identity_id(),
+                authorities,
+                &credential_and_purpose_key,
+                &info.presentation_route(),
+            )
             .await?;
 
         Ok(())
@@ -10,7 +10,7 @@ use ockam_node::Context;
 
 use crate::credentials::Credentials;
 use crate::models::{CredentialAndPurposeKey, Identifier};
-use crate::{IdentitySecureChannelLocalInfo, TrustContext};
+use crate::{IdentitySecureChannelLocalInfo, PresentationRoute, TrustContext};
 
 const TARGET: &str = "ockam::credential_exchange_worker::service";
 
@@ -44,6 +44,7 @@ impl CredentialsServerWorker {
         ctx: &mut Context,
         req: &RequestHeader,
         sender: Identifier,
+        route: PresentationRoute,
         dec: &mut Decoder<'_>,
     ) -> Result<Vec<u8>> {
         trace! {
@@ -80,6 +81,7 @@ impl CredentialsServerWorker {
                         &sender,
                         self.trust_context.authorities().await?.as_slice(),
                         &credential_and_purpose_key,
+                        &route,
                     )
                     .await;
 
@@ -112,6 +114,7 @@ impl CredentialsServerWorker {
                         &sender,
                         self.trust_context.authorities().await?.as_slice(),
                         &credential_and_purpose_key,
+                        &route,
                     )
                     .await;
 
@@ -173,10 +176,14 @@ impl Worker for CredentialsServerWorker {
             }
         };
 
-        let sender =
-            IdentitySecureChannelLocalInfo::find_info(msg.local_message())?.their_identity_id();
+        let info = IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+        let sender = info.their_identity_id();
+        let route = info.presentation_route();
 
-        let r = match self.handle_request(ctx, &req, sender, &mut dec).await {
+        let r = match self
+            .handle_request(ctx, &req, sender, route, &mut dec)
+            .await
+        {
             Ok(r) => r,
             // If an error occurs, send a response with the error code so the listener can
             // fail fast instead of failing silently here and force the listener to timeout.
@@ -2,15 +2,16 @@ use crate::identities::AttributesEntry;
 use crate::models::{CredentialAndPurposeKey, CredentialData, Identifier, PurposePublicKey};
 use crate::utils::now;
 use crate::{
-    CredentialAndPurposeKeyData, IdentitiesRepository, IdentityError, PurposeKeyVerification,
-    TimestampInSeconds,
+    AuthorityThreshold, AuthorityThresholds, CredentialAndPurposeKeyData, IdentitiesRepository,
+    IdentityError, PresentationRoute, PurposeKeyVerification, Revocations, TimestampInSeconds,
 };
 
-use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::collections::{BTreeMap, BTreeSet};
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 use ockam_vault::VaultForVerifyingSignatures;
+use tracing::warn;
 
 /// We allow Credentials to be created in the future related to this machine's time due to
 /// possible time dyssynchronization
@@ -21,6 +22,8 @@ pub struct CredentialsVerification {
     purpose_keys_verification: Arc<PurposeKeyVerification>,
     verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
     identities_repository: Arc<dyn IdentitiesRepository>,
+    revocations: Arc<Revocations>,
+    authority_thresholds: Arc<AuthorityThresholds>,
 }
 
 impl CredentialsVerification {
@@ -29,11 +32,15 @@ impl CredentialsVerification {
         purpose_keys_verification: Arc<PurposeKeyVerification>,
         verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
         identities_repository: Arc<dyn IdentitiesRepository>,
+        revocations: Arc<Revocations>,
+        authority_thresholds: Arc<AuthorityThresholds>,
     ) -> Self {
         Self {
             purpose_keys_verification,
             verifying_vault,
             identities_repository,
+            revocations,
+            authority_thresholds,
         }
     }
 
@@ -51,6 +58,32 @@ impl CredentialsVerification {
         expected_subject: Option<&Identifier>,
         authorities: &[Identifier],
         credential_and_purpose_key: &CredentialAndPurposeKey,
+    ) -> Result<CredentialAndPurposeKeyData> {
+        let data = self
+            .verify_issued_credential(expected_subject, authorities, credential_and_purpose_key)
+            .await?;
+
+        let issuer = &data.purpose_key_data.subject;
+        if let Some(authority_threshold) = self.authority_thresholds.get(issuer) {
+            self.verify_co_signatures(
+                issuer,
+                &authority_threshold,
+                &data,
+                credential_and_purpose_key,
+            )
+            .await?;
+        }
+
+        Ok(data)
+    }
+
+    /// Verify a [`Credential`] signed by its issuer, without requiring the co-signatures of
+    /// the [`AuthorityThreshold`] of the issuer. This is used by the co-signers of a [`Credential`]
+    pub async fn verify_issued_credential(
+        &self,
+        expected_subject: Option<&Identifier>,
+        authorities: &[Identifier],
+        credential_and_purpose_key: &CredentialAndPurposeKey,
     ) -> Result<CredentialAndPurposeKeyData> {
         let purpose_key_data = self
             .purpose_keys_verification
@@ -137,7 +170,18 @@ impl CredentialsVerification {
 
         if credential_data.expires_at < now {
             // Credential expired
-            return Err(IdentityError::CredentialVerificationFailed.into());
+            return Err(IdentityError::CredentialExpired.into());
+        }
+
+        if let Some(subject) = &credential_data.subject {
+            if self.revocations.is_revoked(
+                &purpose_key_data.subject,
+                subject,
+                credential_data.created_at,
+            ) {
+                warn!(%subject, "a revoked credential was presented");
+                return Err(IdentityError::CredentialRevoked.into());
+            }
         }
 
         if let Some(_subject_latest_change_hash) = &credential_data.subject_latest_change_hash {
@@ -163,12 +207,82 @@ impl CredentialsVerification {
         })
     }
 
+    /// Check that a [`Credential`] is signed by enough distinct signers of an [`AuthorityThreshold`].
+    /// The co-signatures which are invalid or made by other identities are not counted
+    async fn verify_co_signatures(
+        &self,
+        issuer: &Identifier,
+        authority_threshold: &AuthorityThreshold,
+        data: &CredentialAndPurposeKeyData,
+        credential_and_purpose_key: &CredentialAndPurposeKey,
+    ) -> Result<()> {
+        let mut signers = BTreeSet::new();
+        if authority_threshold.is_signer(issuer) {
+            signers.insert(issuer.clone());
+        }
+
+        let versioned_data_hash = self
+            .verifying_vault
+            .sha256(&credential_and_purpose_key.credential.data)
+            .await?;
+
+        for co_signature in credential_and_purpose_key.co_signatures() {
+            let purpose_key_data = match self
+                .purpose_keys_verification
+                .verify_purpose_key_attestation(None, &co_signature.purpose_key_attestation)
+                .await
+            {
+                Ok(purpose_key_data) => purpose_key_data,
+                Err(_) => continue,
+            };
+
+            if !authority_threshold.is_signer(&purpose_key_data.subject)
+                || data.credential_data.created_at < purpose_key_data.created_at
+                || data.credential_data.expires_at > purpose_key_data.expires_at
+            {
+                continue;
+            }
+
+            let public_key = match purpose_key_data.public_key {
+                PurposePublicKey::CredentialSigning(public_key) => public_key.into(),
+                PurposePublicKey::SecureChannelStatic(_) => continue,
+            };
+
+            if self
+                .verifying_vault
+                .verify_signature(
+                    &public_key,
+                    &versioned_data_hash.0,
+                    &co_signature.signature.clone().into(),
+                )
+                .await?
+            {
+                signers.insert(purpose_key_data.subject);
+            }
+        }
+
+        if signers.len() < authority_threshold.threshold {
+            warn!(
+                %issuer,
+                signers = signers.len(),
+                threshold = authority_threshold.threshold,
+                "a credential doesn't have enough signatures"
+            );
+            return Err(IdentityError::CredentialThresholdNotReached.into());
+        }
+        Ok(())
+    }
+
     /// Receive someone's [`Credential`]: verify and put attributes from it to the storage
+    ///
+    /// `route` is the route over which the Credential was presented, it must be allowed by the
+    /// route constraints of the Credential
     pub async fn receive_presented_credential(
         &self,
         subject: &Identifier,
         authorities: &[Identifier],
         credential_and_purpose_key_attestation: &CredentialAndPurposeKey,
+        route: &PresentationRoute,
     ) -> Result<()> {
         let credential_data = self
             .verify_credential(
@@ -178,6 +292,13 @@ impl CredentialsVerification {
             )
             .await?;
 
+        if let Some(route_constraints) = &credential_data.credential_data.route_constraints {
+            if !route_constraints.allows(route) {
+                warn!(%subject, %route, "a credential was presented over a route which is not allowed");
+                return Err(IdentityError::CredentialRouteNotAllowed.into());
+            }
+        }
+
         let map = credential_data.credential_data.subject_attributes.map;
         let map: BTreeMap<_, _> = map
             .into_iter()
@@ -1,6 +1,9 @@
+mod attributes_schema;
 mod authority_service;
+mod authority_threshold;
 #[allow(clippy::module_inception)]
 mod credentials;
+mod credentials_co_signer;
 mod credentials_creation;
 mod credentials_issuer;
 mod credentials_retriever;
@@ -8,14 +11,21 @@ mod credentials_server;
 mod credentials_server_worker;
 mod credentials_verification;
 mod one_time_code;
+mod presentation_route;
+mod revocations;
 mod trust_context;
 
+pub use attributes_schema::*;
 pub use authority_service::*;
+pub use authority_threshold::*;
 pub use credentials::*;
+pub use credentials_co_signer::*;
 pub use credentials_creation::*;
 pub use credentials_issuer::*;
 pub use credentials_retriever::*;
 pub use credentials_server::*;
 pub use credentials_verification::*;
 pub use one_time_code::*;
+pub use presentation_route::*;
+pub use revocations::*;
 pub use trust_context::*;
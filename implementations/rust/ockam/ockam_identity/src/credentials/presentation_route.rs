@@ -0,0 +1,76 @@
+use core::fmt::{Display, Formatter};
+
+use crate::models::{CredentialRouteConstraints, Identifier};
+
+/// Route over which a [`crate::models::Credential`] was presented to this node
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PresentationRoute {
+    /// The secure channel was established directly over a transport connection
+    Direct,
+    /// The secure channel was carried by another secure channel with the given identity,
+    /// for example the node of a project relay
+    Via(Identifier),
+}
+
+impl PresentationRoute {
+    /// Return the route of a secure channel, given the identity of the secure channel carrying
+    /// its handshake messages, if any
+    pub fn new(via: Option<Identifier>) -> Self {
+        match via {
+            Some(via) => PresentationRoute::Via(via),
+            None => PresentationRoute::Direct,
+        }
+    }
+}
+
+impl Display for PresentationRoute {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PresentationRoute::Direct => write!(f, "direct"),
+            PresentationRoute::Via(identifier) => write!(f, "via {identifier}"),
+        }
+    }
+}
+
+impl CredentialRouteConstraints {
+    /// Return true if a Credential with these constraints can be presented over `route`
+    pub fn allows(&self, route: &PresentationRoute) -> bool {
+        match route {
+            PresentationRoute::Direct => !self.deny_direct && self.via.is_empty(),
+            PresentationRoute::Via(identifier) => {
+                self.via.is_empty() || self.via.contains(identifier)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    #[test]
+    fn route_constraints() {
+        let relay = Identifier::from_str("Ie92f183eb4c324804ef4d62962dea94cf095a265").unwrap();
+        let other = Identifier::from_str("Ibb37445cacb3ca7a20040a9b36469e321a57d2cd").unwrap();
+
+        let none = CredentialRouteConstraints::default();
+        assert!(none.allows(&PresentationRoute::Direct));
+        assert!(none.allows(&PresentationRoute::Via(other.clone())));
+
+        let deny_direct = CredentialRouteConstraints {
+            via: vec![],
+            deny_direct: true,
+        };
+        assert!(!deny_direct.allows(&PresentationRoute::Direct));
+        assert!(deny_direct.allows(&PresentationRoute::Via(other.clone())));
+
+        let via_relay = CredentialRouteConstraints {
+            via: vec![relay.clone()],
+            deny_direct: false,
+        };
+        assert!(!via_relay.allows(&PresentationRoute::Direct));
+        assert!(via_relay.allows(&PresentationRoute::Via(relay)));
+        assert!(!via_relay.allows(&PresentationRoute::Via(other)));
+    }
+}
@@ -0,0 +1,87 @@
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::RwLock;
+
+use crate::models::{Identifier, RevocationList, RevokedSubject, TimestampInSeconds};
+
+/// Local cache of the [`RevocationList`]s published by Authorities.
+///
+/// It is consulted before trusting a presented Credential: a Credential is revoked if its
+/// subject has been revoked by the Authority which issued it, after the Credential creation
+#[derive(Default)]
+pub struct Revocations {
+    /// Revocation time of each revoked subject, for each Authority
+    revoked: RwLock<BTreeMap<Identifier, BTreeMap<Identifier, TimestampInSeconds>>>,
+}
+
+impl Revocations {
+    /// Create an empty revocations cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Revoke the credentials created by an Authority for a subject, up to a given time
+    pub fn revoke(
+        &self,
+        authority: &Identifier,
+        subject: &Identifier,
+        revoked_at: TimestampInSeconds,
+    ) {
+        let mut revoked = self.revoked.write().unwrap();
+        let revoked_subjects = revoked.entry(authority.clone()).or_default();
+        let revoked_at = match revoked_subjects.get(subject) {
+            Some(previous) if *previous > revoked_at => *previous,
+            _ => revoked_at,
+        };
+        revoked_subjects.insert(subject.clone(), revoked_at);
+    }
+
+    /// Replace the revocation list of an Authority
+    pub fn update(&self, authority: &Identifier, revocation_list: RevocationList) {
+        let revoked_subjects = revocation_list
+            .revoked
+            .into_iter()
+            .map(|r| (r.subject, r.revoked_at))
+            .collect();
+        self.revoked
+            .write()
+            .unwrap()
+            .insert(authority.clone(), revoked_subjects);
+    }
+
+    /// Return true if a Credential created by an Authority for a subject at a given time
+    /// has been revoked
+    pub fn is_revoked(
+        &self,
+        authority: &Identifier,
+        subject: &Identifier,
+        created_at: TimestampInSeconds,
+    ) -> bool {
+        self.revoked
+            .read()
+            .unwrap()
+            .get(authority)
+            .and_then(|revoked_subjects| revoked_subjects.get(subject))
+            .map(|revoked_at| created_at <= *revoked_at)
+            .unwrap_or(false)
+    }
+
+    /// Return the revocation list of an Authority
+    pub fn revocation_list(&self, authority: &Identifier) -> RevocationList {
+        let revoked = self
+            .revoked
+            .read()
+            .unwrap()
+            .get(authority)
+            .map(|revoked_subjects| {
+                revoked_subjects
+                    .iter()
+                    .map(|(subject, revoked_at)| RevokedSubject {
+                        subject: subject.clone(),
+                        revoked_at: *revoked_at,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        RevocationList { revoked }
+    }
+}
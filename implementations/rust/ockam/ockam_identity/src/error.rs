@@ -50,6 +50,37 @@ pub enum IdentityError {
     InvalidHex,
     /// Secret Key doesn't correspond to the Identity
     WrongSecretKey,
+    /// The Credential can't be presented over this route
+    CredentialRouteNotAllowed,
+    /// The signature of a message received over a Secure Channel is invalid
+    MessageSignatureVerificationFailed,
+    /// The other party of a Secure Channel hides its Identity until this party reveals its own
+    SecureChannelPeerIdentityHidden,
+    /// The Credential has been revoked by its Authority
+    CredentialRevoked,
+    /// The handshake pattern requested by the initiator of a Secure Channel is not accepted by the listener
+    HandshakePatternNotAccepted,
+    /// The other party of a Secure Channel doesn't follow the requested handshake pattern,
+    /// or doesn't know the same pre-shared key
+    HandshakePatternMismatch,
+    /// The handshake pattern requires a pre-shared key
+    MissingPreSharedKey,
+    /// The delegation recorded by a device Identity is invalid, revoked or expired
+    DeviceDelegationVerificationFailed,
+    /// The Credential is not signed by enough Authorities of the threshold required by its issuer
+    CredentialThresholdNotReached,
+    /// The resumption ticket of a Secure Channel is unknown, expired or already used
+    InvalidResumptionTicket,
+    /// The handshake extensions requested by the initiator of a Secure Channel are not supported
+    /// by the listener
+    UnsupportedHandshakeExtension,
+    /// The credentials of the other party of a Secure Channel don't have the attributes required
+    /// by the listener
+    SecureChannelRequiredAttributeMissing,
+    /// An early message can only be sent when resuming a Secure Channel without signed messages
+    EarlyMessageNotAllowed,
+    /// The Credential has expired
+    CredentialExpired,
 }
 
 impl ockam_core::compat::error::Error for IdentityError {}
@@ -1,8 +1,9 @@
 use crate::identities::{IdentitiesKeys, IdentitiesRepository};
 use crate::purpose_keys::storage::{PurposeKeysRepository, PurposeKeysStorage};
 use crate::{
-    Credentials, CredentialsServer, CredentialsServerModule, Identifier, IdentitiesBuilder,
-    IdentitiesCreation, IdentitiesReader, IdentitiesStorage, Identity, PurposeKeys, Vault,
+    AuthorityThresholds, Credentials, CredentialsServer, CredentialsServerModule, Identifier,
+    IdentitiesBuilder, IdentitiesCreation, IdentitiesDelegation, IdentitiesReader,
+    IdentitiesStorage, Identity, IdentityHistoryComparison, PurposeKeys, Revocations, Vault,
 };
 
 use ockam_core::compat::sync::Arc;
@@ -15,6 +16,8 @@ pub struct Identities {
     vault: Vault,
     identities_repository: Arc<dyn IdentitiesRepository>,
     purpose_keys_repository: Arc<dyn PurposeKeysRepository>,
+    revocations: Arc<Revocations>,
+    authority_thresholds: Arc<AuthorityThresholds>,
 }
 
 impl Identities {
@@ -28,6 +31,16 @@ impl Identities {
         self.identities_repository.clone()
     }
 
+    /// Return the revoked credentials known to this node
+    pub fn revocations(&self) -> Arc<Revocations> {
+        self.revocations.clone()
+    }
+
+    /// Return the signature thresholds required for the credentials of some authorities
+    pub fn authority_thresholds(&self) -> Arc<AuthorityThresholds> {
+        self.authority_thresholds.clone()
+    }
+
     /// Return the purpose keys repository
     pub fn purpose_keys_repository(&self) -> Arc<dyn PurposeKeysRepository> {
         self.purpose_keys_repository.clone()
@@ -44,6 +57,45 @@ impl Identities {
         .await
     }
 
+    /// Verify all the changes of an exported change history, without storing the [`Identity`].
+    /// The identifier of the [`Identity`] must match the expected identifier, if one is given
+    pub async fn verify_change_history(
+        &self,
+        expected_identifier: Option<&Identifier>,
+        data: &[u8],
+    ) -> Result<Identity> {
+        Identity::import(
+            expected_identifier,
+            data,
+            self.vault.verifying_vault.clone(),
+        )
+        .await
+    }
+
+    /// Compare an [`Identity`] to the version stored in the repository.
+    /// Return None if the [`Identity`] is not known yet
+    pub async fn compare_with_known_identity(
+        &self,
+        identity: &Identity,
+    ) -> Result<Option<IdentityHistoryComparison>> {
+        match self
+            .identities_repository
+            .retrieve_identity(identity.identifier())
+            .await?
+        {
+            Some(change_history) => {
+                let known_identity = Identity::import_from_change_history(
+                    Some(identity.identifier()),
+                    change_history,
+                    self.vault.verifying_vault.clone(),
+                )
+                .await?;
+                Ok(Some(identity.compare(&known_identity)))
+            }
+            None => Ok(None),
+        }
+    }
+
     /// Export an [`Identity`] from the repository
     pub async fn export_identity(&self, identifier: &Identifier) -> Result<Vec<u8>> {
         self.get_identity(identifier).await?.export()
@@ -76,6 +128,15 @@ impl Identities {
         ))
     }
 
+    /// Return the identities delegation service
+    pub fn identities_delegation(&self) -> Arc<IdentitiesDelegation> {
+        Arc::new(IdentitiesDelegation::new(
+            self.repository(),
+            self.vault.identity_vault.clone(),
+            self.vault.verifying_vault.clone(),
+        ))
+    }
+
     /// Return the identities reader
     pub fn identities_reader(&self) -> Arc<dyn IdentitiesReader> {
         self.repository().as_identities_reader()
@@ -88,6 +149,8 @@ impl Identities {
             self.vault.verifying_vault.clone(),
             self.purpose_keys(),
             self.identities_repository.clone(),
+            self.revocations.clone(),
+            self.authority_thresholds.clone(),
         ))
     }
 
@@ -103,11 +166,15 @@ impl Identities {
         vault: Vault,
         identities_repository: Arc<dyn IdentitiesRepository>,
         purpose_keys_repository: Arc<dyn PurposeKeysRepository>,
+        revocations: Arc<Revocations>,
+        authority_thresholds: Arc<AuthorityThresholds>,
     ) -> Identities {
         Identities {
             vault,
             identities_repository,
             purpose_keys_repository,
+            revocations,
+            authority_thresholds,
         }
     }
 
@@ -117,6 +184,8 @@ impl Identities {
             vault: Vault::create(),
             repository: IdentitiesStorage::create(),
             purpose_keys_repository: PurposeKeysStorage::create(),
+            revocations: Arc::new(Revocations::new()),
+            authority_thresholds: Arc::new(AuthorityThresholds::new()),
         }
     }
 }
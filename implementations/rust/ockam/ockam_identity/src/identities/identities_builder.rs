@@ -1,7 +1,7 @@
 use crate::identities::{Identities, IdentitiesRepository, IdentitiesStorage};
 use crate::purpose_keys::storage::{PurposeKeysRepository, PurposeKeysStorage};
 use crate::storage::Storage;
-use crate::{Vault, VaultStorage};
+use crate::{AuthorityThresholds, Revocations, Vault, VaultStorage};
 
 use ockam_core::compat::sync::Arc;
 
@@ -11,6 +11,8 @@ pub struct IdentitiesBuilder {
     pub(crate) vault: Vault,
     pub(crate) repository: Arc<dyn IdentitiesRepository>,
     pub(crate) purpose_keys_repository: Arc<dyn PurposeKeysRepository>,
+    pub(crate) revocations: Arc<Revocations>,
+    pub(crate) authority_thresholds: Arc<AuthorityThresholds>,
 }
 
 /// Return a default identities
@@ -56,12 +58,29 @@ impl IdentitiesBuilder {
         self
     }
 
+    /// Set the revocations cache consulted when verifying credentials
+    pub fn with_revocations(mut self, revocations: Arc<Revocations>) -> Self {
+        self.revocations = revocations;
+        self
+    }
+
+    /// Set the signature thresholds enforced when verifying credentials
+    pub fn with_authority_thresholds(
+        mut self,
+        authority_thresholds: Arc<AuthorityThresholds>,
+    ) -> Self {
+        self.authority_thresholds = authority_thresholds;
+        self
+    }
+
     /// Build identities
     pub fn build(self) -> Arc<Identities> {
         Arc::new(Identities::new(
             self.vault,
             self.repository,
             self.purpose_keys_repository,
+            self.revocations,
+            self.authority_thresholds,
         ))
     }
 }
@@ -0,0 +1,197 @@
+use ockam_core::compat::string::ToString;
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_vault::{SigningKeyType, VaultForSigning, VaultForVerifyingSignatures};
+
+use crate::models::{
+    DeviceDelegation, DeviceDelegationData, Identifier, TimestampInSeconds, VersionedData,
+};
+use crate::utils::now;
+use crate::IdentityOptions;
+use crate::{IdentitiesCreation, IdentitiesKeys, IdentitiesRepository, Identity, IdentityError};
+
+/// We allow delegations to be created in the future related to this machine's time due to
+/// possible time dyssynchronization
+const MAX_ALLOWED_TIME_DRIFT: TimestampInSeconds = TimestampInSeconds(5);
+
+/// This struct supports the delegation of an Identity to devices.
+///
+/// Each device gets its own Identity, with its own key. The first change of that Identity
+/// records a [`DeviceDelegation`] signed by the delegating Identity, so that the device can act
+/// on behalf of the delegating Identity without having access to its key
+pub struct IdentitiesDelegation {
+    repository: Arc<dyn IdentitiesRepository>,
+    identity_vault: Arc<dyn VaultForSigning>,
+    verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
+}
+
+impl IdentitiesDelegation {
+    /// Create a new identities delegation module
+    pub fn new(
+        repository: Arc<dyn IdentitiesRepository>,
+        identity_vault: Arc<dyn VaultForSigning>,
+        verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
+    ) -> Self {
+        Self {
+            repository,
+            identity_vault,
+            verifying_vault,
+        }
+    }
+
+    /// Create and store a new Identity for a device, with a delegation signed by the latest key
+    /// of the delegating Identity. The delegation and the device Identity expire after the `ttl`
+    pub async fn delegate_to_device(
+        &self,
+        delegator: &Identifier,
+        device_name: &str,
+        ttl: TimestampInSeconds,
+    ) -> Result<Identity> {
+        let delegator_identity = self.get_identity(delegator).await?;
+        let latest_change = delegator_identity.get_latest_change()?;
+
+        let created_at = now()?;
+        let expires_at = created_at + ttl;
+        if expires_at > latest_change.data().expires_at {
+            return Err(Error::new(
+                Origin::Identity,
+                Kind::Invalid,
+                "the delegation can't expire after the key of the delegating identity",
+            ));
+        }
+
+        let device_key = self
+            .identity_vault
+            .generate_signing_secret_key(SigningKeyType::EdDSACurve25519)
+            .await?;
+        let device_public_key = self
+            .identity_vault
+            .get_verifying_public_key(&device_key)
+            .await?;
+
+        let delegation_data = DeviceDelegationData {
+            delegator: delegator.clone(),
+            delegator_latest_change_hash: latest_change.change_hash().clone(),
+            device_public_key: device_public_key.into(),
+            device_name: device_name.to_string(),
+            created_at,
+            expires_at,
+        };
+        let versioned_data = VersionedData {
+            version: 1,
+            data: minicbor::to_vec(&delegation_data)?,
+        };
+        let versioned_data = minicbor::to_vec(&versioned_data)?;
+        let hash = self.verifying_vault.sha256(&versioned_data).await?;
+
+        let delegator_key = self
+            .identities_keys()
+            .get_secret_key(&delegator_identity)
+            .await?;
+        let signature = self.identity_vault.sign(&delegator_key, &hash.0).await?;
+        let delegation = DeviceDelegation {
+            data: versioned_data,
+            signature: signature.into(),
+        };
+
+        let options = IdentityOptions::new(device_key, false, created_at, expires_at)
+            .with_delegation(delegation);
+        self.identities_creation()
+            .create_identity_with_options(options)
+            .await
+    }
+
+    /// Return the data of the delegation recorded by an Identity, if it has one and if it
+    /// is valid: it must have been signed by a key of the delegating Identity which has not been
+    /// revoked since, and it must not have expired
+    pub async fn verify_delegation(
+        &self,
+        identifier: &Identifier,
+    ) -> Result<Option<DeviceDelegationData>> {
+        let identity = self.get_identity(identifier).await?;
+        let delegation = match identity
+            .changes()
+            .first()
+            .and_then(|change| change.data().delegation.clone())
+        {
+            Some(delegation) => delegation,
+            None => return Ok(None),
+        };
+
+        let versioned_data_hash = self.verifying_vault.sha256(&delegation.data).await?;
+        let versioned_data = delegation.get_versioned_data()?;
+        if versioned_data.version != 1 {
+            return Err(IdentityError::DeviceDelegationVerificationFailed.into());
+        }
+        let delegation_data = DeviceDelegationData::get_data(&versioned_data)?;
+
+        let delegator = self.get_identity(&delegation_data.delegator).await?;
+        let changes = delegator.changes();
+        let position = changes
+            .iter()
+            .position(|change| {
+                change.change_hash() == &delegation_data.delegator_latest_change_hash
+            })
+            .ok_or(IdentityError::DeviceDelegationVerificationFailed)?;
+
+        if changes[position + 1..]
+            .iter()
+            .any(|change| change.data().revoke_all_purpose_keys)
+        {
+            // The delegating Identity revoked everything signed by its previous keys
+            return Err(IdentityError::DeviceDelegationVerificationFailed.into());
+        }
+
+        let now = now()?;
+        if delegation_data.created_at > now
+            && delegation_data.created_at - now > MAX_ALLOWED_TIME_DRIFT
+        {
+            // The delegation can't be created in the future
+            return Err(IdentityError::DeviceDelegationVerificationFailed.into());
+        }
+        if delegation_data.expires_at < now {
+            // The delegation expired
+            return Err(IdentityError::DeviceDelegationVerificationFailed.into());
+        }
+
+        if !self
+            .verifying_vault
+            .verify_signature(
+                changes[position].primary_public_key(),
+                &versioned_data_hash.0,
+                &delegation.signature.into(),
+            )
+            .await?
+        {
+            return Err(IdentityError::DeviceDelegationVerificationFailed.into());
+        }
+
+        Ok(Some(delegation_data))
+    }
+}
+
+/// Private functions
+impl IdentitiesDelegation {
+    async fn get_identity(&self, identifier: &Identifier) -> Result<Identity> {
+        let change_history = self.repository.get_identity(identifier).await?;
+        Identity::import_from_change_history(
+            Some(identifier),
+            change_history,
+            self.verifying_vault.clone(),
+        )
+        .await
+    }
+
+    fn identities_keys(&self) -> IdentitiesKeys {
+        IdentitiesKeys::new(self.identity_vault.clone(), self.verifying_vault.clone())
+    }
+
+    fn identities_creation(&self) -> IdentitiesCreation {
+        IdentitiesCreation::new(
+            self.repository.clone(),
+            self.identity_vault.clone(),
+            self.verifying_vault.clone(),
+        )
+    }
+}
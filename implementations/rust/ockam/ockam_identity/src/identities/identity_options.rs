@@ -1,3 +1,4 @@
+use crate::models::DeviceDelegation;
 use crate::TimestampInSeconds;
 use ockam_vault::SigningSecretKeyHandle;
 
@@ -7,6 +8,7 @@ pub struct IdentityOptions {
     pub(super) revoke_all_purpose_keys: bool,
     pub(super) created_at: TimestampInSeconds,
     pub(super) expires_at: TimestampInSeconds,
+    pub(super) delegation: Option<DeviceDelegation>,
 }
 
 impl IdentityOptions {
@@ -22,9 +24,16 @@ impl IdentityOptions {
             revoke_all_purpose_keys,
             created_at,
             expires_at,
+            delegation: None,
         }
     }
 
+    /// Record a delegation to a device in the created Identity
+    pub fn with_delegation(mut self, delegation: DeviceDelegation) -> Self {
+        self.delegation = Some(delegation);
+        self
+    }
+
     /// New key
     pub fn signing_secret_key_handle(&self) -> &SigningSecretKeyHandle {
         &self.signing_secret_key_handle
@@ -2,6 +2,7 @@
 mod identities;
 mod identities_builder;
 mod identities_creation;
+mod identities_delegation;
 mod identity_builder;
 mod identity_keys;
 mod identity_options;
@@ -12,6 +13,7 @@ pub mod storage;
 pub use identities::*;
 pub use identities_builder::*;
 pub use identities_creation::*;
+pub use identities_delegation::*;
 pub use identity_builder::*;
 pub use identity_keys::*;
 pub use identity_options::*;
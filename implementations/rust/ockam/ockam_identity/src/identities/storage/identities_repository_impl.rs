@@ -1,24 +1,37 @@
+use core::time::Duration;
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::string::ToString;
-use ockam_core::compat::sync::Arc;
+use ockam_core::compat::sync::{Arc, Mutex};
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
+use tracing::warn;
 
+use crate::credentials::AuthorityStatus;
 use crate::identity::IdentityConstants;
-use crate::models::{ChangeHistory, Identifier};
+use crate::models::{ChangeHistory, Identifier, TimestampInSeconds};
 use crate::storage::{InMemoryStorage, Storage};
-use crate::utils::now;
+use crate::utils::{add_seconds, now};
 use crate::{
     AttributesEntry, IdentitiesReader, IdentitiesRepository, IdentitiesWriter,
     IdentityAttributesReader, IdentityAttributesWriter,
 };
 
+/// Maximum grace period during which expired attributes can still be used
+pub const MAX_ATTRIBUTES_GRACE_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
 /// Implementation of `IdentityAttributes` trait based on an underlying `Storage`
 #[derive(Clone)]
 pub struct IdentitiesStorage {
     storage: Arc<dyn Storage>,
+    /// Time during which the attributes attested by an authority are still returned
+    /// once they have expired, while the authority can't be reached
+    grace_period: Duration,
+    authority_status: AuthorityStatus,
+    /// Identities currently authorized with expired attributes, with the expiration time of
+    /// these attributes, so that each use of the grace period is logged once
+    in_grace_period: Arc<Mutex<BTreeMap<Identifier, TimestampInSeconds>>>,
 }
 
 #[async_trait]
@@ -43,7 +56,59 @@ impl IdentitiesRepository for IdentitiesStorage {
 impl IdentitiesStorage {
     /// Create a new storage for attributes
     pub fn new(storage: Arc<dyn Storage>) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            grace_period: Duration::ZERO,
+            authority_status: AuthorityStatus::default(),
+            in_grace_period: Default::default(),
+        }
+    }
+
+    /// Keep returning the attributes attested by an authority for some time after they have
+    /// expired, but only once a request to the authority has failed, as recorded in
+    /// `authority_status`, and until the authority answers again.
+    /// The grace period is bounded by [`MAX_ATTRIBUTES_GRACE_PERIOD`]
+    pub fn with_grace_period(
+        mut self,
+        grace_period: Duration,
+        authority_status: AuthorityStatus,
+    ) -> Self {
+        self.grace_period = grace_period.min(MAX_ATTRIBUTES_GRACE_PERIOD);
+        self.authority_status = authority_status;
+        self
+    }
+
+    /// Return true if expired attributes can still be used, and log the first use of
+    /// the grace period for an identity
+    fn is_in_grace_period(
+        &self,
+        identity_id: &Identifier,
+        entry: &AttributesEntry,
+        expired_at: TimestampInSeconds,
+        now: TimestampInSeconds,
+    ) -> bool {
+        let unreachable_since = match self.authority_status.unreachable_since() {
+            Some(unreachable_since) => unreachable_since,
+            None => return false,
+        };
+        if entry.attested_by().is_none()
+            || now >= add_seconds(&expired_at, self.grace_period.as_secs())
+        {
+            return false;
+        }
+        if let Ok(mut in_grace_period) = self.in_grace_period.lock() {
+            if in_grace_period.insert(identity_id.clone(), expired_at) != Some(expired_at) {
+                warn!(
+                    identifier = %identity_id,
+                    attested_by = ?entry.attested_by().map(|i| i.to_string()),
+                    expired_at = *expired_at,
+                    grace_period_ends_at = *add_seconds(&expired_at, self.grace_period.as_secs()),
+                    authority_unreachable_since = *unreachable_since,
+                    "authorizing an identity with expired attributes during the grace period"
+                );
+            }
+        }
+        true
     }
 
     /// Create a new storage for attributes
@@ -70,9 +135,15 @@ impl IdentityAttributesReader for IdentitiesStorage {
         let now = now()?;
         match entry.expires() {
             Some(exp) if exp <= now => {
+                if self.is_in_grace_period(identity_id, &entry, exp, now) {
+                    return Ok(Some(entry));
+                }
                 self.storage
                     .del(&id, IdentityConstants::ATTRIBUTES_KEY)
                     .await?;
+                if let Ok(mut in_grace_period) = self.in_grace_period.lock() {
+                    in_grace_period.remove(identity_id);
+                }
                 Ok(None)
             }
             _ => Ok(Some(entry)),
@@ -168,3 +239,78 @@ impl IdentitiesReader for IdentitiesStorage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn expired_attributes_are_kept_during_the_grace_period() -> Result<()> {
+        let subject = Identifier::try_from("I0000000000000000000000000000000000000001")?;
+        let authority = Identifier::try_from("I0000000000000000000000000000000000000002")?;
+        let expired_at = TimestampInSeconds(*now()? - 60);
+        let attributes = BTreeMap::from([(b"role".to_vec(), b"member".to_vec())]);
+
+        // the authority can't be reached
+        let authority_status = AuthorityStatus::default();
+        authority_status.request_failed();
+        let repository = IdentitiesStorage::new(InMemoryStorage::create())
+            .with_grace_period(Duration::from_secs(3600), authority_status);
+        let entry = AttributesEntry::new(
+            attributes.clone(),
+            expired_at,
+            Some(expired_at),
+            Some(authority),
+        );
+        repository.put_attributes(&subject, entry.clone()).await?;
+        assert_eq!(repository.get_attributes(&subject).await?, Some(entry));
+
+        // the attributes which are not attested by an authority have no grace period
+        let entry = AttributesEntry::new(attributes, expired_at, Some(expired_at), None);
+        repository.put_attributes(&subject, entry).await?;
+        assert_eq!(repository.get_attributes(&subject).await?, None);
+
+        // without a grace period, the expired attributes are deleted
+        let repository = IdentitiesStorage::create();
+        let entry = AttributesEntry::new(
+            BTreeMap::new(),
+            expired_at,
+            Some(expired_at),
+            Some(subject.clone()),
+        );
+        repository.put_attributes(&subject, entry).await?;
+        assert_eq!(repository.get_attributes(&subject).await?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn expired_attributes_are_only_kept_while_the_authority_is_unreachable() -> Result<()> {
+        let subject = Identifier::try_from("I0000000000000000000000000000000000000001")?;
+        let authority = Identifier::try_from("I0000000000000000000000000000000000000002")?;
+        let expired_at = TimestampInSeconds(*now()? - 60);
+        let entry = AttributesEntry::new(
+            BTreeMap::from([(b"role".to_vec(), b"member".to_vec())]),
+            expired_at,
+            Some(expired_at),
+            Some(authority),
+        );
+        let authority_status = AuthorityStatus::default();
+        let repository = IdentitiesStorage::new(InMemoryStorage::create())
+            .with_grace_period(Duration::from_secs(3600), authority_status.clone());
+
+        // no request to the authority has failed: the expired attributes are deleted
+        repository.put_attributes(&subject, entry.clone()).await?;
+        assert_eq!(repository.get_attributes(&subject).await?, None);
+
+        // the authority can't be reached: the expired attributes are kept
+        authority_status.request_failed();
+        assert!(authority_status.unreachable_since().is_some());
+        repository.put_attributes(&subject, entry.clone()).await?;
+        assert_eq!(repository.get_attributes(&subject).await?, Some(entry));
+
+        // the authority answers again: the expired attributes are deleted
+        authority_status.request_succeeded();
+        assert_eq!(repository.get_attributes(&subject).await?, None);
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
-use crate::models::{Change, ChangeData, ChangeHash, ChangeSignature, CHANGE_HASH_LEN};
+use crate::models::{
+    Change, ChangeData, ChangeHash, ChangeSignature, DeviceDelegationData, CHANGE_HASH_LEN,
+};
 use crate::verified_change::VerifiedChange;
 use crate::{Identity, IdentityError};
 use arrayref::array_ref;
@@ -93,9 +95,23 @@ impl Identity {
                     // Corrupted changes sequence
                     return Err(IdentityError::IdentityVerificationFailed.into());
                 }
+
+                if change_details.change_data.delegation.is_some() {
+                    // A delegation can only be recorded when the Identity is created
+                    return Err(IdentityError::IdentityVerificationFailed.into());
+                }
             } else if change_details.change_data.previous_change.is_some() {
                 // Should be empty
                 return Err(IdentityError::IdentityVerificationFailed.into());
+            } else if let Some(delegation) = &change_details.change_data.delegation {
+                let delegation_data =
+                    DeviceDelegationData::get_data(&delegation.get_versioned_data()?)?;
+                if delegation_data.device_public_key
+                    != change_details.change_data.primary_public_key
+                {
+                    // The delegation was issued for another key
+                    return Err(IdentityError::IdentityVerificationFailed.into());
+                }
             }
 
             to_be_verified_changes.push(VerifiedChange::new(
@@ -1,4 +1,4 @@
-use crate::models::{ChangeHash, TimestampInSeconds};
+use crate::models::{ChangeHash, DeviceDelegation, TimestampInSeconds};
 use minicbor::{Decode, Encode};
 use ockam_core::compat::vec::Vec;
 use ockam_vault::{
@@ -56,6 +56,9 @@ pub struct ChangeData {
     #[n(4)] pub created_at: TimestampInSeconds,
     /// Expiration [`TimestampInSeconds`] (UTC)
     #[n(5)] pub expires_at: TimestampInSeconds,
+    /// Delegation of another Identity to this Identity, when it is used by a device.
+    /// It can only be present in the very first [`Change`] in the [`ChangeHistory`]
+    #[n(6)] pub delegation: Option<DeviceDelegation>,
 }
 
 /// [`Change`]'s public key
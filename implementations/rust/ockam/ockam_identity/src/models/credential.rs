@@ -43,6 +43,22 @@ pub struct CredentialData {
     #[n(4)] pub created_at: TimestampInSeconds,
     /// Expiration [`TimestampInSeconds`] (UTC)
     #[n(5)] pub expires_at: TimestampInSeconds,
+    /// [`CredentialRouteConstraints`] restricting how that Credential can be presented
+    #[n(6)] pub route_constraints: Option<CredentialRouteConstraints>,
+}
+
+/// Constraints set by the Authority (issuer) on the route over which a [`Credential`] can be
+/// presented. They reduce the value of a Credential exfiltrated from its Subject's node
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CredentialRouteConstraints {
+    /// If not empty, the Credential must be presented over a secure channel carried by another
+    /// secure channel with one of these identities, for example the node of a project relay
+    #[n(1)] pub via: Vec<Identifier>,
+    /// If true, the Credential can't be presented over a secure channel established directly
+    /// over a transport connection, like a raw TCP connection
+    #[n(2)] pub deny_direct: bool,
 }
 
 /// Number that determines which keys&values to expect in the [`Attributes`]
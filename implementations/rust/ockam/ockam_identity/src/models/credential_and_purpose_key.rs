@@ -1,5 +1,6 @@
-use crate::models::{Credential, PurposeKeyAttestation};
+use crate::models::{Credential, CredentialSignature, PurposeKeyAttestation};
 use minicbor::{Decode, Encode};
+use ockam_core::compat::vec::Vec;
 
 /// [`Credential`] and the corresponding [`PurposeKeyAttestation`] that was used to issue that
 /// [`Credential`] and will be used to verify it
@@ -12,4 +13,25 @@ pub struct CredentialAndPurposeKey {
     /// Corresponding [`PurposeKeyAttestation`] that was used to issue that
     /// [`Credential`] and will be used to verify it
     #[n(2)] pub purpose_key_attestation: PurposeKeyAttestation,
+    /// Signatures of the same [`Credential`] data by other Authorities, when the Authority
+    /// issuing the [`Credential`] requires a threshold of signatures
+    #[n(3)] pub co_signatures: Option<Vec<CredentialCoSignature>>,
+}
+
+/// Signature of the data of a [`Credential`] by an Authority which didn't issue it
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CredentialCoSignature {
+    /// Signature over the [`Credential`] data using the co-signer [`PurposeKeyAttestation`]
+    #[n(1)] pub signature: CredentialSignature,
+    /// [`PurposeKeyAttestation`] of the co-signer, used to verify the signature
+    #[n(2)] pub purpose_key_attestation: PurposeKeyAttestation,
+}
+
+impl CredentialAndPurposeKey {
+    /// Return the co-signatures of the [`Credential`]
+    pub fn co_signatures(&self) -> &[CredentialCoSignature] {
+        self.co_signatures.as_deref().unwrap_or_default()
+    }
 }
@@ -0,0 +1,53 @@
+use ockam_core::compat::string::String;
+use ockam_core::compat::vec::Vec;
+
+use crate::models::{ChangeHash, Identifier, PrimaryPublicKey, TimestampInSeconds};
+
+use minicbor::{Decode, Encode};
+use ockam_vault::{ECDSASHA256CurveP256Signature, EdDSACurve25519Signature};
+
+/// Delegation of an [`super::super::identity::Identity`] to a device.
+///
+/// It is recorded in the first [`super::Change`] of the device Identity, and signed by the
+/// delegating Identity, so that the device can act on behalf of the delegating Identity without
+/// having access to its key
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DeviceDelegation {
+    /// CBOR serialized [`super::VersionedData`]
+    /// where VersionedData::data is CBOR serialized [`DeviceDelegationData`]
+    #[cbor(with = "minicbor::bytes")]
+    #[n(1)] pub data: Vec<u8>,
+    /// Signature over data field using a key from the delegating Identity
+    #[n(2)] pub signature: DeviceDelegationSignature,
+}
+
+/// Signature over data field using a key from the delegating Identity
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+pub enum DeviceDelegationSignature {
+    /// Signature using EdDSA Ed25519 key from the delegating Identity
+    #[n(1)] EdDSACurve25519(#[n(0)] EdDSACurve25519Signature),
+    /// Signature using ECDSA P256 key from the delegating Identity
+    #[n(2)] ECDSASHA256CurveP256(#[n(0)] ECDSASHA256CurveP256Signature),
+}
+
+/// Data inside a [`DeviceDelegation`]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DeviceDelegationData {
+    /// [`Identifier`] of the delegating Identity
+    #[n(1)] pub delegator: Identifier,
+    /// Latest [`ChangeHash`] (at the moment of issuing) of the delegating Identity
+    #[n(2)] pub delegator_latest_change_hash: ChangeHash,
+    /// Primary public key of the first [`super::Change`] of the device Identity
+    #[n(3)] pub device_public_key: PrimaryPublicKey,
+    /// Name of the device
+    #[n(4)] pub device_name: String,
+    /// Creation [`TimestampInSeconds`] (UTC)
+    #[n(5)] pub created_at: TimestampInSeconds,
+    /// Expiration [`TimestampInSeconds`] (UTC)
+    #[n(6)] pub expires_at: TimestampInSeconds,
+}
@@ -1,8 +1,11 @@
 mod change_history;
 mod credential;
 mod credential_and_purpose_key;
+mod device_delegation;
 mod identifiers;
 mod purpose_key_attestation;
+mod revocation_list;
+mod signed_message;
 mod timestamp;
 mod utils;
 mod versioned_data;
@@ -10,7 +13,10 @@ mod versioned_data;
 pub use change_history::*;
 pub use credential::*;
 pub use credential_and_purpose_key::*;
+pub use device_delegation::*;
 pub use identifiers::*;
 pub use purpose_key_attestation::*;
+pub use revocation_list::*;
+pub use signed_message::*;
 pub use timestamp::*;
 pub use versioned_data::*;
@@ -0,0 +1,24 @@
+use minicbor::{Decode, Encode};
+use ockam_core::compat::vec::Vec;
+
+use crate::models::{Identifier, TimestampInSeconds};
+
+/// List of the subjects whose credentials have been revoked by an Authority
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RevocationList {
+    /// Revoked subjects
+    #[n(1)] pub revoked: Vec<RevokedSubject>,
+}
+
+/// Subject of revoked credentials
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RevokedSubject {
+    /// Identifier of the subject
+    #[n(1)] pub subject: Identifier,
+    /// Credentials of the subject created up to this time are revoked
+    #[n(2)] pub revoked_at: TimestampInSeconds,
+}
@@ -0,0 +1,26 @@
+use minicbor::{Decode, Encode};
+use ockam_core::compat::vec::Vec;
+use ockam_vault::{ECDSASHA256CurveP256Signature, EdDSACurve25519Signature};
+
+/// Message sent over a Secure Channel in the signed messages mode, along with a signature
+/// created with the Identity Key of its sender
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SignedMessage {
+    /// CBOR serialized [`ockam_core::TransportMessage`]
+    #[cbor(with = "minicbor::bytes")]
+    #[n(1)] pub message: Vec<u8>,
+    /// Signature over message field using the Identity Key of the sender
+    #[n(2)] pub signature: MessageSignature,
+}
+
+/// Signature over [`SignedMessage`] using the Identity Key of the sender
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+pub enum MessageSignature {
+    /// An EdDSA signature using Curve 25519.
+    #[n(1)] EdDSACurve25519(#[n(0)] EdDSACurve25519Signature),
+    /// An ECDSA signature using SHA-256 and Curve P-256.
+    #[n(2)] ECDSASHA256CurveP256(#[n(0)] ECDSASHA256CurveP256Signature),
+}
@@ -0,0 +1,41 @@
+use crate::models::utils::get_versioned_data;
+use crate::models::{
+    DeviceDelegation, DeviceDelegationData, DeviceDelegationSignature, VersionedData,
+};
+
+use ockam_core::Result;
+use ockam_vault::Signature;
+
+impl DeviceDelegation {
+    /// Extract [`VersionedData`]
+    pub fn get_versioned_data(&self) -> Result<VersionedData> {
+        get_versioned_data(&self.data)
+    }
+}
+
+impl DeviceDelegationData {
+    /// Extract [`DeviceDelegationData`] from [`VersionedData`]
+    pub fn get_data(versioned_data: &VersionedData) -> Result<Self> {
+        Ok(minicbor::decode(&versioned_data.data)?)
+    }
+}
+
+impl From<DeviceDelegationSignature> for Signature {
+    fn from(value: DeviceDelegationSignature) -> Self {
+        match value {
+            DeviceDelegationSignature::EdDSACurve25519(value) => Self::EdDSACurve25519(value),
+            DeviceDelegationSignature::ECDSASHA256CurveP256(value) => {
+                Self::ECDSASHA256CurveP256(value)
+            }
+        }
+    }
+}
+
+impl From<Signature> for DeviceDelegationSignature {
+    fn from(value: Signature) -> Self {
+        match value {
+            Signature::EdDSACurve25519(value) => Self::EdDSACurve25519(value),
+            Signature::ECDSASHA256CurveP256(value) => Self::ECDSASHA256CurveP256(value),
+        }
+    }
+}
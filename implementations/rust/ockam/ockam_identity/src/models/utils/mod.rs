@@ -7,6 +7,8 @@ fn get_versioned_data<'a, T: Decode<'a, ()>>(data: &'a [u8]) -> Result<T> {
 
 mod change_history;
 mod credentials;
+mod device_delegation;
 mod identifiers;
 mod purpose_key_attestation;
+mod signed_message;
 mod timestamp;
@@ -0,0 +1,21 @@
+use crate::models::MessageSignature;
+
+use ockam_vault::Signature;
+
+impl From<MessageSignature> for Signature {
+    fn from(value: MessageSignature) -> Self {
+        match value {
+            MessageSignature::EdDSACurve25519(value) => Self::EdDSACurve25519(value),
+            MessageSignature::ECDSASHA256CurveP256(value) => Self::ECDSASHA256CurveP256(value),
+        }
+    }
+}
+
+impl From<Signature> for MessageSignature {
+    fn from(value: Signature) -> Self {
+        match value {
+            Signature::EdDSACurve25519(value) => Self::EdDSACurve25519(value),
+            Signature::ECDSASHA256CurveP256(value) => Self::ECDSASHA256CurveP256(value),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use core::fmt::{Debug, Formatter};
+use ockam_core::access_control::IncomingAccessControl;
+use ockam_core::compat::{boxed::Box, sync::Arc, vec::Vec};
+use ockam_core::Result;
+use ockam_core::{async_trait, RelayMessage};
+use tracing::debug;
+
+use crate::identities::{IdentitiesDelegation, IdentitiesRepository};
+use crate::secure_channel::local_info::IdentitySecureChannelLocalInfo;
+
+/// Access control checking that message senders are devices with a valid delegation
+/// from an Identity having a specific set of attributes
+pub struct DelegatedIdentityAccessControl {
+    required_attributes: Vec<(Vec<u8>, Vec<u8>)>,
+    identities_delegation: Arc<IdentitiesDelegation>,
+    storage: Arc<dyn IdentitiesRepository>,
+}
+
+impl DelegatedIdentityAccessControl {
+    /// Create a new delegated identity access control
+    pub fn new(
+        required_attributes: &[(Vec<u8>, Vec<u8>)],
+        identities_delegation: Arc<IdentitiesDelegation>,
+        storage: Arc<dyn IdentitiesRepository>,
+    ) -> Self {
+        Self {
+            required_attributes: required_attributes.to_vec(),
+            identities_delegation,
+            storage,
+        }
+    }
+}
+
+impl Debug for DelegatedIdentityAccessControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let attributes = format!("{:?}", self.required_attributes.iter().map(|x| &x.0));
+
+        f.debug_struct("Delegated Identity Access Control")
+            .field("Required attributes", &attributes)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl IncomingAccessControl for DelegatedIdentityAccessControl {
+    async fn is_authorized(&self, relay_message: &RelayMessage) -> Result<bool> {
+        let msg_identity_id =
+            match IdentitySecureChannelLocalInfo::find_info(relay_message.local_message()) {
+                Ok(info) => info.their_identity_id(),
+                Err(_) => return Ok(false),
+            };
+
+        let delegation = match self
+            .identities_delegation
+            .verify_delegation(&msg_identity_id)
+            .await
+        {
+            Ok(Some(delegation)) => delegation,
+            Ok(None) => return Ok(false), // Not a device Identity
+            Err(e) => {
+                debug!("invalid delegation for {msg_identity_id}: {e}");
+                return Ok(false);
+            }
+        };
+
+        let attributes = match self.storage.get_attributes(&delegation.delegator).await? {
+            Some(a) => a,
+            None => return Ok(false), // No attributes for the delegating Identity
+        };
+
+        for required_attribute in self.required_attributes.iter() {
+            match attributes.attrs().get(&required_attribute.0) {
+                Some(attr_val) if attr_val == &required_attribute.1 => {}
+                _ => return Ok(false), // No required key, or the value doesn't match
+            }
+        }
+
+        Ok(true)
+    }
+}
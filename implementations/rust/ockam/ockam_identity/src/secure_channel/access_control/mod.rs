@@ -1,5 +1,7 @@
 mod credential_access_control;
+mod delegation_access_control;
 mod identity_access_control;
 
 pub use credential_access_control::*;
+pub use delegation_access_control::*;
 pub use identity_access_control::*;
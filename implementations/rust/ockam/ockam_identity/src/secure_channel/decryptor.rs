@@ -1,14 +1,15 @@
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
-use ockam_core::{Any, Result, Routed, TransportMessage};
+use ockam_core::{Any, QuotaUsage, Result, Routed, TransportMessage, QUOTA_AUDIT_TARGET};
 use ockam_core::{Decodable, LocalMessage};
 use ockam_node::Context;
 
 use crate::models::Identifier;
 use crate::secure_channel::encryptor::{Encryptor, KEY_RENEWAL_INTERVAL};
 use crate::secure_channel::key_tracker::KeyTracker;
+use crate::secure_channel::message_signing::MessageVerifier;
 use crate::secure_channel::nonce_tracker::NonceTracker;
-use crate::secure_channel::Addresses;
+use crate::secure_channel::{Addresses, SecureChannelStatistics};
 use crate::{DecryptionRequest, DecryptionResponse, IdentityError, IdentitySecureChannelLocalInfo};
 
 use ockam_vault::{AeadSecretKeyHandle, VaultForSecureChannels};
@@ -19,22 +20,35 @@ pub(crate) struct DecryptorHandler {
     pub(crate) role: &'static str,
     pub(crate) addresses: Addresses,
     pub(crate) their_identity_id: Identifier,
+    pub(crate) via: Option<Identifier>,
+    pub(crate) message_verifier: Option<MessageVerifier>,
     pub(crate) decryptor: Decryptor,
+    pub(crate) quota_usage: Option<QuotaUsage>,
+    pub(crate) statistics: SecureChannelStatistics,
 }
 
 impl DecryptorHandler {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         role: &'static str,
         addresses: Addresses,
         key: AeadSecretKeyHandle,
         vault: Arc<dyn VaultForSecureChannels>,
         their_identity_id: Identifier,
+        via: Option<Identifier>,
+        message_verifier: Option<MessageVerifier>,
+        quota_usage: Option<QuotaUsage>,
+        statistics: SecureChannelStatistics,
     ) -> Self {
         Self {
             role,
             addresses,
             their_identity_id,
+            via,
+            message_verifier,
             decryptor: Decryptor::new(key, vault),
+            quota_usage,
+            statistics,
         }
     }
 
@@ -57,7 +71,10 @@ impl DecryptorHandler {
         let decrypted_payload = self.decryptor.decrypt(&request.0).await;
 
         let response = match decrypted_payload {
-            Ok(payload) => DecryptionResponse::Ok(payload),
+            Ok(payload) => {
+                self.statistics.add_received(payload.len());
+                DecryptionResponse::Ok(payload)
+            }
             Err(err) => DecryptionResponse::Err(err),
         };
 
@@ -83,10 +100,54 @@ impl DecryptorHandler {
 
         // Decrypt the binary
         let decrypted_payload = self.decryptor.decrypt(&payload).await?;
+        self.handle_payload(ctx, decrypted_payload).await
+    }
+
+    /// Deliver a decrypted message, either received on the channel or sent as early data with
+    /// the first handshake message
+    pub(crate) async fn handle_payload(
+        &mut self,
+        ctx: &mut Context,
+        decrypted_payload: Vec<u8>,
+    ) -> Result<()> {
+        self.statistics.add_received(decrypted_payload.len());
+
+        // In the signed messages mode, drop the messages which are not signed by the other party
+        let (decrypted_payload, signed_message) = match &self.message_verifier {
+            Some(message_verifier) => match message_verifier.verify(&decrypted_payload).await {
+                Ok(signed_message) => (signed_message.message.clone(), Some(signed_message)),
+                Err(err) => {
+                    warn!(
+                        decryptor = %self.addresses.decryptor_remote,
+                        their_identifier = %self.their_identity_id,
+                        "dropping a message: {err}"
+                    );
+                    return Ok(());
+                }
+            },
+            None => (decrypted_payload, None),
+        };
 
         // Encrypted data should be a TransportMessage
         let mut transport_message = TransportMessage::decode(&decrypted_payload)?;
 
+        // Close the channel instead of delivering a message exceeding its quota.
+        // Stopping the encryptor stops the decryptor as well
+        if let Some(exceeded) = self
+            .quota_usage
+            .as_ref()
+            .and_then(|usage| usage.add_message(transport_message.payload.len()))
+        {
+            warn!(
+                target: QUOTA_AUDIT_TARGET,
+                encryptor = %self.addresses.encryptor,
+                their_identifier = %self.their_identity_id,
+                "secure channel closed: {exceeded}"
+            );
+            ctx.stop_worker(self.addresses.encryptor.clone()).await?;
+            return Ok(());
+        }
+
         // Add encryptor hop in the return_route (instead of our address)
         transport_message
             .return_route
@@ -95,8 +156,12 @@ impl DecryptorHandler {
 
         // Mark message LocalInfo with IdentitySecureChannelLocalInfo,
         // replacing any pre-existing entries
-        let local_info =
-            IdentitySecureChannelLocalInfo::mark(vec![], self.their_identity_id.clone())?;
+        let local_info = IdentitySecureChannelLocalInfo::mark(
+            vec![],
+            self.their_identity_id.clone(),
+            self.via.clone(),
+            signed_message,
+        )?;
 
         let msg = LocalMessage::new(transport_message, local_info);
 
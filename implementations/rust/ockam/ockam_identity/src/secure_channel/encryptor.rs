@@ -4,12 +4,21 @@ use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 use ockam_vault::{AeadSecretKeyHandle, VaultForSecureChannels};
 
-use crate::IdentityError;
+use crate::models::TimestampInSeconds;
+use crate::utils::now;
+use crate::{IdentityError, RekeyPolicy};
 
 pub(crate) struct Encryptor {
     key: AeadSecretKeyHandle,
     nonce: u64,
     vault: Arc<dyn VaultForSecureChannels>,
+    rekey_policy: RekeyPolicy,
+    /// Number of bytes encrypted with the current key
+    bytes_since_rekey: u64,
+    /// Time of the first use of the current key, when the rekey policy has a duration
+    key_created_at: Option<TimestampInSeconds>,
+    /// Number of renewals of the key
+    rekeys: u64,
 }
 
 // To simplify the implementation we use the same constant for the size of the message
@@ -52,13 +61,33 @@ impl Encryptor {
             return Err(IdentityError::NonceOverflow.into());
         }
 
-        self.nonce += 1;
+        // When the current key has been used beyond the rekey policy, skip the remaining nonces
+        // of its interval. The other party renews its key as soon as it receives a nonce of
+        // the next interval, and keeps the previous key for the messages still in transit
+        let current_nonce =
+            if current_nonce % KEY_RENEWAL_INTERVAL != 0 && self.is_rekey_policy_exceeded()? {
+                let next_interval_start = (current_nonce / KEY_RENEWAL_INTERVAL + 1)
+                    .checked_mul(KEY_RENEWAL_INTERVAL)
+                    .ok_or(IdentityError::NonceOverflow)?;
+                if next_interval_start == u64::MAX {
+                    return Err(IdentityError::NonceOverflow.into());
+                }
+                next_interval_start
+            } else {
+                current_nonce
+            };
+
+        self.nonce = current_nonce + 1;
 
         if current_nonce > 0 && current_nonce % KEY_RENEWAL_INTERVAL == 0 {
             let new_key = Self::rekey(&self.vault, &self.key).await?;
             let old_key = core::mem::replace(&mut self.key, new_key);
             self.vault.delete_aead_secret_key(old_key).await?;
+            self.bytes_since_rekey = 0;
+            self.key_created_at = None;
+            self.rekeys += 1;
         }
+        self.bytes_since_rekey = self.bytes_since_rekey.saturating_add(payload.len() as u64);
 
         let (small_nonce, nonce) = Self::convert_nonce_from_u64(current_nonce);
 
@@ -79,7 +108,40 @@ impl Encryptor {
         nonce: u64,
         vault: Arc<dyn VaultForSecureChannels>,
     ) -> Self {
-        Self { key, nonce, vault }
+        Self {
+            key,
+            nonce,
+            vault,
+            rekey_policy: RekeyPolicy::default(),
+            bytes_since_rekey: 0,
+            key_created_at: None,
+            rekeys: 0,
+        }
+    }
+
+    /// Also renew the keys according to the given [`RekeyPolicy`]
+    pub(crate) fn with_rekey_policy(mut self, rekey_policy: RekeyPolicy) -> Self {
+        self.rekey_policy = rekey_policy;
+        self
+    }
+
+    /// Number of renewals of the key so far
+    pub(crate) fn rekeys(&self) -> u64 {
+        self.rekeys
+    }
+
+    /// Return true if the current key must be renewed before encrypting the next message
+    fn is_rekey_policy_exceeded(&mut self) -> Result<bool> {
+        let elapsed_seconds = if self.rekey_policy.after_duration.is_some() {
+            let now = now()?;
+            let created_at = *self.key_created_at.get_or_insert(now);
+            now.saturating_sub(*created_at)
+        } else {
+            0
+        };
+        Ok(self
+            .rekey_policy
+            .is_exceeded(self.bytes_since_rekey, elapsed_seconds))
     }
 
     pub(crate) async fn shutdown(&self) -> Result<()> {
@@ -1,12 +1,15 @@
 use ockam_core::compat::boxed::Box;
 use ockam_core::{async_trait, Decodable, Encodable, Route};
-use ockam_core::{Any, Result, Routed, TransportMessage, Worker};
+use ockam_core::{Any, QuotaUsage, Result, Routed, TransportMessage, Worker, QUOTA_AUDIT_TARGET};
 use ockam_node::Context;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
+use crate::models::Identifier;
 use crate::secure_channel::addresses::Addresses;
 use crate::secure_channel::api::{EncryptionRequest, EncryptionResponse};
 use crate::secure_channel::encryptor::Encryptor;
+use crate::secure_channel::message_signing::MessageSigner;
+use crate::secure_channel::SecureChannelStatistics;
 use crate::IdentityError;
 
 pub(crate) struct EncryptorWorker {
@@ -15,20 +18,33 @@ pub(crate) struct EncryptorWorker {
     addresses: Addresses,
     remote_route: Route,
     encryptor: Encryptor,
+    their_identifier: Identifier,
+    message_signer: Option<MessageSigner>,
+    quota_usage: Option<QuotaUsage>,
+    statistics: SecureChannelStatistics,
 }
 
 impl EncryptorWorker {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         role: &'static str,
         addresses: Addresses,
         remote_route: Route,
         encryptor: Encryptor,
+        their_identifier: Identifier,
+        message_signer: Option<MessageSigner>,
+        quota_usage: Option<QuotaUsage>,
+        statistics: SecureChannelStatistics,
     ) -> Self {
         Self {
             role,
             addresses,
             remote_route,
             encryptor,
+            their_identifier,
+            message_signer,
+            quota_usage,
+            statistics,
         }
     }
 
@@ -51,7 +67,11 @@ impl EncryptorWorker {
 
         // Encrypt the message
         let response = match self.encryptor.encrypt(&request.0).await {
-            Ok(encrypted_payload) => EncryptionResponse::Ok(encrypted_payload),
+            Ok(encrypted_payload) => {
+                self.statistics
+                    .add_sent(request.0.len(), self.encryptor.rekeys());
+                EncryptionResponse::Ok(encrypted_payload)
+            }
             // If encryption failed, that means we have some internal error,
             // and we may be in an invalid state, it's better to stop the Worker
             Err(err) => {
@@ -97,9 +117,35 @@ impl EncryptorWorker {
             msg.into_transport_message().payload,
         );
 
+        // Close the channel instead of sending a message exceeding its quota
+        if let Some(exceeded) = self
+            .quota_usage
+            .as_ref()
+            .and_then(|usage| usage.add_message(msg.payload.len()))
+        {
+            warn!(
+                target: QUOTA_AUDIT_TARGET,
+                encryptor = %self.addresses.encryptor,
+                their_identifier = %self.their_identifier,
+                "secure channel closed: {exceeded}"
+            );
+            ctx.stop_worker(self.addresses.encryptor.clone()).await?;
+            return Ok(());
+        }
+
+        // In the signed messages mode, the encrypted message is signed first
+        let payload = match &self.message_signer {
+            Some(message_signer) => minicbor::to_vec(message_signer.sign(msg.encode()?).await?)?,
+            None => msg.encode()?,
+        };
+
         // Encrypt the message
-        let encrypted_payload = match self.encryptor.encrypt(&msg.encode()?).await {
-            Ok(encrypted_payload) => encrypted_payload,
+        let encrypted_payload = match self.encryptor.encrypt(&payload).await {
+            Ok(encrypted_payload) => {
+                self.statistics
+                    .add_sent(payload.len(), self.encryptor.rekeys());
+                encrypted_payload
+            }
             // If encryption failed, that means we have some internal error,
             // and we may be in an invalid state, it's better to stop the Worker
             Err(err) => {
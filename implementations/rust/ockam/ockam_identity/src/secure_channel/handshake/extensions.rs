@@ -0,0 +1,140 @@
+use core::fmt;
+use core::fmt::Formatter;
+use minicbor::bytes::ByteVec;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::rand::thread_rng;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use pqc_kyber::{KYBER_CIPHERTEXTBYTES, KYBER_PUBLICKEYBYTES, KYBER_SSBYTES};
+
+use crate::secure_channel::handshake::error::XXError;
+use crate::{HandshakePattern, IdentityError};
+
+/// Version of the handshake extensions supported by this implementation
+pub(super) const HANDSHAKE_EXTENSIONS_VERSION: u8 = 1;
+
+/// Number of bytes of a Kyber768 ciphertext
+pub(super) const KEM_CIPHERTEXT_LENGTH: usize = KYBER_CIPHERTEXTBYTES;
+
+/// Payload of the first handshake message, when the initiator requests some extensions of the
+/// Noise XX handshake.
+///
+/// A responder only accepts the versions it knows about, so that an initiator never silently
+/// gets a handshake weaker than the one it asked for. Since the payload of the first message
+/// is mixed into the handshake hash, the extensions can't be removed by an attacker either
+#[derive(Clone, Debug, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub(super) struct HandshakeExtensions {
+    #[n(0)] pub(super) version: u8,
+    #[n(1)] pub(super) handshake_pattern: HandshakePattern,
+    /// Kyber768 public key of the initiator, for the post-quantum hybrid key agreement
+    #[n(2)] pub(super) kem_public_key: Option<ByteVec>,
+}
+
+impl HandshakeExtensions {
+    /// Return the extensions announced by the payload of the first handshake message, if any
+    pub(super) fn from_payload(payload: &[u8]) -> Result<Option<HandshakeExtensions>> {
+        let extensions: HandshakeExtensions = match minicbor::decode(payload) {
+            Ok(extensions) => extensions,
+            Err(_) => return Ok(None),
+        };
+        if extensions.version != HANDSHAKE_EXTENSIONS_VERSION {
+            return Err(IdentityError::UnsupportedHandshakeExtension.into());
+        }
+        Ok(Some(extensions))
+    }
+}
+
+/// Post-quantum part of the hybrid key agreement: the X25519 keys of the Noise handshake are
+/// combined with a Kyber768 key encapsulation, so that recorded handshakes can't be decrypted
+/// later with a quantum computer as long as Kyber holds, while staying as secure as X25519 otherwise.
+///
+/// The initiator sends an ephemeral Kyber public key with message 1. The responder encapsulates
+/// a secret to that key and sends the ciphertext, encrypted, with message 2. Both parties then
+/// mix the encapsulated secret into the handshake keys, right after the `ee` Diffie-Hellman key
+#[derive(Clone)]
+pub(super) enum HybridKem {
+    /// Key pair generated by the initiator, waiting for the ciphertext of the responder
+    Initiator {
+        public_key: [u8; KYBER_PUBLICKEYBYTES],
+        secret_key: Vec<u8>,
+    },
+    /// Public key received by the responder
+    Responder { public_key: Vec<u8> },
+}
+
+impl HybridKem {
+    /// Generate the ephemeral key pair of an initiator
+    pub(super) fn initiator() -> Result<HybridKem> {
+        let key_pair =
+            pqc_kyber::keypair(&mut thread_rng()).map_err(|_| XXError::InternalVaultError)?;
+        Ok(HybridKem::Initiator {
+            public_key: key_pair.public,
+            secret_key: key_pair.secret.to_vec(),
+        })
+    }
+
+    /// Use the public key sent by an initiator
+    pub(super) fn responder(public_key: &[u8]) -> Result<HybridKem> {
+        if public_key.len() != KYBER_PUBLICKEYBYTES {
+            return Err(XXError::MessageLenMismatch.into());
+        }
+        Ok(HybridKem::Responder {
+            public_key: public_key.to_vec(),
+        })
+    }
+
+    /// Public key sent with message 1 by the initiator
+    pub(super) fn public_key(&self) -> &[u8] {
+        match self {
+            HybridKem::Initiator { public_key, .. } => public_key,
+            HybridKem::Responder { public_key } => public_key,
+        }
+    }
+
+    /// Encapsulate a secret to the public key of the initiator.
+    /// Return the ciphertext and the secret
+    pub(super) fn encapsulate(&self) -> Result<(Vec<u8>, [u8; KYBER_SSBYTES])> {
+        let (ciphertext, secret) = pqc_kyber::encapsulate(self.public_key(), &mut thread_rng())
+            .map_err(|_| XXError::MessageLenMismatch)?;
+        Ok((ciphertext.to_vec(), secret))
+    }
+
+    /// Decapsulate the secret sent by the responder
+    pub(super) fn decapsulate(&self, ciphertext: &[u8]) -> Result<[u8; KYBER_SSBYTES]> {
+        match self {
+            HybridKem::Initiator { secret_key, .. } => {
+                pqc_kyber::decapsulate(ciphertext, secret_key)
+                    .map_err(|_| XXError::MessageLenMismatch.into())
+            }
+            HybridKem::Responder { .. } => Err(XXError::InvalidInternalState.into()),
+        }
+    }
+}
+
+impl fmt::Debug for HybridKem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HybridKem::Initiator { .. } => write!(f, "HybridKem::Initiator(<redacted>)"),
+            HybridKem::Responder { .. } => write!(f, "HybridKem::Responder"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encapsulated_secrets_are_shared() -> Result<()> {
+        let initiator = HybridKem::initiator()?;
+        let responder = HybridKem::responder(initiator.public_key())?;
+        let (ciphertext, secret) = responder.encapsulate()?;
+        assert_eq!(ciphertext.len(), KEM_CIPHERTEXT_LENGTH);
+        assert_eq!(initiator.decapsulate(&ciphertext)?, secret);
+
+        assert!(HybridKem::responder(&[0u8; 32]).is_err());
+        Ok(())
+    }
+}
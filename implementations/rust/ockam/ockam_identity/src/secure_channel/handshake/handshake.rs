@@ -11,8 +11,9 @@ use sha2::{Digest, Sha256};
 use Status::*;
 
 use crate::secure_channel::handshake::error::XXError;
+use crate::secure_channel::handshake::extensions::{HybridKem, KEM_CIPHERTEXT_LENGTH};
 use crate::secure_channel::handshake::handshake_state_machine::{HandshakeKeys, Status};
-use crate::secure_channel::Role;
+use crate::secure_channel::{PreSharedKey, Role};
 
 /// The number of bytes in a SHA256 digest
 pub const SHA256_SIZE: usize = 32;
@@ -29,6 +30,12 @@ pub(super) struct Handshake {
     vault: Arc<dyn VaultForSecureChannels>,
     protocol_name: [u8; 32],
     pub(super) state: HandshakeState,
+    /// Key mixed into the handshake keys after the second message, for the
+    /// XXPreSharedKey handshake pattern
+    pub(super) pre_shared_key: Option<PreSharedKey>,
+    /// Kyber key encapsulation mixed into the handshake keys after the `ee` Diffie-Hellman key,
+    /// for the post-quantum hybrid key agreement
+    pub(super) kem: Option<HybridKem>,
 }
 
 /// Top-level functions used in the initiator and responder state machines
@@ -88,6 +95,66 @@ impl Handshake {
         Ok(payload.to_vec())
     }
 
+    /// Encode the first message of a resumption with early data, from the initiator to the responder
+    /// That message contains: the initiator ephemeral public key + the resumption request +
+    ///   the early data, encrypted with a key derived from the secret of the ticket
+    pub(super) async fn encode_resumption_message1(
+        &mut self,
+        request: &[u8],
+        early_data: &[u8],
+    ) -> Result<Vec<u8>> {
+        if self.pre_shared_key.is_none() {
+            return Err(XXError::InvalidInternalState.into());
+        }
+        let mut message1 = self.encode_message1(request).await?;
+
+        let mut state = self.state.clone();
+        // ck, k = HKDF(ck, psk, 2)
+        self.mix_pre_shared_key(&mut state).await?;
+
+        // encrypt and output the early data
+        let c = self.encrypt_and_hash(&mut state, early_data).await?;
+        message1.extend(c);
+
+        if message1.len() > NOISE_MAX_MESSAGE_SIZE {
+            return Err(XXError::ExceededMaxMessageLen.into());
+        }
+
+        self.state = state;
+        Ok(message1)
+    }
+
+    /// Decode the first message of a resumption with early data, once the secret of the ticket
+    /// is known, and return the early data. The resumption request has the given length
+    pub(super) async fn decode_resumption_message1(
+        &mut self,
+        message1: &[u8],
+        request_length: usize,
+    ) -> Result<Vec<u8>> {
+        if message1.len() > NOISE_MAX_MESSAGE_SIZE {
+            return Err(XXError::ExceededMaxMessageLen.into());
+        }
+        if self.pre_shared_key.is_none() {
+            return Err(XXError::InvalidInternalState.into());
+        }
+        let request_end = X25519_PUBLIC_KEY_LENGTH + request_length;
+        if message1.len() < request_end {
+            return Err(XXError::MessageLenMismatch.into());
+        }
+        let (message1, c) = message1.split_at(request_end);
+        self.decode_message1(message1).await?;
+
+        let mut state = self.state.clone();
+        // ck, k = HKDF(ck, psk, 2)
+        self.mix_pre_shared_key(&mut state).await?;
+
+        // decrypt the early data
+        let early_data = self.hash_and_decrypt(&mut state, c).await?;
+
+        self.state = state;
+        Ok(early_data)
+    }
+
     /// Encode the second message from the responder to the initiator
     /// That message contains: the responder ephemeral public key + a Diffie-Hellman key +
     ///   an encrypted payload containing the responder identity / signature / credentials
@@ -102,6 +169,15 @@ impl Handshake {
         let dh = self.dh(state.e()?, state.re()?).await?;
         self.hkdf(&mut state, dh).await?;
 
+        // encrypt and output the KEM ciphertext, then ck, k = HKDF(ck, kem secret, 2)
+        if let Some(kem) = &self.kem {
+            let (ciphertext, secret) = kem.encapsulate()?;
+            let c = self.encrypt_and_hash(&mut state, &ciphertext).await?;
+            message2.extend_from_slice(c.as_slice());
+            let secret = self.vault.import_secret_buffer(secret.to_vec()).await?;
+            self.hkdf(&mut state, secret).await?;
+        }
+
         // encrypt and output s.pubKey
         let s_pub_key = self.get_public_key(state.s()?).await?;
         let c = self.encrypt_and_hash(&mut state, &s_pub_key.0).await?;
@@ -111,6 +187,9 @@ impl Handshake {
         let dh = self.dh(state.s()?, state.re()?).await?;
         self.hkdf(&mut state, dh).await?;
 
+        // ck, k = HKDF(ck, psk, 2)
+        self.mix_pre_shared_key(&mut state).await?;
+
         // encrypt and output payload
         let c = self.encrypt_and_hash(&mut state, payload).await?;
         message2.extend(c);
@@ -139,6 +218,25 @@ impl Handshake {
         let dh = self.dh(state.e()?, state.re()?).await?;
         self.hkdf(&mut state, dh).await?;
 
+        // decrypt the KEM ciphertext, then ck, k = HKDF(ck, kem secret, 2)
+        // The ciphertext is then removed, so that the rest of the message is read as usual
+        let message2 = match &self.kem {
+            Some(kem) => {
+                let c = Self::read_message2_kem_ciphertext(message2)?;
+                let ciphertext = self.hash_and_decrypt(&mut state, c).await?;
+                let secret = kem.decapsulate(&ciphertext)?;
+                let secret = self.vault.import_secret_buffer(secret.to_vec()).await?;
+                self.hkdf(&mut state, secret).await?;
+                [
+                    &message2[..X25519_PUBLIC_KEY_LENGTH],
+                    &message2[X25519_PUBLIC_KEY_LENGTH + c.len()..],
+                ]
+                .concat()
+            }
+            None => message2.to_vec(),
+        };
+        let message2 = message2.as_slice();
+
         // decrypt rs.pubKey
         let rs_pub_key = Self::read_message2_encrypted_key(message2)?;
         let rs_pub_key = self.hash_and_decrypt(&mut state, rs_pub_key).await?;
@@ -153,6 +251,9 @@ impl Handshake {
         let dh = self.dh(state.e()?, state.rs()?).await?;
         self.hkdf(&mut state, dh).await?;
 
+        // ck, k = HKDF(ck, psk, 2)
+        self.mix_pre_shared_key(&mut state).await?;
+
         // decrypt payload
         let c = Self::read_message2_payload(message2)?;
         let payload = self.hash_and_decrypt(&mut state, c).await?;
@@ -215,6 +316,98 @@ impl Handshake {
         Ok(payload)
     }
 
+    /// Encode the fourth message from the responder to the initiator
+    /// That message is only sent when the responder hides its identity until the initiator has been
+    /// authenticated. It contains an encrypted payload with the responder identity / signature / credentials
+    pub(super) async fn encode_message4(&mut self, payload: &[u8]) -> Result<Vec<u8>> {
+        let mut state = self.state.clone();
+        // encrypt payload
+        let message4 = self.encrypt_and_hash(&mut state, payload).await?;
+
+        if message4.len() > NOISE_MAX_MESSAGE_SIZE {
+            return Err(XXError::ExceededMaxMessageLen.into());
+        }
+
+        self.state = state;
+        Ok(message4)
+    }
+
+    /// Decode the fourth message sent by the responder
+    pub(super) async fn decode_message4(&mut self, message4: &[u8]) -> Result<Vec<u8>> {
+        if message4.len() > NOISE_MAX_MESSAGE_SIZE {
+            return Err(XXError::ExceededMaxMessageLen.into());
+        }
+
+        let mut state = self.state.clone();
+        // decrypt payload
+        let payload = self.hash_and_decrypt(&mut state, message4).await?;
+        self.state = state;
+        Ok(payload)
+    }
+
+    /// Encode the second message of a resumption, from the responder to the initiator
+    /// That message contains: the responder ephemeral public key + an encrypted payload containing
+    ///   a new resumption ticket. The keys are derived from the ephemeral keys and the secret of the ticket
+    pub(super) async fn encode_resumption_message2(&mut self, payload: &[u8]) -> Result<Vec<u8>> {
+        if self.pre_shared_key.is_none() {
+            return Err(XXError::InvalidInternalState.into());
+        }
+
+        let mut state = self.state.clone();
+        // output e.pubKey
+        let e_pub_key = self.get_public_key(state.e()?).await?;
+        state.mix_hash(&e_pub_key.0);
+        let mut message2 = e_pub_key.0.to_vec();
+
+        // ck, k = HKDF(ck, DH(e, re), 2)
+        let dh = self.dh(state.e()?, state.re()?).await?;
+        self.hkdf(&mut state, dh).await?;
+
+        // ck, k = HKDF(ck, psk, 2)
+        self.mix_pre_shared_key(&mut state).await?;
+
+        // encrypt and output payload
+        let c = self.encrypt_and_hash(&mut state, payload).await?;
+        message2.extend(c);
+
+        if message2.len() > NOISE_MAX_MESSAGE_SIZE {
+            return Err(XXError::ExceededMaxMessageLen.into());
+        }
+
+        self.state = state;
+        Ok(message2)
+    }
+
+    /// Decode the second message of a resumption, sent by the responder
+    pub(super) async fn decode_resumption_message2(&mut self, message2: &[u8]) -> Result<Vec<u8>> {
+        if message2.len() > NOISE_MAX_MESSAGE_SIZE {
+            return Err(XXError::ExceededMaxMessageLen.into());
+        }
+        if self.pre_shared_key.is_none() {
+            return Err(XXError::InvalidInternalState.into());
+        }
+
+        let mut state = self.state.clone();
+        // decode re.pubKey
+        let re_pub_key = Self::read_key(message2)?;
+        state.re = Some(X25519PublicKey(*re_pub_key));
+        state.mix_hash(re_pub_key);
+
+        // ck, k = HKDF(ck, DH(e, re), 2)
+        let dh = self.dh(state.e()?, state.re()?).await?;
+        self.hkdf(&mut state, dh).await?;
+
+        // ck, k = HKDF(ck, psk, 2)
+        self.mix_pre_shared_key(&mut state).await?;
+
+        // decrypt payload
+        let c = Self::read_end::<X25519_PUBLIC_KEY_LENGTH>(message2)?;
+        let payload = self.hash_and_decrypt(&mut state, c).await?;
+
+        self.state = state;
+        Ok(payload)
+    }
+
     /// Set the final state of the state machine by creating the encryption / decryption keys
     /// and return the other party identity
     pub(super) async fn set_final_state(&mut self, role: Role) -> Result<()> {
@@ -260,6 +453,8 @@ impl Handshake {
             vault,
             protocol_name: *PROTOCOL_NAME,
             state: HandshakeState::new(static_key, ephemeral_key),
+            pre_shared_key: None,
+            kem: None,
         })
     }
 
@@ -282,6 +477,18 @@ impl Handshake {
         self.vault.x25519_ecdh(key, public_key).await
     }
 
+    /// Mix the pre-shared key, if any, into the ck and k keys
+    async fn mix_pre_shared_key(&self, state: &mut HandshakeState) -> Result<()> {
+        if let Some(pre_shared_key) = &self.pre_shared_key {
+            let psk = self
+                .vault
+                .import_secret_buffer(pre_shared_key.as_bytes().to_vec())
+                .await?;
+            self.hkdf(state, psk).await?;
+        }
+        Ok(())
+    }
+
     /// Compute two derived ck, and k keys based on existing ck and k keys + a Diffie-Hellman key
     async fn hkdf(&self, state: &mut HandshakeState, dh: SecretBufferHandle) -> Result<()> {
         let hkdf_output = self
@@ -403,10 +610,17 @@ impl Handshake {
     }
 
     /// Read the message 1 payload which is present after the public key
-    fn read_message1_payload(message: &[u8]) -> Result<&[u8]> {
+    pub(super) fn read_message1_payload(message: &[u8]) -> Result<&[u8]> {
         Self::read_end::<X25519_PUBLIC_KEY_LENGTH>(message)
     }
 
+    /// Read the message 2 encrypted KEM ciphertext, which is present after the public key
+    /// with the post-quantum hybrid key agreement
+    fn read_message2_kem_ciphertext(message: &[u8]) -> Result<&[u8]> {
+        const L: usize = KEM_CIPHERTEXT_LENGTH + AES_GCM_TAGSIZE;
+        Self::read_middle::<X25519_PUBLIC_KEY_LENGTH, L>(message)
+    }
+
     /// Read the message 2 encrypted key, which is present after the public key
     fn read_message2_encrypted_key(message: &[u8]) -> Result<&[u8]> {
         const L: usize = X25519_PUBLIC_KEY_LENGTH + AES_GCM_TAGSIZE;
@@ -763,6 +977,8 @@ mod tests {
                 vault,
                 protocol_name,
                 state: HandshakeState::new(static_key, ephemeral_key),
+                pre_shared_key: None,
+                kem: None,
             })
         }
 
@@ -781,6 +997,8 @@ mod tests {
                 vault,
                 protocol_name,
                 state: HandshakeState::new(static_key, ephemeral_key),
+                pre_shared_key: None,
+                kem: None,
             })
         }
     }
@@ -1,16 +1,19 @@
 use minicbor::{Decode, Encode};
-use ockam_core::compat::string::ToString;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::{String, ToString};
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::{boxed::Box, vec::Vec};
 use ockam_core::{async_trait, Result};
 use ockam_vault::{AeadSecretKeyHandle, X25519PublicKey};
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 
 use crate::models::{
     ChangeHistory, CredentialAndPurposeKey, Identifier, PurposeKeyAttestation, PurposePublicKey,
 };
+use crate::secure_channel::{ResumptionTicketPayload, LOW_TRUST_LEVEL, TRUST_LEVEL_ATTRIBUTE};
 use crate::{
-    Identities, Identity, IdentityError, SecureChannelTrustInfo, TrustContext, TrustPolicy,
+    Identities, Identity, IdentityAttributesReader, IdentityAttributesWriter, IdentityError,
+    PresentationRoute, SecureChannelTrustInfo, TrustContext, TrustPolicy,
 };
 
 /// Interface for a state machine in a key exchange protocol
@@ -18,6 +21,8 @@ use crate::{
 pub(super) trait StateMachine: Send + Sync + 'static {
     async fn on_event(&mut self, event: Event) -> Result<Action>;
     fn get_handshake_results(&self) -> Option<HandshakeResults>;
+    /// Set the route over which the messages of the other party are received
+    fn set_presentation_route(&mut self, presentation_route: PresentationRoute);
 }
 
 /// Events received by the state machine, either initializing the state machine
@@ -42,6 +47,7 @@ pub(super) enum Status {
     WaitingForMessage1,
     WaitingForMessage2,
     WaitingForMessage3,
+    WaitingForMessage4,
     Ready(HandshakeKeys),
 }
 
@@ -58,6 +64,13 @@ pub(super) struct HandshakeKeys {
 pub(super) struct HandshakeResults {
     pub(super) handshake_keys: HandshakeKeys,
     pub(super) their_identifier: Identifier,
+    pub(super) their_messages_signed: bool,
+    /// Resumption ticket sent to the other party
+    pub(super) issued_ticket: Option<ResumptionTicketPayload>,
+    /// Resumption ticket sent by the other party
+    pub(super) received_ticket: Option<ResumptionTicketPayload>,
+    /// Early data sent by the initiator with the first message of a resumption
+    pub(super) early_data: Option<Vec<u8>>,
 }
 
 /// This struct implements functions common to both initiator and the responder state machines
@@ -68,7 +81,19 @@ pub(super) struct CommonStateMachine {
     pub(super) credentials: Vec<CredentialAndPurposeKey>,
     pub(super) trust_policy: Arc<dyn TrustPolicy>,
     pub(super) trust_context: Option<TrustContext>,
+    pub(super) presentation_route: PresentationRoute,
+    pub(super) signed_messages: bool,
+    pub(super) hide_identity: bool,
+    pub(super) issued_ticket: Option<ResumptionTicketPayload>,
+    pub(super) received_ticket: Option<ResumptionTicketPayload>,
+    /// Attributes which must be given to the other party by its credentials
+    pub(super) required_attributes: BTreeMap<String, String>,
+    /// Accept the other party without the required attributes, with a low trust level
+    pub(super) progressive_trust: bool,
+    /// Early data received with the first message of a resumption
+    pub(super) early_data: Option<Vec<u8>>,
     their_identifier: Option<Identifier>,
+    their_messages_signed: bool,
 }
 
 impl CommonStateMachine {
@@ -79,6 +104,8 @@ impl CommonStateMachine {
         credentials: Vec<CredentialAndPurposeKey>,
         trust_policy: Arc<dyn TrustPolicy>,
         trust_context: Option<TrustContext>,
+        signed_messages: bool,
+        hide_identity: bool,
     ) -> Self {
         Self {
             identities,
@@ -87,7 +114,16 @@ impl CommonStateMachine {
             credentials,
             trust_policy,
             trust_context,
+            presentation_route: PresentationRoute::Direct,
+            signed_messages,
+            hide_identity,
+            issued_ticket: None,
+            received_ticket: None,
+            required_attributes: BTreeMap::new(),
+            progressive_trust: false,
+            early_data: None,
             their_identifier: None,
+            their_messages_signed: false,
         }
     }
 
@@ -97,6 +133,8 @@ impl CommonStateMachine {
     ///  - the current Identity Change History
     ///  - the current Secure Channel Purpose Key Attestation
     ///  - the Identity Credentials and corresponding Credentials Purpose Key Attestations
+    ///  - the signed messages mode, if the messages of the current party will be signed
+    ///  - a resumption ticket, if the current party is a responder issuing tickets
     ///
     pub(super) async fn make_identity_payload(&self) -> Result<Vec<u8>> {
         // prepare the payload that will be sent either in message 2 or message 3
@@ -109,6 +147,8 @@ impl CommonStateMachine {
             change_history,
             purpose_key_attestation: self.purpose_key_attestation.clone(),
             credentials: self.credentials.clone(),
+            signed_messages: self.signed_messages.then_some(true),
+            resumption_ticket: self.issued_ticket.clone(),
         };
         Ok(minicbor::to_vec(payload)?)
     }
@@ -157,6 +197,25 @@ impl CommonStateMachine {
         self.verify_credentials(identity.identifier(), peer.credentials)
             .await?;
         self.their_identifier = Some(identity.identifier().clone());
+        self.their_messages_signed = peer.signed_messages.unwrap_or(false);
+        self.received_ticket = peer.resumption_ticket;
+        Ok(())
+    }
+
+    /// Resume a channel with the other party of a previous channel, known from a resumption ticket.
+    /// Its identity and credentials have already been verified, but the trust policy is checked again
+    pub(super) async fn resume(
+        &mut self,
+        their_identifier: Identifier,
+        their_messages_signed: bool,
+    ) -> Result<()> {
+        let trust_info = SecureChannelTrustInfo::new(their_identifier.clone());
+        if !self.trust_policy.check(&trust_info).await? {
+            return Err(IdentityError::SecureChannelTrustCheckFailed.into());
+        }
+        self.check_required_attributes(&their_identifier).await?;
+        self.their_identifier = Some(their_identifier);
+        self.their_messages_signed = their_messages_signed;
         Ok(())
     }
 
@@ -193,6 +252,7 @@ impl CommonStateMachine {
                         their_identifier,
                         &[trust_context.authority()?.identifier().clone()],
                         credential,
+                        &self.presentation_route,
                     )
                     .await;
 
@@ -210,6 +270,55 @@ impl CommonStateMachine {
             return Err(IdentityError::SecureChannelVerificationFailedMissingTrustContext.into());
         };
 
+        self.check_required_attributes(their_identifier).await
+    }
+
+    /// Check that the verified attributes of the other party have the required values, so that
+    /// a party without the expected credentials is rejected during the handshake.
+    ///
+    /// With progressive trust, a party without any attribute or without the required attributes
+    /// is accepted, but given the [`TRUST_LEVEL_ATTRIBUTE`] with the [`LOW_TRUST_LEVEL`] value,
+    /// until it presents a credential
+    async fn check_required_attributes(&self, their_identifier: &Identifier) -> Result<()> {
+        if self.required_attributes.is_empty() && !self.progressive_trust {
+            return Ok(());
+        }
+        let attributes = self
+            .identities
+            .repository()
+            .get_attributes(their_identifier)
+            .await?;
+        let missing = self.required_attributes.iter().find(|(name, value)| {
+            attributes
+                .as_ref()
+                .and_then(|entry| entry.attrs().get(name.as_bytes()))
+                .map(|v| v.as_slice())
+                != Some(value.as_bytes())
+        });
+        if self.progressive_trust {
+            if attributes.is_none() || missing.is_some() {
+                info!(
+                    "the secure channel with {} is accepted with a low trust level",
+                    their_identifier
+                );
+                self.identities
+                    .repository()
+                    .put_attribute_value(
+                        their_identifier,
+                        TRUST_LEVEL_ATTRIBUTE.as_bytes().to_vec(),
+                        LOW_TRUST_LEVEL.as_bytes().to_vec(),
+                    )
+                    .await?;
+            }
+            return Ok(());
+        }
+        if let Some((name, value)) = missing {
+            warn!(
+                "the attribute {}={} is required for a secure channel with {}",
+                name, value, their_identifier
+            );
+            return Err(IdentityError::SecureChannelRequiredAttributeMissing.into());
+        }
         Ok(())
     }
 
@@ -224,6 +333,10 @@ impl CommonStateMachine {
             (Some(their_identifier), Some(handshake_keys)) => Some(HandshakeResults {
                 their_identifier,
                 handshake_keys,
+                their_messages_signed: self.their_messages_signed,
+                issued_ticket: self.issued_ticket.clone(),
+                received_ticket: self.received_ticket.clone(),
+                early_data: self.early_data.clone(),
             }),
             _ => None,
         }
@@ -244,4 +357,9 @@ pub(super) struct IdentityAndCredentials {
     /// Credentials associated to the identity along with corresponding Credentials Purpose Keys
     /// to verify those Credentials
     #[n(3)] pub(super) credentials: Vec<CredentialAndPurposeKey>,
+    /// True if the messages sent by that party after the handshake are signed with its
+    /// Identity Key
+    #[n(4)] pub(super) signed_messages: Option<bool>,
+    /// Ticket issued by a responder to resume the Secure Channel later
+    #[n(5)] pub(super) resumption_ticket: Option<ResumptionTicketPayload>,
 }
@@ -1,17 +1,19 @@
 use alloc::sync::Arc;
 use core::time::Duration;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::String;
 use ockam_core::compat::{boxed::Box, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{
-    AllowAll, Any, Decodable, DenyAll, Error, Mailbox, Mailboxes, OutgoingAccessControl, Route,
-    Routed,
+    Address, AllowAll, Any, Decodable, DenyAll, Error, Mailbox, Mailboxes, OutgoingAccessControl,
+    Quota, QuotaExceeded, QuotaUsage, Route, Routed, QUOTA_AUDIT_TARGET,
 };
 use ockam_core::{AllowOnwardAddress, Result, Worker};
 use ockam_node::callback::CallbackSender;
 use ockam_node::{Context, WorkerBuilder};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use crate::models::{CredentialAndPurposeKey, Identifier};
+use crate::models::{CredentialAndPurposeKey, Identifier, TimestampInSeconds};
 use crate::secure_channel::decryptor::DecryptorHandler;
 use crate::secure_channel::encryptor::Encryptor;
 use crate::secure_channel::encryptor_worker::EncryptorWorker;
@@ -24,10 +26,13 @@ use crate::secure_channel::handshake::handshake_state_machine::{
 };
 use crate::secure_channel::handshake::initiator_state_machine::InitiatorStateMachine;
 use crate::secure_channel::handshake::responder_state_machine::ResponderStateMachine;
-use crate::secure_channel::{Addresses, Role};
+use crate::secure_channel::message_signing::{MessageSigner, MessageVerifier};
+use crate::secure_channel::{Addresses, Role, SecureChannelStatistics};
+use crate::utils::now;
 use crate::{
-    IdentityError, SecureChannelPurposeKey, SecureChannelRegistryEntry, SecureChannels,
-    TrustContext, TrustPolicy,
+    HandshakePattern, IdentityError, IdentitySecureChannelLocalInfo, PreSharedKey,
+    PresentationRoute, RekeyPolicy, ResumptionTicket, SecureChannelPurposeKey,
+    SecureChannelRegistryEntry, SecureChannels, TrustContext, TrustPolicy,
 };
 
 /// This struct implements a Worker receiving and sending messages
@@ -40,7 +45,12 @@ pub(crate) struct HandshakeWorker {
     addresses: Addresses,
     role: Role,
     remote_route: Option<Route>,
+    quota: Quota,
+    signed_messages: bool,
+    rekey_policy: RekeyPolicy,
     decryptor_handler: Option<DecryptorHandler>,
+    /// Identity of the secure channel carrying the handshake messages, if any
+    via: Option<Identifier>,
 }
 
 #[ockam_core::worker]
@@ -97,20 +107,28 @@ impl Worker for HandshakeWorker {
             return result;
         };
 
+        // The credentials of the other party are checked against the route of its messages
+        self.via = IdentitySecureChannelLocalInfo::find_info(message.local_message())
+            .ok()
+            .map(|info| info.their_identity_id());
+        self.state_machine
+            .set_presentation_route(PresentationRoute::new(self.via.clone()));
+
         let transport_message = message.into_transport_message();
-        if let SendMessage(message) = self
+        let action = self
             .state_machine
             .on_event(ReceivedMessage(Vec::<u8>::decode(
                 &transport_message.payload,
             )?))
-            .await?
-        {
-            // set the remote route by taking the most up to date message return route
-            // In the case of the initiator the first return route mentions the secure channel listener
-            // address so we need to wait for the return route corresponding to the remote handshake worker
-            // when it has been spawned
-            self.remote_route = Some(transport_message.return_route);
+            .await?;
 
+        // set the remote route by taking the most up to date message return route
+        // In the case of the initiator the first return route mentions the secure channel listener
+        // address so we need to wait for the return route corresponding to the remote handshake worker
+        // when it has been spawned. A resumed channel is ready as soon as that route is known
+        self.remote_route = Some(transport_message.return_route);
+
+        if let SendMessage(message) = action {
             context
                 .send_from_address(
                     self.remote_route()?,
@@ -122,8 +140,16 @@ impl Worker for HandshakeWorker {
 
         // if we reached the final state we can make a pair of encryptor/decryptor
         if let Some(final_state) = self.state_machine.get_handshake_results() {
+            let early_data = final_state.early_data.clone();
             // start the encryptor worker and return the decryptor
-            self.decryptor_handler = Some(self.finalize(context, final_state).await?);
+            let decryptor_handler = self.finalize(context, final_state).await?;
+            let decryptor_handler = self.decryptor_handler.insert(decryptor_handler);
+            // deliver the early data sent with the first message of a resumption
+            if let Some(early_data) = early_data {
+                decryptor_handler
+                    .handle_payload(context, early_data)
+                    .await?;
+            }
             if let Some(callback_sender) = self.callback_sender.take() {
                 callback_sender.send(())?;
             }
@@ -148,6 +174,8 @@ impl Worker for HandshakeWorker {
 
 impl HandshakeWorker {
     /// Create a new HandshakeWorker with a role of either INITIATOR or RESPONDER
+    ///
+    /// The initiator uses the first of the handshake patterns, the responder accepts all of them
     #[allow(clippy::too_many_arguments)]
     pub(crate) async fn create(
         context: &Context,
@@ -161,6 +189,18 @@ impl HandshakeWorker {
         trust_context: Option<TrustContext>,
         remote_route: Option<Route>,
         timeout: Option<Duration>,
+        quota: Quota,
+        signed_messages: bool,
+        hide_identity: bool,
+        handshake_patterns: Vec<HandshakePattern>,
+        pre_shared_key: Option<PreSharedKey>,
+        resumption_ticket: Option<ResumptionTicket>,
+        resumption_ticket_lifetime: Option<Duration>,
+        pq_hybrid: bool,
+        rekey_policy: RekeyPolicy,
+        required_attributes: BTreeMap<String, String>,
+        progressive_trust: bool,
+        early_data: Option<Vec<u8>>,
         role: Role,
     ) -> Result<()> {
         let vault = secure_channels.identities.vault().secure_channel_vault;
@@ -175,6 +215,16 @@ impl HandshakeWorker {
                     credentials,
                     trust_policy,
                     trust_context,
+                    signed_messages,
+                    hide_identity,
+                    handshake_patterns
+                        .first()
+                        .copied()
+                        .unwrap_or(HandshakePattern::XX),
+                    pre_shared_key,
+                    resumption_ticket,
+                    pq_hybrid,
+                    early_data,
                 )
                 .await?,
             )
@@ -188,6 +238,14 @@ impl HandshakeWorker {
                     credentials,
                     trust_policy,
                     trust_context,
+                    signed_messages,
+                    hide_identity,
+                    handshake_patterns,
+                    pre_shared_key,
+                    secure_channels.resumption_tickets(),
+                    resumption_ticket_lifetime,
+                    required_attributes,
+                    progressive_trust,
                 )
                 .await?,
             )
@@ -208,7 +266,11 @@ impl HandshakeWorker {
             role,
             remote_route: remote_route.clone(),
             addresses: addresses.clone(),
+            quota,
+            signed_messages,
+            rekey_policy,
             decryptor_handler: None,
+            via: None,
         };
 
         WorkerBuilder::new(worker)
@@ -288,6 +350,44 @@ impl HandshakeWorker {
         context: &Context,
         handshake_results: HandshakeResults,
     ) -> Result<DecryptorHandler> {
+        // the messages sent in both directions are counted against the quota of the channel
+        let quota_usage = if self.quota.is_unlimited() {
+            None
+        } else {
+            Some(QuotaUsage::new(self.quota))
+        };
+
+        // in the signed messages mode, messages are signed with the identity key of the sender
+        let message_signer = if self.signed_messages {
+            let identities = self.secure_channels.identities();
+            let identity = identities.get_identity(&self.identifier).await?;
+            let key = identities
+                .identities_keys()
+                .get_secret_key(&identity)
+                .await?;
+            Some(MessageSigner::new(
+                identities.vault().identity_vault,
+                identities.vault().verifying_vault,
+                key,
+            ))
+        } else {
+            None
+        };
+        let message_verifier = if handshake_results.their_messages_signed {
+            let identities = self.secure_channels.identities();
+            let their_identity = identities
+                .get_identity(&handshake_results.their_identifier)
+                .await?;
+            Some(MessageVerifier::new(
+                identities.vault().verifying_vault,
+                their_identity.get_latest_public_key()?,
+            ))
+        } else {
+            None
+        };
+
+        let statistics = SecureChannelStatistics::new(now().unwrap_or(TimestampInSeconds(0)));
+
         // create a decryptor to delegate the processing of all messages after the handshake
         let decryptor = DecryptorHandler::new(
             self.role.str(),
@@ -295,6 +395,10 @@ impl HandshakeWorker {
             handshake_results.handshake_keys.decryption_key,
             self.secure_channels.identities.vault().secure_channel_vault,
             handshake_results.their_identifier.clone(),
+            self.via.clone(),
+            message_verifier,
+            quota_usage.clone(),
+            statistics.clone(),
         );
 
         // create a separate encryptor worker which will be started independently
@@ -307,7 +411,12 @@ impl HandshakeWorker {
                     handshake_results.handshake_keys.encryption_key,
                     0,
                     self.secure_channels.identities.vault().secure_channel_vault,
-                ),
+                )
+                .with_rekey_policy(self.rekey_policy),
+                handshake_results.their_identifier.clone(),
+                message_signer,
+                quota_usage,
+                statistics.clone(),
             );
 
             let next_hop = self.remote_route()?.next()?.clone();
@@ -328,6 +437,28 @@ impl HandshakeWorker {
                 .await?;
         }
 
+        if let Some(max_duration) = self.quota.max_duration {
+            self.close_after(context, max_duration, &handshake_results.their_identifier)
+                .await?;
+        }
+
+        // keep the resumption tickets, now that the other party has been authenticated
+        let resumption_tickets = self.secure_channels.resumption_tickets();
+        if let Some(ticket) = &handshake_results.issued_ticket {
+            resumption_tickets.issue(
+                ticket,
+                handshake_results.their_identifier.clone(),
+                handshake_results.their_messages_signed,
+            );
+        }
+        if let Some(ticket) = handshake_results.received_ticket {
+            resumption_tickets.receive(
+                ticket,
+                handshake_results.their_identifier.clone(),
+                handshake_results.their_messages_signed,
+            );
+        }
+
         info!(
             "Initialized SecureChannel {} at local: {}, remote: {}",
             self.role.str(),
@@ -351,6 +482,8 @@ impl HandshakeWorker {
             self.identifier.clone(),
             handshake_results.their_identifier,
             their_decryptor_address,
+            self.remote_route()?,
+            statistics,
         );
 
         self.secure_channels
@@ -359,4 +492,36 @@ impl HandshakeWorker {
 
         Ok(decryptor)
     }
+
+    /// Close the channel once it reaches the maximum duration of its quota
+    async fn close_after(
+        &self,
+        context: &Context,
+        max_duration: Duration,
+        their_identifier: &Identifier,
+    ) -> Result<()> {
+        let timer_context = context
+            .new_detached(
+                Address::random_tagged("SecureChannel.quota"),
+                DenyAll,
+                DenyAll,
+            )
+            .await?;
+        let encryptor = self.addresses.encryptor.clone();
+        let their_identifier = their_identifier.clone();
+        context.runtime().spawn(async move {
+            timer_context.sleep(max_duration).await;
+            // stopping the encryptor stops the whole channel, if it is still running
+            if timer_context.stop_worker(encryptor.clone()).await.is_ok() {
+                warn!(
+                    target: QUOTA_AUDIT_TARGET,
+                    %encryptor,
+                    %their_identifier,
+                    "secure channel closed: {}",
+                    QuotaExceeded::Duration(max_duration)
+                );
+            }
+        });
+        Ok(())
+    }
 }
@@ -12,12 +12,19 @@ use Status::*;
 
 use crate::models::{CredentialAndPurposeKey, Identifier};
 use crate::secure_channel::handshake::error::XXError;
+use crate::secure_channel::handshake::extensions::{
+    HandshakeExtensions, HybridKem, HANDSHAKE_EXTENSIONS_VERSION,
+};
 use crate::secure_channel::handshake::handshake::Handshake;
 use crate::secure_channel::handshake::handshake_state_machine::{
     Action, CommonStateMachine, Event, HandshakeKeys, HandshakeResults, IdentityAndCredentials,
     StateMachine, Status,
 };
-use crate::{Identities, Role, SecureChannelPurposeKey, TrustContext, TrustPolicy};
+use crate::secure_channel::{ResumptionRequest, ResumptionResponse, ResumptionTicket};
+use crate::{
+    HandshakePattern, Identities, IdentityError, PreSharedKey, PresentationRoute, Role,
+    SecureChannelPurposeKey, TrustContext, TrustPolicy,
+};
 
 /// Implementation of a state machine for the key exchange on the initiator side
 #[async_trait]
@@ -28,27 +35,105 @@ impl StateMachine for InitiatorStateMachine {
             // Initialize the handshake and send message 1
             (Initial, Initialize) => {
                 self.initialize_handshake().await?;
-                let message1 = self.encode_message1(&[]).await?;
+                // The handshake pattern and its extensions, or the ticket of a resumption,
+                // are announced with message 1
+                let payload = match (&self.resumption_ticket, &self.handshake.kem) {
+                    (Some(ticket), _) => minicbor::to_vec(ResumptionRequest {
+                        ticket_id: (*ticket.id()).into(),
+                        early_data: self.early_data.as_ref().map(|_| true),
+                    })?,
+                    (None, Some(kem)) => minicbor::to_vec(HandshakeExtensions {
+                        version: HANDSHAKE_EXTENSIONS_VERSION,
+                        handshake_pattern: self.handshake_pattern,
+                        kem_public_key: Some(kem.public_key().to_vec().into()),
+                    })?,
+                    (None, None) => self.handshake_pattern.to_payload()?,
+                };
+                let message1 = match self.early_data.take() {
+                    Some(early_data) => {
+                        self.encode_resumption_message1(&payload, &early_data)
+                            .await?
+                    }
+                    None => self.encode_message1(&payload).await?,
+                };
 
                 // Send message 1 and wait for message 2
                 self.handshake.state.status = WaitingForMessage2;
                 Ok(SendMessage(message1))
             }
+            // Process message 2 and complete a resumption
+            (WaitingForMessage2, ReceivedMessage(message)) if self.resumption_ticket.is_some() => {
+                // Message 2 can only be decrypted if the responder knows the secret of the ticket
+                let message2_payload = self
+                    .decode_resumption_message2(&message)
+                    .await
+                    .map_err(|_| IdentityError::InvalidResumptionTicket)?;
+                let response: ResumptionResponse = minicbor::decode(&message2_payload)?;
+                let ticket = self
+                    .resumption_ticket
+                    .take()
+                    .ok_or(XXError::InvalidInternalState)?;
+                self.common
+                    .resume(
+                        ticket.their_identifier().clone(),
+                        ticket.their_messages_signed(),
+                    )
+                    .await?;
+                self.common.received_ticket = response.ticket;
+                self.set_final_state(Initiator).await?;
+                Ok(NoAction)
+            }
             // Process message 2 and send message 3
             (WaitingForMessage2, ReceivedMessage(message)) => {
-                let message2_payload = self.decode_message2(&message).await?;
-                let their_identity_payload: IdentityAndCredentials =
-                    minicbor::decode(&message2_payload)?;
-                self.verify_identity(their_identity_payload, &self.handshake.state.rs()?.clone())
-                    .await?;
+                // With a pre-shared key, message 2 can only be decrypted if the responder
+                // uses the same key
+                let message2_payload = match self.decode_message2(&message).await {
+                    Ok(payload) => payload,
+                    Err(_) if self.handshake_pattern == HandshakePattern::XXPreSharedKey => {
+                        return Err(IdentityError::HandshakePatternMismatch.into())
+                    }
+                    Err(e) => return Err(e),
+                };
                 let identity_payload = self
                     .identity_payload
                     .take()
                     .ok_or(XXError::InvalidInternalState)?;
+
+                // An empty payload means that the responder hides its identity until we are
+                // authenticated. In that case its identity is sent with message 4
+                if message2_payload.is_empty() {
+                    if self.common.hide_identity {
+                        return Err(IdentityError::SecureChannelPeerIdentityHidden.into());
+                    }
+                    let message3 = self.encode_message3(&identity_payload).await?;
+                    self.handshake.state.status = WaitingForMessage4;
+                    return Ok(SendMessage(message3));
+                }
+
+                // A responder which doesn't support handshake patterns sends its identity
+                // even if we asked it to hide it
+                if self.handshake_pattern == HandshakePattern::XXHiddenResponder {
+                    return Err(IdentityError::HandshakePatternMismatch.into());
+                }
+
+                let their_identity_payload: IdentityAndCredentials =
+                    minicbor::decode(&message2_payload)?;
+                self.verify_identity(their_identity_payload, &self.handshake.state.rs()?.clone())
+                    .await?;
                 let message3 = self.encode_message3(&identity_payload).await?;
                 self.set_final_state(Initiator).await?;
                 Ok(SendMessage(message3))
             }
+            // Process message 4, when the responder hides its identity
+            (WaitingForMessage4, ReceivedMessage(message)) => {
+                let message4_payload = self.decode_message4(&message).await?;
+                let their_identity_payload: IdentityAndCredentials =
+                    minicbor::decode(&message4_payload)?;
+                self.verify_identity(their_identity_payload, &self.handshake.state.rs()?.clone())
+                    .await?;
+                self.set_final_state(Initiator).await?;
+                Ok(NoAction)
+            }
             // incorrect state / event
             (s, e) => Err(Error::new(
                 Origin::Channel,
@@ -64,6 +149,10 @@ impl StateMachine for InitiatorStateMachine {
     fn get_handshake_results(&self) -> Option<HandshakeResults> {
         self.make_handshake_results(self.get_handshake_keys())
     }
+
+    fn set_presentation_route(&mut self, presentation_route: PresentationRoute) {
+        self.common.presentation_route = presentation_route;
+    }
 }
 
 /// Implementation of the state machine actions, delegated to the Handshake module
@@ -72,6 +161,12 @@ pub(super) struct InitiatorStateMachine {
     pub(super) handshake: Handshake,
     /// this serialized payload contains an identity, its credentials and a signature of its static key
     pub(super) identity_payload: Option<Vec<u8>>,
+    /// handshake pattern announced to the responder
+    pub(super) handshake_pattern: HandshakePattern,
+    /// ticket used to resume a previous channel, instead of the handshake pattern
+    pub(super) resumption_ticket: Option<ResumptionTicket>,
+    /// data sent with message 1 of a resumption
+    pub(super) early_data: Option<Vec<u8>>,
 }
 
 impl InitiatorStateMachine {
@@ -86,8 +181,11 @@ impl InitiatorStateMachine {
             #[call(initialize)]
             async fn initialize_handshake(&mut self) -> Result<()>;
             async fn encode_message1(&mut self, payload: &[u8]) -> Result<Vec<u8>>;
+            async fn encode_resumption_message1(&mut self, request: &[u8], early_data: &[u8]) -> Result<Vec<u8>>;
             async fn decode_message2(&mut self, message: &[u8]) -> Result<Vec<u8>>;
+            async fn decode_resumption_message2(&mut self, message: &[u8]) -> Result<Vec<u8>>;
             async fn encode_message3(&mut self, payload: &[u8]) -> Result<Vec<u8>>;
+            async fn decode_message4(&mut self, message: &[u8]) -> Result<Vec<u8>>;
             async fn set_final_state(&mut self, role: Role) -> Result<()>;
             fn get_handshake_keys(&self) -> Option<HandshakeKeys>;
         }
@@ -103,7 +201,20 @@ impl InitiatorStateMachine {
         credentials: Vec<CredentialAndPurposeKey>,
         trust_policy: Arc<dyn TrustPolicy>,
         trust_context: Option<TrustContext>,
+        signed_messages: bool,
+        hide_identity: bool,
+        handshake_pattern: HandshakePattern,
+        pre_shared_key: Option<PreSharedKey>,
+        resumption_ticket: Option<ResumptionTicket>,
+        pq_hybrid: bool,
+        early_data: Option<Vec<u8>>,
     ) -> Result<InitiatorStateMachine> {
+        // Early data is only protected by the secret of a ticket, which can only be used once,
+        // and can't be signed before the channel is created
+        if early_data.is_some() && (resumption_ticket.is_none() || signed_messages) {
+            return Err(IdentityError::EarlyMessageNotAllowed.into());
+        }
+
         let common = CommonStateMachine::new(
             identities,
             identifier,
@@ -111,13 +222,31 @@ impl InitiatorStateMachine {
             credentials,
             trust_policy,
             trust_context,
+            signed_messages,
+            hide_identity,
         );
         let identity_payload = common.make_identity_payload().await?;
 
+        let mut handshake = Handshake::new(vault, purpose_key.key().clone()).await?;
+        if let Some(ticket) = &resumption_ticket {
+            handshake.pre_shared_key = Some(ticket.secret().clone());
+        } else if handshake_pattern == HandshakePattern::XXPreSharedKey {
+            handshake.pre_shared_key =
+                Some(pre_shared_key.ok_or(IdentityError::MissingPreSharedKey)?);
+        }
+        // A resumption doesn't use any Diffie-Hellman or KEM key from the previous channel,
+        // and only relies on the secret of the ticket
+        if pq_hybrid && resumption_ticket.is_none() {
+            handshake.kem = Some(HybridKem::initiator()?);
+        }
+
         Ok(InitiatorStateMachine {
             common,
-            handshake: Handshake::new(vault, purpose_key.key().clone()).await?,
+            handshake,
             identity_payload: Some(identity_payload),
+            handshake_pattern,
+            resumption_ticket,
+            early_data,
         })
     }
 }
@@ -1,10 +1,15 @@
 use async_trait::async_trait;
+use core::time::Duration;
 use delegate::delegate;
+use minicbor::Decoder;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::{String, ToString};
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::{boxed::Box, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 use ockam_vault::{VaultForSecureChannels, X25519PublicKey};
+use tracing::warn;
 use Action::*;
 use Event::*;
 use Role::*;
@@ -12,12 +17,19 @@ use Status::*;
 
 use crate::models::{CredentialAndPurposeKey, Identifier};
 use crate::secure_channel::handshake::error::XXError;
+use crate::secure_channel::handshake::extensions::{HandshakeExtensions, HybridKem};
 use crate::secure_channel::handshake::handshake::Handshake;
 use crate::secure_channel::handshake::handshake_state_machine::{
     Action, CommonStateMachine, Event, HandshakeKeys, HandshakeResults, IdentityAndCredentials,
     StateMachine, Status,
 };
-use crate::{Identities, Role, SecureChannelPurposeKey, TrustContext, TrustPolicy};
+use crate::secure_channel::{
+    ResumptionRequest, ResumptionResponse, ResumptionTicketPayload, ResumptionTickets,
+};
+use crate::{
+    HandshakePattern, Identities, IdentityError, PreSharedKey, PresentationRoute, Role,
+    SecureChannelPurposeKey, TrustContext, TrustPolicy,
+};
 
 /// Implementation of a state machine for the key exchange on the responder side
 #[async_trait]
@@ -33,11 +45,26 @@ impl StateMachine for ResponderStateMachine {
             }
             // Process message 1 and send message 2
             (WaitingForMessage1, ReceivedMessage(message)) => {
-                self.decode_message1(&message).await?;
-                let identity_payload = self
-                    .identity_payload
-                    .take()
-                    .ok_or(XXError::InvalidInternalState)?;
+                // A resumption is completed with message 2. The resumption request can be
+                // followed by encrypted early data
+                let mut decoder = Decoder::new(Handshake::read_message1_payload(&message)?);
+                if let Ok(request) = decoder.decode::<ResumptionRequest>() {
+                    let request_length = decoder.position();
+                    let message2 = self.resume(&message, request, request_length).await?;
+                    self.set_final_state(Responder).await?;
+                    return Ok(SendMessage(message2));
+                }
+                let message1_payload = self.decode_message1(&message).await?;
+                self.accept_handshake_pattern(&message1_payload)?;
+                // When hiding our identity, an empty payload is sent and our identity is only
+                // sent with message 4, once the initiator has been authenticated
+                let identity_payload = if self.common.hide_identity {
+                    vec![]
+                } else {
+                    self.identity_payload
+                        .take()
+                        .ok_or(XXError::InvalidInternalState)?
+                };
                 let message2 = self.encode_message2(&identity_payload).await?;
 
                 self.handshake.state.status = WaitingForMessage3;
@@ -50,6 +77,18 @@ impl StateMachine for ResponderStateMachine {
                     minicbor::decode(&message3_payload)?;
                 self.verify_identity(their_identity_payload, &self.handshake.state.rs()?.clone())
                     .await?;
+
+                // Send our identity now that the initiator is authenticated
+                if self.common.hide_identity {
+                    let identity_payload = self
+                        .identity_payload
+                        .take()
+                        .ok_or(XXError::InvalidInternalState)?;
+                    let message4 = self.encode_message4(&identity_payload).await?;
+                    self.set_final_state(Responder).await?;
+                    return Ok(SendMessage(message4));
+                }
+
                 self.set_final_state(Responder).await?;
                 Ok(NoAction)
             }
@@ -68,6 +107,10 @@ impl StateMachine for ResponderStateMachine {
     fn get_handshake_results(&self) -> Option<HandshakeResults> {
         self.make_handshake_results(self.get_handshake_keys())
     }
+
+    fn set_presentation_route(&mut self, presentation_route: PresentationRoute) {
+        self.common.presentation_route = presentation_route;
+    }
 }
 
 pub struct ResponderStateMachine {
@@ -75,6 +118,94 @@ pub struct ResponderStateMachine {
     handshake: Handshake,
     /// this serialized payload contains an identity, its credentials and a signature of its static key
     identity_payload: Option<Vec<u8>>,
+    /// handshake patterns which can be requested by the initiator
+    accepted_handshake_patterns: Vec<HandshakePattern>,
+    /// key used with the XXPreSharedKey handshake pattern
+    pre_shared_key: Option<PreSharedKey>,
+    /// tickets issued by this node, when they can be used to resume a channel
+    resumption_tickets: Option<ResumptionTickets>,
+}
+
+impl ResponderStateMachine {
+    /// Check that the handshake pattern requested by the initiator is accepted and
+    /// configure the handshake for that pattern, and for the requested extensions if any
+    fn accept_handshake_pattern(&mut self, message1_payload: &[u8]) -> Result<()> {
+        let handshake_pattern = match HandshakeExtensions::from_payload(message1_payload)? {
+            Some(extensions) => {
+                if let Some(kem_public_key) = &extensions.kem_public_key {
+                    self.handshake.kem = Some(HybridKem::responder(kem_public_key)?);
+                }
+                extensions.handshake_pattern
+            }
+            None => HandshakePattern::from_payload(message1_payload)
+                .map_err(|_| IdentityError::HandshakePatternNotAccepted)?,
+        };
+        if !self
+            .accepted_handshake_patterns
+            .contains(&handshake_pattern)
+        {
+            let accepted: Vec<String> = self
+                .accepted_handshake_patterns
+                .iter()
+                .map(|p| p.to_string())
+                .collect();
+            warn!(
+                "the handshake pattern {handshake_pattern} is not accepted by this listener. Accepted patterns: {}",
+                accepted.join(", ")
+            );
+            return Err(IdentityError::HandshakePatternNotAccepted.into());
+        }
+
+        match handshake_pattern {
+            HandshakePattern::XX => (),
+            HandshakePattern::XXHiddenResponder => self.common.hide_identity = true,
+            HandshakePattern::XXPreSharedKey => {
+                self.handshake.pre_shared_key = Some(
+                    self.pre_shared_key
+                        .clone()
+                        .ok_or(IdentityError::MissingPreSharedKey)?,
+                )
+            }
+        }
+        Ok(())
+    }
+
+    /// Accept the resumption of a channel with a ticket issued by this node, and return
+    /// message 2 with a new ticket
+    async fn resume(
+        &mut self,
+        message1: &[u8],
+        request: ResumptionRequest,
+        request_length: usize,
+    ) -> Result<Vec<u8>> {
+        let ticket = match self
+            .resumption_tickets
+            .as_ref()
+            .and_then(|tickets| tickets.take_issued(&request.ticket_id))
+        {
+            Some(ticket) => ticket,
+            None => {
+                warn!("a secure channel resumption was requested with an invalid ticket");
+                return Err(IdentityError::InvalidResumptionTicket.into());
+            }
+        };
+        self.common
+            .resume(ticket.their_identifier, ticket.their_messages_signed)
+            .await?;
+        self.handshake.pre_shared_key = Some(ticket.secret);
+        if request.early_data == Some(true) {
+            self.common.early_data = Some(
+                self.decode_resumption_message1(message1, request_length)
+                    .await?,
+            );
+        } else {
+            self.decode_message1(message1).await?;
+        }
+        let payload = minicbor::to_vec(ResumptionResponse {
+            ticket: self.common.issued_ticket.clone(),
+        })?;
+        self.encode_resumption_message2(&payload).await
+    }
 }
 
 impl ResponderStateMachine {
@@ -89,8 +220,11 @@ impl ResponderStateMachine {
             #[call(initialize)]
             async fn initialize_handshake(&mut self) -> Result<()>;
             async fn decode_message1(&mut self, message: &[u8]) -> Result<Vec<u8>>;
+            async fn decode_resumption_message1(&mut self, message: &[u8], request_length: usize) -> Result<Vec<u8>>;
             async fn encode_message2(&mut self, payload: &[u8]) -> Result<Vec<u8>>;
+            async fn encode_resumption_message2(&mut self, payload: &[u8]) -> Result<Vec<u8>>;
             async fn decode_message3(&mut self, message: &[u8]) -> Result<Vec<u8>>;
+            async fn encode_message4(&mut self, payload: &[u8]) -> Result<Vec<u8>>;
             async fn set_final_state(&mut self, role: Role) -> Result<()>;
             fn get_handshake_keys(&self) -> Option<HandshakeKeys>;
         }
@@ -106,21 +240,39 @@ impl ResponderStateMachine {
         credentials: Vec<CredentialAndPurposeKey>,
         trust_policy: Arc<dyn TrustPolicy>,
         trust_context: Option<TrustContext>,
+        signed_messages: bool,
+        hide_identity: bool,
+        accepted_handshake_patterns: Vec<HandshakePattern>,
+        pre_shared_key: Option<PreSharedKey>,
+        resumption_tickets: ResumptionTickets,
+        resumption_ticket_lifetime: Option<Duration>,
+        required_attributes: BTreeMap<String, String>,
+        progressive_trust: bool,
     ) -> Result<ResponderStateMachine> {
-        let common = CommonStateMachine::new(
+        let mut common = CommonStateMachine::new(
             identities,
             identifier,
             purpose_key.attestation().clone(),
             credentials,
             trust_policy,
             trust_context,
+            signed_messages,
+            hide_identity,
         );
+        common.required_attributes = required_attributes;
+        common.progressive_trust = progressive_trust;
+        if let Some(lifetime) = resumption_ticket_lifetime {
+            common.issued_ticket = Some(ResumptionTicketPayload::new(lifetime)?);
+        }
         let identity_payload = common.make_identity_payload().await?;
 
         Ok(ResponderStateMachine {
             common,
             handshake: Handshake::new(vault, purpose_key.key().clone()).await?,
             identity_payload: Some(identity_payload),
+            accepted_handshake_patterns,
+            pre_shared_key,
+            resumption_tickets: resumption_ticket_lifetime.map(|_| resumption_tickets),
         })
     }
 }
@@ -0,0 +1,97 @@
+use core::fmt;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::string::String;
+use ockam_core::compat::vec::Vec;
+
+/// Handshake patterns which can be used to establish a Secure Channel.
+///
+/// All the patterns are variations of the Noise XX pattern. The initiator of a Secure Channel
+/// uses one pattern and announces it with the first handshake message. The listener only
+/// accepts the patterns it has been configured with
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+pub enum HandshakePattern {
+    /// Both parties send their Identity during the handshake, the responder first
+    #[n(0)] XX,
+    /// The responder only sends its Identity once the initiator has been authenticated
+    #[n(1)] XXHiddenResponder,
+    /// A key shared by both parties in advance is mixed into the handshake keys after the
+    /// second message. Parties which don't know that key can't complete the handshake
+    #[n(2)] XXPreSharedKey,
+}
+
+impl HandshakePattern {
+    /// Patterns accepted by a Secure Channel Listener by default
+    pub fn default_accepted() -> Vec<HandshakePattern> {
+        vec![HandshakePattern::XX, HandshakePattern::XXHiddenResponder]
+    }
+
+    /// Return the payload of the first handshake message announcing this pattern.
+    /// The XX pattern is announced with an empty payload, as sent by the initiators which
+    /// don't support handshake patterns
+    pub(crate) fn to_payload(self) -> ockam_core::Result<Vec<u8>> {
+        match self {
+            HandshakePattern::XX => Ok(vec![]),
+            _ => Ok(minicbor::to_vec(self)?),
+        }
+    }
+
+    /// Return the pattern announced by the payload of the first handshake message
+    pub(crate) fn from_payload(payload: &[u8]) -> ockam_core::Result<HandshakePattern> {
+        if payload.is_empty() {
+            Ok(HandshakePattern::XX)
+        } else {
+            Ok(minicbor::decode(payload)?)
+        }
+    }
+}
+
+impl Display for HandshakePattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HandshakePattern::XX => write!(f, "xx"),
+            HandshakePattern::XXHiddenResponder => write!(f, "xx-hidden-responder"),
+            HandshakePattern::XXPreSharedKey => write!(f, "xx-psk"),
+        }
+    }
+}
+
+impl FromStr for HandshakePattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "xx" => Ok(HandshakePattern::XX),
+            "xx-hidden-responder" => Ok(HandshakePattern::XXHiddenResponder),
+            "xx-psk" => Ok(HandshakePattern::XXPreSharedKey),
+            _ => Err(format!(
+                "unknown handshake pattern {s}, it must be one of: xx, xx-hidden-responder, xx-psk"
+            )),
+        }
+    }
+}
+
+/// Key shared in advance by the parties of a Secure Channel using the
+/// [`HandshakePattern::XXPreSharedKey`] pattern
+#[derive(Clone, PartialEq, Eq)]
+pub struct PreSharedKey([u8; 32]);
+
+impl PreSharedKey {
+    /// Create a pre-shared key
+    pub fn new(key: [u8; 32]) -> Self {
+        Self(key)
+    }
+
+    /// Key bytes
+    pub(crate) fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for PreSharedKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "PreSharedKey(<redacted>)")
+    }
+}
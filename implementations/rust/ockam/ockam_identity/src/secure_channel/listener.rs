@@ -110,6 +110,18 @@ impl Worker for IdentityChannelListener {
             self.options.trust_context.clone(),
             None,
             None,
+            self.options.quota,
+            self.options.signed_messages,
+            self.options.hide_identity,
+            self.options.handshake_patterns.clone(),
+            self.options.pre_shared_key.clone(),
+            None,
+            self.options.resumption_ticket_lifetime,
+            false,
+            self.options.rekey_policy,
+            self.options.required_attributes.clone(),
+            self.options.progressive_trust,
+            None,
             Role::Responder,
         )
         .await?;
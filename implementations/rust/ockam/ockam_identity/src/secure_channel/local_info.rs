@@ -2,8 +2,8 @@ use ockam_core::compat::vec::Vec;
 use ockam_core::{Decodable, Encodable, LocalInfo, LocalMessage, Result};
 use serde::{Deserialize, Serialize};
 
-use crate::models::Identifier;
-use crate::IdentityError;
+use crate::models::{Identifier, SignedMessage};
+use crate::{IdentityError, PresentationRoute};
 
 /// Identity SecureChannel LocalInfo unique Identifier
 pub const IDENTITY_SECURE_CHANNEL_IDENTIFIER: &str = "IDENTITY_SECURE_CHANNEL_IDENTIFIER";
@@ -12,6 +12,8 @@ pub const IDENTITY_SECURE_CHANNEL_IDENTIFIER: &str = "IDENTITY_SECURE_CHANNEL_ID
 #[derive(Serialize, Deserialize)]
 pub struct IdentitySecureChannelLocalInfo {
     their_identity_id: Identifier,
+    via: Option<Identifier>,
+    signed_message: Option<Vec<u8>>,
 }
 
 impl IdentitySecureChannelLocalInfo {
@@ -59,20 +61,54 @@ impl IdentitySecureChannelLocalInfo {
     pub fn their_identity_id(&self) -> Identifier {
         self.their_identity_id.clone()
     }
+
+    /// Route over which the secure channel was established
+    pub fn presentation_route(&self) -> PresentationRoute {
+        PresentationRoute::new(self.via.clone())
+    }
+
+    /// Return true if the message was signed with the Identity Key of the other party
+    /// and its signature was verified
+    pub fn is_signed(&self) -> bool {
+        self.signed_message.is_some()
+    }
+
+    /// Return the verified [`SignedMessage`], if the message was sent in the signed messages mode.
+    /// It can be kept as a proof that the other party sent that message
+    pub fn signed_message(&self) -> Result<Option<SignedMessage>> {
+        match &self.signed_message {
+            Some(signed_message) => Ok(Some(minicbor::decode(signed_message)?)),
+            None => Ok(None),
+        }
+    }
 }
 
 impl IdentitySecureChannelLocalInfo {
     /// Mark a `LocalInfo` vector with `IdentitySecureChannelLocalInfo`
     /// replacing any pre-existing entries
+    ///
+    /// `via` is the identity of the secure channel which carried the handshake of this secure
+    /// channel, if any. `signed_message` is the verified message, in the signed messages mode
     pub fn mark(
         mut local_info: Vec<LocalInfo>,
         their_identity_id: Identifier,
+        via: Option<Identifier>,
+        signed_message: Option<SignedMessage>,
     ) -> Result<Vec<LocalInfo>> {
+        let signed_message = signed_message.map(minicbor::to_vec).transpose()?;
+
         // strip out any pre-existing IdentitySecureChannelLocalInfo
         local_info.retain(|x| x.type_identifier() != IDENTITY_SECURE_CHANNEL_IDENTIFIER);
 
         // mark the vector
-        local_info.push(Self { their_identity_id }.to_local_info()?);
+        local_info.push(
+            Self {
+                their_identity_id,
+                via,
+                signed_message,
+            }
+            .to_local_info()?,
+        );
 
         Ok(local_info)
     }
@@ -0,0 +1,75 @@
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use ockam_vault::{
+    SigningSecretKeyHandle, VaultForSigning, VaultForVerifyingSignatures, VerifyingPublicKey,
+};
+
+use crate::models::SignedMessage;
+use crate::IdentityError;
+
+/// Sign the messages sent over a secure channel with the Identity Key of the sender
+pub(crate) struct MessageSigner {
+    vault: Arc<dyn VaultForSigning>,
+    verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
+    key: SigningSecretKeyHandle,
+}
+
+impl MessageSigner {
+    pub fn new(
+        vault: Arc<dyn VaultForSigning>,
+        verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
+        key: SigningSecretKeyHandle,
+    ) -> Self {
+        Self {
+            vault,
+            verifying_vault,
+            key,
+        }
+    }
+
+    /// Return a [`SignedMessage`] for an encoded `TransportMessage`
+    pub async fn sign(&self, message: Vec<u8>) -> Result<SignedMessage> {
+        let hash = self.verifying_vault.sha256(&message).await?;
+        let signature = self.vault.sign(&self.key, &hash.0).await?;
+        Ok(SignedMessage {
+            message,
+            signature: signature.into(),
+        })
+    }
+}
+
+/// Verify the signature of the messages received over a secure channel with the Identity Key of
+/// the other party
+pub(crate) struct MessageVerifier {
+    vault: Arc<dyn VaultForVerifyingSignatures>,
+    public_key: VerifyingPublicKey,
+}
+
+impl MessageVerifier {
+    pub fn new(
+        vault: Arc<dyn VaultForVerifyingSignatures>,
+        public_key: VerifyingPublicKey,
+    ) -> Self {
+        Self { vault, public_key }
+    }
+
+    /// Verify a [`SignedMessage`] and return it if it is valid
+    pub async fn verify(&self, message: &[u8]) -> Result<SignedMessage> {
+        let signed_message: SignedMessage = minicbor::decode(message)?;
+        let hash = self.vault.sha256(&signed_message.message).await?;
+        if self
+            .vault
+            .verify_signature(
+                &self.public_key,
+                &hash.0,
+                &signed_message.signature.clone().into(),
+            )
+            .await?
+        {
+            Ok(signed_message)
+        } else {
+            Err(IdentityError::MessageSignatureVerificationFailed.into())
+        }
+    }
+}
@@ -6,13 +6,19 @@ mod decryptor;
 mod encryptor;
 mod encryptor_worker;
 mod handshake;
+mod handshake_pattern;
 mod key_tracker;
 mod listener;
 mod local_info;
+mod message_signing;
 mod nonce_tracker;
 mod options;
 mod registry;
+mod rekey_policy;
+mod resumption;
+mod retry_policy;
 mod role;
+mod statistics;
 /// List of trust policies to setup ABAC controls
 pub mod trust_policy;
 
@@ -20,16 +26,23 @@ pub use access_control::*;
 pub(crate) use addresses::*;
 pub use api::*;
 pub(crate) use handshake::*;
+pub use handshake_pattern::*;
 pub(crate) use listener::*;
 pub use local_info::*;
 pub use options::*;
 pub use registry::*;
+pub use rekey_policy::*;
+pub use resumption::*;
+pub use retry_policy::*;
 pub(crate) use role::*;
+pub use statistics::*;
 pub use trust_policy::*;
 
 #[cfg(test)]
 mod tests {
-    use crate::secure_channel::{decryptor::Decryptor, encryptor::Encryptor};
+    use crate::secure_channel::decryptor::Decryptor;
+    use crate::secure_channel::encryptor::{Encryptor, KEY_RENEWAL_INTERVAL};
+    use crate::RekeyPolicy;
     use ockam_core::compat::rand::RngCore;
     use ockam_core::Result;
     use ockam_vault::{SoftwareVaultForSecureChannels, VaultForSecureChannels};
@@ -105,6 +118,30 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_rekey_policy() {
+        let (encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
+        let mut encryptor =
+            encryptor.with_rekey_policy(RekeyPolicy::default().with_after_bytes(10));
+
+        for n in 0..100 {
+            // The key is renewed every 3 messages, while some messages are still in transit
+            let msgs = vec![vec![n; 4], vec![n + 1; 4]];
+            let mut ciphertexts = Vec::new();
+            for msg in msgs.iter() {
+                ciphertexts.push(encryptor.encrypt(msg).await.unwrap());
+            }
+            for (msg, ciphertext) in msgs.iter().zip(ciphertexts.iter()).rev() {
+                assert_eq!(msg, &decryptor.decrypt(ciphertext).await.unwrap());
+            }
+        }
+        // 200 messages were sent over 67 keys, skipping the remaining nonces of each key
+        let ciphertext = encryptor.encrypt(&[0]).await.unwrap();
+        let mut nonce = [0u8; 8];
+        nonce.copy_from_slice(&ciphertext[0..8]);
+        assert!(u64::from_be_bytes(nonce) >= 66 * KEY_RENEWAL_INTERVAL);
+    }
+
     #[tokio::test]
     async fn test_attack_nonce() {
         let (mut encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
@@ -1,10 +1,14 @@
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::String;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
-use ockam_core::{Address, OutgoingAccessControl, Result};
+use ockam_core::{Address, OutgoingAccessControl, Quota, Result, Route, TransportMessage};
 
 use crate::models::CredentialAndPurposeKey;
-use crate::secure_channel::Addresses;
+use crate::secure_channel::{
+    Addresses, HandshakePattern, HandshakeRetryPolicy, PreSharedKey, RekeyPolicy, ResumptionTicket,
+};
 use crate::{TrustContext, TrustEveryonePolicy, TrustPolicy};
 
 use core::fmt;
@@ -14,6 +18,13 @@ use core::time::Duration;
 /// This is the default timeout for creating a secure channel
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
 
+/// Attribute given to the initiators accepted with progressive trust, see
+/// [`SecureChannelListenerOptions::with_progressive_trust`]
+pub const TRUST_LEVEL_ATTRIBUTE: &str = "trust_level";
+
+/// Trust level of the initiators which haven't presented the expected credential yet
+pub const LOW_TRUST_LEVEL: &str = "low";
+
 /// Trust options for a Secure Channel
 pub struct SecureChannelOptions {
     pub(crate) flow_control_id: FlowControlId,
@@ -21,6 +32,16 @@ pub struct SecureChannelOptions {
     pub(crate) trust_context: Option<TrustContext>,
     pub(crate) credentials: Vec<CredentialAndPurposeKey>,
     pub(crate) timeout: Duration,
+    pub(crate) retry_policy: HandshakeRetryPolicy,
+    pub(crate) quota: Quota,
+    pub(crate) signed_messages: bool,
+    pub(crate) hide_identity: bool,
+    pub(crate) handshake_pattern: HandshakePattern,
+    pub(crate) pre_shared_key: Option<PreSharedKey>,
+    pub(crate) resumption_ticket: Option<ResumptionTicket>,
+    pub(crate) pq_hybrid: bool,
+    pub(crate) rekey_policy: RekeyPolicy,
+    pub(crate) early_message: Option<TransportMessage>,
 }
 
 impl fmt::Debug for SecureChannelOptions {
@@ -43,6 +64,16 @@ impl SecureChannelOptions {
             trust_context: None,
             credentials: vec![],
             timeout: DEFAULT_TIMEOUT,
+            retry_policy: HandshakeRetryPolicy::default(),
+            quota: Quota::default(),
+            signed_messages: false,
+            hide_identity: false,
+            handshake_pattern: HandshakePattern::XX,
+            pre_shared_key: None,
+            resumption_ticket: None,
+            pq_hybrid: false,
+            rekey_policy: RekeyPolicy::default(),
+            early_message: None,
         }
     }
 
@@ -52,6 +83,14 @@ impl SecureChannelOptions {
         self
     }
 
+    /// Start a new handshake when a handshake doesn't complete before the timeout, according to
+    /// the given [`HandshakeRetryPolicy`]. Only the first handshake uses the resumption ticket
+    /// and the early message, if any, since a ticket can only be used once
+    pub fn with_retry_policy(mut self, retry_policy: HandshakeRetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
     /// Adds provided credentials
     pub fn with_credentials(mut self, credentials: Vec<CredentialAndPurposeKey>) -> Self {
         self.credentials.extend(credentials);
@@ -76,6 +115,84 @@ impl SecureChannelOptions {
         self
     }
 
+    /// Close the Secure Channel when it exceeds the given [`Quota`]
+    pub fn with_quota(mut self, quota: Quota) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Sign each message sent over the Secure Channel with the Identity Key, so that the other
+    /// party can keep a proof of the messages it received
+    pub fn with_signed_messages(mut self) -> Self {
+        self.signed_messages = true;
+        self
+    }
+
+    /// Never reveal the Identity to a responder hiding its own Identity until the initiator has
+    /// been authenticated. Note that the Identity of the initiator is otherwise always sent after
+    /// the responder has been authenticated
+    pub fn with_identity_hiding(mut self) -> Self {
+        self.hide_identity = true;
+        self
+    }
+
+    /// Use a handshake pattern different from the default [`HandshakePattern::XX`].
+    /// The Secure Channel creation fails if the listener doesn't accept that pattern
+    pub fn with_handshake_pattern(mut self, handshake_pattern: HandshakePattern) -> Self {
+        self.handshake_pattern = handshake_pattern;
+        self
+    }
+
+    /// Use the [`HandshakePattern::XXPreSharedKey`] pattern with the given key
+    pub fn with_pre_shared_key(mut self, pre_shared_key: PreSharedKey) -> Self {
+        self.handshake_pattern = HandshakePattern::XXPreSharedKey;
+        self.pre_shared_key = Some(pre_shared_key);
+        self
+    }
+
+    /// Resume a Secure Channel with a ticket sent by the responder of a previous channel, see
+    /// [`SecureChannels::resumption_ticket`](crate::SecureChannels::resumption_ticket).
+    /// The channel creation fails if the listener doesn't accept the ticket anymore, so a short
+    /// timeout can be set to quickly fall back to a full handshake
+    pub fn with_resumption_ticket(mut self, resumption_ticket: ResumptionTicket) -> Self {
+        self.resumption_ticket = Some(resumption_ticket);
+        self
+    }
+
+    /// Send a message with the first handshake message of a resumption, see
+    /// [`SecureChannelOptions::with_resumption_ticket`], saving a round trip for short
+    /// request / response exchanges over high-latency links. The onward route of the message
+    /// starts on the other side of the channel, like the routes following the channel address.
+    ///
+    /// The message is only protected by the secret of the ticket, which can only be used once,
+    /// and not by the keys of the new channel. The channel creation fails if there is no ticket,
+    /// or if the messages are signed
+    pub fn with_early_message(
+        mut self,
+        onward_route: impl Into<Route>,
+        return_route: impl Into<Route>,
+        payload: Vec<u8>,
+    ) -> Self {
+        self.early_message = Some(TransportMessage::v1(onward_route, return_route, payload));
+        self
+    }
+
+    /// Combine the X25519 key agreement of the handshake with a Kyber768 key encapsulation,
+    /// to protect long-lived channels against the recording of their traffic until it can be
+    /// decrypted by a quantum computer. The channel creation fails if the listener doesn't support
+    /// this handshake extension
+    pub fn with_pq_hybrid(mut self) -> Self {
+        self.pq_hybrid = true;
+        self
+    }
+
+    /// Renew the keys used to encrypt messages according to the given [`RekeyPolicy`],
+    /// in addition to the renewal every 32 messages
+    pub fn with_rekey_policy(mut self, rekey_policy: RekeyPolicy) -> Self {
+        self.rekey_policy = rekey_policy;
+        self
+    }
+
     /// Freshly generated [`FlowControlId`]
     pub fn producer_flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
@@ -130,6 +247,15 @@ pub struct SecureChannelListenerOptions {
     pub(crate) trust_policy: Arc<dyn TrustPolicy>,
     pub(crate) trust_context: Option<TrustContext>,
     pub(crate) credentials: Vec<CredentialAndPurposeKey>,
+    pub(crate) quota: Quota,
+    pub(crate) signed_messages: bool,
+    pub(crate) hide_identity: bool,
+    pub(crate) handshake_patterns: Vec<HandshakePattern>,
+    pub(crate) pre_shared_key: Option<PreSharedKey>,
+    pub(crate) resumption_ticket_lifetime: Option<Duration>,
+    pub(crate) rekey_policy: RekeyPolicy,
+    pub(crate) required_attributes: BTreeMap<String, String>,
+    pub(crate) progressive_trust: bool,
 }
 
 impl fmt::Debug for SecureChannelListenerOptions {
@@ -150,6 +276,15 @@ impl SecureChannelListenerOptions {
             trust_policy: Arc::new(TrustEveryonePolicy),
             trust_context: None,
             credentials: vec![],
+            quota: Quota::default(),
+            signed_messages: false,
+            hide_identity: false,
+            handshake_patterns: HandshakePattern::default_accepted(),
+            pre_shared_key: None,
+            resumption_ticket_lifetime: None,
+            rekey_policy: RekeyPolicy::default(),
+            required_attributes: BTreeMap::new(),
+            progressive_trust: false,
         }
     }
 
@@ -186,6 +321,78 @@ impl SecureChannelListenerOptions {
         self
     }
 
+    /// Close each spawned Secure Channel when it exceeds the given [`Quota`]
+    pub fn with_quota(mut self, quota: Quota) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Sign each message sent over the spawned Secure Channels with the Identity Key
+    pub fn with_signed_messages(mut self) -> Self {
+        self.signed_messages = true;
+        self
+    }
+
+    /// Hide the Identity of the listener until the initiator of each spawned Secure Channel has
+    /// been authenticated and trusted. The Identity is then sent with an additional handshake
+    /// message, so that only trusted initiators can learn it
+    pub fn with_identity_hiding(mut self) -> Self {
+        self.hide_identity = true;
+        self
+    }
+
+    /// Only accept the given handshake patterns, instead of [`HandshakePattern::default_accepted`].
+    /// Initiators requesting another pattern are rejected during the handshake
+    pub fn with_handshake_patterns(mut self, handshake_patterns: Vec<HandshakePattern>) -> Self {
+        self.handshake_patterns = handshake_patterns;
+        self
+    }
+
+    /// Only accept initiators using the [`HandshakePattern::XXPreSharedKey`] pattern with the
+    /// given key
+    pub fn with_pre_shared_key(mut self, pre_shared_key: PreSharedKey) -> Self {
+        self.handshake_patterns = vec![HandshakePattern::XXPreSharedKey];
+        self.pre_shared_key = Some(pre_shared_key);
+        self
+    }
+
+    /// Send a [`ResumptionTicket`] to the initiators, valid for the given duration, and accept
+    /// the resumption of their channels with these tickets
+    pub fn with_resumption_tickets(mut self, lifetime: Duration) -> Self {
+        self.resumption_ticket_lifetime = Some(lifetime);
+        self
+    }
+
+    /// Reject the initiators whose credentials, presented during the handshake, don't give them
+    /// the attribute `name` with the given value. Can be called several times to require
+    /// several attributes. A trust context is needed to verify the credentials
+    pub fn with_required_attribute(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.required_attributes.insert(name.into(), value.into());
+        self
+    }
+
+    /// Accept the initiators which have no attributes yet, or not the required ones, instead of
+    /// rejecting them. They are given the [`TRUST_LEVEL_ATTRIBUTE`] with the [`LOW_TRUST_LEVEL`]
+    /// value, so that access controls can restrict them, for example to an enrollment service.
+    ///
+    /// Once such an initiator presents a credential over the channel, its attributes are replaced
+    /// by the attributes of the credential, which upgrades its trust level
+    pub fn with_progressive_trust(mut self) -> Self {
+        self.progressive_trust = true;
+        self
+    }
+
+    /// Renew the keys used by the spawned Secure Channels to encrypt messages according to the
+    /// given [`RekeyPolicy`], in addition to the renewal every 32 messages
+    pub fn with_rekey_policy(mut self, rekey_policy: RekeyPolicy) -> Self {
+        self.rekey_policy = rekey_policy;
+        self
+    }
+
     /// Freshly generated [`FlowControlId`]
     pub fn spawner_flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
@@ -1,9 +1,10 @@
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::compat::vec::Vec;
-use ockam_core::{Address, Result};
+use ockam_core::{Address, Result, Route};
 
 use crate::models::Identifier;
+use crate::secure_channel::SecureChannelStatistics;
 use crate::IdentityError;
 
 /// Known information about particular SecureChannel
@@ -17,6 +18,8 @@ pub struct SecureChannelRegistryEntry {
     my_id: Identifier,
     their_id: Identifier,
     their_decryptor_address: Address,
+    remote_route: Route,
+    statistics: SecureChannelStatistics,
 }
 
 impl SecureChannelRegistryEntry {
@@ -31,6 +34,8 @@ impl SecureChannelRegistryEntry {
         my_id: Identifier,
         their_id: Identifier,
         their_decryptor_address: Address,
+        remote_route: Route,
+        statistics: SecureChannelStatistics,
     ) -> Self {
         Self {
             encryptor_messaging_address,
@@ -41,6 +46,8 @@ impl SecureChannelRegistryEntry {
             my_id,
             their_id,
             their_decryptor_address,
+            remote_route,
+            statistics,
         }
     }
 
@@ -83,6 +90,16 @@ impl SecureChannelRegistryEntry {
     pub fn their_decryptor_address(&self) -> Address {
         self.their_decryptor_address.clone()
     }
+
+    /// Route to their `Decryptor`
+    pub fn remote_route(&self) -> &Route {
+        &self.remote_route
+    }
+
+    /// Live statistics of the channel
+    pub fn statistics(&self) -> &SecureChannelStatistics {
+        &self.statistics
+    }
 }
 
 /// Registry of all known Secure Channels
@@ -0,0 +1,39 @@
+use core::time::Duration;
+
+/// Limits on the use of a Secure Channel key before it is renewed.
+///
+/// The keys of a Secure Channel are always renewed every 32 messages.
+/// With a [`RekeyPolicy`] they are also renewed as soon as a key has encrypted the given number
+/// of bytes, or has been in use for the given duration. The encryptor then moves its nonce to the
+/// next renewal interval, so that the other party renews its key with the next message, without
+/// dropping the messages still in transit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RekeyPolicy {
+    /// Maximum number of bytes encrypted with the same key
+    pub after_bytes: Option<u64>,
+    /// Maximum duration of use of the same key
+    pub after_duration: Option<Duration>,
+}
+
+impl RekeyPolicy {
+    /// Set the maximum number of bytes
+    pub fn with_after_bytes(mut self, after_bytes: u64) -> Self {
+        self.after_bytes = Some(after_bytes);
+        self
+    }
+
+    /// Set the maximum duration
+    pub fn with_after_duration(mut self, after_duration: Duration) -> Self {
+        self.after_duration = Some(after_duration);
+        self
+    }
+
+    /// Return true if a key must be renewed after encrypting the given number of bytes
+    /// over the given number of seconds
+    pub(crate) fn is_exceeded(&self, bytes: u64, elapsed_seconds: u64) -> bool {
+        self.after_bytes.map_or(false, |max| bytes >= max)
+            || self
+                .after_duration
+                .map_or(false, |max| elapsed_seconds >= max.as_secs())
+    }
+}
@@ -0,0 +1,242 @@
+use core::fmt;
+use core::fmt::Formatter;
+use core::time::Duration;
+use minicbor::bytes::ByteArray;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::rand::{thread_rng, RngCore};
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::Result;
+
+use crate::models::{Identifier, TimestampInSeconds};
+use crate::secure_channel::PreSharedKey;
+use crate::utils::{add_seconds, now};
+
+/// Number of bytes of the identifier of a resumption ticket
+pub(crate) const RESUMPTION_TICKET_ID_LENGTH: usize = 16;
+
+type ResumptionTicketId = [u8; RESUMPTION_TICKET_ID_LENGTH];
+
+/// Ticket used by the initiator of a Secure Channel to resume it, after the previous channel
+/// with the same responder has been interrupted, for example by a transport drop.
+///
+/// A resumed channel is created with 2 handshake messages instead of 3, and the Identities and
+/// credentials of both parties are not exchanged and verified again: the keys of the channel are
+/// derived from fresh ephemeral keys and from a secret shared by both parties when the ticket was
+/// issued. A ticket can only be used once, the resumed channel comes with a new ticket.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ResumptionTicket {
+    id: ResumptionTicketId,
+    secret: PreSharedKey,
+    their_identifier: Identifier,
+    their_messages_signed: bool,
+    expires_at: TimestampInSeconds,
+}
+
+impl ResumptionTicket {
+    /// Identifier of the responder which issued the ticket
+    pub fn their_identifier(&self) -> &Identifier {
+        &self.their_identifier
+    }
+
+    /// Expiration time of the ticket
+    pub fn expires_at(&self) -> TimestampInSeconds {
+        self.expires_at
+    }
+
+    pub(crate) fn id(&self) -> &ResumptionTicketId {
+        &self.id
+    }
+
+    pub(crate) fn secret(&self) -> &PreSharedKey {
+        &self.secret
+    }
+
+    pub(crate) fn their_messages_signed(&self) -> bool {
+        self.their_messages_signed
+    }
+}
+
+impl fmt::Debug for ResumptionTicket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResumptionTicket")
+            .field("their_identifier", &self.their_identifier)
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+/// Ticket sent by a responder with its Identity, or with the second message of a resumption
+#[derive(Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub(crate) struct ResumptionTicketPayload {
+    #[n(1)] id: ByteArray<RESUMPTION_TICKET_ID_LENGTH>,
+    #[n(2)] secret: ByteArray<32>,
+    #[n(3)] expires_at: TimestampInSeconds,
+}
+
+impl ResumptionTicketPayload {
+    /// Create a random ticket, valid for the given duration
+    pub(crate) fn new(lifetime: Duration) -> Result<Self> {
+        let mut id = [0u8; RESUMPTION_TICKET_ID_LENGTH];
+        let mut secret = [0u8; 32];
+        thread_rng().fill_bytes(&mut id);
+        thread_rng().fill_bytes(&mut secret);
+        Ok(Self {
+            id: id.into(),
+            secret: secret.into(),
+            expires_at: add_seconds(&now()?, lifetime.as_secs()),
+        })
+    }
+}
+
+impl fmt::Debug for ResumptionTicketPayload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "ResumptionTicketPayload(<redacted>)")
+    }
+}
+
+/// Payload of the first message of a resumption, sent by the initiator
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub(crate) struct ResumptionRequest {
+    #[n(1)] pub(crate) ticket_id: ByteArray<RESUMPTION_TICKET_ID_LENGTH>,
+    /// True if the request is followed by encrypted early data
+    #[n(2)] pub(crate) early_data: Option<bool>,
+}
+
+/// Payload of the second message of a resumption, sent by the responder
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub(crate) struct ResumptionResponse {
+    #[n(1)] pub(crate) ticket: Option<ResumptionTicketPayload>,
+}
+
+/// Ticket issued by a listener, until it is used by the initiator
+#[derive(Clone)]
+pub(crate) struct IssuedTicket {
+    pub(crate) secret: PreSharedKey,
+    pub(crate) their_identifier: Identifier,
+    pub(crate) their_messages_signed: bool,
+    expires_at: TimestampInSeconds,
+}
+
+/// Resumption tickets issued by the Secure Channel listeners, and received by the initiators
+#[derive(Clone, Default)]
+pub struct ResumptionTickets {
+    issued: Arc<RwLock<BTreeMap<ResumptionTicketId, IssuedTicket>>>,
+    // The last ticket received from each responder
+    received: Arc<RwLock<BTreeMap<Identifier, ResumptionTicket>>>,
+}
+
+impl ResumptionTickets {
+    /// Return the ticket received from a responder, if it has not expired
+    pub fn get(&self, their_identifier: &Identifier) -> Option<ResumptionTicket> {
+        let ticket = self
+            .received
+            .read()
+            .unwrap()
+            .get(their_identifier)
+            .cloned()?;
+        if is_expired(ticket.expires_at) {
+            self.received.write().unwrap().remove(their_identifier);
+            return None;
+        }
+        Some(ticket)
+    }
+
+    /// Store the ticket sent by a responder, replacing the previous one
+    pub(crate) fn receive(
+        &self,
+        payload: ResumptionTicketPayload,
+        their_identifier: Identifier,
+        their_messages_signed: bool,
+    ) {
+        let ticket = ResumptionTicket {
+            id: *payload.id,
+            secret: PreSharedKey::new(*payload.secret),
+            their_identifier: their_identifier.clone(),
+            their_messages_signed,
+            expires_at: payload.expires_at,
+        };
+        self.received
+            .write()
+            .unwrap()
+            .insert(their_identifier, ticket);
+    }
+
+    /// Forget a received ticket, once it has been used
+    pub(crate) fn forget(&self, ticket: &ResumptionTicket) {
+        let mut received = self.received.write().unwrap();
+        if received.get(&ticket.their_identifier).map(|t| t.id) == Some(ticket.id) {
+            received.remove(&ticket.their_identifier);
+        }
+    }
+
+    /// Keep a ticket sent to an initiator, once the initiator has been authenticated
+    pub(crate) fn issue(
+        &self,
+        payload: &ResumptionTicketPayload,
+        their_identifier: Identifier,
+        their_messages_signed: bool,
+    ) {
+        let mut issued = self.issued.write().unwrap();
+        issued.retain(|_, ticket| !is_expired(ticket.expires_at));
+        issued.insert(
+            *payload.id,
+            IssuedTicket {
+                secret: PreSharedKey::new(*payload.secret),
+                their_identifier,
+                their_messages_signed,
+                expires_at: payload.expires_at,
+            },
+        );
+    }
+
+    /// Remove an issued ticket to use it, if it has not expired
+    pub(crate) fn take_issued(&self, id: &ResumptionTicketId) -> Option<IssuedTicket> {
+        let ticket = self.issued.write().unwrap().remove(id)?;
+        if is_expired(ticket.expires_at) {
+            None
+        } else {
+            Some(ticket)
+        }
+    }
+}
+
+/// A ticket is considered as expired when the current time is unknown
+fn is_expired(expires_at: TimestampInSeconds) -> bool {
+    now().map_or(true, |now| now >= expires_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tickets_are_used_once() {
+        let tickets = ResumptionTickets::default();
+        let identifier: Identifier = "Ie92f183eb4c324804ef4d62962dea94cf095a265"
+            .try_into()
+            .unwrap();
+        let payload = ResumptionTicketPayload::new(Duration::from_secs(60)).unwrap();
+
+        tickets.issue(&payload, identifier.clone(), false);
+        let issued = tickets.take_issued(&payload.id).unwrap();
+        assert_eq!(issued.their_identifier, identifier);
+        assert!(tickets.take_issued(&payload.id).is_none());
+
+        tickets.receive(payload.clone(), identifier.clone(), true);
+        let ticket = tickets.get(&identifier).unwrap();
+        assert_eq!(ticket.secret(), &issued.secret);
+        tickets.forget(&ticket);
+        assert!(tickets.get(&identifier).is_none());
+
+        let expired = ResumptionTicketPayload::new(Duration::ZERO).unwrap();
+        tickets.issue(&expired, identifier, false);
+        assert!(tickets.take_issued(&expired.id).is_none());
+    }
+}
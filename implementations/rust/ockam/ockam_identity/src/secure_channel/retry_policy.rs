@@ -0,0 +1,73 @@
+use core::time::Duration;
+
+/// Retries of a Secure Channel handshake which didn't complete before its timeout.
+///
+/// By default a handshake is not retried. With a [`HandshakeRetryPolicy`] a new handshake is
+/// started after each timeout, up to the given number of retries, waiting between two attempts
+/// for a backoff which starts at `initial_backoff` and is doubled after each retry, up to
+/// `max_backoff`. Other handshake failures, like a rejected identity, are never retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandshakeRetryPolicy {
+    /// Maximum number of handshakes started after the first one
+    pub max_retries: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Maximum delay between two attempts
+    pub max_backoff: Duration,
+}
+
+impl Default for HandshakeRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl HandshakeRetryPolicy {
+    /// Set the maximum number of retries
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the delay before the first retry
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Set the maximum delay between two attempts
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Return the delay before the given retry, starting at 0
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.checked_pow(retry).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = HandshakeRetryPolicy::default()
+            .with_initial_backoff(Duration::from_millis(500))
+            .with_max_backoff(Duration::from_secs(3));
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(500));
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(3));
+        assert_eq!(policy.backoff(40), Duration::from_secs(3));
+    }
+}
@@ -0,0 +1,77 @@
+use ockam_core::compat::sync::{Arc, RwLock};
+
+use crate::models::TimestampInSeconds;
+use crate::utils::now;
+
+/// Live statistics of a Secure Channel
+///
+/// Clones share the same counters, so that the encryptor and the decryptor of a channel
+/// update them together, while they can be read from the [`crate::SecureChannelRegistry`].
+#[derive(Debug, Clone)]
+pub struct SecureChannelStatistics {
+    created_at: TimestampInSeconds,
+    counters: Arc<RwLock<Counters>>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    bytes_in: u64,
+    bytes_out: u64,
+    last_activity: Option<TimestampInSeconds>,
+    rekeys: u64,
+}
+
+impl SecureChannelStatistics {
+    /// Start collecting the statistics of a channel created at the given time
+    pub fn new(created_at: TimestampInSeconds) -> Self {
+        Self {
+            created_at,
+            counters: Default::default(),
+        }
+    }
+
+    /// Creation time of the channel
+    pub fn created_at(&self) -> TimestampInSeconds {
+        self.created_at
+    }
+
+    /// Number of bytes received and decrypted so far
+    pub fn bytes_in(&self) -> u64 {
+        self.counters.read().unwrap().bytes_in
+    }
+
+    /// Number of bytes encrypted and sent so far
+    pub fn bytes_out(&self) -> u64 {
+        self.counters.read().unwrap().bytes_out
+    }
+
+    /// Time of the last message sent or received, if any
+    pub fn last_activity(&self) -> Option<TimestampInSeconds> {
+        self.counters.read().unwrap().last_activity
+    }
+
+    /// Number of renewals of the key used to encrypt the sent messages
+    pub fn rekeys(&self) -> u64 {
+        self.counters.read().unwrap().rekeys
+    }
+
+    /// Count a message of `len` bytes received on the channel
+    pub(crate) fn add_received(&self, len: usize) {
+        let mut counters = self.counters.write().unwrap();
+        counters.bytes_in = counters.bytes_in.saturating_add(len as u64);
+        if let Ok(now) = now() {
+            counters.last_activity = Some(now);
+        }
+    }
+
+    /// Count a message of `len` bytes sent on the channel, once the sending key has been
+    /// renewed `rekeys` times
+    pub(crate) fn add_sent(&self, len: usize, rekeys: u64) {
+        let mut counters = self.counters.write().unwrap();
+        counters.bytes_out = counters.bytes_out.saturating_add(len as u64);
+        if let Ok(now) = now() {
+            counters.last_activity = Some(now);
+        }
+        counters.rekeys = rekeys;
+    }
+}
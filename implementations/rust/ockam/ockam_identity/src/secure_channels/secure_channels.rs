@@ -1,14 +1,16 @@
+use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::sync::Arc;
-use ockam_core::Result;
-use ockam_core::{Address, Route};
+use ockam_core::errcode::Kind;
+use ockam_core::{Address, Encodable, Result, Route};
 use ockam_node::Context;
+use tracing::warn;
 
 use crate::identities::Identities;
 use crate::models::Identifier;
 use crate::secure_channel::handshake_worker::HandshakeWorker;
 use crate::secure_channel::{
-    Addresses, IdentityChannelListener, Role, SecureChannelListenerOptions, SecureChannelOptions,
-    SecureChannelRegistry,
+    Addresses, IdentityChannelListener, ResumptionTicket, ResumptionTickets, Role,
+    SecureChannelListenerOptions, SecureChannelOptions, SecureChannelRegistry,
 };
 use crate::{SecureChannel, SecureChannelListener, SecureChannelsBuilder, Vault};
 
@@ -17,6 +19,7 @@ use crate::{SecureChannel, SecureChannelListener, SecureChannelsBuilder, Vault};
 pub struct SecureChannels {
     pub(crate) identities: Arc<Identities>,
     pub(crate) secure_channel_registry: SecureChannelRegistry,
+    pub(crate) resumption_tickets: ResumptionTickets,
 }
 
 impl SecureChannels {
@@ -28,6 +31,7 @@ impl SecureChannels {
         Self {
             identities,
             secure_channel_registry,
+            resumption_tickets: ResumptionTickets::default(),
         }
     }
 
@@ -46,6 +50,16 @@ impl SecureChannels {
         self.secure_channel_registry.clone()
     }
 
+    /// Return the resumption tickets issued and received by the secure channels
+    pub fn resumption_tickets(&self) -> ResumptionTickets {
+        self.resumption_tickets.clone()
+    }
+
+    /// Return the ticket received from a responder to resume a channel with it, if any
+    pub fn resumption_ticket(&self, their_identifier: &Identifier) -> Option<ResumptionTicket> {
+        self.resumption_tickets.get(their_identifier)
+    }
+
     /// Create a builder for secure channels
     pub fn builder() -> SecureChannelsBuilder {
         SecureChannelsBuilder {
@@ -88,14 +102,16 @@ impl SecureChannels {
         route: impl Into<Route>,
         options: impl Into<SecureChannelOptions>,
     ) -> Result<SecureChannel> {
-        let addresses = Addresses::generate(Role::Initiator);
-        let options = options.into();
+        let mut options = options.into();
         let flow_control_id = options.flow_control_id.clone();
 
         let route = route.into();
         let next = route.next()?;
-        options.setup_flow_control(ctx.flow_controls(), &addresses, next)?;
-        let access_control = options.create_access_control(ctx.flow_controls());
+
+        // a ticket can only be used once
+        if let Some(ticket) = &options.resumption_ticket {
+            self.resumption_tickets.forget(ticket);
+        }
 
         // TODO: Allow manual PurposeKey management
         let purpose_key = self
@@ -105,21 +121,63 @@ impl SecureChannels {
             .get_or_create_secure_channel_purpose_key(identifier)
             .await?;
 
-        HandshakeWorker::create(
-            ctx,
-            Arc::new(self.clone()),
-            addresses.clone(),
-            identifier.clone(),
-            purpose_key,
-            options.trust_policy,
-            access_control.decryptor_outgoing_access_control,
-            options.credentials,
-            options.trust_context,
-            Some(route),
-            Some(options.timeout),
-            Role::Initiator,
-        )
-        .await?;
+        // a retried handshake is a full handshake
+        let mut resumption_ticket = options.resumption_ticket.take();
+        let mut early_data = options
+            .early_message
+            .take()
+            .map(|m| m.encode())
+            .transpose()?;
+        let mut retry = 0;
+        let addresses = loop {
+            let addresses = Addresses::generate(Role::Initiator);
+            options.setup_flow_control(ctx.flow_controls(), &addresses, next)?;
+            let access_control = options.create_access_control(ctx.flow_controls());
+
+            let result = HandshakeWorker::create(
+                ctx,
+                Arc::new(self.clone()),
+                addresses.clone(),
+                identifier.clone(),
+                purpose_key.clone(),
+                options.trust_policy.clone(),
+                access_control.decryptor_outgoing_access_control,
+                options.credentials.clone(),
+                options.trust_context.clone(),
+                Some(route.clone()),
+                Some(options.timeout),
+                options.quota,
+                options.signed_messages,
+                options.hide_identity,
+                vec![options.handshake_pattern],
+                options.pre_shared_key.clone(),
+                resumption_ticket.take(),
+                None,
+                options.pq_hybrid,
+                options.rekey_policy,
+                BTreeMap::new(),
+                false,
+                early_data.take(),
+                Role::Initiator,
+            )
+            .await;
+
+            match result {
+                Ok(()) => break addresses,
+                Err(e)
+                    if e.code().kind == Kind::Timeout
+                        && retry < options.retry_policy.max_retries =>
+                {
+                    // the handshake worker of a timed out handshake is not needed anymore
+                    let _ = ctx.stop_worker(addresses.decryptor_remote).await;
+                    let backoff = options.retry_policy.backoff(retry);
+                    retry += 1;
+                    warn!(%route, retry, ?backoff, "the secure channel handshake timed out, retrying");
+                    ctx.sleep(backoff).await;
+                }
+                Err(e) => return Err(e),
+            }
+        };
 
         Ok(SecureChannel::new(
             addresses.encryptor,
@@ -1,18 +1,21 @@
 use core::time::Duration;
 use ockam_core::compat::sync::Arc;
-use ockam_core::{route, Address, AllowAll, Any, DenyAll, Mailboxes, Result, Routed, Worker};
+use ockam_core::{
+    route, Address, AllowAll, Any, DenyAll, Encodable, Mailboxes, Quota, Result, Routed, Worker,
+};
 use ockam_identity::models::{CredentialSchemaIdentifier, Identifier};
 use ockam_identity::secure_channels::secure_channels;
 use ockam_identity::utils::AttributesBuilder;
 use ockam_identity::{
-    AuthorityService, DecryptionResponse, EncryptionRequest, EncryptionResponse,
-    IdentityAccessControlBuilder, IdentitySecureChannelLocalInfo, SecureChannelListenerOptions,
-    SecureChannelOptions, SecureChannels, TrustContext, TrustEveryonePolicy, TrustIdentifierPolicy,
-    Vault,
+    AuthorityService, DecryptionResponse, EncryptionRequest, EncryptionResponse, HandshakePattern,
+    HandshakeRetryPolicy, IdentityAccessControlBuilder, IdentitySecureChannelLocalInfo,
+    PreSharedKey, SecureChannelListenerOptions, SecureChannelOptions, SecureChannels, TrustContext,
+    TrustEveryonePolicy, TrustIdentifierPolicy, Vault,
 };
 use ockam_node::{Context, MessageReceiveOptions, WorkerBuilder};
 use ockam_vault::{
     SoftwareVaultForSecureChannels, SoftwareVaultForSigning, SoftwareVaultForVerifyingSignatures,
+    VaultForVerifyingSignatures,
 };
 use std::sync::atomic::{AtomicU8, Ordering};
 
@@ -1136,3 +1139,816 @@ async fn should_stop_encryptor__and__decryptor__in__secure_channel(
 
     ctx.stop().await
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn should_close_secure_channel__when__quota_exceeded(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_options =
+        SecureChannelListenerOptions::new().with_quota(Quota::default().with_max_messages(2));
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(ctx, bob.identifier(), "bob_listener", bob_options)
+        .await?;
+
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    for n in 0..3 {
+        child_ctx
+            .send(
+                route![alice_channel.clone(), child_ctx.address()],
+                n.to_string(),
+            )
+            .await?;
+    }
+
+    assert_eq!(child_ctx.receive::<String>().await?.body(), "0");
+    assert_eq!(child_ctx.receive::<String>().await?.body(), "1");
+    let third = child_ctx
+        .receive_extended::<String>(
+            MessageReceiveOptions::new().with_timeout(Duration::from_millis(200)),
+        )
+        .await;
+    assert!(third.is_err());
+
+    // Only the channel of Alice is left, the channel of Bob was closed
+    let channels = secure_channels.secure_channel_registry().get_channel_list();
+    assert_eq!(channels.len(), 1);
+    assert_eq!(
+        channels[0].encryptor_messaging_address(),
+        alice_channel.encryptor_address()
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn test_channel_signed_messages(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            bob.identifier(),
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+
+    let alice_options = SecureChannelOptions::new().with_signed_messages();
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_listener"],
+            alice_options,
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    child_ctx
+        .send(
+            route![alice_channel.clone(), child_ctx.address()],
+            "Hello, Bob!".to_string(),
+        )
+        .await?;
+
+    let msg = child_ctx.receive::<String>().await?;
+
+    // the message of Alice is signed with her identity key
+    let local_info = IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+    assert!(local_info.is_signed());
+    let signed_message = local_info.signed_message()?.unwrap();
+    let alice_public_key = secure_channels
+        .identities()
+        .get_identity(alice.identifier())
+        .await?
+        .get_latest_public_key()?;
+    let hash = secure_channels
+        .identities()
+        .vault()
+        .verifying_vault
+        .sha256(&signed_message.message)
+        .await?;
+    assert!(
+        secure_channels
+            .identities()
+            .vault()
+            .verifying_vault
+            .verify_signature(&alice_public_key, &hash.0, &signed_message.signature.into())
+            .await?
+    );
+
+    // the messages of Bob are not signed
+    ctx.flow_controls()
+        .add_consumer("child", alice_channel.flow_control_id());
+
+    let return_route = msg.return_route();
+    assert_eq!("Hello, Bob!", msg.body());
+
+    child_ctx
+        .send(return_route, "Hello, Alice!".to_string())
+        .await?;
+
+    let msg = child_ctx.receive::<String>().await?;
+    let local_info = IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+    assert!(!local_info.is_signed());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn test_channel_hidden_identity(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_options = SecureChannelListenerOptions::new()
+        .with_trust_policy(TrustIdentifierPolicy::new(alice.identifier().clone()))
+        .with_identity_hiding();
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(ctx, bob.identifier(), "bob_listener", bob_options)
+        .await?;
+
+    let alice_options = SecureChannelOptions::new()
+        .with_trust_policy(TrustIdentifierPolicy::new(bob.identifier().clone()));
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_listener"],
+            alice_options,
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+    ctx.flow_controls()
+        .add_consumer("child", alice_channel.flow_control_id());
+
+    child_ctx
+        .send(
+            route![alice_channel.clone(), child_ctx.address()],
+            "Hello, Bob!".to_string(),
+        )
+        .await?;
+    let msg = child_ctx.receive::<String>().await?;
+    let local_info = IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+    assert_eq!(&local_info.their_identity_id(), alice.identifier());
+
+    child_ctx
+        .send(msg.return_route(), "Hello, Alice!".to_string())
+        .await?;
+    let msg = child_ctx.receive::<String>().await?;
+    let local_info = IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+    assert_eq!(&local_info.their_identity_id(), bob.identifier());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn test_channel_hidden_identity_rejected(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+    let charlie = identities_creation.create_identity().await?;
+
+    // Bob only reveals his identity to Charlie
+    let bob_options = SecureChannelListenerOptions::new()
+        .with_trust_policy(TrustIdentifierPolicy::new(charlie.identifier().clone()))
+        .with_identity_hiding();
+    secure_channels
+        .create_secure_channel_listener(ctx, bob.identifier(), "bob_listener", bob_options)
+        .await?;
+
+    let result = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_listener"],
+            SecureChannelOptions::new().with_timeout(Duration::from_millis(500)),
+        )
+        .await;
+    assert!(result.is_err());
+
+    // Charlie refuses to reveal his identity before Bob
+    let result = secure_channels
+        .create_secure_channel(
+            ctx,
+            charlie.identifier(),
+            route!["bob_listener"],
+            SecureChannelOptions::new()
+                .with_identity_hiding()
+                .with_timeout(Duration::from_millis(500)),
+        )
+        .await;
+    assert!(result.is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn test_channel_handshake_patterns(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let psk = PreSharedKey::new([1; 32]);
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            bob.identifier(),
+            "bob_psk_listener",
+            SecureChannelListenerOptions::new().with_pre_shared_key(psk.clone()),
+        )
+        .await?;
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            bob.identifier(),
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+
+    // Same pre-shared key
+    let result = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_psk_listener"],
+            SecureChannelOptions::new().with_pre_shared_key(psk),
+        )
+        .await;
+    assert!(result.is_ok());
+
+    // Different pre-shared key
+    let result = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_psk_listener"],
+            SecureChannelOptions::new()
+                .with_pre_shared_key(PreSharedKey::new([2; 32]))
+                .with_timeout(Duration::from_millis(500)),
+        )
+        .await;
+    assert!(result.is_err());
+
+    // Pattern not accepted by the listener
+    let result = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_psk_listener"],
+            SecureChannelOptions::new().with_timeout(Duration::from_millis(500)),
+        )
+        .await;
+    assert!(result.is_err());
+
+    // The responder hides its identity when asked to
+    let result = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_listener"],
+            SecureChannelOptions::new().with_handshake_pattern(HandshakePattern::XXHiddenResponder),
+        )
+        .await;
+    assert!(result.is_ok());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn test_channel_resumption(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            bob.identifier(),
+            "bob_listener",
+            SecureChannelListenerOptions::new().with_resumption_tickets(Duration::from_secs(60)),
+        )
+        .await?;
+
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    secure_channels
+        .stop_secure_channel(ctx, channel.encryptor_address())
+        .await?;
+
+    // The channel is resumed with the ticket sent by bob
+    let ticket = secure_channels.resumption_ticket(bob.identifier()).unwrap();
+    assert_eq!(ticket.their_identifier(), bob.identifier());
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_listener"],
+            SecureChannelOptions::new().with_resumption_ticket(ticket.clone()),
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+    child_ctx
+        .send(
+            route![channel.clone(), child_ctx.address()],
+            "Hello, Bob!".to_string(),
+        )
+        .await?;
+    let msg = child_ctx.receive::<String>().await?;
+    let local_info = IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+    assert_eq!(&local_info.their_identity_id(), alice.identifier());
+
+    // A ticket can only be used once, but a new ticket is sent with the resumed channel
+    let result = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_listener"],
+            SecureChannelOptions::new()
+                .with_resumption_ticket(ticket)
+                .with_timeout(Duration::from_millis(500)),
+        )
+        .await;
+    assert!(result.is_err());
+    assert!(secure_channels
+        .resumption_ticket(bob.identifier())
+        .is_some());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn test_channel_resumption_early_message(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            bob.identifier(),
+            "bob_listener",
+            SecureChannelListenerOptions::new().with_resumption_tickets(Duration::from_secs(60)),
+        )
+        .await?;
+
+    // An early message can only be sent when resuming a channel
+    let result = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_listener"],
+            SecureChannelOptions::new().with_early_message(
+                route!["bob_inbox"],
+                route!["alice_inbox"],
+                "Hello, Bob!".to_string().encode()?,
+            ),
+        )
+        .await;
+    assert!(result.is_err());
+
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    secure_channels
+        .stop_secure_channel(ctx, channel.encryptor_address())
+        .await?;
+
+    let mut bob_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "bob_inbox",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("bob_inbox", bob_listener.flow_control_id());
+    let mut alice_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "alice_inbox",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+
+    // The early message is sent with the first message of the resumption
+    let ticket = secure_channels.resumption_ticket(bob.identifier()).unwrap();
+    let alice_options = SecureChannelOptions::new()
+        .with_resumption_ticket(ticket)
+        .with_early_message(
+            route!["bob_inbox"],
+            route!["alice_inbox"],
+            "Hello, Bob!".to_string().encode()?,
+        );
+    ctx.flow_controls()
+        .add_consumer("alice_inbox", &alice_options.producer_flow_control_id());
+    secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_listener"],
+            alice_options,
+        )
+        .await?;
+
+    let msg = bob_ctx.receive::<String>().await?;
+    let local_info = IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+    assert_eq!(&local_info.their_identity_id(), alice.identifier());
+    assert_eq!("Hello, Bob!", msg.body());
+
+    // The reply is sent over the resumed channel
+    bob_ctx
+        .send(msg.return_route(), "Hello, Alice!".to_string())
+        .await?;
+    let msg = alice_ctx.receive::<String>().await?;
+    let local_info = IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+    assert_eq!(&local_info.their_identity_id(), bob.identifier());
+    assert_eq!("Hello, Alice!", msg.body());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn test_channel_pq_hybrid(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            bob.identifier(),
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+
+    // The extension is supported with all the handshake patterns
+    for handshake_pattern in [HandshakePattern::XX, HandshakePattern::XXHiddenResponder] {
+        let channel = secure_channels
+            .create_secure_channel(
+                ctx,
+                alice.identifier(),
+                route!["bob_listener"],
+                SecureChannelOptions::new()
+                    .with_handshake_pattern(handshake_pattern)
+                    .with_pq_hybrid(),
+            )
+            .await?;
+
+        let mut child_ctx = ctx
+            .new_detached_with_mailboxes(Mailboxes::main(
+                format!("child_{handshake_pattern}"),
+                Arc::new(AllowAll),
+                Arc::new(AllowAll),
+            ))
+            .await?;
+        ctx.flow_controls()
+            .add_consumer(child_ctx.address(), bob_listener.flow_control_id());
+        child_ctx
+            .send(
+                route![channel, child_ctx.address()],
+                "Hello, Bob!".to_string(),
+            )
+            .await?;
+        let msg = child_ctx.receive::<String>().await?;
+        let local_info = IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+        assert_eq!(&local_info.their_identity_id(), alice.identifier());
+        assert_eq!("Hello, Bob!", msg.body());
+    }
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn test_channel_required_attributes(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities_creation = secure_channels.identities().identities_creation();
+    let credentials_creation = secure_channels
+        .identities()
+        .credentials()
+        .credentials_creation();
+
+    let authority = identities_creation.create_identity().await?;
+    let alice = identities_creation.create_identity().await?;
+    let carol = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let trust_context = TrustContext::new(
+        "test".to_string(),
+        Some(AuthorityService::new(
+            secure_channels.identities().credentials(),
+            authority.identifier().clone(),
+            None,
+        )),
+    );
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            bob.identifier(),
+            "bob_listener",
+            SecureChannelListenerOptions::new()
+                .with_trust_context(trust_context.clone())
+                .with_required_attribute("department", "ops"),
+        )
+        .await?;
+
+    // Only alice has a credential with the required attribute
+    for (initiator, department, accepted) in [(&alice, "ops", true), (&carol, "dev", false)] {
+        let credential = credentials_creation
+            .issue_credential(
+                authority.identifier(),
+                initiator.identifier(),
+                AttributesBuilder::with_schema(CredentialSchemaIdentifier(0))
+                    .with_attribute("department", department)
+                    .build(),
+                Duration::from_secs(60),
+            )
+            .await?;
+
+        let channel = secure_channels
+            .create_secure_channel(
+                ctx,
+                initiator.identifier(),
+                route!["bob_listener"],
+                SecureChannelOptions::new()
+                    .with_trust_context(trust_context.clone())
+                    .with_credential(credential)
+                    .with_timeout(Duration::from_millis(500)),
+            )
+            .await?;
+
+        let mut child_ctx = ctx
+            .new_detached_with_mailboxes(Mailboxes::main(
+                format!("child_{department}"),
+                Arc::new(AllowAll),
+                Arc::new(AllowAll),
+            ))
+            .await?;
+        ctx.flow_controls()
+            .add_consumer(child_ctx.address(), bob_listener.flow_control_id());
+        child_ctx
+            .send(
+                route![channel, child_ctx.address()],
+                "Hello, Bob!".to_string(),
+            )
+            .await?;
+        let result = child_ctx
+            .receive_extended::<String>(
+                MessageReceiveOptions::new().with_timeout(Duration::from_millis(200)),
+            )
+            .await;
+        assert_eq!(result.is_ok(), accepted);
+    }
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn test_channel_statistics(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            bob.identifier(),
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    for _ in 0..40 {
+        child_ctx
+            .send(
+                route![alice_channel.clone(), child_ctx.address()],
+                "Hello, Bob!".to_string(),
+            )
+            .await?;
+        child_ctx.receive::<String>().await?;
+    }
+
+    let registry = secure_channels.secure_channel_registry();
+    let alice_entry = registry
+        .get_channel_by_encryptor_address(alice_channel.encryptor_address())
+        .unwrap();
+    let alice_statistics = alice_entry.statistics();
+    assert!(alice_statistics.bytes_out() > 0);
+    assert_eq!(alice_statistics.bytes_in(), 0);
+    assert!(alice_statistics.last_activity().is_some());
+    // The sending key is renewed every 32 messages
+    assert_eq!(alice_statistics.rekeys(), 1);
+
+    let bob_entry = registry
+        .get_channel_list()
+        .into_iter()
+        .find(|entry| !entry.is_initiator())
+        .unwrap();
+    assert_eq!(
+        bob_entry.statistics().bytes_in(),
+        alice_statistics.bytes_out()
+    );
+    assert_eq!(bob_entry.statistics().bytes_out(), 0);
+
+    ctx.stop().await
+}
+
+/// Drop the first message sent to the listener, then forward the next ones to it
+struct DropFirstMessage {
+    dropped: bool,
+}
+
+#[ockam_core::async_trait]
+impl Worker for DropFirstMessage {
+    type Message = Any;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        if !self.dropped {
+            self.dropped = true;
+            return Ok(());
+        }
+        let mut local_message = msg.into_local_message();
+        local_message
+            .transport_mut()
+            .onward_route
+            .modify()
+            .replace("bob_listener");
+        ctx.forward(local_message).await
+    }
+}
+
+#[ockam_macros::test]
+async fn test_channel_handshake_retry(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            bob.identifier(),
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+    WorkerBuilder::new(DropFirstMessage { dropped: false })
+        .with_address("lossy_link")
+        .with_incoming_access_control(AllowAll)
+        .with_outgoing_access_control(AllowAll)
+        .start(ctx)
+        .await?;
+
+    // Without retries the handshake times out, since its first message is lost
+    let options = || {
+        SecureChannelOptions::new()
+            .with_timeout(Duration::from_millis(500))
+            .with_retry_policy(
+                HandshakeRetryPolicy::default()
+                    .with_max_retries(1)
+                    .with_initial_backoff(Duration::from_millis(100)),
+            )
+    };
+    let res = secure_channels
+        .create_secure_channel(
+            ctx,
+            alice.identifier(),
+            route!["lossy_link"],
+            options().with_retry_policy(HandshakeRetryPolicy::default()),
+        )
+        .await;
+    assert!(res.is_err());
+
+    // The dropped flag is reset, and the second handshake succeeds
+    ctx.stop_worker("lossy_link").await?;
+    WorkerBuilder::new(DropFirstMessage { dropped: false })
+        .with_address("lossy_link")
+        .with_incoming_access_control(AllowAll)
+        .with_outgoing_access_control(AllowAll)
+        .start(ctx)
+        .await?;
+    let channel = secure_channels
+        .create_secure_channel(ctx, alice.identifier(), route!["lossy_link"], options())
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+    child_ctx
+        .send(route![channel.clone(), "child"], "Hello, Bob!".to_string())
+        .await?;
+    assert_eq!("Hello, Bob!", child_ctx.receive::<String>().await?.body());
+
+    ctx.stop().await
+}
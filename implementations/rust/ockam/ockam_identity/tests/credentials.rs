@@ -4,12 +4,13 @@ use std::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::{async_trait, Any, DenyAll};
 use ockam_core::{route, Result, Routed, Worker};
-use ockam_identity::models::CredentialSchemaIdentifier;
+use ockam_identity::models::{CredentialSchemaIdentifier, TimestampInSeconds};
 use ockam_identity::secure_channels::secure_channels;
 use ockam_identity::utils::AttributesBuilder;
 use ockam_identity::{
-    AuthorityService, CredentialAccessControl, CredentialsMemoryRetriever,
+    AuthorityService, AuthorityThreshold, CredentialAccessControl, CredentialsMemoryRetriever,
     SecureChannelListenerOptions, SecureChannelOptions, TrustContext, TrustIdentifierPolicy,
+    LOW_TRUST_LEVEL, TRUST_LEVEL_ATTRIBUTE,
 };
 use ockam_node::{Context, WorkerBuilder};
 
@@ -329,3 +330,229 @@ impl Worker for CountingWorker {
         Ok(())
     }
 }
+
+#[ockam_macros::test]
+async fn revoked_credential(ctx: &mut Context) -> Result<()> {
+    let identities = secure_channels().identities();
+    let identities_creation = identities.identities_creation();
+    let credentials = identities.credentials();
+
+    let authority = identities_creation.create_identity().await?;
+    let client = identities_creation.create_identity().await?;
+
+    let credential = credentials
+        .credentials_creation()
+        .issue_credential(
+            authority.identifier(),
+            client.identifier(),
+            AttributesBuilder::with_schema(CredentialSchemaIdentifier(0))
+                .with_attribute("is_superuser", "true")
+                .build(),
+            Duration::from_secs(60),
+        )
+        .await?;
+    let created_at = credentials
+        .credentials_verification()
+        .verify_credential(
+            Some(client.identifier()),
+            &[authority.identifier().clone()],
+            &credential,
+        )
+        .await?
+        .credential_data
+        .created_at;
+
+    // a revocation older than the credential doesn't apply to it
+    credentials.revocations().revoke(
+        authority.identifier(),
+        client.identifier(),
+        created_at - TimestampInSeconds(1),
+    );
+    assert!(credentials
+        .credentials_verification()
+        .verify_credential(
+            Some(client.identifier()),
+            &[authority.identifier().clone()],
+            &credential,
+        )
+        .await
+        .is_ok());
+
+    credentials
+        .revocations()
+        .revoke(authority.identifier(), client.identifier(), created_at);
+    assert!(credentials
+        .credentials_verification()
+        .verify_credential(
+            Some(client.identifier()),
+            &[authority.identifier().clone()],
+            &credential,
+        )
+        .await
+        .is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn credential_with_authority_threshold(ctx: &mut Context) -> Result<()> {
+    let identities = secure_channels().identities();
+    let identities_creation = identities.identities_creation();
+    let credentials = identities.credentials();
+
+    let authority = identities_creation.create_identity().await?;
+    let co_signer1 = identities_creation.create_identity().await?;
+    let co_signer2 = identities_creation.create_identity().await?;
+    let other = identities_creation.create_identity().await?;
+    let client = identities_creation.create_identity().await?;
+
+    credentials.authority_thresholds().set(
+        authority.identifier(),
+        AuthorityThreshold::new(
+            vec![
+                authority.identifier().clone(),
+                co_signer1.identifier().clone(),
+                co_signer2.identifier().clone(),
+            ],
+            2,
+        )?,
+    );
+
+    let mut credential = credentials
+        .credentials_creation()
+        .issue_credential(
+            authority.identifier(),
+            client.identifier(),
+            AttributesBuilder::with_schema(CredentialSchemaIdentifier(0))
+                .with_attribute("is_superuser", "true")
+                .build(),
+            Duration::from_secs(60),
+        )
+        .await?;
+    let verify = |credential| {
+        let credentials = credentials.clone();
+        let client = client.identifier().clone();
+        let authority = authority.identifier().clone();
+        async move {
+            credentials
+                .credentials_verification()
+                .verify_credential(Some(&client), &[authority], &credential)
+                .await
+        }
+    };
+    assert!(verify(credential.clone()).await.is_err());
+
+    // a co-signature by an identity which is not a signer is not counted
+    let co_signature = credentials
+        .credentials_creation()
+        .co_sign_credential(other.identifier(), &credential)
+        .await?;
+    credential.co_signatures = Some(vec![co_signature]);
+    assert!(verify(credential.clone()).await.is_err());
+
+    let co_signature = credentials
+        .credentials_creation()
+        .co_sign_credential(co_signer1.identifier(), &credential)
+        .await?;
+    credential
+        .co_signatures
+        .get_or_insert_with(Vec::new)
+        .push(co_signature);
+    assert!(verify(credential).await.is_ok());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn progressive_trust(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let identities_repository = identities.repository();
+    let credentials = identities.credentials();
+    let credentials_service = identities.credentials_server();
+
+    let authority = identities_creation.create_identity().await?;
+    let server = identities_creation.create_identity().await?;
+    let device = identities_creation.create_identity().await?;
+
+    let trust_context = TrustContext::new(
+        "test_trust_context_id".to_string(),
+        Some(AuthorityService::new(
+            secure_channels.identities().credentials(),
+            authority.identifier().clone(),
+            None,
+        )),
+    );
+
+    // The device has no credential yet, but is accepted with a low trust level
+    let listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            server.identifier(),
+            "listener",
+            SecureChannelListenerOptions::new()
+                .with_trust_context(trust_context.clone())
+                .with_required_attribute("role", "device")
+                .with_progressive_trust(),
+        )
+        .await?;
+
+    ctx.flow_controls()
+        .add_consumer("credential_exchange", listener.flow_control_id());
+    credentials_service
+        .start(
+            ctx,
+            trust_context,
+            server.identifier().clone(),
+            "credential_exchange".into(),
+            false,
+        )
+        .await?;
+
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            device.identifier(),
+            route!["listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    let attrs = identities_repository
+        .get_attributes(device.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(
+        attrs.attrs().get(TRUST_LEVEL_ATTRIBUTE.as_bytes()).unwrap(),
+        LOW_TRUST_LEVEL.as_bytes()
+    );
+
+    // Once enrolled, the device presents its credential over the same channel
+    let credential = credentials
+        .credentials_creation()
+        .issue_credential(
+            authority.identifier(),
+            device.identifier(),
+            AttributesBuilder::with_schema(CredentialSchemaIdentifier(0))
+                .with_attribute("role", "device")
+                .build(),
+            Duration::from_secs(60),
+        )
+        .await?;
+    credentials_service
+        .present_credential(ctx, route![channel, "credential_exchange"], credential)
+        .await?;
+
+    let attrs = identities_repository
+        .get_attributes(device.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(attrs.attrs().get("role".as_bytes()).unwrap(), b"device");
+    assert!(attrs
+        .attrs()
+        .get(TRUST_LEVEL_ATTRIBUTE.as_bytes())
+        .is_none());
+
+    ctx.stop().await
+}
@@ -0,0 +1,83 @@
+use ockam_core::Result;
+use ockam_identity::{Identities, TimestampInSeconds};
+
+const THIRTY_DAYS: TimestampInSeconds = TimestampInSeconds(30 * 24 * 60 * 60);
+
+#[tokio::test]
+async fn test_delegate_to_device() -> Result<()> {
+    let identities = Identities::builder().build();
+    let identities_creation = identities.identities_creation();
+    let identities_delegation = identities.identities_delegation();
+
+    let root = identities_creation.create_identity().await?;
+    let device = identities_delegation
+        .delegate_to_device(root.identifier(), "laptop2", THIRTY_DAYS)
+        .await?;
+    assert_ne!(root.identifier(), device.identifier());
+
+    let delegation = identities_delegation
+        .verify_delegation(device.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(&delegation.delegator, root.identifier());
+    assert_eq!(delegation.device_name, "laptop2");
+
+    // an identity created without delegation has no delegation
+    assert!(identities_delegation
+        .verify_delegation(root.identifier())
+        .await?
+        .is_none());
+
+    // the device identity can rotate its own key
+    identities_creation
+        .rotate_identity(device.identifier())
+        .await?;
+    assert!(identities_delegation
+        .verify_delegation(device.identifier())
+        .await?
+        .is_some());
+
+    // a simple rotation of the root key keeps the delegation valid
+    identities_creation
+        .rotate_identity(root.identifier())
+        .await?;
+    assert!(identities_delegation
+        .verify_delegation(device.identifier())
+        .await?
+        .is_some());
+
+    // but not a rotation revoking everything signed by the previous keys
+    let options = identities_creation
+        .identity_builder()
+        .with_purpose_keys_revocation()
+        .build_options()
+        .await?;
+    identities_creation
+        .rotate_identity_with_options(root.identifier(), options)
+        .await?;
+    assert!(identities_delegation
+        .verify_delegation(device.identifier())
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delegation_cannot_outlive_the_root_key() -> Result<()> {
+    let identities = Identities::builder().build();
+    let root = identities
+        .identities_creation()
+        .identity_builder()
+        .with_ttl(60u64)
+        .build()
+        .await?;
+
+    let result = identities
+        .identities_delegation()
+        .delegate_to_device(root.identifier(), "laptop2", THIRTY_DAYS)
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use crate::common::crazy_vault::{CrazySigningVault, CrazyVerifyingVault};
 use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 use ockam_identity::models::ChangeHistory;
-use ockam_identity::{Identifier, Identities, Identity, Vault};
+use ockam_identity::{Identifier, Identities, Identity, IdentityHistoryComparison, Vault};
 use rand::{thread_rng, Rng};
 
 mod common;
@@ -25,6 +25,64 @@ async fn test_valid_identity() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_verify_exported_change_history() -> Result<()> {
+    let identities = Identities::builder().build();
+    let identities_creation = identities.identities_creation();
+    let identity = identities_creation.create_identity().await?;
+    let identifier = identity.identifier().clone();
+    let first_history = identity.export()?;
+
+    identities_creation.rotate_identity(&identifier).await?;
+    let rotated_history = identities.export_identity(&identifier).await?;
+
+    // The whole signature chain is verified, and the identifier is checked if one is expected
+    let rotated = identities
+        .verify_change_history(Some(&identifier), &rotated_history)
+        .await?;
+    assert_eq!(rotated.changes().len(), 2);
+    let other_identifier = identities_creation
+        .create_identity()
+        .await?
+        .identifier()
+        .clone();
+    assert!(identities
+        .verify_change_history(Some(&other_identifier), &rotated_history)
+        .await
+        .is_err());
+    let mut corrupted_history = rotated_history.clone();
+    let last = corrupted_history.len() - 1;
+    corrupted_history[last] ^= 0xff;
+    assert!(identities
+        .verify_change_history(None, &corrupted_history)
+        .await
+        .is_err());
+
+    // The verified identity is compared to the version known locally
+    let first = identities
+        .verify_change_history(None, &first_history)
+        .await?;
+    assert_eq!(
+        identities.compare_with_known_identity(&first).await?,
+        Some(IdentityHistoryComparison::Older)
+    );
+    assert_eq!(
+        identities.compare_with_known_identity(&rotated).await?,
+        Some(IdentityHistoryComparison::Equal)
+    );
+    let unknown = Identities::builder()
+        .build()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    assert_eq!(
+        identities.compare_with_known_identity(&unknown).await?,
+        None
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_invalid_signature() -> Result<()> {
     let mut vault = Vault::create();
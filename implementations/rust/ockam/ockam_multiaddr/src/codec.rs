@@ -1,5 +1,7 @@
 use super::{Buffer, Checked, Code, Codec, Protocol};
-use crate::proto::{DnsAddr, Node, Project, Secure, Service, Space, Tcp, Worker};
+use crate::proto::{
+    Ble, DnsAddr, Node, Project, Quic, Secure, Service, Space, Tcp, Tls, Udp, Worker, Ws, Wss,
+};
 use crate::{Error, ProtoValue};
 use core::fmt;
 use unsigned_varint::decode;
@@ -49,13 +51,49 @@ impl Codec for StdCodec {
                 let (x, y) = input.split_at(2);
                 Ok((Checked(x), y))
             }
+            Udp::CODE => {
+                if input.len() < 2 {
+                    return Err(Error::required_bytes(Udp::CODE, 2));
+                }
+                let (x, y) = input.split_at(2);
+                Ok((Checked(x), y))
+            }
+            Quic::CODE => {
+                if input.len() < 2 {
+                    return Err(Error::required_bytes(Quic::CODE, 2));
+                }
+                let (x, y) = input.split_at(2);
+                Ok((Checked(x), y))
+            }
+            Ws::CODE => {
+                if input.len() < 2 {
+                    return Err(Error::required_bytes(Ws::CODE, 2));
+                }
+                let (x, y) = input.split_at(2);
+                Ok((Checked(x), y))
+            }
+            Wss::CODE => {
+                if input.len() < 2 {
+                    return Err(Error::required_bytes(Wss::CODE, 2));
+                }
+                let (x, y) = input.split_at(2);
+                Ok((Checked(x), y))
+            }
+            Tls::CODE => {
+                if input.len() < 2 {
+                    return Err(Error::required_bytes(Tls::CODE, 2));
+                }
+                let (x, y) = input.split_at(2);
+                Ok((Checked(x), y))
+            }
             c @ Worker::CODE
             | c @ DnsAddr::CODE
             | c @ Service::CODE
             | c @ Node::CODE
             | c @ Project::CODE
             | c @ Space::CODE
-            | c @ Secure::CODE => {
+            | c @ Secure::CODE
+            | c @ Ble::CODE => {
                 let (len, input) = decode::usize(input)?;
                 if input.len() < len {
                     return Err(Error::required_bytes(c, len));
@@ -75,12 +113,18 @@ impl Codec for StdCodec {
             #[cfg(feature = "std")]
             crate::proto::Ip6::CODE => crate::proto::Ip6::read_bytes(input).is_ok(),
             Tcp::CODE => Tcp::read_bytes(input).is_ok(),
+            Udp::CODE => Udp::read_bytes(input).is_ok(),
+            Quic::CODE => Quic::read_bytes(input).is_ok(),
+            Ws::CODE => Ws::read_bytes(input).is_ok(),
+            Wss::CODE => Wss::read_bytes(input).is_ok(),
+            Tls::CODE => Tls::read_bytes(input).is_ok(),
             DnsAddr::CODE => DnsAddr::read_bytes(input).is_ok(),
             Service::CODE => Service::read_bytes(input).is_ok(),
             Node::CODE => Node::read_bytes(input).is_ok(),
             Project::CODE => Project::read_bytes(input).is_ok(),
             Space::CODE => Space::read_bytes(input).is_ok(),
             Secure::CODE => Secure::read_bytes(input).is_ok(),
+            Ble::CODE => Ble::read_bytes(input).is_ok(),
             _ => false,
         }
     }
@@ -93,12 +137,18 @@ impl Codec for StdCodec {
             #[cfg(feature = "std")]
             crate::proto::Ip6::CODE => crate::proto::Ip6::read_bytes(val.data())?.write_bytes(buf),
             Tcp::CODE => Tcp::read_bytes(val.data())?.write_bytes(buf),
+            Udp::CODE => Udp::read_bytes(val.data())?.write_bytes(buf),
+            Quic::CODE => Quic::read_bytes(val.data())?.write_bytes(buf),
+            Ws::CODE => Ws::read_bytes(val.data())?.write_bytes(buf),
+            Wss::CODE => Wss::read_bytes(val.data())?.write_bytes(buf),
+            Tls::CODE => Tls::read_bytes(val.data())?.write_bytes(buf),
             DnsAddr::CODE => DnsAddr::read_bytes(val.data())?.write_bytes(buf),
             Service::CODE => Service::read_bytes(val.data())?.write_bytes(buf),
             Node::CODE => Node::read_bytes(val.data())?.write_bytes(buf),
             Project::CODE => Project::read_bytes(val.data())?.write_bytes(buf),
             Space::CODE => Space::read_bytes(val.data())?.write_bytes(buf),
             Secure::CODE => Secure::read_bytes(val.data())?.write_bytes(buf),
+            Ble::CODE => Ble::read_bytes(val.data())?.write_bytes(buf),
             code => return Err(Error::unregistered(code)),
         }
         Ok(())
@@ -129,6 +179,26 @@ impl Codec for StdCodec {
                 Tcp::read_str(value)?.write_bytes(buf);
                 Ok(())
             }
+            Udp::PREFIX => {
+                Udp::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
+            Quic::PREFIX => {
+                Quic::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
+            Ws::PREFIX => {
+                Ws::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
+            Wss::PREFIX => {
+                Wss::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
+            Tls::PREFIX => {
+                Tls::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
             DnsAddr::PREFIX => {
                 DnsAddr::read_str(value)?.write_bytes(buf);
                 Ok(())
@@ -141,6 +211,10 @@ impl Codec for StdCodec {
                 Node::read_str(value)?.write_bytes(buf);
                 Ok(())
             }
+            Ble::PREFIX => {
+                Ble::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
             Project::PREFIX => {
                 Project::read_str(value)?.write_bytes(buf);
                 Ok(())
@@ -182,6 +256,26 @@ impl Codec for StdCodec {
                 Tcp::read_bytes(value)?.write_str(f)?;
                 Ok(())
             }
+            Udp::CODE => {
+                Udp::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
+            Quic::CODE => {
+                Quic::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
+            Ws::CODE => {
+                Ws::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
+            Wss::CODE => {
+                Wss::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
+            Tls::CODE => {
+                Tls::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
             DnsAddr::CODE => {
                 DnsAddr::read_bytes(value)?.write_str(f)?;
                 Ok(())
@@ -194,6 +288,10 @@ impl Codec for StdCodec {
                 Node::read_bytes(value)?.write_str(f)?;
                 Ok(())
             }
+            Ble::CODE => {
+                Ble::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
             Project::CODE => {
                 Project::read_bytes(value)?.write_str(f)?;
                 Ok(())
@@ -27,7 +27,7 @@ use once_cell::race::OnceBox;
 use std::net::{SocketAddrV4, SocketAddrV6};
 use tinyvec::{Array, ArrayVec, TinyVec};
 
-use crate::proto::{DnsAddr, Ip4, Ip6, Tcp};
+use crate::proto::{DnsAddr, Ip4, Ip6, Quic, Tcp, Tls, Udp, Ws, Wss};
 pub use error::Error;
 use ockam_core::env::FromString;
 pub use registry::{Registry, RegistryBuilder};
@@ -601,20 +601,29 @@ impl MultiAddr {
             match p.code() {
                 Ip4::CODE => {
                     let ip4 = p.cast::<Ip4>().unwrap();
-                    let port = it.next().unwrap().cast::<Tcp>().unwrap();
-                    return Ok(SocketAddrV4::new(*ip4, *port).to_string());
+                    let port = port(it.next())?;
+                    return Ok(SocketAddrV4::new(*ip4, port).to_string());
                 }
                 Ip6::CODE => {
                     let ip6 = p.cast::<Ip6>().unwrap();
-                    let port = it.next().unwrap().cast::<Tcp>().unwrap();
-                    return Ok(SocketAddrV6::new(*ip6, *port, 0, 0).to_string());
+                    let port = port(it.next())?;
+                    return Ok(SocketAddrV6::new(*ip6, port, 0, 0).to_string());
                 }
                 DnsAddr::CODE => {
                     let host = p.cast::<DnsAddr>().unwrap();
                     if let Some(p) = it.peek() {
-                        if p.code() == Tcp::CODE {
-                            let port = p.cast::<Tcp>().unwrap();
-                            return Ok(format!("{}:{}", &*host, *port));
+                        if [
+                            Tcp::CODE,
+                            Tls::CODE,
+                            Udp::CODE,
+                            Quic::CODE,
+                            Ws::CODE,
+                            Wss::CODE,
+                        ]
+                        .contains(&p.code())
+                        {
+                            let port = port(Some(p.clone()))?;
+                            return Ok(format!("{}:{}", &*host, port));
                         }
                     }
                 }
@@ -627,6 +636,20 @@ impl MultiAddr {
     }
 }
 
+/// Return the TCP, TLS, UDP, QUIC or WebSocket port following a host in a MultiAddr
+fn port(p: Option<ProtoValue>) -> Result<u16, Error> {
+    match p {
+        Some(p) if p.code() == Tcp::CODE => Ok(*p.cast::<Tcp>().unwrap()),
+        Some(p) if p.code() == Udp::CODE => Ok(*p.cast::<Udp>().unwrap()),
+        Some(p) if p.code() == Quic::CODE => Ok(*p.cast::<Quic>().unwrap()),
+        Some(p) if p.code() == Ws::CODE => Ok(*p.cast::<Ws>().unwrap()),
+        Some(p) if p.code() == Wss::CODE => Ok(*p.cast::<Wss>().unwrap()),
+        Some(p) if p.code() == Tls::CODE => Ok(*p.cast::<Tls>().unwrap()),
+        Some(p) => Err(Error::invalid_proto(p.code())),
+        None => Err(Error::message("No port found")),
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Match {
     Val(Code),
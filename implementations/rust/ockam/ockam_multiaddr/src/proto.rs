@@ -153,6 +153,231 @@ impl Protocol<'_> for Tcp {
     }
 }
 
+/// A UDP port number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Udp(pub u16);
+
+impl Udp {
+    pub fn new(v: u16) -> Self {
+        Udp(v)
+    }
+}
+
+impl Deref for Udp {
+    type Target = u16;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Protocol<'_> for Udp {
+    const CODE: Code = Code::new(273);
+    const PREFIX: &'static str = "udp";
+
+    fn read_str(input: Checked<&str>) -> Result<Self, Error> {
+        u16::from_str(&input).map(Udp).map_err(Error::message)
+    }
+
+    fn read_bytes(input: Checked<&[u8]>) -> Result<Self, Error> {
+        let mut b = [0; 2];
+        b.copy_from_slice(&input);
+        Ok(Udp(u16::from_be_bytes(b)))
+    }
+
+    fn write_str(&self, f: &mut fmt::Formatter) -> Result<(), Error> {
+        write!(f, "/{}/{}", Self::PREFIX, self.0)?;
+        Ok(())
+    }
+
+    fn write_bytes(&self, buf: &mut dyn Buffer) {
+        let mut b = encode::u32_buffer();
+        let uvi = encode::u32(Self::CODE.into(), &mut b);
+        buf.extend_with(uvi);
+        buf.extend_with(&self.0.to_be_bytes())
+    }
+}
+
+/// A QUIC port number, on which QUIC connections are accepted over UDP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Quic(pub u16);
+
+impl Quic {
+    pub fn new(v: u16) -> Self {
+        Quic(v)
+    }
+}
+
+impl Deref for Quic {
+    type Target = u16;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Protocol<'_> for Quic {
+    const CODE: Code = Code::new(460);
+    const PREFIX: &'static str = "quic";
+
+    fn read_str(input: Checked<&str>) -> Result<Self, Error> {
+        u16::from_str(&input).map(Quic).map_err(Error::message)
+    }
+
+    fn read_bytes(input: Checked<&[u8]>) -> Result<Self, Error> {
+        let mut b = [0; 2];
+        b.copy_from_slice(&input);
+        Ok(Quic(u16::from_be_bytes(b)))
+    }
+
+    fn write_str(&self, f: &mut fmt::Formatter) -> Result<(), Error> {
+        write!(f, "/{}/{}", Self::PREFIX, self.0)?;
+        Ok(())
+    }
+
+    fn write_bytes(&self, buf: &mut dyn Buffer) {
+        let mut b = encode::u32_buffer();
+        let uvi = encode::u32(Self::CODE.into(), &mut b);
+        buf.extend_with(uvi);
+        buf.extend_with(&self.0.to_be_bytes())
+    }
+}
+
+/// A WebSocket port number, on which plain WebSocket connections are accepted over TCP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ws(pub u16);
+
+impl Ws {
+    pub fn new(v: u16) -> Self {
+        Ws(v)
+    }
+}
+
+impl Deref for Ws {
+    type Target = u16;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Protocol<'_> for Ws {
+    const CODE: Code = Code::new(477);
+    const PREFIX: &'static str = "ws";
+
+    fn read_str(input: Checked<&str>) -> Result<Self, Error> {
+        u16::from_str(&input).map(Ws).map_err(Error::message)
+    }
+
+    fn read_bytes(input: Checked<&[u8]>) -> Result<Self, Error> {
+        let mut b = [0; 2];
+        b.copy_from_slice(&input);
+        Ok(Ws(u16::from_be_bytes(b)))
+    }
+
+    fn write_str(&self, f: &mut fmt::Formatter) -> Result<(), Error> {
+        write!(f, "/{}/{}", Self::PREFIX, self.0)?;
+        Ok(())
+    }
+
+    fn write_bytes(&self, buf: &mut dyn Buffer) {
+        let mut b = encode::u32_buffer();
+        let uvi = encode::u32(Self::CODE.into(), &mut b);
+        buf.extend_with(uvi);
+        buf.extend_with(&self.0.to_be_bytes())
+    }
+}
+
+/// A secure WebSocket port number, on which WebSocket connections are accepted over TLS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Wss(pub u16);
+
+impl Wss {
+    pub fn new(v: u16) -> Self {
+        Wss(v)
+    }
+}
+
+impl Deref for Wss {
+    type Target = u16;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Protocol<'_> for Wss {
+    const CODE: Code = Code::new(478);
+    const PREFIX: &'static str = "wss";
+
+    fn read_str(input: Checked<&str>) -> Result<Self, Error> {
+        u16::from_str(&input).map(Wss).map_err(Error::message)
+    }
+
+    fn read_bytes(input: Checked<&[u8]>) -> Result<Self, Error> {
+        let mut b = [0; 2];
+        b.copy_from_slice(&input);
+        Ok(Wss(u16::from_be_bytes(b)))
+    }
+
+    fn write_str(&self, f: &mut fmt::Formatter) -> Result<(), Error> {
+        write!(f, "/{}/{}", Self::PREFIX, self.0)?;
+        Ok(())
+    }
+
+    fn write_bytes(&self, buf: &mut dyn Buffer) {
+        let mut b = encode::u32_buffer();
+        let uvi = encode::u32(Self::CODE.into(), &mut b);
+        buf.extend_with(uvi);
+        buf.extend_with(&self.0.to_be_bytes())
+    }
+}
+
+/// A TCP port number, on which TCP connections are accepted over TLS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tls(pub u16);
+
+impl Tls {
+    pub fn new(v: u16) -> Self {
+        Tls(v)
+    }
+}
+
+impl Deref for Tls {
+    type Target = u16;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Protocol<'_> for Tls {
+    const CODE: Code = Code::new(448);
+    const PREFIX: &'static str = "tls";
+
+    fn read_str(input: Checked<&str>) -> Result<Self, Error> {
+        u16::from_str(&input).map(Tls).map_err(Error::message)
+    }
+
+    fn read_bytes(input: Checked<&[u8]>) -> Result<Self, Error> {
+        let mut b = [0; 2];
+        b.copy_from_slice(&input);
+        Ok(Tls(u16::from_be_bytes(b)))
+    }
+
+    fn write_str(&self, f: &mut fmt::Formatter) -> Result<(), Error> {
+        write!(f, "/{}/{}", Self::PREFIX, self.0)?;
+        Ok(())
+    }
+
+    fn write_bytes(&self, buf: &mut dyn Buffer) {
+        let mut b = encode::u32_buffer();
+        let uvi = encode::u32(Self::CODE.into(), &mut b);
+        buf.extend_with(uvi);
+        buf.extend_with(&self.0.to_be_bytes())
+    }
+}
+
 macro_rules! gen_str_proto {
     ($t:ident, $c:literal, $p:literal) => {
         #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -210,3 +435,4 @@ gen_str_proto!(Node, 72526, "node");
 gen_str_proto!(Project, 82526, "project");
 gen_str_proto!(Space, 92526, "space");
 gen_str_proto!(Secure, 99526, "secure");
+gen_str_proto!(Ble, 112526, "ble");
@@ -1,6 +1,8 @@
 use super::{Code, Codec, Protocol};
 use crate::codec::StdCodec;
-use crate::proto::{DnsAddr, Node, Project, Secure, Service, Space, Tcp, Worker};
+use crate::proto::{
+    Ble, DnsAddr, Node, Project, Quic, Secure, Service, Space, Tcp, Tls, Udp, Worker, Ws, Wss,
+};
 use alloc::collections::btree_map::BTreeMap;
 use alloc::sync::Arc;
 use core::fmt;
@@ -27,6 +29,11 @@ impl Default for Registry {
         let mut r = RegistryBuilder::new();
         r.register(Worker::CODE, Worker::PREFIX, std_codec.clone());
         r.register(Tcp::CODE, Tcp::PREFIX, std_codec.clone());
+        r.register(Udp::CODE, Udp::PREFIX, std_codec.clone());
+        r.register(Quic::CODE, Quic::PREFIX, std_codec.clone());
+        r.register(Ws::CODE, Ws::PREFIX, std_codec.clone());
+        r.register(Wss::CODE, Wss::PREFIX, std_codec.clone());
+        r.register(Tls::CODE, Tls::PREFIX, std_codec.clone());
         r.register(DnsAddr::CODE, DnsAddr::PREFIX, std_codec.clone());
         #[allow(clippy::redundant_clone)]
         r.register(Service::CODE, Service::PREFIX, std_codec.clone());
@@ -38,6 +45,8 @@ impl Default for Registry {
         r.register(Space::CODE, Space::PREFIX, std_codec.clone());
         #[allow(clippy::redundant_clone)]
         r.register(Secure::CODE, Secure::PREFIX, std_codec.clone());
+        #[allow(clippy::redundant_clone)]
+        r.register(Ble::CODE, Ble::PREFIX, std_codec.clone());
         #[cfg(feature = "std")]
         r.register(
             crate::proto::Ip4::CODE,
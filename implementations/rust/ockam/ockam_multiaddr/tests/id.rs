@@ -1,5 +1,7 @@
 use core::fmt;
-use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp};
+use ockam_multiaddr::proto::{
+    Ble, DnsAddr, Ip4, Ip6, Node, Project, Quic, Secure, Service, Space, Tcp, Tls, Udp, Ws, Wss,
+};
 use ockam_multiaddr::{Code, Match, MultiAddr, Protocol};
 use quickcheck::{quickcheck, Arbitrary, Gen};
 use rand::distributions::{Alphanumeric, DistString};
@@ -100,6 +102,26 @@ quickcheck! {
                         addr.push_back(Tcp::new(0)).unwrap();
                         prot.push_back(Tcp::CODE);
                     }
+                    Udp::CODE => {
+                        addr.push_back(Udp::new(0)).unwrap();
+                        prot.push_back(Udp::CODE);
+                    }
+                    Quic::CODE => {
+                        addr.push_back(Quic::new(0)).unwrap();
+                        prot.push_back(Quic::CODE);
+                    }
+                    Ws::CODE => {
+                        addr.push_back(Ws::new(0)).unwrap();
+                        prot.push_back(Ws::CODE);
+                    }
+                    Wss::CODE => {
+                        addr.push_back(Wss::new(0)).unwrap();
+                        prot.push_back(Wss::CODE);
+                    }
+                    Tls::CODE => {
+                        addr.push_back(Tls::new(0)).unwrap();
+                        prot.push_back(Tls::CODE);
+                    }
                     DnsAddr::CODE => {
                         addr.push_back(DnsAddr::new("localhost")).unwrap();
                         prot.push_back(DnsAddr::CODE);
@@ -124,6 +146,10 @@ quickcheck! {
                         addr.push_back(Node::new("node")).unwrap();
                         prot.push_back(Node::CODE);
                     }
+                    Ble::CODE => {
+                        addr.push_back(Ble::new("ble")).unwrap();
+                        prot.push_back(Ble::CODE);
+                    }
                     Project::CODE => {
                         addr.push_back(Project::new("project")).unwrap();
                         prot.push_back(Project::CODE);
@@ -145,12 +171,18 @@ quickcheck! {
 
 const PROTOS: &[Code] = &[
     Tcp::CODE,
+    Udp::CODE,
+    Quic::CODE,
+    Ws::CODE,
+    Wss::CODE,
+    Tls::CODE,
     DnsAddr::CODE,
     Ip4::CODE,
     Ip6::CODE,
     Secure::CODE,
     Service::CODE,
     Node::CODE,
+    Ble::CODE,
     Project::CODE,
     Space::CODE,
 ];
@@ -161,6 +193,11 @@ impl Arbitrary for Addr {
         for _ in 0..g.size() {
             match *g.choose(PROTOS).unwrap() {
                 Tcp::CODE => a.push_back(Tcp::new(u16::arbitrary(g))).unwrap(),
+                Udp::CODE => a.push_back(Udp::new(u16::arbitrary(g))).unwrap(),
+                Quic::CODE => a.push_back(Quic::new(u16::arbitrary(g))).unwrap(),
+                Ws::CODE => a.push_back(Ws::new(u16::arbitrary(g))).unwrap(),
+                Wss::CODE => a.push_back(Wss::new(u16::arbitrary(g))).unwrap(),
+                Tls::CODE => a.push_back(Tls::new(u16::arbitrary(g))).unwrap(),
                 DnsAddr::CODE => a.push_back(DnsAddr::new(gen_hostname())).unwrap(),
                 Ip4::CODE => a.push_back(Ip4::new(Ipv4Addr::arbitrary(g))).unwrap(),
                 Ip6::CODE => a.push_back(Ip6::new(Ipv6Addr::arbitrary(g))).unwrap(),
@@ -169,6 +206,7 @@ impl Arbitrary for Addr {
                 Project::CODE => a.push_back(Project::new(gen_string())).unwrap(),
                 Space::CODE => a.push_back(Space::new(gen_string())).unwrap(),
                 Node::CODE => a.push_back(Node::new(gen_string())).unwrap(),
+                Ble::CODE => a.push_back(Ble::new(gen_string())).unwrap(),
                 _ => unreachable!(),
             }
         }
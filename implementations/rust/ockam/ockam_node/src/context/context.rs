@@ -1,6 +1,6 @@
 use crate::channel_types::{SmallReceiver, SmallSender};
 use crate::tokio::runtime::Handle;
-use crate::{error::*, AsyncDropSender, NodeMessage};
+use crate::{error::*, AsyncDropSender, NodeMessage, WorkerStats};
 use core::sync::atomic::AtomicUsize;
 use ockam_core::compat::collections::HashMap;
 use ockam_core::compat::sync::{Arc, RwLock};
@@ -128,6 +128,22 @@ impl Context {
             .take_workers()
     }
 
+    /// Return the statistics of all the workers and processors running on a node
+    pub async fn worker_stats(&self) -> Result<Vec<WorkerStats>> {
+        let (msg, mut reply_rx) = NodeMessage::worker_stats();
+
+        self.sender
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        reply_rx
+            .recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
+            .take_worker_stats()
+    }
+
     /// Send a shutdown acknowledgement to the router
     pub(crate) async fn send_stop_ack(&self) -> Result<()> {
         self.sender
@@ -25,6 +25,8 @@ pub enum NodeMessage {
     },
     /// Return a list of all worker addresses
     ListWorkers(SmallSender<NodeReplyResult>),
+    /// Return the statistics of all workers and processors
+    WorkerStats(SmallSender<NodeReplyResult>),
     /// Add an existing address to a cluster
     SetCluster(Address, String, SmallSender<NodeReplyResult>),
     /// Stop an existing worker
@@ -54,6 +56,7 @@ impl fmt::Display for NodeMessage {
         match self {
             NodeMessage::StartWorker { .. } => write!(f, "StartWorker"),
             NodeMessage::ListWorkers(_) => write!(f, "ListWorkers"),
+            NodeMessage::WorkerStats(_) => write!(f, "WorkerStats"),
             NodeMessage::SetCluster(_, _, _) => write!(f, "SetCluster"),
             NodeMessage::StopWorker(_, _, _) => write!(f, "StopWorker"),
             NodeMessage::StartProcessor(_, _, _) => write!(f, "StartProcessor"),
@@ -118,6 +121,12 @@ impl NodeMessage {
         (Self::ListWorkers(tx), rx)
     }
 
+    /// Create a worker statistics message and reply receiver
+    pub fn worker_stats() -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::WorkerStats(tx), rx)
+    }
+
     /// Create a set cluster message and reply receiver
     pub fn set_cluster(addr: Address, label: String) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
@@ -164,6 +173,8 @@ pub enum RouterReply {
     Ok,
     /// A list of worker addresses
     Workers(Vec<Address>),
+    /// The statistics of a list of workers
+    WorkerStats(Vec<WorkerStats>),
     /// Message sender to a specific worker
     Sender {
         /// The address a message is being sent to
@@ -175,6 +186,19 @@ pub enum RouterReply {
     State(bool),
 }
 
+/// Statistics about a worker or a processor, as seen by the router
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerStats {
+    /// Primary address of the worker
+    pub address: Address,
+    /// True if the address belongs to a processor
+    pub processor: bool,
+    /// Number of messages currently waiting in the worker mailbox
+    pub mailbox_len: usize,
+    /// Number of messages routed to the worker since it was started
+    pub messages: usize,
+}
+
 /// Specify the type of node shutdown
 ///
 /// For most users `ShutdownType::Graceful()` is recommended.  The
@@ -259,6 +283,11 @@ impl RouterReply {
         Ok(Self::Workers(v))
     }
 
+    /// Return [RouterReply::WorkerStats] for the given statistics
+    pub fn worker_stats(v: Vec<WorkerStats>) -> NodeReplyResult {
+        Ok(Self::WorkerStats(v))
+    }
+
     /// Return [RouterReply::Sender] for the given information
     pub fn sender(addr: Address, sender: MessageSender<RelayMessage>) -> NodeReplyResult {
         Ok(RouterReply::Sender { addr, sender })
@@ -280,6 +309,14 @@ impl RouterReply {
         }
     }
 
+    /// Consume the wrapper and return [RouterReply::WorkerStats]
+    pub fn take_worker_stats(self) -> Result<Vec<WorkerStats>> {
+        match self {
+            Self::WorkerStats(s) => Ok(s),
+            _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
+        }
+    }
+
     /// Consume the wrapper and return [RouterReply::State]
     pub fn take_state(self) -> Result<bool> {
         match self {
@@ -249,6 +249,11 @@ impl Router {
                 .await
                 .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
 
+            WorkerStats(sender) => sender
+                .send(RouterReply::worker_stats(self.map.worker_stats()))
+                .await
+                .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
+
             SetCluster(addr, label, reply) => {
                 debug!("Setting cluster on address {}", addr);
                 let msg = self.map.set_cluster(label, addr);
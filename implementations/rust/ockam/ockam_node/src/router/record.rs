@@ -2,7 +2,7 @@ use crate::channel_types::{MessageSender, SmallSender};
 use crate::relay::CtrlSignal;
 use crate::{
     error::{NodeError, NodeReason},
-    NodeReplyResult, RouterReply,
+    NodeReplyResult, RouterReply, WorkerStats,
 };
 use core::sync::atomic::{AtomicUsize, Ordering};
 use ockam_core::{
@@ -121,6 +121,19 @@ impl InternalMap {
         self.metrics.0.load(Ordering::Acquire)
     }
 
+    /// Return the statistics of all the workers and processors
+    pub(super) fn worker_stats(&self) -> Vec<WorkerStats> {
+        self.address_records_map
+            .iter()
+            .map(|(address, record)| WorkerStats {
+                address: address.clone(),
+                processor: record.meta.processor,
+                mailbox_len: record.msg_count.load(Ordering::Acquire),
+                messages: record.msg_total.load(Ordering::Acquire),
+            })
+            .collect()
+    }
+
     /// Add an address to a particular cluster
     pub(super) fn set_cluster(&mut self, label: String, primary: Address) -> NodeReplyResult {
         let rec = self
@@ -240,6 +253,7 @@ pub struct AddressRecord {
     ready: ReadyState,
     meta: AddressMeta,
     msg_count: Arc<AtomicUsize>,
+    msg_total: AtomicUsize,
 }
 
 impl AddressRecord {
@@ -269,12 +283,14 @@ impl AddressRecord {
             state: AddressState::Running,
             ready: ReadyState::Initialising(vec![]),
             msg_count,
+            msg_total: AtomicUsize::new(0),
             meta,
         }
     }
 
     pub fn increment_msg_count(&self) {
         self.msg_count.fetch_add(1, Ordering::Acquire);
+        self.msg_total.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Signal this worker to stop -- it will no longer be able to receive messages
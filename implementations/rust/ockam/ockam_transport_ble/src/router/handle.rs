@@ -44,6 +44,11 @@ impl BleRouterHandle {
 }
 
 impl BleRouterHandle {
+    /// Address receiving the registrations of the connection workers
+    pub(crate) fn api_addr(&self) -> Address {
+        self.api_addr.clone()
+    }
+
     /// Register a new connection worker with this router
     pub async fn register(&self, pair: &WorkerPair) -> Result<()> {
         let ble_address: Address = format!("{}#{}", crate::BLE, pair.peer()).into();
@@ -98,11 +103,12 @@ impl BleRouterHandle {
     }
 
     /// Establish an outgoing BLE connection on an existing transport
+    /// and return the address of its sender worker
     pub async fn connect<A: BleClientDriver + BleStreamDriver + Send + 'static, S: AsRef<str>>(
         &self,
         mut ble_client: BleClient<A>,
         peer: S,
-    ) -> Result<()> {
+    ) -> Result<Address> {
         let (peer_addr, servicenames) = Self::resolve_peer(peer.as_ref())?;
 
         debug!("scanning all available adapters");
@@ -112,10 +118,12 @@ impl BleRouterHandle {
         ble_client.connect().await?;
 
         let stream = crate::driver::AsyncStream::with_ble_device(ble_client);
-        let pair = BleSendWorker::start_pair(&self.ctx, stream, peer_addr, servicenames).await?;
+        let pair =
+            BleSendWorker::start_pair(&self.ctx, stream, peer_addr, servicenames, self.api_addr())
+                .await?;
 
         self.register(&pair).await?;
 
-        Ok(())
+        Ok(pair.tx_addr())
     }
 }
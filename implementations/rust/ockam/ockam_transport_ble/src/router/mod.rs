@@ -22,6 +22,11 @@ pub enum BleRouterMessage {
         /// The clients own worker bus address.
         self_addr: Address,
     },
+    /// Remove the routes of a client whose connection was closed.
+    Unregister {
+        /// The clients own worker bus address.
+        self_addr: Address,
+    },
 }
 
 /// A Bluetooth Low Energy address router and connection listener
@@ -72,6 +77,11 @@ impl BleRouter {
         Ok(())
     }
 
+    fn handle_unregister(&mut self, self_addr: Address) {
+        debug!("BLE unregistration request: {}", self_addr);
+        self.map.retain(|_, addr| addr != &self_addr);
+    }
+
     async fn handle_route(&mut self, ctx: &Context, mut msg: LocalMessage) -> Result<()> {
         debug!("Ble route request: {:?}", msg.transport().onward_route);
 
@@ -126,6 +136,10 @@ impl Worker for BleRouter {
                     trace!("handle_message register: {:?} => {:?}", accepts, self_addr);
                     self.handle_register(accepts, self_addr).await?;
                 }
+                BleRouterMessage::Unregister { self_addr } => {
+                    trace!("handle_message unregister: {:?}", self_addr);
+                    self.handle_unregister(self_addr);
+                }
             };
         } else {
             return Err(TransportError::InvalidAddress.into());
@@ -2,7 +2,7 @@ use core::str::FromStr;
 use core::sync::atomic::{AtomicBool, Ordering};
 
 use ockam_core::compat::boxed::Box;
-use ockam_core::{async_trait, AsyncTryClone, Result};
+use ockam_core::{async_trait, Address, AsyncTryClone, Result};
 use ockam_node::Context;
 
 use crate::driver::{BleClient, BleServer};
@@ -73,6 +73,10 @@ impl BleTransport {
     /// One worker handles outgoing messages, while another handles
     /// incoming messages. The local worker address is chosen based on
     /// the peer the worker is meant to be connected to.
+    ///
+    /// Return the address of the worker handling the outgoing messages.
+    /// This worker is stopped, and the peer can be connected again, when
+    /// the connection is closed.
     pub async fn connect<
         A: BleClientDriver + BleStreamDriver + Send + 'static,
         S: AsRef<str> + core::fmt::Debug,
@@ -80,7 +84,7 @@ impl BleTransport {
         &self,
         ble_client: BleClient<A>,
         peer: S,
-    ) -> Result<()> {
+    ) -> Result<Address> {
         self.router_handle.connect(ble_client, peer.as_ref()).await
     }
 
@@ -82,6 +82,7 @@ where
                 // TODO resolve connecting BleClient's addresses
                 crate::parse_ble_addr("ble_client_addr").unwrap(),
                 vec![],
+                self.router_handle.api_addr(),
             )
             .await?;
 
@@ -16,6 +16,7 @@ use crate::driver::{BleEvent, BleStreamDriver, PacketBuffer};
 pub struct BleRecvProcessor<A> {
     rx_stream: Source<A>,
     peer_addr: Address,
+    sender_addr: Address,
     packet_buffer: PacketBuffer,
 }
 
@@ -23,10 +24,11 @@ impl<A> BleRecvProcessor<A>
 where
     A: BleStreamDriver + Send + 'static,
 {
-    pub(crate) fn new(rx_stream: Source<A>, peer_addr: Address) -> Self {
+    pub(crate) fn new(rx_stream: Source<A>, peer_addr: Address, sender_addr: Address) -> Self {
         Self {
             rx_stream,
             peer_addr,
+            sender_addr,
             packet_buffer: PacketBuffer::default(),
         }
     }
@@ -56,6 +58,9 @@ where
             }
             Ok(BleEvent::DisconnectionComplete) => {
                 debug!("\t=> BleEvent::DisconnectionComplete");
+                // The sender stops this processor and unregisters the peer
+                ctx.stop_worker(self.sender_addr.clone()).await?;
+                return Ok(false);
             }
             Ok(BleEvent::Received(fragment)) => {
                 debug!("\t=> BleEvent::ReceivedData -> {:?} bytes", fragment.len());
@@ -6,6 +6,7 @@ use ockam_node::Context;
 use ockam_transport_core::TransportError;
 
 use crate::driver::{AsyncStream, BleStreamDriver, PacketBuffer, Sink, Source};
+use crate::router::BleRouterMessage;
 use crate::workers::BleRecvProcessor;
 use crate::BleAddr;
 
@@ -37,18 +38,22 @@ pub(crate) struct BleSendWorker<A> {
     rx_stream: Option<Source<A>>,
     tx_stream: Option<Sink<A>>,
     peer: BleAddr,
+    rx_addr: Option<Address>,
+    router_api_addr: Address,
 }
 
 impl<A> BleSendWorker<A>
 where
     A: BleStreamDriver + Send + 'static,
 {
-    fn new(stream: AsyncStream<A>, peer: BleAddr) -> Self {
+    fn new(stream: AsyncStream<A>, peer: BleAddr, router_api_addr: Address) -> Self {
         let (tx, rx) = stream.split();
         Self {
             rx_stream: Some(rx),
             tx_stream: Some(tx),
             peer,
+            rx_addr: None,
+            router_api_addr,
         }
     }
 
@@ -57,11 +62,12 @@ where
         stream: AsyncStream<A>,
         peer: BleAddr,
         servicenames: Vec<String>,
+        router_api_addr: Address,
     ) -> Result<WorkerPair> {
         debug!("Creating new BLE worker pair");
 
         let tx_addr = Address::random_local();
-        let sender = BleSendWorker::new(stream, peer.clone());
+        let sender = BleSendWorker::new(stream, peer.clone(), router_api_addr);
 
         debug!("start send worker({:?})", tx_addr.clone());
         ctx.start_worker(tx_addr.clone(), sender).await?;
@@ -89,8 +95,11 @@ where
 
         if let Some(rx_stream) = self.rx_stream.take() {
             let rx_addr = Address::random_local();
-            let receiver =
-                BleRecvProcessor::new(rx_stream, format!("{}#{}", crate::BLE, self.peer).into());
+            let receiver = BleRecvProcessor::new(
+                rx_stream,
+                format!("{}#{}", crate::BLE, self.peer).into(),
+                ctx.address(),
+            );
             ctx.start_processor_with_access_control(
                 rx_addr.clone(),
                 receiver,
@@ -98,6 +107,7 @@ where
                 AllowAll, // FIXME: @ac
             )
             .await?;
+            self.rx_addr = Some(rx_addr);
             debug!("started receiver");
         } else {
             error!("TransportError::GenericIo");
@@ -107,6 +117,22 @@ where
         Ok(())
     }
 
+    // Once the connection is closed, the receiver is stopped and the peer
+    // is removed from the router, so that it can be connected again
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        debug!("shutdown for peer: {:?}", self.peer);
+        if let Some(rx_addr) = self.rx_addr.take() {
+            let _ = ctx.stop_processor(rx_addr).await;
+        }
+        ctx.send(
+            self.router_api_addr.clone(),
+            BleRouterMessage::Unregister {
+                self_addr: ctx.address(),
+            },
+        )
+        .await
+    }
+
     // BleSendWorker will receive messages from the BleRouter to send
     // across the TcpStream to the next remote peer.
     async fn handle_message(
@@ -137,7 +163,7 @@ where
             Ok(_) => (),
             Err(e) => {
                 error!("Failed to send fragment to peer {}: {:?}", self.peer, e);
-                ctx.stop_worker(ctx.address()).await?;
+                return ctx.stop_worker(ctx.address()).await;
             }
         }
 
@@ -149,7 +175,7 @@ where
                 Ok(_) => (),
                 Err(e) => {
                     error!("Failed to send fragment to peer {}: {:?}", self.peer, e);
-                    ctx.stop_worker(ctx.address()).await?;
+                    return ctx.stop_worker(ctx.address()).await;
                 }
             }
 
@@ -0,0 +1,90 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+use std::sync::Arc;
+
+use ockam_core::{async_trait, route, Result};
+use ockam_node::Context;
+use ockam_transport_ble::driver::{BleClientDriver, BleEvent, BleStreamDriver};
+use ockam_transport_ble::{BleAddr, BleClient, BleTransport, BLE};
+
+/// BLE peripheral which stays connected until it is told to disconnect
+#[derive(Clone, Default)]
+struct MockPeripheral {
+    disconnected: Arc<AtomicBool>,
+    written: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl BleClientDriver for MockPeripheral {
+    async fn scan(&mut self, _ble_addr: &BleAddr) -> Result<()> {
+        Ok(())
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BleStreamDriver for MockPeripheral {
+    async fn poll<'b>(&mut self, _buffer: &'b mut [u8]) -> Result<BleEvent<'b>> {
+        if self.disconnected.load(Ordering::SeqCst) {
+            Ok(BleEvent::DisconnectionComplete)
+        } else {
+            ockam_node::tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(BleEvent::None)
+        }
+    }
+
+    async fn write(&mut self, _buffer: &[u8]) -> Result<()> {
+        self.written.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Wait until a condition is true, or fail after a few seconds
+async fn wait_until(condition: impl Fn() -> bool) {
+    for _ in 0..100 {
+        if condition() {
+            return;
+        }
+        ockam_node::tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("the condition is not true after 5 seconds");
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn ble_connection__peripheral_disconnected__can_be_connected_again(
+    ctx: &mut Context,
+) -> Result<()> {
+    let ble = BleTransport::create(ctx).await?;
+    let peer = route![(BLE, "ockam_ble_1"), "echo"];
+
+    let first = MockPeripheral::default();
+    let sender = ble
+        .connect(BleClient::with_adapter(first.clone()), "ockam_ble_1")
+        .await?;
+    assert!(ctx.list_workers().await?.contains(&sender));
+
+    // Once the peripheral disconnects, its sender is stopped and its route removed
+    first.disconnected.store(true, Ordering::SeqCst);
+    let mut attempts = 0;
+    while ctx.list_workers().await?.contains(&sender) {
+        attempts += 1;
+        assert!(
+            attempts < 100,
+            "the sender of the peripheral is not stopped"
+        );
+        ockam_node::tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    // The messages sent to the peripheral are routed to the new connection
+    let second = MockPeripheral::default();
+    ble.connect(BleClient::with_adapter(second.clone()), "ockam_ble_1")
+        .await?;
+    ctx.send(peer, "hello".to_string()).await?;
+    wait_until(|| second.written.load(Ordering::SeqCst)).await;
+
+    ctx.stop().await
+}
@@ -0,0 +1,23 @@
+//! This crate provides a QUIC Transport for Ockam's Routing Protocol.
+//!
+//! A single UDP port is used by a listener for all its connections. The routing messages
+//! sent to a peer are carried, in order, by a stream of the QUIC connection to that peer,
+//! and a connection survives the changes of address of its client (path migration).
+//!
+//! The QUIC connections are encrypted with a self-signed certificate which is not verified:
+//! the peers must be authenticated with an Ockam secure channel established over the transport.
+use ockam_core::TransportType;
+
+pub use transport::{QuicListener, QuicListenerOptions, QuicTransport, QuicTransportExtension};
+
+mod router;
+mod tls;
+mod transport;
+mod workers;
+
+pub const QUIC: TransportType = TransportType::new(6);
+
+pub const CLUSTER_NAME: &str = "_internals.transport.quic";
+
+/// Protocol negotiated with ALPN during the QUIC handshake
+const ALPN_PROTOCOL: &[u8] = b"ockam";
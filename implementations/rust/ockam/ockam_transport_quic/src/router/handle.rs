@@ -0,0 +1,68 @@
+use crate::router::messages::{QuicRouterRequest, QuicRouterResponse};
+use crate::QuicListener;
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{Address, AllowAll, Result};
+use ockam_node::Context;
+use ockam_transport_core::TransportError;
+use std::net::SocketAddr;
+
+/// A handle to connect to a QuicRouter
+///
+/// Dropping this handle is harmless.
+pub(crate) struct QuicRouterHandle {
+    ctx: Context,
+    api_addr: Address,
+}
+
+impl QuicRouterHandle {
+    pub async fn try_new(ctx: &Context, api_addr: &Address) -> Result<Self> {
+        // FIXME: @ac. The handle will only ever need to send & receive messages
+        // to & from the router.
+        let handle_ctx = ctx
+            .new_detached(
+                Address::random_tagged("QuicRouterHandle.detached"),
+                AllowAll,
+                AllowAll,
+            )
+            .await?;
+
+        Ok(Self {
+            ctx: handle_ctx,
+            api_addr: api_addr.clone(),
+        })
+    }
+
+    /// Request router start listening on a local UDP port
+    /// so the local node can act as a server to other nodes
+    pub async fn listen(
+        &self,
+        local_addr: SocketAddr,
+        flow_control_id: Option<FlowControlId>,
+    ) -> Result<QuicListener> {
+        let msg = QuicRouterRequest::Listen {
+            local_addr,
+            flow_control_id,
+        };
+        match self
+            .ctx
+            .send_and_receive(self.api_addr.clone(), msg)
+            .await?
+        {
+            QuicRouterResponse::Listen(res) => res,
+            _ => Err(TransportError::InvalidRouterResponseType.into()),
+        }
+    }
+
+    /// Request router to stop listening on a local UDP port
+    pub async fn stop_listener(&self, listener: QuicListener) -> Result<()> {
+        let msg = QuicRouterRequest::StopListener { listener };
+        match self
+            .ctx
+            .send_and_receive(self.api_addr.clone(), msg)
+            .await?
+        {
+            QuicRouterResponse::StopListener(res) => res,
+            _ => Err(TransportError::InvalidRouterResponseType.into()),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::QuicListener;
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{Message, Result};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+#[derive(Serialize, Deserialize, Debug, Message)]
+pub enum QuicRouterRequest {
+    /// Accept QUIC connections on a local UDP port so the local node can
+    /// act as a server to other nodes
+    Listen {
+        local_addr: SocketAddr,
+        flow_control_id: Option<FlowControlId>,
+    },
+    /// Stop accepting connections on a local UDP port
+    StopListener { listener: QuicListener },
+}
+
+#[derive(Serialize, Deserialize, Debug, Message)]
+pub enum QuicRouterResponse {
+    Listen(Result<QuicListener>),
+    StopListener(Result<()>),
+}
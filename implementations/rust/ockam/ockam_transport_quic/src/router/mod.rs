@@ -0,0 +1,6 @@
+pub(crate) use handle::QuicRouterHandle;
+pub(crate) use quic_router::QuicRouter;
+
+mod handle;
+mod messages;
+mod quic_router;
@@ -0,0 +1,242 @@
+use crate::router::messages::{QuicRouterRequest, QuicRouterResponse};
+use crate::router::QuicRouterHandle;
+use crate::tls::{client_config, server_config};
+use crate::workers::{start_connection, Connections, QuicListenProcessor};
+use crate::{QuicListener, QUIC};
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{
+    async_trait, Address, AllowAll, Any, Decodable, DenyAll, LocalMessage, Mailbox, Mailboxes,
+    Result, Routed, Worker,
+};
+use ockam_node::{Context, WorkerBuilder};
+use ockam_transport_core::TransportError;
+use quinn::Endpoint;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs};
+use std::sync::Arc;
+use tracing::{debug, error, trace, warn};
+
+/// The router for the QUIC transport
+///
+/// The router opens a single 'client' endpoint for the connections which are
+/// initiated by the local node. A connection to a peer is established the first time
+/// a message is routed to it, and then reused for all the messages sent to that peer.
+///
+/// The router opens a 'server' endpoint whenever a user calls
+/// [`listen()`](crate::QuicTransport::listen) on the transport.
+///
+/// For each connection, a sender ([`QuicSendWorker`](crate::workers::QuicSendWorker)) and
+/// a receiver ([`QuicRecvProcessor`](crate::workers::QuicRecvProcessor)) handle the
+/// messages sent and received on that connection. The messages received on a connection
+/// bypass the router since the receiver injects the sender's address into their return route.
+pub(crate) struct QuicRouter {
+    ctx: Context,
+    main_addr: Address,
+    api_addr: Address,
+    /// Endpoint of the 'client' connections
+    client_endpoint: Endpoint,
+    /// Senders of the open connections, by address of their peer
+    connections: Connections,
+}
+
+impl QuicRouter {
+    /// Create and register a new QUIC router with the node context
+    pub(crate) async fn register(ctx: &Context) -> Result<QuicRouterHandle> {
+        // This context is only used to start workers, doesn't need to send nor receive messages
+        let child_ctx = ctx
+            .new_detached(
+                Address::random_tagged("QuicRouter.detached"),
+                DenyAll,
+                DenyAll,
+            )
+            .await?;
+
+        let main_addr = Address::random_tagged("QuicRouter.main_addr");
+        let api_addr = Address::random_tagged("QuicRouter.api_addr");
+        debug!("Initialising new QuicRouter with address {}", &main_addr);
+
+        let handle = QuicRouterHandle::try_new(&child_ctx, &api_addr).await?;
+
+        let mut client_endpoint =
+            Endpoint::client(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0))
+                .map_err(|_| TransportError::BindFailed)?;
+        client_endpoint.set_default_client_config(client_config());
+
+        let router = Self {
+            ctx: child_ctx,
+            main_addr: main_addr.clone(),
+            api_addr: api_addr.clone(),
+            client_endpoint,
+            connections: Connections::default(),
+        };
+
+        let main_mailbox = Mailbox::new(
+            main_addr.clone(),
+            Arc::new(AllowAll), // FIXME: @ac
+            Arc::new(AllowAll), // FIXME: @ac
+        );
+        let api_mailbox = Mailbox::new(
+            api_addr.clone(),
+            Arc::new(AllowAll), // FIXME: @ac
+            Arc::new(AllowAll), // FIXME: @ac
+        );
+        WorkerBuilder::new(router)
+            .with_mailboxes(Mailboxes::new(main_mailbox, vec![api_mailbox]))
+            .start(ctx)
+            .await?;
+
+        trace!("Registering QUIC router for type = {}", QUIC);
+        ctx.register(QUIC, main_addr).await?;
+
+        Ok(handle)
+    }
+
+    /// Handle the routing of 'client' messages
+    async fn handle_route(&mut self, ctx: &Context, mut msg: LocalMessage) -> Result<()> {
+        let peer = msg.transport().onward_route.next()?.clone();
+        if peer.transport_type() != QUIC {
+            error!(addr = %peer, "Destination address is not QUIC");
+            return Err(TransportError::UnknownRoute.into());
+        }
+        let peer = resolve_peer(peer.address())?;
+
+        // Forward the message to the sender of the connection to the peer
+        let sender = self.connection_sender(peer).await?;
+        msg.transport_mut().onward_route.modify().prepend(sender);
+        ctx.forward(msg).await
+    }
+
+    /// Return the sender of the connection to a peer, connecting to the peer if necessary
+    async fn connection_sender(&self, peer: SocketAddr) -> Result<Address> {
+        if let Some(sender) = self.connections.get(&peer) {
+            return Ok(sender);
+        }
+        debug!(%peer, "Connecting to a QUIC peer");
+        let connection = self
+            .client_endpoint
+            .connect(peer, "localhost")
+            .map_err(|e| {
+                warn!(%peer, %e, "Failed to connect to a QUIC peer");
+                TransportError::PeerNotFound
+            })?
+            .await
+            .map_err(|e| {
+                warn!(%peer, %e, "Failed to connect to a QUIC peer");
+                TransportError::PeerNotFound
+            })?;
+        start_connection(&self.ctx, connection, self.connections.clone(), None).await
+    }
+
+    /// Start accepting connections on the given socket address.
+    ///
+    /// When a flow control id is given, the receivers of the accepted connections are
+    /// producers for that flow control id, and the received messages can only be sent to
+    /// its consumers.
+    async fn create_listener(
+        &self,
+        local_addr: SocketAddr,
+        flow_control_id: Option<FlowControlId>,
+    ) -> Result<QuicListener> {
+        let endpoint = Endpoint::server(server_config()?, local_addr)
+            .map_err(|_| TransportError::BindFailed)?;
+        // Could be different from the local_addr, e.g., if binding to port 0
+        let socket_address = endpoint
+            .local_addr()
+            .map_err(|_| TransportError::InvalidAddress)?;
+        debug!("Accepting QUIC connections on {}", socket_address);
+
+        let processor_address = Address::random_tagged("QuicListenProcessor");
+        QuicListenProcessor::start(
+            &self.ctx,
+            processor_address.clone(),
+            endpoint,
+            self.connections.clone(),
+            flow_control_id.clone(),
+        )
+        .await?;
+
+        Ok(QuicListener {
+            socket_address,
+            processor_address,
+            flow_control_id,
+        })
+    }
+}
+
+/// Resolve the address of a peer to an IPv4 socket address
+fn resolve_peer(peer: &str) -> Result<SocketAddr> {
+    let peer_addr = peer
+        .to_socket_addrs()
+        .map_err(|_| TransportError::InvalidAddress)?
+        .find(SocketAddr::is_ipv4)
+        .ok_or_else(|| {
+            warn!("No IPv4 address resolved for peer {:?}", peer);
+            TransportError::UnknownRoute
+        })?;
+    if peer_addr.port() == 0 {
+        warn!(%peer_addr, "Will not connect to address");
+        return Err(TransportError::InvalidAddress.into());
+    }
+    Ok(peer_addr)
+}
+
+#[async_trait]
+impl Worker for QuicRouter {
+    type Message = Any;
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await?;
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, _ctx: &mut Context) -> Result<()> {
+        self.client_endpoint.close(0u32.into(), b"");
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let msg_addr = msg.msg_addr();
+
+        if msg_addr == self.main_addr {
+            // Process messages on main_addr
+            trace!(
+                "handle_message() MAIN_ADDR: onward_route = {}, return_route = {}",
+                msg.onward_route(),
+                msg.return_route(),
+            );
+            let msg = msg.into_local_message();
+            self.handle_route(ctx, msg).await?;
+        } else if msg_addr == self.api_addr {
+            // Process messages on api_addr
+            let return_route = msg.return_route();
+            let msg = QuicRouterRequest::decode(msg.payload())?;
+            trace!("handle_message() API_ADDR: msg = {:?}", msg);
+            match msg {
+                QuicRouterRequest::Listen {
+                    local_addr,
+                    flow_control_id,
+                } => {
+                    let res = self.create_listener(local_addr, flow_control_id).await;
+                    ctx.send_from_address(return_route, QuicRouterResponse::Listen(res), msg_addr)
+                        .await?;
+                }
+                QuicRouterRequest::StopListener { listener } => {
+                    let res = self
+                        .ctx
+                        .stop_processor(listener.processor_address.clone())
+                        .await;
+                    ctx.send_from_address(
+                        return_route,
+                        QuicRouterResponse::StopListener(res),
+                        msg_addr,
+                    )
+                    .await?;
+                }
+            };
+        } else {
+            return Err(TransportError::Protocol.into());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use crate::ALPN_PROTOCOL;
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+use ockam_transport_core::TransportError;
+use quinn::{ClientConfig, ServerConfig};
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::{Certificate, PrivateKey, ServerName};
+use std::time::SystemTime;
+use tracing::error;
+
+/// Configuration of a QUIC listener, using a freshly generated self-signed certificate
+pub(crate) fn server_config() -> Result<ServerConfig> {
+    let certificate =
+        rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).map_err(|e| {
+            error!(%e, "Failed to generate a certificate for a QUIC listener");
+            TransportError::BindFailed
+        })?;
+    let certificate_der = certificate
+        .serialize_der()
+        .map_err(|_| TransportError::BindFailed)?;
+    let private_key = PrivateKey(certificate.serialize_private_key_der());
+    let mut crypto = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(vec![Certificate(certificate_der)], private_key)
+        .map_err(|_| TransportError::BindFailed)?;
+    crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+    Ok(ServerConfig::with_crypto(Arc::new(crypto)))
+}
+
+/// Configuration of the QUIC connections initiated by a node.
+///
+/// The certificate of the listener is not verified, since the peers are authenticated by
+/// the secure channels established over the transport
+pub(crate) fn client_config() -> ClientConfig {
+    let mut crypto = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(AnyCertificate))
+        .with_no_client_auth();
+    crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+    ClientConfig::new(Arc::new(crypto))
+}
+
+/// Certificate verifier accepting any certificate
+struct AnyCertificate;
+
+impl ServerCertVerifier for AnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
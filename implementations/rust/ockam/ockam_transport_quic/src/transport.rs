@@ -0,0 +1,119 @@
+use crate::router::{QuicRouter, QuicRouterHandle};
+use ockam_core::flow_control::{FlowControlId, FlowControls};
+use ockam_core::{async_trait, Address, Result};
+use ockam_node::{Context, HasContext};
+use ockam_transport_core::TransportError;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+/// High level management interface for QUIC transport
+///
+/// A node will have, at most, one QUIC transport running.
+///
+/// The connections to the peers are established when a first message is sent to them,
+/// with routes like `route![(QUIC, "127.0.0.1:4000"), "echoer"]`.
+///
+/// The connections initiated by this node only support IPv4.
+pub struct QuicTransport {
+    router_handle: QuicRouterHandle,
+}
+
+impl QuicTransport {
+    /// Create a new QUIC transport for the current node
+    pub async fn create(ctx: &Context) -> Result<QuicTransport> {
+        let router_handle = QuicRouter::register(ctx).await?;
+        Ok(Self { router_handle })
+    }
+
+    /// Start accepting QUIC connections on a specified local address
+    pub async fn listen(&self, bind_addr: impl AsRef<str>) -> Result<QuicListener> {
+        let bind_addr = parse_socket_addr(bind_addr.as_ref())?;
+        self.router_handle.listen(bind_addr, None).await
+    }
+
+    /// Start accepting QUIC connections on a specified local address.
+    /// The messages received on these connections can only be sent to the consumers of
+    /// the listener [`FlowControlId`].
+    ///
+    /// Returns the listener, with the local address that it is bound to.
+    /// This can be useful, for example, when binding to port 0 to figure out
+    /// which port was actually bound.
+    pub async fn listen_with_options(
+        &self,
+        bind_addr: impl AsRef<str>,
+        options: QuicListenerOptions,
+    ) -> Result<QuicListener> {
+        let bind_addr = parse_socket_addr(bind_addr.as_ref())?;
+        self.router_handle
+            .listen(bind_addr, Some(options.flow_control_id))
+            .await
+    }
+
+    /// Stop accepting connections on the local address of a listener.
+    /// The connections which were already accepted are kept
+    pub async fn stop_listener(&self, listener: &QuicListener) -> Result<()> {
+        self.router_handle.stop_listener(listener.clone()).await
+    }
+}
+
+/// Trust Options for a QUIC listener
+#[derive(Debug)]
+pub struct QuicListenerOptions {
+    flow_control_id: FlowControlId,
+}
+
+impl QuicListenerOptions {
+    /// Mark this QUIC listener as a Producer with a random [`FlowControlId`]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            flow_control_id: FlowControls::generate_flow_control_id(),
+        }
+    }
+
+    /// Getter for freshly generated [`FlowControlId`]
+    pub fn flow_control_id(&self) -> FlowControlId {
+        self.flow_control_id.clone()
+    }
+}
+
+/// Result of [`QuicTransport::listen_with_options`] call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuicListener {
+    pub(crate) socket_address: SocketAddr,
+    pub(crate) processor_address: Address,
+    pub(crate) flow_control_id: Option<FlowControlId>,
+}
+
+impl QuicListener {
+    /// Local address the listener is bound to
+    pub fn socket_address(&self) -> SocketAddr {
+        self.socket_address
+    }
+
+    /// Address of the processor accepting the connections
+    pub fn processor_address(&self) -> &Address {
+        &self.processor_address
+    }
+
+    /// [`FlowControlId`] of the received messages, if the listener was created with options
+    pub fn flow_control_id(&self) -> Option<&FlowControlId> {
+        self.flow_control_id.as_ref()
+    }
+}
+
+fn parse_socket_addr(s: &str) -> Result<SocketAddr> {
+    s.parse().map_err(|_| TransportError::InvalidAddress.into())
+}
+
+/// This trait adds a `create_quic_transport` method to any struct returning a Context.
+/// This is the case for an ockam::Node, so you can write `node.create_quic_transport()`
+#[async_trait]
+pub trait QuicTransportExtension: HasContext {
+    /// Create a QUIC transport
+    async fn create_quic_transport(&self) -> Result<QuicTransport> {
+        QuicTransport::create(self.get_context()).await
+    }
+}
+
+impl<A: HasContext> QuicTransportExtension for A {}
@@ -0,0 +1,86 @@
+use super::{QuicRecvProcessor, QuicSendWorker};
+use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl};
+use ockam_core::{Address, AllowAll, OutgoingAccessControl, Result};
+use ockam_node::Context;
+use quinn::Connection;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+/// Senders of the open QUIC connections, by address of their peer.
+///
+/// The entries are shared by the router, which reuses the connections, and the receivers,
+/// which remove their connection when it is closed
+#[derive(Clone, Default)]
+pub(crate) struct Connections {
+    senders: Arc<Mutex<HashMap<SocketAddr, Address>>>,
+}
+
+impl Connections {
+    pub(crate) fn get(&self, peer: &SocketAddr) -> Option<Address> {
+        self.senders.lock().unwrap().get(peer).cloned()
+    }
+
+    fn insert(&self, peer: SocketAddr, sender: Address) {
+        self.senders.lock().unwrap().insert(peer, sender);
+    }
+
+    /// Remove the connection of a sender, unless it was replaced by a new connection
+    pub(crate) fn remove(&self, peer: &SocketAddr, sender: &Address) {
+        let mut senders = self.senders.lock().unwrap();
+        if senders.get(peer) == Some(sender) {
+            senders.remove(peer);
+        }
+    }
+}
+
+/// Start the sender and the receiver of an established connection, and return the address
+/// of the sender.
+///
+/// When a flow control id is given, the receiver is a producer for that flow control id,
+/// and the received messages can only be sent to its consumers.
+pub(crate) async fn start_connection(
+    ctx: &Context,
+    connection: Connection,
+    connections: Connections,
+    flow_control_id: Option<FlowControlId>,
+) -> Result<Address> {
+    let peer = connection.remote_address();
+    debug!(%peer, "Starting a QUIC connection");
+
+    let sender_addr = Address::random_tagged("QuicSendWorker");
+    let receiver_addr = Address::random_tagged("QuicRecvProcessor");
+
+    ctx.start_worker(sender_addr.clone(), QuicSendWorker::new(connection.clone()))
+        .await?;
+
+    let outgoing_access_control: Arc<dyn OutgoingAccessControl> = match &flow_control_id {
+        Some(flow_control_id) => {
+            ctx.flow_controls().add_producer(
+                receiver_addr.clone(),
+                flow_control_id,
+                None,
+                vec![sender_addr.clone()],
+            );
+            Arc::new(FlowControlOutgoingAccessControl::new(
+                ctx.flow_controls(),
+                flow_control_id.clone(),
+                None,
+            ))
+        }
+        None => Arc::new(AllowAll),
+    };
+    QuicRecvProcessor::start(
+        ctx,
+        receiver_addr,
+        connection,
+        connections.clone(),
+        sender_addr.clone(),
+        outgoing_access_control,
+    )
+    .await?;
+
+    connections.insert(peer, sender_addr.clone());
+    Ok(sender_addr)
+}
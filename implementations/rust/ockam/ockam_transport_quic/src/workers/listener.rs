@@ -0,0 +1,73 @@
+use super::{start_connection, Connections};
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{async_trait, Address, Processor, Result};
+use ockam_node::Context;
+use quinn::Endpoint;
+use tracing::{debug, warn};
+
+/// A listener for the QUIC transport
+///
+/// This processor accepts the connections on a local endpoint, and starts a
+/// sender and a receiver for each of them. See [`QuicRouter`](crate::router::QuicRouter)
+/// for more details.
+pub(crate) struct QuicListenProcessor {
+    endpoint: Endpoint,
+    connections: Connections,
+    flow_control_id: Option<FlowControlId>,
+}
+
+impl QuicListenProcessor {
+    pub(crate) async fn start(
+        ctx: &Context,
+        address: Address,
+        endpoint: Endpoint,
+        connections: Connections,
+        flow_control_id: Option<FlowControlId>,
+    ) -> Result<()> {
+        let processor = Self {
+            endpoint,
+            connections,
+            flow_control_id,
+        };
+        ctx.start_processor(address, processor).await
+    }
+}
+
+#[async_trait]
+impl Processor for QuicListenProcessor {
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await
+    }
+
+    async fn shutdown(&mut self, _ctx: &mut Context) -> Result<()> {
+        self.endpoint.close(0u32.into(), b"");
+        Ok(())
+    }
+
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        debug!("Waiting for incoming QUIC connection...");
+        let connecting = match self.endpoint.accept().await {
+            Some(connecting) => connecting,
+            None => {
+                debug!("The QUIC endpoint is closed");
+                return Ok(false);
+            }
+        };
+        let peer = connecting.remote_address();
+        match connecting.await {
+            Ok(connection) => {
+                start_connection(
+                    ctx,
+                    connection,
+                    self.connections.clone(),
+                    self.flow_control_id.clone(),
+                )
+                .await?;
+            }
+            Err(e) => warn!(%peer, %e, "Failed to accept a QUIC connection"),
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,12 @@
+pub(crate) use connections::*;
+pub(crate) use listener::*;
+pub(crate) use receiver::*;
+pub(crate) use sender::*;
+
+mod connections;
+mod listener;
+mod receiver;
+mod sender;
+
+/// Maximum length of an encoded routing message sent on a QUIC stream
+pub(crate) const MAX_MESSAGE_LENGTH: usize = 16 * 1024 * 1024;
@@ -0,0 +1,124 @@
+use super::{Connections, MAX_MESSAGE_LENGTH};
+use crate::QUIC;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{
+    async_trait, route, Address, AllowAll, Decodable, LocalMessage, OutgoingAccessControl,
+    Processor, Result, TransportMessage,
+};
+use ockam_node::Context;
+use ockam_transport_core::TransportError;
+use quinn::{Connection, RecvStream};
+use tracing::{debug, warn};
+
+/// A receiver for the QUIC transport
+///
+/// This processor receives the messages sent by a peer on a unidirectional stream of their
+/// connection. See [`QuicRouter`](crate::router::QuicRouter) for more details.
+///
+/// When a message is received, the address of the paired sender
+/// ([`QuicSendWorker`](crate::workers::QuicSendWorker)) is injected into the message's
+/// return route so that replies are sent on the same connection.
+///
+/// When the connection is closed, the receiver stops with its sender.
+pub(crate) struct QuicRecvProcessor {
+    connection: Connection,
+    stream: Option<RecvStream>,
+    connections: Connections,
+    /// Address of our sender counterpart
+    sender_addr: Address,
+}
+
+impl QuicRecvProcessor {
+    pub(crate) async fn start(
+        ctx: &Context,
+        address: Address,
+        connection: Connection,
+        connections: Connections,
+        sender_addr: Address,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<()> {
+        let processor = Self {
+            connection,
+            stream: None,
+            connections,
+            sender_addr,
+        };
+
+        ctx.start_processor_with_access_control(
+            address,
+            processor,
+            AllowAll,
+            outgoing_access_control,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Read the next message sent by the peer
+    async fn read_message(&mut self) -> Result<TransportMessage> {
+        if self.stream.is_none() {
+            let stream = self
+                .connection
+                .accept_uni()
+                .await
+                .map_err(|_| TransportError::ConnectionDrop)?;
+            self.stream = Some(stream);
+        }
+        let stream = self.stream.as_mut().ok_or(TransportError::ConnectionDrop)?;
+
+        let mut length = [0u8; 4];
+        stream
+            .read_exact(&mut length)
+            .await
+            .map_err(|_| TransportError::ConnectionDrop)?;
+        let length = u32::from_be_bytes(length) as usize;
+        if length > MAX_MESSAGE_LENGTH {
+            return Err(TransportError::AttackAttmept.into());
+        }
+        let mut encoded = vec![0u8; length];
+        stream
+            .read_exact(&mut encoded)
+            .await
+            .map_err(|_| TransportError::ConnectionDrop)?;
+        TransportMessage::decode(&encoded).map_err(|_| TransportError::RecvBadMessage.into())
+    }
+}
+
+#[async_trait]
+impl Processor for QuicRecvProcessor {
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await
+    }
+
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let peer = self.connection.remote_address();
+        let mut msg = match self.read_message().await {
+            Ok(msg) => msg,
+            Err(e) => {
+                debug!(%peer, %e, "The QUIC connection is closed");
+                self.connections.remove(&peer, &self.sender_addr);
+                if let Err(e) = ctx.stop_worker(self.sender_addr.clone()).await {
+                    warn!(%peer, %e, "Failed to stop the sender of a QUIC connection");
+                }
+                return Ok(false);
+            }
+        };
+
+        // Set return route to go directly to paired sender, skipping the QUIC router.
+        // The address of the peer can change during the connection (path migration)
+        msg.return_route = route![
+            self.sender_addr.clone(),
+            Address::new(QUIC, peer.to_string()),
+            msg.return_route
+        ];
+
+        debug!(onward_route = %msg.onward_route,
+            return_route = %msg.return_route,
+            "Forwarding QUIC message");
+        ctx.forward(LocalMessage::new(msg, vec![])).await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,83 @@
+use super::MAX_MESSAGE_LENGTH;
+use crate::QUIC;
+use ockam_core::{async_trait, Any, Encodable, Result, Routed, Worker};
+use ockam_node::Context;
+use ockam_transport_core::TransportError;
+use quinn::{Connection, SendStream};
+use tracing::{error, trace};
+
+/// A sender for the QUIC transport
+///
+/// This worker sends the messages routed to a peer on a unidirectional stream of the
+/// connection to that peer, so that they are received in order. The stream is opened when the
+/// first message is sent. See [`QuicRouter`](crate::router::QuicRouter) for more details.
+pub(crate) struct QuicSendWorker {
+    connection: Connection,
+    stream: Option<SendStream>,
+}
+
+impl QuicSendWorker {
+    /// Create a new `QuicSendWorker`
+    pub(crate) fn new(connection: Connection) -> Self {
+        Self {
+            connection,
+            stream: None,
+        }
+    }
+
+    /// Return the stream of the messages, opening it if necessary
+    async fn stream(&mut self) -> Result<&mut SendStream> {
+        if self.stream.is_none() {
+            let stream = self.connection.open_uni().await.map_err(|e| {
+                error!(%e, "Failed to open a QUIC stream");
+                TransportError::ConnectionDrop
+            })?;
+            self.stream = Some(stream);
+        }
+        self.stream
+            .as_mut()
+            .ok_or_else(|| TransportError::ConnectionDrop.into())
+    }
+}
+
+#[async_trait]
+impl Worker for QuicSendWorker {
+    type Message = Any;
+    type Context = Context;
+
+    async fn shutdown(&mut self, _ctx: &mut Context) -> Result<()> {
+        self.connection.close(0u32.into(), b"");
+        Ok(())
+    }
+
+    async fn handle_message(
+        &mut self,
+        _ctx: &mut Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        // Parse message and remove our address and the peer address from its routing
+        let mut msg = msg.into_transport_message();
+        msg.onward_route.step()?;
+        let peer_addr = msg.onward_route.step()?;
+        if peer_addr.transport_type() != QUIC {
+            error!(addr = %peer_addr, "Destination address is not QUIC");
+            return Err(TransportError::UnknownRoute.into());
+        }
+
+        trace!("Sending message to {:?}", msg.onward_route);
+        let encoded = msg.encode().map_err(|_| TransportError::SendBadMessage)?;
+        if encoded.len() > MAX_MESSAGE_LENGTH {
+            return Err(TransportError::Capacity.into());
+        }
+
+        // Each message is prefixed by its length
+        let stream = self.stream().await?;
+        let mut frame = (encoded.len() as u32).to_be_bytes().to_vec();
+        frame.extend(encoded);
+        stream.write_all(&frame).await.map_err(|e| {
+            error!(%peer_addr, %e, "Failed to send a message");
+            TransportError::ConnectionDrop
+        })?;
+        Ok(())
+    }
+}
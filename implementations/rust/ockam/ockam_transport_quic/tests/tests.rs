@@ -0,0 +1,46 @@
+use ockam_core::{route, Address, AllowAll, Result, Routed, Worker};
+use ockam_node::{Context, MessageReceiveOptions};
+use ockam_transport_quic::{QuicTransport, QUIC};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The messages sent to a listener are received in order, and the replies
+/// are sent back on the same connection
+#[ockam_macros::test]
+async fn send_receive_in_order(ctx: &mut Context) -> Result<()> {
+    let transport = QuicTransport::create(ctx).await?;
+    ctx.start_worker("echoer", Echoer).await?;
+    let listener = transport.listen("127.0.0.1:0").await?;
+
+    let mut child_ctx = ctx
+        .new_detached(Address::random_tagged("App.detached"), AllowAll, AllowAll)
+        .await?;
+    let route = route![(QUIC, listener.socket_address().to_string()), "echoer"];
+    for i in 0..10 {
+        child_ctx
+            .send(route.clone(), format!("message {i}"))
+            .await?;
+    }
+    for i in 0..10 {
+        let reply = child_ctx
+            .receive_extended::<String>(MessageReceiveOptions::new().with_timeout(TIMEOUT))
+            .await?;
+        assert_eq!(reply.body(), format!("message {i}"));
+    }
+
+    transport.stop_listener(&listener).await?;
+    ctx.stop().await
+}
+
+struct Echoer;
+
+#[ockam_core::worker]
+impl Worker for Echoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
@@ -0,0 +1,204 @@
+//! Framing of the routing messages sent over a serial line.
+//!
+//! A serial line is a stream of bytes without any boundaries, where bytes can be lost or
+//! corrupted. Each encoded routing message is sent as a frame:
+//!
+//!  - the message is followed by the CRC-32 (IEEE 802.3) of the message, in little-endian,
+//!  - the result is encoded with COBS (Consistent Overhead Byte Stuffing), which removes all
+//!    the zero bytes,
+//!  - a zero byte terminates the frame.
+//!
+//! A receiver can then always find the start of the next frame after a corrupted one, and the
+//! frames with an invalid checksum are dropped. Consecutive zero bytes are ignored, so a device
+//! can send a zero byte at any time to terminate a partially sent frame.
+
+use ockam_core::compat::vec::Vec;
+
+/// Byte terminating each frame
+pub const FRAME_DELIMITER: u8 = 0;
+
+const CHECKSUM_LENGTH: usize = 4;
+
+/// Error of a received frame, which is dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    /// The frame is not valid COBS
+    Encoding,
+    /// The checksum of the frame doesn't match its content
+    Checksum,
+    /// The frame is longer than the maximum length of a message
+    TooLong,
+}
+
+/// Return the frame carrying a message, including its delimiter
+pub fn encode_frame(message: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(message.len() + CHECKSUM_LENGTH);
+    data.extend_from_slice(message);
+    data.extend_from_slice(&crc32(message).to_le_bytes());
+
+    let mut frame = Vec::with_capacity(data.len() + data.len() / 254 + 2);
+    cobs_encode(&data, &mut frame);
+    frame.push(FRAME_DELIMITER);
+    frame
+}
+
+/// Split a stream of received bytes into messages
+#[derive(Debug)]
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+    max_encoded_length: usize,
+    /// True when the bytes of the current frame are dropped because it is too long
+    discarding: bool,
+}
+
+impl FrameDecoder {
+    /// Create a decoder accepting messages up to `max_message_length` bytes
+    pub fn new(max_message_length: usize) -> Self {
+        let data_length = max_message_length + CHECKSUM_LENGTH;
+        Self {
+            buffer: Vec::new(),
+            max_encoded_length: data_length + data_length / 254 + 2,
+            discarding: false,
+        }
+    }
+
+    /// Add received bytes, and return the messages of the frames they complete
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Result<Vec<u8>, FrameError>> {
+        let mut messages = Vec::new();
+        for &byte in bytes {
+            if byte == FRAME_DELIMITER {
+                if self.discarding {
+                    messages.push(Err(FrameError::TooLong));
+                } else if !self.buffer.is_empty() {
+                    messages.push(decode_frame(&self.buffer));
+                }
+                self.buffer.clear();
+                self.discarding = false;
+            } else if self.discarding {
+                continue;
+            } else if self.buffer.len() >= self.max_encoded_length {
+                self.buffer.clear();
+                self.discarding = true;
+            } else {
+                self.buffer.push(byte);
+            }
+        }
+        messages
+    }
+}
+
+/// Decode a frame, without its delimiter, and verify its checksum
+fn decode_frame(frame: &[u8]) -> Result<Vec<u8>, FrameError> {
+    let mut data = cobs_decode(frame).ok_or(FrameError::Encoding)?;
+    if data.len() < CHECKSUM_LENGTH {
+        return Err(FrameError::Encoding);
+    }
+    let checksum = data.split_off(data.len() - CHECKSUM_LENGTH);
+    if checksum != crc32(&data).to_le_bytes() {
+        return Err(FrameError::Checksum);
+    }
+    Ok(data)
+}
+
+fn cobs_encode(data: &[u8], output: &mut Vec<u8>) {
+    let mut code_index = output.len();
+    let mut code = 1u8;
+    output.push(0);
+    for &byte in data {
+        if byte != 0 {
+            output.push(byte);
+            code += 1;
+        }
+        if byte == 0 || code == 0xFF {
+            output[code_index] = code;
+            code_index = output.len();
+            code = 1;
+            output.push(0);
+        }
+    }
+    output[code_index] = code;
+}
+
+fn cobs_decode(data: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(data.len());
+    let mut index = 0;
+    while index < data.len() {
+        let code = data[index] as usize;
+        if code == 0 || index + code > data.len() {
+            return None;
+        }
+        output.extend_from_slice(&data[index + 1..index + code]);
+        index += code;
+        if code < 0xFF && index < data.len() {
+            output.push(0);
+        }
+    }
+    Some(output)
+}
+
+/// CRC-32 (IEEE 802.3) of some data
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let long: Vec<u8> = (0..1000).map(|i| (i % 256) as u8).collect();
+        let messages: Vec<&[u8]> = vec![b"", &[0], &[0, 0, 1], &[0xFF; 254], &[0xFF; 600], &long];
+
+        let mut stream = Vec::new();
+        for message in &messages {
+            let frame = encode_frame(message);
+            assert_eq!(frame.iter().filter(|b| **b == FRAME_DELIMITER).count(), 1);
+            stream.extend(frame);
+        }
+
+        // The bytes are received in arbitrary chunks
+        let mut decoder = FrameDecoder::new(1000);
+        let mut received = Vec::new();
+        for chunk in stream.chunks(7) {
+            received.extend(decoder.push(chunk));
+        }
+        let expected: Vec<Result<Vec<u8>, FrameError>> =
+            messages.iter().map(|m| Ok(m.to_vec())).collect();
+        assert_eq!(received, expected);
+    }
+
+    #[test]
+    fn invalid_frames_are_dropped() {
+        let mut decoder = FrameDecoder::new(16);
+
+        let mut corrupted = encode_frame(b"hello");
+        corrupted[2] ^= 0x01;
+        assert_eq!(decoder.push(&corrupted), vec![Err(FrameError::Checksum)]);
+
+        assert_eq!(
+            decoder.push(&encode_frame(&[1; 32])),
+            vec![Err(FrameError::TooLong)]
+        );
+
+        // The decoder resynchronizes on the next delimiter
+        let mut bytes = vec![0x42, 0x42];
+        bytes.push(FRAME_DELIMITER);
+        bytes.extend(encode_frame(b"hello"));
+        let received = decoder.push(&bytes);
+        assert_eq!(received.last(), Some(&Ok(b"hello".to_vec())));
+    }
+}
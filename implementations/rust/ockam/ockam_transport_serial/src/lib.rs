@@ -0,0 +1,18 @@
+//! This crate provides a Serial port (UART) Transport for Ockam's Routing Protocol.
+//!
+//! It allows a gateway to route messages to a microcontroller connected to one of its serial
+//! ports. Each port carries the routing messages of a single peer, in frames delimited with
+//! COBS and verified with a CRC-32 checksum. See the [`framing`] module for more details.
+//!
+//! A serial line is not encrypted: the peers must be authenticated with an Ockam secure channel
+//! established over the transport.
+pub use transport::{SerialOptions, SerialPort, SerialTransport, SerialTransportExtension};
+
+pub mod framing;
+mod transport;
+mod workers;
+
+pub const CLUSTER_NAME: &str = "_internals.transport.serial";
+
+/// Baud rate used when the options of a port don't specify one
+pub const DEFAULT_BAUD_RATE: u32 = 115_200;
@@ -0,0 +1,190 @@
+use crate::workers::{SerialRecvProcessor, SerialSendWorker};
+use crate::DEFAULT_BAUD_RATE;
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
+use ockam_core::{async_trait, Address, AsyncTryClone, Result};
+use ockam_node::{Context, HasContext};
+use ockam_transport_core::TransportError;
+use tokio_serial::SerialPortBuilderExt;
+use tracing::{debug, error};
+
+/// High level management interface for the Serial transport
+///
+/// Each opened port is connected to a single peer, for example a microcontroller
+/// attached to a UART of the gateway. Messages are sent to that peer with routes
+/// starting with the sender address of the port, like
+/// `route![port.sender_address().clone(), "echoer"]`.
+///
+/// ```rust,no_run
+/// use ockam_transport_serial::{SerialOptions, SerialTransport};
+/// # use ockam_core::{route, Result};
+/// # use ockam_node::Context;
+/// # async fn test(ctx: Context) -> Result<()> {
+/// let serial = SerialTransport::create(&ctx).await?;
+/// let port = serial
+///     .open("/dev/ttyUSB0", SerialOptions::new().with_baud_rate(57_600))
+///     .await?;
+/// ctx.send(route![port.sender_address().clone(), "echoer"], "Hello".to_string())
+///     .await?;
+/// # Ok(()) }
+/// ```
+pub struct SerialTransport {
+    ctx: Context,
+}
+
+impl SerialTransport {
+    /// Create a Serial transport
+    pub async fn create(ctx: &Context) -> Result<Self> {
+        Ok(Self {
+            ctx: ctx.async_try_clone().await?,
+        })
+    }
+
+    /// Open a serial port, like `/dev/ttyUSB0` or `COM3`, and start the workers
+    /// sending and receiving the messages of its peer
+    pub async fn open(
+        &self,
+        path: impl Into<String>,
+        options: SerialOptions,
+    ) -> Result<SerialPort> {
+        let path = path.into();
+        let stream = tokio_serial::new(&path, options.baud_rate)
+            .open_native_async()
+            .map_err(|e| {
+                error!(%path, %e, "Failed to open a serial port");
+                TransportError::GenericIo
+            })?;
+        let (read_half, write_half) = tokio::io::split(stream);
+
+        let port = SerialPort {
+            path,
+            sender_address: Address::random_tagged("SerialSendWorker"),
+            receiver_address: Address::random_tagged("SerialRecvProcessor"),
+            flow_control_id: options.flow_control_id.clone(),
+        };
+        options.setup_flow_control(self.ctx.flow_controls(), &port);
+        let receiver_outgoing_access_control = Arc::new(FlowControlOutgoingAccessControl::new(
+            self.ctx.flow_controls(),
+            options.flow_control_id,
+            None,
+        ));
+
+        SerialSendWorker::start(
+            &self.ctx,
+            port.sender_address.clone(),
+            port.receiver_address.clone(),
+            write_half,
+        )
+        .await?;
+        SerialRecvProcessor::start(
+            &self.ctx,
+            port.receiver_address.clone(),
+            port.sender_address.clone(),
+            read_half,
+            receiver_outgoing_access_control,
+        )
+        .await?;
+
+        debug!(path = %port.path, baud_rate = options.baud_rate, "Opened a serial port");
+        Ok(port)
+    }
+
+    /// Stop the workers of a port, and close it
+    pub async fn close(&self, port: &SerialPort) -> Result<()> {
+        self.ctx.stop_worker(port.sender_address.clone()).await
+    }
+}
+
+/// Trust Options for a serial port
+#[derive(Debug)]
+pub struct SerialOptions {
+    baud_rate: u32,
+    consumer: Vec<FlowControlId>,
+    flow_control_id: FlowControlId,
+}
+
+impl SerialOptions {
+    /// Mark the receiver of this port as a Producer with a random [`FlowControlId`],
+    /// using the [`DEFAULT_BAUD_RATE`]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            baud_rate: DEFAULT_BAUD_RATE,
+            consumer: vec![],
+            flow_control_id: FlowControls::generate_flow_control_id(),
+        }
+    }
+
+    /// Set the baud rate of the port
+    pub fn with_baud_rate(mut self, baud_rate: u32) -> Self {
+        self.baud_rate = baud_rate;
+        self
+    }
+
+    /// Mark that this port is a Consumer for the given [`FlowControlId`]
+    pub fn as_consumer(mut self, id: &FlowControlId) -> Self {
+        self.consumer.push(id.clone());
+        self
+    }
+
+    /// Getter for freshly generated [`FlowControlId`]
+    pub fn flow_control_id(&self) -> FlowControlId {
+        self.flow_control_id.clone()
+    }
+
+    fn setup_flow_control(&self, flow_controls: &FlowControls, port: &SerialPort) {
+        flow_controls.add_producer(
+            port.receiver_address.clone(),
+            &self.flow_control_id,
+            None,
+            vec![port.sender_address.clone()],
+        );
+
+        for id in &self.consumer {
+            flow_controls.add_consumer(port.sender_address.clone(), id);
+        }
+    }
+}
+
+/// Result of [`SerialTransport::open`] call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerialPort {
+    path: String,
+    sender_address: Address,
+    receiver_address: Address,
+    flow_control_id: FlowControlId,
+}
+
+impl SerialPort {
+    /// Path of the device
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Address of the worker sending the messages to the peer
+    pub fn sender_address(&self) -> &Address {
+        &self.sender_address
+    }
+
+    /// Address of the processor receiving the messages of the peer
+    pub fn receiver_address(&self) -> &Address {
+        &self.receiver_address
+    }
+
+    /// [`FlowControlId`] of the received messages
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+}
+
+/// This trait adds a `create_serial_transport` method to any struct returning a Context.
+/// This is the case for an ockam::Node, so you can write `node.create_serial_transport()`
+#[async_trait]
+pub trait SerialTransportExtension: HasContext {
+    /// Create a Serial transport
+    async fn create_serial_transport(&self) -> Result<SerialTransport> {
+        SerialTransport::create(self.get_context()).await
+    }
+}
+
+impl<A: HasContext> SerialTransportExtension for A {}
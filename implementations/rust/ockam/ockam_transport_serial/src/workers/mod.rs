@@ -0,0 +1,8 @@
+pub(crate) use receiver::*;
+pub(crate) use sender::*;
+
+mod receiver;
+mod sender;
+
+/// Maximum length of an encoded routing message sent on a serial port
+pub(crate) const MAX_MESSAGE_LENGTH: usize = 64 * 1024;
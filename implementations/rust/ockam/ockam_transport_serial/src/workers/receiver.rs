@@ -0,0 +1,124 @@
+use super::MAX_MESSAGE_LENGTH;
+use crate::framing::FrameDecoder;
+use ockam_core::compat::collections::VecDeque;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{
+    async_trait, route, Address, AllowAll, Decodable, LocalMessage, OutgoingAccessControl,
+    Processor, Result, TransportMessage,
+};
+use ockam_node::Context;
+use ockam_transport_core::TransportError;
+use tokio::io::{AsyncReadExt, ReadHalf};
+use tokio_serial::SerialStream;
+use tracing::{debug, warn};
+
+/// Size of the buffer receiving the bytes of a serial port
+const READ_BUFFER_LENGTH: usize = 1024;
+
+/// A receiver for the Serial transport
+///
+/// This processor splits the bytes received on a serial port into frames, and forwards the
+/// messages they carry. The frames which are corrupted are dropped.
+///
+/// When a message is received, the address of the paired sender
+/// ([`SerialSendWorker`](crate::workers::SerialSendWorker)) is injected into the message's
+/// return route so that replies are sent on the same port.
+///
+/// When the port is closed, the receiver stops with its sender.
+pub(crate) struct SerialRecvProcessor {
+    read_half: ReadHalf<SerialStream>,
+    decoder: FrameDecoder,
+    /// Messages received but not forwarded yet
+    received: VecDeque<Vec<u8>>,
+    /// Address of our sender counterpart
+    sender_addr: Address,
+}
+
+impl SerialRecvProcessor {
+    pub(crate) async fn start(
+        ctx: &Context,
+        address: Address,
+        sender_addr: Address,
+        read_half: ReadHalf<SerialStream>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<()> {
+        let processor = Self {
+            read_half,
+            decoder: FrameDecoder::new(MAX_MESSAGE_LENGTH),
+            received: VecDeque::new(),
+            sender_addr,
+        };
+
+        ctx.start_processor_with_access_control(
+            address,
+            processor,
+            AllowAll,
+            outgoing_access_control,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Read the next message sent by the peer
+    async fn read_message(&mut self) -> Result<Vec<u8>> {
+        let mut buffer = [0u8; READ_BUFFER_LENGTH];
+        loop {
+            if let Some(message) = self.received.pop_front() {
+                return Ok(message);
+            }
+            let length = self
+                .read_half
+                .read(&mut buffer)
+                .await
+                .map_err(|_| TransportError::ConnectionDrop)?;
+            if length == 0 {
+                return Err(TransportError::ConnectionDrop.into());
+            }
+            for frame in self.decoder.push(&buffer[..length]) {
+                match frame {
+                    Ok(message) => self.received.push_back(message),
+                    Err(e) => warn!(?e, "Dropped an invalid frame received on a serial port"),
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Processor for SerialRecvProcessor {
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await
+    }
+
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let encoded = match self.read_message().await {
+            Ok(encoded) => encoded,
+            Err(e) => {
+                debug!(%e, "The serial port is closed");
+                if let Err(e) = ctx.stop_worker(self.sender_addr.clone()).await {
+                    warn!(%e, "Failed to stop the sender of a serial port");
+                }
+                return Ok(false);
+            }
+        };
+        let mut msg = match TransportMessage::decode(&encoded) {
+            Ok(msg) => msg,
+            Err(e) => {
+                warn!(%e, "Dropped an invalid message received on a serial port");
+                return Ok(true);
+            }
+        };
+
+        // Set return route to go directly to paired sender
+        msg.return_route = route![self.sender_addr.clone(), msg.return_route];
+
+        debug!(onward_route = %msg.onward_route,
+            return_route = %msg.return_route,
+            "Forwarding serial message");
+        ctx.forward(LocalMessage::new(msg, vec![])).await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,81 @@
+use super::MAX_MESSAGE_LENGTH;
+use crate::framing::encode_frame;
+use ockam_core::{async_trait, Address, AllowAll, Any, Encodable, Result, Routed, Worker};
+use ockam_node::Context;
+use ockam_transport_core::TransportError;
+use tokio::io::{AsyncWriteExt, WriteHalf};
+use tokio_serial::SerialStream;
+use tracing::{error, trace, warn};
+
+/// A sender for the Serial transport
+///
+/// This worker sends the messages routed to the peer of a serial port, each one in its own
+/// frame. See [`framing`](crate::framing) for more details.
+///
+/// When the sender is stopped, it also stops its paired receiver.
+pub(crate) struct SerialSendWorker {
+    write_half: WriteHalf<SerialStream>,
+    /// Address of our receiver counterpart
+    receiver_addr: Address,
+}
+
+impl SerialSendWorker {
+    pub(crate) async fn start(
+        ctx: &Context,
+        address: Address,
+        receiver_addr: Address,
+        write_half: WriteHalf<SerialStream>,
+    ) -> Result<()> {
+        let worker = Self {
+            write_half,
+            receiver_addr,
+        };
+        ctx.start_worker_with_access_control(address, worker, AllowAll, AllowAll)
+            .await
+    }
+
+    async fn write_frame(&mut self, frame: &[u8]) -> std::io::Result<()> {
+        self.write_half.write_all(frame).await?;
+        self.write_half.flush().await
+    }
+}
+
+#[async_trait]
+impl Worker for SerialSendWorker {
+    type Message = Any;
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Context) -> Result<()> {
+        if let Err(e) = ctx.stop_processor(self.receiver_addr.clone()).await {
+            warn!(%e, "Failed to stop the receiver of a serial port");
+        }
+        Ok(())
+    }
+
+    async fn handle_message(
+        &mut self,
+        _ctx: &mut Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        // Remove our address from the routing of the message
+        let mut msg = msg.into_transport_message();
+        msg.onward_route.step()?;
+
+        trace!("Sending message to {:?}", msg.onward_route);
+        let encoded = msg.encode().map_err(|_| TransportError::SendBadMessage)?;
+        if encoded.len() > MAX_MESSAGE_LENGTH {
+            return Err(TransportError::Capacity.into());
+        }
+
+        let frame = encode_frame(&encoded);
+        if let Err(e) = self.write_frame(&frame).await {
+            error!(%e, "Failed to send a message on a serial port");
+            return Err(TransportError::ConnectionDrop.into());
+        }
+        Ok(())
+    }
+}
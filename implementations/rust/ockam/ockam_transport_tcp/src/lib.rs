@@ -25,7 +25,11 @@ mod transport;
 
 use ockam_core::TransportType;
 pub use options::{TcpConnectionOptions, TcpListenerOptions};
-pub use portal::{PortalInternalMessage, PortalMessage, MAX_PAYLOAD_SIZE};
+pub use portal::{
+    CanaryPolicy, CanaryStatus, CanaryStickiness, CanarySummary, InletCanary, OutletTls,
+    PortalDataFlow, PortalInternalMessage, PortalMessage, PortalProtocol, PortalStatistics,
+    MAX_PAYLOAD_SIZE,
+};
 pub use registry::*;
 pub use transport::common::*;
 pub use transport::*;
@@ -1,4 +1,5 @@
 use crate::workers::Addresses;
+use crate::{TcpConnectionTls, TcpListenerTls};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
 use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl};
@@ -13,6 +14,7 @@ pub(crate) struct TcpConnectionAccessControl {
 pub struct TcpConnectionOptions {
     pub(super) consumer: Vec<FlowControlId>,
     pub(crate) flow_control_id: FlowControlId,
+    pub(crate) tls: Option<TcpConnectionTls>,
 }
 
 impl TcpConnectionOptions {
@@ -22,6 +24,7 @@ impl TcpConnectionOptions {
         Self {
             consumer: vec![],
             flow_control_id: FlowControls::generate_flow_control_id(),
+            tls: None,
         }
     }
 
@@ -36,6 +39,12 @@ impl TcpConnectionOptions {
     pub fn flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
     }
+
+    /// Wrap the connection in TLS
+    pub fn with_tls(mut self, tls: TcpConnectionTls) -> Self {
+        self.tls = Some(tls);
+        self
+    }
 }
 
 impl TcpConnectionOptions {
@@ -71,6 +80,7 @@ impl TcpConnectionOptions {
 #[derive(Debug)]
 pub struct TcpListenerOptions {
     pub(crate) flow_control_id: FlowControlId,
+    pub(crate) tls: Option<TcpListenerTls>,
 }
 
 impl TcpListenerOptions {
@@ -81,6 +91,7 @@ impl TcpListenerOptions {
     pub fn new() -> Self {
         Self {
             flow_control_id: FlowControls::generate_flow_control_id(),
+            tls: None,
         }
     }
 
@@ -88,6 +99,12 @@ impl TcpListenerOptions {
     pub fn spawner_flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
     }
+
+    /// Wrap the accepted connections in TLS
+    pub fn with_tls(mut self, tls: TcpListenerTls) -> Self {
+        self.tls = Some(tls);
+        self
+    }
 }
 
 impl TcpListenerOptions {
@@ -0,0 +1,410 @@
+use crate::PortalStatistics;
+use core::fmt;
+use core::fmt::{Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result, Route};
+use std::collections::hash_map::DefaultHasher;
+
+/// Minimum number of canary connections before the error rate of a canary is evaluated
+const MIN_EVALUATED_CONNECTIONS: u64 = 10;
+
+/// Canary route of an Inlet.
+///
+/// A fraction of the new connections of the Inlet are sent to the canary route, while the other
+/// connections keep using the Inlet route. The canary connections are counted with their own
+/// [`PortalStatistics`], a connection being counted as failed when it ends before any data was
+/// received from the Outlet.
+///
+/// The share of the new connections sent to the canary route can be changed while the canary is
+/// running, with weights given to both routes, to shift the traffic gradually.
+///
+/// The canary is either promoted, all the new connections then using its route, or rolled back.
+/// This is decided explicitly or, according to its [`CanaryPolicy`], from its error rate
+#[derive(Debug, Clone, Default)]
+pub struct InletCanary {
+    inner: Arc<RwLock<Option<CanaryState>>>,
+}
+
+/// Conditions for an automatic decision on a canary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanaryPolicy {
+    /// Percentage of failed connections above which the canary is rolled back
+    pub error_threshold: u8,
+    /// Number of canary connections after which the canary is promoted if its error rate is
+    /// below the threshold. If missing, the canary can only be promoted explicitly
+    pub promote_after: Option<u64>,
+}
+
+/// How the connections of a client are spread between the Inlet route and the canary route
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CanaryStickiness {
+    /// Each new connection is sent to one of the routes, independently of its client
+    #[default]
+    None,
+    /// All the connections coming from the same IP address are sent to the same route,
+    /// for the stateful servers which expect the requests of a session on the same server
+    SourceIp,
+}
+
+impl Display for CanaryStickiness {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CanaryStickiness::None => write!(f, "none"),
+            CanaryStickiness::SourceIp => write!(f, "source-ip"),
+        }
+    }
+}
+
+impl FromStr for CanaryStickiness {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(CanaryStickiness::None),
+            "source-ip" => Ok(CanaryStickiness::SourceIp),
+            _ => Err("the stickiness must be one of: none, source-ip".to_string()),
+        }
+    }
+}
+
+/// Status of a canary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CanaryStatus {
+    /// A fraction of the new connections are sent to the canary route
+    Running,
+    /// All the new connections are sent to the canary route
+    Promoted,
+    /// No more connections are sent to the canary route
+    RolledBack,
+}
+
+impl Display for CanaryStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CanaryStatus::Running => write!(f, "running"),
+            CanaryStatus::Promoted => write!(f, "promoted"),
+            CanaryStatus::RolledBack => write!(f, "rolled back"),
+        }
+    }
+}
+
+/// Current state of a canary
+#[derive(Debug, Clone)]
+pub struct CanarySummary {
+    /// Canary route
+    pub route: Route,
+    /// Percentage of the new connections sent to the canary route while it is running
+    pub percentage: u8,
+    /// How the connections of a client are spread between the routes while the canary is running
+    pub stickiness: CanaryStickiness,
+    /// Canary status
+    pub status: CanaryStatus,
+    /// Number of connections sent to the canary route
+    pub connections: u64,
+    /// Number of these connections which failed
+    pub failures: u64,
+}
+
+#[derive(Debug, Clone)]
+struct CanaryState {
+    route: Route,
+    percentage: u8,
+    policy: CanaryPolicy,
+    stickiness: CanaryStickiness,
+    statistics: PortalStatistics,
+    status: CanaryStatus,
+    /// Number of new connections of the Inlet since the canary was started
+    selections: u64,
+}
+
+/// Route selected by a canary for a new connection
+pub(super) enum CanarySelection {
+    /// The connection is sent to the canary route
+    Canary(Route, PortalStatistics),
+    /// The canary has been promoted, its route replaces the Inlet route
+    Promoted(Route),
+}
+
+impl InletCanary {
+    /// Start sending a percentage of the new connections to a canary route.
+    /// A previous canary is replaced
+    pub fn start(
+        &self,
+        route: Route,
+        percentage: u8,
+        policy: CanaryPolicy,
+        stickiness: CanaryStickiness,
+    ) -> Result<()> {
+        if percentage == 0 || percentage > 100 {
+            return Err(Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "the canary percentage must be between 1 and 100",
+            ));
+        }
+        *self.inner.write().unwrap() = Some(CanaryState {
+            route,
+            percentage,
+            policy,
+            stickiness,
+            statistics: PortalStatistics::default(),
+            status: CanaryStatus::Running,
+            selections: 0,
+        });
+        Ok(())
+    }
+
+    /// Change the share of the new connections sent to the route of a running canary, from
+    /// the relative weights of the Inlet route and of the canary route. The statistics of the
+    /// canary are kept.
+    ///
+    /// With a [`CanaryStickiness::SourceIp`] stickiness, increasing the weight of the canary
+    /// route only moves clients from the Inlet route to the canary route, and conversely
+    pub fn set_weights(&self, route_weight: u32, canary_weight: u32) -> Result<CanarySummary> {
+        let total = route_weight as u64 + canary_weight as u64;
+        if total == 0 {
+            return Err(Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "at least one of the weights must be positive",
+            ));
+        }
+        let mut inner = self.inner.write().unwrap();
+        match inner.as_mut() {
+            Some(state) if state.status == CanaryStatus::Running => {
+                // rounded to the nearest percentage
+                state.percentage = ((canary_weight as u64 * 200 + total) / (total * 2)) as u8;
+                Ok(state.summary())
+            }
+            Some(state) => Err(Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                format!("the canary has already been {}", state.status),
+            )),
+            None => Err(no_canary()),
+        }
+    }
+
+    /// Send all the new connections to the canary route
+    pub fn promote(&self) -> Result<CanarySummary> {
+        self.decide(CanaryStatus::Promoted)
+    }
+
+    /// Stop sending new connections to the canary route
+    pub fn rollback(&self) -> Result<CanarySummary> {
+        self.decide(CanaryStatus::RolledBack)
+    }
+
+    /// Remove the canary, the new connections then use the Inlet route
+    pub fn clear(&self) {
+        *self.inner.write().unwrap() = None;
+    }
+
+    /// Return the current state of the canary, if one was started
+    pub fn summary(&self) -> Option<CanarySummary> {
+        self.inner
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(CanaryState::summary)
+    }
+
+    fn decide(&self, status: CanaryStatus) -> Result<CanarySummary> {
+        let mut inner = self.inner.write().unwrap();
+        match inner.as_mut() {
+            Some(state) if state.status == CanaryStatus::Running => {
+                state.status = status;
+                Ok(state.summary())
+            }
+            Some(state) => Err(Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                format!("the canary has already been {}", state.status),
+            )),
+            None => Err(no_canary()),
+        }
+    }
+
+    /// Select the route of a new connection from a client.
+    /// Return None if the Inlet route must be used
+    pub(super) fn select(&self, peer: &SocketAddr) -> Option<CanarySelection> {
+        let mut inner = self.inner.write().unwrap();
+        let state = inner.as_mut()?;
+        state.evaluate();
+        match state.status {
+            CanaryStatus::Running => {
+                let percentage = state.percentage as u64;
+                let is_canary = match state.stickiness {
+                    // The canary connections are evenly spread amongst the new connections
+                    CanaryStickiness::None => {
+                        let n = state.selections;
+                        state.selections += 1;
+                        (n + 1) * percentage / 100 > n * percentage / 100
+                    }
+                    // The clients are spread amongst the routes
+                    CanaryStickiness::SourceIp => {
+                        let mut hasher = DefaultHasher::new();
+                        peer.ip().hash(&mut hasher);
+                        hasher.finish() % 100 < percentage
+                    }
+                };
+                if is_canary {
+                    Some(CanarySelection::Canary(
+                        state.route.clone(),
+                        state.statistics.clone(),
+                    ))
+                } else {
+                    None
+                }
+            }
+            CanaryStatus::Promoted => Some(CanarySelection::Promoted(state.route.clone())),
+            CanaryStatus::RolledBack => None,
+        }
+    }
+}
+
+fn no_canary() -> Error {
+    Error::new(
+        Origin::Transport,
+        Kind::NotFound,
+        "there is no canary for this inlet",
+    )
+}
+
+impl CanaryState {
+    fn summary(&self) -> CanarySummary {
+        CanarySummary {
+            route: self.route.clone(),
+            percentage: self.percentage,
+            stickiness: self.stickiness,
+            status: self.status,
+            connections: self.statistics.sessions(),
+            failures: self.statistics.failures(),
+        }
+    }
+
+    /// Promote or roll back the canary according to its policy
+    fn evaluate(&mut self) {
+        if self.status != CanaryStatus::Running {
+            return;
+        }
+        let connections = self.statistics.sessions();
+        if connections < MIN_EVALUATED_CONNECTIONS {
+            return;
+        }
+        let failures = self.statistics.failures();
+        if failures * 100 > connections * self.policy.error_threshold as u64 {
+            self.status = CanaryStatus::RolledBack;
+        } else if self
+            .policy
+            .promote_after
+            .map_or(false, |n| connections >= n)
+        {
+            self.status = CanaryStatus::Promoted;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::route;
+
+    fn peer(i: u8) -> SocketAddr {
+        SocketAddr::from(([10, 0, 0, i], 40000 + i as u16))
+    }
+
+    #[test]
+    fn a_percentage_of_the_connections_are_sent_to_the_canary() {
+        let canary = InletCanary::default();
+        let policy = CanaryPolicy {
+            error_threshold: 100,
+            promote_after: None,
+        };
+        canary
+            .start(route!["canary"], 10, policy, CanaryStickiness::None)
+            .unwrap();
+
+        let selected = (0..100)
+            .filter(|_| matches!(canary.select(&peer(1)), Some(CanarySelection::Canary(..))))
+            .count();
+        assert_eq!(selected, 10);
+
+        canary.promote().unwrap();
+        assert!(matches!(
+            canary.select(&peer(1)),
+            Some(CanarySelection::Promoted(..))
+        ));
+        assert!(canary.rollback().is_err());
+    }
+
+    #[test]
+    fn the_traffic_is_shifted_with_weights() {
+        let canary = InletCanary::default();
+        let policy = CanaryPolicy {
+            error_threshold: 100,
+            promote_after: None,
+        };
+        canary
+            .start(route!["canary"], 10, policy, CanaryStickiness::None)
+            .unwrap();
+        assert!(canary.set_weights(0, 0).is_err());
+
+        assert_eq!(canary.set_weights(1, 3).unwrap().percentage, 75);
+        let selected = (0..100)
+            .filter(|_| canary.select(&peer(1)).is_some())
+            .count();
+        assert_eq!(selected, 75);
+
+        canary.set_weights(1, 0).unwrap();
+        assert!((0..100).all(|_| canary.select(&peer(1)).is_none()));
+        assert_eq!(canary.summary().unwrap().status, CanaryStatus::Running);
+    }
+
+    #[test]
+    fn a_failing_canary_is_rolled_back() {
+        let canary = InletCanary::default();
+        let policy = CanaryPolicy {
+            error_threshold: 20,
+            promote_after: Some(100),
+        };
+        canary
+            .start(route!["canary"], 100, policy, CanaryStickiness::None)
+            .unwrap();
+
+        for _ in 0..MIN_EVALUATED_CONNECTIONS {
+            if let Some(CanarySelection::Canary(_, statistics)) = canary.select(&peer(1)) {
+                statistics.connection_opened();
+                statistics.connection_failed();
+                statistics.connection_closed();
+            }
+        }
+        assert!(canary.select(&peer(1)).is_none());
+        assert_eq!(canary.summary().unwrap().status, CanaryStatus::RolledBack);
+    }
+
+    #[test]
+    fn the_connections_of_a_client_are_sent_to_the_same_route() {
+        let canary = InletCanary::default();
+        let policy = CanaryPolicy {
+            error_threshold: 100,
+            promote_after: None,
+        };
+        canary
+            .start(route!["canary"], 50, policy, CanaryStickiness::SourceIp)
+            .unwrap();
+
+        for i in 0..20 {
+            let first = canary.select(&peer(i)).is_some();
+            for port in 0..10 {
+                let other_connection = SocketAddr::from(([10, 0, 0, i], port));
+                assert_eq!(canary.select(&other_connection).is_some(), first);
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::portal::addresses::PortalType;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use ockam_core::compat::string::{String, ToString};
+
+/// Direction in which data is allowed to flow through a portal
+///
+/// With a one-way data flow, the data written by the application on the other side is
+/// discarded by the portal workers, whatever the applications do. Only the messages used to
+/// open and close the connections are still exchanged by the Inlet and the Outlet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PortalDataFlow {
+    /// Data flows in both directions
+    #[default]
+    Bidirectional,
+    /// Data only flows from the clients connected to the Inlet to the server of the Outlet
+    ClientToServer,
+    /// Data only flows from the server of the Outlet to the clients connected to the Inlet
+    ServerToClient,
+}
+
+impl PortalDataFlow {
+    /// Return true if a portal worker can forward the data read from its TCP connection
+    pub(super) fn can_read(&self, portal_type: &PortalType) -> bool {
+        matches!(
+            (self, portal_type),
+            (PortalDataFlow::Bidirectional, _)
+                | (PortalDataFlow::ClientToServer, PortalType::Inlet)
+                | (PortalDataFlow::ServerToClient, PortalType::Outlet)
+        )
+    }
+
+    /// Return true if a portal worker can write the data received from the other side of the
+    /// portal to its TCP connection
+    pub(super) fn can_write(&self, portal_type: &PortalType) -> bool {
+        matches!(
+            (self, portal_type),
+            (PortalDataFlow::Bidirectional, _)
+                | (PortalDataFlow::ClientToServer, PortalType::Outlet)
+                | (PortalDataFlow::ServerToClient, PortalType::Inlet)
+        )
+    }
+}
+
+impl Display for PortalDataFlow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            PortalDataFlow::Bidirectional => "bidirectional",
+            PortalDataFlow::ClientToServer => "client-to-server",
+            PortalDataFlow::ServerToClient => "server-to-client",
+        })
+    }
+}
+
+impl FromStr for PortalDataFlow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bidirectional" => Ok(PortalDataFlow::Bidirectional),
+            "client-to-server" => Ok(PortalDataFlow::ClientToServer),
+            "server-to-client" => Ok(PortalDataFlow::ServerToClient),
+            _ => Err(
+                "the data flow must be one of: bidirectional, client-to-server, server-to-client"
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_way_data_flows() {
+        let flow = PortalDataFlow::ClientToServer;
+        assert!(flow.can_read(&PortalType::Inlet));
+        assert!(!flow.can_write(&PortalType::Inlet));
+        assert!(!flow.can_read(&PortalType::Outlet));
+        assert!(flow.can_write(&PortalType::Outlet));
+
+        let flow = PortalDataFlow::ServerToClient;
+        assert!(!flow.can_read(&PortalType::Inlet));
+        assert!(flow.can_write(&PortalType::Inlet));
+        assert!(flow.can_read(&PortalType::Outlet));
+        assert!(!flow.can_write(&PortalType::Outlet));
+
+        let flow = PortalDataFlow::default();
+        assert!(flow.can_read(&PortalType::Inlet) && flow.can_write(&PortalType::Inlet));
+        assert!(flow.can_read(&PortalType::Outlet) && flow.can_write(&PortalType::Outlet));
+
+        assert_eq!(
+            "server-to-client".parse::<PortalDataFlow>().unwrap(),
+            PortalDataFlow::ServerToClient
+        );
+        assert!("both".parse::<PortalDataFlow>().is_err());
+    }
+}
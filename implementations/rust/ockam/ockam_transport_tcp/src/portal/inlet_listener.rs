@@ -1,4 +1,6 @@
 use crate::portal::addresses::{Addresses, PortalType};
+use crate::portal::CanarySelection;
+use crate::transport::common::bind_tcp_listener;
 use crate::{portal::TcpPortalWorker, TcpInletOptions, TcpRegistry};
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::{async_trait, compat::boxed::Box};
@@ -46,7 +48,7 @@ impl TcpInletListenProcessor {
         let processor_address = Address::random_tagged("TcpInletListenProcessor");
 
         debug!("Binding TcpPortalListenerWorker to {}", addr);
-        let inner = match TcpListener::bind(addr).await {
+        let inner = match bind_tcp_listener(addr) {
             Ok(addr) => addr,
             Err(err) => {
                 error!(%addr, %err, "could not bind to address");
@@ -81,8 +83,20 @@ impl Processor for TcpInletListenProcessor {
     }
 
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
+
         let addresses = Addresses::generate(PortalType::Inlet);
-        let outlet_listener_route = self.outlet_listener_route.clone();
+        let (outlet_listener_route, statistics) = match self.options.canary.select(&peer) {
+            Some(CanarySelection::Canary(route, statistics)) => (route, statistics),
+            Some(CanarySelection::Promoted(route)) => {
+                self.outlet_listener_route = route.clone();
+                (route, self.options.statistics.clone())
+            }
+            None => (
+                self.outlet_listener_route.clone(),
+                self.options.statistics.clone(),
+            ),
+        };
 
         self.options.setup_flow_control(
             ctx.flow_controls(),
@@ -90,7 +104,6 @@ impl Processor for TcpInletListenProcessor {
             outlet_listener_route.next()?,
         );
 
-        let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
         TcpPortalWorker::start_new_inlet(
             ctx,
             self.registry.clone(),
@@ -99,6 +112,10 @@ impl Processor for TcpInletListenProcessor {
             outlet_listener_route,
             addresses,
             self.options.incoming_access_control.clone(),
+            statistics,
+            self.options.data_flow,
+            self.options.quota,
+            self.options.expected_protocol,
         )
         .await?;
 
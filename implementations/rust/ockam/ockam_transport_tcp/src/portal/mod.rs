@@ -1,13 +1,23 @@
 mod addresses;
+mod canary;
+mod data_flow;
 mod inlet_listener;
 pub mod options;
 mod outlet_listener;
 mod portal_message;
 mod portal_receiver;
 mod portal_worker;
+mod protocol;
+mod statistics;
+mod tls;
 
+pub use canary::*;
+pub use data_flow::*;
 pub(crate) use inlet_listener::*;
 pub(crate) use outlet_listener::*;
 pub use portal_message::*;
 pub(crate) use portal_receiver::*;
 pub(crate) use portal_worker::*;
+pub use protocol::*;
+pub use statistics::*;
+pub use tls::*;
@@ -1,12 +1,18 @@
 use crate::portal::addresses::Addresses;
+use crate::{InletCanary, OutletTls, PortalDataFlow, PortalProtocol, PortalStatistics};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControls};
-use ockam_core::{Address, AllowAll, IncomingAccessControl};
+use ockam_core::{Address, AllowAll, IncomingAccessControl, Quota};
 
 /// Trust Options for an Inlet
 #[derive(Debug)]
 pub struct TcpInletOptions {
     pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(super) statistics: PortalStatistics,
+    pub(super) data_flow: PortalDataFlow,
+    pub(super) quota: Quota,
+    pub(super) canary: InletCanary,
+    pub(super) expected_protocol: Option<PortalProtocol>,
 }
 
 impl TcpInletOptions {
@@ -14,6 +20,11 @@ impl TcpInletOptions {
     pub fn new() -> Self {
         Self {
             incoming_access_control: Arc::new(AllowAll),
+            statistics: PortalStatistics::default(),
+            data_flow: PortalDataFlow::default(),
+            quota: Quota::default(),
+            canary: InletCanary::default(),
+            expected_protocol: None,
         }
     }
 
@@ -35,6 +46,36 @@ impl TcpInletOptions {
         self
     }
 
+    /// Send a fraction of the new connections to the route of an [`InletCanary`]
+    pub fn with_canary(mut self, canary: InletCanary) -> Self {
+        self.canary = canary;
+        self
+    }
+
+    /// Count the traffic of the Inlet connections with the given [`PortalStatistics`]
+    pub fn with_statistics(mut self, statistics: PortalStatistics) -> Self {
+        self.statistics = statistics;
+        self
+    }
+
+    /// Restrict the direction in which data can flow through the Inlet connections
+    pub fn with_data_flow(mut self, data_flow: PortalDataFlow) -> Self {
+        self.data_flow = data_flow;
+        self
+    }
+
+    /// Close each Inlet connection when it exceeds the given [`Quota`]
+    pub fn with_quota(mut self, quota: Quota) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Close the Inlet connections whose first bytes don't match the given [`PortalProtocol`]
+    pub fn with_expected_protocol(mut self, protocol: PortalProtocol) -> Self {
+        self.expected_protocol = Some(protocol);
+        self
+    }
+
     pub(super) fn setup_flow_control(
         &self,
         flow_controls: &FlowControls,
@@ -62,6 +103,10 @@ impl Default for TcpInletOptions {
 pub struct TcpOutletOptions {
     pub(super) consumer: Vec<FlowControlId>,
     pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(super) statistics: PortalStatistics,
+    pub(super) data_flow: PortalDataFlow,
+    pub(super) quota: Quota,
+    pub(super) tls: Option<OutletTls>,
 }
 
 impl TcpOutletOptions {
@@ -70,6 +115,10 @@ impl TcpOutletOptions {
         Self {
             consumer: vec![],
             incoming_access_control: Arc::new(AllowAll),
+            statistics: PortalStatistics::default(),
+            data_flow: PortalDataFlow::default(),
+            quota: Quota::default(),
+            tls: None,
         }
     }
 
@@ -100,6 +149,30 @@ impl TcpOutletOptions {
         self
     }
 
+    /// Count the traffic of the Outlet connections with the given [`PortalStatistics`]
+    pub fn with_statistics(mut self, statistics: PortalStatistics) -> Self {
+        self.statistics = statistics;
+        self
+    }
+
+    /// Restrict the direction in which data can flow through the Outlet connections
+    pub fn with_data_flow(mut self, data_flow: PortalDataFlow) -> Self {
+        self.data_flow = data_flow;
+        self
+    }
+
+    /// Close each Outlet connection when it exceeds the given [`Quota`]
+    pub fn with_quota(mut self, quota: Quota) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Wrap the Outlet connections to the target in TLS
+    pub fn with_tls(mut self, tls: OutletTls) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
     pub(super) fn setup_flow_control_for_outlet_listener(
         &self,
         flow_controls: &FlowControls,
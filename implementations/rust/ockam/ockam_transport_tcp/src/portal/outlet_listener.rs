@@ -92,6 +92,10 @@ impl Worker for TcpOutletListenWorker {
             return_route.clone(),
             addresses.clone(),
             self.options.incoming_access_control.clone(),
+            self.options.statistics.clone(),
+            self.options.data_flow,
+            self.options.quota,
+            self.options.tls.clone(),
         )
         .await?;
 
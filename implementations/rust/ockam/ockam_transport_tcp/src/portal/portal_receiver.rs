@@ -1,11 +1,16 @@
 use crate::portal::portal_message::MAX_PAYLOAD_SIZE;
-use crate::{PortalInternalMessage, PortalMessage, TcpRegistry};
+use crate::portal::protocol::ProtocolCheck;
+use crate::portal::PortalReadHalf;
+use crate::{PortalInternalMessage, PortalMessage, PortalProtocol, PortalStatistics, TcpRegistry};
 use ockam_core::compat::vec::Vec;
 use ockam_core::{async_trait, Encodable, LocalMessage, Route, TransportMessage};
-use ockam_core::{route, Address, Processor, Result};
+use ockam_core::{
+    route, Address, Processor, QuotaExceeded, QuotaUsage, Result, QUOTA_AUDIT_TARGET,
+};
 use ockam_node::Context;
-use tokio::{io::AsyncReadExt, net::tcp::OwnedReadHalf};
-use tracing::{error, warn};
+use tokio::io::AsyncReadExt;
+use tokio::time::Instant;
+use tracing::{debug, error, warn};
 
 /// A TCP Portal receiving message processor
 ///
@@ -15,18 +20,34 @@ use tracing::{error, warn};
 pub(crate) struct TcpPortalRecvProcessor {
     registry: TcpRegistry,
     buf: Vec<u8>,
-    read_half: OwnedReadHalf,
+    read_half: PortalReadHalf,
     sender_address: Address,
     onward_route: Route,
+    statistics: PortalStatistics,
+    /// False if the portal data flow doesn't allow this side to send data
+    forward_data: bool,
+    quota_usage: QuotaUsage,
+    /// Time at which the connection is closed if the quota has a maximum duration
+    deadline: Option<Instant>,
+    /// Protocol expected at the beginning of the connection, until the first bytes are checked
+    expected_protocol: Option<PortalProtocol>,
+    /// First bytes of the connection, kept until they can be checked
+    first_bytes: Vec<u8>,
 }
 
 impl TcpPortalRecvProcessor {
     /// Create a new `TcpPortalRecvProcessor`
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         registry: TcpRegistry,
-        read_half: OwnedReadHalf,
+        read_half: PortalReadHalf,
         sender_address: Address,
         onward_route: Route,
+        statistics: PortalStatistics,
+        forward_data: bool,
+        quota_usage: QuotaUsage,
+        deadline: Option<Instant>,
+        expected_protocol: Option<PortalProtocol>,
     ) -> Self {
         Self {
             registry,
@@ -34,8 +55,46 @@ impl TcpPortalRecvProcessor {
             read_half,
             sender_address,
             onward_route,
+            statistics,
+            forward_data,
+            quota_usage,
+            deadline,
+            expected_protocol,
+            first_bytes: Vec::new(),
         }
     }
+
+    /// Notify the Sender and the other side of the portal that the connection is closed
+    async fn notify_disconnection(&self, ctx: &Context) -> Result<()> {
+        if let Err(err) = ctx
+            .send(
+                route![self.sender_address.clone()],
+                PortalInternalMessage::Disconnect,
+            )
+            .await
+        {
+            warn!(
+                "Error notifying Tcp Portal Sender about dropped connection {}",
+                err
+            );
+        }
+
+        let msg = TransportMessage::v1(
+            self.onward_route.clone(),
+            self.sender_address.clone(),
+            PortalMessage::Disconnect.encode()?,
+        );
+        ctx.forward(LocalMessage::new(msg, vec![])).await
+    }
+
+    /// Close the connection because it exceeded its quota
+    async fn close_for_quota(&self, ctx: &Context, exceeded: QuotaExceeded) -> Result<()> {
+        warn!(
+            target: QUOTA_AUDIT_TARGET,
+            "Tcp Portal at: {} closed the connection: {exceeded}", self.sender_address
+        );
+        self.notify_disconnection(ctx).await
+    }
 }
 
 #[async_trait]
@@ -58,36 +117,65 @@ impl Processor for TcpPortalRecvProcessor {
     async fn process(&mut self, ctx: &mut Context) -> Result<bool> {
         self.buf.clear();
 
-        let _len = match self.read_half.read_buf(&mut self.buf).await {
-            Ok(len) => len,
-            Err(err) => {
+        let read = match self.deadline {
+            Some(deadline) => {
+                tokio::time::timeout_at(deadline, self.read_half.read_buf(&mut self.buf)).await
+            }
+            None => Ok(self.read_half.read_buf(&mut self.buf).await),
+        };
+
+        let len = match read {
+            Ok(Ok(len)) => len,
+            Ok(Err(err)) => {
                 error!("Tcp Portal connection read failed with error: {}", err);
                 return Ok(false);
             }
+            Err(_) => {
+                let max_duration = self.quota_usage.quota().max_duration.unwrap_or_default();
+                self.close_for_quota(ctx, QuotaExceeded::Duration(max_duration))
+                    .await?;
+                return Ok(false);
+            }
         };
 
         if self.buf.is_empty() {
-            // Notify Sender that connection was closed
-            if let Err(err) = ctx
-                .send(
-                    route![self.sender_address.clone()],
-                    PortalInternalMessage::Disconnect,
-                )
-                .await
-            {
-                warn!(
-                    "Error notifying Tcp Portal Sender about dropped connection {}",
-                    err
-                );
-            }
+            self.notify_disconnection(ctx).await?;
+            return Ok(false);
+        }
 
-            let msg = TransportMessage::v1(
-                self.onward_route.clone(),
-                self.sender_address.clone(),
-                PortalMessage::Disconnect.encode()?,
+        self.statistics.add_bytes_received(len);
+
+        // The data is still read, to detect when the connection is closed, but it is dropped
+        if !self.forward_data {
+            debug!(
+                "Tcp Portal dropped {} bytes, the portal data flow is one-way",
+                len
             );
-            ctx.forward(LocalMessage::new(msg, vec![])).await?;
+            return Ok(true);
+        }
+
+        if let Some(protocol) = self.expected_protocol {
+            self.first_bytes.extend_from_slice(&self.buf);
+            match protocol.check(&self.first_bytes) {
+                ProtocolCheck::Incomplete => return Ok(true),
+                ProtocolCheck::Mismatched => {
+                    warn!(
+                        "Tcp Portal at: {} closed the connection: its first bytes are not {protocol}",
+                        self.sender_address
+                    );
+                    self.notify_disconnection(ctx).await?;
+                    return Ok(false);
+                }
+                ProtocolCheck::Matched => {
+                    self.expected_protocol = None;
+                    self.buf.clear();
+                    self.buf.append(&mut self.first_bytes);
+                }
+            }
+        }
 
+        if let Some(exceeded) = self.quota_usage.add_message(self.buf.len()) {
+            self.close_for_quota(ctx, exceeded).await?;
             return Ok(false);
         }
 
@@ -1,19 +1,28 @@
 use crate::portal::addresses::{Addresses, PortalType};
-use crate::{portal::TcpPortalRecvProcessor, PortalInternalMessage, PortalMessage, TcpRegistry};
+use crate::{
+    portal::TcpPortalRecvProcessor, OutletTls, PortalDataFlow, PortalInternalMessage,
+    PortalMessage, PortalProtocol, PortalStatistics, TcpRegistry,
+};
 use core::time::Duration;
 use ockam_core::compat::{boxed::Box, net::SocketAddr, sync::Arc};
 use ockam_core::{
     async_trait, AllowAll, AllowOnwardAddresses, AllowSourceAddress, Decodable, DenyAll,
-    IncomingAccessControl, Mailbox, Mailboxes,
+    IncomingAccessControl, Mailbox, Mailboxes, Quota, QuotaUsage, QUOTA_AUDIT_TARGET,
 };
 use ockam_core::{Any, Result, Route, Routed, Worker};
 use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
 use ockam_transport_core::TransportError;
-use tokio::io::AsyncWriteExt;
-use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 use tokio::net::TcpStream;
+use tokio::time::Instant;
 use tracing::{debug, info, trace, warn};
 
+/// Read half of a portal connection, which might be wrapped in TLS
+pub(crate) type PortalReadHalf = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Write half of a portal connection, which might be wrapped in TLS
+pub(crate) type PortalWriteHalf = Box<dyn AsyncWrite + Send + Unpin>;
+
 /// Enumerate all `TcpPortalWorker` states
 ///
 /// Possible state transitions are:
@@ -37,17 +46,29 @@ enum State {
 pub(crate) struct TcpPortalWorker {
     registry: TcpRegistry,
     state: State,
-    write_half: Option<OwnedWriteHalf>,
-    read_half: Option<OwnedReadHalf>,
+    write_half: Option<PortalWriteHalf>,
+    read_half: Option<PortalReadHalf>,
     peer: SocketAddr,
     addresses: Addresses,
     remote_route: Option<Route>,
     is_disconnecting: bool,
     portal_type: PortalType,
+    statistics: PortalStatistics,
+    data_flow: PortalDataFlow,
+    quota_usage: QuotaUsage,
+    /// Time at which the connection is closed if the quota has a maximum duration
+    deadline: Option<Instant>,
+    /// True once data has been received from the other side of the portal
+    received_payload: bool,
+    /// Protocol expected at the beginning of the data read from the TCP connection
+    expected_protocol: Option<PortalProtocol>,
+    /// TLS configuration of the connection created by an Outlet
+    tls: Option<OutletTls>,
 }
 
 impl TcpPortalWorker {
     /// Start a new `TcpPortalWorker` of type [`TypeName::Inlet`]
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn start_new_inlet(
         ctx: &Context,
         registry: TcpRegistry,
@@ -56,6 +77,10 @@ impl TcpPortalWorker {
         ping_route: Route,
         addresses: Addresses,
         access_control: Arc<dyn IncomingAccessControl>,
+        statistics: PortalStatistics,
+        data_flow: PortalDataFlow,
+        quota: Quota,
+        expected_protocol: Option<PortalProtocol>,
     ) -> Result<()> {
         Self::start(
             ctx,
@@ -66,11 +91,17 @@ impl TcpPortalWorker {
             addresses,
             PortalType::Inlet,
             access_control,
+            statistics,
+            data_flow,
+            quota,
+            expected_protocol,
+            None,
         )
         .await
     }
 
     /// Start a new `TcpPortalWorker` of type [`TypeName::Outlet`]
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn start_new_outlet(
         ctx: &Context,
         registry: TcpRegistry,
@@ -78,6 +109,10 @@ impl TcpPortalWorker {
         pong_route: Route,
         addresses: Addresses,
         access_control: Arc<dyn IncomingAccessControl>,
+        statistics: PortalStatistics,
+        data_flow: PortalDataFlow,
+        quota: Quota,
+        tls: Option<OutletTls>,
     ) -> Result<()> {
         Self::start(
             ctx,
@@ -88,6 +123,11 @@ impl TcpPortalWorker {
             addresses,
             PortalType::Outlet,
             access_control,
+            statistics,
+            data_flow,
+            quota,
+            None,
+            tls,
         )
         .await
     }
@@ -103,6 +143,11 @@ impl TcpPortalWorker {
         addresses: Addresses,
         portal_type: PortalType,
         access_control: Arc<dyn IncomingAccessControl>,
+        statistics: PortalStatistics,
+        data_flow: PortalDataFlow,
+        quota: Quota,
+        expected_protocol: Option<PortalProtocol>,
+        tls: Option<OutletTls>,
     ) -> Result<()> {
         info!(
             "Creating new {:?} at internal: {}, remote: {}",
@@ -114,7 +159,10 @@ impl TcpPortalWorker {
         let (rx, tx) = match stream {
             Some(s) => {
                 let (rx, tx) = s.into_split();
-                (Some(rx), Some(tx))
+                (
+                    Some(Box::new(rx) as PortalReadHalf),
+                    Some(Box::new(tx) as PortalWriteHalf),
+                )
             }
             None => (None, None),
         };
@@ -129,6 +177,13 @@ impl TcpPortalWorker {
             remote_route: None,
             is_disconnecting: false,
             portal_type,
+            statistics,
+            data_flow,
+            quota_usage: QuotaUsage::new(quota),
+            deadline: quota.max_duration.map(|d| Instant::now() + d),
+            received_payload: false,
+            expected_protocol,
+            tls,
         };
 
         let internal_mailbox = Mailbox::new(
@@ -156,6 +211,7 @@ impl TcpPortalWorker {
 enum DisconnectionReason {
     FailedTx,
     FailedRx,
+    QuotaExceeded,
     Remote,
 }
 
@@ -173,6 +229,11 @@ impl TcpPortalWorker {
                 rx,
                 self.addresses.internal.clone(),
                 onward_route,
+                self.statistics.clone(),
+                self.data_flow.can_read(&self.portal_type),
+                self.quota_usage.clone(),
+                self.deadline,
+                self.expected_protocol,
             );
 
             ProcessorBuilder::new(receiver)
@@ -252,7 +313,7 @@ impl TcpPortalWorker {
             DisconnectionReason::FailedTx => {
                 self.notify_remote_about_disconnection(ctx).await?;
             }
-            DisconnectionReason::FailedRx => {
+            DisconnectionReason::FailedRx | DisconnectionReason::QuotaExceeded => {
                 self.notify_remote_about_disconnection(ctx).await?;
                 self.stop_receiver(ctx).await?;
             }
@@ -296,10 +357,44 @@ impl TcpPortalWorker {
         .await?;
 
         if self.write_half.is_none() {
-            let stream = TcpStream::connect(self.peer)
-                .await
-                .map_err(TransportError::from)?;
-            let (rx, tx) = stream.into_split();
+            let stream = match TcpStream::connect(self.peer).await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    // Let the Inlet close its connection instead of waiting for data
+                    ctx.send_from_address(
+                        pong_route,
+                        PortalMessage::Disconnect,
+                        self.addresses.remote.clone(),
+                    )
+                    .await?;
+                    return Err(TransportError::from(err).into());
+                }
+            };
+            let (rx, tx): (PortalReadHalf, PortalWriteHalf) = match &self.tls {
+                Some(tls) => match tls.connect(self.peer, stream).await {
+                    Ok(stream) => {
+                        let (rx, tx) = tokio::io::split(stream);
+                        (Box::new(rx), Box::new(tx))
+                    }
+                    Err(err) => {
+                        warn!(
+                            "Outlet at: {} failed to establish a TLS session with {}: {err}",
+                            self.addresses.internal, self.peer
+                        );
+                        ctx.send_from_address(
+                            pong_route,
+                            PortalMessage::Disconnect,
+                            self.addresses.remote.clone(),
+                        )
+                        .await?;
+                        return Err(err);
+                    }
+                },
+                None => {
+                    let (rx, tx) = stream.into_split();
+                    (Box::new(rx), Box::new(tx))
+                }
+            };
             self.write_half = Some(tx);
             self.read_half = Some(rx);
 
@@ -324,6 +419,9 @@ impl Worker for TcpPortalWorker {
     type Message = Any;
 
     async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        // Counted here since `shutdown` is called even if the initialization fails
+        self.statistics.connection_opened();
+
         let state = self.clone_state();
 
         match state {
@@ -345,6 +443,10 @@ impl Worker for TcpPortalWorker {
 
     async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
         self.registry.remove_portal_worker(&self.addresses.remote);
+        if matches!(self.portal_type, PortalType::Inlet) && !self.received_payload {
+            self.statistics.connection_failed();
+        }
+        self.statistics.connection_closed();
 
         Ok(())
     }
@@ -422,9 +524,31 @@ impl Worker for TcpPortalWorker {
 
                     match msg {
                         PortalMessage::Payload(payload) => {
-                            if let Some(tx) = &mut self.write_half {
+                            self.received_payload = true;
+                            if !self.data_flow.can_write(&self.portal_type) {
+                                // The data sent against the data flow of the portal is dropped
+                                debug!(
+                                    "{:?} at: {} dropped {} bytes, the portal data flow is {}",
+                                    self.portal_type.str(),
+                                    self.addresses.internal,
+                                    payload.len(),
+                                    self.data_flow
+                                );
+                            } else if let Some(exceeded) =
+                                self.quota_usage.add_message(payload.len())
+                            {
+                                warn!(
+                                    target: QUOTA_AUDIT_TARGET,
+                                    peer = %self.peer,
+                                    "{:?} at: {} closed the connection: {exceeded}",
+                                    self.portal_type.str(),
+                                    self.addresses.internal
+                                );
+                                self.start_disconnection(ctx, DisconnectionReason::QuotaExceeded)
+                                    .await?;
+                            } else if let Some(tx) = &mut self.write_half {
                                 match tx.write_all(&payload).await {
-                                    Ok(()) => {}
+                                    Ok(()) => self.statistics.add_bytes_sent(payload.len()),
                                     Err(err) => {
                                         warn!(
                                             "Failed to send message to peer {} with error: {}",
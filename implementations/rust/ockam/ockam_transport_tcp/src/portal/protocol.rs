@@ -0,0 +1,137 @@
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use ockam_core::compat::string::{String, ToString};
+
+/// Methods which can start an HTTP/1.x request, and the preface of HTTP/2 connections
+const HTTP_PREFIXES: &[&[u8]] = &[
+    b"GET ",
+    b"HEAD ",
+    b"POST ",
+    b"PUT ",
+    b"DELETE ",
+    b"CONNECT ",
+    b"OPTIONS ",
+    b"TRACE ",
+    b"PATCH ",
+    b"PRI * HTTP/2.0",
+];
+
+/// Protocol expected at the beginning of the Inlet connections
+///
+/// The first bytes sent by a client connected to an Inlet are checked before being forwarded
+/// to the Outlet. The connection is closed if they don't start like the expected protocol, so
+/// that misconfigured clients can't send arbitrary traffic to the server of the Outlet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortalProtocol {
+    /// An HTTP/1.x request or an HTTP/2 connection preface
+    Http,
+    /// A TLS handshake record
+    Tls,
+    /// An SSH identification string
+    Ssh,
+}
+
+/// Result of the check of the first bytes of a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ProtocolCheck {
+    /// The bytes start like the expected protocol
+    Matched,
+    /// The bytes don't start like the expected protocol
+    Mismatched,
+    /// More bytes are needed to decide
+    Incomplete,
+}
+
+impl PortalProtocol {
+    /// Check the first bytes sent by a client
+    pub(super) fn check(&self, data: &[u8]) -> ProtocolCheck {
+        match self {
+            PortalProtocol::Http => HTTP_PREFIXES
+                .iter()
+                .map(|prefix| check_prefix(prefix, data))
+                .fold(ProtocolCheck::Mismatched, |result, check| {
+                    match (result, check) {
+                        (ProtocolCheck::Matched, _) | (_, ProtocolCheck::Matched) => {
+                            ProtocolCheck::Matched
+                        }
+                        (ProtocolCheck::Incomplete, _) | (_, ProtocolCheck::Incomplete) => {
+                            ProtocolCheck::Incomplete
+                        }
+                        _ => ProtocolCheck::Mismatched,
+                    }
+                }),
+            // Content type 22 (handshake), followed by a 3.x record version
+            PortalProtocol::Tls => match data {
+                [] | [0x16] | [0x16, 0x03] => ProtocolCheck::Incomplete,
+                [0x16, 0x03, minor, ..] if *minor <= 0x04 => ProtocolCheck::Matched,
+                _ => ProtocolCheck::Mismatched,
+            },
+            PortalProtocol::Ssh => check_prefix(b"SSH-", data),
+        }
+    }
+}
+
+fn check_prefix(prefix: &[u8], data: &[u8]) -> ProtocolCheck {
+    if data.starts_with(prefix) {
+        ProtocolCheck::Matched
+    } else if prefix.starts_with(data) {
+        ProtocolCheck::Incomplete
+    } else {
+        ProtocolCheck::Mismatched
+    }
+}
+
+impl Display for PortalProtocol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            PortalProtocol::Http => "http",
+            PortalProtocol::Tls => "tls",
+            PortalProtocol::Ssh => "ssh",
+        })
+    }
+}
+
+impl FromStr for PortalProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "http" => Ok(PortalProtocol::Http),
+            "tls" => Ok(PortalProtocol::Tls),
+            "ssh" => Ok(PortalProtocol::Ssh),
+            _ => Err("the protocol must be one of: http, tls, ssh".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_first_bytes() {
+        let http = PortalProtocol::Http;
+        assert_eq!(http.check(b"GET / HTTP/1.1\r\n"), ProtocolCheck::Matched);
+        assert_eq!(http.check(b"PO"), ProtocolCheck::Incomplete);
+        assert_eq!(http.check(b"SSH-2.0"), ProtocolCheck::Mismatched);
+
+        let tls = PortalProtocol::Tls;
+        assert_eq!(tls.check(&[0x16, 0x03, 0x01, 0x02]), ProtocolCheck::Matched);
+        assert_eq!(tls.check(&[0x16]), ProtocolCheck::Incomplete);
+        assert_eq!(tls.check(b"GET /"), ProtocolCheck::Mismatched);
+
+        let ssh = PortalProtocol::Ssh;
+        assert_eq!(
+            ssh.check(b"SSH-2.0-OpenSSH_9.0\r\n"),
+            ProtocolCheck::Matched
+        );
+        assert_eq!(ssh.check(b"SS"), ProtocolCheck::Incomplete);
+        assert_eq!(ssh.check(&[0x16, 0x03, 0x01]), ProtocolCheck::Mismatched);
+
+        assert_eq!(
+            "tls".parse::<PortalProtocol>().unwrap(),
+            PortalProtocol::Tls
+        );
+        assert!("ftp".parse::<PortalProtocol>().is_err());
+    }
+}
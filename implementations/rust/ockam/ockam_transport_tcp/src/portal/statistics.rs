@@ -0,0 +1,90 @@
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use ockam_core::compat::sync::Arc;
+
+/// Traffic counters shared by all the connections of an Inlet or an Outlet
+///
+/// The counters are cumulative, a rate can be computed by sampling them periodically.
+#[derive(Debug, Clone, Default)]
+pub struct PortalStatistics {
+    inner: Arc<PortalStatisticsInner>,
+}
+
+#[derive(Debug, Default)]
+struct PortalStatisticsInner {
+    bytes_received: AtomicU64,
+    bytes_sent: AtomicU64,
+    connections: AtomicUsize,
+    sessions: AtomicU64,
+    failures: AtomicU64,
+}
+
+impl PortalStatistics {
+    /// Number of bytes read from the TCP connections of the portal
+    pub fn bytes_received(&self) -> u64 {
+        self.inner.bytes_received.load(Ordering::Relaxed)
+    }
+
+    /// Number of bytes written to the TCP connections of the portal
+    pub fn bytes_sent(&self) -> u64 {
+        self.inner.bytes_sent.load(Ordering::Relaxed)
+    }
+
+    /// Number of TCP connections currently open
+    pub fn connections(&self) -> usize {
+        self.inner.connections.load(Ordering::Relaxed)
+    }
+
+    /// Number of TCP connections opened since the portal was created
+    pub fn sessions(&self) -> u64 {
+        self.inner.sessions.load(Ordering::Relaxed)
+    }
+
+    /// Number of Inlet connections which ended before any data was received from the Outlet
+    pub fn failures(&self) -> u64 {
+        self.inner.failures.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn add_bytes_received(&self, n: usize) {
+        self.inner
+            .bytes_received
+            .fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    pub(super) fn add_bytes_sent(&self, n: usize) {
+        self.inner.bytes_sent.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    pub(super) fn connection_opened(&self) {
+        self.inner.connections.fetch_add(1, Ordering::Relaxed);
+        self.inner.sessions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn connection_closed(&self) {
+        self.inner.connections.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn connection_failed(&self) {
+        self.inner.failures.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics_are_shared_between_clones() {
+        let statistics = PortalStatistics::default();
+        let clone = statistics.clone();
+        clone.connection_opened();
+        clone.add_bytes_received(10);
+        clone.add_bytes_sent(5);
+
+        assert_eq!(statistics.connections(), 1);
+        assert_eq!(statistics.bytes_received(), 10);
+        assert_eq!(statistics.bytes_sent(), 5);
+
+        clone.connection_closed();
+        assert_eq!(statistics.connections(), 0);
+    }
+}
@@ -0,0 +1,143 @@
+use core::fmt;
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use rustls_pemfile::Item;
+use std::net::SocketAddr;
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::{Certificate, ClientConfig, PrivateKey, RootCertStore, ServerName};
+use tokio_rustls::TlsConnector;
+
+/// TLS configuration of an Outlet wrapping the connections to its target in TLS
+#[derive(Clone)]
+pub struct OutletTls {
+    config: Arc<ClientConfig>,
+    server_name: Option<ServerName>,
+}
+
+impl OutletTls {
+    /// Create a TLS configuration verifying the certificate of the target with the
+    /// certificates of a PEM bundle, or with the native root certificates if no bundle is given.
+    ///
+    /// The Outlet authenticates to the target with a PEM certificate chain and private key
+    /// when a client certificate is given
+    pub fn new(
+        ca_certificates: Option<&[u8]>,
+        client_certificate: Option<(&[u8], &[u8])>,
+    ) -> Result<Self> {
+        let builder = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(root_certificates(ca_certificates)?);
+        let config = match client_certificate {
+            Some((certificate_chain, private_key)) => builder
+                .with_client_auth_cert(
+                    parse_certificates(certificate_chain)?,
+                    parse_private_key(private_key)?,
+                )
+                .map_err(error)?,
+            None => builder.with_no_client_auth(),
+        };
+
+        Ok(Self {
+            config: Arc::new(config),
+            server_name: None,
+        })
+    }
+
+    /// Send this name with SNI and verify it against the certificate of the target,
+    /// instead of the IP address of the target
+    pub fn with_server_name(mut self, server_name: &str) -> Result<Self> {
+        self.server_name = Some(ServerName::try_from(server_name).map_err(error)?);
+        Ok(self)
+    }
+
+    /// Start a TLS session on a connection to the target
+    pub(super) async fn connect(
+        &self,
+        peer: SocketAddr,
+        stream: TcpStream,
+    ) -> Result<TlsStream<TcpStream>> {
+        let server_name = self
+            .server_name
+            .clone()
+            .unwrap_or(ServerName::IpAddress(peer.ip()));
+        TlsConnector::from(self.config.clone())
+            .connect(server_name, stream)
+            .await
+            .map_err(|e| Error::new(Origin::Transport, Kind::Io, e))
+    }
+}
+
+impl fmt::Debug for OutletTls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OutletTls")
+            .field("server_name", &self.server_name)
+            .finish()
+    }
+}
+
+/// Return the certificates of a PEM bundle, or the native root certificates if no bundle is given
+pub(crate) fn root_certificates(ca_certificates: Option<&[u8]>) -> Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    match ca_certificates {
+        Some(pem) => {
+            for certificate in parse_certificates(pem)? {
+                roots.add(&certificate).map_err(error)?;
+            }
+        }
+        None => {
+            for certificate in rustls_native_certs::load_native_certs().map_err(error)? {
+                roots.add(&Certificate(certificate.0)).map_err(error)?;
+            }
+        }
+    }
+    if roots.is_empty() {
+        return Err(error("no CA certificate was found"));
+    }
+    Ok(roots)
+}
+
+/// Parse the certificates of a PEM bundle
+pub(crate) fn parse_certificates(pem: &[u8]) -> Result<Vec<Certificate>> {
+    let certificates = rustls_pemfile::certs(&mut &pem[..]).map_err(error)?;
+    if certificates.is_empty() {
+        return Err(error("no certificate was found in the PEM data"));
+    }
+    Ok(certificates.into_iter().map(Certificate).collect())
+}
+
+/// Parse the first private key of a PEM file
+pub(crate) fn parse_private_key(pem: &[u8]) -> Result<PrivateKey> {
+    for item in rustls_pemfile::read_all(&mut &pem[..]).map_err(error)? {
+        match item {
+            Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => {
+                return Ok(PrivateKey(key))
+            }
+            _ => continue,
+        }
+    }
+    Err(error("no private key was found in the PEM data"))
+}
+
+pub(crate) fn error(e: impl fmt::Display) -> Error {
+    Error::new(
+        Origin::Transport,
+        Kind::Invalid,
+        format!("invalid TLS configuration: {e}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_tls_configuration() {
+        assert!(OutletTls::new(Some(b"not a certificate"), None).is_err());
+        assert!(
+            parse_private_key(b"-----BEGIN CERTIFICATE-----\n-----END CERTIFICATE-----\n").is_err()
+        );
+    }
+}
@@ -5,6 +5,7 @@ use ockam_core::compat::net::{SocketAddr, ToSocketAddrs};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Address, Result};
 use ockam_transport_core::TransportError;
+use socket2::{Domain, Protocol, Socket, Type};
 
 /// Result of [`TcpTransport::connect`] call.
 #[derive(Clone, Debug)]
@@ -143,16 +144,56 @@ pub fn resolve_peer(peer: String) -> Result<SocketAddr> {
     Err(TransportError::InvalidAddress.into())
 }
 
+/// Resolve the given peer to all its [`SocketAddr`](std::net::SocketAddr)s, in the order
+/// returned by the resolver
+pub fn resolve_peers(peer: String) -> Result<Vec<SocketAddr>> {
+    if let Ok(p) = parse_socket_addr(&peer) {
+        return Ok(vec![p]);
+    }
+
+    let mut addresses: Vec<SocketAddr> = vec![];
+    for address in peer
+        .to_socket_addrs()
+        .map_err(|_| TransportError::InvalidAddress)?
+    {
+        if !addresses.contains(&address) {
+            addresses.push(address);
+        }
+    }
+    if addresses.is_empty() {
+        return Err(TransportError::InvalidAddress.into());
+    }
+    Ok(addresses)
+}
+
+/// Bind a TCP listener. A listener bound to the unspecified IPv6 address `[::]` is dual-stack:
+/// it also accepts the IPv4 connections, whatever the default of the operating system is
+pub(crate) fn bind_tcp_listener(addr: SocketAddr) -> std::io::Result<tokio::net::TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    if let SocketAddr::V6(v6) = addr {
+        if v6.ip().is_unspecified() {
+            socket.set_only_v6(false)?;
+        }
+    }
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+    socket.set_nonblocking(true)?;
+    tokio::net::TcpListener::from_std(socket.into())
+}
+
 pub(super) fn parse_socket_addr(s: &str) -> Result<SocketAddr> {
     Ok(s.parse().map_err(|_| TransportError::InvalidAddress)?)
 }
 
 #[cfg(test)]
 mod test {
-    use crate::transport::common::parse_socket_addr;
+    use crate::transport::common::{bind_tcp_listener, parse_socket_addr};
     use core::fmt::Debug;
     use ockam_core::{Error, Result};
     use ockam_transport_core::TransportError;
+    use std::net::SocketAddr;
 
     fn assert_transport_error<T>(result: Result<T>, error: TransportError)
     where
@@ -201,4 +242,22 @@ mod test {
         let result = parse_socket_addr("127.0.0.1:8080");
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_dual_stack_listener_accepts_ipv4_connections() {
+        let addr: SocketAddr = "[::]:0".parse().unwrap();
+        let listener = match bind_tcp_listener(addr) {
+            Ok(listener) => listener,
+            // IPv6 is disabled on this host
+            Err(_) => return,
+        };
+        let port = listener.local_addr().unwrap().port();
+
+        let ipv4_addr: SocketAddr = format!("127.0.0.1:{port}").parse().unwrap();
+        let (connected, accepted) =
+            tokio::join!(tokio::net::TcpStream::connect(ipv4_addr), listener.accept());
+        let connected = connected.unwrap();
+        let (_, peer) = accepted.unwrap();
+        assert_eq!(peer.port(), connected.local_addr().unwrap().port());
+    }
 }
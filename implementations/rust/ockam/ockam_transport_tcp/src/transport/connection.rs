@@ -1,7 +1,10 @@
-use crate::transport::common::{resolve_peer, TcpConnection};
+use crate::transport::common::{parse_socket_addr, TcpConnection};
+use crate::transport::happy_eyeballs::{connect_first, CONNECTION_ATTEMPT_DELAY};
 use crate::workers::{Addresses, TcpRecvProcessor, TcpSendWorker};
-use crate::{TcpConnectionMode, TcpConnectionOptions, TcpTransport};
+use crate::{TcpConnectionMode, TcpConnectionOptions, TcpConnectionTls, TcpTransport, TLS_PREFIX};
+use ockam_core::compat::net::SocketAddr;
 use ockam_core::{Address, Result};
+use ockam_transport_core::TransportError;
 
 impl TcpTransport {
     /// Establish an outgoing TCP connection.
@@ -16,15 +19,55 @@ impl TcpTransport {
     /// let connection = tcp.connect("127.0.0.1:5000", TcpConnectionOptions::new()).await?; // and connect to port 5000
     /// # Ok(()) }
     /// ```
+    ///
+    /// When a [`TcpProxy`](crate::TcpProxy) is set on the transport, the connection is opened
+    /// via that proxy, unless the peer is bypassed.
+    ///
+    /// The connection is wrapped in TLS when the options have a [`TcpConnectionTls`]
+    /// configuration, or when the peer is given as `tls://host:port`. In the latter case,
+    /// the certificate of the peer is verified with the native root certificates by default.
+    ///
+    /// When the host name of the peer resolves to several addresses, the connection attempts
+    /// are raced as described in RFC 8305 ("Happy Eyeballs"), and the first connection
+    /// established is used.
     pub async fn connect(
         &self,
         peer: impl Into<String>,
         options: TcpConnectionOptions,
     ) -> Result<TcpConnection> {
-        // Resolve peer address
-        let socket = resolve_peer(peer.into())?;
+        let peer = peer.into();
+        let (peer, tls) = match peer.strip_prefix(TLS_PREFIX) {
+            Some(host_and_port) => {
+                let tls = match options.tls.clone() {
+                    Some(tls) => tls,
+                    None => TcpConnectionTls::new(None)?,
+                };
+                (host_and_port.to_string(), Some(tls))
+            }
+            None => (peer, options.tls.clone()),
+        };
 
-        let (read_half, write_half) = TcpSendWorker::connect(socket).await?;
+        let (socket, stream) = match self.proxy().filter(|p| p.applies_to(&peer)) {
+            Some(proxy) => {
+                let stream = proxy.connect(&peer).await?;
+                TcpSendWorker::set_keepalive(&stream);
+                // The socket address of a proxied connection is the address of the proxy
+                let socket = stream.peer_addr().map_err(TransportError::from)?;
+                (socket, stream)
+            }
+            None => {
+                // Resolve all the addresses of the peer, and race the connection attempts
+                let sockets = self.resolve_addresses(&peer).await?;
+                match sockets.as_slice() {
+                    [socket] => (*socket, TcpSendWorker::connect(*socket).await?),
+                    _ => connect_first(sockets, CONNECTION_ATTEMPT_DELAY).await?,
+                }
+            }
+        };
+        let (read_half, write_half) = match &tls {
+            Some(tls) => TcpSendWorker::split_tls(tls.connect(&peer, stream).await?),
+            None => TcpSendWorker::split(stream),
+        };
 
         let mode = TcpConnectionMode::Outgoing;
         let addresses = Addresses::generate(mode);
@@ -70,4 +113,18 @@ impl TcpTransport {
     pub async fn disconnect(&self, address: impl Into<Address>) -> Result<()> {
         self.ctx.stop_worker(address.into()).await
     }
+
+    /// Resolve a peer given as `host:port` with the resolver of the transport
+    async fn resolve_addresses(&self, peer: &str) -> Result<Vec<SocketAddr>> {
+        if let Ok(address) = parse_socket_addr(peer) {
+            return Ok(vec![address]);
+        }
+        let (host, port) = peer
+            .rsplit_once(':')
+            .ok_or(TransportError::InvalidAddress)?;
+        let port = port
+            .parse::<u16>()
+            .map_err(|_| TransportError::InvalidAddress)?;
+        self.resolver().resolve(host, port).await
+    }
 }
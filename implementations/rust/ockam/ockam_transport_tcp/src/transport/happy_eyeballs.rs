@@ -0,0 +1,101 @@
+//! Connection to a peer with several addresses, following the "Happy Eyeballs" algorithm
+//! of RFC 8305.
+//!
+//! When the host name of a peer resolves to several IPv4 and IPv6 addresses, the connection
+//! attempts are started one after the other, alternating the address families, without waiting
+//! for the previous attempts to fail. The first connection to succeed is used and the other
+//! attempts are cancelled, so that a partially unreachable peer doesn't delay the connection.
+
+use crate::workers::TcpSendWorker;
+use core::time::Duration;
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::Result;
+use ockam_transport_core::TransportError;
+use tokio::net::TcpStream;
+use tokio::task::JoinSet;
+use tracing::debug;
+
+/// Delay before starting the next connection attempt, when the previous ones are still
+/// in progress (the "Connection Attempt Delay" of RFC 8305)
+pub(crate) const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Connect to the first reachable address, racing the connection attempts
+pub(crate) async fn connect_first(
+    addresses: Vec<SocketAddr>,
+    attempt_delay: Duration,
+) -> Result<(SocketAddr, TcpStream)> {
+    let mut addresses = interleave_families(addresses).into_iter().peekable();
+    let mut attempts = JoinSet::new();
+    let mut last_error = None;
+    loop {
+        if let Some(address) = addresses.next() {
+            attempts.spawn(async move { (address, TcpSendWorker::connect(address).await) });
+        }
+        if attempts.is_empty() {
+            break;
+        }
+
+        let more_addresses = addresses.peek().is_some();
+        tokio::select! {
+            Some(result) = attempts.join_next() => match result {
+                Ok((address, Ok(stream))) => {
+                    debug!(%address, "Connected to the first reachable address");
+                    return Ok((address, stream));
+                }
+                Ok((_, Err(e))) => last_error = Some(e),
+                Err(_) => last_error = Some(TransportError::GenericIo.into()),
+            },
+            _ = tokio::time::sleep(attempt_delay), if more_addresses => {},
+            else => break,
+        }
+    }
+    Err(last_error.unwrap_or_else(|| TransportError::InvalidAddress.into()))
+}
+
+/// Order the addresses by alternating their families, starting with the family of the
+/// first address. The relative order of the addresses of a family is kept
+fn interleave_families(addresses: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let first_is_ipv6 = addresses.first().map_or(false, |a| a.is_ipv6());
+    let (mut first, mut second): (Vec<_>, Vec<_>) = addresses
+        .into_iter()
+        .partition(|a| a.is_ipv6() == first_is_ipv6);
+    let mut interleaved = Vec::with_capacity(first.len() + second.len());
+    first.reverse();
+    second.reverse();
+    while let Some(address) = first.pop() {
+        interleaved.push(address);
+        if let Some(address) = second.pop() {
+            interleaved.push(address);
+        }
+    }
+    interleaved.extend(second.into_iter().rev());
+    interleaved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn families_are_interleaved() {
+        let addresses: Vec<SocketAddr> = ["[::1]:1", "[::2]:1", "[::3]:1", "10.0.0.1:1"]
+            .iter()
+            .map(|a| a.parse().unwrap())
+            .collect();
+        let expected: Vec<SocketAddr> = ["[::1]:1", "10.0.0.1:1", "[::2]:1", "[::3]:1"]
+            .iter()
+            .map(|a| a.parse().unwrap())
+            .collect();
+        assert_eq!(interleave_families(addresses), expected);
+
+        let addresses: Vec<SocketAddr> = ["10.0.0.1:1", "[::1]:1", "10.0.0.2:1", "10.0.0.3:1"]
+            .iter()
+            .map(|a| a.parse().unwrap())
+            .collect();
+        let expected: Vec<SocketAddr> = ["10.0.0.1:1", "[::1]:1", "10.0.0.2:1", "10.0.0.3:1"]
+            .iter()
+            .map(|a| a.parse().unwrap())
+            .collect();
+        assert_eq!(interleave_families(addresses), expected);
+    }
+}
@@ -2,9 +2,9 @@ use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, Address, AsyncTryClone, Error, Result, TransportType};
 use ockam_node::Context;
 use ockam_transport_core::Transport;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
-use crate::{TcpConnectionOptions, TcpRegistry, TcpTransport, TCP};
+use crate::{SystemDnsResolver, TcpConnectionOptions, TcpRegistry, TcpTransport, TCP};
 
 impl TcpTransport {
     /// Create a TCP transport
@@ -21,6 +21,8 @@ impl TcpTransport {
         let tcp = Self {
             ctx: ctx.async_try_clone().await?,
             registry: TcpRegistry::default(),
+            proxy: Default::default(),
+            resolver: Arc::new(RwLock::new(Arc::new(SystemDnsResolver))),
         };
         // make the TCP transport available in the list of supported transports for
         // later address resolution when socket addresses will need to be instantiated as TCP
@@ -1,14 +1,22 @@
 pub(crate) mod common;
 mod connection;
+mod happy_eyeballs;
 mod lifecycle;
 mod listener;
 mod portals;
+mod proxy;
+mod resolver;
+mod tls;
 
 pub use common::*;
+pub use proxy::*;
+pub use resolver::*;
+pub use tls::*;
 
 pub use crate::portal::options::*;
 
 use crate::TcpRegistry;
+use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::{async_trait, AsyncTryClone, Result};
 use ockam_node::{Context, HasContext};
 
@@ -55,6 +63,32 @@ use ockam_node::{Context, HasContext};
 pub struct TcpTransport {
     ctx: Context,
     registry: TcpRegistry,
+    proxy: Arc<RwLock<Option<TcpProxy>>>,
+    resolver: Arc<RwLock<Arc<dyn DnsResolver>>>,
+}
+
+impl TcpTransport {
+    /// Open the outgoing connections via a proxy, or directly if `None` is given.
+    /// The connections which are already established are not modified
+    pub fn set_proxy(&self, proxy: Option<TcpProxy>) {
+        *self.proxy.write().unwrap() = proxy;
+    }
+
+    /// Proxy used to open the outgoing connections
+    pub fn proxy(&self) -> Option<TcpProxy> {
+        self.proxy.read().unwrap().clone()
+    }
+
+    /// Resolve the host names of the peers with another resolver than the one of the
+    /// operating system, for example a [`CustomDnsResolver`] using DNS over HTTPS
+    pub fn set_resolver(&self, resolver: Arc<dyn DnsResolver>) {
+        *self.resolver.write().unwrap() = resolver;
+    }
+
+    /// Resolver of the host names of the peers
+    pub fn resolver(&self) -> Arc<dyn DnsResolver> {
+        self.resolver.read().unwrap().clone()
+    }
 }
 
 /// This trait adds a `create_tcp_transport` method to any struct returning a Context.
@@ -0,0 +1,264 @@
+use core::fmt;
+use core::str::FromStr;
+use hickory_resolver::config::{
+    LookupIpStrategy, NameServerConfig, Protocol, ResolverConfig, ResolverOpts,
+};
+use hickory_resolver::TokioAsyncResolver;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, Error, Result};
+use ockam_transport_core::TransportError;
+use std::net::{IpAddr, SocketAddr};
+use tracing::debug;
+
+/// Number of DNS answers cached by a [`CustomDnsResolver`]
+const DNS_CACHE_SIZE: usize = 256;
+
+/// Resolution of the host names of the peers of a [`TcpTransport`](crate::TcpTransport)
+#[async_trait]
+pub trait DnsResolver: fmt::Debug + Send + Sync + 'static {
+    /// Return the addresses of a host, with the given port
+    async fn resolve(&self, host: &str, port: u16) -> Result<Vec<SocketAddr>>;
+}
+
+/// Resolver using the DNS configuration of the operating system
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemDnsResolver;
+
+#[async_trait]
+impl DnsResolver for SystemDnsResolver {
+    async fn resolve(&self, host: &str, port: u16) -> Result<Vec<SocketAddr>> {
+        let addresses = tokio::net::lookup_host((host, port)).await.map_err(|e| {
+            debug!(%host, %e, "Failed to resolve a host name");
+            TransportError::InvalidAddress
+        })?;
+        let mut resolved: Vec<SocketAddr> = vec![];
+        for address in addresses {
+            if !resolved.contains(&address) {
+                resolved.push(address);
+            }
+        }
+        if resolved.is_empty() {
+            return Err(TransportError::InvalidAddress.into());
+        }
+        Ok(resolved)
+    }
+}
+
+/// Protocol used to query a [`DnsServer`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DnsProtocol {
+    /// Plain DNS over UDP, falling back to TCP for the long answers
+    Udp,
+    /// DNS over TLS (RFC 7858)
+    Tls,
+    /// DNS over HTTPS (RFC 8484)
+    Https,
+}
+
+/// DNS server queried by a [`CustomDnsResolver`].
+///
+/// A server is parsed from `IP[:PORT]` for plain DNS, or from `tls://NAME@IP[:PORT]` and
+/// `https://NAME@IP[:PORT]` for DNS over TLS and DNS over HTTPS, where `NAME` is the name
+/// verified in the certificate of the server. For example `https://cloudflare-dns.com@1.1.1.1`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DnsServer {
+    protocol: DnsProtocol,
+    address: SocketAddr,
+    tls_name: Option<String>,
+}
+
+impl DnsServer {
+    /// Plain DNS server
+    pub fn udp(address: SocketAddr) -> Self {
+        Self {
+            protocol: DnsProtocol::Udp,
+            address,
+            tls_name: None,
+        }
+    }
+
+    /// DNS over TLS server, whose certificate is issued for `tls_name`
+    pub fn tls(address: SocketAddr, tls_name: impl Into<String>) -> Self {
+        Self {
+            protocol: DnsProtocol::Tls,
+            address,
+            tls_name: Some(tls_name.into()),
+        }
+    }
+
+    /// DNS over HTTPS server, whose certificate is issued for `tls_name`
+    pub fn https(address: SocketAddr, tls_name: impl Into<String>) -> Self {
+        Self {
+            protocol: DnsProtocol::Https,
+            address,
+            tls_name: Some(tls_name.into()),
+        }
+    }
+
+    /// Protocol used to query the server
+    pub fn protocol(&self) -> DnsProtocol {
+        self.protocol
+    }
+
+    /// Address of the server
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    fn name_server_config(&self) -> NameServerConfig {
+        let protocol = match self.protocol {
+            DnsProtocol::Udp => Protocol::Udp,
+            DnsProtocol::Tls => Protocol::Tls,
+            DnsProtocol::Https => Protocol::Https,
+        };
+        let mut config = NameServerConfig::new(self.address, protocol);
+        config.tls_dns_name = self.tls_name.clone();
+        config
+    }
+}
+
+impl FromStr for DnsServer {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                format!("invalid DNS server {s}, expected IP[:PORT], tls://NAME@IP[:PORT] or https://NAME@IP[:PORT]"),
+            )
+        };
+        let (protocol, rest) = match s.split_once("://") {
+            None => (DnsProtocol::Udp, s),
+            Some(("udp", rest)) => (DnsProtocol::Udp, rest),
+            Some(("tls", rest)) => (DnsProtocol::Tls, rest),
+            Some(("https", rest)) => (DnsProtocol::Https, rest),
+            Some(_) => return Err(invalid()),
+        };
+        let (tls_name, address) = match rest.split_once('@') {
+            Some((name, address)) if !name.is_empty() => (Some(name.to_string()), address),
+            Some(_) => return Err(invalid()),
+            None => (None, rest),
+        };
+        let default_port = match protocol {
+            DnsProtocol::Udp => 53,
+            DnsProtocol::Tls => 853,
+            DnsProtocol::Https => 443,
+        };
+        let address = match address.parse::<SocketAddr>() {
+            Ok(address) => address,
+            Err(_) => SocketAddr::new(
+                address
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .parse::<IpAddr>()
+                    .map_err(|_| invalid())?,
+                default_port,
+            ),
+        };
+        match (protocol, tls_name) {
+            (DnsProtocol::Udp, None) => Ok(Self::udp(address)),
+            (DnsProtocol::Tls, Some(name)) => Ok(Self::tls(address, name)),
+            (DnsProtocol::Https, Some(name)) => Ok(Self::https(address, name)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for DnsServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.protocol, &self.tls_name) {
+            (DnsProtocol::Tls, Some(name)) => write!(f, "tls://{name}@{}", self.address),
+            (DnsProtocol::Https, Some(name)) => write!(f, "https://{name}@{}", self.address),
+            _ => write!(f, "{}", self.address),
+        }
+    }
+}
+
+/// Resolver querying some DNS servers instead of the ones of the operating system,
+/// possibly over TLS or HTTPS.
+///
+/// The answers are cached for the duration of their TTL.
+pub struct CustomDnsResolver {
+    servers: Vec<DnsServer>,
+    resolver: TokioAsyncResolver,
+}
+
+impl CustomDnsResolver {
+    /// Create a resolver querying the given servers, in order
+    pub fn new(servers: Vec<DnsServer>) -> Result<Self> {
+        if servers.is_empty() {
+            return Err(Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "at least one DNS server must be given",
+            ));
+        }
+        let mut config = ResolverConfig::new();
+        for server in &servers {
+            config.add_name_server(server.name_server_config());
+        }
+        let mut options = ResolverOpts::default();
+        options.cache_size = DNS_CACHE_SIZE;
+        // Both families are resolved, to race the connections to all the addresses of a peer
+        options.ip_strategy = LookupIpStrategy::Ipv4AndIpv6;
+        Ok(Self {
+            servers,
+            resolver: TokioAsyncResolver::tokio(config, options),
+        })
+    }
+
+    /// Servers queried by this resolver
+    pub fn servers(&self) -> &[DnsServer] {
+        &self.servers
+    }
+}
+
+impl fmt::Debug for CustomDnsResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomDnsResolver")
+            .field("servers", &self.servers)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl DnsResolver for CustomDnsResolver {
+    async fn resolve(&self, host: &str, port: u16) -> Result<Vec<SocketAddr>> {
+        let lookup = self.resolver.lookup_ip(host).await.map_err(|e| {
+            debug!(%host, %e, "Failed to resolve a host name");
+            TransportError::InvalidAddress
+        })?;
+        let addresses: Vec<SocketAddr> =
+            lookup.iter().map(|ip| SocketAddr::new(ip, port)).collect();
+        if addresses.is_empty() {
+            return Err(TransportError::InvalidAddress.into());
+        }
+        Ok(addresses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dns_servers() {
+        let server = DnsServer::from_str("1.1.1.1").unwrap();
+        assert_eq!(server, DnsServer::udp("1.1.1.1:53".parse().unwrap()));
+
+        let server = DnsServer::from_str("tls://dns.example.com@[2001:db8::1]").unwrap();
+        assert_eq!(server.protocol(), DnsProtocol::Tls);
+        assert_eq!(server.address(), "[2001:db8::1]:853".parse().unwrap());
+
+        let server = DnsServer::from_str("https://cloudflare-dns.com@1.1.1.1:8443").unwrap();
+        assert_eq!(
+            server.to_string(),
+            "https://cloudflare-dns.com@1.1.1.1:8443"
+        );
+
+        assert!(DnsServer::from_str("https://1.1.1.1").is_err());
+        assert!(DnsServer::from_str("quic://dns.example.com@1.1.1.1").is_err());
+        assert!(DnsServer::from_str("dns.example.com").is_err());
+    }
+}
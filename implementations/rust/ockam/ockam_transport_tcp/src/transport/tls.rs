@@ -0,0 +1,132 @@
+use crate::portal::{error, parse_certificates, parse_private_key, root_certificates};
+use core::fmt;
+use core::str::FromStr;
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use std::net::IpAddr;
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::{ClientConfig, ServerConfig, ServerName};
+use tokio_rustls::{client, server, TlsAcceptor, TlsConnector};
+
+/// Prefix of the peer addresses which must be reached over TLS, for example `tls://example.com:443`
+pub const TLS_PREFIX: &str = "tls://";
+
+/// TLS configuration of a TCP listener wrapping the accepted connections in TLS,
+/// so that they look like standard TLS connections to the middleboxes inspecting the traffic
+#[derive(Clone)]
+pub struct TcpListenerTls {
+    config: Arc<ServerConfig>,
+}
+
+impl TcpListenerTls {
+    /// Create a TLS configuration presenting a PEM certificate chain, with its PEM private key
+    pub fn new(certificate_chain: &[u8], private_key: &[u8]) -> Result<Self> {
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(
+                parse_certificates(certificate_chain)?,
+                parse_private_key(private_key)?,
+            )
+            .map_err(error)?;
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+
+    /// Run the TLS handshake of an accepted connection
+    pub(crate) async fn accept(&self, stream: TcpStream) -> Result<server::TlsStream<TcpStream>> {
+        TlsAcceptor::from(self.config.clone())
+            .accept(stream)
+            .await
+            .map_err(|e| Error::new(Origin::Transport, Kind::Io, e))
+    }
+}
+
+impl fmt::Debug for TcpListenerTls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TcpListenerTls").finish()
+    }
+}
+
+/// TLS configuration of an outgoing TCP connection wrapped in TLS
+#[derive(Clone)]
+pub struct TcpConnectionTls {
+    config: Arc<ClientConfig>,
+    server_name: Option<ServerName>,
+}
+
+impl TcpConnectionTls {
+    /// Create a TLS configuration verifying the certificate of the peer with the
+    /// certificates of a PEM bundle, or with the native root certificates if no bundle is given
+    pub fn new(ca_certificates: Option<&[u8]>) -> Result<Self> {
+        let config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(root_certificates(ca_certificates)?)
+            .with_no_client_auth();
+        Ok(Self {
+            config: Arc::new(config),
+            server_name: None,
+        })
+    }
+
+    /// Send this name with SNI and verify it against the certificate of the peer,
+    /// instead of the host of the peer address
+    pub fn with_server_name(mut self, server_name: &str) -> Result<Self> {
+        self.server_name = Some(ServerName::try_from(server_name).map_err(error)?);
+        Ok(self)
+    }
+
+    /// Start a TLS session on a connection to a `host:port` peer
+    pub(crate) async fn connect(
+        &self,
+        peer: &str,
+        stream: TcpStream,
+    ) -> Result<client::TlsStream<TcpStream>> {
+        let server_name = match &self.server_name {
+            Some(server_name) => server_name.clone(),
+            None => server_name(peer)?,
+        };
+        TlsConnector::from(self.config.clone())
+            .connect(server_name, stream)
+            .await
+            .map_err(|e| Error::new(Origin::Transport, Kind::Io, e))
+    }
+}
+
+impl fmt::Debug for TcpConnectionTls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TcpConnectionTls")
+            .field("server_name", &self.server_name)
+            .finish()
+    }
+}
+
+/// Return the name verified against the certificate of a `host:port` peer
+fn server_name(peer: &str) -> Result<ServerName> {
+    let host = peer.rsplit_once(':').map(|(host, _)| host).unwrap_or(peer);
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    match IpAddr::from_str(host) {
+        Ok(ip) => Ok(ServerName::IpAddress(ip)),
+        Err(_) => ServerName::try_from(host).map_err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_names() {
+        assert_eq!(
+            server_name("example.com:443").unwrap(),
+            ServerName::try_from("example.com").unwrap()
+        );
+        assert_eq!(
+            server_name("[::1]:443").unwrap(),
+            ServerName::IpAddress("::1".parse().unwrap())
+        );
+        assert!(TcpListenerTls::new(b"not a certificate", b"not a key").is_err());
+    }
+}
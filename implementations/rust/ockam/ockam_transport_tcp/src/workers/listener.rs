@@ -1,11 +1,16 @@
+use crate::transport::common::bind_tcp_listener;
 use crate::workers::{Addresses, TcpRecvProcessor};
 use crate::{TcpConnectionMode, TcpListenerInfo, TcpListenerOptions, TcpRegistry, TcpSendWorker};
+use core::time::Duration;
 use ockam_core::{async_trait, compat::net::SocketAddr};
 use ockam_core::{Address, Processor, Result};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
 use tokio::net::TcpListener;
-use tracing::debug;
+use tracing::{debug, warn};
+
+/// Maximum duration of the TLS handshake of an accepted connection
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// A TCP Listen processor
 ///
@@ -27,9 +32,7 @@ impl TcpListenProcessor {
         options: TcpListenerOptions,
     ) -> Result<(SocketAddr, Address)> {
         debug!("Binding TcpListener to {}", addr);
-        let inner = TcpListener::bind(addr)
-            .await
-            .map_err(TransportError::from)?;
+        let inner = bind_tcp_listener(addr).map_err(TransportError::from)?;
         let saddr = inner.local_addr().map_err(TransportError::from)?;
 
         let address = Address::random_tagged("TcpListenProcessor");
@@ -77,6 +80,23 @@ impl Processor for TcpListenProcessor {
         let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
         debug!("TCP connection accepted");
 
+        let (read_half, write_half) = match &self.options.tls {
+            Some(tls) => {
+                match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, tls.accept(stream)).await {
+                    Ok(Ok(stream)) => TcpSendWorker::split_tls(stream),
+                    Ok(Err(e)) => {
+                        warn!(%peer, %e, "TLS handshake failed");
+                        return Ok(true);
+                    }
+                    Err(_) => {
+                        warn!(%peer, "TLS handshake timed out");
+                        return Ok(true);
+                    }
+                }
+            }
+            None => TcpSendWorker::split(stream),
+        };
+
         let mode = TcpConnectionMode::Incoming;
         let addresses = Addresses::generate(mode);
 
@@ -87,8 +107,6 @@ impl Processor for TcpListenProcessor {
             .options
             .create_access_control(ctx.flow_controls(), receiver_flow_control_id.clone());
 
-        let (read_half, write_half) = stream.into_split();
-
         // Worker to receive messages from the Node and send them over the wire
         TcpSendWorker::start(
             ctx,
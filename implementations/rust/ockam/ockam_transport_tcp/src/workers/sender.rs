@@ -16,11 +16,16 @@ use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::TransportError;
 use serde::{Deserialize, Serialize};
 use socket2::{SockRef, TcpKeepalive};
-use tokio::io::AsyncWriteExt;
-use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 use tokio::net::TcpStream;
 use tracing::{debug, info, trace, warn};
 
+/// Read half of a connection, which can be wrapped in TLS
+pub(crate) type TcpReadHalf = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Write half of a connection, which can be wrapped in TLS
+pub(crate) type TcpWriteHalf = Box<dyn AsyncWrite + Send + Unpin>;
+
 #[derive(Serialize, Deserialize, Message, Clone)]
 pub(crate) enum TcpSendWorkerMsg {
     ConnectionClosed,
@@ -36,7 +41,7 @@ pub(crate) enum TcpSendWorkerMsg {
 /// to dispatch to a remote peer.
 pub(crate) struct TcpSendWorker {
     registry: TcpRegistry,
-    write_half: OwnedWriteHalf,
+    write_half: TcpWriteHalf,
     socket_address: SocketAddr,
     addresses: Addresses,
     mode: TcpConnectionMode,
@@ -48,7 +53,7 @@ impl TcpSendWorker {
     /// Create a new `TcpSendWorker`
     fn new(
         registry: TcpRegistry,
-        write_half: OwnedWriteHalf,
+        write_half: TcpWriteHalf,
         socket_address: SocketAddr,
         addresses: Addresses,
         mode: TcpConnectionMode,
@@ -73,7 +78,7 @@ impl TcpSendWorker {
     pub(crate) async fn start(
         ctx: &Context,
         registry: TcpRegistry,
-        write_half: OwnedWriteHalf,
+        write_half: TcpWriteHalf,
         addresses: &Addresses,
         socket_address: SocketAddr,
         mode: TcpConnectionMode,
@@ -119,9 +124,7 @@ impl TcpSendWorker {
         Ok(())
     }
 
-    pub(crate) async fn connect(
-        socket_address: SocketAddr,
-    ) -> Result<(OwnedReadHalf, OwnedWriteHalf)> {
+    pub(crate) async fn connect(socket_address: SocketAddr) -> Result<TcpStream> {
         debug!(addr = %socket_address, "Connecting");
         let connection = match TcpStream::connect(socket_address).await {
             Ok(c) => {
@@ -134,6 +137,12 @@ impl TcpSendWorker {
             }
         };
 
+        Self::set_keepalive(&connection);
+        Ok(connection)
+    }
+
+    /// Enable the keepalive probes of an established connection
+    pub(crate) fn set_keepalive(connection: &TcpStream) {
         let mut keepalive = TcpKeepalive::new()
             .with_time(Duration::from_secs(300))
             .with_interval(Duration::from_secs(75));
@@ -144,10 +153,23 @@ impl TcpSendWorker {
             }
         }
 
-        let socket = SockRef::from(&connection);
+        let socket = SockRef::from(connection);
         socket.set_tcp_keepalive(&keepalive).unwrap();
+    }
+
+    /// Split a connection into its read and write halves
+    pub(crate) fn split(connection: TcpStream) -> (TcpReadHalf, TcpWriteHalf) {
+        let (read_half, write_half) = connection.into_split();
+        (Box::new(read_half), Box::new(write_half))
+    }
 
-        Ok(connection.into_split())
+    /// Split a connection wrapped in TLS into its read and write halves
+    pub(crate) fn split_tls<S>(connection: S) -> (TcpReadHalf, TcpWriteHalf)
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let (read_half, write_half) = tokio::io::split(connection);
+        (Box::new(read_half), Box::new(write_half))
     }
 }
 
@@ -130,6 +130,34 @@ async fn tcp_lifecycle__disconnect__should_stop_worker(ctx: &mut Context) -> Res
     Ok(())
 }
 
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tcp_lifecycle__connections_to_same_peer__should_have_their_own_flow_control(
+    ctx: &mut Context,
+) -> Result<()> {
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport
+        .listen("127.0.0.1:0", TcpListenerOptions::new())
+        .await?;
+
+    let connection1 = transport
+        .connect(listener.socket_string(), TcpConnectionOptions::new())
+        .await?;
+    let connection2 = transport
+        .connect(listener.socket_string(), TcpConnectionOptions::new())
+        .await?;
+    assert_ne!(connection1.sender_address(), connection2.sender_address());
+    assert_ne!(connection1.flow_control_id(), connection2.flow_control_id());
+
+    transport.disconnect(connection1).await?;
+    transport.disconnect(connection2).await?;
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
 #[allow(non_snake_case)]
 #[ockam_macros::test]
 async fn tcp_lifecycle__stop_listener__should_stop_accepting_connections(
@@ -11,8 +11,7 @@ use ockam_core::TransportType;
 
 pub use hole_puncher::{PunchError, UdpHolePuncher};
 pub use rendezvous_service::UdpRendezvousService;
-pub use transport::UdpTransport;
-pub use transport::UdpTransportExtension;
+pub use transport::{UdpListener, UdpListenerOptions, UdpTransport, UdpTransportExtension};
 
 mod hole_puncher;
 mod rendezvous_service;
@@ -1,6 +1,9 @@
 use crate::router::messages::{UdpRouterRequest, UdpRouterResponse};
+use crate::UdpListener;
+use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Address, AllowAll, Result};
 use ockam_node::Context;
+use ockam_transport_core::TransportError;
 use std::net::SocketAddr;
 
 /// A handle to connect to a UdpRouter
@@ -31,12 +34,35 @@ impl UdpRouterHandle {
 
     /// Request router start listening on a local UDP port
     /// so the local node can act as a server to other nodes
-    pub async fn listen(&self, local_addr: SocketAddr) -> Result<()> {
-        let msg = UdpRouterRequest::Listen { local_addr };
-        let UdpRouterResponse::Listen(res) = self
+    pub async fn listen(
+        &self,
+        local_addr: SocketAddr,
+        flow_control_id: Option<FlowControlId>,
+    ) -> Result<UdpListener> {
+        let msg = UdpRouterRequest::Listen {
+            local_addr,
+            flow_control_id,
+        };
+        match self
             .ctx
             .send_and_receive(self.api_addr.clone(), msg)
-            .await?;
-        res
+            .await?
+        {
+            UdpRouterResponse::Listen(res) => res,
+            _ => Err(TransportError::InvalidRouterResponseType.into()),
+        }
+    }
+
+    /// Request router to stop listening on a local UDP port
+    pub async fn stop_listener(&self, listener: UdpListener) -> Result<()> {
+        let msg = UdpRouterRequest::StopListener { listener };
+        match self
+            .ctx
+            .send_and_receive(self.api_addr.clone(), msg)
+            .await?
+        {
+            UdpRouterResponse::StopListener(res) => res,
+            _ => Err(TransportError::InvalidRouterResponseType.into()),
+        }
     }
 }
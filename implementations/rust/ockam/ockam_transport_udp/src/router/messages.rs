@@ -1,3 +1,5 @@
+use crate::UdpListener;
+use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Message, Result};
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
@@ -6,10 +8,16 @@ use std::net::SocketAddr;
 pub enum UdpRouterRequest {
     /// Listen on a local UDP port so the local node can
     /// act as a server to other nodes
-    Listen { local_addr: SocketAddr },
+    Listen {
+        local_addr: SocketAddr,
+        flow_control_id: Option<FlowControlId>,
+    },
+    /// Stop listening on a local UDP port
+    StopListener { listener: UdpListener },
 }
 
 #[derive(Serialize, Deserialize, Debug, Message)]
 pub enum UdpRouterResponse {
-    Listen(Result<()>),
+    Listen(Result<UdpListener>),
+    StopListener(Result<()>),
 }
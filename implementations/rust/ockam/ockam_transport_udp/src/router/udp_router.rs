@@ -1,10 +1,12 @@
 use crate::router::messages::{UdpRouterRequest, UdpRouterResponse};
 use crate::router::UdpRouterHandle;
-use crate::workers::{TransportMessageCodec, UdpListenProcessor, UdpSendWorker};
+use crate::workers::{TransportMessageCodec, UdpListenProcessor, UdpSendWorker, UdpSocketSender};
+use crate::UdpListener;
 use futures_util::StreamExt;
+use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl};
 use ockam_core::{
     async_trait, Address, AllowAll, Any, Decodable, DenyAll, LocalMessage, Mailbox, Mailboxes,
-    Result, Routed, Worker,
+    OutgoingAccessControl, Result, Routed, Worker,
 };
 use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::TransportError;
@@ -62,8 +64,10 @@ impl UdpRouter {
         let client_sender = Self::create_sender_listener(
             &child_ctx,
             SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 0),
+            None,
         )
-        .await?;
+        .await?
+        .sender_address;
 
         let router = Self {
             ctx: child_ctx,
@@ -103,8 +107,15 @@ impl UdpRouter {
 
     /// Create a sender, listener pair for the given socket address.
     ///
-    /// Returns the address of the created sender.
-    async fn create_sender_listener(ctx: &Context, local_addr: SocketAddr) -> Result<Address> {
+    /// When a flow control id is given, the listener is a producer for that flow control id,
+    /// and the received messages can only be sent to its consumers.
+    ///
+    /// Returns the bound socket address and the addresses of the created sender and listener.
+    async fn create_sender_listener(
+        ctx: &Context,
+        local_addr: SocketAddr,
+        flow_control_id: Option<FlowControlId>,
+    ) -> Result<UdpListener> {
         // This transport only supports IPv4
         if !local_addr.is_ipv4() {
             error!(local_addr = %local_addr, "This transport only supprts IPv4");
@@ -115,22 +126,57 @@ impl UdpRouter {
         let socket = UdpSocket::bind(local_addr)
             .await
             .map_err(|_| TransportError::InvalidAddress)?;
+        // Could be different from the local_addr, e.g., if binding to port 0
+        let socket_address = socket
+            .local_addr()
+            .map_err(|_| TransportError::InvalidAddress)?;
 
         // Split socket into sink and stream
         let (sink, stream) = UdpFramed::new(socket, TransportMessageCodec).split();
+        let socket_sender = UdpSocketSender::start(sink);
 
-        debug!("Creating new sender and listener for {}", local_addr);
+        debug!("Creating new sender and listener for {}", socket_address);
 
         // Create sender
         let sender_addr = Address::random_tagged("UdpSendWorker");
-        let sender = UdpSendWorker::new(sink);
+        let sender = UdpSendWorker::new(socket_sender.clone());
         // FIXME: @ac
         ctx.start_worker(sender_addr.clone(), sender).await?;
 
         // Create listener
-        UdpListenProcessor::start(ctx, stream, sender_addr.clone()).await?;
+        let listener_addr = Address::random_tagged("UdpListenProcessor");
+        let outgoing_access_control: Arc<dyn OutgoingAccessControl> = match &flow_control_id {
+            Some(flow_control_id) => {
+                ctx.flow_controls().add_producer(
+                    listener_addr.clone(),
+                    flow_control_id,
+                    None,
+                    vec![sender_addr.clone()],
+                );
+                Arc::new(FlowControlOutgoingAccessControl::new(
+                    ctx.flow_controls(),
+                    flow_control_id.clone(),
+                    None,
+                ))
+            }
+            None => Arc::new(AllowAll),
+        };
+        UdpListenProcessor::start(
+            ctx,
+            listener_addr.clone(),
+            stream,
+            socket_sender,
+            sender_addr.clone(),
+            outgoing_access_control,
+        )
+        .await?;
 
-        Ok(sender_addr)
+        Ok(UdpListener {
+            socket_address,
+            sender_address: sender_addr,
+            processor_address: listener_addr,
+            flow_control_id,
+        })
     }
 }
 
@@ -162,12 +208,30 @@ impl Worker for UdpRouter {
             let msg = UdpRouterRequest::decode(msg.payload())?;
             trace!("handle_message() API_ADDR: msg = {:?}", msg);
             match msg {
-                UdpRouterRequest::Listen { local_addr } => {
-                    let res = Self::create_sender_listener(&self.ctx, local_addr).await;
-                    let res = res.map(|_| ());
+                UdpRouterRequest::Listen {
+                    local_addr,
+                    flow_control_id,
+                } => {
+                    let res =
+                        Self::create_sender_listener(&self.ctx, local_addr, flow_control_id).await;
                     ctx.send_from_address(return_route, UdpRouterResponse::Listen(res), msg_addr)
                         .await?;
                 }
+                UdpRouterRequest::StopListener { listener } => {
+                    let res = async {
+                        self.ctx
+                            .stop_processor(listener.processor_address.clone())
+                            .await?;
+                        self.ctx.stop_worker(listener.sender_address.clone()).await
+                    }
+                    .await;
+                    ctx.send_from_address(
+                        return_route,
+                        UdpRouterResponse::StopListener(res),
+                        msg_addr,
+                    )
+                    .await?;
+                }
             };
         } else {
             return Err(TransportError::Protocol.into());
@@ -1,12 +1,18 @@
 use crate::router::{UdpRouter, UdpRouterHandle};
-use ockam_core::{async_trait, Result};
+use ockam_core::flow_control::{FlowControlId, FlowControls};
+use ockam_core::{async_trait, Address, Result};
 use ockam_node::{Context, HasContext};
 use ockam_transport_core::TransportError;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 
 /// High level management interface for UDP transport
 ///
 /// A node will have, at most, one UDP transport running.
 ///
+/// The routing messages are acknowledged by the receiving node, sent again when they are lost,
+/// and delivered in the order they were sent.
+///
 /// This transport only supports IPv4.
 pub struct UdpTransport {
     router_handle: UdpRouterHandle,
@@ -21,12 +27,89 @@ impl UdpTransport {
 
     /// Start listening to incoming datagrams on a specified local address
     pub async fn listen<S: AsRef<str>>(&self, bind_addr: S) -> Result<()> {
-        let bind_addr = bind_addr
-            .as_ref()
-            .parse()
-            .map_err(|_| TransportError::InvalidAddress)?;
-        self.router_handle.listen(bind_addr).await
+        let bind_addr = parse_socket_addr(bind_addr.as_ref())?;
+        self.router_handle.listen(bind_addr, None).await?;
+        Ok(())
+    }
+
+    /// Start listening to incoming datagrams on a specified local address.
+    /// The received messages can only be sent to the consumers of the listener
+    /// [`FlowControlId`].
+    ///
+    /// Returns the listener, with the local address that it is bound to.
+    /// This can be useful, for example, when binding to port 0 to figure out
+    /// which port was actually bound.
+    pub async fn listen_with_options(
+        &self,
+        bind_addr: impl AsRef<str>,
+        options: UdpListenerOptions,
+    ) -> Result<UdpListener> {
+        let bind_addr = parse_socket_addr(bind_addr.as_ref())?;
+        self.router_handle
+            .listen(bind_addr, Some(options.flow_control_id))
+            .await
     }
+
+    /// Stop listening on the local address of a listener
+    pub async fn stop_listener(&self, listener: &UdpListener) -> Result<()> {
+        self.router_handle.stop_listener(listener.clone()).await
+    }
+}
+
+/// Trust Options for a UDP listener
+#[derive(Debug)]
+pub struct UdpListenerOptions {
+    flow_control_id: FlowControlId,
+}
+
+impl UdpListenerOptions {
+    /// Mark this UDP listener as a Producer with a random [`FlowControlId`]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            flow_control_id: FlowControls::generate_flow_control_id(),
+        }
+    }
+
+    /// Getter for freshly generated [`FlowControlId`]
+    pub fn flow_control_id(&self) -> FlowControlId {
+        self.flow_control_id.clone()
+    }
+}
+
+/// Result of [`UdpTransport::listen_with_options`] call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UdpListener {
+    pub(crate) socket_address: SocketAddr,
+    pub(crate) sender_address: Address,
+    pub(crate) processor_address: Address,
+    pub(crate) flow_control_id: Option<FlowControlId>,
+}
+
+impl UdpListener {
+    /// Local address the listener is bound to
+    pub fn socket_address(&self) -> SocketAddr {
+        self.socket_address
+    }
+
+    /// Address of the worker sending the replies to the received messages
+    pub fn sender_address(&self) -> &Address {
+        &self.sender_address
+    }
+
+    /// Address of the processor receiving the datagrams
+    pub fn processor_address(&self) -> &Address {
+        &self.processor_address
+    }
+
+    /// [`FlowControlId`] of the received messages, if the listener was created with options
+    pub fn flow_control_id(&self) -> Option<&FlowControlId> {
+        self.flow_control_id.as_ref()
+    }
+}
+
+fn parse_socket_addr(s: &str) -> Result<SocketAddr> {
+    s.parse().map_err(|_| TransportError::InvalidAddress.into())
 }
 
 /// This trait adds a `create_udp_transport` method to any struct returning a Context.
@@ -1,34 +1,163 @@
 use bytes::{Buf, BufMut, BytesMut};
-use ockam_core::TransportMessage;
+use ockam_core::Fragment;
 use ockam_core::{Decodable, Encodable};
 use ockam_transport_core::TransportError;
 use tokio_util::codec::{Decoder, Encoder};
 
+/// Tag of a datagram carrying a routing message
+const DATA: u8 = 0;
+/// Tag of a datagram acknowledging a routing message
+const ACK: u8 = 1;
+/// Size of the session and sequence number of a datagram
+const HEADER_LEN: usize = 4 + 8;
+/// Maximum size of a datagram, small enough to not be fragmented by IP on most networks
+const MAX_DATAGRAM_LEN: usize = 1200;
+/// Maximum size of an encoded fragment of a routing message
+pub(crate) const MAX_FRAGMENT_LEN: usize = MAX_DATAGRAM_LEN - 1 - HEADER_LEN - 2;
+
+/// Datagram exchanged by the UDP transport.
+///
+/// The routing messages are split into fragments which fit in a datagram. The fragments are
+/// numbered for each peer, and acknowledged by the peer, so that they can be sent again when
+/// they are lost, and delivered in order. See [`Reliability`](super::Reliability)
+#[derive(Debug, Clone)]
+pub(crate) enum UdpPacket {
+    /// A fragment of a routing message
+    Data {
+        /// Random identifier of the socket sending the fragment
+        session: u32,
+        /// Sequence number of the fragment for its destination
+        seq: u64,
+        fragment: Fragment,
+    },
+    /// Acknowledgement of a fragment
+    Ack { session: u32, seq: u64 },
+}
+
 pub(crate) struct TransportMessageCodec;
 
-impl Encoder<TransportMessage> for TransportMessageCodec {
+impl Encoder<UdpPacket> for TransportMessageCodec {
     type Error = TransportError;
-    fn encode(&mut self, item: TransportMessage, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        let msg_buf = item.encode().map_err(|_| TransportError::SendBadMessage)?;
-        let len = msg_buf.len();
-        dst.put_u16(len as u16);
-        dst.put(&msg_buf[..]);
+    fn encode(&mut self, item: UdpPacket, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        match item {
+            UdpPacket::Data {
+                session,
+                seq,
+                fragment,
+            } => {
+                let msg_buf = fragment
+                    .encode()
+                    .map_err(|_| TransportError::SendBadMessage)?;
+                let len = u16::try_from(msg_buf.len()).map_err(|_| TransportError::Capacity)?;
+                dst.put_u8(DATA);
+                dst.put_u32(session);
+                dst.put_u64(seq);
+                dst.put_u16(len);
+                dst.put(&msg_buf[..]);
+            }
+            UdpPacket::Ack { session, seq } => {
+                dst.put_u8(ACK);
+                dst.put_u32(session);
+                dst.put_u64(seq);
+            }
+        }
         Ok(())
     }
 }
 
 impl Decoder for TransportMessageCodec {
-    type Item = TransportMessage;
+    type Item = UdpPacket;
     type Error = TransportError;
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
         if src.is_empty() {
             return Ok(None);
         }
+        if src.len() < 1 + HEADER_LEN {
+            src.clear();
+            return Err(TransportError::RecvBadMessage);
+        }
+
+        let tag = src.get_u8();
+        let session = src.get_u32();
+        let seq = src.get_u64();
+        match tag {
+            DATA => {
+                if src.len() < 2 {
+                    src.clear();
+                    return Err(TransportError::RecvBadMessage);
+                }
+                let len = src.get_u16() as usize;
+                if src.len() < len {
+                    src.clear();
+                    return Err(TransportError::RecvBadMessage);
+                }
+                let fragment = Fragment::decode(&src.split_to(len)[..])
+                    .map_err(|_| TransportError::RecvBadMessage)?;
+                Ok(Some(UdpPacket::Data {
+                    session,
+                    seq,
+                    fragment,
+                }))
+            }
+            ACK => Ok(Some(UdpPacket::Ack { session, seq })),
+            _ => {
+                src.clear();
+                Err(TransportError::RecvBadMessage)
+            }
+        }
+    }
+}
 
-        let len = src.get_u16() as usize;
-        let msg = TransportMessage::decode(&src.split_to(len)[..])
-            .map_err(|_| TransportError::RecvBadMessage)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_packets() {
+        let fragment = Fragment {
+            message_id: 3,
+            index: 0,
+            count: 1,
+            data: vec![1, 2, 3],
+        };
+        let mut buf = BytesMut::new();
+        TransportMessageCodec
+            .encode(
+                UdpPacket::Data {
+                    session: 7,
+                    seq: 42,
+                    fragment,
+                },
+                &mut buf,
+            )
+            .unwrap();
+        TransportMessageCodec
+            .encode(
+                UdpPacket::Ack {
+                    session: 7,
+                    seq: 42,
+                },
+                &mut buf,
+            )
+            .unwrap();
+
+        match TransportMessageCodec.decode(&mut buf).unwrap() {
+            Some(UdpPacket::Data {
+                session: 7,
+                seq: 42,
+                fragment,
+            }) => assert_eq!(fragment.data, vec![1, 2, 3]),
+            other => panic!("unexpected packet {other:?}"),
+        }
+        assert!(matches!(
+            TransportMessageCodec.decode(&mut buf).unwrap(),
+            Some(UdpPacket::Ack {
+                session: 7,
+                seq: 42
+            })
+        ));
 
-        Ok(Some(msg))
+        let mut truncated = BytesMut::from(&[DATA, 0, 0][..]);
+        assert!(TransportMessageCodec.decode(&mut truncated).is_err());
     }
 }
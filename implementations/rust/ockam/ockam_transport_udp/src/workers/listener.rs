@@ -1,9 +1,12 @@
-use super::TransportMessageCodec;
+use super::{TransportMessageCodec, UdpPacket, UdpSocketSender};
 use crate::UDP;
 use futures_util::stream::SplitStream;
 use futures_util::StreamExt;
-use ockam_core::{async_trait, route, Address, AllowAll, LocalMessage, Processor, Result};
+use ockam_core::{
+    async_trait, route, Address, AllowAll, LocalMessage, OutgoingAccessControl, Processor, Result,
+};
 use ockam_node::Context;
+use std::sync::Arc;
 use tokio_util::udp::UdpFramed;
 use tracing::{debug, warn};
 
@@ -15,9 +18,14 @@ use tracing::{debug, warn};
 /// When a message is received, the address of the paired sender
 /// ([`UdpSendWorker`](crate::workers::UdpSendWorker)) is injected into the message's
 /// return route so that replies are sent to the sender.
+///
+/// The received fragments are acknowledged, and the messages they carry are forwarded in the
+/// order they were sent, using the reliability layer shared with the paired sender.
 pub(crate) struct UdpListenProcessor {
-    /// The read half of the udnerlying UDP socket.
+    /// The read half of the underlying UDP socket.
     stream: SplitStream<UdpFramed<TransportMessageCodec>>,
+    /// The write half of the underlying UDP socket, used to acknowledge the received messages
+    socket_sender: Arc<UdpSocketSender>,
     /// Address of our sender counterpart
     sender_addr: Address,
 }
@@ -25,18 +33,25 @@ pub(crate) struct UdpListenProcessor {
 impl UdpListenProcessor {
     pub(crate) async fn start(
         ctx: &Context,
+        address: Address,
         stream: SplitStream<UdpFramed<TransportMessageCodec>>,
+        socket_sender: Arc<UdpSocketSender>,
         sender_addr: Address,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     ) -> Result<()> {
         let processor = Self {
             stream,
+            socket_sender,
             sender_addr,
         };
-        let addr = Address::random_tagged("UdpListenProcessor");
 
-        // FIXME: @ac
-        ctx.start_processor_with_access_control(addr.clone(), processor, AllowAll, AllowAll)
-            .await?;
+        ctx.start_processor_with_access_control(
+            address,
+            processor,
+            AllowAll,
+            outgoing_access_control,
+        )
+        .await?;
 
         Ok(())
     }
@@ -52,9 +67,9 @@ impl Processor for UdpListenProcessor {
 
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
         debug!("Waiting for incoming UDP datagram...");
-        let (mut msg, addr) = match self.stream.next().await {
+        let (packet, addr) = match self.stream.next().await {
             Some(res) => match res {
-                Ok((msg, addr)) => (msg, addr),
+                Ok((packet, addr)) => (packet, addr),
                 Err(e) => {
                     warn!(
                         "Failed to read message, will wait for next message: {:?}",
@@ -69,17 +84,43 @@ impl Processor for UdpListenProcessor {
             }
         };
 
-        // Set return route to go directly to paired sender, skipping the UDP router
-        msg.return_route = route![
-            self.sender_addr.clone(),
-            Address::new(UDP, addr.to_string()),
-            msg.return_route
-        ];
+        let messages = match packet {
+            UdpPacket::Data {
+                session,
+                seq,
+                fragment,
+            } => {
+                match self
+                    .socket_sender
+                    .receive_fragment(addr, session, seq, fragment)
+                    .await
+                {
+                    Ok(messages) => messages,
+                    Err(e) => {
+                        warn!(%addr, %e, "Failed to acknowledge message");
+                        return Ok(true);
+                    }
+                }
+            }
+            UdpPacket::Ack { session, seq } => {
+                self.socket_sender.acknowledge(addr, session, seq);
+                return Ok(true);
+            }
+        };
+
+        for mut msg in messages {
+            // Set return route to go directly to paired sender, skipping the UDP router
+            msg.return_route = route![
+                self.sender_addr.clone(),
+                Address::new(UDP, addr.to_string()),
+                msg.return_route
+            ];
 
-        debug!(onward_route = %msg.onward_route,
-            return_route = %msg.return_route,
-            "Forwarding UDP message");
-        ctx.forward(LocalMessage::new(msg, vec![])).await?;
+            debug!(onward_route = %msg.onward_route,
+                return_route = %msg.return_route,
+                "Forwarding UDP message");
+            ctx.forward(LocalMessage::new(msg, vec![])).await?;
+        }
 
         Ok(true)
     }
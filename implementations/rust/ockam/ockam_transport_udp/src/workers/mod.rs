@@ -2,8 +2,12 @@
 
 pub(crate) use codec::*;
 pub(crate) use listener::*;
+pub(crate) use reliability::*;
 pub(crate) use sender::*;
+pub(crate) use socket::*;
 
 mod codec;
 mod listener;
+mod reliability;
 mod sender;
+mod socket;
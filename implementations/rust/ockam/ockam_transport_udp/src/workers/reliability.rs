@@ -0,0 +1,239 @@
+use super::UdpPacket;
+use hashbrown::HashMap;
+use ockam_core::Fragment;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Delay after which a fragment which has not been acknowledged is sent again
+const RETRANSMIT_AFTER: Duration = Duration::from_millis(300);
+/// Number of times a fragment is sent before it is dropped
+const MAX_SENDS: u32 = 10;
+/// Maximum number of fragments received out of order kept for a peer
+const REORDER_WINDOW: u64 = 1024;
+/// Delay after which a missing fragment is skipped, to deliver the next ones
+const REORDER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Interval between two checks of the fragments to send again
+pub(crate) const RETRANSMIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Reliability layer of a UDP socket.
+///
+/// The fragments of routing messages sent to a peer are numbered and kept until the peer
+/// acknowledges them. They are sent again when they are not acknowledged in time. The fragments
+/// received from a peer are acknowledged, de-duplicated and delivered in the order they were sent.
+///
+/// The sequence numbers are scoped by a random session identifier of the sending socket,
+/// so that a peer which restarts starts a new sequence
+pub(crate) struct Reliability {
+    session: u32,
+    outgoing: HashMap<SocketAddr, OutgoingPeer>,
+    incoming: HashMap<SocketAddr, IncomingPeer>,
+}
+
+#[derive(Default)]
+struct OutgoingPeer {
+    next_seq: u64,
+    unacknowledged: BTreeMap<u64, Unacknowledged>,
+}
+
+struct Unacknowledged {
+    fragment: Fragment,
+    sent_at: Instant,
+    sends: u32,
+}
+
+struct IncomingPeer {
+    session: u32,
+    next_seq: u64,
+    pending: BTreeMap<u64, Fragment>,
+    /// Time since which a missing fragment prevents the delivery of the next ones
+    blocked_since: Option<Instant>,
+}
+
+impl IncomingPeer {
+    fn new(session: u32) -> Self {
+        Self {
+            session,
+            next_seq: 0,
+            pending: BTreeMap::new(),
+            blocked_since: None,
+        }
+    }
+}
+
+impl Reliability {
+    pub(crate) fn new() -> Self {
+        Self {
+            session: rand::random(),
+            outgoing: HashMap::new(),
+            incoming: HashMap::new(),
+        }
+    }
+
+    /// Number a fragment for a peer, and keep it until it is acknowledged
+    pub(crate) fn send(&mut self, peer: SocketAddr, fragment: Fragment) -> UdpPacket {
+        let outgoing = self.outgoing.entry(peer).or_default();
+        let seq = outgoing.next_seq;
+        outgoing.next_seq += 1;
+        outgoing.unacknowledged.insert(
+            seq,
+            Unacknowledged {
+                fragment: fragment.clone(),
+                sent_at: Instant::now(),
+                sends: 1,
+            },
+        );
+        UdpPacket::Data {
+            session: self.session,
+            seq,
+            fragment,
+        }
+    }
+
+    /// Forget a fragment acknowledged by a peer
+    pub(crate) fn acknowledge(&mut self, peer: SocketAddr, session: u32, seq: u64) {
+        if session != self.session {
+            return;
+        }
+        if let Some(outgoing) = self.outgoing.get_mut(&peer) {
+            outgoing.unacknowledged.remove(&seq);
+        }
+    }
+
+    /// Handle a fragment received from a peer.
+    ///
+    /// Return None if the fragment can't be accepted yet and must not be acknowledged,
+    /// otherwise the fragments which can now be delivered, in order
+    pub(crate) fn receive(
+        &mut self,
+        peer: SocketAddr,
+        session: u32,
+        seq: u64,
+        fragment: Fragment,
+    ) -> Option<Vec<Fragment>> {
+        let incoming = self
+            .incoming
+            .entry(peer)
+            .or_insert_with(|| IncomingPeer::new(session));
+        if incoming.session != session {
+            *incoming = IncomingPeer::new(session);
+        }
+        if seq >= incoming.next_seq + REORDER_WINDOW {
+            return None;
+        }
+        if seq >= incoming.next_seq {
+            incoming.pending.insert(seq, fragment);
+        }
+
+        let mut delivered = Self::deliver(incoming);
+        if incoming.pending.is_empty() {
+            incoming.blocked_since = None;
+        } else {
+            let blocked_since = *incoming.blocked_since.get_or_insert_with(Instant::now);
+            if blocked_since.elapsed() > REORDER_TIMEOUT {
+                if let Some(first) = incoming.pending.keys().next() {
+                    warn!(%peer, missing = first - incoming.next_seq, "skipping lost UDP fragments");
+                    incoming.next_seq = *first;
+                }
+                delivered.extend(Self::deliver(incoming));
+                incoming.blocked_since = None;
+            }
+        }
+        Some(delivered)
+    }
+
+    /// Return the fragments which must be sent again
+    pub(crate) fn retransmissions(&mut self) -> Vec<(SocketAddr, UdpPacket)> {
+        let session = self.session;
+        let mut packets = vec![];
+        for (peer, outgoing) in self.outgoing.iter_mut() {
+            outgoing.unacknowledged.retain(|seq, unacknowledged| {
+                if unacknowledged.sent_at.elapsed() < RETRANSMIT_AFTER {
+                    return true;
+                }
+                if unacknowledged.sends >= MAX_SENDS {
+                    warn!(%peer, seq, "dropping a UDP fragment which was never acknowledged");
+                    return false;
+                }
+                unacknowledged.sends += 1;
+                unacknowledged.sent_at = Instant::now();
+                packets.push((
+                    *peer,
+                    UdpPacket::Data {
+                        session,
+                        seq: *seq,
+                        fragment: unacknowledged.fragment.clone(),
+                    },
+                ));
+                true
+            });
+        }
+        packets
+    }
+
+    /// Remove the consecutive fragments following the last delivered one
+    fn deliver(incoming: &mut IncomingPeer) -> Vec<Fragment> {
+        let mut delivered = vec![];
+        while let Some(fragment) = incoming.pending.remove(&incoming.next_seq) {
+            delivered.push(fragment);
+            incoming.next_seq += 1;
+        }
+        delivered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragment(i: u8) -> Fragment {
+        Fragment {
+            message_id: i as u64,
+            index: 0,
+            count: 1,
+            data: vec![i],
+        }
+    }
+
+    #[test]
+    fn messages_are_delivered_once_and_in_order() {
+        let peer: SocketAddr = "127.0.0.1:4000".parse().unwrap();
+        let mut sender = Reliability::new();
+        let mut receiver = Reliability::new();
+
+        let packets: Vec<_> = (0..3).map(|i| sender.send(peer, fragment(i))).collect();
+        let mut received = vec![];
+        for packet in packets.iter().rev().chain(packets.iter()) {
+            if let UdpPacket::Data {
+                session,
+                seq,
+                fragment,
+            } = packet.clone()
+            {
+                received.extend(receiver.receive(peer, session, seq, fragment).unwrap());
+            }
+        }
+        let payloads: Vec<_> = received.iter().map(|f| f.data[0]).collect();
+        assert_eq!(payloads, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn unacknowledged_messages_are_sent_again() {
+        let peer: SocketAddr = "127.0.0.1:4000".parse().unwrap();
+        let mut sender = Reliability::new();
+        let session = sender.session;
+        sender.send(peer, fragment(0));
+        sender.send(peer, fragment(1));
+        sender.acknowledge(peer, session, 0);
+
+        std::thread::sleep(RETRANSMIT_AFTER);
+        let retransmissions = sender.retransmissions();
+        assert_eq!(retransmissions.len(), 1);
+        assert!(matches!(
+            retransmissions[0].1,
+            UdpPacket::Data { seq: 1, .. }
+        ));
+    }
+}
@@ -1,11 +1,10 @@
-use super::TransportMessageCodec;
+use super::UdpSocketSender;
 use crate::UDP;
-use futures_util::{stream::SplitSink, SinkExt};
-use ockam_core::{async_trait, Any, Result, Routed, TransportMessage, Worker};
+use ockam_core::{async_trait, Any, Result, Routed, Worker};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
 use std::net::{SocketAddr, ToSocketAddrs};
-use tokio_util::udp::UdpFramed;
+use std::sync::Arc;
 use tracing::{error, trace, warn};
 
 /// A sender for the UDP transport
@@ -13,16 +12,14 @@ use tracing::{error, trace, warn};
 /// This worker handles the sending of messages on a
 /// local socket. See [`UdpRouter`](crate::router::UdpRouter) for more details.
 pub(crate) struct UdpSendWorker {
-    /// The read half of the udnerlying UDP socket.
-    sink: SplitSink<UdpFramed<TransportMessageCodec>, (TransportMessage, SocketAddr)>,
+    /// The write half of the underlying UDP socket.
+    sender: Arc<UdpSocketSender>,
 }
 
 impl UdpSendWorker {
     /// Create a new `UdpSendWorker`
-    pub(crate) fn new(
-        sink: SplitSink<UdpFramed<TransportMessageCodec>, (TransportMessage, SocketAddr)>,
-    ) -> Self {
-        Self { sink }
+    pub(crate) fn new(sender: Arc<UdpSocketSender>) -> Self {
+        Self { sender }
     }
 }
 
@@ -74,14 +71,14 @@ impl Worker for UdpSendWorker {
         }
 
         // Send
-        match self.sink.send((msg.clone(), addr)).await {
+        match self.sender.send_message(addr, msg).await {
             Ok(()) => {
                 trace!("Successful send to {}", addr);
                 Ok(())
             }
             Err(e) => {
                 error!("Failed send to {}: {:?}", addr, e);
-                Err(e.into())
+                Err(e)
             }
         }
     }
@@ -0,0 +1,142 @@
+use super::{Reliability, TransportMessageCodec, UdpPacket, MAX_FRAGMENT_LEN, RETRANSMIT_INTERVAL};
+use futures_util::stream::SplitSink;
+use futures_util::SinkExt;
+use hashbrown::HashMap;
+use ockam_core::{
+    Fragment, FragmentationLimits, Fragmenter, Reassembler, Result, TransportMessage,
+};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, Weak};
+use tokio_util::udp::UdpFramed;
+use tracing::{trace, warn};
+
+type UdpSink = SplitSink<UdpFramed<TransportMessageCodec>, (UdpPacket, SocketAddr)>;
+
+/// Sending half of a UDP socket, shared by its sender, its listener which sends the
+/// acknowledgements, and the task sending again the unacknowledged fragments.
+///
+/// The routing messages are split into fragments which fit in a datagram, and reassembled
+/// from the fragments received from each peer
+pub(crate) struct UdpSocketSender {
+    sink: tokio::sync::Mutex<UdpSink>,
+    reliability: Mutex<Reliability>,
+    fragmenter: Mutex<Fragmenter>,
+    /// Reassembler of the messages of each peer, with the session of the peer
+    reassemblers: Mutex<HashMap<SocketAddr, (u32, Reassembler)>>,
+}
+
+impl UdpSocketSender {
+    /// Create the sending half of a socket, and start sending again its unacknowledged
+    /// routing messages until the socket is dropped
+    pub(crate) fn start(sink: UdpSink) -> Arc<Self> {
+        let sender = Arc::new(Self {
+            sink: tokio::sync::Mutex::new(sink),
+            reliability: Mutex::new(Reliability::new()),
+            fragmenter: Mutex::new(Fragmenter::new(MAX_FRAGMENT_LEN)),
+            reassemblers: Mutex::new(HashMap::new()),
+        });
+        tokio::spawn(Self::retransmit(Arc::downgrade(&sender)));
+        sender
+    }
+
+    /// Send a routing message to a peer
+    pub(crate) async fn send_message(
+        &self,
+        peer: SocketAddr,
+        message: TransportMessage,
+    ) -> Result<()> {
+        let fragments = self.fragmenter.lock().unwrap().fragment(&message)?;
+        for fragment in fragments {
+            let packet = self.reliability.lock().unwrap().send(peer, fragment);
+            self.send_packet(peer, packet).await?;
+        }
+        Ok(())
+    }
+
+    /// Handle a fragment received from a peer, and acknowledge it.
+    /// Return the routing messages which can now be delivered, in order
+    pub(crate) async fn receive_fragment(
+        &self,
+        peer: SocketAddr,
+        session: u32,
+        seq: u64,
+        fragment: Fragment,
+    ) -> Result<Vec<TransportMessage>> {
+        let delivered = self
+            .reliability
+            .lock()
+            .unwrap()
+            .receive(peer, session, seq, fragment);
+        match delivered {
+            Some(delivered) => {
+                self.send_packet(peer, UdpPacket::Ack { session, seq })
+                    .await?;
+                Ok(self.reassemble(peer, session, delivered))
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Reassemble the routing messages of a peer from its fragments, in order
+    fn reassemble(
+        &self,
+        peer: SocketAddr,
+        session: u32,
+        fragments: Vec<Fragment>,
+    ) -> Vec<TransportMessage> {
+        let mut reassemblers = self.reassemblers.lock().unwrap();
+        let (peer_session, reassembler) = reassemblers
+            .entry(peer)
+            .or_insert_with(|| (session, Reassembler::new(FragmentationLimits::default())));
+        // The message identifiers start again when the peer starts a new session
+        if *peer_session != session {
+            *peer_session = session;
+            *reassembler = Reassembler::new(FragmentationLimits::default());
+        }
+
+        let mut messages = vec![];
+        for fragment in fragments {
+            match reassembler.push(fragment) {
+                Ok(Some(message)) => messages.push(message),
+                Ok(None) => {}
+                Err(e) => warn!(%peer, %e, "dropping a UDP message which can't be reassembled"),
+            }
+        }
+        messages
+    }
+
+    /// Handle the acknowledgement of a fragment by a peer
+    pub(crate) fn acknowledge(&self, peer: SocketAddr, session: u32, seq: u64) {
+        self.reliability
+            .lock()
+            .unwrap()
+            .acknowledge(peer, session, seq)
+    }
+
+    async fn send_packet(&self, peer: SocketAddr, packet: UdpPacket) -> Result<()> {
+        self.sink
+            .lock()
+            .await
+            .send((packet, peer))
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn retransmit(sender: Weak<Self>) {
+        let mut interval = tokio::time::interval(RETRANSMIT_INTERVAL);
+        loop {
+            interval.tick().await;
+            let sender = match sender.upgrade() {
+                Some(sender) => sender,
+                None => return,
+            };
+            let packets = sender.reliability.lock().unwrap().retransmissions();
+            for (peer, packet) in packets {
+                trace!(%peer, "sending again an unacknowledged UDP message");
+                if let Err(e) = sender.send_packet(peer, packet).await {
+                    warn!(%peer, %e, "failed to send again a UDP message");
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use ockam_core::compat::rand::{self, Rng};
 use ockam_core::{route, Address, AllowAll, Result, Routed, Worker};
 use ockam_node::{Context, MessageReceiveOptions, MessageSendReceiveOptions};
-use ockam_transport_udp::{UdpTransport, UDP};
+use ockam_transport_udp::{UdpListenerOptions, UdpTransport, UDP};
 use std::net::SocketAddr;
 use std::time::Duration;
 use tracing::{debug, error, trace};
@@ -192,6 +192,45 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+/// The messages received by a listener created with options can only be sent to
+/// the consumers of its flow control id
+#[ockam_macros::test]
+async fn listener_flow_control(ctx: &mut Context) -> Result<()> {
+    let transport = UdpTransport::create(ctx).await?;
+    ctx.start_worker("echoer", Echoer::new()).await?;
+
+    let options = UdpListenerOptions::new();
+    let flow_control_id = options.flow_control_id();
+    let listener = transport
+        .listen_with_options("127.0.0.1:0", options)
+        .await?;
+    let r = route![(UDP, listener.socket_address().to_string()), "echoer"];
+
+    let res: Result<Routed<String>> = ctx
+        .send_and_receive_extended(
+            r.clone(),
+            String::from("Hola"),
+            MessageSendReceiveOptions::new().with_timeout(Duration::from_secs(1)),
+        )
+        .await;
+    assert!(res.is_err(), "The echoer is not a consumer of the listener");
+
+    ctx.flow_controls().add_consumer("echoer", &flow_control_id);
+    let reply = ctx
+        .send_and_receive_extended::<String>(
+            r,
+            String::from("Hola"),
+            MessageSendReceiveOptions::new().with_timeout(TIMEOUT),
+        )
+        .await?
+        .body();
+    assert_eq!(reply, "Hola");
+
+    transport.stop_listener(&listener).await?;
+    ctx.stop().await?;
+    Ok(())
+}
+
 pub struct Echoer {
     prev_src_addr: Option<String>,
 }
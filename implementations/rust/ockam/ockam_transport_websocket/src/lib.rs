@@ -94,6 +94,7 @@ pub use transport::*;
 use crate::router::{WebSocketRouter, WebSocketRouterHandle};
 
 mod error;
+mod peer;
 mod router;
 mod transport;
 mod workers;
@@ -0,0 +1,206 @@
+use core::fmt;
+use core::str::FromStr;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use ockam_core::{Address, Result};
+use ockam_transport_core::TransportError;
+
+use crate::error::WebSocketError;
+use crate::workers::{TcpClientStream, WebSocketStream};
+use crate::WS;
+
+/// Maximum size of the response of an HTTP proxy to a `CONNECT` request
+const MAX_PROXY_RESPONSE_LENGTH: usize = 8192;
+
+/// Remote endpoint of an outgoing WebSocket connection.
+///
+/// It is parsed from the value of a `WS` address, which can be:
+///  - `host:port`, for a plain WebSocket connection,
+///  - `ws://host[:port][/path]`, for a plain WebSocket connection,
+///  - `wss://host[:port][/path]`, for a WebSocket connection secured with TLS.
+///
+/// When the `HTTPS_PROXY` environment variable (for `wss`) or the `HTTP_PROXY` environment
+/// variable (for `ws`) is set, the connection is tunneled via the proxy with an HTTP `CONNECT`
+/// request, unless the host is listed in the `NO_PROXY` environment variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct WebSocketPeer {
+    /// Value of the `WS` address, as used in routes
+    address: String,
+    secure: bool,
+    /// Host and port of the peer
+    authority: String,
+    path: String,
+}
+
+impl WebSocketPeer {
+    /// Address of the peer, as used in routes
+    pub(crate) fn address(&self) -> Address {
+        Address::new(WS, self.address.clone())
+    }
+
+    /// Host of the peer, without its port
+    fn host(&self) -> &str {
+        self.authority
+            .rsplit_once(':')
+            .map(|(host, _)| host)
+            .unwrap_or(&self.authority)
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+    }
+
+    /// Open a WebSocket connection to the peer, via a proxy if one is configured
+    pub(crate) async fn connect(&self) -> Result<WebSocketStream<TcpClientStream>> {
+        let url = self.to_string();
+        let (stream, _) = match self.proxy() {
+            Some(proxy) => {
+                debug!(%proxy, peer = %url, "Connecting to a WebSocket peer via an HTTP proxy");
+                let stream = tunnel(&proxy, &self.authority).await?;
+                tokio_tungstenite::client_async_tls(url, stream).await
+            }
+            None => tokio_tungstenite::connect_async(url).await,
+        }
+        .map_err(WebSocketError::from)?;
+        Ok(stream)
+    }
+
+    /// Return the `host:port` of the HTTP proxy to use to reach the peer, if any
+    fn proxy(&self) -> Option<String> {
+        let variables: &[&str] = if self.secure {
+            &["HTTPS_PROXY", "https_proxy"]
+        } else {
+            &["HTTP_PROXY", "http_proxy"]
+        };
+        let proxy = variables
+            .iter()
+            .find_map(|v| std::env::var(v).ok())
+            .filter(|p| !p.is_empty())?;
+        let no_proxy = ["NO_PROXY", "no_proxy"]
+            .iter()
+            .find_map(|v| std::env::var(v).ok())
+            .unwrap_or_default();
+        if is_excluded(self.host(), &no_proxy) {
+            return None;
+        }
+        let proxy = proxy.trim_start_matches("http://").trim_end_matches('/');
+        Some(proxy.to_string())
+    }
+}
+
+impl FromStr for WebSocketPeer {
+    type Err = ockam_core::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (secure, rest) = if let Some(rest) = s.strip_prefix("wss://") {
+            (true, rest)
+        } else if let Some(rest) = s.strip_prefix("ws://") {
+            (false, rest)
+        } else {
+            (false, s)
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, "/"),
+        };
+        if authority.is_empty() || authority.contains(char::is_whitespace) {
+            return Err(TransportError::InvalidAddress.into());
+        }
+        let has_port = authority
+            .rsplit_once(':')
+            .map(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+            .unwrap_or(false);
+        let authority = if has_port {
+            authority.to_string()
+        } else {
+            format!("{authority}:{}", if secure { 443 } else { 80 })
+        };
+        Ok(Self {
+            address: s.to_string(),
+            secure,
+            authority,
+            path: path.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for WebSocketPeer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scheme = if self.secure { "wss" } else { "ws" };
+        write!(f, "{}://{}{}", scheme, self.authority, self.path)
+    }
+}
+
+/// Return true if a host matches an entry of a `NO_PROXY` list, either exactly or as a subdomain
+fn is_excluded(host: &str, no_proxy: &str) -> bool {
+    no_proxy
+        .split(',')
+        .map(|entry| entry.trim().trim_start_matches('.'))
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| entry == "*" || host == entry || host.ends_with(&format!(".{entry}")))
+}
+
+/// Open a TCP tunnel to `authority` via an HTTP proxy
+async fn tunnel(proxy: &str, authority: &str) -> Result<TcpStream> {
+    let mut stream = TcpStream::connect(proxy)
+        .await
+        .map_err(TransportError::from)?;
+    let request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(TransportError::from)?;
+
+    // The response is read byte by byte so that nothing sent by the peer after the headers
+    // is consumed
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_PROXY_RESPONSE_LENGTH {
+            return Err(TransportError::Protocol.into());
+        }
+        stream
+            .read_exact(&mut byte)
+            .await
+            .map_err(TransportError::from)?;
+        response.push(byte[0]);
+    }
+    let response = String::from_utf8_lossy(&response);
+    match response.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') => Ok(stream),
+        status => {
+            warn!(%proxy, %authority, ?status, "The HTTP proxy refused to open a tunnel");
+            Err(TransportError::ConnectionDrop.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_peers() {
+        let peer = WebSocketPeer::from_str("127.0.0.1:4000").unwrap();
+        assert_eq!(peer.to_string(), "ws://127.0.0.1:4000/");
+        assert_eq!(peer.address(), Address::new(WS, "127.0.0.1:4000"));
+
+        let peer = WebSocketPeer::from_str("wss://relay.example.com/ockam").unwrap();
+        assert_eq!(peer.to_string(), "wss://relay.example.com:443/ockam");
+        assert_eq!(peer.host(), "relay.example.com");
+
+        let peer = WebSocketPeer::from_str("ws://[::1]:8080").unwrap();
+        assert_eq!(peer.to_string(), "ws://[::1]:8080/");
+        assert_eq!(peer.host(), "::1");
+
+        assert!(WebSocketPeer::from_str("wss://").is_err());
+    }
+
+    #[test]
+    fn exclude_hosts_from_proxy() {
+        assert!(is_excluded("relay.example.com", "localhost, .example.com"));
+        assert!(is_excluded("localhost", "localhost,example.com"));
+        assert!(!is_excluded("example.org", "localhost,example.com"));
+        assert!(is_excluded("example.org", "*"));
+    }
+}
@@ -1,4 +1,3 @@
-use core::str::FromStr;
 use std::collections::BTreeMap;
 use std::ops::Deref;
 use std::sync::Arc;
@@ -11,8 +10,9 @@ use ockam_core::{
 use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::TransportError;
 
+use crate::peer::WebSocketPeer;
 use crate::workers::WorkerPair;
-use crate::{WebSocketAddress, WS};
+use crate::WS;
 use serde::{Deserialize, Serialize};
 
 mod handle;
@@ -36,7 +36,7 @@ pub enum WebSocketRouterResponse {
 /// A WebSocket address router and connection listener.
 ///
 /// In order to create new WebSocket connection workers you need a router to
-/// map remote addresses of `type = 2` to worker addresses.  This type
+/// map remote addresses of `type = 3` to worker addresses.  This type
 /// facilitates this.
 ///
 /// Optionally you can also start listening for incoming connections
@@ -220,21 +220,14 @@ impl WebSocketRouter {
     }
 
     async fn connect(&mut self, peer: String) -> Result<Address> {
-        // Get peer address and connect to it.
-        let (peer_addr, hostnames) = WebSocketRouterHandle::resolve_peer(peer)?;
+        let peer: WebSocketPeer = peer.parse()?;
 
         // Create a new `WorkerPair` for the given peer, initializing a new pair
         // of sender worker and receiver processor.
-        let pair = WorkerPair::from_client(&self.ctx, peer_addr, hostnames).await?;
+        let pair = WorkerPair::from_client(&self.ctx, peer).await?;
 
         // Handle node's register request.
-        let mut accepts = vec![pair.peer()];
-        accepts.extend(
-            pair.hostnames()
-                .iter()
-                .filter_map(|x| WebSocketAddress::from_str(x).ok())
-                .map(|addr| addr.into()),
-        );
+        let accepts = vec![pair.peer()];
         let self_addr = pair.tx_addr();
         self.handle_register(accepts, self_addr.clone()).await?;
 
@@ -2,6 +2,7 @@ use core::fmt;
 use std::net::SocketAddr;
 use std::str::FromStr;
 
+use ockam_core::flow_control::{FlowControlId, FlowControls};
 use ockam_core::{async_trait, Address, Result};
 use ockam_node::{Context, HasContext};
 
@@ -22,6 +23,15 @@ use crate::{parse_socket_addr, WebSocketRouter, WebSocketRouterHandle, WS};
 /// This step is optional because the underlying WebSocketRouter is capable of lazily
 /// establishing a connection upon arrival of an initial message.
 ///
+/// The peers are given as `host:port`, `ws://host[:port][/path]` or `wss://host[:port][/path]`.
+/// `wss` connections are secured with TLS, so that a node can reach a peer, for example a
+/// relay, through a network which only allows HTTPS traffic. When the `HTTPS_PROXY` (for `wss`)
+/// or `HTTP_PROXY` (for `ws`) environment variable is set, the connections are tunneled via
+/// that HTTP proxy, unless the host of the peer is listed in the `NO_PROXY` environment variable.
+///
+/// The listeners only accept plain `ws` connections. TLS can be terminated in front of them,
+/// by a reverse proxy or a load balancer.
+///
 /// ```rust
 /// use ockam_transport_websocket::WebSocketTransport;
 /// # use ockam_core::Result;
@@ -75,6 +85,7 @@ impl WebSocketTransport {
     /// let ws = WebSocketTransport::create(&ctx).await?;
     /// ws.listen("127.0.0.1:8000").await?; // Listen on port 8000
     /// ws.connect("127.0.0.1:5000").await?; // and connect to port 5000
+    /// ws.connect("wss://relay.example.com/ockam").await?; // and to a relay, over TLS
     /// # Ok(()) }
     /// ```
     pub async fn connect<S: AsRef<str>>(&self, peer: S) -> Result<()> {
@@ -98,7 +109,44 @@ impl WebSocketTransport {
     /// # Ok(()) }
     pub async fn listen<S: AsRef<str>>(&self, bind_addr: S) -> Result<SocketAddr> {
         let bind_addr = parse_socket_addr(bind_addr)?;
-        self.router_handle.bind(bind_addr).await
+        self.router_handle.bind(bind_addr, None).await
+    }
+
+    /// Start listening to incoming connections on an existing transport.
+    /// The messages received on these connections can only be sent to the consumers of
+    /// the listener [`FlowControlId`].
+    ///
+    /// Returns the local address that this transport is bound to.
+    pub async fn listen_with_options<S: AsRef<str>>(
+        &self,
+        bind_addr: S,
+        options: WebSocketListenerOptions,
+    ) -> Result<SocketAddr> {
+        let bind_addr = parse_socket_addr(bind_addr)?;
+        self.router_handle
+            .bind(bind_addr, Some(options.flow_control_id))
+            .await
+    }
+}
+
+/// Trust Options for a WebSocket listener
+#[derive(Debug)]
+pub struct WebSocketListenerOptions {
+    flow_control_id: FlowControlId,
+}
+
+impl WebSocketListenerOptions {
+    /// Mark this WebSocket listener as a Producer with a random [`FlowControlId`]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            flow_control_id: FlowControls::generate_flow_control_id(),
+        }
+    }
+
+    /// Getter for freshly generated [`FlowControlId`]
+    pub fn flow_control_id(&self) -> FlowControlId {
+        self.flow_control_id.clone()
     }
 }
 
@@ -2,6 +2,7 @@ use std::net::SocketAddr;
 
 use tokio::net::TcpListener;
 
+use ockam_core::flow_control::FlowControlId;
 use ockam_core::{async_trait, Address, AllowAll, Processor, Result};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
@@ -16,6 +17,7 @@ use crate::{error::WebSocketError, workers::WorkerPair, WebSocketRouterHandle};
 pub(crate) struct WebSocketListenProcessor {
     inner: TcpListener,
     router_handle: WebSocketRouterHandle,
+    flow_control_id: Option<FlowControlId>,
 }
 
 impl WebSocketListenProcessor {
@@ -24,6 +26,7 @@ impl WebSocketListenProcessor {
         ctx: &Context,
         router_handle: WebSocketRouterHandle,
         addr: SocketAddr,
+        flow_control_id: Option<FlowControlId>,
     ) -> Result<SocketAddr> {
         debug!("Binding WebSocketListener to {}", addr);
         let inner = TcpListener::bind(addr)
@@ -33,6 +36,7 @@ impl WebSocketListenProcessor {
         let processor = Self {
             inner,
             router_handle,
+            flow_control_id,
         };
         let waddr = Address::random_tagged("WebSocketListenProcessor");
         ctx.start_processor_with_access_control(
@@ -57,13 +61,24 @@ impl Processor for WebSocketListenProcessor {
 
         // Wait for an incoming connection
         let (tcp_stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
-        let ws_stream = tokio_tungstenite::accept_async(tcp_stream)
-            .await
-            .map_err(WebSocketError::from)?;
+        // A failed handshake, for example a health check from a load balancer,
+        // must not stop the listener
+        let ws_stream = match tokio_tungstenite::accept_async(tcp_stream).await {
+            Ok(ws_stream) => ws_stream,
+            Err(e) => {
+                warn!(
+                    "WebSocket handshake with {} failed: {}",
+                    peer,
+                    WebSocketError::from(e)
+                );
+                return Ok(true);
+            }
+        };
         debug!("TCP connection accepted");
 
         // Spawn a connection worker for it
-        let pair = WorkerPair::from_server(ctx, ws_stream, peer, vec![]).await?;
+        let pair =
+            WorkerPair::from_server(ctx, ws_stream, peer, self.flow_control_id.clone()).await?;
 
         // Register the connection with the local TcpRouter
         self.router_handle.register(&pair).await?;
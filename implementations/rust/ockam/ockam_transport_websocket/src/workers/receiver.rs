@@ -1,10 +1,7 @@
-use std::net::SocketAddr;
-
 use futures_util::stream::SplitStream;
 use futures_util::StreamExt;
 use tokio_tungstenite::WebSocketStream;
 
-use crate::WebSocketAddress;
 use ockam_core::{
     async_trait, Address, Decodable, LocalMessage, Processor, Result, TransportMessage,
 };
@@ -30,10 +27,10 @@ impl<S> WebSocketRecvProcessor<S>
 where
     S: AsyncStream,
 {
-    pub(crate) fn new(ws_stream: SplitStream<WebSocketStream<S>>, peer: SocketAddr) -> Self {
+    pub(crate) fn new(ws_stream: SplitStream<WebSocketStream<S>>, peer_addr: Address) -> Self {
         Self {
             ws_stream,
-            peer_addr: WebSocketAddress::from(peer).into(),
+            peer_addr,
         }
     }
 }
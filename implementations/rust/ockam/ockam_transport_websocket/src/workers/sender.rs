@@ -6,14 +6,15 @@ use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
 use tokio_tungstenite::tungstenite::protocol::Message as WebSocketMessage;
 
-use crate::error::WebSocketError;
+use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl};
 use ockam_core::{
     async_trait, route, Address, AllowAll, Any, Decodable, Encodable, LocalMessage, Mailbox,
-    Mailboxes, Result, Routed, TransportMessage, Worker,
+    Mailboxes, OutgoingAccessControl, Result, Routed, TransportMessage, Worker,
 };
 use ockam_node::{Context, DelayedEvent, WorkerBuilder};
 use ockam_transport_core::TransportError;
 
+use crate::peer::WebSocketPeer;
 use crate::workers::{
     AsyncStream, TcpClientStream, TcpServerStream, WebSocketRecvProcessor, WebSocketStream,
 };
@@ -22,15 +23,11 @@ use crate::WebSocketAddress;
 /// Transmit and receive peers of a WebSocket connection.
 #[derive(Debug)]
 pub(crate) struct WorkerPair {
-    hostnames: Vec<String>,
     peer: Address,
     tx_addr: Address,
 }
 
 impl WorkerPair {
-    pub(crate) fn hostnames(&self) -> &[String] {
-        &self.hostnames
-    }
     pub(crate) fn peer(&self) -> Address {
         self.peer.clone()
     }
@@ -42,13 +39,10 @@ impl WorkerPair {
     /// returns a `WorkerPair` instance that will be registered by the `WebSocketRouter`.
     ///
     /// The WebSocket stream is created when the `WebSocketSendWorker` is initialized.
-    pub(crate) async fn from_client(
-        ctx: &Context,
-        peer: SocketAddr,
-        hostnames: Vec<String>,
-    ) -> Result<WorkerPair> {
+    pub(crate) async fn from_client(ctx: &Context, peer: WebSocketPeer) -> Result<WorkerPair> {
         trace!("Creating new WS worker pair");
 
+        let peer_addr = peer.address();
         let internal_addr = Address::random_tagged("WebSocketSender.internal.from_client");
         let sender = WebSocketSendWorker::<TcpClientStream>::new(
             peer,
@@ -77,28 +71,32 @@ impl WorkerPair {
 
         // Return a handle to the worker pair
         Ok(WorkerPair {
-            hostnames,
-            peer: WebSocketAddress::from(peer).into(),
+            peer: peer_addr,
             tx_addr,
         })
     }
 
     /// Spawn instances of `WebSocketSendWorker` and `WebSocketRecvProcessor` and
     /// returns a `WorkerPair` instance that will be registered by the `WebSocketRouter`.
+    ///
+    /// When a flow control id is given, the messages received from the peer can only be
+    /// sent to the consumers of that flow control id.
     pub(crate) async fn from_server(
         ctx: &Context,
         stream: WebSocketStream<TcpServerStream>,
         peer: SocketAddr,
-        hostnames: Vec<String>,
+        flow_control_id: Option<FlowControlId>,
     ) -> Result<WorkerPair> {
         trace!("Creating new WS worker pair");
 
+        let peer_addr: Address = WebSocketAddress::from(peer).into();
         let internal_addr = Address::random_tagged("WebSocketSender.internal.from_server");
         let sender = WebSocketSendWorker::<TcpServerStream>::new(
             stream,
-            peer,
+            peer_addr.clone(),
             internal_addr.clone(),
             DelayedEvent::create(ctx, internal_addr.clone(), vec![]).await?,
+            flow_control_id,
         );
 
         let tx_addr = Address::random_tagged("WebSocketSender.tx_addr.from_server");
@@ -121,8 +119,7 @@ impl WorkerPair {
 
         // Return a handle to the worker pair
         Ok(WorkerPair {
-            hostnames,
-            peer: WebSocketAddress::from(peer).into(),
+            peer: peer_addr,
             tx_addr,
         })
     }
@@ -139,10 +136,14 @@ where
 {
     ws_stream: Option<SplitStream<WebSocketStream<S>>>,
     ws_sink: Option<SplitSink<WebSocketStream<S>, WebSocketMessage>>,
-    peer: SocketAddr,
+    /// Address of the peer, as used in routes
+    peer: Address,
+    /// Endpoint to connect to, for an outgoing connection
+    remote: Option<WebSocketPeer>,
     internal_addr: Address,
     heartbeat: DelayedEvent<Vec<u8>>,
     heartbeat_interval: Option<Duration>,
+    flow_control_id: Option<FlowControlId>,
 }
 
 impl<S> WebSocketSendWorker<S>
@@ -152,12 +153,29 @@ where
     async fn handle_initialize(&mut self, ctx: &mut Context) -> Result<()> {
         if let Some(ws_stream) = self.ws_stream.take() {
             let rx_addr = Address::random_tagged("WebSocketSendWorker.rx_addr");
-            let receiver = WebSocketRecvProcessor::new(ws_stream, self.peer);
+            let receiver = WebSocketRecvProcessor::new(ws_stream, self.peer.clone());
+            let outgoing_access_control: Arc<dyn OutgoingAccessControl> =
+                match &self.flow_control_id {
+                    Some(flow_control_id) => {
+                        ctx.flow_controls().add_producer(
+                            rx_addr.clone(),
+                            flow_control_id,
+                            None,
+                            vec![ctx.address()],
+                        );
+                        Arc::new(FlowControlOutgoingAccessControl::new(
+                            ctx.flow_controls(),
+                            flow_control_id.clone(),
+                            None,
+                        ))
+                    }
+                    None => Arc::new(AllowAll), // FIXME: @ac
+                };
             ctx.start_processor_with_access_control(
                 rx_addr.clone(),
                 receiver,
                 AllowAll, // FIXME: @ac
-                AllowAll, // FIXME: @ac
+                outgoing_access_control,
             )
             .await?;
         } else {
@@ -229,40 +247,47 @@ where
 impl WebSocketSendWorker<TcpServerStream> {
     fn new(
         stream: WebSocketStream<TcpServerStream>,
-        peer: SocketAddr,
+        peer: Address,
         internal_addr: Address,
         heartbeat: DelayedEvent<Vec<u8>>,
+        flow_control_id: Option<FlowControlId>,
     ) -> Self {
         let (ws_sink, ws_stream) = stream.split();
         Self {
             ws_sink: Some(ws_sink),
             ws_stream: Some(ws_stream),
             peer,
+            remote: None,
             internal_addr,
             heartbeat,
             heartbeat_interval: None,
+            flow_control_id,
         }
     }
 }
 
 impl WebSocketSendWorker<TcpClientStream> {
-    fn new(peer: SocketAddr, internal_addr: Address, heartbeat: DelayedEvent<Vec<u8>>) -> Self {
+    fn new(
+        remote: WebSocketPeer,
+        internal_addr: Address,
+        heartbeat: DelayedEvent<Vec<u8>>,
+    ) -> Self {
         Self {
             ws_stream: None,
             ws_sink: None,
-            peer,
+            peer: remote.address(),
+            remote: Some(remote),
             internal_addr,
             heartbeat,
             heartbeat_interval: None,
+            flow_control_id: None,
         }
     }
 
     async fn initialize_stream(&mut self) -> Result<()> {
         if self.ws_stream.is_none() {
-            let peer = WebSocketAddress::from(self.peer).to_string();
-            let (stream, _) = tokio_tungstenite::connect_async(peer)
-                .await
-                .map_err(WebSocketError::from)?;
+            let remote = self.remote.as_ref().ok_or(TransportError::PeerNotFound)?;
+            let stream = remote.connect().await?;
             let (ws_sink, ws_stream) = stream.split();
             self.ws_sink = Some(ws_sink);
             self.ws_stream = Some(ws_stream);
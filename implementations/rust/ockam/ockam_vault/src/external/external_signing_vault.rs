@@ -0,0 +1,186 @@
+use crate::{
+    ECDSASHA256CurveP256PublicKey, EdDSACurve25519PublicKey, ExternalSigner, HandleToSecret,
+    Signature, SigningKeyType, SigningSecretKeyHandle, VaultError, VaultForSigning,
+    VerifyingPublicKey,
+};
+
+use ockam_core::compat::{boxed::Box, sync::Arc, vec::Vec};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, Error, Result};
+
+/// [`VaultForSigning`] implementation delegating the signatures to an [`ExternalSigner`].
+///
+/// The handle of a key is its public key, so that no state needs to be kept by the vault.
+/// The keys are managed by the external signer: they can't be generated or deleted
+#[derive(Clone)]
+pub struct ExternalSigningVault {
+    signer: Arc<dyn ExternalSigner>,
+}
+
+impl ExternalSigningVault {
+    /// Constructor
+    pub fn new(signer: Arc<dyn ExternalSigner>) -> Self {
+        Self { signer }
+    }
+
+    /// Return the [`SigningSecretKeyHandle`] of the key corresponding to a [`VerifyingPublicKey`]
+    pub fn secret_key_handle(verifying_public_key: &VerifyingPublicKey) -> SigningSecretKeyHandle {
+        match verifying_public_key {
+            VerifyingPublicKey::EdDSACurve25519(public_key) => {
+                SigningSecretKeyHandle::EdDSACurve25519(HandleToSecret::new(public_key.0.to_vec()))
+            }
+            VerifyingPublicKey::ECDSASHA256CurveP256(public_key) => {
+                SigningSecretKeyHandle::ECDSASHA256CurveP256(HandleToSecret::new(
+                    public_key.0.to_vec(),
+                ))
+            }
+        }
+    }
+
+    /// Return the [`VerifyingPublicKey`] referenced by a [`SigningSecretKeyHandle`]
+    fn verifying_public_key(handle: &SigningSecretKeyHandle) -> Result<VerifyingPublicKey> {
+        match handle {
+            SigningSecretKeyHandle::EdDSACurve25519(handle) => Ok(
+                VerifyingPublicKey::EdDSACurve25519(EdDSACurve25519PublicKey(
+                    handle
+                        .value()
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| VaultError::InvalidPublicLength)?,
+                )),
+            ),
+            SigningSecretKeyHandle::ECDSASHA256CurveP256(handle) => Ok(
+                VerifyingPublicKey::ECDSASHA256CurveP256(ECDSASHA256CurveP256PublicKey(
+                    handle
+                        .value()
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| VaultError::InvalidPublicLength)?,
+                )),
+            ),
+        }
+    }
+
+    /// Return the [`VerifyingPublicKey`] referenced by a handle, if the signer still has that key
+    async fn known_verifying_public_key(
+        &self,
+        handle: &SigningSecretKeyHandle,
+    ) -> Result<VerifyingPublicKey> {
+        let public_key = Self::verifying_public_key(handle)?;
+        if self
+            .signer
+            .verifying_public_keys()
+            .await?
+            .contains(&public_key)
+        {
+            Ok(public_key)
+        } else {
+            Err(VaultError::KeyNotFound.into())
+        }
+    }
+}
+
+#[async_trait]
+impl VaultForSigning for ExternalSigningVault {
+    async fn sign(
+        &self,
+        signing_secret_key_handle: &SigningSecretKeyHandle,
+        data: &[u8],
+    ) -> Result<Signature> {
+        let public_key = Self::verifying_public_key(signing_secret_key_handle)?;
+        self.signer.sign(&public_key, data).await
+    }
+
+    async fn generate_signing_secret_key(
+        &self,
+        _signing_key_type: SigningKeyType,
+    ) -> Result<SigningSecretKeyHandle> {
+        Err(Error::new(
+            Origin::Vault,
+            Kind::Unsupported,
+            "the keys of an external signer can't be generated, an existing key must be used",
+        ))
+    }
+
+    async fn get_verifying_public_key(
+        &self,
+        signing_secret_key_handle: &SigningSecretKeyHandle,
+    ) -> Result<VerifyingPublicKey> {
+        self.known_verifying_public_key(signing_secret_key_handle)
+            .await
+    }
+
+    async fn get_secret_key_handle(
+        &self,
+        verifying_public_key: &VerifyingPublicKey,
+    ) -> Result<SigningSecretKeyHandle> {
+        if self
+            .signer
+            .verifying_public_keys()
+            .await?
+            .contains(verifying_public_key)
+        {
+            Ok(Self::secret_key_handle(verifying_public_key))
+        } else {
+            Err(VaultError::KeyNotFound.into())
+        }
+    }
+
+    async fn delete_signing_secret_key(
+        &self,
+        _signing_secret_key_handle: SigningSecretKeyHandle,
+    ) -> Result<bool> {
+        // The keys belong to the external signer
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EdDSACurve25519Signature;
+
+    struct TestSigner {
+        public_key: VerifyingPublicKey,
+    }
+
+    #[async_trait]
+    impl ExternalSigner for TestSigner {
+        async fn verifying_public_keys(&self) -> Result<Vec<VerifyingPublicKey>> {
+            Ok(vec![self.public_key.clone()])
+        }
+
+        async fn sign(
+            &self,
+            verifying_public_key: &VerifyingPublicKey,
+            _data: &[u8],
+        ) -> Result<Signature> {
+            if verifying_public_key != &self.public_key {
+                return Err(VaultError::KeyNotFound.into());
+            }
+            Ok(Signature::EdDSACurve25519(EdDSACurve25519Signature(
+                [1; 64],
+            )))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_external_signing_vault() -> Result<()> {
+        let public_key = VerifyingPublicKey::EdDSACurve25519(EdDSACurve25519PublicKey([2; 32]));
+        let vault = ExternalSigningVault::new(Arc::new(TestSigner {
+            public_key: public_key.clone(),
+        }));
+
+        let handle = vault.get_secret_key_handle(&public_key).await?;
+        assert_eq!(vault.get_verifying_public_key(&handle).await?, public_key);
+        assert!(vault.sign(&handle, b"data").await.is_ok());
+
+        let unknown = VerifyingPublicKey::EdDSACurve25519(EdDSACurve25519PublicKey([3; 32]));
+        assert!(vault.get_secret_key_handle(&unknown).await.is_err());
+        assert!(vault
+            .generate_signing_secret_key(SigningKeyType::EdDSACurve25519)
+            .await
+            .is_err());
+        Ok(())
+    }
+}
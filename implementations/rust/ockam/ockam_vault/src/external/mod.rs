@@ -0,0 +1,3 @@
+mod external_signing_vault;
+
+pub use external_signing_vault::*;
@@ -42,10 +42,14 @@ mod traits;
 /// Software implementation of Vault traits
 mod software;
 
+/// Vault implementations delegating to an [`ExternalSigner`]
+mod external;
+
 /// Main vault types: PublicKey, Secret, SecretAttributes etc...
 mod types;
 
 pub use error::*;
+pub use external::*;
 pub use software::*;
 pub use traits::*;
 pub use types::*;
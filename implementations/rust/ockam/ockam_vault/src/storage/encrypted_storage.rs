@@ -0,0 +1,143 @@
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, Error, Result};
+use ockam_node::KeyValueStorage;
+use std::path::{Path, PathBuf};
+
+use crate::legacy::{KeyId, StoredSecret};
+use crate::storage::persistent_storage::StoredSecrets;
+
+/// Encryption of the data of a Vault at rest
+pub trait StorageEncryption: Send + Sync + 'static {
+    /// Encrypt the serialized secrets of a Vault
+    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>>;
+
+    /// Decrypt the serialized secrets of a Vault
+    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Storage for a Vault data backed by an encrypted file.
+///
+/// The secrets are decrypted once, when the storage is created, and are only kept in memory.
+/// The file is encrypted again every time a secret is added or deleted. The decrypted data has
+/// the same format as the file of a [`super::PersistentStorage`].
+/// WARNING: This implementation doesn't lock the file, the same file must not be modified
+/// by several processes at the same time.
+pub struct EncryptedStorage {
+    path: PathBuf,
+    encryption: Arc<dyn StorageEncryption>,
+    secrets: RwLock<StoredSecrets>,
+}
+
+impl EncryptedStorage {
+    /// Create a new encrypted file storage for a Vault. The file is created if it doesn't exist
+    pub async fn create(
+        path: &Path,
+        encryption: Arc<dyn StorageEncryption>,
+    ) -> Result<Arc<dyn KeyValueStorage<KeyId, StoredSecret>>> {
+        let secrets = if path.exists() {
+            let decrypted = encryption.decrypt(&std::fs::read(path).map_err(io_error)?)?;
+            serde_json::from_slice(&decrypted).map_err(invalid_data)?
+        } else {
+            StoredSecrets::default()
+        };
+        let storage = EncryptedStorage {
+            path: path.to_path_buf(),
+            encryption,
+            secrets: RwLock::new(secrets),
+        };
+        if !path.exists() {
+            storage.write(&storage.secrets.read().unwrap())?;
+        }
+        Ok(Arc::new(storage))
+    }
+
+    /// Encrypt the secrets and replace the file atomically
+    fn write(&self, secrets: &StoredSecrets) -> Result<()> {
+        let data = serde_json::to_vec(secrets).map_err(invalid_data)?;
+        let encrypted = self.encryption.encrypt(&data)?;
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, encrypted).map_err(io_error)?;
+        std::fs::rename(tmp_path, &self.path).map_err(io_error)
+    }
+}
+
+#[async_trait]
+impl KeyValueStorage<KeyId, StoredSecret> for EncryptedStorage {
+    async fn put(&self, key_id: KeyId, stored_secret: StoredSecret) -> Result<()> {
+        let mut secrets = self.secrets.write().unwrap();
+        secrets.add_stored_secret(key_id, stored_secret);
+        self.write(&secrets)
+    }
+
+    async fn get(&self, key_id: &KeyId) -> Result<Option<StoredSecret>> {
+        Ok(self.secrets.read().unwrap().get_stored_secret(key_id))
+    }
+
+    async fn delete(&self, key_id: &KeyId) -> Result<Option<StoredSecret>> {
+        let mut secrets = self.secrets.write().unwrap();
+        let deleted = secrets.delete_stored_secret(key_id);
+        if deleted.is_some() {
+            self.write(&secrets)?;
+        }
+        Ok(deleted)
+    }
+
+    async fn keys(&self) -> Result<Vec<KeyId>> {
+        Ok(self
+            .secrets
+            .read()
+            .unwrap()
+            .secrets
+            .keys()
+            .cloned()
+            .collect())
+    }
+}
+
+fn io_error(e: std::io::Error) -> Error {
+    Error::new(Origin::Vault, Kind::Io, e)
+}
+
+fn invalid_data(e: serde_json::Error) -> Error {
+    Error::new(Origin::Vault, Kind::Invalid, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::legacy::{Secret, SecretAttributes};
+    use tempfile::tempdir;
+
+    /// Reversible encryption, for the tests only
+    struct XorEncryption;
+
+    impl StorageEncryption for XorEncryption {
+        fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+            Ok(data.iter().map(|b| b ^ 0x5a).collect())
+        }
+
+        fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+            self.encrypt(data)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_storage() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("vault.age");
+        let storage = EncryptedStorage::create(&path, Arc::new(XorEncryption)).await?;
+
+        let key_id: KeyId = "key".into();
+        let stored_secret = StoredSecret::new(Secret::new(vec![1; 32]), SecretAttributes::Ed25519);
+        storage.put(key_id.clone(), stored_secret.clone()).await?;
+
+        // the secrets are not stored in plain text
+        let contents = std::fs::read(&path).unwrap();
+        assert!(serde_json::from_slice::<serde_json::Value>(&contents).is_err());
+
+        let storage = EncryptedStorage::create(&path, Arc::new(XorEncryption)).await?;
+        assert_eq!(storage.get(&key_id).await?, Some(stored_secret));
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
+/// Storage of secrets to an encrypted file
+mod encrypted_storage;
 /// Storage of secrets to a file
 mod persistent_storage;
 
+pub use encrypted_storage::*;
 pub use persistent_storage::*;
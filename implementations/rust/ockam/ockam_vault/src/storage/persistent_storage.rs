@@ -29,8 +29,8 @@ impl PersistentStorage {
 
 /// This struct is serialized to a file in order to persist vault data
 #[derive(Debug, Clone, Default)]
-struct StoredSecrets {
-    secrets: BTreeMap<KeyId, StoredSecret>,
+pub(crate) struct StoredSecrets {
+    pub(crate) secrets: BTreeMap<KeyId, StoredSecret>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -44,15 +44,15 @@ struct FileSecret {
 }
 
 impl StoredSecrets {
-    fn add_stored_secret(&mut self, key_id: KeyId, stored_secret: StoredSecret) {
+    pub(crate) fn add_stored_secret(&mut self, key_id: KeyId, stored_secret: StoredSecret) {
         self.secrets.insert(key_id, stored_secret);
     }
 
-    fn get_stored_secret(&self, key_id: &KeyId) -> Option<StoredSecret> {
+    pub(crate) fn get_stored_secret(&self, key_id: &KeyId) -> Option<StoredSecret> {
         self.secrets.get(key_id).cloned()
     }
 
-    fn delete_stored_secret(&mut self, key_id: &KeyId) -> Option<StoredSecret> {
+    pub(crate) fn delete_stored_secret(&mut self, key_id: &KeyId) -> Option<StoredSecret> {
         self.secrets.remove(key_id)
     }
 }
@@ -0,0 +1,21 @@
+use crate::{Signature, VerifyingPublicKey};
+
+use ockam_core::compat::{boxed::Box, vec::Vec};
+use ockam_core::{async_trait, Result};
+
+/// Signer keeping its keys outside of Ockam, for example an ssh-agent.
+///
+/// The keys can't be generated or deleted through Ockam, they are only referenced by their
+/// [`VerifyingPublicKey`]. See [`crate::ExternalSigningVault`] to use them as a [`crate::VaultForSigning`].
+#[async_trait]
+pub trait ExternalSigner: Send + Sync + 'static {
+    /// Get the [`VerifyingPublicKey`]s of all the keys which can be used to sign.
+    async fn verifying_public_keys(&self) -> Result<Vec<VerifyingPublicKey>>;
+
+    /// Sign data with the key corresponding to a [`VerifyingPublicKey`].
+    async fn sign(
+        &self,
+        verifying_public_key: &VerifyingPublicKey,
+        data: &[u8],
+    ) -> Result<Signature>;
+}
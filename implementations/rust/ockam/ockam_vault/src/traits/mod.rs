@@ -1,7 +1,9 @@
+mod external_signer;
 mod vault_for_secure_channels;
 mod vault_for_signing;
 mod vault_for_verifying_signatures;
 
+pub use external_signer::*;
 pub use vault_for_secure_channels::*;
 pub use vault_for_signing::*;
 pub use vault_for_verifying_signatures::*;
@@ -0,0 +1,39 @@
+use ockam_core::errcode::{Kind, Origin};
+use thiserror::Error;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("pkcs11 module {module} could not be loaded: {error}")]
+    Module { module: String, error: String },
+    #[error("no pkcs11 token was found")]
+    TokenNotFound,
+    #[error("no pkcs11 token has the label {0}")]
+    TokenLabelNotFound(String),
+    #[error("pkcs11 session error: {0}")]
+    Session(String),
+    #[error("pkcs11 error creating new key: {0}")]
+    Create(String),
+    #[error("pkcs11 error signing message with key {keyid}: {error}")]
+    Sign { keyid: String, error: String },
+    #[error("pkcs11 error exporting public key {keyid}: {error}")]
+    Export { keyid: String, error: String },
+    #[error("pkcs11 error deleting key {keyid}: {error}")]
+    Delete { keyid: String, error: String },
+    #[error("key type is not supported")]
+    UnsupportedKeyType,
+    #[error("public key is incorrect")]
+    InvalidPublicKey,
+    #[error("signature is incorrect")]
+    InvalidSignature,
+    #[error("key was not found")]
+    KeyNotFound,
+    #[error("invalid handle")]
+    InvalidHandle,
+}
+
+impl From<Error> for ockam_core::Error {
+    fn from(e: Error) -> Self {
+        ockam_core::Error::new(Origin::Other, Kind::Io, e)
+    }
+}
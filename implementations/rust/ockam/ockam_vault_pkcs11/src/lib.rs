@@ -0,0 +1,18 @@
+//! PKCS#11 implementation of the ockam_vault::VaultForSigning trait
+//!
+//! The signing keys are generated and used inside a PKCS#11 token, for example a YubiKey or an
+//! HSM, and never exist in the filesystem.
+#![deny(unsafe_code)]
+#![warn(
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unused_import_braces,
+    unused_qualifications
+)]
+
+mod error;
+mod pkcs11_signing_vault;
+
+pub use error::*;
+pub use pkcs11_signing_vault::*;
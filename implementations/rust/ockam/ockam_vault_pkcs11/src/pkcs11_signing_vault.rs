@@ -0,0 +1,402 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use tracing::{debug, error};
+
+use ockam_core::{async_trait, Result};
+use ockam_vault::{
+    ECDSASHA256CurveP256PublicKey, ECDSASHA256CurveP256Signature, HandleToSecret, Signature,
+    SigningKeyType, SigningSecretKeyHandle, VaultError, VaultForSigning, VerifyingPublicKey,
+};
+
+use crate::error::Error;
+
+/// DER encoding of the OID of the NIST P-256 curve, used as the CKA_EC_PARAMS of the keys
+const P256_EC_PARAMS: [u8; 10] = [0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+/// Label given to the keys created by this vault
+const KEY_LABEL: &[u8] = b"ockam";
+
+/// Configuration of a PKCS#11 vault
+#[derive(Debug, Clone)]
+pub struct Pkcs11Config {
+    /// Path of the PKCS#11 module, for example `/usr/lib/softhsm/libsofthsm2.so`
+    pub module: PathBuf,
+    /// Label of the token storing the keys. The first token found is used if it is not set
+    pub token_label: Option<String>,
+    /// PIN of the user of the token
+    pub pin: Option<String>,
+}
+
+impl Pkcs11Config {
+    /// Create a new configuration for a PKCS#11 module
+    pub fn new(module: impl Into<PathBuf>) -> Self {
+        Self {
+            module: module.into(),
+            token_label: None,
+            pin: None,
+        }
+    }
+
+    /// Select the token with the given label
+    pub fn with_token_label(mut self, token_label: impl Into<String>) -> Self {
+        self.token_label = Some(token_label.into());
+        self
+    }
+
+    /// Login to the token with the given PIN
+    pub fn with_pin(mut self, pin: impl Into<String>) -> Self {
+        self.pin = Some(pin.into());
+        self
+    }
+}
+
+struct Pkcs11KeyPair {
+    key: SigningSecretKeyHandle,
+    public_key: VerifyingPublicKey,
+}
+
+/// Security module implementation using a PKCS#11 token.
+///
+/// The keys are NIST P-256 keys identified by their CKA_ID. PKCS#11 calls are blocking, so they
+/// are run on the blocking thread pool of the runtime.
+pub struct Pkcs11SigningVault {
+    session: Arc<Mutex<Session>>,
+    // Store mapping from PublicKey to KeyId in memory
+    // This is fetched at the Vault initialization
+    // and is updated locally during add/delete operations
+    // WARNING: The assumption is that there is no concurrent access to the same keys from
+    // different places.
+    keys: Arc<RwLock<Vec<Pkcs11KeyPair>>>,
+}
+
+impl Pkcs11SigningVault {
+    /// Open a session on a PKCS#11 token and load the keys it contains
+    pub async fn create(config: Pkcs11Config) -> Result<Self> {
+        let (session, keys) = tokio::task::spawn_blocking(move || Self::open(&config))
+            .await
+            .map_err(|e| Error::Session(e.to_string()))??;
+        Ok(Self {
+            session: Arc::new(Mutex::new(session)),
+            keys: Arc::new(RwLock::new(keys)),
+        })
+    }
+
+    fn open(config: &Pkcs11Config) -> Result<(Session, Vec<Pkcs11KeyPair>)> {
+        let module_error = |e: cryptoki::error::Error| Error::Module {
+            module: config.module.display().to_string(),
+            error: e.to_string(),
+        };
+        let pkcs11 = Pkcs11::new(&config.module).map_err(module_error)?;
+        pkcs11
+            .initialize(CInitializeArgs::OsThreads)
+            .map_err(module_error)?;
+
+        let slots = pkcs11.get_slots_with_token().map_err(module_error)?;
+        let slot = match &config.token_label {
+            Some(label) => slots
+                .into_iter()
+                .find(|slot| {
+                    pkcs11
+                        .get_token_info(*slot)
+                        .map(|info| info.label().trim() == label)
+                        .unwrap_or(false)
+                })
+                .ok_or_else(|| Error::TokenLabelNotFound(label.clone()))?,
+            None => slots.into_iter().next().ok_or(Error::TokenNotFound)?,
+        };
+
+        let session = pkcs11.open_rw_session(slot).map_err(session_error)?;
+        if let Some(pin) = &config.pin {
+            session
+                .login(UserType::User, Some(&AuthPin::new(pin.clone())))
+                .map_err(session_error)?;
+        }
+
+        let mut key_pairs = vec![];
+        let private_keys = session
+            .find_objects(&[
+                Attribute::Class(ObjectClass::PRIVATE_KEY),
+                Attribute::KeyType(KeyType::EC),
+                Attribute::Sign(true),
+            ])
+            .map_err(session_error)?;
+        for private_key in private_keys {
+            let id = match session.get_attributes(private_key, &[AttributeType::Id]) {
+                Ok(attributes) => match attributes.into_iter().next() {
+                    Some(Attribute::Id(id)) if !id.is_empty() => id,
+                    _ => continue,
+                },
+                Err(err) => {
+                    error!("Error reading the id of a key: {err}");
+                    continue;
+                }
+            };
+            match Self::read_public_key(&session, &id) {
+                Ok(public_key) => key_pairs.push(Pkcs11KeyPair {
+                    key: handle_from_id(id),
+                    public_key,
+                }),
+                // The public key may be missing, or have a different curve.
+                // Therefore, the best strategy is to just skip that key
+                Err(err) => error!("Error exporting public key: {err}"),
+            }
+        }
+        debug!(keys = key_pairs.len(), "pkcs11 vault opened");
+
+        Ok((session, key_pairs))
+    }
+
+    fn find_object(session: &Session, class: ObjectClass, id: &[u8]) -> Result<ObjectHandle> {
+        session
+            .find_objects(&[Attribute::Class(class), Attribute::Id(id.to_vec())])
+            .map_err(session_error)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::KeyNotFound.into())
+    }
+
+    fn read_public_key(session: &Session, id: &[u8]) -> Result<VerifyingPublicKey> {
+        let export_error = |error: String| Error::Export {
+            keyid: hex_id(id),
+            error,
+        };
+        let public_key = Self::find_object(session, ObjectClass::PUBLIC_KEY, id)?;
+        let attributes = session
+            .get_attributes(
+                public_key,
+                &[AttributeType::EcParams, AttributeType::EcPoint],
+            )
+            .map_err(|e| export_error(e.to_string()))?;
+
+        let mut point = None;
+        for attribute in attributes {
+            match attribute {
+                Attribute::EcParams(params) if params != P256_EC_PARAMS => {
+                    return Err(Error::UnsupportedKeyType.into())
+                }
+                Attribute::EcPoint(p) => point = Some(p),
+                _ => {}
+            }
+        }
+        let point = point.ok_or_else(|| export_error("missing EC point".to_string()))?;
+
+        // The point is usually wrapped in a DER octet string, but some modules return it as is
+        let point = match point.as_slice() {
+            [0x04, 0x41, rest @ ..] if rest.len() == 65 => rest.to_vec(),
+            _ => point,
+        };
+        let point: [u8; 65] = point.try_into().map_err(|_| Error::InvalidPublicKey)?;
+        Ok(VerifyingPublicKey::ECDSASHA256CurveP256(
+            ECDSASHA256CurveP256PublicKey(point),
+        ))
+    }
+
+    /// Run a blocking operation with the session of the token
+    async fn with_session<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Session) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let session = self.session.clone();
+        tokio::task::spawn_blocking(move || {
+            let session = session.lock().map_err(|e| Error::Session(e.to_string()))?;
+            f(&session)
+        })
+        .await
+        .map_err(|e| Error::Session(e.to_string()))?
+    }
+
+    /// Return list of all keys
+    pub fn keys(&self) -> Vec<SigningSecretKeyHandle> {
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .map(|x| x.key.clone())
+            .collect()
+    }
+
+    /// Return number of keys
+    pub async fn number_of_keys(&self) -> Result<usize> {
+        Ok(self.keys.read().unwrap().len())
+    }
+}
+
+#[async_trait]
+impl VaultForSigning for Pkcs11SigningVault {
+    async fn sign(
+        &self,
+        signing_secret_key_handle: &SigningSecretKeyHandle,
+        data: &[u8],
+    ) -> Result<Signature> {
+        let id = id_from_handle(signing_secret_key_handle)?;
+        // The digest is computed here since not all the tokens support CKM_ECDSA_SHA256
+        let digest = Sha256::digest(data).to_vec();
+        let signature = self
+            .with_session(move |session| {
+                let private_key = Self::find_object(session, ObjectClass::PRIVATE_KEY, &id)?;
+                session
+                    .sign(&Mechanism::Ecdsa, private_key, &digest)
+                    .map_err(|e| {
+                        Error::Sign {
+                            keyid: hex_id(&id),
+                            error: e.to_string(),
+                        }
+                        .into()
+                    })
+            })
+            .await?;
+
+        // CKM_ECDSA returns the concatenation of r and s
+        let signature: [u8; 64] = signature.try_into().map_err(|_| Error::InvalidSignature)?;
+        Ok(Signature::ECDSASHA256CurveP256(
+            ECDSASHA256CurveP256Signature(signature),
+        ))
+    }
+
+    async fn generate_signing_secret_key(
+        &self,
+        signing_key_type: SigningKeyType,
+    ) -> Result<SigningSecretKeyHandle> {
+        if signing_key_type != SigningKeyType::ECDSASHA256CurveP256 {
+            return Err(VaultError::InvalidKeyType.into());
+        }
+
+        let mut id = vec![0u8; 16];
+        rand::thread_rng().fill_bytes(&mut id);
+        let key_id = id.clone();
+        let public_key = self
+            .with_session(move |session| {
+                let public_template = [
+                    Attribute::Token(true),
+                    Attribute::Verify(true),
+                    Attribute::EcParams(P256_EC_PARAMS.to_vec()),
+                    Attribute::Id(key_id.clone()),
+                    Attribute::Label(KEY_LABEL.to_vec()),
+                ];
+                // The private key can't leave the token
+                let private_template = [
+                    Attribute::Token(true),
+                    Attribute::Private(true),
+                    Attribute::Sensitive(true),
+                    Attribute::Extractable(false),
+                    Attribute::Sign(true),
+                    Attribute::Id(key_id.clone()),
+                    Attribute::Label(KEY_LABEL.to_vec()),
+                ];
+                session
+                    .generate_key_pair(
+                        &Mechanism::EccKeyPairGen,
+                        &public_template,
+                        &private_template,
+                    )
+                    .map_err(|e| Error::Create(e.to_string()))?;
+                Self::read_public_key(session, &key_id)
+            })
+            .await?;
+        debug!(keyid = hex_id(&id), "created new key");
+
+        let key = handle_from_id(id);
+        self.keys.write().unwrap().push(Pkcs11KeyPair {
+            key: key.clone(),
+            public_key,
+        });
+
+        Ok(key)
+    }
+
+    async fn get_verifying_public_key(
+        &self,
+        signing_secret_key_handle: &SigningSecretKeyHandle,
+    ) -> Result<VerifyingPublicKey> {
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .find_map(|x| {
+                if &x.key == signing_secret_key_handle {
+                    Some(x.public_key.clone())
+                } else {
+                    None
+                }
+            })
+            .ok_or(Error::KeyNotFound.into())
+    }
+
+    async fn get_secret_key_handle(
+        &self,
+        verifying_public_key: &VerifyingPublicKey,
+    ) -> Result<SigningSecretKeyHandle> {
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .find_map(|x| {
+                if &x.public_key == verifying_public_key {
+                    Some(x.key.clone())
+                } else {
+                    None
+                }
+            })
+            .ok_or(Error::KeyNotFound.into())
+    }
+
+    async fn delete_signing_secret_key(
+        &self,
+        signing_secret_key_handle: SigningSecretKeyHandle,
+    ) -> Result<bool> {
+        let id = id_from_handle(&signing_secret_key_handle)?;
+        let deleted = self
+            .with_session(move |session| {
+                let mut deleted = false;
+                for class in [ObjectClass::PRIVATE_KEY, ObjectClass::PUBLIC_KEY] {
+                    let objects = session
+                        .find_objects(&[Attribute::Class(class), Attribute::Id(id.clone())])
+                        .map_err(session_error)?;
+                    for object in objects {
+                        session.destroy_object(object).map_err(|e| Error::Delete {
+                            keyid: hex_id(&id),
+                            error: e.to_string(),
+                        })?;
+                        deleted = true;
+                    }
+                }
+                Ok(deleted)
+            })
+            .await?;
+
+        if deleted {
+            self.keys
+                .write()
+                .unwrap()
+                .retain(|x| x.key != signing_secret_key_handle);
+        }
+        Ok(deleted)
+    }
+}
+
+fn handle_from_id(id: Vec<u8>) -> SigningSecretKeyHandle {
+    SigningSecretKeyHandle::ECDSASHA256CurveP256(HandleToSecret::new(id))
+}
+
+fn id_from_handle(handle: &SigningSecretKeyHandle) -> Result<Vec<u8>> {
+    match handle {
+        SigningSecretKeyHandle::EdDSACurve25519(_) => Err(Error::InvalidHandle.into()),
+        SigningSecretKeyHandle::ECDSASHA256CurveP256(handle) => Ok(handle.value().clone()),
+    }
+}
+
+fn hex_id(id: &[u8]) -> String {
+    id.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn session_error(e: cryptoki::error::Error) -> Error {
+    Error::Session(e.to_string())
+}
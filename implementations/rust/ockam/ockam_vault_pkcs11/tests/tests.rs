@@ -0,0 +1,75 @@
+use ockam_core::Result;
+use ockam_vault::{
+    SigningKeyType, SoftwareVaultForVerifyingSignatures, VaultForSigning,
+    VaultForVerifyingSignatures,
+};
+use ockam_vault_pkcs11::{Pkcs11Config, Pkcs11SigningVault};
+
+/// These tests need to be executed with the following environment variables
+/// OCKAM_PKCS11_MODULE: path of the PKCS#11 module, for example /usr/lib/softhsm/libsofthsm2.so
+/// OCKAM_PKCS11_PIN: PIN of the user of the token
+/// OCKAM_PKCS11_TOKEN_LABEL (optional): label of the token
+async fn create_vault() -> Result<Pkcs11SigningVault> {
+    let mut config = Pkcs11Config::new(std::env::var("OCKAM_PKCS11_MODULE").unwrap())
+        .with_pin(std::env::var("OCKAM_PKCS11_PIN").unwrap());
+    if let Ok(token_label) = std::env::var("OCKAM_PKCS11_TOKEN_LABEL") {
+        config = config.with_token_label(token_label);
+    }
+    Pkcs11SigningVault::create(config).await
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_sign_verify() -> Result<()> {
+    let signing_vault = create_vault().await?;
+    let handle = signing_vault
+        .generate_signing_secret_key(SigningKeyType::ECDSASHA256CurveP256)
+        .await?;
+    let message = b"hello world";
+    let signature = signing_vault.sign(&handle, message.as_slice()).await?;
+    let public_key = signing_vault.get_verifying_public_key(&handle).await?;
+
+    let verifier = SoftwareVaultForVerifyingSignatures::new();
+    assert!(
+        verifier
+            .verify_signature(&public_key, message, &signature)
+            .await?
+    );
+
+    signing_vault.delete_signing_secret_key(handle).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_keys_management() -> Result<()> {
+    let signing_vault = create_vault().await?;
+
+    let number_of_keys1 = signing_vault.number_of_keys().await?;
+
+    let handle = signing_vault
+        .generate_signing_secret_key(SigningKeyType::ECDSASHA256CurveP256)
+        .await?;
+
+    let number_of_keys2 = signing_vault.number_of_keys().await?;
+    assert_eq!(number_of_keys1 + 1, number_of_keys2);
+
+    let public_key = signing_vault.get_verifying_public_key(&handle).await?;
+
+    let handle2 = signing_vault.get_secret_key_handle(&public_key).await?;
+    assert_eq!(handle, handle2);
+
+    // The keys are persisted in the token
+    let signing_vault2 = create_vault().await?;
+    assert_eq!(
+        signing_vault2.get_secret_key_handle(&public_key).await?,
+        handle
+    );
+
+    signing_vault.delete_signing_secret_key(handle).await?;
+    let number_of_keys3 = signing_vault.number_of_keys().await?;
+    assert_eq!(number_of_keys2, number_of_keys3 + 1);
+
+    Ok(())
+}
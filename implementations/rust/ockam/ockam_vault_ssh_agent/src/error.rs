@@ -0,0 +1,27 @@
+use ockam_core::errcode::{Kind, Origin};
+use thiserror::Error;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("the SSH_AUTH_SOCK environment variable is not set")]
+    SocketNotSet,
+    #[error("ssh-agent socket {socket} could not be reached: {error}")]
+    Connection { socket: String, error: String },
+    #[error("ssh-agent is only supported on unix platforms")]
+    UnsupportedPlatform,
+    #[error("ssh-agent refused the request")]
+    Refused,
+    #[error("invalid ssh-agent message: {0}")]
+    InvalidMessage(String),
+    #[error("key type is not supported, only ssh-ed25519 keys can be used")]
+    UnsupportedKeyType,
+    #[error("no ssh-agent key matches {0}")]
+    KeyNotFound(String),
+}
+
+impl From<Error> for ockam_core::Error {
+    fn from(e: Error) -> Self {
+        ockam_core::Error::new(Origin::Other, Kind::Io, e)
+    }
+}
@@ -0,0 +1,18 @@
+//! ssh-agent implementation of the ockam_vault::ExternalSigner trait
+//!
+//! The signing keys are the Ed25519 keys loaded in an ssh-agent. They never leave the agent,
+//! which computes all the signatures.
+#![deny(unsafe_code)]
+#![warn(
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unused_import_braces,
+    unused_qualifications
+)]
+
+mod error;
+mod ssh_agent_signer;
+
+pub use error::*;
+pub use ssh_agent_signer::*;
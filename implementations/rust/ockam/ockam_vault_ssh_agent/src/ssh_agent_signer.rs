@@ -0,0 +1,303 @@
+use std::path::{Path, PathBuf};
+
+use data_encoding::{BASE64, BASE64_NOPAD};
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+use ockam_core::{async_trait, Result};
+use ockam_vault::{
+    EdDSACurve25519PublicKey, EdDSACurve25519Signature, ExternalSigner, Signature,
+    VerifyingPublicKey,
+};
+
+use crate::error::Error;
+
+/// Environment variable containing the path of the ssh-agent socket
+pub const SSH_AUTH_SOCK_ENV: &str = "SSH_AUTH_SOCK";
+
+/// Name of the Ed25519 keys and signatures in the ssh wire format
+const SSH_ED25519: &str = "ssh-ed25519";
+
+// Messages of the ssh-agent protocol, see
+// https://datatracker.ietf.org/doc/html/draft-miller-ssh-agent
+const SSH_AGENT_FAILURE: u8 = 5;
+const SSH_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+const SSH_AGENT_IDENTITIES_ANSWER: u8 = 12;
+const SSH_AGENTC_SIGN_REQUEST: u8 = 13;
+const SSH_AGENT_SIGN_RESPONSE: u8 = 14;
+
+/// Maximum size of a message accepted from the agent
+const MAX_MESSAGE_LENGTH: usize = 256 * 1024;
+
+/// Key loaded in an ssh-agent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshAgentKey {
+    /// Public key used to verify the signatures of the key
+    pub public_key: VerifyingPublicKey,
+    /// Comment of the key, usually the path of the key file or an email address
+    pub comment: String,
+}
+
+impl SshAgentKey {
+    /// Return the key in the OpenSSH public key format, as printed by `ssh-add -L`
+    pub fn openssh_public_key(&self) -> String {
+        format!("{SSH_ED25519} {}", BASE64.encode(&self.blob()))
+    }
+
+    /// Return the SHA256 fingerprint of the key, as printed by `ssh-add -l`
+    pub fn fingerprint(&self) -> String {
+        format!(
+            "SHA256:{}",
+            BASE64_NOPAD.encode(&Sha256::digest(self.blob()))
+        )
+    }
+
+    /// Return true if this key is designated by its comment, its fingerprint or its
+    /// OpenSSH public key
+    pub fn matches(&self, key: &str) -> bool {
+        let key = key.trim();
+        self.comment == key
+            || self.fingerprint() == key
+            || key.split_whitespace().take(2).collect::<Vec<_>>().join(" ")
+                == self.openssh_public_key()
+    }
+
+    fn blob(&self) -> Vec<u8> {
+        match &self.public_key {
+            VerifyingPublicKey::EdDSACurve25519(public_key) => ed25519_blob(public_key),
+            VerifyingPublicKey::ECDSASHA256CurveP256(_) => vec![],
+        }
+    }
+}
+
+/// [`ExternalSigner`] implementation using the Ed25519 keys of an ssh-agent.
+///
+/// A new connection to the agent is made for each request, so that the signer keeps working
+/// when the agent is restarted. The other key types of the agent are ignored
+#[derive(Debug, Clone)]
+pub struct SshAgentSigner {
+    socket: PathBuf,
+}
+
+impl SshAgentSigner {
+    /// Create a signer using the agent listening on a given socket
+    pub fn new(socket: impl Into<PathBuf>) -> Self {
+        Self {
+            socket: socket.into(),
+        }
+    }
+
+    /// Create a signer using the agent referenced by the `SSH_AUTH_SOCK` environment variable
+    pub fn from_env() -> Result<Self> {
+        let socket = std::env::var_os(SSH_AUTH_SOCK_ENV).ok_or(Error::SocketNotSet)?;
+        Ok(Self::new(socket))
+    }
+
+    /// Path of the agent socket
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    /// Return the Ed25519 keys loaded in the agent
+    pub async fn keys(&self) -> Result<Vec<SshAgentKey>> {
+        let response = self.request(&[SSH_AGENTC_REQUEST_IDENTITIES]).await?;
+        let mut reader = Reader::new(&response);
+        if reader.byte()? != SSH_AGENT_IDENTITIES_ANSWER {
+            return Err(Error::Refused.into());
+        }
+        let count = reader.u32()?;
+        let mut keys = vec![];
+        for _ in 0..count {
+            let blob = reader.string()?;
+            let comment = String::from_utf8_lossy(reader.string()?).to_string();
+            match parse_ed25519_blob(blob) {
+                Ok(public_key) => keys.push(SshAgentKey {
+                    public_key: VerifyingPublicKey::EdDSACurve25519(public_key),
+                    comment,
+                }),
+                Err(_) => debug!(%comment, "skipping an ssh-agent key which is not an ed25519 key"),
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Return the key designated by its comment, its fingerprint or its OpenSSH public key
+    pub async fn find_key(&self, key: &str) -> Result<SshAgentKey> {
+        self.keys()
+            .await?
+            .into_iter()
+            .find(|k| k.matches(key))
+            .ok_or_else(|| Error::KeyNotFound(key.to_string()).into())
+    }
+
+    /// Send a request to the agent and return the response
+    async fn request(&self, request: &[u8]) -> Result<Vec<u8>> {
+        #[cfg(unix)]
+        {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let connection_error = |e: std::io::Error| Error::Connection {
+                socket: self.socket.display().to_string(),
+                error: e.to_string(),
+            };
+            let mut stream = tokio::net::UnixStream::connect(&self.socket)
+                .await
+                .map_err(connection_error)?;
+            let mut message = (request.len() as u32).to_be_bytes().to_vec();
+            message.extend_from_slice(request);
+            stream.write_all(&message).await.map_err(connection_error)?;
+
+            let length = stream.read_u32().await.map_err(connection_error)? as usize;
+            if length == 0 || length > MAX_MESSAGE_LENGTH {
+                return Err(Error::InvalidMessage(format!("invalid length {length}")).into());
+            }
+            let mut response = vec![0u8; length];
+            stream
+                .read_exact(&mut response)
+                .await
+                .map_err(connection_error)?;
+            if response[0] == SSH_AGENT_FAILURE {
+                return Err(Error::Refused.into());
+            }
+            Ok(response)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = request;
+            Err(Error::UnsupportedPlatform.into())
+        }
+    }
+}
+
+#[async_trait]
+impl ExternalSigner for SshAgentSigner {
+    async fn verifying_public_keys(&self) -> Result<Vec<VerifyingPublicKey>> {
+        Ok(self
+            .keys()
+            .await?
+            .into_iter()
+            .map(|k| k.public_key)
+            .collect())
+    }
+
+    async fn sign(
+        &self,
+        verifying_public_key: &VerifyingPublicKey,
+        data: &[u8],
+    ) -> Result<Signature> {
+        let public_key = match verifying_public_key {
+            VerifyingPublicKey::EdDSACurve25519(public_key) => public_key,
+            VerifyingPublicKey::ECDSASHA256CurveP256(_) => {
+                return Err(Error::UnsupportedKeyType.into())
+            }
+        };
+        let mut request = vec![SSH_AGENTC_SIGN_REQUEST];
+        write_string(&mut request, &ed25519_blob(public_key));
+        write_string(&mut request, data);
+        // no flags, they only apply to RSA keys
+        request.extend_from_slice(&0u32.to_be_bytes());
+
+        let response = self.request(&request).await?;
+        let mut reader = Reader::new(&response);
+        if reader.byte()? != SSH_AGENT_SIGN_RESPONSE {
+            return Err(Error::Refused.into());
+        }
+        let mut signature = Reader::new(reader.string()?);
+        if signature.string()? != SSH_ED25519.as_bytes() {
+            return Err(Error::UnsupportedKeyType.into());
+        }
+        let signature = signature
+            .string()?
+            .try_into()
+            .map_err(|_| Error::InvalidMessage("invalid ed25519 signature".to_string()))?;
+        Ok(Signature::EdDSACurve25519(EdDSACurve25519Signature(
+            signature,
+        )))
+    }
+}
+
+/// Encode an Ed25519 public key in the ssh wire format
+fn ed25519_blob(public_key: &EdDSACurve25519PublicKey) -> Vec<u8> {
+    let mut blob = vec![];
+    write_string(&mut blob, SSH_ED25519.as_bytes());
+    write_string(&mut blob, &public_key.0);
+    blob
+}
+
+/// Decode an Ed25519 public key encoded in the ssh wire format
+fn parse_ed25519_blob(blob: &[u8]) -> Result<EdDSACurve25519PublicKey> {
+    let mut reader = Reader::new(blob);
+    if reader.string()? != SSH_ED25519.as_bytes() {
+        return Err(Error::UnsupportedKeyType.into());
+    }
+    let key = reader
+        .string()?
+        .try_into()
+        .map_err(|_| Error::InvalidMessage("invalid ed25519 public key".to_string()))?;
+    Ok(EdDSACurve25519PublicKey(key))
+}
+
+fn write_string(buffer: &mut Vec<u8>, value: &[u8]) {
+    buffer.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    buffer.extend_from_slice(value);
+}
+
+/// Reader for the values of the ssh wire format
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        if self.data.len() < length {
+            return Err(Error::InvalidMessage("truncated message".to_string()).into());
+        }
+        let (value, rest) = self.data.split_at(length);
+        self.data = rest;
+        Ok(value)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn string(&mut self) -> Result<&'a [u8]> {
+        let length = self.u32()? as usize;
+        self.take(length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ed25519_blob() {
+        let public_key = EdDSACurve25519PublicKey([7; 32]);
+        let blob = ed25519_blob(&public_key);
+        assert_eq!(blob.len(), 4 + SSH_ED25519.len() + 4 + 32);
+        assert_eq!(parse_ed25519_blob(&blob).unwrap(), public_key);
+        assert!(parse_ed25519_blob(&blob[..blob.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_key_matches() {
+        let key = SshAgentKey {
+            public_key: VerifyingPublicKey::EdDSACurve25519(EdDSACurve25519PublicKey([7; 32])),
+            comment: "alice@laptop".to_string(),
+        };
+        assert!(key.matches("alice@laptop"));
+        assert!(key.matches(&key.fingerprint()));
+        assert!(key.matches(&format!("{} alice@laptop", key.openssh_public_key())));
+        assert!(!key.matches("bob@laptop"));
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use ockam_core::Result;
+use ockam_vault::{
+    ExternalSigningVault, SoftwareVaultForVerifyingSignatures, VaultForSigning,
+    VaultForVerifyingSignatures,
+};
+use ockam_vault_ssh_agent::SshAgentSigner;
+
+/// This test needs to be executed with an ssh-agent holding at least one Ed25519 key,
+/// referenced by the SSH_AUTH_SOCK environment variable
+#[tokio::test]
+#[ignore]
+async fn test_sign_verify() -> Result<()> {
+    let signer = SshAgentSigner::from_env()?;
+    let key = signer.keys().await?.into_iter().next().unwrap();
+    let signing_vault = ExternalSigningVault::new(Arc::new(signer));
+
+    let handle = signing_vault.get_secret_key_handle(&key.public_key).await?;
+    let message = b"hello world";
+    let signature = signing_vault.sign(&handle, message.as_slice()).await?;
+    let public_key = signing_vault.get_verifying_public_key(&handle).await?;
+    assert_eq!(public_key, key.public_key);
+
+    let verifier = SoftwareVaultForVerifyingSignatures::new();
+    assert!(
+        verifier
+            .verify_signature(&public_key, message, &signature)
+            .await?
+    );
+
+    Ok(())
+}